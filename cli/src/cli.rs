@@ -7,7 +7,7 @@ use crate::notifier::{Notification, Notifier};
 use crate::result::Result;
 use waglayla_daemon::{DaemonEvent, DaemonKind, Daemons};
 use waglayla_wallet_core::rpc::DynRpcApi;
-use waglayla_wallet_core::storage::{IdT, PrvKeyDataInfo};
+use waglayla_wallet_core::storage::{AssocPrvKeyDataIds, IdT, PrvKeyDataInfo};
 use waglayla_wrpc_client::WaglaylaRpcClient;
 use workflow_core::channel::*;
 use workflow_core::time::Instant;
@@ -534,7 +534,7 @@ impl WaglaylaCli {
     async fn select_account_with_args(&self, autoselect: bool) -> Result<Arc<dyn Account>> {
         let mut selection = None;
 
-        let mut list_by_key = Vec::<(Arc<PrvKeyDataInfo>, Vec<(usize, Arc<dyn Account>)>)>::new();
+        let mut list_by_key = Vec::<(String, Vec<(usize, Arc<dyn Account>)>)>::new();
         let mut flat_list = Vec::<Arc<dyn Account>>::new();
 
         let mut keys = self.wallet.keys().await?;
@@ -547,7 +547,20 @@ impl WaglaylaCli {
                 flat_list.push(account.clone());
             }
 
-            list_by_key.push((key.clone(), prv_key_accounts));
+            list_by_key.push((key.to_string(), prv_key_accounts));
+        }
+
+        let watch_only_accounts = self
+            .watch_only_accounts()
+            .await?
+            .into_iter()
+            .map(|account| {
+                flat_list.push(account.clone());
+                (flat_list.len() - 1, account)
+            })
+            .collect::<Vec<_>>();
+        if !watch_only_accounts.is_empty() {
+            list_by_key.push(("watch-only".to_string(), watch_only_accounts));
         }
 
         if flat_list.is_empty() {
@@ -559,8 +572,8 @@ impl WaglaylaCli {
         while selection.is_none() {
             tprintln!(self);
 
-            list_by_key.iter().for_each(|(prv_key_data_info, accounts)| {
-                tprintln!(self, "• {prv_key_data_info}");
+            list_by_key.iter().for_each(|(group, accounts)| {
+                tprintln!(self, "• {group}");
 
                 accounts.iter().for_each(|(seq, account)| {
                     let seq = style(seq.to_string()).cyan();
@@ -655,11 +668,33 @@ impl WaglaylaCli {
                 tprintln!(self, "      {}", style(receive_address.to_string()).blue());
             }
         }
+
+        let watch_only_accounts = self.watch_only_accounts().await?;
+        if !watch_only_accounts.is_empty() {
+            tprintln!(self, "• {}", style("watch-only").dim());
+            for account in watch_only_accounts {
+                let receive_address = account.receive_address()?;
+                tprintln!(self, "    • {}", account.get_list_string()?);
+                tprintln!(self, "      {}", style(receive_address.to_string()).blue());
+            }
+        }
         tprintln!(self);
 
         Ok(())
     }
 
+    /// Returns the accounts without private key data, which are not listed under any of the wallet keys
+    async fn watch_only_accounts(&self) -> Result<Vec<Arc<dyn Account>>> {
+        let mut watch_only_accounts = Vec::new();
+        let mut accounts = self.wallet.accounts(None).await?;
+        while let Some(account) = accounts.try_next().await? {
+            if matches!(account.to_storage()?.prv_key_data_ids, AssocPrvKeyDataIds::None) {
+                watch_only_accounts.push(account);
+            }
+        }
+        Ok(watch_only_accounts)
+    }
+
     pub async fn shutdown(&self) -> Result<()> {
         if !self.shutdown.load(Ordering::SeqCst) {
             self.shutdown.store(true, Ordering::SeqCst);
@@ -84,6 +84,10 @@ impl Account {
                                 "account import mnemonic multisig [additional keys]",
                                 "Import mnemonic and additional keys for a multisig account",
                             ),
+                            (
+                                "account import watch-only [ecdsa] <xpub|pubkey|addresses> [keys]",
+                                "Import a watch-only account tracking an extended public key, a public key or addresses ('ecdsa' for ECDSA keys)",
+                            ),
                         ],
                         None,
                     )?;
@@ -175,9 +179,24 @@ impl Account {
 
                         return Ok(());
                     }
+                    "watch-only" => {
+                        let ecdsa = argv.first().is_some_and(|arg| arg == "ecdsa");
+                        if ecdsa {
+                            argv.remove(0);
+                        }
+                        if argv.is_empty() {
+                            tprintln!(ctx, "usage: 'account import watch-only [ecdsa] <xpub|pubkey|addresses> [keys]'");
+                            tprintln!(ctx, "please specify the type of the public data to watch\r\n");
+                            return Ok(());
+                        }
+
+                        let source_kind = argv.remove(0);
+                        crate::wizards::import::import_watch_only(&ctx, source_kind.as_str(), &argv, ecdsa).await?;
+                        return Ok(());
+                    }
                     _ => {
                         tprintln!(ctx, "unknown account import type: '{import_kind}'");
-                        tprintln!(ctx, "supported import types are: 'mnemonic' or 'legacy-data'\r\n");
+                        tprintln!(ctx, "supported import types are: 'mnemonic', 'legacy-data' or 'watch-only'\r\n");
                         return Ok(());
                     }
                 }
@@ -216,7 +235,8 @@ impl Account {
                 (
                     "import <import-type> [<key-type> [extra keys]]",
                     "Import accounts from a private key using 24 or 12 word mnemonic or legacy data \
-                (KDX and waglaylanet web wallet). Use 'account import' for additional help.",
+                (KDX and waglaylanet web wallet), or watch-only accounts from public keys or addresses. \
+                Use 'account import' for additional help.",
                 ),
                 ("name <name>", "Name or rename the selected account (use 'remove' to remove the name"),
                 ("scan [<derivations>] or scan [<start>] [<derivations>]", "Scan extended address derivation chain (legacy accounts)"),
@@ -5,9 +5,25 @@ use crate::imports::*;
 pub struct Broadcast;
 
 impl Broadcast {
-    async fn main(self: Arc<Self>, ctx: &Arc<dyn Context>, _argv: Vec<String>, _cmd: &str) -> Result<()> {
+    async fn main(self: Arc<Self>, ctx: &Arc<dyn Context>, argv: Vec<String>, _cmd: &str) -> Result<()> {
         let ctx = ctx.clone().downcast_arc::<WaglaylaCli>()?;
-        ctx.wallet().broadcast().await?;
+
+        if argv.is_empty() {
+            tprintln!(ctx, "usage: broadcast <pskt> [<pskt> ...]");
+            tprintln!(ctx, "");
+            tprintln!(ctx, "PSKTs of the same transaction signed by different cosigners are combined before broadcasting");
+            return Ok(());
+        }
+
+        let mut pskt = Pskt::deserialize(argv.first().unwrap())?;
+        for other in argv.iter().skip(1) {
+            pskt.combine(&Pskt::deserialize(other)?)?;
+        }
+        pskt.finalize()?;
+
+        let id = ctx.wallet().broadcast(&pskt).await?;
+        tprintln!(ctx, "Transaction {id} submitted");
+
         Ok(())
     }
 }
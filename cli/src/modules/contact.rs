@@ -0,0 +1,148 @@
+use crate::imports::*;
+
+#[derive(Default, Handler)]
+#[help("Manage the wallet address book")]
+pub struct Contact;
+
+impl Contact {
+    async fn main(self: Arc<Self>, ctx: &Arc<dyn Context>, argv: Vec<String>, _cmd: &str) -> Result<()> {
+        let ctx = ctx.clone().downcast_arc::<WaglaylaCli>()?;
+        let wallet = ctx.wallet();
+
+        if !wallet.is_open() {
+            return Err(Error::WalletIsNotOpen);
+        }
+
+        if argv.is_empty() {
+            return self.display_help(ctx, argv).await;
+        }
+
+        let action = argv.first().unwrap();
+        match action.as_str() {
+            "list" => {
+                let AddressBookEnumerateResponse { entries } =
+                    wallet.address_book_enumerate_call(AddressBookEnumerateRequest {}).await?;
+                self.list(&ctx, &entries);
+            }
+            "search" => {
+                if argv.len() != 2 {
+                    return self.display_help(ctx, argv).await;
+                }
+                let search = argv[1].clone();
+                let AddressBookSearchResponse { entries } =
+                    wallet.address_book_search_call(AddressBookSearchRequest { search }).await?;
+                self.list(&ctx, &entries);
+            }
+            "add" => {
+                if argv.len() != 3 {
+                    return self.display_help(ctx, argv).await;
+                }
+                let alias = argv[1].clone();
+                let address = Address::try_from(argv[2].as_str())?;
+                let title = ctx.term().ask(false, "Title (optional): ").await?.trim().to_string();
+                let network_id = self.ask_network_binding(&ctx, None).await?;
+                let entry = AddressBookEntry::new(alias, title, address, network_id);
+
+                let (wallet_secret, _) = ctx.ask_wallet_secret(None).await?;
+                wallet.address_book_add_call(AddressBookAddRequest { wallet_secret, entry }).await?;
+                tprintln!(ctx, "contact added");
+            }
+            "edit" => {
+                if argv.len() != 2 {
+                    return self.display_help(ctx, argv).await;
+                }
+                let alias = argv[1].clone();
+                let existing = wallet
+                    .store()
+                    .as_address_book_store()?
+                    .load_single(&alias)
+                    .await?
+                    .ok_or_else(|| Error::custom(format!("contact '{alias}' not found")))?;
+
+                tprintln!(ctx, "press <enter> to keep the current value");
+                let mut entry = (*existing).clone();
+                let name = ctx.term().ask(false, &format!("Alias ({}): ", entry.alias)).await?.trim().to_string();
+                if !name.is_empty() {
+                    entry.alias = name;
+                }
+                let address = ctx.term().ask(false, &format!("Address ({}): ", entry.address)).await?.trim().to_string();
+                if !address.is_empty() {
+                    entry.address = Address::try_from(address.as_str())?;
+                }
+                let title = ctx.term().ask(false, &format!("Title ({}): ", entry.title)).await?.trim().to_string();
+                if !title.is_empty() {
+                    entry.title = title;
+                }
+                entry.network_id = self.ask_network_binding(&ctx, entry.network_id).await?;
+
+                let (wallet_secret, _) = ctx.ask_wallet_secret(None).await?;
+                wallet.address_book_edit_call(AddressBookEditRequest { wallet_secret, alias, entry }).await?;
+                tprintln!(ctx, "contact updated");
+            }
+            "remove" => {
+                if argv.len() != 2 {
+                    return self.display_help(ctx, argv).await;
+                }
+                let alias = argv[1].clone();
+                let (wallet_secret, _) = ctx.ask_wallet_secret(None).await?;
+                wallet.address_book_remove_call(AddressBookRemoveRequest { wallet_secret, alias }).await?;
+                tprintln!(ctx, "contact removed");
+            }
+            v => {
+                tprintln!(ctx, "unknown command: '{v}'\r\n");
+                return self.display_help(ctx, argv).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn list(&self, ctx: &Arc<WaglaylaCli>, entries: &[AddressBookEntry]) {
+        if entries.is_empty() {
+            tprintln!(ctx, "no contacts found");
+            return;
+        }
+
+        for entry in entries {
+            let network = entry.network_id.map(|network_id| format!(" [{network_id}]")).unwrap_or_default();
+            if entry.title.is_empty() {
+                tprintln!(ctx, "{}: {}{network}", style(&entry.alias).blue(), entry.address);
+            } else {
+                tprintln!(ctx, "{}: {} - {}{network}", style(&entry.alias).blue(), entry.address, entry.title);
+            }
+        }
+    }
+
+    /// Asks whether the contact should be restricted to the current wallet network
+    async fn ask_network_binding(&self, ctx: &Arc<WaglaylaCli>, current: Option<NetworkId>) -> Result<Option<NetworkId>> {
+        let network_id = ctx.wallet().network_id()?;
+        let default = if current.is_some() { "Y/n" } else { "y/N" };
+        let answer =
+            ctx.term().ask(false, &format!("Restrict to the {network_id} network? ({default}): ")).await?.trim().to_lowercase();
+        let bind = match answer.as_str() {
+            "" => current.is_some(),
+            "y" | "yes" => true,
+            _ => false,
+        };
+        Ok(bind.then_some(network_id))
+    }
+
+    async fn display_help(self: Arc<Self>, ctx: Arc<WaglaylaCli>, _argv: Vec<String>) -> Result<()> {
+        ctx.term().help(
+            &[
+                ("list", "List address book contacts"),
+                ("search <text>", "Search contacts by alias, title or address"),
+                ("add <alias> <address>", "Add a contact (prompts for the title and the network binding)"),
+                ("edit <alias>", "Edit a contact"),
+                ("remove <alias>", "Remove a contact"),
+            ],
+            None,
+        )?;
+
+        tprintln!(ctx);
+        tprintln!(ctx, "contact aliases can be used in place of addresses with the 'send' command");
+        tprintln!(ctx);
+
+        Ok(())
+    }
+}
@@ -1,13 +1,34 @@
 use crate::imports::*;
 
 #[derive(Default, Handler)]
+#[help("Create unsigned transactions (PSKT) to be signed by the account key holders")]
 pub struct CreateUnsignedTx;
 
 impl CreateUnsignedTx {
-    async fn main(self: Arc<Self>, ctx: &Arc<dyn Context>, _argv: Vec<String>, _cmd: &str) -> Result<()> {
+    async fn main(self: Arc<Self>, ctx: &Arc<dyn Context>, argv: Vec<String>, _cmd: &str) -> Result<()> {
         let ctx = ctx.clone().downcast_arc::<WaglaylaCli>()?;
-        let _account = ctx.wallet().account()?;
-        // TODO account.create_unsigned_transaction().await?;
+        let account = ctx.wallet().account()?;
+
+        if argv.len() < 2 {
+            tprintln!(ctx, "usage: create-unsigned-tx <address> <amount> [<priority fee>]");
+            return Ok(());
+        }
+
+        let address = Address::try_from(argv.first().unwrap().as_str())?;
+        let amount_sompi = try_parse_required_nonzero_waglayla_as_sompi_u64(argv.get(1))?;
+        let priority_fee_sompi = try_parse_optional_waglayla_as_sompi_i64(argv.get(2))?.unwrap_or(0);
+        let outputs = PaymentOutputs::from((address, amount_sompi));
+        let abortable = Abortable::default();
+
+        let (summary, pskts) =
+            account.create_unsigned_transactions(outputs.into(), priority_fee_sompi.into(), None, None, &abortable).await?;
+
+        tprintln!(ctx, "Created {} unsigned transaction(s) - {summary}", pskts.len());
+        for pskt in pskts.iter() {
+            tprintln!(ctx, "\n{}", pskt.serialize()?);
+        }
+        tprintln!(ctx, "\nSign each transaction using 'sign <pskt>' and submit them in order using 'broadcast <pskt>'\n");
+
         Ok(())
     }
 }
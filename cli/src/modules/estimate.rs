@@ -1,5 +1,4 @@
 use crate::imports::*;
-use waglayla_wallet_core::tx::PaymentDestination;
 
 #[derive(Default, Handler)]
 #[help("Estimate the fees for a transaction of a given amount")]
@@ -12,18 +11,25 @@ impl Estimate {
         let account = ctx.wallet().account()?;
 
         if argv.is_empty() {
-            tprintln!(ctx, "usage: estimate <amount> [<priority fee>]");
+            tprintln!(ctx, "usage: estimate <amount> [<priority fee|priority|normal|low>] [<txid>-<index> ...]");
             return Ok(());
         }
 
         let amount_sompi = try_parse_required_nonzero_waglayla_as_sompi_u64(argv.first())?;
-        let priority_fee_sompi = try_parse_optional_waglayla_as_sompi_i64(argv.get(1))?.unwrap_or(0);
+        let priority_fee = try_parse_priority_fee(argv.get(1))?;
+        let utxo_selection = try_parse_utxo_selection(argv.get(2..))?;
         let abortable = Abortable::default();
 
         // just use any address for an estimate (change address)
         let change_address = account.change_address()?;
         let destination = PaymentDestination::PaymentOutputs(PaymentOutputs::from((change_address.clone(), amount_sompi)));
-        let estimate = account.estimate(destination, priority_fee_sompi.into(), None, &abortable).await?;
+        let fees = match priority_fee {
+            PriorityFee::Sompi(priority_fee_sompi) => priority_fee_sompi.into(),
+            PriorityFee::FeeRate(priority) => {
+                account.clone().fees_from_fee_estimate(destination.clone(), priority, None, utxo_selection.clone(), &abortable).await?
+            }
+        };
+        let estimate = account.estimate(destination, fees, None, utxo_selection, &abortable).await?;
 
         tprintln!(ctx, "Estimate - {estimate}");
 
@@ -1,5 +1,9 @@
 use crate::imports::*;
+use chrono::NaiveDate;
+use std::path::Path;
 use waglayla_wallet_core::account::{multisig::MultiSig, Account, MULTISIG_ACCOUNT_KIND};
+use waglayla_wallet_core::storage::transaction::{TransactionExportFilter, TransactionExportFormat};
+use workflow_store::fs;
 
 #[derive(Default, Handler)]
 #[help("Export transactions, a wallet or a private key")]
@@ -10,12 +14,19 @@ impl Export {
         let ctx = ctx.clone().downcast_arc::<WaglaylaCli>()?;
 
         if argv.is_empty() || argv.first() == Some(&"help".to_string()) {
-            tprintln!(ctx, "usage: export [mnemonic]");
+            tprintln!(ctx, "usage: export [mnemonic|transactions]");
+            tprintln!(ctx, "");
+            tprintln!(ctx, "export transactions [csv|json] [all] [from <YYYY-MM-DD>] [to <YYYY-MM-DD>]");
+            tprintln!(ctx, "                    [from-daa <score>] [to-daa <score>] [file <path>]");
+            tprintln!(ctx, "");
+            tprintln!(ctx, "    exports the transaction history of the selected account (or of all accounts");
+            tprintln!(ctx, "    if 'all' is specified), dates are in UTC and inclusive, the 'to-daa' score is excluded");
             return Ok(());
         }
 
         let what = argv.first().unwrap();
         match what.as_str() {
+            "transactions" => export_transactions(ctx, &argv[1..]).await,
             "mnemonic" => {
                 let account = ctx.account().await?;
                 if account.account_kind() == MULTISIG_ACCOUNT_KIND {
@@ -30,6 +41,59 @@ impl Export {
     }
 }
 
+async fn export_transactions(ctx: Arc<WaglaylaCli>, argv: &[String]) -> Result<()> {
+    let mut format = TransactionExportFormat::default();
+    let mut all_accounts = false;
+    let mut filter = TransactionExportFilter::default();
+    let mut file = None;
+
+    let mut args = argv.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "csv" | "json" => format = arg.parse()?,
+            "all" => all_accounts = true,
+            "from" | "to" | "from-daa" | "to-daa" | "file" => {
+                let value = args.next().ok_or_else(|| Error::custom(format!("missing value for '{arg}'")))?;
+                match arg.as_str() {
+                    "from" => filter.start_unixtime_msec = Some(parse_date_msec(value)?),
+                    "to" => filter.end_unixtime_msec = Some(parse_date_msec(value)? + 24 * 60 * 60 * 1000),
+                    "from-daa" => filter.start_daa_score = Some(value.parse()?),
+                    "to-daa" => filter.end_daa_score = Some(value.parse()?),
+                    _ => file = Some(value.clone()),
+                }
+            }
+            _ => return Err(format!("Invalid argument: {}", arg).into()),
+        }
+    }
+
+    let wallet = ctx.wallet();
+    let network_id = wallet.network_id()?;
+    let account_ids = if all_accounts { None } else { Some(vec![*ctx.account().await?.id()]) };
+
+    let mut stream = wallet.export_transactions(network_id, account_ids, filter, format).await?;
+    let mut data = String::new();
+    while let Some(chunk) = stream.try_next().await? {
+        data.push_str(&chunk);
+    }
+
+    if let Some(file) = file {
+        fs::write_string(Path::new(&file), &data).await?;
+        tprintln!(ctx, "transactions exported to '{file}'");
+    } else {
+        data.lines().for_each(|line| tprintln!(ctx, "{line}"));
+    }
+
+    Ok(())
+}
+
+/// Parses a `YYYY-MM-DD` date into the unix time (in milliseconds) of its UTC midnight
+fn parse_date_msec(date: &str) -> Result<u64> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| Error::custom(format!("invalid date '{date}' (expected YYYY-MM-DD)")))?;
+    let timestamp = date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp_millis();
+    u64::try_from(timestamp).map_err(|_| Error::custom(format!("date '{date}' precedes the unix epoch")))
+}
+
 async fn export_multisig_account(ctx: Arc<WaglaylaCli>, account: Arc<MultiSig>) -> Result<()> {
     match &account.prv_key_data_ids() {
         None => Err(Error::KeyDataNotFound),
@@ -5,6 +5,7 @@ pub mod address;
 pub mod broadcast;
 pub mod close;
 pub mod connect;
+pub mod contact;
 #[path = "create-unsigned-tx.rs"]
 pub mod create_unsigned_tx;
 pub mod details;
@@ -40,25 +41,21 @@ pub mod sweep;
 pub mod theme;
 pub mod track;
 pub mod transfer;
+pub mod utxo;
 pub mod wallet;
 
 // this module is registered manually within
 // applications that support metrics
 pub mod metrics;
 
-// TODO
-// broadcast
-// create-unsigned-tx
-// sign
-
 pub fn register_handlers(cli: &Arc<WaglaylaCli>) -> Result<()> {
     register_handlers!(
         cli,
         cli.handlers(),
         [
-            account, address, close, connect, details, disconnect, estimate, exit, export, guide, help, history, rpc, list, miner,
-            message, monitor, mute, network, node, open, ping, reload, select, send, server, settings, sweep, track, transfer,
-            wallet,
+            account, address, broadcast, close, connect, contact, create_unsigned_tx, details, disconnect, estimate, exit,
+            export, guide, help, history, rpc, list, miner, message, monitor, mute, network, node, open, ping, reload, select,
+            send, server, settings, sign, sweep, track, transfer, utxo, wallet,
             // halt,
             // theme,  start, stop
         ]
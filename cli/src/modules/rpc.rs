@@ -65,6 +65,10 @@ impl Rpc {
                 let result = rpc.get_peer_addresses_call(GetPeerAddressesRequest {}).await?;
                 self.println(&ctx, result);
             }
+            RpcApiOps::GetBannedPeers => {
+                let result = rpc.get_banned_peers_call(GetBannedPeersRequest {}).await?;
+                self.println(&ctx, result);
+            }
             RpcApiOps::GetSink => {
                 let result = rpc.get_sink_call(GetSinkRequest {}).await?;
                 self.println(&ctx, result);
@@ -170,17 +174,25 @@ impl Rpc {
             }
             RpcApiOps::Ban => {
                 if argv.is_empty() {
-                    return Err(Error::custom("Please specify peer IP address"));
+                    return Err(Error::custom(
+                        "Please specify peer IP address or subnet and optionally a ban duration in seconds and a reason",
+                    ));
                 }
-                let ip: RpcIpAddress = argv.remove(0).parse()?;
-                let result = rpc.ban_call(BanRequest { ip }).await?;
+                let ip = argv.remove(0).parse::<RpcIpSubnet>().map_err(|err| Error::custom(err.to_string()))?;
+                let duration = if argv.is_empty() {
+                    None
+                } else {
+                    Some(argv.remove(0).parse::<u64>().map_err(|_| Error::custom("Could not parse ban duration to u64"))?)
+                };
+                let reason = (!argv.is_empty()).then(|| argv.join(" "));
+                let result = rpc.ban_call(BanRequest { ip, duration, reason }).await?;
                 self.println(&ctx, result);
             }
             RpcApiOps::Unban => {
                 if argv.is_empty() {
-                    return Err(Error::custom("Please specify peer IP address"));
+                    return Err(Error::custom("Please specify peer IP address or subnet"));
                 }
-                let ip: RpcIpAddress = argv.remove(0).parse()?;
+                let ip = argv.remove(0).parse::<RpcIpSubnet>().map_err(|err| Error::custom(err.to_string()))?;
                 let result = rpc.unban_call(UnbanRequest { ip }).await?;
                 self.println(&ctx, result);
             }
@@ -229,6 +241,66 @@ impl Rpc {
                     }
                 }
             }
+            RpcApiOps::GetFeeEstimate => {
+                let result = rpc.get_fee_estimate_call(GetFeeEstimateRequest {}).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetTransaction => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Missing transaction id argument"));
+                }
+                let transaction_id = argv.remove(0);
+                let transaction_id = RpcTransactionId::from_hex(transaction_id.as_str())?;
+                let result = rpc.get_transaction_call(GetTransactionRequest { transaction_id }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetTransactionsByAddresses => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Please specify at least one address"));
+                }
+                let addresses = argv.iter().map(|s| Address::try_from(s.as_str())).collect::<std::result::Result<Vec<_>, _>>()?;
+                let result = rpc
+                    .get_transactions_by_addresses_call(GetTransactionsByAddressesRequest {
+                        addresses,
+                        from_daa_score: 0,
+                        limit: api::rpc::MAX_ADDRESS_TRANSACTIONS_PAGE_SIZE,
+                    })
+                    .await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetChainBlockByDaaScore => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Please specify a daa_score"));
+                }
+                let daa_score = argv.remove(0).parse::<u64>().map_err(|_| Error::custom("Could not parse daa_score to u64"))?;
+                let result = rpc.get_chain_block_by_daa_score_call(GetChainBlockByDaaScoreRequest { daa_score }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetChainBlockByTimestamp => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Please specify a timestamp in milliseconds"));
+                }
+                let timestamp = argv.remove(0).parse::<u64>().map_err(|_| Error::custom("Could not parse timestamp to u64"))?;
+                let result = rpc.get_chain_block_by_timestamp_call(GetChainBlockByTimestampRequest { timestamp }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetChainBlockHeaders => {
+                if argv.len() < 2 {
+                    return Err(Error::custom("Please specify a from_daa_score and a to_daa_score"));
+                }
+                let daa_scores = argv.iter().take(2).map(|s| s.parse::<u64>()).collect::<std::result::Result<Vec<_>, _>>();
+                let Ok(daa_scores) = daa_scores else {
+                    return Err(Error::custom("Could not parse daa_scores to u64"));
+                };
+                let result = rpc
+                    .get_chain_block_headers_call(GetChainBlockHeadersRequest {
+                        from_daa_score: daa_scores[0],
+                        to_daa_score: daa_scores[1],
+                        limit: api::rpc::MAX_CHAIN_BLOCK_HEADERS_PAGE_SIZE,
+                    })
+                    .await?;
+                self.println(&ctx, result);
+            }
             _ => {
                 tprintln!(ctx, "rpc method exists but is not supported by the cli: '{op_str}'\r\n");
                 return Ok(());
@@ -12,23 +12,31 @@ impl Send {
         let account = ctx.wallet().account()?;
 
         if argv.len() < 2 {
-            tprintln!(ctx, "usage: send <address> <amount> <priority fee>");
+            tprintln!(ctx, "usage: send <address|contact> <amount> <priority fee|priority|normal|low> [<txid>-<index> ...]");
             return Ok(());
         }
 
-        let address = Address::try_from(argv.first().unwrap().as_str())?;
+        let address = ctx.wallet().resolve_destination(argv.first().unwrap()).await?;
         let amount_sompi = try_parse_required_nonzero_waglayla_as_sompi_u64(argv.get(1))?;
-        let priority_fee_sompi = try_parse_optional_waglayla_as_sompi_i64(argv.get(2))?.unwrap_or(0);
-        let outputs = PaymentOutputs::from((address.clone(), amount_sompi));
+        let priority_fee = try_parse_priority_fee(argv.get(2))?;
+        let utxo_selection = try_parse_utxo_selection(argv.get(3..))?;
+        let destination: PaymentDestination = PaymentOutputs::from((address.clone(), amount_sompi)).into();
         let abortable = Abortable::default();
+        let fees = match priority_fee {
+            PriorityFee::Sompi(priority_fee_sompi) => priority_fee_sompi.into(),
+            PriorityFee::FeeRate(priority) => {
+                account.clone().fees_from_fee_estimate(destination.clone(), priority, None, utxo_selection.clone(), &abortable).await?
+            }
+        };
         let (wallet_secret, payment_secret) = ctx.ask_wallet_secret(Some(&account)).await?;
 
         // let ctx_ = ctx.clone();
         let (summary, _ids) = account
             .send(
-                outputs.into(),
-                priority_fee_sompi.into(),
+                destination,
+                fees,
                 None,
+                utxo_selection,
                 wallet_secret,
                 payment_secret,
                 &abortable,
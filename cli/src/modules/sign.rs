@@ -5,9 +5,25 @@ use crate::imports::*;
 pub struct Sign;
 
 impl Sign {
-    async fn main(self: Arc<Self>, ctx: &Arc<dyn Context>, _argv: Vec<String>, _cmd: &str) -> Result<()> {
-        let _ctx = ctx.clone().downcast_arc::<WaglaylaCli>()?;
-        // TODO - ctx.wallet().account()?.sign().await?;
+    async fn main(self: Arc<Self>, ctx: &Arc<dyn Context>, argv: Vec<String>, _cmd: &str) -> Result<()> {
+        let ctx = ctx.clone().downcast_arc::<WaglaylaCli>()?;
+        let account = ctx.wallet().account()?;
+
+        if argv.is_empty() {
+            tprintln!(ctx, "usage: sign <pskt>");
+            return Ok(());
+        }
+
+        let mut pskt = Pskt::deserialize(argv.first().unwrap())?;
+        let (wallet_secret, payment_secret) = ctx.ask_wallet_secret(Some(&account)).await?;
+        let signatures = account.sign_pskt(&mut pskt, wallet_secret, payment_secret).await?;
+
+        tprintln!(ctx, "Added {signatures} signature(s) to transaction {}", pskt.id());
+        for (index, input) in pskt.inputs.iter().enumerate() {
+            let (signatures, required) = input.signature_status()?;
+            tprintln!(ctx, "  input {index}: {signatures} of {required} required signature(s)");
+        }
+        tprintln!(ctx, "\n{}\n", pskt.serialize()?);
 
         Ok(())
     }
@@ -0,0 +1,102 @@
+use crate::imports::*;
+
+#[derive(Default, Handler)]
+#[help("List, freeze and unfreeze UTXOs of the selected account (coin control)")]
+pub struct Utxo;
+
+impl Utxo {
+    async fn main(self: Arc<Self>, ctx: &Arc<dyn Context>, argv: Vec<String>, _cmd: &str) -> Result<()> {
+        let ctx = ctx.clone().downcast_arc::<WaglaylaCli>()?;
+        let wallet = ctx.wallet();
+
+        if !wallet.is_open() {
+            return Err(Error::WalletIsNotOpen);
+        }
+
+        if argv.is_empty() {
+            return self.display_help(ctx, argv).await;
+        }
+
+        let account = ctx.account().await?;
+        let account_id = *account.id();
+
+        let action = argv.first().unwrap();
+        match action.as_str() {
+            "list" => {
+                let AccountsGetUtxosResponse { utxos } =
+                    wallet.accounts_get_utxos_call(AccountsGetUtxosRequest { account_id }).await?;
+                let frozen_only = argv.get(1).map(|arg| arg == "frozen").unwrap_or(false);
+                self.list(&ctx, utxos.iter().filter(|utxo| !frozen_only || utxo.is_frozen));
+            }
+            "freeze" | "unfreeze" => {
+                if argv.len() < 2 {
+                    return self.display_help(ctx, argv).await;
+                }
+                let outpoints = argv[1..].iter().map(|outpoint| try_parse_outpoint(outpoint)).collect::<Result<Vec<_>>>()?;
+                if action == "freeze" {
+                    wallet.accounts_freeze_utxos_call(AccountsFreezeUtxosRequest { account_id, outpoints }).await?;
+                    tprintln!(ctx, "UTXOs frozen");
+                } else {
+                    wallet.accounts_unfreeze_utxos_call(AccountsUnfreezeUtxosRequest { account_id, outpoints }).await?;
+                    tprintln!(ctx, "UTXOs unfrozen");
+                }
+            }
+            v => {
+                tprintln!(ctx, "unknown command: '{v}'\r\n");
+                return self.display_help(ctx, argv).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn list<'a>(&self, ctx: &Arc<WaglaylaCli>, utxos: impl Iterator<Item = &'a AccountUtxo>) {
+        let mut empty = true;
+        for utxo in utxos {
+            empty = false;
+            let AccountUtxo { outpoint, address, amount, is_coinbase, is_mature, is_frozen, .. } = utxo;
+            let mut flags = vec![];
+            if !is_mature {
+                flags.push("pending");
+            }
+            if *is_coinbase {
+                flags.push("coinbase");
+            }
+            if *is_frozen {
+                flags.push("frozen");
+            }
+            let flags = if flags.is_empty() { String::new() } else { format!(" [{}]", flags.join(", ")) };
+            let address = address.as_ref().map(|address| address.to_string()).unwrap_or_default();
+            tprintln!(
+                ctx,
+                "{}-{}: {} WALA {}{flags}",
+                outpoint.transaction_id,
+                outpoint.index,
+                style(sompi_to_waglayla_string(*amount)).green(),
+                style(address).blue()
+            );
+        }
+
+        if empty {
+            tprintln!(ctx, "no UTXOs found");
+        }
+    }
+
+    async fn display_help(self: Arc<Self>, ctx: Arc<WaglaylaCli>, _argv: Vec<String>) -> Result<()> {
+        ctx.term().help(
+            &[
+                ("list [frozen]", "List UTXOs of the selected account (or only the frozen ones)"),
+                ("freeze <txid>-<index> ...", "Exclude UTXOs from the automatic UTXO selection"),
+                ("unfreeze <txid>-<index> ...", "Allow frozen UTXOs to be selected automatically again"),
+            ],
+            None,
+        )?;
+
+        tprintln!(ctx);
+        tprintln!(ctx, "UTXOs can be spent explicitly, even if frozen, by listing them after the");
+        tprintln!(ctx, "priority fee of the 'send' and 'estimate' commands");
+        tprintln!(ctx);
+
+        Ok(())
+    }
+}
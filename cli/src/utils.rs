@@ -1,7 +1,10 @@
 use crate::error::Error;
 use crate::result::Result;
 use waglayla_consensus_core::constants::SOMPI_PER_WAGLAYLA;
+use waglayla_consensus_core::tx::{TransactionId, TransactionOutpoint};
+use waglayla_wallet_core::tx::FeeRatePriority;
 use std::fmt::Display;
+use std::str::FromStr;
 
 pub fn try_parse_required_nonzero_waglayla_as_sompi_u64<S: ToString + Display>(waglayla_amount: Option<S>) -> Result<u64> {
     if let Some(waglayla_amount) = waglayla_amount {
@@ -58,3 +61,40 @@ pub fn try_parse_optional_waglayla_as_sompi_i64<S: ToString + Display>(waglayla_
         Ok(None)
     }
 }
+
+/// Priority fee supplied to the `send` and `estimate` commands.
+pub enum PriorityFee {
+    /// Explicit priority fee in sompi
+    Sompi(i64),
+    /// Fees selected from the node fee estimate for the given priority
+    FeeRate(FeeRatePriority),
+}
+
+/// Parses an optional priority fee supplied either as a Waglayla amount
+/// or as a `priority`, `normal` or `low` fee rate priority.
+pub fn try_parse_priority_fee<S: ToString + Display>(priority_fee: Option<S>) -> Result<PriorityFee> {
+    if let Some(priority) = priority_fee.as_ref().and_then(|fee| FeeRatePriority::try_from(fee.to_string().as_str()).ok()) {
+        Ok(PriorityFee::FeeRate(priority))
+    } else {
+        Ok(PriorityFee::Sompi(try_parse_optional_waglayla_as_sompi_i64(priority_fee)?.unwrap_or(0)))
+    }
+}
+
+/// Parses a UTXO outpoint supplied as `<txid>-<index>`.
+pub fn try_parse_outpoint(outpoint: &str) -> Result<TransactionOutpoint> {
+    let invalid = || Error::custom(format!("Supplied outpoint is not valid: '{outpoint}' (expected <txid>-<index>)"));
+    let (transaction_id, index) = outpoint.split_once('-').ok_or_else(invalid)?;
+    let transaction_id = TransactionId::from_str(transaction_id).map_err(|_| invalid())?;
+    let index = index.parse::<u32>().map_err(|_| invalid())?;
+    Ok(TransactionOutpoint::new(transaction_id, index))
+}
+
+/// Parses an optional list of `<txid>-<index>` outpoints used for explicit UTXO selection.
+pub fn try_parse_utxo_selection(outpoints: Option<&[String]>) -> Result<Option<Vec<TransactionOutpoint>>> {
+    match outpoints {
+        Some(outpoints) if !outpoints.is_empty() => {
+            Ok(Some(outpoints.iter().map(|outpoint| try_parse_outpoint(outpoint)).collect::<Result<Vec<_>>>()?))
+        }
+        _ => Ok(None),
+    }
+}
@@ -2,8 +2,10 @@ use crate::error::Error;
 use crate::imports::*;
 use crate::result::Result;
 use crate::WaglaylaCli;
-use waglayla_bip32::{Language, Mnemonic};
+use waglayla_bip32::{secp256k1, ExtendedPublicKey, Language, Mnemonic};
+use waglayla_wallet_core::account::watchonly::WatchOnlySource;
 use waglayla_wallet_core::account::{BIP32_ACCOUNT_KIND, LEGACY_ACCOUNT_KIND, MULTISIG_ACCOUNT_KIND};
+use std::str::FromStr;
 use std::sync::Arc;
 
 pub async fn prompt_for_mnemonic(term: &Arc<Terminal>) -> Result<Vec<String>> {
@@ -130,3 +132,61 @@ pub(crate) async fn import_with_mnemonic(ctx: &Arc<WaglaylaCli>, account_kind: A
     wallet.select(Some(&account)).await?;
     Ok(())
 }
+
+/// Imports a watch-only account tracking the funds of an extended public key (`xpub`),
+/// a public key (`pubkey`) or a list of addresses (`addresses`) without any private keys.
+/// The keys are ECDSA keys if `ecdsa` is set, Schnorr keys otherwise.
+pub(crate) async fn import_watch_only(ctx: &Arc<WaglaylaCli>, source_kind: &str, args: &[String], ecdsa: bool) -> Result<()> {
+    let wallet = ctx.wallet();
+
+    if !wallet.is_open() {
+        return Err(Error::WalletIsNotOpen);
+    }
+
+    let term = ctx.term();
+
+    let args = if args.is_empty() {
+        let prompt = match source_kind {
+            "xpub" => "Enter extended public key: ",
+            "pubkey" => "Enter public key (hex): ",
+            "addresses" => "Enter space separated addresses: ",
+            _ => return Err(Error::Custom(format!("unsupported watch-only source: '{source_kind}'"))),
+        };
+        term.ask(false, prompt).await?.split_whitespace().map(|s| s.to_string()).collect::<Vec<_>>()
+    } else {
+        args.to_vec()
+    };
+
+    if args.is_empty() {
+        return Err(Error::UserAbort);
+    }
+
+    let source = match source_kind {
+        "xpub" | "pubkey" if args.len() > 1 => return Err(Error::Custom(format!("too many arguments: {}", args[1..].join(" ")))),
+        "xpub" => WatchOnlySource::Xpub(
+            ExtendedPublicKey::<secp256k1::PublicKey>::from_str(&args[0])
+                .map_err(|err| Error::Custom(format!("invalid extended public key: {err}")))?,
+        ),
+        "pubkey" => WatchOnlySource::PublicKey(
+            secp256k1::PublicKey::from_str(&args[0]).map_err(|err| Error::Custom(format!("invalid public key: {err}")))?,
+        ),
+        "addresses" => {
+            let addresses =
+                args.iter().map(|address| Address::try_from(address.as_str())).collect::<std::result::Result<Vec<_>, _>>()?;
+            WatchOnlySource::try_from_addresses(addresses)?
+        }
+        _ => return Err(Error::Custom(format!("unsupported watch-only source: '{source_kind}'"))),
+    };
+
+    tprintln!(ctx);
+    let account_name = term.ask(false, "Please enter account name (optional, press <enter> to skip): ").await?.trim().to_string();
+    let account_name = account_name.is_not_empty().then_some(account_name);
+    let wallet_secret = Secret::new(term.ask(true, "Enter wallet password: ").await?.trim().as_bytes().to_vec());
+
+    let account_create_args = AccountCreateArgs::new_watch_only(account_name, source, ecdsa);
+    let account = wallet.create_account(&wallet_secret, account_create_args, true).await?;
+
+    tprintln!(ctx, "\naccount imported: {}\n", account.get_list_string()?);
+    wallet.select(Some(&account)).await?;
+    Ok(())
+}
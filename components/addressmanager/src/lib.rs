@@ -2,7 +2,13 @@ mod port_mapping_extender;
 mod stores;
 extern crate self as address_manager;
 
-use std::{collections::HashSet, iter, net::SocketAddr, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    iter,
+    net::SocketAddr,
+    sync::Arc,
+    time::Duration,
+};
 
 use address_manager::port_mapping_extender::Extender;
 use igd_next::{
@@ -15,14 +21,17 @@ use itertools::{
 };
 use waglayla_consensus_core::config::Config;
 use waglayla_core::{debug, info, task::tick::TickService, time::unix_now, warn};
-use waglayla_database::prelude::{CachePolicy, StoreResultExtensions, DB};
-use waglayla_utils::networking::IpAddress;
+use waglayla_database::prelude::{CachePolicy, DB};
+use waglayla_utils::networking::{IpAddress, IpSubnet, OnionAddress};
 use local_ip_address::list_afinet_netifas;
 use parking_lot::Mutex;
-use stores::banned_address_store::{BannedAddressesStore, BannedAddressesStoreReader, ConnectionBanTimestamp, DbBannedAddressesStore};
+use stores::{
+    banned_address_store::{DbBannedAddressesStore, LEGACY_BAN_DURATION},
+    banned_subnet_store::{BannedSubnetsStore, BannedSubnetsStoreReader, DbBannedSubnetsStore},
+};
 use thiserror::Error;
 
-pub use stores::NetAddress;
+pub use stores::{banned_subnet_store::BanEntry, NetAddress};
 
 const MAX_ADDRESSES: usize = 4096;
 const MAX_CONNECTION_FAILED_COUNT: u64 = 3;
@@ -52,7 +61,8 @@ pub enum UpnpError {
 }
 
 pub struct AddressManager {
-    banned_address_store: DbBannedAddressesStore,
+    banned_subnet_store: DbBannedSubnetsStore,
+    banned_subnets: HashMap<IpSubnet, BanEntry>,
     address_store: address_store_with_cache::Store,
     config: Arc<Config>,
     local_net_addresses: Vec<NetAddress>,
@@ -60,8 +70,13 @@ pub struct AddressManager {
 
 impl AddressManager {
     pub fn new(config: Arc<Config>, db: Arc<DB>, tick_service: Arc<TickService>) -> (Arc<Mutex<Self>>, Option<Extender>) {
+        // Bans are kept in memory as well, since checking whether an IP is banned requires going over all banned subnets
+        let mut banned_subnet_store = DbBannedSubnetsStore::new(db.clone(), CachePolicy::Empty);
+        Self::migrate_legacy_bans(db.clone(), &mut banned_subnet_store);
+        let banned_subnets = banned_subnet_store.iterator().map(|res| res.unwrap()).collect();
         let mut instance = Self {
-            banned_address_store: DbBannedAddressesStore::new(db.clone(), CachePolicy::Count(MAX_ADDRESSES)),
+            banned_subnet_store,
+            banned_subnets,
             address_store: address_store_with_cache::new(db),
             local_net_addresses: Vec::new(),
             config,
@@ -72,10 +87,29 @@ impl AddressManager {
         (Arc::new(Mutex::new(instance)), extender)
     }
 
+    /// Moves the still active legacy per-IP bans to the banned subnets store. Legacy entries hold the ban
+    /// start time, so their expiry is derived from the fixed legacy ban duration
+    fn migrate_legacy_bans(db: Arc<DB>, banned_subnet_store: &mut DbBannedSubnetsStore) {
+        let mut legacy_store = DbBannedAddressesStore::new(db, CachePolicy::Empty);
+        let now = unix_now();
+        let mut migrated = 0;
+        for (ip, timestamp) in legacy_store.iterator().filter_map(|res| res.ok()) {
+            let expiry = timestamp.0.saturating_add(LEGACY_BAN_DURATION);
+            if expiry > now && banned_subnet_store.get(ip.into()).is_err() {
+                banned_subnet_store.set(ip.into(), BanEntry::new(expiry, "legacy ban".to_string())).unwrap();
+                migrated += 1;
+            }
+        }
+        legacy_store.delete_all().unwrap();
+        if migrated > 0 {
+            info!("Migrated {} legacy banned addresses", migrated);
+        }
+    }
+
     fn init_local_addresses(&mut self, tick_service: Arc<TickService>) -> Option<Extender> {
         self.local_net_addresses = self.local_addresses().collect();
 
-        let extender = if self.local_net_addresses.is_empty() && !self.config.disable_upnp {
+        let extender = if self.local_net_addresses.is_empty() && !self.config.disable_upnp && !self.config.disable_address_discovery {
             let (net_address, ExtendHelper { gateway, local_addr, external_port }) = match self.upnp() {
                 Err(err) => {
                     warn!("[UPnP] Error adding port mapping: {err}");
@@ -115,7 +149,7 @@ impl AddressManager {
     fn local_addresses(&self) -> impl Iterator<Item = NetAddress> + '_ {
         match self.config.externalip {
             // An external IP was passed, we will try to bind that if it's valid
-            Some(local_net_address) if local_net_address.ip.is_publicly_routable() => {
+            Some(local_net_address) if local_net_address.is_publicly_routable() => {
                 info!("External address is publicly routable {}", local_net_address);
                 return Left(iter::once(local_net_address));
             }
@@ -129,6 +163,10 @@ impl AddressManager {
     }
 
     fn routable_addresses_from_net_interfaces(&self) -> impl Iterator<Item = NetAddress> + '_ {
+        if self.config.disable_address_discovery {
+            return Left(Right(iter::empty()));
+        }
+
         // check whatever was passed as listen address (if routable)
         // otherwise(listen_address === 0.0.0.0) check all interfaces
         let listen_address = self.config.p2p_listen_address.normalize(self.config.default_p2p_port());
@@ -210,7 +248,7 @@ impl AddressManager {
             let port =
                 gateway.add_any_port(igd::PortMappingProtocol::TCP, local_addr, UPNP_DEADLINE_SEC as u32, UPNP_REGISTRATION_NAME)?;
             info!("[UPnP] Added port mapping to random external port: {ip}:{port}");
-            return Ok(Some((NetAddress { ip, port }, ExtendHelper { gateway, local_addr, external_port: port })));
+            return Ok(Some((NetAddress::new(ip, port), ExtendHelper { gateway, local_addr, external_port: port })));
         }
 
         match gateway.add_port(
@@ -223,7 +261,7 @@ impl AddressManager {
             Ok(_) => {
                 info!("[UPnP] Added port mapping to default external port: {ip}:{desired_external_port}");
                 Ok(Some((
-                    NetAddress { ip, port: desired_external_port },
+                    NetAddress::new(ip, desired_external_port),
                     ExtendHelper { gateway, local_addr, external_port: desired_external_port },
                 )))
             }
@@ -235,7 +273,7 @@ impl AddressManager {
                     UPNP_REGISTRATION_NAME,
                 )?;
                 info!("[UPnP] Added port mapping to random external port: {ip}:{port}");
-                Ok(Some((NetAddress { ip, port }, ExtendHelper { gateway, local_addr, external_port: port })))
+                Ok(Some((NetAddress::new(ip, port), ExtendHelper { gateway, local_addr, external_port: port })))
             }
             Err(err) => Err(err.into()),
         }
@@ -257,6 +295,12 @@ impl AddressManager {
             return;
         }
 
+        // The onion address behind an OnionCat IP cannot be recovered, so such addresses are not dialable
+        if !address.is_onion() && OnionAddress::is_mapped_ip(&address.ip) {
+            debug!("[Address manager] skipping onion mapped address {} with unknown onion address", address.ip);
+            return;
+        }
+
         if self.address_store.has(address) {
             return;
         }
@@ -294,27 +338,34 @@ impl AddressManager {
         self.address_store.iterate_prioritized_random_addresses(exceptions)
     }
 
-    pub fn ban(&mut self, ip: IpAddress) {
-        self.banned_address_store.set(ip.into(), ConnectionBanTimestamp(unix_now())).unwrap();
-        self.address_store.remove_by_ip(ip.into());
+    /// Bans `subnet` for `duration` with the given `reason`, removing all its known addresses
+    pub fn ban(&mut self, subnet: IpSubnet, duration: Duration, reason: String) {
+        let entry = BanEntry::new(unix_now().saturating_add(u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)), reason);
+        self.banned_subnet_store.set(subnet, entry.clone()).unwrap();
+        self.banned_subnets.insert(subnet, entry);
+        self.address_store.remove_by_subnet(subnet);
     }
 
-    pub fn unban(&mut self, ip: IpAddress) {
-        self.banned_address_store.remove(ip.into()).unwrap();
+    /// Lifts the ban of `subnet`. Returns `false` if `subnet` is not banned
+    pub fn unban(&mut self, subnet: IpSubnet) -> bool {
+        if self.banned_subnets.remove(&subnet).is_none() {
+            return false;
+        }
+        self.banned_subnet_store.remove(subnet).unwrap();
+        true
     }
 
+    /// Returns whether `ip` belongs to any banned subnet
     pub fn is_banned(&mut self, ip: IpAddress) -> bool {
-        const MAX_BANNED_TIME: u64 = 24 * 60 * 60 * 1000;
-        match self.banned_address_store.get(ip.into()).unwrap_option() {
-            Some(timestamp) => {
-                if unix_now() - timestamp.0 > MAX_BANNED_TIME {
-                    self.unban(ip);
-                    false
-                } else {
-                    true
-                }
-            }
-            None => false,
+        self.remove_expired_bans();
+        self.banned_subnets.keys().any(|subnet| subnet.contains(&ip))
+    }
+
+    fn remove_expired_bans(&mut self) {
+        let now = unix_now();
+        let expired = self.banned_subnets.iter().filter(|(_, entry)| now >= entry.expiry).map(|(subnet, _)| *subnet).collect_vec();
+        for subnet in expired {
+            self.unban(subnet);
         }
     }
 
@@ -322,8 +373,14 @@ impl AddressManager {
         self.address_store.iterate_addresses().collect_vec()
     }
 
-    pub fn get_all_banned_addresses(&self) -> Vec<IpAddress> {
-        self.banned_address_store.iterator().map(|x| IpAddress::from(x.unwrap().0)).collect_vec()
+    /// Returns all banned subnets, single banned IPs being full-length subnets
+    pub fn get_all_banned_addresses(&mut self) -> Vec<IpSubnet> {
+        self.get_banned_subnets().into_iter().map(|(subnet, _)| subnet).collect_vec()
+    }
+
+    pub fn get_banned_subnets(&mut self) -> Vec<(IpSubnet, BanEntry)> {
+        self.remove_expired_bans();
+        self.banned_subnets.iter().map(|(subnet, entry)| (*subnet, entry.clone())).collect_vec()
     }
 }
 
@@ -332,13 +389,12 @@ mod address_store_with_cache {
     // We don't expect it to be expensive since we limit the number of saved addresses.
     use std::{
         collections::{HashMap, HashSet},
-        net::IpAddr,
         sync::Arc,
     };
 
     use itertools::Itertools;
     use waglayla_database::prelude::{CachePolicy, DB};
-    use waglayla_utils::networking::PrefixBucket;
+    use waglayla_utils::networking::{IpSubnet, PrefixBucket};
     use rand::{
         distributions::{WeightedError, WeightedIndex},
         prelude::Distribution,
@@ -346,7 +402,7 @@ mod address_store_with_cache {
 
     use crate::{
         stores::{
-            address_store::{AddressesStore, DbAddressesStore, Entry},
+            address_store::{AddressesStore, AddressesStoreReader, DbAddressesStore, DbLegacyAddressesStore, Entry},
             AddressKey,
         },
         NetAddress, MAX_ADDRESSES, MAX_CONNECTION_FAILED_COUNT,
@@ -360,7 +416,8 @@ mod address_store_with_cache {
     impl Store {
         fn new(db: Arc<DB>) -> Self {
             // We manage the cache ourselves on this level, so we disable the inner builtin cache
-            let db_store = DbAddressesStore::new(db, CachePolicy::Empty);
+            let mut db_store = DbAddressesStore::new(db.clone(), CachePolicy::Empty);
+            Self::migrate_legacy_addresses(db, &mut db_store);
             let mut addresses = HashMap::new();
             for (key, entry) in db_store.iterator().map(|res| res.unwrap()) {
                 addresses.insert(key, entry);
@@ -369,6 +426,17 @@ mod address_store_with_cache {
             Self { db_store, addresses }
         }
 
+        /// Moves the entries of the legacy address store, which predate onion support, to `db_store`
+        fn migrate_legacy_addresses(db: Arc<DB>, db_store: &mut DbAddressesStore) {
+            let mut legacy_store = DbLegacyAddressesStore::new(db, CachePolicy::Empty);
+            for (key, entry) in legacy_store.iterator().filter_map(|res| res.ok()) {
+                if db_store.get(key).is_err() {
+                    db_store.set(key, entry).unwrap();
+                }
+            }
+            legacy_store.delete_all().unwrap();
+        }
+
         pub fn has(&mut self, address: NetAddress) -> bool {
             self.addresses.contains_key(&address.into())
         }
@@ -450,8 +518,8 @@ mod address_store_with_cache {
             RandomWeightedIterator::new(weights, filtered_addresses)
         }
 
-        pub fn remove_by_ip(&mut self, ip: IpAddr) {
-            for key in self.addresses.keys().filter(|key| key.is_ip(ip)).copied().collect_vec() {
+        pub fn remove_by_subnet(&mut self, subnet: IpSubnet) {
+            for key in self.addresses.keys().filter(|key| key.is_in_subnet(subnet)).copied().collect_vec() {
                 self.remove_by_key(key);
             }
         }
@@ -623,3 +691,67 @@ mod address_store_with_cache {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stores::{address_store::DbLegacyAddressesStore, banned_address_store::ConnectionBanTimestamp};
+    use waglayla_consensus_core::config::params::SIMNET_PARAMS;
+    use waglayla_database::create_temp_db;
+    use waglayla_database::prelude::ConnBuilder;
+    use std::{net::IpAddr, str::FromStr};
+
+    #[test]
+    fn test_legacy_bans_migration() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let now = unix_now();
+        let active: IpAddr = "1.2.3.4".parse().unwrap();
+        let expired: IpAddr = "5.6.7.8".parse().unwrap();
+
+        // Legacy entries hold the ban start time
+        let mut legacy_store = DbBannedAddressesStore::new(db.clone(), CachePolicy::Empty);
+        legacy_store.set(active, ConnectionBanTimestamp(now - 1000)).unwrap();
+        legacy_store.set(expired, ConnectionBanTimestamp(now - LEGACY_BAN_DURATION - 1000)).unwrap();
+
+        let (am, _) = AddressManager::new(Arc::new(Config::new(SIMNET_PARAMS)), db.clone(), Arc::new(TickService::default()));
+        let mut am_guard = am.lock();
+        assert!(am_guard.is_banned(active.into()));
+        assert!(!am_guard.is_banned(expired.into()));
+        let banned_subnets = am_guard.get_banned_subnets();
+        assert_eq!(banned_subnets.len(), 1);
+        assert_eq!(banned_subnets[0].0, IpSubnet::from(active));
+        assert_eq!(banned_subnets[0].1.expiry, now - 1000 + LEGACY_BAN_DURATION);
+
+        // The legacy store is emptied by the migration
+        assert_eq!(legacy_store.iterator().count(), 0);
+    }
+
+    #[test]
+    fn test_ban_duration_saturates() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let ip: IpAddr = "1.2.3.4".parse().unwrap();
+
+        // A duration exceeding the expiry range bans forever instead of wrapping around
+        let (am, _) = AddressManager::new(Arc::new(Config::new(SIMNET_PARAMS)), db, Arc::new(TickService::default()));
+        let mut am_guard = am.lock();
+        am_guard.ban(ip.into(), Duration::MAX, "test".to_string());
+        assert!(am_guard.is_banned(ip.into()));
+        assert_eq!(am_guard.get_banned_subnets()[0].1.expiry, u64::MAX);
+    }
+
+    #[test]
+    fn test_legacy_addresses_migration() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let ip = IpAddress::from_str("1.2.3.4").unwrap();
+
+        // Legacy entries hold addresses serialized without the onion field
+        let mut legacy_store = DbLegacyAddressesStore::new(db.clone(), CachePolicy::Empty);
+        legacy_store.set(ip, 16111, 2).unwrap();
+
+        let (am, _) = AddressManager::new(Arc::new(Config::new(SIMNET_PARAMS)), db.clone(), Arc::new(TickService::default()));
+        assert_eq!(am.lock().get_all_addresses(), vec![NetAddress::new(ip, 16111)]);
+
+        // The legacy store is emptied by the migration
+        assert_eq!(legacy_store.iterator().count(), 0);
+    }
+}
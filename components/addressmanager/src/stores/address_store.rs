@@ -4,7 +4,7 @@ use waglayla_database::{
     prelude::{CachedDbAccess, DirectDbWriter},
     registry::DatabaseStorePrefixes,
 };
-use waglayla_utils::mem_size::MemSizeEstimator;
+use waglayla_utils::{mem_size::MemSizeEstimator, networking::IpAddress};
 use serde::{Deserialize, Serialize};
 use std::net::Ipv6Addr;
 use std::{error::Error, fmt::Display, sync::Arc};
@@ -79,7 +79,7 @@ pub struct DbAddressesStore {
 
 impl DbAddressesStore {
     pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
-        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::Addresses.into()) }
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::NetAddresses.into()) }
     }
 
     pub fn iterator(&self) -> impl Iterator<Item = Result<(AddressKey, Entry), Box<dyn Error>>> + '_ {
@@ -117,3 +117,58 @@ impl AddressesStore for DbAddressesStore {
         self.set(key, Entry { connection_failed_count, address: entry.address })
     }
 }
+
+/// The layout of [`NetAddress`] prior to onion support
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct LegacyNetAddress {
+    ip: IpAddress,
+    port: u16,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct LegacyEntry {
+    connection_failed_count: u64,
+    address: LegacyNetAddress,
+}
+
+impl MemSizeEstimator for LegacyEntry {}
+
+impl From<LegacyEntry> for Entry {
+    fn from(entry: LegacyEntry) -> Self {
+        Self { connection_failed_count: entry.connection_failed_count, address: NetAddress::new(entry.address.ip, entry.address.port) }
+    }
+}
+
+/// Legacy address entries, superseded by [`DbAddressesStore`]. The store is only read in order to
+/// migrate its entries, since its addresses are serialized without the onion address field
+#[derive(Clone)]
+pub struct DbLegacyAddressesStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<DbAddressKey, LegacyEntry>,
+}
+
+impl DbLegacyAddressesStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::Addresses.into()) }
+    }
+
+    pub fn iterator(&self) -> impl Iterator<Item = Result<(AddressKey, Entry), Box<dyn Error>>> + '_ {
+        self.access.iterator().map(|iter_result| match iter_result {
+            Ok((key_bytes, entry)) => match <[u8; ADDRESS_KEY_SIZE]>::try_from(&key_bytes[..]) {
+                Ok(address_key_slice) => Ok((DbAddressKey(address_key_slice).into(), entry.into())),
+                Err(e) => Err(e.into()),
+            },
+            Err(e) => Err(e),
+        })
+    }
+
+    #[cfg(test)]
+    pub fn set(&mut self, address: IpAddress, port: u16, connection_failed_count: u64) -> StoreResult<()> {
+        let entry = LegacyEntry { connection_failed_count, address: LegacyNetAddress { ip: address, port } };
+        self.access.write(DirectDbWriter::new(&self.db), AddressKey::from(NetAddress::new(address, port)).into(), entry)
+    }
+
+    pub fn delete_all(&mut self) -> StoreResult<()> {
+        self.access.delete_all(DirectDbWriter::new(&self.db))
+    }
+}
@@ -1,27 +1,22 @@
 use waglayla_database::{
-    prelude::{CachePolicy, StoreError, StoreResult},
+    prelude::{CachePolicy, StoreResult},
     prelude::{CachedDbAccess, DirectDbWriter, DB},
     registry::DatabaseStorePrefixes,
 };
 use waglayla_utils::mem_size::MemSizeEstimator;
 use serde::{Deserialize, Serialize};
 use std::net::{IpAddr, Ipv6Addr};
-use std::{error::Error, fmt::Display, sync::Arc};
+use std::{error::Error, sync::Arc};
 
+/// The fixed duration of legacy bans, in milliseconds
+pub const LEGACY_BAN_DURATION: u64 = 24 * 60 * 60 * 1000;
+
+/// The unix time (in milliseconds) at which a legacy ban started
 #[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct ConnectionBanTimestamp(pub u64);
 
 impl MemSizeEstimator for ConnectionBanTimestamp {}
 
-pub trait BannedAddressesStoreReader {
-    fn get(&self, address: IpAddr) -> Result<ConnectionBanTimestamp, StoreError>;
-}
-
-pub trait BannedAddressesStore: BannedAddressesStoreReader {
-    fn set(&mut self, ip: IpAddr, timestamp: ConnectionBanTimestamp) -> StoreResult<()>;
-    fn remove(&mut self, ip: IpAddr) -> StoreResult<()>;
-}
-
 const IPV6_LEN: usize = 16;
 const ADDRESS_KEY_SIZE: usize = IPV6_LEN;
 
@@ -34,13 +29,6 @@ impl AsRef<[u8]> for AddressKey {
     }
 }
 
-impl Display for AddressKey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let ip: Ipv6Addr = (*self).into();
-        write!(f, "{ip}")
-    }
-}
-
 impl From<IpAddr> for AddressKey {
     fn from(ip: IpAddr) -> Self {
         Self(match ip {
@@ -50,12 +38,6 @@ impl From<IpAddr> for AddressKey {
     }
 }
 
-impl From<AddressKey> for Ipv6Addr {
-    fn from(k: AddressKey) -> Self {
-        k.0.into()
-    }
-}
-
 impl From<AddressKey> for IpAddr {
     fn from(k: AddressKey) -> Self {
         let ipv6: Ipv6Addr = k.0.into();
@@ -66,6 +48,8 @@ impl From<AddressKey> for IpAddr {
     }
 }
 
+/// Legacy per-IP bans, superseded by the banned subnets store. The store is only read in order to
+/// migrate its entries, since its values hold the ban start time rather than the ban expiry
 #[derive(Clone)]
 pub struct DbBannedAddressesStore {
     db: Arc<DB>,
@@ -80,30 +64,19 @@ impl DbBannedAddressesStore {
     pub fn iterator(&self) -> impl Iterator<Item = Result<(IpAddr, ConnectionBanTimestamp), Box<dyn Error>>> + '_ {
         self.access.iterator().map(|iter_result| match iter_result {
             Ok((key_bytes, connection_ban_timestamp)) => match <[u8; ADDRESS_KEY_SIZE]>::try_from(&key_bytes[..]) {
-                Ok(address_key_slice) => {
-                    let addr_key = AddressKey(address_key_slice);
-                    let address: IpAddr = addr_key.into();
-                    Ok((address, connection_ban_timestamp))
-                }
+                Ok(address_key_slice) => Ok((AddressKey(address_key_slice).into(), connection_ban_timestamp)),
                 Err(e) => Err(e.into()),
             },
             Err(e) => Err(e),
         })
     }
-}
-
-impl BannedAddressesStoreReader for DbBannedAddressesStore {
-    fn get(&self, ip: IpAddr) -> Result<ConnectionBanTimestamp, StoreError> {
-        self.access.read(ip.into())
-    }
-}
 
-impl BannedAddressesStore for DbBannedAddressesStore {
-    fn set(&mut self, ip: IpAddr, timestamp: ConnectionBanTimestamp) -> StoreResult<()> {
+    #[cfg(test)]
+    pub fn set(&mut self, ip: IpAddr, timestamp: ConnectionBanTimestamp) -> StoreResult<()> {
         self.access.write(DirectDbWriter::new(&self.db), ip.into(), timestamp)
     }
 
-    fn remove(&mut self, ip: IpAddr) -> StoreResult<()> {
-        self.access.delete(DirectDbWriter::new(&self.db), ip.into())
+    pub fn delete_all(&mut self) -> StoreResult<()> {
+        self.access.delete_all(DirectDbWriter::new(&self.db))
     }
 }
@@ -0,0 +1,118 @@
+use waglayla_database::{
+    prelude::{CachePolicy, StoreError, StoreResult},
+    prelude::{CachedDbAccess, DirectDbWriter, DB},
+    registry::DatabaseStorePrefixes,
+};
+use waglayla_utils::{mem_size::MemSizeEstimator, networking::IpSubnet};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv6Addr};
+use std::{error::Error, fmt::Display, sync::Arc};
+
+/// A ban of a subnet, expiring at the unix time `expiry` (in milliseconds)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BanEntry {
+    pub expiry: u64,
+    pub reason: String,
+}
+
+impl BanEntry {
+    pub fn new(expiry: u64, reason: String) -> Self {
+        Self { expiry, reason }
+    }
+}
+
+impl MemSizeEstimator for BanEntry {}
+
+pub trait BannedSubnetsStoreReader {
+    fn get(&self, subnet: IpSubnet) -> Result<BanEntry, StoreError>;
+}
+
+pub trait BannedSubnetsStore: BannedSubnetsStoreReader {
+    fn set(&mut self, subnet: IpSubnet, entry: BanEntry) -> StoreResult<()>;
+    fn remove(&mut self, subnet: IpSubnet) -> StoreResult<()>;
+}
+
+const IPV6_LEN: usize = 16;
+const SUBNET_KEY_SIZE: usize = IPV6_LEN + 1;
+const IPV4_MAPPED_PREFIX_LEN: u8 = 96;
+
+/// The subnet network as an IPv6 (or IPv4-mapped) address, followed by the IPv6 prefix length
+#[derive(Eq, Hash, PartialEq, Debug, Copy, Clone)]
+struct SubnetKey([u8; SUBNET_KEY_SIZE]);
+
+impl AsRef<[u8]> for SubnetKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Display for SubnetKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let network: Ipv6Addr = <[u8; IPV6_LEN]>::try_from(&self.0[..IPV6_LEN]).unwrap().into();
+        write!(f, "{}/{}", network, self.0[IPV6_LEN])
+    }
+}
+
+impl From<IpSubnet> for SubnetKey {
+    fn from(subnet: IpSubnet) -> Self {
+        let (network, prefix_len) = match subnet.network().0 {
+            IpAddr::V4(ip) => (ip.to_ipv6_mapped(), subnet.prefix_len() + IPV4_MAPPED_PREFIX_LEN),
+            IpAddr::V6(ip) => (ip, subnet.prefix_len()),
+        };
+        let mut bytes = [0u8; SUBNET_KEY_SIZE];
+        bytes[..IPV6_LEN].copy_from_slice(&network.octets());
+        bytes[IPV6_LEN] = prefix_len;
+        Self(bytes)
+    }
+}
+
+impl TryFrom<SubnetKey> for IpSubnet {
+    type Error = ipnet::PrefixLenError;
+
+    fn try_from(k: SubnetKey) -> Result<Self, Self::Error> {
+        let network: Ipv6Addr = <[u8; IPV6_LEN]>::try_from(&k.0[..IPV6_LEN]).unwrap().into();
+        // IPv4-mapped subnets are converted back to IPv4 by the canonicalization of `IpSubnet`
+        IpSubnet::new(IpAddr::V6(network), k.0[IPV6_LEN])
+    }
+}
+
+#[derive(Clone)]
+pub struct DbBannedSubnetsStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<SubnetKey, BanEntry>,
+}
+
+impl DbBannedSubnetsStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::BannedSubnets.into()) }
+    }
+
+    pub fn iterator(&self) -> impl Iterator<Item = Result<(IpSubnet, BanEntry), Box<dyn Error>>> + '_ {
+        self.access.iterator().map(|iter_result| match iter_result {
+            Ok((key_bytes, ban_entry)) => match <[u8; SUBNET_KEY_SIZE]>::try_from(&key_bytes[..]) {
+                Ok(subnet_key_slice) => match IpSubnet::try_from(SubnetKey(subnet_key_slice)) {
+                    Ok(subnet) => Ok((subnet, ban_entry)),
+                    Err(e) => Err(e.into()),
+                },
+                Err(e) => Err(e.into()),
+            },
+            Err(e) => Err(e),
+        })
+    }
+}
+
+impl BannedSubnetsStoreReader for DbBannedSubnetsStore {
+    fn get(&self, subnet: IpSubnet) -> Result<BanEntry, StoreError> {
+        self.access.read(subnet.into())
+    }
+}
+
+impl BannedSubnetsStore for DbBannedSubnetsStore {
+    fn set(&mut self, subnet: IpSubnet, entry: BanEntry) -> StoreResult<()> {
+        self.access.write(DirectDbWriter::new(&self.db), subnet.into(), entry)
+    }
+
+    fn remove(&mut self, subnet: IpSubnet) -> StoreResult<()> {
+        self.access.delete(DirectDbWriter::new(&self.db), subnet.into())
+    }
+}
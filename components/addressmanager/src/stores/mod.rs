@@ -1,9 +1,11 @@
 use std::net::{IpAddr, Ipv6Addr};
 
+use waglayla_utils::networking::IpSubnet;
 pub use waglayla_utils::networking::NetAddress;
 
 pub(super) mod address_store;
 pub(super) mod banned_address_store;
+pub(super) mod banned_subnet_store;
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub struct AddressKey(Ipv6Addr, u16);
@@ -13,11 +15,8 @@ impl AddressKey {
         Self(ip, port)
     }
 
-    pub fn is_ip(&self, ip: IpAddr) -> bool {
-        match ip {
-            IpAddr::V4(ip) => ip.to_ipv6_mapped() == self.0,
-            IpAddr::V6(ip) => ip == self.0,
-        }
+    pub fn is_in_subnet(&self, subnet: IpSubnet) -> bool {
+        subnet.contains(&IpAddr::V6(self.0))
     }
 }
 
@@ -11,8 +11,8 @@ use futures_util::future::join_all;
 use itertools::Itertools;
 use waglayla_addressmanager::{AddressManager, NetAddress};
 use waglayla_core::{debug, info, warn};
-use waglayla_p2p_lib::{common::ProtocolError, ConnectionError, Peer};
-use waglayla_utils::triggers::SingleTrigger;
+use waglayla_p2p_lib::{common::ProtocolError, ConnectionError, IdentityKey, Peer, PinnedPeer};
+use waglayla_utils::{networking::IpSubnet, triggers::SingleTrigger};
 use parking_lot::Mutex as ParkingLotMutex;
 use rand::{seq::SliceRandom, thread_rng};
 use tokio::{
@@ -28,10 +28,12 @@ pub struct ConnectionManager {
     p2p_adaptor: Arc<waglayla_p2p_lib::Adaptor>,
     outbound_target: usize,
     inbound_limit: usize,
+    whitelist: Vec<IpSubnet>,
+    pinned_peers: Vec<PinnedPeer>,
     dns_seeders: &'static [&'static str],
     default_port: u16,
     address_manager: Arc<ParkingLotMutex<AddressManager>>,
-    connection_requests: TokioMutex<HashMap<SocketAddr, ConnectionRequest>>,
+    connection_requests: TokioMutex<HashMap<NetAddress, ConnectionRequest>>,
     force_next_iteration: UnboundedSender<()>,
     shutdown_signal: SingleTrigger,
 }
@@ -54,6 +56,8 @@ impl ConnectionManager {
         p2p_adaptor: Arc<waglayla_p2p_lib::Adaptor>,
         outbound_target: usize,
         inbound_limit: usize,
+        whitelist: Vec<IpSubnet>,
+        pinned_peers: Vec<PinnedPeer>,
         dns_seeders: &'static [&'static str],
         default_port: u16,
         address_manager: Arc<ParkingLotMutex<AddressManager>>,
@@ -63,6 +67,8 @@ impl ConnectionManager {
             p2p_adaptor,
             outbound_target,
             inbound_limit,
+            whitelist,
+            pinned_peers,
             address_manager,
             connection_requests: Default::default(),
             force_next_iteration: tx,
@@ -103,7 +109,7 @@ impl ConnectionManager {
         self.handle_inbound_connections(&peer_by_address).await;
     }
 
-    pub async fn add_connection_request(&self, address: SocketAddr, is_permanent: bool) {
+    pub async fn add_connection_request(&self, address: NetAddress, is_permanent: bool) {
         // If the request already exists, it resets the attempts count and overrides the `is_permanent` setting.
         self.connection_requests.lock().await.insert(address, ConnectionRequest::new(is_permanent));
         self.force_next_iteration.send(()).unwrap(); // We force the next iteration of the connection loop.
@@ -119,7 +125,7 @@ impl ConnectionManager {
         for (address, request) in requests.iter() {
             let address = *address;
             let request = request.clone();
-            let is_connected = peer_by_address.contains_key(&address);
+            let is_connected = peer_by_address.contains_key(&SocketAddr::from(address));
             if is_connected && !request.is_permanent {
                 // The peer is connected and the request is not permanent - no need to keep the request
                 continue;
@@ -178,14 +184,14 @@ impl ConnectionManager {
             let mut addrs_to_connect = Vec::with_capacity(missing_connections);
             let mut jobs = Vec::with_capacity(missing_connections);
             for _ in 0..missing_connections {
-                let Some(net_addr) = addr_iter.next() else {
+                // Onion addresses are skipped if no onion proxy is configured
+                let Some(net_addr) = addr_iter.find(|net_addr| self.p2p_adaptor.is_reachable(net_addr)) else {
                     connecting = false;
                     break;
                 };
-                let socket_addr = SocketAddr::new(net_addr.ip.into(), net_addr.port).to_string();
-                debug!("Connecting to {}", &socket_addr);
+                debug!("Connecting to {}", net_addr);
                 addrs_to_connect.push(net_addr);
-                jobs.push(self.p2p_adaptor.connect_peer(socket_addr.clone()));
+                jobs.push(self.p2p_adaptor.connect_peer(net_addr.to_string()));
             }
 
             if progressing && !jobs.is_empty() {
@@ -237,7 +243,15 @@ impl ConnectionManager {
     }
 
     async fn handle_inbound_connections(self: &Arc<Self>, peer_by_address: &HashMap<SocketAddr, Peer>) {
-        let active_inbound = peer_by_address.values().filter(|peer| !peer.is_outbound()).collect_vec();
+        // Whitelisted and pinned peers do not count towards the inbound limit
+        let active_inbound = peer_by_address
+            .values()
+            .filter(|peer| {
+                !peer.is_outbound()
+                    && !self.is_whitelisted(peer.net_address().ip())
+                    && !peer.identity_key().is_some_and(|key| self.is_pinned(peer.net_address().ip(), &key))
+            })
+            .collect_vec();
         let active_inbound_len = active_inbound.len();
         if self.inbound_limit >= active_inbound_len {
             return;
@@ -279,33 +293,63 @@ impl ConnectionManager {
         }
     }
 
-    /// Bans the given IP and disconnects from all the peers with that IP.
+    /// Bans the given subnet for `duration` and disconnects from all the peers within it.
+    /// Whitelisted IPs and IPs with a permanent request are exempt from the ban, and connected pinned peers are not disconnected.
     ///
     /// _GO-WAGLAYLAD: BanByIP_
-    pub async fn ban(&self, ip: IpAddr) {
-        if self.ip_has_permanent_connection(ip).await {
+    pub async fn ban(&self, subnet: IpSubnet, duration: Duration, reason: String) {
+        if self.is_whitelisted_subnet(subnet) || self.subnet_has_permanent_connection(subnet).await {
             return;
         }
         for peer in self.p2p_adaptor.active_peers() {
-            if peer.net_address().ip() == ip {
+            let ip = peer.net_address().ip();
+            let is_pinned = peer.identity_key().is_some_and(|key| self.is_pinned(ip, &key));
+            if subnet.contains(&ip) && !self.is_whitelisted(ip) && !is_pinned && !self.ip_has_permanent_connection(ip).await {
                 self.p2p_adaptor.terminate(peer.key()).await;
             }
         }
-        self.address_manager.lock().ban(ip.into());
+        self.address_manager.lock().ban(subnet, duration, reason);
     }
 
     /// Returns whether the given address is banned.
     pub async fn is_banned(&self, address: &SocketAddr) -> bool {
-        !self.is_permanent(address).await && self.address_manager.lock().is_banned(address.ip().into())
+        !self.is_whitelisted(address.ip())
+            && !self.is_permanent(address).await
+            && self.address_manager.lock().is_banned(address.ip().into())
+    }
+
+    /// Returns whether the given IP belongs to a whitelisted subnet.
+    pub fn is_whitelisted(&self, ip: IpAddr) -> bool {
+        self.whitelist.iter().any(|subnet| subnet.contains(&ip))
+    }
+
+    /// Returns whether `subnet` overlaps any whitelisted subnet.
+    pub fn is_whitelisted_subnet(&self, subnet: IpSubnet) -> bool {
+        self.whitelist.iter().any(|whitelisted| whitelisted.overlaps(&subnet))
+    }
+
+    /// Returns whether the given peer identity key is pinned to `ip`. Peers authenticating with a pinned key are trusted like whitelisted ones.
+    pub fn is_pinned(&self, ip: IpAddr, identity_key: &IdentityKey) -> bool {
+        self.pinned_peers.iter().any(|pinned_peer| pinned_peer.key == *identity_key && pinned_peer.matches(ip))
+    }
+
+    /// Returns the identity key pinned to `ip`, if any. Peers connecting from `ip` must authenticate with it.
+    pub fn pinned_identity_key(&self, ip: IpAddr) -> Option<IdentityKey> {
+        self.pinned_peers.iter().find(|pinned_peer| pinned_peer.matches(ip)).map(|pinned_peer| pinned_peer.key)
     }
 
     /// Returns whether the given address is a permanent request.
     pub async fn is_permanent(&self, address: &SocketAddr) -> bool {
-        self.connection_requests.lock().await.contains_key(address)
+        self.connection_requests.lock().await.keys().any(|request_address| SocketAddr::from(*request_address) == *address)
     }
 
     /// Returns whether the given IP has some permanent request.
     pub async fn ip_has_permanent_connection(&self, ip: IpAddr) -> bool {
-        self.connection_requests.lock().await.iter().any(|(address, request)| request.is_permanent && address.ip() == ip)
+        self.connection_requests.lock().await.iter().any(|(address, request)| request.is_permanent && *address.ip == ip)
+    }
+
+    /// Returns whether some IP of `subnet` has a permanent request.
+    pub async fn subnet_has_permanent_connection(&self, subnet: IpSubnet) -> bool {
+        self.connection_requests.lock().await.iter().any(|(address, request)| request.is_permanent && subnet.contains(&address.ip))
     }
 }
@@ -19,6 +19,7 @@ use crate::{
     pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList},
     trusted::{ExternalGhostdagData, TrustedBlock},
     tx::{MutableTransaction, Transaction, TransactionOutpoint, UtxoEntry},
+    utxo::utxo_diff::UtxoDiff,
     BlockHashSet, BlueWorkType, ChainPath,
 };
 use waglayla_hashes::Hash;
@@ -287,6 +288,13 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the UTXO diff of a chain block relative to its selected parent, i.e. the outputs
+    /// created and spent by the transactions it accepted (intermediate outputs spent within
+    /// the same mergeset appear in neither side of the diff).
+    fn get_block_utxo_diff(&self, hash: Hash) -> ConsensusResult<Arc<UtxoDiff>> {
+        unimplemented!()
+    }
+
     fn is_chain_block(&self, hash: Hash) -> ConsensusResult<bool> {
         unimplemented!()
     }
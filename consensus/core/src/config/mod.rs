@@ -17,6 +17,15 @@ use {
     params::Params,
 };
 
+/// Default misbehaviour score at which a peer is banned
+pub const DEFAULT_BAN_THRESHOLD: u32 = 100;
+
+/// Default decay of peer misbehaviour scores in points per minute
+pub const DEFAULT_BAN_SCORE_DECAY: u32 = 1;
+
+/// Default ban duration in seconds (24 hours)
+pub const DEFAULT_BAN_DURATION: u64 = 24 * 60 * 60;
+
 /// Various consensus configurations all bundled up under a single struct. Use `Config::new` for directly building from
 /// a `Params` instance. For anything more complex it is recommended to use `ConfigBuilder`. NOTE: this struct can be
 /// implicitly de-refed into `Params`
@@ -42,6 +51,15 @@ pub struct Config {
     /// Enable the UTXO index
     pub utxoindex: bool,
 
+    /// Enable the transaction index
+    pub txindex: bool,
+
+    /// Enable the address transaction history index
+    pub addressindex: bool,
+
+    /// Enable the chain block index by DAA score and timestamp
+    pub chainindex: bool,
+
     /// Enable RPC commands which affect the state of the node
     pub unsafe_rpc: bool,
 
@@ -66,6 +84,23 @@ pub struct Config {
 
     pub disable_upnp: bool,
 
+    /// Only advertise `externalip` to peers, never addresses discovered from network interfaces or via UPnP.
+    /// Set when connecting through a proxy, so that peers do not learn the node IP
+    pub disable_address_discovery: bool,
+
+    /// Misbehaviour score at which a peer is banned. Zero disables automatic banning
+    pub ban_threshold: u32,
+
+    /// Rate, in points per minute, at which peer misbehaviour scores decay
+    pub ban_score_decay: u32,
+
+    /// Duration of automatic bans and of RPC bans which do not specify a duration, in seconds
+    pub ban_duration: u64,
+
+    /// Relay transactions using Dandelion: locally submitted transactions are forwarded along a random stem
+    /// of peers before being diffused, so that their origin cannot be told from who announced them first
+    pub dandelion: bool,
+
     /// A scale factor to apply to memory allocation bounds
     pub ram_scale: f64,
 }
@@ -83,6 +118,9 @@ impl Config {
             is_archival: false,
             enable_sanity_checks: false,
             utxoindex: false,
+            txindex: false,
+            addressindex: false,
+            chainindex: false,
             unsafe_rpc: false,
             enable_unsynced_mining: false,
             enable_mainnet_mining: false,
@@ -94,6 +132,11 @@ impl Config {
             // #[cfg(feature = "devnet-prealloc")]
             initial_utxo_set: Default::default(),
             disable_upnp: false,
+            disable_address_discovery: false,
+            ban_threshold: DEFAULT_BAN_THRESHOLD,
+            ban_score_decay: DEFAULT_BAN_SCORE_DECAY,
+            ban_duration: DEFAULT_BAN_DURATION,
+            dandelion: false,
             ram_scale: 1.0,
         }
     }
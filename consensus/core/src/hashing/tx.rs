@@ -1,6 +1,6 @@
 use super::HasherExtensions;
 use crate::tx::{Transaction, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput};
-use waglayla_hashes::{Hash, Hasher};
+use waglayla_hashes::{siphash24, Hash, Hasher};
 
 /// A bitmask defining which transaction fields we
 /// want to encode and which to ignore.
@@ -27,6 +27,19 @@ pub(crate) fn id(tx: &Transaction) -> TransactionId {
     hasher.finalize()
 }
 
+/// The number of bytes of a short transaction id
+pub const SHORT_ID_SIZE: usize = 6;
+
+/// Returns the short id of a transaction within the block with hash `block_hash`, as used by compact block relay.
+/// Short ids are keyed by the block hash so that transactions with colliding short ids cannot be crafted in advance.
+///
+/// A receiving node computes the short id of every mempool transaction for each compact block, so, like in BIP152,
+/// the short id is a truncated SipHash-2-4 rather than a cryptographic hash.
+pub fn short_id(transaction_id: &TransactionId, block_hash: &Hash) -> u64 {
+    let [w0, w1, w2, w3] = block_hash.to_le_u64();
+    siphash24(w0 ^ w2, w1 ^ w3, &transaction_id.as_bytes()) & ((1 << (8 * SHORT_ID_SIZE)) - 1)
+}
+
 /// Write the transaction into the provided hasher according to the encoding flags
 fn write_transaction<T: Hasher>(hasher: &mut T, tx: &Transaction, encoding_flags: TxEncodingFlags, include_mass_field: bool) {
     hasher.update(tx.version.to_le_bytes()).write_len(tx.inputs.len());
@@ -171,4 +184,15 @@ mod tests {
         drop(inputs);
         drop(outputs);
     }
+
+    #[test]
+    fn test_short_id() {
+        let (id, other_id) = (TransactionId::from_u64_word(1), TransactionId::from_u64_word(2));
+        let (block_hash, other_block_hash) = (Hash::from_u64_word(3), Hash::from_u64_word(4));
+        let expected = short_id(&id, &block_hash);
+        assert!(expected < 1 << (8 * SHORT_ID_SIZE));
+        assert_eq!(expected, short_id(&id, &block_hash));
+        assert_ne!(expected, short_id(&other_id, &block_hash));
+        assert_ne!(expected, short_id(&id, &other_block_hash));
+    }
 }
@@ -56,6 +56,24 @@ impl NetworkType {
         }
     }
 
+    pub fn default_http_rpc_port(&self) -> u16 {
+        match self {
+            NetworkType::Mainnet => 17110,
+            NetworkType::Testnet => 17210,
+            NetworkType::Simnet => 17510,
+            NetworkType::Devnet => 17610,
+        }
+    }
+
+    pub fn default_prometheus_port(&self) -> u16 {
+        match self {
+            NetworkType::Mainnet => 15110,
+            NetworkType::Testnet => 15210,
+            NetworkType::Simnet => 15510,
+            NetworkType::Devnet => 15610,
+        }
+    }
+
     pub fn iter() -> impl Iterator<Item = Self> {
         static NETWORK_TYPES: [NetworkType; 4] =
             [NetworkType::Mainnet, NetworkType::Testnet, NetworkType::Devnet, NetworkType::Simnet];
@@ -446,6 +446,17 @@ impl<T: AsRef<Transaction>> MutableTransaction<T> {
             *entry = None;
         }
     }
+
+    /// Returns the calculated feerate, i.e. the fee paid per gram of the full contextual (compute & storage) mass.
+    /// Returns `None` if the fee was not populated yet or if the contextual mass is not set.
+    pub fn calculated_feerate(&self) -> Option<f64> {
+        let contextual_mass = self.tx.as_ref().mass();
+        if contextual_mass > 0 {
+            self.calculated_fee.map(|fee| fee as f64 / contextual_mass as f64)
+        } else {
+            None
+        }
+    }
 }
 
 impl<T: AsRef<Transaction>> AsRef<Transaction> for MutableTransaction<T> {
@@ -1,5 +1,5 @@
 use derive_more::Display;
-use waglayla_consensus_core::{acceptance_data::AcceptanceData, block::Block, utxo::utxo_diff::UtxoDiff};
+use waglayla_consensus_core::{acceptance_data::AcceptanceData, block::Block, tx::TransactionId, utxo::utxo_diff::UtxoDiff};
 use waglayla_hashes::Hash;
 use waglayla_notify::{
     events::EventType,
@@ -42,6 +42,9 @@ pub enum Notification {
 
     #[display(fmt = "NewBlockTemplate notification")]
     NewBlockTemplate(NewBlockTemplateNotification),
+
+    #[display(fmt = "TransactionsEvicted notification: {} evicted transactions replaced by {}", "_0.evicted_transaction_ids.len()", "_0.replacement_transaction_id")]
+    TransactionsEvicted(TransactionsEvictedNotification),
 }
 }
 
@@ -181,3 +184,15 @@ pub struct PruningPointUtxoSetOverrideNotification {}
 
 #[derive(Debug, Clone)]
 pub struct NewBlockTemplateNotification {}
+
+#[derive(Debug, Clone)]
+pub struct TransactionsEvictedNotification {
+    pub replacement_transaction_id: TransactionId,
+    pub evicted_transaction_ids: Arc<Vec<TransactionId>>,
+}
+
+impl TransactionsEvictedNotification {
+    pub fn new(replacement_transaction_id: TransactionId, evicted_transaction_ids: Arc<Vec<TransactionId>>) -> Self {
+        Self { replacement_transaction_id, evicted_transaction_ids }
+    }
+}
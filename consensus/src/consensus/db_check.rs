@@ -0,0 +1,480 @@
+//! Offline consistency checks over the consensus database stores.
+//!
+//! The checks only read from the stores and are meant to run against a database which is not being
+//! written to concurrently, e.g., via `waglaylad --check-db` while the node is stopped.
+
+use crate::{
+    config::Config,
+    consensus::storage::ConsensusStorage,
+    model::stores::{
+        ghostdag::GhostdagStoreReader, headers::HeaderStoreReader, pruning::PruningStoreReader, reachability::ReachabilityStoreReader,
+        relations::RelationsStoreReader, selected_chain::SelectedChainStoreReader, utxo_multisets::UtxoMultisetsStoreReader,
+        virtual_state::VirtualStateStoreReader,
+    },
+    processes::reachability::inquirer::is_dag_ancestor_of,
+};
+use serde::Serialize;
+use std::{collections::VecDeque, fmt::Display, sync::Arc};
+use waglayla_consensus_core::{blockhash::ORIGIN, muhash::MuHashExtensions};
+use waglayla_database::prelude::StoreResult;
+use waglayla_hashes::Hash;
+use waglayla_muhash::MuHash;
+
+/// The default number of error descriptions kept per check. Errors beyond this limit are only counted.
+pub const DEFAULT_MAX_REPORTED_ERRORS: usize = 100;
+
+/// The result of a single consistency check
+#[derive(Debug, Clone, Serialize)]
+pub struct DbCheck {
+    pub name: String,
+    /// The number of entries examined by this check
+    pub checked: u64,
+    /// The total number of inconsistencies found
+    pub error_count: u64,
+    /// Descriptions of the first inconsistencies found
+    pub errors: Vec<String>,
+    #[serde(skip)]
+    max_reported_errors: usize,
+}
+
+impl DbCheck {
+    pub fn new(name: &str, max_reported_errors: usize) -> Self {
+        Self { name: name.to_owned(), checked: 0, error_count: 0, errors: Vec::new(), max_reported_errors }
+    }
+
+    pub fn report_error(&mut self, error: impl Display) {
+        self.error_count += 1;
+        if self.errors.len() < self.max_reported_errors {
+            self.errors.push(error.to_string());
+        }
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.error_count == 0
+    }
+
+    /// Reports an error if `result` indicates that `hash` is missing from the store described by `what`
+    fn expect_present(&mut self, hash: Hash, what: &str, result: StoreResult<bool>) {
+        match result {
+            Ok(true) => {}
+            Ok(false) => self.report_error(format_args!("block {hash}: missing {what}")),
+            Err(err) => self.report_error(format_args!("block {hash}: failed reading {what}: {err}")),
+        }
+    }
+}
+
+/// A machine-readable report of all consistency checks performed over a database
+#[derive(Debug, Clone, Serialize)]
+pub struct DbCheckReport {
+    pub consistent: bool,
+    pub checks: Vec<DbCheck>,
+    /// Descriptions of repairs applied following the checks
+    pub repairs: Vec<String>,
+}
+
+impl DbCheckReport {
+    pub fn new() -> Self {
+        Self { consistent: true, checks: Vec::new(), repairs: Vec::new() }
+    }
+
+    pub fn push(&mut self, check: DbCheck) {
+        self.consistent &= check.is_consistent();
+        self.checks.push(check);
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.consistent
+    }
+}
+
+impl Default for DbCheckReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Verifies invariants which should hold between the various consensus stores
+pub struct ConsensusDbChecker {
+    storage: Arc<ConsensusStorage>,
+    genesis_hash: Hash,
+    max_reported_errors: usize,
+}
+
+impl ConsensusDbChecker {
+    pub fn new(storage: Arc<ConsensusStorage>, config: &Config) -> Self {
+        Self { storage, genesis_hash: config.genesis.hash, max_reported_errors: DEFAULT_MAX_REPORTED_ERRORS }
+    }
+
+    pub fn with_max_reported_errors(self, max_reported_errors: usize) -> Self {
+        Self { max_reported_errors, ..self }
+    }
+
+    /// Runs all checks and returns the full report
+    pub fn run(&self) -> DbCheckReport {
+        let mut report = DbCheckReport::new();
+        report.push(self.check_reachability_intervals());
+        report.push(self.check_statuses());
+        report.push(self.check_ghostdag_relations());
+        report.push(self.check_virtual_utxo_set());
+        report.push(self.check_utxo_multisets());
+        report.push(self.check_pruning_utxo_set());
+        report
+    }
+
+    fn new_check(&self, name: &str) -> DbCheck {
+        DbCheck::new(name, self.max_reported_errors)
+    }
+
+    /// Traverses the reachability tree from `origin` and verifies that intervals are properly nested and
+    /// allocated, that tree links are bidirectional, and that future covering sets are ordered
+    pub fn check_reachability_intervals(&self) -> DbCheck {
+        let mut check = self.new_check("reachability_intervals");
+        let store = self.storage.reachability_store.read();
+        let mut queue = VecDeque::from([ORIGIN]);
+        while let Some(parent) = queue.pop_front() {
+            check.checked += 1;
+            let (parent_interval, children) = match (store.get_interval(parent), store.get_children(parent)) {
+                (Ok(interval), Ok(children)) => (interval, children),
+                (Err(err), _) | (_, Err(err)) => {
+                    check.report_error(format_args!("block {parent}: failed reading reachability data: {err}"));
+                    continue;
+                }
+            };
+            if parent_interval.is_empty() {
+                check.report_error(format_args!("block {parent}: empty interval {parent_interval}"));
+            }
+
+            let mut child_intervals = Vec::with_capacity(children.len());
+            for child in children.iter().copied() {
+                queue.push_back(child);
+                match store.get_parent(child) {
+                    Ok(tree_parent) if tree_parent == parent => {}
+                    Ok(tree_parent) => check.report_error(format_args!(
+                        "block {child}: listed as a tree child of {parent} but its tree parent is {tree_parent}"
+                    )),
+                    Err(err) => check.report_error(format_args!("block {child}: failed reading tree parent: {err}")),
+                }
+                match store.get_interval(child) {
+                    Ok(child_interval) => {
+                        if !parent_interval.strictly_contains(child_interval) {
+                            check.report_error(format_args!(
+                                "block {child}: interval {child_interval} is out of the bounds of parent {parent} interval {parent_interval}"
+                            ));
+                        }
+                        child_intervals.push(child_interval);
+                    }
+                    Err(err) => check.report_error(format_args!("block {child}: failed reading interval: {err}")),
+                }
+            }
+
+            // Consecutive siblings are expected to have adjacent intervals
+            if child_intervals.len() == children.len() {
+                for (siblings, intervals) in children.windows(2).zip(child_intervals.windows(2)) {
+                    let (_, left_end): (u64, u64) = intervals[0].into();
+                    let (right_start, _): (u64, u64) = intervals[1].into();
+                    if left_end + 1 != right_start {
+                        check.report_error(format_args!(
+                            "blocks {} and {}: non-consecutive sibling intervals {} and {}",
+                            siblings[0], siblings[1], intervals[0], intervals[1]
+                        ));
+                    }
+                }
+            }
+
+            match store.get_future_covering_set(parent) {
+                Ok(future_covering_set) => {
+                    for neighbors in future_covering_set.windows(2) {
+                        match (store.get_interval(neighbors[0]), store.get_interval(neighbors[1])) {
+                            (Ok(left), Ok(right)) => {
+                                let (_, left_end): (u64, u64) = left.into();
+                                let (right_start, _): (u64, u64) = right.into();
+                                if left.is_empty() || right.is_empty() || left_end >= right_start {
+                                    check.report_error(format_args!(
+                                        "block {parent}: future covering set items {} {left} and {} {right} are not ordered",
+                                        neighbors[0], neighbors[1]
+                                    ));
+                                }
+                            }
+                            (Err(err), _) | (_, Err(err)) => {
+                                check.report_error(format_args!("block {parent}: failed reading future covering set intervals: {err}"))
+                            }
+                        }
+                    }
+                }
+                Err(err) => check.report_error(format_args!("block {parent}: failed reading future covering set: {err}")),
+            }
+        }
+        check
+    }
+
+    /// Verifies that every block with a non-invalid status has the data its status implies
+    pub fn check_statuses(&self) -> DbCheck {
+        let mut check = self.new_check("block_statuses");
+        let statuses = self.storage.statuses_store.read();
+        let reachability = self.storage.reachability_store.read();
+        for entry in statuses.iterator() {
+            let (hash, status) = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    check.report_error(format_args!("failed reading status entry: {err}"));
+                    continue;
+                }
+            };
+            check.checked += 1;
+            if status.is_invalid() {
+                continue;
+            }
+            if status.has_block_header() {
+                check.expect_present(hash, "header", self.storage.headers_store.has(hash));
+            }
+            if status.has_block_body() {
+                check.expect_present(hash, "block body", self.storage.block_transactions_store.has(hash));
+            }
+            check.expect_present(hash, "ghostdag data", self.storage.ghostdag_primary_store.has(hash));
+            check.expect_present(hash, "reachability data", reachability.has(hash));
+        }
+        check
+    }
+
+    /// Verifies that ghostdag data agrees with the block relations. Only blocks in the future of the pruning
+    /// point are checked, since pruning trims the ghostdag data and relations of blocks kept below it
+    pub fn check_ghostdag_relations(&self) -> DbCheck {
+        let mut check = self.new_check("ghostdag_relations");
+        let pruning_point = match self.storage.pruning_point_store.read().pruning_point() {
+            Ok(pruning_point) => pruning_point,
+            Err(err) => {
+                check.report_error(format_args!("failed reading the pruning point: {err}"));
+                return check;
+            }
+        };
+        let statuses = self.storage.statuses_store.read();
+        let reachability = self.storage.reachability_store.read();
+        let relations = self.storage.relations_stores.read();
+        let ghostdag = &self.storage.ghostdag_primary_store;
+        for entry in statuses.iterator() {
+            let Ok((hash, status)) = entry else {
+                // Unreadable entries are reported by the statuses check
+                continue;
+            };
+            if status.is_invalid() || hash == pruning_point {
+                continue;
+            }
+            match is_dag_ancestor_of(&*reachability, pruning_point, hash) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(err) => {
+                    check.report_error(format_args!("block {hash}: failed querying reachability: {err}"));
+                    continue;
+                }
+            }
+            check.checked += 1;
+
+            let data = match ghostdag.get_data(hash) {
+                Ok(data) => data,
+                Err(err) => {
+                    check.report_error(format_args!("block {hash}: failed reading ghostdag data: {err}"));
+                    continue;
+                }
+            };
+            let parents = match relations[0].get_parents(hash) {
+                Ok(parents) => parents,
+                Err(err) => {
+                    check.report_error(format_args!("block {hash}: failed reading relations: {err}"));
+                    continue;
+                }
+            };
+
+            if !parents.contains(&data.selected_parent) {
+                check.report_error(format_args!("block {hash}: selected parent {} is not a parent", data.selected_parent));
+            }
+            if data.mergeset_blues.first() != Some(&data.selected_parent) {
+                check.report_error(format_args!(
+                    "block {hash}: selected parent {} is not the first mergeset blue",
+                    data.selected_parent
+                ));
+            }
+            for parent in parents.iter().copied() {
+                if !data.mergeset_blues.contains(&parent) && !data.mergeset_reds.contains(&parent) {
+                    check.report_error(format_args!("block {hash}: parent {parent} is missing from the mergeset"));
+                }
+            }
+            match ghostdag.get_blue_score(data.selected_parent) {
+                Ok(selected_parent_blue_score) => {
+                    let expected = selected_parent_blue_score + data.mergeset_blues.len() as u64;
+                    if data.blue_score != expected {
+                        check.report_error(format_args!("block {hash}: blue score {} but expected {expected}", data.blue_score));
+                    }
+                }
+                Err(err) => check.report_error(format_args!(
+                    "block {hash}: failed reading ghostdag data of selected parent {}: {err}",
+                    data.selected_parent
+                )),
+            }
+        }
+        check
+    }
+
+    /// Verifies that the MuHash of the virtual UTXO set matches the multiset stored with the virtual state
+    pub fn check_virtual_utxo_set(&self) -> DbCheck {
+        let mut check = self.new_check("virtual_utxo_set");
+        let virtual_stores = self.storage.virtual_stores.read();
+        let virtual_state = match virtual_stores.state.get() {
+            Ok(virtual_state) => virtual_state,
+            Err(err) => {
+                check.report_error(format_args!("failed reading the virtual state: {err}"));
+                return check;
+            }
+        };
+        let mut multiset = MuHash::new();
+        for entry in virtual_stores.utxo_set.iterator() {
+            match entry {
+                Ok((outpoint, entry)) => {
+                    check.checked += 1;
+                    multiset.add_utxo(&outpoint, &entry);
+                }
+                Err(err) => check.report_error(format_args!("failed reading virtual UTXO entry: {err}")),
+            }
+        }
+        let (actual, expected) = (multiset.finalize(), virtual_state.multiset.clone().finalize());
+        if actual != expected {
+            check.report_error(format_args!(
+                "virtual UTXO set commitment {actual} does not match the virtual state multiset {expected}"
+            ));
+        }
+        check
+    }
+
+    /// Verifies that the stored UTXO multisets of the selected chain blocks, from the pruning point to the sink,
+    /// match the UTXO commitments of their headers
+    pub fn check_utxo_multisets(&self) -> DbCheck {
+        let mut check = self.new_check("utxo_multisets");
+        let pruning_point = match self.storage.pruning_point_store.read().pruning_point() {
+            Ok(pruning_point) => pruning_point,
+            Err(err) => {
+                check.report_error(format_args!("failed reading the pruning point: {err}"));
+                return check;
+            }
+        };
+        let selected_chain = self.storage.selected_chain_store.read();
+        let (start, end) = match (selected_chain.get_by_hash(pruning_point), selected_chain.get_tip()) {
+            (Ok(start), Ok((end, _))) => (start, end),
+            (Err(err), _) | (_, Err(err)) => {
+                check.report_error(format_args!("failed reading the selected chain bounds: {err}"));
+                return check;
+            }
+        };
+        for index in start..=end {
+            let hash = match selected_chain.get_by_index(index) {
+                Ok(hash) => hash,
+                Err(err) => {
+                    check.report_error(format_args!("selected chain index {index}: {err}"));
+                    continue;
+                }
+            };
+            // The genesis commitment is hard-coded and might reflect a state which was never stored locally
+            if hash == self.genesis_hash {
+                continue;
+            }
+            check.checked += 1;
+            match (self.storage.utxo_multisets_store.get(hash), self.storage.headers_store.get_header(hash)) {
+                (Ok(mut multiset), Ok(header)) => {
+                    let actual = multiset.finalize();
+                    if actual != header.utxo_commitment {
+                        check.report_error(format_args!(
+                            "block {hash}: stored multiset {actual} does not match the header UTXO commitment {}",
+                            header.utxo_commitment
+                        ));
+                    }
+                }
+                (Err(err), _) | (_, Err(err)) => check.report_error(format_args!("block {hash}: {err}")),
+            }
+        }
+        check
+    }
+
+    /// Verifies that the pruning UTXO set is positioned at the pruning point and matches its UTXO commitment
+    pub fn check_pruning_utxo_set(&self) -> DbCheck {
+        let mut check = self.new_check("pruning_utxo_set");
+        let pruning_point = match self.storage.pruning_point_store.read().pruning_point() {
+            Ok(pruning_point) => pruning_point,
+            Err(err) => {
+                check.report_error(format_args!("failed reading the pruning point: {err}"));
+                return check;
+            }
+        };
+        let pruning_utxoset = self.storage.pruning_utxoset_stores.read();
+        let position = match pruning_utxoset.utxoset_position() {
+            Ok(position) => position,
+            Err(err) => {
+                check.report_error(format_args!("failed reading the pruning UTXO set position: {err}"));
+                return check;
+            }
+        };
+        if position != pruning_point {
+            check.report_error(format_args!("pruning UTXO set is positioned at {position} but the pruning point is {pruning_point}"));
+        }
+        let mut multiset = MuHash::new();
+        for entry in pruning_utxoset.utxo_set.iterator() {
+            match entry {
+                Ok((outpoint, entry)) => {
+                    check.checked += 1;
+                    multiset.add_utxo(&outpoint, &entry);
+                }
+                Err(err) => check.report_error(format_args!("failed reading pruning UTXO entry: {err}")),
+            }
+        }
+        if position == self.genesis_hash {
+            return check;
+        }
+        match self.storage.headers_store.get_header(position) {
+            Ok(header) => {
+                let actual = multiset.finalize();
+                if actual != header.utxo_commitment {
+                    check.report_error(format_args!(
+                        "pruning UTXO set commitment {actual} does not match the UTXO commitment {} of block {position}",
+                        header.utxo_commitment
+                    ));
+                }
+            }
+            Err(err) => check.report_error(format_args!("block {position}: failed reading header: {err}")),
+        }
+        check
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{consensus::test_consensus::TestConsensus, model::stores::utxo_multisets::UtxoMultisetsStore};
+    use waglayla_consensus_core::config::{params::MAINNET_PARAMS, ConfigBuilder};
+
+    #[tokio::test]
+    async fn test_db_check() {
+        let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+        let consensus = TestConsensus::new(&config);
+        let wait_handles = consensus.init();
+
+        let mut tip = config.genesis.hash;
+        for i in 2..12u64 {
+            consensus.add_utxo_valid_block_with_parents(i.into(), vec![tip], vec![]).await.unwrap();
+            tip = i.into();
+        }
+
+        let storage = consensus.consensus_clone().storage.clone();
+        let checker = ConsensusDbChecker::new(storage.clone(), &config);
+        let report = checker.run();
+        assert!(report.is_consistent(), "{}", serde_json::to_string_pretty(&report).unwrap());
+        assert!(report.checks.iter().all(|check| check.checked > 0 || check.name == "pruning_utxo_set"));
+
+        // Corrupt the stored multiset of a chain block
+        storage.utxo_multisets_store.delete(5.into()).unwrap();
+        storage.utxo_multisets_store.insert(5.into(), MuHash::new()).unwrap();
+        let report = checker.run();
+        assert!(!report.is_consistent());
+        let check = report.checks.iter().find(|check| check.name == "utxo_multisets").unwrap();
+        assert_eq!(check.error_count, 1);
+        assert!(report.checks.iter().filter(|check| check.name != "utxo_multisets").all(DbCheck::is_consistent));
+
+        consensus.shutdown(wait_handles);
+    }
+}
@@ -17,7 +17,7 @@ use waglayla_database::{
 use waglayla_txscript::caches::TxScriptCacheCounters;
 use waglayla_utils::mem_size::MemSizeEstimator;
 use parking_lot::RwLock;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, error::Error, fs, path::PathBuf, sync::Arc};
 
@@ -82,13 +82,19 @@ pub struct MultiConsensusManagementStore {
 
 impl MultiConsensusManagementStore {
     pub fn new(db: Arc<DB>) -> Self {
-        let mut store = Self {
+        let mut store = Self::new_uninitialized(db);
+        store.init();
+        store
+    }
+
+    /// Creates the store without writing default metadata if it is missing, as required for read-only connections.
+    /// Queries on an uninitialized store return a `KeyNotFound` error.
+    pub fn new_uninitialized(db: Arc<DB>) -> Self {
+        Self {
             db: db.clone(),
             entries: CachedDbAccess::new(db.clone(), CachePolicy::Count(16), DatabaseStorePrefixes::ConsensusEntries.into()),
             metadata: CachedDbItem::new(db, DatabaseStorePrefixes::MultiConsensusMetadata.into()),
-        };
-        store.init();
-        store
+        }
     }
 
     fn init(&mut self) {
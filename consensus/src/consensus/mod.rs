@@ -20,7 +20,7 @@ use waglayla_consensus_core::{acceptance_data::AcceptanceData, api::{BlockValida
     coinbase::CoinbaseResult,
     consensus::{ConsensusError, ConsensusResult},
     tx::TxResult,
-}, errors::{difficulty::DifficultyError, pruning::PruningImportError}, header::Header, muhash::MuHashExtensions, network::NetworkType, pruning::{PruningPointProof, PruningPointsList, PruningPointTrustedData}, trusted::{ExternalGhostdagData, TrustedBlock}, tx::{MutableTransaction, Transaction, TransactionOutpoint, UtxoEntry}};
+}, errors::{difficulty::DifficultyError, pruning::PruningImportError}, header::Header, muhash::MuHashExtensions, network::NetworkType, pruning::{PruningPointProof, PruningPointsList, PruningPointTrustedData}, trusted::{ExternalGhostdagData, TrustedBlock}, tx::{MutableTransaction, Transaction, TransactionOutpoint, UtxoEntry}, utxo::utxo_diff::UtxoDiff};
 use waglayla_consensus_notify::root::ConsensusNotificationRoot;
 use waglayla_consensusmanager::{SessionLock, SessionReadGuard};
 use waglayla_core::info;
@@ -46,6 +46,7 @@ use crate::{
             relations::RelationsStoreReader,
             statuses::StatusesStoreReader,
             tips::TipsStoreReader,
+            utxo_diffs::UtxoDiffsStoreReader,
             utxo_set::{UtxoSetStore, UtxoSetStoreReader},
         },
     },
@@ -65,6 +66,7 @@ use self::{services::ConsensusServices, storage::ConsensusStorage};
 
 pub mod cache_policy_builder;
 pub mod ctl;
+pub mod db_check;
 pub mod factory;
 pub mod services;
 pub mod storage;
@@ -833,6 +835,10 @@ impl ConsensusApi for Consensus {
             .collect::<ConsensusResult<Vec<_>>>()
     }
 
+    fn get_block_utxo_diff(&self, hash: Hash) -> ConsensusResult<Arc<UtxoDiff>> {
+        self.utxo_diffs_store.get(hash).unwrap_option().ok_or(ConsensusError::MissingData(hash))
+    }
+
     fn is_chain_block(&self, hash: Hash) -> ConsensusResult<bool> {
         self.is_chain_ancestor_of(hash, self.get_sink())
     }
@@ -13,7 +13,7 @@ use waglayla_hashes::Hash;
 use waglayla_notify::subscription::context::SubscriptionContext;
 use parking_lot::RwLock;
 
-use waglayla_database::create_temp_db;
+use waglayla_database::{create_memory_db, create_temp_db};
 use waglayla_database::prelude::ConnBuilder;
 use std::future::Future;
 use std::{sync::Arc, thread::JoinHandle};
@@ -107,6 +107,16 @@ impl TestConsensus {
         Self { consensus, block_builder, params: config.params.clone(), db_lifetime }
     }
 
+    /// Creates a test consensus instance based on `config` with an in-memory DB and no notifier.
+    /// Faster than [`TestConsensus::new`] and leaves nothing on disk
+    pub fn with_memory_db(config: &Config) -> Self {
+        let (db_lifetime, db) = create_memory_db!();
+        let (dummy_notification_sender, _) = async_channel::unbounded();
+        let mut tc = Self::with_db(db, config, dummy_notification_sender);
+        tc.db_lifetime = db_lifetime;
+        tc
+    }
+
     /// Clone the inner consensus Arc. For general usage of the underlying consensus simply deref
     pub fn consensus_clone(&self) -> Arc<Consensus> {
         self.consensus.clone()
@@ -11,7 +11,7 @@ use waglayla_database::prelude::StoreResult;
 use waglayla_database::prelude::DB;
 use waglayla_database::registry::DatabaseStorePrefixes;
 use waglayla_hashes::Hash;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use std::sync::Arc;
 
 pub trait ChildrenStoreReader {
@@ -7,7 +7,7 @@ use waglayla_database::prelude::DB;
 use waglayla_database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter};
 use waglayla_database::registry::DatabaseStorePrefixes;
 use waglayla_hashes::Hash;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 
 pub trait DaaStoreReader {
     fn get_mergeset_non_daa(&self, hash: Hash) -> Result<Arc<BlockHashSet>, StoreError>;
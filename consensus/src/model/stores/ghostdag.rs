@@ -11,7 +11,7 @@ use waglayla_hashes::Hash;
 use itertools::EitherOrBoth::{Both, Left, Right};
 use itertools::Itertools;
 use waglayla_utils::mem_size::MemSizeEstimator;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use serde::{Deserialize, Serialize};
 use std::iter::once;
 use std::mem::size_of;
@@ -8,7 +8,7 @@ use waglayla_database::prelude::{StoreError, StoreResult};
 use waglayla_database::registry::DatabaseStorePrefixes;
 use waglayla_hashes::Hash;
 use waglayla_utils::mem_size::MemSizeEstimator;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use serde::{Deserialize, Serialize};
 
 pub trait HeaderStoreReader {
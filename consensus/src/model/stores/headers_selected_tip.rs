@@ -3,7 +3,7 @@ use waglayla_database::prelude::StoreResult;
 use waglayla_database::prelude::DB;
 use waglayla_database::prelude::{BatchDbWriter, CachedDbItem, DirectDbWriter};
 use waglayla_database::registry::DatabaseStorePrefixes;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use std::sync::Arc;
 
 /// Reader API for `SelectedTipStore`.
@@ -5,7 +5,7 @@ use waglayla_database::prelude::{CachePolicy, DB};
 use waglayla_database::prelude::{StoreError, StoreResult};
 use waglayla_database::registry::DatabaseStorePrefixes;
 use waglayla_hashes::Hash;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 
 use super::U64Key;
 
@@ -5,7 +5,7 @@ use waglayla_database::prelude::DB;
 use waglayla_database::prelude::{BatchDbWriter, CachedDbItem, DirectDbWriter};
 use waglayla_database::registry::DatabaseStorePrefixes;
 use waglayla_hashes::Hash;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
@@ -6,7 +6,7 @@ use waglayla_database::prelude::DB;
 use waglayla_database::prelude::{BatchDbWriter, CachedDbItem};
 use waglayla_database::registry::DatabaseStorePrefixes;
 use waglayla_hashes::Hash;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 
 use super::utxo_set::DbUtxoSetStore;
 
@@ -14,7 +14,7 @@ use waglayla_hashes::Hash;
 use itertools::Itertools;
 use waglayla_utils::mem_size::MemSizeEstimator;
 use parking_lot::{RwLockUpgradableReadGuard, RwLockWriteGuard};
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::hash_map::Entry::{Occupied, Vacant},
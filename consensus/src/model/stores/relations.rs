@@ -8,7 +8,7 @@ use waglayla_database::prelude::{ReadLock, StoreError};
 use waglayla_database::prelude::{StoreResult, DB};
 use waglayla_database::registry::{DatabaseStorePrefixes, SEPARATOR};
 use waglayla_hashes::Hash;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use std::collections::hash_map::Entry;
 use std::collections::HashSet;
 use std::iter::once;
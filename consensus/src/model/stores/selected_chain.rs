@@ -2,7 +2,7 @@ use waglayla_consensus_core::blockstatus::BlockStatus;
 use waglayla_consensus_core::ChainPath;
 use waglayla_database::registry::DatabaseStorePrefixes;
 use parking_lot::RwLockWriteGuard;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 
 use std::sync::Arc;
 
@@ -1,8 +1,8 @@
 use waglayla_consensus_core::{blockstatus::BlockStatus, BlockHasher};
 use waglayla_database::registry::DatabaseStorePrefixes;
 use parking_lot::{RwLock, RwLockWriteGuard};
-use rocksdb::WriteBatch;
-use std::sync::Arc;
+use waglayla_database::prelude::WriteBatch;
+use std::{error::Error, sync::Arc};
 
 use waglayla_database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter};
 use waglayla_database::prelude::{CachePolicy, DB};
@@ -46,6 +46,17 @@ impl DbStatusesStore {
     pub fn delete_batch(&self, batch: &mut WriteBatch, hash: Hash) -> Result<(), StoreError> {
         self.access.delete(BatchDbWriter::new(batch), hash)
     }
+
+    /// Iterates over all `(hash, status)` entries currently persisted in the DB
+    pub fn iterator(&self) -> impl Iterator<Item = Result<(Hash, BlockStatus), Box<dyn Error>>> + '_ {
+        self.access.iterator().map(|iter_result| match iter_result {
+            Ok((key_bytes, status)) => match Hash::try_from(key_bytes.as_ref()) {
+                Ok(hash) => Ok((hash, status)),
+                Err(e) => Err(e.into()),
+            },
+            Err(e) => Err(e),
+        })
+    }
 }
 
 pub trait StatusesStoreBatchExtensions {
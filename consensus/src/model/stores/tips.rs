@@ -11,7 +11,7 @@ use waglayla_database::prelude::DB;
 use waglayla_database::prelude::{BatchDbWriter, DirectDbWriter};
 use waglayla_database::registry::DatabaseStorePrefixes;
 use waglayla_hashes::Hash;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 
 /// Reader API for `TipsStore`.
 pub trait TipsStoreReader {
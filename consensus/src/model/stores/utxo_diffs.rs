@@ -7,7 +7,7 @@ use waglayla_database::prelude::DB;
 use waglayla_database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter};
 use waglayla_database::registry::DatabaseStorePrefixes;
 use waglayla_hashes::Hash;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 
 /// Store for holding the UTXO difference (delta) of a block relative to its selected parent.
 /// Note that this data is lazy-computed only for blocks which are candidates to being chain
@@ -7,7 +7,7 @@ use waglayla_database::registry::DatabaseStorePrefixes;
 use waglayla_hashes::Hash;
 use waglayla_math::Uint3072;
 use waglayla_muhash::MuHash;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use std::sync::Arc;
 
 pub trait UtxoMultisetsStoreReader {
@@ -13,7 +13,7 @@ use waglayla_database::prelude::{StoreError, DB};
 use waglayla_database::registry::DatabaseStorePrefixes;
 use waglayla_hashes::Hash;
 use waglayla_muhash::MuHash;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use serde::{Deserialize, Serialize};
 
 use super::ghostdag::GhostdagData;
@@ -35,7 +35,7 @@ use waglayla_hashes::Hash;
 use waglayla_notify::notifier::Notify;
 use parking_lot::RwLock;
 use rayon::ThreadPool;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use std::sync::{atomic::Ordering, Arc};
 
 pub struct BlockBodyProcessor {
@@ -42,7 +42,7 @@ use waglayla_hashes::Hash;
 use waglayla_utils::vec::VecExtensions;
 use parking_lot::RwLock;
 use rayon::ThreadPool;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use std::sync::{atomic::Ordering, Arc};
 
 use super::super::ProcessingCounters;
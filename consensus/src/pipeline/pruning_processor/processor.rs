@@ -40,7 +40,7 @@ use waglayla_hashes::Hash;
 use waglayla_muhash::MuHash;
 use waglayla_utils::iter::IterExtensions;
 use parking_lot::RwLockUpgradableReadGuard;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use std::{
     collections::VecDeque,
     ops::Deref,
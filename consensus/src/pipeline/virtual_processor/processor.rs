@@ -85,7 +85,7 @@ use rayon::{
     prelude::{IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator},
     ThreadPool,
 };
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 use std::{
     cmp::min,
     collections::{BinaryHeap, HashMap, VecDeque},
@@ -12,7 +12,7 @@ use std::{
 use itertools::Itertools;
 use waglayla_math::int::SignedInteger;
 use parking_lot::{Mutex, RwLock};
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 
 use waglayla_consensus_core::{
     blockhash::{self, BlockHashExtensions, BlockHashes, ORIGIN},
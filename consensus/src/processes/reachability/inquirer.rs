@@ -271,7 +271,7 @@ mod tests {
     use waglayla_database::{create_temp_db, prelude::CachePolicy};
     use parking_lot::RwLock;
     use rand::seq::IteratorRandom;
-    use rocksdb::WriteBatch;
+    use waglayla_database::prelude::WriteBatch;
     use std::{iter::once, ops::Deref};
 
     #[test]
@@ -10,7 +10,7 @@ use waglayla_consensus_core::{
 };
 use waglayla_database::prelude::{BatchDbWriter, DbWriter, DirectWriter, StoreError};
 use waglayla_hashes::Hash;
-use rocksdb::WriteBatch;
+use waglayla_database::prelude::WriteBatch;
 
 /// Initializes this relations store with an `origin` root
 pub fn init<S: RelationsStore + ChildrenStore + ?Sized>(relations: &mut S) {
@@ -13,6 +13,7 @@ use workflow_wasm::prelude::*;
 use pyo3::{IntoPy, Py, PyAny, Python};
 
 pub use hashers::*;
+pub use siphash::siphash24;
 use waglayla_utils::{
     hex::{FromHex, ToHex},
     mem_size::MemSizeEstimator,
@@ -21,6 +22,7 @@ use waglayla_utils::{
 
 mod hashers;
 mod pow_hashers;
+mod siphash;
 
 pub const HASH_SIZE: usize = 32;
 
@@ -0,0 +1,92 @@
+/// Returns the SipHash-2-4 hash of `data` keyed with `(k0, k1)`.
+///
+/// SipHash is a fast keyed hash for short inputs. Collisions cannot be crafted without knowledge of the key,
+/// which makes it suitable for ids truncated from a cryptographic hash, such as compact block short ids (BIP152).
+pub fn siphash24(k0: u64, k1: u64, data: &[u8]) -> u64 {
+    let mut state = SipState::new(k0, k1);
+    let mut chunks = data.chunks_exact(8);
+    for chunk in &mut chunks {
+        state.compress(u64::from_le_bytes(chunk.try_into().unwrap()));
+    }
+    let last = chunks.remainder().iter().enumerate().fold((data.len() as u64) << 56, |last, (i, &b)| last | (b as u64) << (8 * i));
+    state.compress(last);
+    state.finalize()
+}
+
+struct SipState {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+}
+
+impl SipState {
+    #[inline(always)]
+    fn new(k0: u64, k1: u64) -> Self {
+        Self { v0: k0 ^ 0x736f6d6570736575, v1: k1 ^ 0x646f72616e646f6d, v2: k0 ^ 0x6c7967656e657261, v3: k1 ^ 0x7465646279746573 }
+    }
+
+    #[inline(always)]
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13) ^ self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16) ^ self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21) ^ self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17) ^ self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    #[inline(always)]
+    fn compress(&mut self, m: u64) {
+        self.v3 ^= m;
+        self.round();
+        self.round();
+        self.v0 ^= m;
+    }
+
+    #[inline(always)]
+    fn finalize(mut self) -> u64 {
+        self.v2 ^= 0xff;
+        for _ in 0..4 {
+            self.round();
+        }
+        self.v0 ^ self.v1 ^ self.v2 ^ self.v3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::siphash24;
+    use rand::{thread_rng, Rng, RngCore};
+
+    #[test]
+    fn test_vectors() {
+        // Vectors from the SipHash reference implementation, keyed with the bytes 00..0f
+        let (k0, k1) = (0x0706050403020100, 0x0f0e0d0c0b0a0908);
+        let message = (0..64u8).collect::<Vec<_>>();
+        assert_eq!(siphash24(k0, k1, &message[..0]), 0x726fdb47dd0e0e31);
+        assert_eq!(siphash24(k0, k1, &message[..8]), 0x93f5f5799a932462);
+        assert_eq!(siphash24(k0, k1, &message[..15]), 0xa129ca6149be45e5);
+        assert_eq!(siphash24(k0, k1, &message[..63]), 0x958a324ceb064572);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_matches_std_siphash24() {
+        use std::hash::{Hasher, SipHasher};
+
+        let mut rng = thread_rng();
+        for len in 0..=64 {
+            let (k0, k1) = (rng.gen(), rng.gen());
+            let mut data = vec![0u8; len];
+            rng.fill_bytes(&mut data);
+            let mut hasher = SipHasher::new_with_keys(k0, k1);
+            hasher.write(&data);
+            assert_eq!(siphash24(k0, k1, &data), hasher.finish(), "SipHash-2-4 mismatch for {len} bytes");
+        }
+    }
+}
@@ -1,8 +1,7 @@
 use crate::{cache::CachePolicy, db::DB, errors::StoreError};
 
-use super::prelude::{Cache, DbKey, DbWriter};
+use super::prelude::{prefix_range, Cache, DbKey, DbWriter};
 use waglayla_utils::mem_size::MemSizeEstimator;
-use rocksdb::{Direction, IterateBounds, IteratorMode, ReadOptions};
 use serde::{de::DeserializeOwned, Serialize};
 use std::{collections::hash_map::RandomState, error::Error, hash::BuildHasher, sync::Arc};
 
@@ -43,19 +42,19 @@ where
     where
         TKey: Clone + AsRef<[u8]>,
     {
-        Ok(self.cache.contains_key(&key) || self.db.get_pinned(DbKey::new(&self.prefix, key))?.is_some())
+        Ok(self.cache.contains_key(&key) || self.db.get(DbKey::new(&self.prefix, key))?.is_some())
     }
 
     pub fn read(&self, key: TKey) -> Result<TData, StoreError>
     where
         TKey: Clone + AsRef<[u8]> + ToString,
-        TData: DeserializeOwned, // We need `DeserializeOwned` since the slice coming from `db.get_pinned` has short lifetime
+        TData: DeserializeOwned, // We need `DeserializeOwned` since the slice coming from `db.get` has short lifetime
     {
         if let Some(data) = self.cache.get(&key) {
             Ok(data)
         } else {
             let db_key = DbKey::new(&self.prefix, key.clone());
-            if let Some(slice) = self.db.get_pinned(&db_key)? {
+            if let Some(slice) = self.db.get(&db_key)? {
                 let data: TData = bincode::deserialize(&slice)?;
                 self.cache.insert(key, data.clone());
                 Ok(data)
@@ -68,19 +67,15 @@ where
     pub fn iterator(&self) -> impl Iterator<Item = Result<(Box<[u8]>, TData), Box<dyn Error>>> + '_
     where
         TKey: Clone + AsRef<[u8]>,
-        TData: DeserializeOwned, // We need `DeserializeOwned` since the slice coming from `db.get_pinned` has short lifetime
+        TData: DeserializeOwned, // We need `DeserializeOwned` since the slice coming from `db.get` has short lifetime
     {
         let prefix_key = DbKey::prefix_only(&self.prefix);
-        let mut read_opts = ReadOptions::default();
-        read_opts.set_iterate_range(rocksdb::PrefixRange(prefix_key.as_ref()));
-        self.db.iterator_opt(IteratorMode::From(prefix_key.as_ref(), Direction::Forward), read_opts).map(move |iter_result| {
-            match iter_result {
-                Ok((key, data_bytes)) => match bincode::deserialize(&data_bytes) {
-                    Ok(data) => Ok((key[prefix_key.prefix_len()..].into(), data)),
-                    Err(e) => Err(e.into()),
-                },
+        self.db.prefix_iterator(prefix_key.as_ref(), None).map(move |iter_result| match iter_result {
+            Ok((key, data_bytes)) => match bincode::deserialize(&data_bytes) {
+                Ok(data) => Ok((key[prefix_key.prefix_len()..].into(), data)),
                 Err(e) => Err(e.into()),
-            }
+            },
+            Err(e) => Err(e.into()),
         })
     }
 
@@ -153,15 +148,15 @@ where
         Ok(())
     }
 
-    /// Deletes all entries in the store using the underlying rocksdb `delete_range` operation
+    /// Deletes all entries in the store using the underlying storage backend `delete_range` operation
     pub fn delete_all(&self, mut writer: impl DbWriter) -> Result<(), StoreError>
     where
         TKey: Clone + AsRef<[u8]>,
     {
         self.cache.remove_all();
         let db_key = DbKey::prefix_only(&self.prefix);
-        let (from, to) = rocksdb::PrefixRange(db_key.as_ref()).into_bounds();
-        writer.delete_range(from.unwrap(), to.unwrap())?;
+        let (from, to) = prefix_range(db_key.as_ref());
+        writer.delete_range(from, to.unwrap())?;
         Ok(())
     }
 
@@ -187,15 +182,8 @@ where
             },
         );
 
-        let mut read_opts = ReadOptions::default();
-        read_opts.set_iterate_range(rocksdb::PrefixRange(db_key.as_ref()));
-
-        let mut db_iterator = match seek_from {
-            Some(seek_key) => {
-                self.db.iterator_opt(IteratorMode::From(DbKey::new(&self.prefix, seek_key).as_ref(), Direction::Forward), read_opts)
-            }
-            None => self.db.iterator_opt(IteratorMode::Start, read_opts),
-        };
+        let seek_key = seek_from.map(|seek_key| DbKey::new(&self.prefix, seek_key));
+        let mut db_iterator = self.db.prefix_iterator(db_key.as_ref(), seek_key.as_ref().map(|seek_key| seek_key.as_ref()));
 
         if skip_first {
             db_iterator.next();
@@ -218,16 +206,22 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        create_temp_db,
-        prelude::{BatchDbWriter, ConnBuilder, DirectDbWriter},
-    };
+    use crate::prelude::{BatchDbWriter, DirectDbWriter, WriteBatch, DB};
+    #[cfg(feature = "rocksdb")]
+    use crate::{create_temp_db, prelude::ConnBuilder};
     use waglayla_hashes::Hash;
-    use rocksdb::WriteBatch;
 
     #[test]
     fn test_delete_all() {
-        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        #[cfg(feature = "rocksdb")]
+        {
+            let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+            test_delete_all_impl(db);
+        }
+        test_delete_all_impl(Arc::new(DB::new_in_memory()));
+    }
+
+    fn test_delete_all_impl(db: Arc<DB>) {
         let access = CachedDbAccess::<Hash, u64>::new(db.clone(), CachePolicy::Count(2), vec![1, 2]);
 
         access.write_many(DirectDbWriter::new(&db), &mut (0..16).map(|i| (i.into(), 2))).unwrap();
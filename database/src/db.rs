@@ -1,44 +1,174 @@
-use rocksdb::{DBWithThreadMode, MultiThreaded};
-use std::ops::{Deref, DerefMut};
+use std::path::Path;
+#[cfg(feature = "rocksdb")]
 use std::path::PathBuf;
 
+pub use backend::{prefix_range, DbEntry, DbIterator, DbSlice, StorageBackend};
+pub use batch::{BatchOp, WriteBatch};
+#[cfg(feature = "rocksdb")]
 pub use conn_builder::ConnBuilder;
-use waglayla_utils::fd_budget::FDGuard;
+pub use memory_backend::MemoryBackend;
+#[cfg(feature = "rocksdb")]
+pub use rocksdb_backend::RocksDbBackend;
 
+use crate::errors::DbError;
+
+mod backend;
+mod batch;
+#[cfg(feature = "rocksdb")]
 mod conn_builder;
+mod memory_backend;
+#[cfg(feature = "rocksdb")]
+mod rocksdb_backend;
+
+/// The storage backends a [`DB`] can run on
+enum Backend {
+    #[cfg(feature = "rocksdb")]
+    RocksDb(RocksDbBackend),
+    Memory(MemoryBackend),
+    /// A storage engine implemented outside of this crate
+    Custom(Box<dyn StorageBackend>),
+}
+
+/// Calls the same [`StorageBackend`] method on whichever backend is in use, dispatching statically
+/// for the backends of this crate
+macro_rules! dispatch {
+    ($self:expr, $backend:ident => $call:expr) => {
+        match &$self.backend {
+            #[cfg(feature = "rocksdb")]
+            Backend::RocksDb($backend) => $call,
+            Backend::Memory($backend) => $call,
+            Backend::Custom($backend) => $call,
+        }
+    };
+}
 
-/// The DB type used for Waglaylad stores
+/// The DB type used for Waglaylad stores. Dispatches all operations to the underlying [`StorageBackend`],
+/// which is [`RocksDbBackend`] unless the DB is created in memory or with another engine. The dispatch
+/// to the backends of this crate is a plain `match`, so calls into them are static and can be inlined.
 pub struct DB {
-    inner: DBWithThreadMode<MultiThreaded>,
-    _fd_guard: FDGuard,
+    backend: Backend,
 }
 
 impl DB {
-    pub fn new(inner: DBWithThreadMode<MultiThreaded>, fd_guard: FDGuard) -> Self {
-        Self { inner, _fd_guard: fd_guard }
+    #[cfg(feature = "rocksdb")]
+    pub fn new(backend: RocksDbBackend) -> Self {
+        Self { backend: Backend::RocksDb(backend) }
     }
-}
 
-impl DerefMut for DB {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.inner
+    /// Creates a DB backed by a [`MemoryBackend`]. Nothing is persisted to disk.
+    pub fn new_in_memory() -> Self {
+        Self { backend: Backend::Memory(MemoryBackend::default()) }
     }
-}
 
-impl Deref for DB {
-    type Target = DBWithThreadMode<MultiThreaded>;
+    /// Creates a DB running on any [`StorageBackend`], e.g. a storage engine implemented outside of this crate
+    pub fn with_backend(backend: impl StorageBackend + 'static) -> Self {
+        Self { backend: Backend::Custom(Box::new(backend)) }
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.inner
+    #[inline]
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<DbSlice<'_>>, DbError> {
+        dispatch!(self, backend => backend.get(key.as_ref()))
+    }
+
+    #[inline]
+    pub fn put<K, V>(&self, key: K, value: V) -> Result<(), DbError>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        dispatch!(self, backend => backend.put(key.as_ref(), value.as_ref()))
+    }
+
+    #[inline]
+    pub fn delete<K: AsRef<[u8]>>(&self, key: K) -> Result<(), DbError> {
+        dispatch!(self, backend => backend.delete(key.as_ref()))
+    }
+
+    /// Atomically applies all operations of `batch`
+    #[inline]
+    pub fn write(&self, batch: WriteBatch) -> Result<(), DbError> {
+        dispatch!(self, backend => backend.write(batch))
+    }
+
+    /// Iterates over all entries prefixed by `prefix` in ascending key order, starting from `seek_from` if provided
+    #[inline]
+    pub fn prefix_iterator(&self, prefix: &[u8], seek_from: Option<&[u8]>) -> DbIterator<'_> {
+        dispatch!(self, backend => backend.prefix_iterator(prefix, seek_from))
+    }
+
+    /// The on-disk location of the DB. Empty for in-memory backends.
+    pub fn path(&self) -> &Path {
+        dispatch!(self, backend => backend.path())
     }
 }
 
 /// Deletes an existing DB if it exists
+#[cfg(feature = "rocksdb")]
 pub fn delete_db(db_dir: PathBuf) {
     if !db_dir.exists() {
         return;
     }
-    let options = rocksdb::Options::default();
-    let path = db_dir.to_str().unwrap();
-    <DBWithThreadMode<MultiThreaded>>::destroy(&options, path).expect("DB is expected to be deletable");
+    RocksDbBackend::destroy(db_dir.as_path()).expect("DB is expected to be deletable");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// An engine defined outside of the crate's backends, counting the batch operations it applies
+    struct RecordingBackend {
+        inner: MemoryBackend,
+        applied_ops: Arc<AtomicUsize>,
+    }
+
+    impl StorageBackend for RecordingBackend {
+        fn get(&self, key: &[u8]) -> Result<Option<DbSlice<'_>>, DbError> {
+            self.inner.get(key)
+        }
+
+        fn put(&self, key: &[u8], value: &[u8]) -> Result<(), DbError> {
+            self.inner.put(key, value)
+        }
+
+        fn delete(&self, key: &[u8]) -> Result<(), DbError> {
+            self.inner.delete(key)
+        }
+
+        fn write(&self, batch: WriteBatch) -> Result<(), DbError> {
+            self.applied_ops.fetch_add(batch.len(), Ordering::Relaxed);
+            self.inner.write(batch)
+        }
+
+        fn prefix_iterator<'a>(&'a self, prefix: &[u8], seek_from: Option<&[u8]>) -> DbIterator<'a> {
+            DbIterator::new(self.inner.prefix_iterator(prefix, seek_from))
+        }
+
+        fn path(&self) -> &Path {
+            self.inner.path()
+        }
+    }
+
+    #[test]
+    fn test_custom_backend() {
+        let applied_ops = Arc::new(AtomicUsize::new(0));
+        let db = DB::with_backend(RecordingBackend { inner: MemoryBackend::default(), applied_ops: applied_ops.clone() });
+        db.put([1, 1], [10]).unwrap();
+        db.put([2, 1], [20]).unwrap();
+
+        let mut batch = WriteBatch::default();
+        batch.put([1, 2], [11]);
+        batch.delete([2, 1]);
+        db.write(batch).unwrap();
+
+        assert_eq!(applied_ops.load(Ordering::Relaxed), 2);
+        assert_eq!(db.path(), Path::new(""));
+        assert_eq!(&*db.get([1, 2]).unwrap().unwrap(), &[11]);
+        assert!(db.get([2, 1]).unwrap().is_none());
+        let entries = db.prefix_iterator(&[1], None).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(entries, vec![(Box::from([1u8, 1].as_slice()), Box::from([10u8].as_slice())), ([1, 2].into(), [11].into())]);
+    }
 }
@@ -0,0 +1,161 @@
+use std::{ops::Deref, path::Path, sync::Arc};
+
+#[cfg(feature = "rocksdb")]
+use rocksdb::{DBIteratorWithThreadMode, DBPinnableSlice, DBWithThreadMode, MultiThreaded};
+
+use super::memory_backend::MemoryIterator;
+use crate::{db::WriteBatch, errors::DbError};
+
+/// A value read from the storage backend. Backends may hand out a view into their own memory (e.g. pinned RocksDB slices).
+pub struct DbSlice<'a>(SliceInner<'a>);
+
+enum SliceInner<'a> {
+    #[cfg(feature = "rocksdb")]
+    Pinned(DBPinnableSlice<'a>),
+    Borrowed(&'a [u8]),
+    Shared(Arc<[u8]>),
+    Owned(Box<[u8]>),
+}
+
+#[cfg(feature = "rocksdb")]
+impl<'a> From<DBPinnableSlice<'a>> for DbSlice<'a> {
+    fn from(slice: DBPinnableSlice<'a>) -> Self {
+        Self(SliceInner::Pinned(slice))
+    }
+}
+
+impl<'a> From<&'a [u8]> for DbSlice<'a> {
+    fn from(slice: &'a [u8]) -> Self {
+        Self(SliceInner::Borrowed(slice))
+    }
+}
+
+impl From<Arc<[u8]>> for DbSlice<'_> {
+    fn from(slice: Arc<[u8]>) -> Self {
+        Self(SliceInner::Shared(slice))
+    }
+}
+
+impl From<Box<[u8]>> for DbSlice<'_> {
+    fn from(slice: Box<[u8]>) -> Self {
+        Self(SliceInner::Owned(slice))
+    }
+}
+
+impl From<Vec<u8>> for DbSlice<'_> {
+    fn from(slice: Vec<u8>) -> Self {
+        Self(SliceInner::Owned(slice.into()))
+    }
+}
+
+impl Deref for DbSlice<'_> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        match &self.0 {
+            #[cfg(feature = "rocksdb")]
+            SliceInner::Pinned(slice) => slice,
+            SliceInner::Borrowed(slice) => slice,
+            SliceInner::Shared(slice) => slice,
+            SliceInner::Owned(slice) => slice,
+        }
+    }
+}
+
+/// A raw `(key, value)` DB entry
+pub type DbEntry = (Box<[u8]>, Box<[u8]>);
+
+/// An iterator over `(key, value)` pairs in ascending key order. Keys include their full prefix.
+pub struct DbIterator<'a>(IteratorInner<'a>);
+
+enum IteratorInner<'a> {
+    #[cfg(feature = "rocksdb")]
+    RocksDb(DBIteratorWithThreadMode<'a, DBWithThreadMode<MultiThreaded>>),
+    Memory(MemoryIterator<'a>),
+    Boxed(Box<dyn Iterator<Item = Result<DbEntry, DbError>> + 'a>),
+}
+
+impl<'a> DbIterator<'a> {
+    /// Wraps the iterator of a storage engine implemented outside of this crate
+    pub fn new(iterator: impl Iterator<Item = Result<DbEntry, DbError>> + 'a) -> Self {
+        Self(IteratorInner::Boxed(Box::new(iterator)))
+    }
+}
+
+#[cfg(feature = "rocksdb")]
+impl<'a> From<DBIteratorWithThreadMode<'a, DBWithThreadMode<MultiThreaded>>> for DbIterator<'a> {
+    fn from(iterator: DBIteratorWithThreadMode<'a, DBWithThreadMode<MultiThreaded>>) -> Self {
+        Self(IteratorInner::RocksDb(iterator))
+    }
+}
+
+impl<'a> From<MemoryIterator<'a>> for DbIterator<'a> {
+    fn from(iterator: MemoryIterator<'a>) -> Self {
+        Self(IteratorInner::Memory(iterator))
+    }
+}
+
+impl Iterator for DbIterator<'_> {
+    type Item = Result<DbEntry, DbError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            #[cfg(feature = "rocksdb")]
+            IteratorInner::RocksDb(iterator) => iterator.next().map(|item| item.map_err(DbError::from)),
+            IteratorInner::Memory(iterator) => iterator.next(),
+            IteratorInner::Boxed(iterator) => iterator.next(),
+        }
+    }
+}
+
+/// A key-value storage engine the [`DB`](crate::prelude::DB) can run on.
+///
+/// Implementations must keep keys ordered lexicographically by bytes and apply a [`WriteBatch`] atomically,
+/// iterating over its operations as [`BatchOp`](crate::prelude::BatchOp)s. The backends of this crate are dispatched statically,
+/// while any other engine is plugged in with [`DB::with_backend`](crate::prelude::DB::with_backend).
+pub trait StorageBackend: Send + Sync {
+    fn get(&self, key: &[u8]) -> Result<Option<DbSlice<'_>>, DbError>;
+
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<(), DbError>;
+
+    fn delete(&self, key: &[u8]) -> Result<(), DbError>;
+
+    /// Atomically applies all operations of `batch`, in insertion order
+    fn write(&self, batch: WriteBatch) -> Result<(), DbError>;
+
+    /// Iterates over all entries whose key starts with `prefix`, starting from the first key
+    /// greater or equal to `seek_from` if provided, or from the start of the prefix otherwise
+    fn prefix_iterator<'a>(&'a self, prefix: &[u8], seek_from: Option<&[u8]>) -> DbIterator<'a>;
+
+    /// The on-disk location of the storage. Empty for in-memory backends.
+    fn path(&self) -> &Path;
+}
+
+/// Returns the `[from, to)` key range covering all keys starting with `prefix`.
+///
+/// `to` is `None` when no such upper bound exists, i.e., when `prefix` is empty or consists of `0xff` bytes only.
+pub fn prefix_range(prefix: &[u8]) -> (Vec<u8>, Option<Vec<u8>>) {
+    let mut to = prefix.to_vec();
+    while let Some(last) = to.pop() {
+        if last != u8::MAX {
+            to.push(last + 1);
+            return (prefix.to_vec(), Some(to));
+        }
+    }
+    (prefix.to_vec(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_range() {
+        assert_eq!(prefix_range(&[1, 2]), (vec![1, 2], Some(vec![1, 3])));
+        assert_eq!(prefix_range(&[1, 255]), (vec![1, 255], Some(vec![2])));
+        assert_eq!(prefix_range(&[255, 255]), (vec![255, 255], None));
+        assert_eq!(prefix_range(&[]), (vec![], None));
+    }
+}
@@ -0,0 +1,164 @@
+/// A single write operation of a [`WriteBatch`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BatchOp {
+    Put(Box<[u8]>, Box<[u8]>),
+    Delete(Box<[u8]>),
+    /// Deletes all keys in the `[from, to)` range
+    DeleteRange(Box<[u8]>, Box<[u8]>),
+}
+
+/// A set of write operations applied atomically by [`DB::write`](crate::prelude::DB::write).
+///
+/// With the `rocksdb` feature, the operations are recorded straight into a native RocksDB batch which the default
+/// backend writes as is. Any other [`StorageBackend`](crate::prelude::StorageBackend) consumes the batch as a list
+/// of [`BatchOp`]s through [`IntoIterator`].
+#[derive(Default)]
+pub struct WriteBatch {
+    #[cfg(feature = "rocksdb")]
+    native: rocksdb::WriteBatch,
+    #[cfg(not(feature = "rocksdb"))]
+    ops: Vec<BatchOp>,
+}
+
+impl WriteBatch {
+    pub fn put<K, V>(&mut self, key: K, value: V)
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        #[cfg(feature = "rocksdb")]
+        self.native.put(key, value);
+        #[cfg(not(feature = "rocksdb"))]
+        self.ops.push(BatchOp::Put(key.as_ref().into(), value.as_ref().into()));
+    }
+
+    pub fn delete<K: AsRef<[u8]>>(&mut self, key: K) {
+        #[cfg(feature = "rocksdb")]
+        self.native.delete(key);
+        #[cfg(not(feature = "rocksdb"))]
+        self.ops.push(BatchOp::Delete(key.as_ref().into()));
+    }
+
+    pub fn delete_range<K: AsRef<[u8]>>(&mut self, from: K, to: K) {
+        #[cfg(feature = "rocksdb")]
+        self.native.delete_range(from, to);
+        #[cfg(not(feature = "rocksdb"))]
+        self.ops.push(BatchOp::DeleteRange(from.as_ref().into(), to.as_ref().into()));
+    }
+
+    #[cfg(feature = "rocksdb")]
+    pub fn len(&self) -> usize {
+        self.native.len()
+    }
+
+    #[cfg(not(feature = "rocksdb"))]
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The native RocksDB batch, written by the default backend without any conversion
+    #[cfg(feature = "rocksdb")]
+    pub(super) fn into_native(self) -> rocksdb::WriteBatch {
+        self.native
+    }
+}
+
+impl IntoIterator for WriteBatch {
+    type Item = BatchOp;
+    type IntoIter = std::vec::IntoIter<BatchOp>;
+
+    /// Iterates over the operations of the batch, in insertion order
+    #[cfg(feature = "rocksdb")]
+    fn into_iter(self) -> Self::IntoIter {
+        decode_rocksdb_batch(self.native.data()).into_iter()
+    }
+
+    /// Iterates over the operations of the batch, in insertion order
+    #[cfg(not(feature = "rocksdb"))]
+    fn into_iter(self) -> Self::IntoIter {
+        self.ops.into_iter()
+    }
+}
+
+/// Decodes the serialized form of a RocksDB batch: a 12 bytes header (sequence number and count) followed by a record
+/// per operation, made of a tag byte and of varint32 length prefixed slices. Only the records written by [`WriteBatch`]
+/// are expected.
+#[cfg(any(feature = "rocksdb", test))]
+fn decode_rocksdb_batch(data: &[u8]) -> Vec<BatchOp> {
+    const HEADER_SIZE: usize = 12;
+    const TYPE_DELETION: u8 = 0x0;
+    const TYPE_VALUE: u8 = 0x1;
+    const TYPE_RANGE_DELETION: u8 = 0xf;
+
+    fn read_slice(data: &mut &[u8]) -> Box<[u8]> {
+        let mut len = 0;
+        for shift in (0..35).step_by(7) {
+            let (&byte, rest) = data.split_first().expect("truncated RocksDB batch");
+            *data = rest;
+            len |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        let (slice, rest) = data.split_at(len);
+        *data = rest;
+        slice.into()
+    }
+
+    let mut data = &data[HEADER_SIZE..];
+    let mut ops = Vec::new();
+    while let Some((&tag, rest)) = data.split_first() {
+        data = rest;
+        ops.push(match tag {
+            TYPE_VALUE => BatchOp::Put(read_slice(&mut data), read_slice(&mut data)),
+            TYPE_DELETION => BatchOp::Delete(read_slice(&mut data)),
+            TYPE_RANGE_DELETION => BatchOp::DeleteRange(read_slice(&mut data), read_slice(&mut data)),
+            _ => panic!("unexpected RocksDB batch record tag {tag:#x}"),
+        });
+    }
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_ops() {
+        let mut batch = WriteBatch::default();
+        batch.put([1, 2], [11]);
+        batch.delete([2, 1]);
+        batch.delete_range([3], [4]);
+        assert_eq!(batch.len(), 3);
+        assert_eq!(
+            batch.into_iter().collect::<Vec<_>>(),
+            vec![
+                BatchOp::Put([1, 2].into(), [11].into()),
+                BatchOp::Delete([2, 1].into()),
+                BatchOp::DeleteRange([3].into(), [4].into())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_rocksdb_batch() {
+        let long_value = vec![7u8; 200];
+        let mut data = vec![0; 12];
+        data.extend([0x1, 2, 1, 2, 0xc8, 0x01]);
+        data.extend(&long_value);
+        data.extend([0x0, 1, 5]);
+        data.extend([0xf, 1, 3, 1, 4]);
+        assert_eq!(
+            decode_rocksdb_batch(&data),
+            vec![
+                BatchOp::Put([1, 2].into(), long_value.into()),
+                BatchOp::Delete([5].into()),
+                BatchOp::DeleteRange([3].into(), [4].into())
+            ]
+        );
+    }
+}
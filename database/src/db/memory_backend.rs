@@ -0,0 +1,166 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::Bound,
+    path::Path,
+    sync::Arc,
+};
+
+use parking_lot::RwLock;
+
+use super::{
+    backend::{prefix_range, DbEntry, DbIterator, DbSlice, StorageBackend},
+    batch::{BatchOp, WriteBatch},
+};
+use crate::errors::DbError;
+
+/// The number of entries an iterator copies out of the map on each lock acquisition
+const ITERATOR_CHUNK_SIZE: usize = 256;
+
+type Map = BTreeMap<Box<[u8]>, Arc<[u8]>>;
+
+/// A [`StorageBackend`] keeping all data in an ordered in-memory map.
+///
+/// Meant for tests and simulations: it is fast, fully deterministic and leaves nothing on disk.
+#[derive(Default)]
+pub struct MemoryBackend {
+    map: Arc<RwLock<Map>>,
+}
+
+fn delete_range(map: &mut Map, from: &[u8], to: &[u8]) {
+    if from < to {
+        let keys = map.range::<[u8], _>((Bound::Included(from), Bound::Excluded(to))).map(|(k, _)| k.clone()).collect::<Vec<_>>();
+        keys.into_iter().for_each(|key| {
+            map.remove(&key);
+        });
+    }
+}
+
+impl StorageBackend for MemoryBackend {
+    fn get(&self, key: &[u8]) -> Result<Option<DbSlice<'_>>, DbError> {
+        Ok(self.map.read().get(key).map(|value| DbSlice::from(value.clone())))
+    }
+
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<(), DbError> {
+        self.map.write().insert(key.into(), value.into());
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<(), DbError> {
+        self.map.write().remove(key);
+        Ok(())
+    }
+
+    fn write(&self, batch: WriteBatch) -> Result<(), DbError> {
+        let mut map = self.map.write();
+        for op in batch {
+            match op {
+                BatchOp::Put(key, value) => {
+                    map.insert(key, value.into());
+                }
+                BatchOp::Delete(key) => {
+                    map.remove(&key);
+                }
+                BatchOp::DeleteRange(from, to) => delete_range(&mut map, &from, &to),
+            }
+        }
+        Ok(())
+    }
+
+    fn prefix_iterator<'a>(&'a self, prefix: &[u8], seek_from: Option<&[u8]>) -> DbIterator<'a> {
+        let (from, to) = prefix_range(prefix);
+        let from = match seek_from {
+            Some(seek_key) if seek_key > from.as_slice() => seek_key.to_vec(),
+            _ => from,
+        };
+        MemoryIterator {
+            map: &self.map,
+            next: Bound::Included(from.into()),
+            to: to.map_or(Bound::Unbounded, |to| Bound::Excluded(to.into())),
+            buffer: VecDeque::new(),
+            done: false,
+        }
+        .into()
+    }
+
+    fn path(&self) -> &Path {
+        Path::new("")
+    }
+}
+
+/// Iterates over a key range by copying chunks of entries out of the map, so that no lock
+/// is held between calls and the map can be written to while iterating
+pub(super) struct MemoryIterator<'a> {
+    map: &'a RwLock<Map>,
+    next: Bound<Box<[u8]>>,
+    to: Bound<Box<[u8]>>,
+    buffer: VecDeque<DbEntry>,
+    done: bool,
+}
+
+impl Iterator for MemoryIterator<'_> {
+    type Item = Result<DbEntry, DbError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() && !self.done {
+            let map = self.map.read();
+            let is_empty_range = match (&self.next, &self.to) {
+                (Bound::Included(from) | Bound::Excluded(from), Bound::Excluded(to)) => from >= to,
+                _ => false,
+            };
+            if !is_empty_range {
+                self.buffer.extend(
+                    map.range::<[u8], _>((self.next.as_ref().map(|k| &**k), self.to.as_ref().map(|k| &**k)))
+                        .take(ITERATOR_CHUNK_SIZE)
+                        .map(|(k, v)| (k.clone(), Box::<[u8]>::from(&**v))),
+                );
+            }
+            match self.buffer.back() {
+                Some((last, _)) if self.buffer.len() == ITERATOR_CHUNK_SIZE => self.next = Bound::Excluded(last.clone()),
+                _ => self.done = true,
+            }
+        }
+        self.buffer.pop_front().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect_keys(backend: &MemoryBackend, prefix: &[u8], seek_from: Option<&[u8]>) -> Vec<Vec<u8>> {
+        backend.prefix_iterator(prefix, seek_from).map(|item| item.unwrap().0.to_vec()).collect()
+    }
+
+    #[test]
+    fn test_memory_backend() {
+        let backend = MemoryBackend::default();
+        for i in 0..1000u16 {
+            backend.put(&[1, (i >> 8) as u8, i as u8], &i.to_le_bytes()).unwrap();
+        }
+        backend.put(&[0, 1], &[]).unwrap();
+        backend.put(&[2], &[]).unwrap();
+
+        // Iteration spans multiple chunks and stays within the prefix
+        let keys = collect_keys(&backend, &[1], None);
+        assert_eq!(keys.len(), 1000);
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(&*backend.get(&[1, 3, 231]).unwrap().unwrap(), &999u16.to_le_bytes());
+
+        // Seeking
+        assert_eq!(collect_keys(&backend, &[1], Some(&[1, 3, 230])), vec![vec![1, 3, 230], vec![1, 3, 231]]);
+        assert_eq!(collect_keys(&backend, &[1], Some(&[0])).len(), 1000);
+        assert!(collect_keys(&backend, &[1], Some(&[2])).is_empty());
+
+        // Batch deletion of a range, atomic with the other ops and applied in insertion order
+        let (from, to) = prefix_range(&[1]);
+        let mut batch = WriteBatch::default();
+        batch.put([1, 9, 9], [8]);
+        batch.delete_range(from, to.unwrap());
+        batch.put([1, 0, 0], [7]);
+        batch.delete([2]);
+        backend.write(batch).unwrap();
+        assert_eq!(collect_keys(&backend, &[1], None), vec![vec![1, 0, 0]]);
+        assert!(backend.get(&[2]).unwrap().is_none());
+        assert_eq!(collect_keys(&backend, &[], None), vec![vec![0, 1], vec![1, 0, 0]]);
+    }
+}
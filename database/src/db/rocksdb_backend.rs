@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use rocksdb::{DBWithThreadMode, Direction, IteratorMode, MultiThreaded, ReadOptions};
+use waglayla_utils::fd_budget::FDGuard;
+
+use super::{
+    backend::{DbIterator, DbSlice, StorageBackend},
+    batch::WriteBatch,
+};
+use crate::errors::DbError;
+
+/// The default [`StorageBackend`], backed by a multi-threaded RocksDB instance
+pub struct RocksDbBackend {
+    inner: DBWithThreadMode<MultiThreaded>,
+    _fd_guard: FDGuard,
+}
+
+impl RocksDbBackend {
+    pub fn new(inner: DBWithThreadMode<MultiThreaded>, fd_guard: FDGuard) -> Self {
+        Self { inner, _fd_guard: fd_guard }
+    }
+
+    /// Destroys the RocksDB instance stored at `path`
+    pub fn destroy(path: &Path) -> Result<(), DbError> {
+        let options = rocksdb::Options::default();
+        Ok(<DBWithThreadMode<MultiThreaded>>::destroy(&options, path)?)
+    }
+}
+
+impl StorageBackend for RocksDbBackend {
+    fn get(&self, key: &[u8]) -> Result<Option<DbSlice<'_>>, DbError> {
+        Ok(self.inner.get_pinned(key)?.map(DbSlice::from))
+    }
+
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<(), DbError> {
+        Ok(self.inner.put(key, value)?)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<(), DbError> {
+        Ok(self.inner.delete(key)?)
+    }
+
+    fn write(&self, batch: WriteBatch) -> Result<(), DbError> {
+        Ok(self.inner.write(batch.into_native())?)
+    }
+
+    fn prefix_iterator<'a>(&'a self, prefix: &[u8], seek_from: Option<&[u8]>) -> DbIterator<'a> {
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_iterate_range(rocksdb::PrefixRange(prefix));
+        let mode = match seek_from {
+            Some(seek_key) => IteratorMode::From(seek_key, Direction::Forward),
+            None => IteratorMode::Start,
+        };
+        self.inner.iterator_opt(mode, read_opts).into()
+    }
+
+    fn path(&self) -> &Path {
+        self.inner.path()
+    }
+}
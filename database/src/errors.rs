@@ -18,13 +18,24 @@ pub enum StoreError {
     #[error("data inconsistency: {0}")]
     DataInconsistency(String),
 
-    #[error("rocksdb error {0}")]
-    DbError(#[from] rocksdb::Error),
+    #[error("{0}")]
+    DbError(#[from] DbError),
 
     #[error("bincode error {0}")]
     DeserializationError(#[from] Box<bincode::ErrorKind>),
 }
 
+/// An error raised by the underlying [`StorageBackend`](crate::prelude::StorageBackend)
+#[derive(Error, Debug)]
+pub enum DbError {
+    #[cfg(feature = "rocksdb")]
+    #[error("rocksdb error {0}")]
+    RocksDb(#[from] rocksdb::Error),
+
+    #[error("storage backend error {0}")]
+    Backend(String),
+}
+
 pub type StoreResult<T> = std::result::Result<T, StoreError>;
 
 pub trait StoreResultExtensions<T> {
@@ -19,6 +19,8 @@ pub mod prelude {
     pub use super::key::DbKey;
     pub use super::set_access::{CachedDbSetAccess, DbSetAccess, ReadLock};
     pub use super::writer::{BatchDbWriter, DbWriter, DirectDbWriter, DirectWriter, MemoryWriter};
-    pub use db::{delete_db, ConnBuilder, DB};
-    pub use errors::{StoreError, StoreResult, StoreResultEmptyTuple, StoreResultExtensions};
+    #[cfg(feature = "rocksdb")]
+    pub use db::{delete_db, ConnBuilder, RocksDbBackend};
+    pub use db::{prefix_range, BatchOp, DbEntry, DbIterator, DbSlice, MemoryBackend, StorageBackend, WriteBatch, DB};
+    pub use errors::{DbError, StoreError, StoreResult, StoreResultEmptyTuple, StoreResultExtensions};
 }
@@ -46,13 +46,30 @@ pub enum DatabaseStorePrefixes {
     ConsensusEntries = 125,
 
     // ---- Components ----
-    Addresses = 128,
-    BannedAddresses = 129,
+    Addresses = 128,       // Legacy address entries without onion support, superseded by `NetAddresses`
+    BannedAddresses = 129, // Legacy per-IP bans, superseded by `BannedSubnets`
+    BannedSubnets = 130,
+    NetAddresses = 131,
 
     // ---- Indexes ----
     UtxoIndex = 192,
     UtxoIndexTips = 193,
     CirculatingSupply = 194,
+    TxIndexInclusions = 195,
+    TxIndexAcceptances = 196,
+    TxIndexBlocks = 197,
+    TxIndexBlocksByDaaScore = 198,
+    TxIndexSink = 199,
+    TxIndexPruningPoint = 200,
+    AddressIndexHistory = 201,
+    AddressIndexBlocks = 202,
+    AddressIndexBlocksByDaaScore = 203,
+    AddressIndexSink = 204,
+    AddressIndexPruningPoint = 205,
+    ChainIndexBlocksByDaaScore = 206,
+    ChainIndexBlocksByMaxTimestamp = 207,
+    ChainIndexSink = 208,
+    ChainIndexPruningPoint = 209,
 
     // ---- Separator ----
     /// Reserved as a separator
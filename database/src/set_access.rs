@@ -1,8 +1,7 @@
 use crate::{cache::CachePolicy, db::DB, errors::StoreError};
 
-use super::prelude::{Cache, DbKey, DbWriter};
+use super::prelude::{prefix_range, Cache, DbKey, DbWriter};
 use parking_lot::{RwLock, RwLockReadGuard};
-use rocksdb::{IterateBounds, IteratorMode, ReadOptions};
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
     collections::{hash_map::RandomState, HashSet},
@@ -141,8 +140,8 @@ where
 
     pub fn delete_bucket(&self, mut writer: impl DbWriter, key: TKey) -> Result<(), StoreError> {
         let db_key = DbKey::new_with_bucket(&self.prefix, &key, []);
-        let (from, to) = rocksdb::PrefixRange(db_key.as_ref()).into_bounds();
-        writer.delete_range(from.unwrap(), to.unwrap())?;
+        let (from, to) = prefix_range(db_key.as_ref());
+        writer.delete_range(from, to.unwrap())?;
         Ok(())
     }
 
@@ -162,10 +161,7 @@ where
         TData: DeserializeOwned,
     {
         let db_key = DbKey::new_with_bucket(&self.prefix, &key, []);
-        let mut read_opts = ReadOptions::default();
-        read_opts.set_iterate_range(rocksdb::PrefixRange(db_key.as_ref()));
-
-        let mut db_iterator = self.db.iterator_opt(IteratorMode::Start, read_opts);
+        let mut db_iterator = self.db.prefix_iterator(db_key.as_ref(), None);
 
         if skip_first {
             db_iterator.next();
@@ -196,16 +192,22 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        create_temp_db,
-        prelude::{BatchDbWriter, ConnBuilder, DirectDbWriter},
-    };
+    use crate::prelude::{BatchDbWriter, DirectDbWriter, WriteBatch, DB};
+    #[cfg(feature = "rocksdb")]
+    use crate::{create_temp_db, prelude::ConnBuilder};
     use waglayla_hashes::Hash;
-    use rocksdb::WriteBatch;
 
     #[test]
     fn test_delete_bucket() {
-        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        #[cfg(feature = "rocksdb")]
+        {
+            let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+            test_delete_bucket_impl(db);
+        }
+        test_delete_bucket_impl(Arc::new(DB::new_in_memory()));
+    }
+
+    fn test_delete_bucket_impl(db: Arc<DB>) {
         let access = DbSetAccess::<Hash, u64>::new(db.clone(), vec![1, 2]);
 
         for i in 0..16 {
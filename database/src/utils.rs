@@ -1,3 +1,5 @@
+#[cfg(feature = "rocksdb")]
+use crate::prelude::RocksDbBackend;
 use crate::prelude::DB;
 use std::sync::Weak;
 use tempfile::TempDir;
@@ -31,12 +33,9 @@ impl Drop for DbLifetime {
             }
         }
         assert_eq!(self.weak_db_ref.strong_count(), 0, "DB is expected to have no strong references when lifetime is dropped");
-        if let Some(dir) = self.optional_tempdir.take() {
-            let options = rocksdb::Options::default();
-            let path_buf = dir.path().to_owned();
-            let path = path_buf.to_str().unwrap();
-            <rocksdb::DBWithThreadMode<rocksdb::MultiThreaded>>::destroy(&options, path)
-                .expect("DB is expected to be deletable since there are no references to it");
+        if let Some(_dir) = self.optional_tempdir.take() {
+            #[cfg(feature = "rocksdb")]
+            RocksDbBackend::destroy(_dir.path()).expect("DB is expected to be deletable since there are no references to it");
         }
     }
 }
@@ -61,6 +60,16 @@ macro_rules! create_temp_db {
     }};
 }
 
+/// Creates a DB held entirely in memory, see [`MemoryBackend`](crate::prelude::MemoryBackend).
+/// Callers must keep the `TempDbLifetime` guard for as long as they wish the DB to exist.
+#[macro_export]
+macro_rules! create_memory_db {
+    () => {{
+        let db = std::sync::Arc::new($crate::prelude::DB::new_in_memory());
+        ($crate::utils::DbLifetime::without_destroy(std::sync::Arc::downgrade(&db)), db)
+    }};
+}
+
 /// Creates a DB within the provided directory path.
 /// Callers must keep the `TempDbLifetime` guard for as long as they wish the DB instance to exist.
 #[macro_export]
@@ -1,16 +1,15 @@
 use waglayla_utils::refs::Refs;
-use rocksdb::WriteBatch;
 
-use crate::prelude::DB;
+use crate::prelude::{DbError, WriteBatch, DB};
 
 /// Abstraction over direct/batched DB writing
 pub trait DbWriter {
-    fn put<K, V>(&mut self, key: K, value: V) -> Result<(), rocksdb::Error>
+    fn put<K, V>(&mut self, key: K, value: V) -> Result<(), DbError>
     where
         K: AsRef<[u8]>,
         V: AsRef<[u8]>;
-    fn delete<K: AsRef<[u8]>>(&mut self, key: K) -> Result<(), rocksdb::Error>;
-    fn delete_range<K>(&mut self, from: K, to: K) -> Result<(), rocksdb::Error>
+    fn delete<K: AsRef<[u8]>>(&mut self, key: K) -> Result<(), DbError>;
+    fn delete_range<K>(&mut self, from: K, to: K) -> Result<(), DbError>
     where
         K: AsRef<[u8]>;
 }
@@ -34,7 +33,7 @@ impl<'a> DirectDbWriter<'a> {
 }
 
 impl DbWriter for DirectDbWriter<'_> {
-    fn put<K, V>(&mut self, key: K, value: V) -> Result<(), rocksdb::Error>
+    fn put<K, V>(&mut self, key: K, value: V) -> Result<(), DbError>
     where
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
@@ -42,11 +41,11 @@ impl DbWriter for DirectDbWriter<'_> {
         self.db.put(key, value)
     }
 
-    fn delete<K: AsRef<[u8]>>(&mut self, key: K) -> Result<(), rocksdb::Error> {
+    fn delete<K: AsRef<[u8]>>(&mut self, key: K) -> Result<(), DbError> {
         self.db.delete(key)
     }
 
-    fn delete_range<K>(&mut self, from: K, to: K) -> Result<(), rocksdb::Error>
+    fn delete_range<K>(&mut self, from: K, to: K) -> Result<(), DbError>
     where
         K: AsRef<[u8]>,
     {
@@ -69,7 +68,7 @@ impl<'a> BatchDbWriter<'a> {
 }
 
 impl DbWriter for BatchDbWriter<'_> {
-    fn put<K, V>(&mut self, key: K, value: V) -> Result<(), rocksdb::Error>
+    fn put<K, V>(&mut self, key: K, value: V) -> Result<(), DbError>
     where
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
@@ -78,12 +77,12 @@ impl DbWriter for BatchDbWriter<'_> {
         Ok(())
     }
 
-    fn delete<K: AsRef<[u8]>>(&mut self, key: K) -> Result<(), rocksdb::Error> {
+    fn delete<K: AsRef<[u8]>>(&mut self, key: K) -> Result<(), DbError> {
         self.batch.delete(key);
         Ok(())
     }
 
-    fn delete_range<K>(&mut self, from: K, to: K) -> Result<(), rocksdb::Error>
+    fn delete_range<K>(&mut self, from: K, to: K) -> Result<(), DbError>
     where
         K: AsRef<[u8]>,
     {
@@ -94,7 +93,7 @@ impl DbWriter for BatchDbWriter<'_> {
 
 impl<T: DbWriter> DbWriter for &mut T {
     #[inline]
-    fn put<K, V>(&mut self, key: K, value: V) -> Result<(), rocksdb::Error>
+    fn put<K, V>(&mut self, key: K, value: V) -> Result<(), DbError>
     where
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
@@ -103,12 +102,12 @@ impl<T: DbWriter> DbWriter for &mut T {
     }
 
     #[inline]
-    fn delete<K: AsRef<[u8]>>(&mut self, key: K) -> Result<(), rocksdb::Error> {
+    fn delete<K: AsRef<[u8]>>(&mut self, key: K) -> Result<(), DbError> {
         (*self).delete(key)
     }
 
     #[inline]
-    fn delete_range<K>(&mut self, from: K, to: K) -> Result<(), rocksdb::Error>
+    fn delete_range<K>(&mut self, from: K, to: K) -> Result<(), DbError>
     where
         K: AsRef<[u8]>,
     {
@@ -123,7 +122,7 @@ impl<T: DirectWriter> DirectWriter for &mut T {}
 pub struct MemoryWriter;
 
 impl DbWriter for MemoryWriter {
-    fn put<K, V>(&mut self, _key: K, _value: V) -> Result<(), rocksdb::Error>
+    fn put<K, V>(&mut self, _key: K, _value: V) -> Result<(), DbError>
     where
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
@@ -131,11 +130,11 @@ impl DbWriter for MemoryWriter {
         Ok(())
     }
 
-    fn delete<K: AsRef<[u8]>>(&mut self, _key: K) -> Result<(), rocksdb::Error> {
+    fn delete<K: AsRef<[u8]>>(&mut self, _key: K) -> Result<(), DbError> {
         Ok(())
     }
 
-    fn delete_range<K>(&mut self, _from: K, _to: K) -> Result<(), rocksdb::Error>
+    fn delete_range<K>(&mut self, _from: K, _to: K) -> Result<(), DbError>
     where
         K: AsRef<[u8]>,
     {
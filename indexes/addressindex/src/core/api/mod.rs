@@ -0,0 +1,88 @@
+use waglayla_consensus_core::{acceptance_data::AcceptanceData, tx::ScriptPublicKeys};
+use waglayla_consensusmanager::spawn_blocking;
+use waglayla_database::prelude::StoreResult;
+use waglayla_hashes::Hash;
+use parking_lot::RwLock;
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{errors::AddressIndexResult, model::AddressHistoryPage};
+
+///Addressindex API targeted at retrieval calls.
+pub trait AddressIndexApi: Send + Sync + Debug {
+    /// Retrieve a page of the transactions paying or spending from the queried script public keys,
+    /// starting at transactions accepted with a DAA score of `from_daa_score`.
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_address_history(
+        &self,
+        script_public_keys: ScriptPublicKeys,
+        from_daa_score: u64,
+        limit: usize,
+    ) -> StoreResult<AddressHistoryPage>;
+
+    /// Retrieve the sink the addressindex was last synced with (used for testing purposes).
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_addressindex_sink(&self) -> StoreResult<Hash>;
+
+    /// Checks if the addressindex's db is synced with consensus.
+    ///
+    /// Note:
+    /// 1) Use a read lock when accessing this method
+    /// 2) due to potential sync-gaps is_synced is unreliable while consensus is actively resolving virtual states.
+    fn is_synced(&self) -> AddressIndexResult<bool>;
+
+    /// Update the address history with the given virtual chain changes,
+    /// and drop the history accepted below the pruning point.
+    ///
+    /// Note: Use a write lock when accessing this method
+    fn update_via_virtual_chain_changed(
+        &mut self,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+    ) -> AddressIndexResult<()>;
+
+    /// Resync the addressindex from the consensus db
+    ///
+    /// Note: Use a write lock when accessing this method
+    fn resync(&mut self) -> AddressIndexResult<()>;
+}
+
+/// Async proxy for the address history index
+#[derive(Debug, Clone)]
+pub struct AddressIndexProxy {
+    inner: Arc<RwLock<dyn AddressIndexApi>>,
+}
+
+impl AddressIndexProxy {
+    pub fn new(inner: Arc<RwLock<dyn AddressIndexApi>>) -> Self {
+        Self { inner }
+    }
+
+    pub async fn get_address_history(
+        self,
+        script_public_keys: ScriptPublicKeys,
+        from_daa_score: u64,
+        limit: usize,
+    ) -> StoreResult<AddressHistoryPage> {
+        spawn_blocking(move || self.inner.read().get_address_history(script_public_keys, from_daa_score, limit)).await.unwrap()
+    }
+
+    pub async fn update_via_virtual_chain_changed(
+        self,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+    ) -> AddressIndexResult<()> {
+        spawn_blocking(move || {
+            self.inner.write().update_via_virtual_chain_changed(
+                added_chain_block_hashes,
+                removed_chain_block_hashes,
+                added_chain_blocks_acceptance_data,
+            )
+        })
+        .await
+        .unwrap()
+    }
+}
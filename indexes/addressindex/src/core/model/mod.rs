@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use waglayla_consensus_core::tx::{ScriptPublicKey, TransactionId, TransactionOutpoint};
+use waglayla_hashes::Hash;
+use waglayla_utils::mem_size::MemSizeEstimator;
+
+/// A UTXO paid to, or spent from, an indexed script public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressHistoryUtxo {
+    pub outpoint: TransactionOutpoint,
+    pub amount: u64,
+    pub block_daa_score: u64,
+    pub is_coinbase: bool,
+}
+
+impl AddressHistoryUtxo {
+    pub fn new(outpoint: TransactionOutpoint, amount: u64, block_daa_score: u64, is_coinbase: bool) -> Self {
+        Self { outpoint, amount, block_daa_score, is_coinbase }
+    }
+}
+
+/// A transaction accepted by the selected chain which paid or spent from an indexed script public key.
+///
+/// `received` holds the outputs of the transaction paying the script public key, and `spent` holds the
+/// previous outputs of the script public key consumed by the inputs of the transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressTransaction {
+    pub transaction_id: TransactionId,
+    pub accepting_block_hash: Hash,
+    pub accepting_block_daa_score: u64,
+    pub received: Vec<AddressHistoryUtxo>,
+    pub spent: Vec<AddressHistoryUtxo>,
+}
+
+impl AddressTransaction {
+    pub fn new(transaction_id: TransactionId, accepting_block_hash: Hash, accepting_block_daa_score: u64) -> Self {
+        Self { transaction_id, accepting_block_hash, accepting_block_daa_score, received: vec![], spent: vec![] }
+    }
+}
+
+impl MemSizeEstimator for AddressTransaction {}
+
+/// A page of address history, ordered by accepting DAA score and then by transaction id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddressHistoryPage {
+    pub transactions: Vec<(ScriptPublicKey, AddressTransaction)>,
+    /// The DAA score the next page starts from, or `None` if the history was fully returned.
+    ///
+    /// A page never splits the transactions accepted by the same chain block, so it may slightly
+    /// exceed the requested limit.
+    pub next_daa_score: Option<u64>,
+}
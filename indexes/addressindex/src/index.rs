@@ -0,0 +1,303 @@
+use crate::{
+    api::AddressIndexApi,
+    errors::AddressIndexResult,
+    model::{AddressHistoryPage, AddressHistoryUtxo, AddressTransaction},
+    stores::store_manager::Store,
+    IDENT,
+};
+use waglayla_consensus_core::{
+    acceptance_data::AcceptanceData,
+    api::ConsensusApi,
+    tx::{ScriptPublicKey, ScriptPublicKeys, TransactionId, TransactionOutpoint, UtxoEntry},
+};
+use waglayla_consensusmanager::ConsensusManager;
+use waglayla_core::{info, trace};
+use waglayla_database::prelude::{StoreResult, WriteBatch, DB};
+use waglayla_hashes::Hash;
+use waglayla_index_core::{reset::IndexConsensusResetHandler, sync_points::is_synced};
+use parking_lot::RwLock;
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
+
+const RESYNC_CHUNK_SIZE: usize = 256; // Chain blocks per acceptance data request; each of them can merge many blocks with full bodies.
+
+/// AddressIndex indexes the transactions accepted by the selected chain by the script public keys they pay or spend from.
+/// Only the history above the consensus pruning point is kept, older entries are dropped as the pruning point advances.
+/// Note: The AddressIndex struct by itself is not thread safe, only correct usage of the supplied RwLock via `new` makes it so.
+/// please follow guidelines found in the comments under `addressindex::core::api::AddressIndexApi` for proper thread safety.
+pub struct AddressIndex {
+    consensus_manager: Arc<ConsensusManager>,
+    store: Store,
+}
+
+impl AddressIndex {
+    /// Creates a new [`AddressIndex`] within a [`RwLock`]
+    pub fn new(consensus_manager: Arc<ConsensusManager>, db: Arc<DB>) -> AddressIndexResult<Arc<RwLock<Self>>> {
+        let mut addressindex = Self { consensus_manager: consensus_manager.clone(), store: Store::new(db) };
+        if !addressindex.is_synced()? {
+            addressindex.resync()?;
+        }
+        let addressindex = Arc::new(RwLock::new(addressindex));
+        consensus_manager
+            .register_consensus_reset_handler(Arc::new(IndexConsensusResetHandler::new(Arc::downgrade(&addressindex), Self::resync)));
+        Ok(addressindex)
+    }
+
+    /// Indexes the transactions accepted by a chain block by the script public keys they pay or spend from.
+    ///
+    /// The spent entries are resolved from the outputs created earlier in the same mergeset, and otherwise
+    /// from the UTXO diff of the chain block, which holds every pre-existing entry consumed by its mergeset.
+    fn index_chain_block(
+        &mut self,
+        batch: &mut WriteBatch,
+        consensus: &dyn ConsensusApi,
+        block_hash: Hash,
+        acceptance_data: &AcceptanceData,
+    ) -> AddressIndexResult<()> {
+        let daa_score = consensus.get_header(block_hash)?.daa_score;
+        let utxo_diff = consensus.get_block_utxo_diff(block_hash)?;
+        let mut created: HashMap<TransactionOutpoint, UtxoEntry> = HashMap::new();
+        let mut history: HashMap<(ScriptPublicKey, TransactionId), AddressTransaction> = HashMap::new();
+
+        for mergeset_block_data in acceptance_data.iter().filter(|x| !x.accepted_transactions.is_empty()) {
+            let block = consensus.get_block(mergeset_block_data.block_hash)?;
+            for accepted in mergeset_block_data.accepted_transactions.iter() {
+                let transaction = &block.transactions[accepted.index_within_block as usize];
+                let transaction_id = accepted.transaction_id;
+                for input in transaction.inputs.iter() {
+                    let outpoint = input.previous_outpoint;
+                    if let Some(utxo) = created.get(&outpoint).or_else(|| utxo_diff.remove.get(&outpoint)) {
+                        history
+                            .entry((utxo.script_public_key.clone(), transaction_id))
+                            .or_insert_with(|| AddressTransaction::new(transaction_id, block_hash, daa_score))
+                            .spent
+                            .push(AddressHistoryUtxo::new(outpoint, utxo.amount, utxo.block_daa_score, utxo.is_coinbase));
+                    }
+                }
+
+                let is_coinbase = transaction.is_coinbase();
+                for (index, output) in transaction.outputs.iter().enumerate() {
+                    let outpoint = TransactionOutpoint::new(transaction_id, index as u32);
+                    history
+                        .entry((output.script_public_key.clone(), transaction_id))
+                        .or_insert_with(|| AddressTransaction::new(transaction_id, block_hash, daa_score))
+                        .received
+                        .push(AddressHistoryUtxo::new(outpoint, output.value, daa_score, is_coinbase));
+                    created.insert(outpoint, UtxoEntry::new(output.value, output.script_public_key.clone(), daa_score, is_coinbase));
+                }
+            }
+        }
+
+        trace!("[{0}] indexing {1} address history entries of chain block {2}", IDENT, history.len(), block_hash);
+        self.store.add_chain_block_history(
+            batch,
+            block_hash,
+            daa_score,
+            history.into_iter().map(|((script_public_key, _), transaction)| (script_public_key, transaction)).collect(),
+        )?;
+        Ok(())
+    }
+
+    /// Drops the history below `pruning_point` and records it as the new addressindex pruning point.
+    fn prune(&mut self, consensus: &dyn ConsensusApi, pruning_point: Hash) -> AddressIndexResult<()> {
+        let pruning_point_daa_score = consensus.get_header(pruning_point)?.daa_score;
+        let mut batch = WriteBatch::default();
+        let pruned = self.store.prune_below_daa_score(&mut batch, pruning_point_daa_score)?;
+        self.store.set_pruning_point(&mut batch, pruning_point)?;
+        self.store.write_batch(batch)?;
+        trace!("[{0}] pruned {1} chain blocks below pruning point {2}", IDENT, pruned, pruning_point);
+        Ok(())
+    }
+}
+
+impl AddressIndexApi for AddressIndex {
+    /// Retrieve a page of the address history of the queried script public keys from the addressindex db.
+    fn get_address_history(
+        &self,
+        script_public_keys: ScriptPublicKeys,
+        from_daa_score: u64,
+        limit: usize,
+    ) -> StoreResult<AddressHistoryPage> {
+        trace!(
+            "[{0}] retrieving the history of {1} script public keys from DAA score {2}",
+            IDENT,
+            script_public_keys.len(),
+            from_daa_score
+        );
+
+        self.store.get_address_history(script_public_keys, from_daa_score, limit)
+    }
+
+    /// Retrieve the stored sink of the addressindex.
+    fn get_addressindex_sink(&self) -> StoreResult<Hash> {
+        trace!("[{0}] retrieving sink", IDENT);
+
+        self.store.get_sink()
+    }
+
+    /// Checks to see if the [AddressIndex] is sync'd. This is done via comparing the addressindex committed sink and pruning point with those of the consensus database.
+    ///
+    /// **Note:** Due to sync gaps between the addressindex and consensus, this function is only reliable while consensus is not processing new blocks.
+    fn is_synced(&self) -> AddressIndexResult<bool> {
+        trace!("[{0}] checking sync status...", IDENT);
+
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        let res = is_synced(self.store.get_sink(), self.store.get_pruning_point(), &*session)?;
+        trace!("[{0}] sync status is {1}", IDENT, res);
+        Ok(res)
+    }
+
+    /// Updates the [AddressIndex] via the virtual chain changes supplied:
+    /// 1) Drops the history of removed chain blocks and indexes the history of added chain blocks.
+    /// 2) Commits the new sink, and prunes the index if the consensus pruning point moved.
+    fn update_via_virtual_chain_changed(
+        &mut self,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+    ) -> AddressIndexResult<()> {
+        trace!("[{0}] updating...", IDENT);
+        trace!("[{0}] adding {1} chain blocks", IDENT, added_chain_block_hashes.len());
+        trace!("[{0}] removing {1} chain blocks", IDENT, removed_chain_block_hashes.len());
+
+        let consensus = self.consensus_manager.consensus();
+        let session = consensus.unguarded_session_blocking();
+
+        // The chain changes and the new sink are committed at once, the pruning follows in a batch of its own
+        let mut batch = WriteBatch::default();
+        for block_hash in removed_chain_block_hashes.iter().copied() {
+            self.store.remove_chain_block_history(&mut batch, block_hash)?;
+        }
+
+        // The acceptance data is expected along with the notification, but fall back to consensus if it was left out
+        let added_chain_blocks_acceptance_data = if added_chain_blocks_acceptance_data.len() == added_chain_block_hashes.len() {
+            added_chain_blocks_acceptance_data
+        } else {
+            Arc::new(session.get_blocks_acceptance_data(&added_chain_block_hashes)?)
+        };
+        for (block_hash, acceptance_data) in added_chain_block_hashes.iter().copied().zip(added_chain_blocks_acceptance_data.iter()) {
+            self.index_chain_block(&mut batch, &*session, block_hash, acceptance_data)?;
+        }
+
+        // Commit the new sink
+        if let Some(sink) = added_chain_block_hashes.last().copied() {
+            self.store.set_sink(&mut batch, sink)?;
+        }
+        self.store.write_batch(batch)?;
+
+        // Follow the consensus pruning point
+        let pruning_point = session.pruning_point();
+        if self.store.get_pruning_point()? != pruning_point {
+            self.prune(&*session, pruning_point)?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes and reinstates the addressindex database, syncing it from scratch via the consensus database.
+    ///
+    /// **Notes:**
+    /// 1) Only the transactions accepted by the selected chain from the pruning point to the sink are indexed.
+    /// 2) resyncing while consensus notifies of new chain changes, may result in a corrupted db.
+    fn resync(&mut self) -> AddressIndexResult<()> {
+        info!("Resyncing the addressindex...");
+
+        self.store.delete_all()?;
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        let pruning_point = session.pruning_point();
+        let sink = session.get_sink();
+        let chain_path = session.get_virtual_chain_from_block(pruning_point)?;
+        trace!("[{0}] resyncing {1} chain blocks from consensus db", IDENT, chain_path.added.len());
+
+        for chunk in chain_path.added.chunks(RESYNC_CHUNK_SIZE) {
+            let acceptance_data = session.get_blocks_acceptance_data(chunk)?;
+            for (chain_block_hash, acceptance_data) in chunk.iter().copied().zip(acceptance_data.iter()) {
+                let mut batch = WriteBatch::default();
+                self.index_chain_block(&mut batch, &*session, chain_block_hash, acceptance_data)?;
+                self.store.write_batch(batch)?;
+            }
+        }
+
+        // Commit to the remaining stores.
+
+        trace!("[{0}] committing consensus sink {1} and pruning point {2} from consensus db", IDENT, sink, pruning_point);
+        let mut batch = WriteBatch::default();
+        self.store.set_sink(&mut batch, sink)?;
+        self.store.set_pruning_point(&mut batch, pruning_point)?;
+        self.store.write_batch(batch)?;
+
+        Ok(())
+    }
+}
+
+impl Debug for AddressIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AddressIndex").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{api::AddressIndexApi, AddressIndex};
+    use waglayla_consensus::{config::Config, consensus::test_consensus::TestConsensus, params::DEVNET_PARAMS};
+    use waglayla_consensus_core::{api::ConsensusApi, tx::ScriptPublicKeys};
+    use waglayla_consensusmanager::ConsensusManager;
+    use waglayla_database::create_temp_db;
+    use waglayla_database::prelude::ConnBuilder;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_addressindex() {
+        waglayla_core::log::try_init_logger("INFO");
+
+        // Initialize a test consensus with a short selected chain: genesis <- 1 <- 2 <- 3 <- 4
+        let (_addressindex_db_lifetime, addressindex_db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let config = Config::new(DEVNET_PARAMS);
+        let tc = Arc::new(TestConsensus::new(&config));
+        let wait_handles = tc.init();
+        tc.add_utxo_valid_block_with_parents(1.into(), vec![config.genesis.hash], vec![]).await.unwrap();
+        for i in 2..=4u64 {
+            tc.add_utxo_valid_block_with_parents(i.into(), vec![(i - 1).into()], vec![]).await.unwrap();
+        }
+
+        // Sync the addressindex from scratch.
+        let consensus_manager = Arc::new(ConsensusManager::from_consensus(tc.consensus_clone()));
+        let addressindex = AddressIndex::new(consensus_manager, addressindex_db).unwrap();
+        assert!(addressindex.read().is_synced().expect("expected bool"));
+        assert_eq!(addressindex.read().get_addressindex_sink().expect("expected sink"), 4.into());
+
+        // All test blocks pay the same miner script, and the coinbases of blocks 1 to 3 are accepted by their chain children.
+        let coinbase = tc.get_block(2.into()).unwrap().transactions[0].clone();
+        let script_public_key = coinbase.outputs[0].script_public_key.clone();
+        let script_public_keys = ScriptPublicKeys::from_iter([script_public_key.clone()]);
+        let page = addressindex.read().get_address_history(script_public_keys.clone(), 0, 100).unwrap();
+        assert_eq!(page.next_daa_score, None);
+        let transaction = page
+            .transactions
+            .iter()
+            .find(|(_, transaction)| transaction.transaction_id == coinbase.id())
+            .map(|(_, transaction)| transaction.clone())
+            .expect("expected the coinbase to be indexed");
+        assert_eq!(transaction.accepting_block_hash, 3.into());
+        assert_eq!(transaction.received.len(), 1);
+        assert!(transaction.spent.is_empty());
+
+        // Pages are ordered by accepting DAA score and chained through `next_daa_score`.
+        let first_page = addressindex.read().get_address_history(script_public_keys.clone(), 0, 1).unwrap();
+        assert_eq!(first_page.transactions.len(), 1);
+        let next_daa_score = first_page.next_daa_score.expect("expected more pages");
+        let second_page = addressindex.read().get_address_history(script_public_keys.clone(), next_daa_score, 100).unwrap();
+        assert_eq!(first_page.transactions.len() + second_page.transactions.len(), page.transactions.len());
+        assert!(second_page.transactions.iter().all(|(_, transaction)| transaction.accepting_block_daa_score >= next_daa_score));
+
+        // Removing block 3 from the selected chain drops the history it accepted.
+        addressindex.write().update_via_virtual_chain_changed(Arc::new(vec![]), Arc::new(vec![3.into()]), Arc::new(vec![])).unwrap();
+        let page = addressindex.read().get_address_history(script_public_keys, 0, 100).unwrap();
+        assert!(page.transactions.iter().all(|(_, transaction)| transaction.transaction_id != coinbase.id()));
+
+        tc.shutdown(wait_handles);
+    }
+}
@@ -0,0 +1,138 @@
+use std::{mem::size_of, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use waglayla_consensus_core::tx::{ScriptPublicKey, TransactionId};
+use waglayla_database::{
+    prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, DirectDbWriter, StoreError, StoreResult, WriteBatch, DB},
+    registry::DatabaseStorePrefixes,
+};
+use waglayla_hashes::{Hash, HASH_SIZE};
+use waglayla_utils::mem_size::MemSizeEstimator;
+
+/// The address history entries a chain block contributes to the addressindex, kept so they
+/// can be removed when the block leaves the selected chain or falls below the pruning point.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AddressIndexBlock {
+    pub daa_score: u64,
+    pub entries: Vec<(ScriptPublicKey, TransactionId)>,
+}
+
+impl AddressIndexBlock {
+    pub fn new(daa_score: u64, entries: Vec<(ScriptPublicKey, TransactionId)>) -> Self {
+        Self { daa_score, entries }
+    }
+}
+
+impl MemSizeEstimator for AddressIndexBlock {}
+
+/// Size of the [BlockByDaaScoreKey] in bytes.
+pub const BLOCK_BY_DAA_SCORE_KEY_SIZE: usize = size_of::<u64>() + HASH_SIZE;
+
+/// Key ordering indexed blocks by DAA score, used for pruning the index from the bottom up.
+/// Consists of 8 bytes of big endian DAA score, followed by 32 bytes of block [Hash].
+#[derive(Eq, Hash, PartialEq, Debug, Copy, Clone)]
+struct BlockByDaaScoreKey([u8; BLOCK_BY_DAA_SCORE_KEY_SIZE]);
+
+impl BlockByDaaScoreKey {
+    fn new(daa_score: u64, block_hash: Hash) -> Self {
+        let mut bytes = [0; BLOCK_BY_DAA_SCORE_KEY_SIZE];
+        bytes[..size_of::<u64>()].copy_from_slice(&daa_score.to_be_bytes());
+        bytes[size_of::<u64>()..].copy_from_slice(&block_hash.as_bytes());
+        Self(bytes)
+    }
+
+    fn daa_score(&self) -> u64 {
+        u64::from_be_bytes(self.0[..size_of::<u64>()].try_into().unwrap())
+    }
+
+    fn block_hash(&self) -> Hash {
+        Hash::from_slice(&self.0[size_of::<u64>()..])
+    }
+}
+
+impl AsRef<[u8]> for BlockByDaaScoreKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Reader API for `AddressIndexBlocksStore`.
+pub trait AddressIndexBlocksStoreReader {
+    fn get(&self, block_hash: Hash) -> StoreResult<Option<Arc<AddressIndexBlock>>>;
+
+    /// Returns the hashes of all indexed blocks with a DAA score lower than `daa_score`.
+    fn get_blocks_below_daa_score(&self, daa_score: u64) -> StoreResult<Vec<Hash>>;
+}
+
+pub trait AddressIndexBlocksStore: AddressIndexBlocksStoreReader {
+    fn set(&mut self, batch: &mut WriteBatch, block_hash: Hash, block: Arc<AddressIndexBlock>) -> StoreResult<()>;
+    fn delete(&mut self, batch: &mut WriteBatch, block_hash: Hash) -> StoreResult<()>;
+    fn delete_all(&mut self) -> StoreResult<()>;
+}
+
+/// A DB + cache implementation of `AddressIndexBlocksStore` trait
+#[derive(Clone)]
+pub struct DbAddressIndexBlocksStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<Hash, Arc<AddressIndexBlock>>,
+    by_daa_score_access: CachedDbAccess<BlockByDaaScoreKey, Hash>,
+}
+
+impl DbAddressIndexBlocksStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self {
+            db: Arc::clone(&db),
+            access: CachedDbAccess::new(db.clone(), cache_policy, DatabaseStorePrefixes::AddressIndexBlocks.into()),
+            by_daa_score_access: CachedDbAccess::new(
+                db,
+                CachePolicy::Empty,
+                DatabaseStorePrefixes::AddressIndexBlocksByDaaScore.into(),
+            ),
+        }
+    }
+}
+
+impl AddressIndexBlocksStoreReader for DbAddressIndexBlocksStore {
+    fn get(&self, block_hash: Hash) -> StoreResult<Option<Arc<AddressIndexBlock>>> {
+        match self.access.read(block_hash) {
+            Ok(block) => Ok(Some(block)),
+            Err(StoreError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn get_blocks_below_daa_score(&self, daa_score: u64) -> StoreResult<Vec<Hash>> {
+        let mut block_hashes = Vec::new();
+        // Keys are ordered by big endian DAA score, so we can stop at the first key reaching `daa_score`
+        for res in self.by_daa_score_access.iterator() {
+            let (key, _) = res.map_err(|err| StoreError::DataInconsistency(err.to_string()))?;
+            let key = BlockByDaaScoreKey(<[u8; BLOCK_BY_DAA_SCORE_KEY_SIZE]>::try_from(&key[..]).unwrap());
+            if key.daa_score() >= daa_score {
+                break;
+            }
+            block_hashes.push(key.block_hash());
+        }
+        Ok(block_hashes)
+    }
+}
+
+impl AddressIndexBlocksStore for DbAddressIndexBlocksStore {
+    fn set(&mut self, batch: &mut WriteBatch, block_hash: Hash, block: Arc<AddressIndexBlock>) -> StoreResult<()> {
+        self.by_daa_score_access.write(BatchDbWriter::new(batch), BlockByDaaScoreKey::new(block.daa_score, block_hash), block_hash)?;
+        self.access.write(BatchDbWriter::new(batch), block_hash, block)
+    }
+
+    fn delete(&mut self, batch: &mut WriteBatch, block_hash: Hash) -> StoreResult<()> {
+        if let Some(block) = self.get(block_hash)? {
+            self.by_daa_score_access.delete(BatchDbWriter::new(batch), BlockByDaaScoreKey::new(block.daa_score, block_hash))?;
+            self.access.delete(BatchDbWriter::new(batch), block_hash)?;
+        }
+        Ok(())
+    }
+
+    /// Removes all entries in the cache and db, besides prefixes themselves.
+    fn delete_all(&mut self) -> StoreResult<()> {
+        self.by_daa_score_access.delete_all(DirectDbWriter::new(&self.db))?;
+        self.access.delete_all(DirectDbWriter::new(&self.db))
+    }
+}
@@ -0,0 +1,153 @@
+use std::{mem::size_of, sync::Arc};
+
+use waglayla_consensus_core::tx::{ScriptPublicKey, ScriptPublicKeyVersion, TransactionId};
+use waglayla_database::{
+    prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, DirectDbWriter, StoreError, StoreResult, WriteBatch, DB},
+    registry::DatabaseStorePrefixes,
+};
+
+use crate::model::AddressTransaction;
+
+/// [`ScriptPublicKeyBucket`].
+/// Consists of 2 bytes of little endian [ScriptPublicKeyVersion] bytes, followed by 8 bytes of little endian
+/// script length and a variable size of script bytes. Same layout as the bucket of the utxoindex.
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+struct ScriptPublicKeyBucket(Vec<u8>);
+
+impl From<&ScriptPublicKey> for ScriptPublicKeyBucket {
+    fn from(script_public_key: &ScriptPublicKey) -> Self {
+        let mut bytes: Vec<u8> =
+            Vec::with_capacity(size_of::<ScriptPublicKeyVersion>() + size_of::<u64>() + script_public_key.script().len());
+        bytes.extend_from_slice(&script_public_key.version().to_le_bytes());
+        bytes.extend_from_slice(&(script_public_key.script().len() as u64).to_le_bytes());
+        bytes.extend_from_slice(script_public_key.script());
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for ScriptPublicKeyBucket {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+/// Full [AddressTransaction] access key.
+/// Consists of variable amount of bytes of [ScriptPublicKeyBucket], followed by 8 bytes of big endian accepting
+/// DAA score and 32 bytes of [TransactionId], so the history of a script public key is ordered by acceptance.
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+struct AddressHistoryKey(Arc<Vec<u8>>);
+
+impl AddressHistoryKey {
+    fn new(script_public_key: &ScriptPublicKey, accepting_block_daa_score: u64, transaction_id: TransactionId) -> Self {
+        let mut key = Self::seek_key(script_public_key, accepting_block_daa_score);
+        Arc::make_mut(&mut key.0).extend_from_slice(&transaction_id.as_bytes());
+        key
+    }
+
+    /// A partial key pointing at the first transaction accepted at `accepting_block_daa_score` or later.
+    fn seek_key(script_public_key: &ScriptPublicKey, accepting_block_daa_score: u64) -> Self {
+        let bucket = ScriptPublicKeyBucket::from(script_public_key);
+        let mut bytes = Vec::with_capacity(bucket.as_ref().len() + size_of::<u64>() + waglayla_hashes::HASH_SIZE);
+        bytes.extend_from_slice(bucket.as_ref());
+        bytes.extend_from_slice(&accepting_block_daa_score.to_be_bytes());
+        Self(Arc::new(bytes))
+    }
+}
+
+impl AsRef<[u8]> for AddressHistoryKey {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+/// Reader API for `AddressHistoryStore`.
+pub trait AddressHistoryStoreReader {
+    /// Returns up to `limit` transactions of `script_public_key` accepted with a DAA score of at least `from_daa_score`,
+    /// along with a flag telling whether the history of the script public key was exhausted.
+    ///
+    /// The transactions accepted at the DAA score of the last returned transaction are always returned in full,
+    /// so the result may exceed `limit`.
+    fn get_history(
+        &self,
+        script_public_key: &ScriptPublicKey,
+        from_daa_score: u64,
+        limit: usize,
+    ) -> StoreResult<(Vec<AddressTransaction>, bool)>;
+}
+
+pub trait AddressHistoryStore: AddressHistoryStoreReader {
+    fn set(&mut self, batch: &mut WriteBatch, script_public_key: &ScriptPublicKey, transaction: AddressTransaction)
+        -> StoreResult<()>;
+    fn delete(
+        &mut self,
+        batch: &mut WriteBatch,
+        script_public_key: &ScriptPublicKey,
+        accepting_block_daa_score: u64,
+        transaction_id: TransactionId,
+    ) -> StoreResult<()>;
+    fn delete_all(&mut self) -> StoreResult<()>;
+}
+
+/// A DB + cache implementation of `AddressHistoryStore` trait
+#[derive(Clone)]
+pub struct DbAddressHistoryStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<AddressHistoryKey, AddressTransaction>,
+}
+
+impl DbAddressHistoryStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::AddressIndexHistory.into()) }
+    }
+}
+
+impl AddressHistoryStoreReader for DbAddressHistoryStore {
+    fn get_history(
+        &self,
+        script_public_key: &ScriptPublicKey,
+        from_daa_score: u64,
+        limit: usize,
+    ) -> StoreResult<(Vec<AddressTransaction>, bool)> {
+        let bucket = ScriptPublicKeyBucket::from(script_public_key);
+        let seek_key = AddressHistoryKey::seek_key(script_public_key, from_daa_score);
+        let mut transactions: Vec<AddressTransaction> = Vec::new();
+        for res in self.access.seek_iterator(Some(bucket.as_ref()), Some(seek_key), usize::MAX, false) {
+            let (_, transaction) = res.map_err(|err| StoreError::DataInconsistency(err.to_string()))?;
+            if transactions.len() >= limit
+                && transactions.last().is_some_and(|last| last.accepting_block_daa_score != transaction.accepting_block_daa_score)
+            {
+                return Ok((transactions, false));
+            }
+            transactions.push(transaction);
+        }
+        Ok((transactions, true))
+    }
+}
+
+impl AddressHistoryStore for DbAddressHistoryStore {
+    fn set(
+        &mut self,
+        batch: &mut WriteBatch,
+        script_public_key: &ScriptPublicKey,
+        transaction: AddressTransaction,
+    ) -> StoreResult<()> {
+        let key = AddressHistoryKey::new(script_public_key, transaction.accepting_block_daa_score, transaction.transaction_id);
+        self.access.write(BatchDbWriter::new(batch), key, transaction)
+    }
+
+    fn delete(
+        &mut self,
+        batch: &mut WriteBatch,
+        script_public_key: &ScriptPublicKey,
+        accepting_block_daa_score: u64,
+        transaction_id: TransactionId,
+    ) -> StoreResult<()> {
+        self.access
+            .delete(BatchDbWriter::new(batch), AddressHistoryKey::new(script_public_key, accepting_block_daa_score, transaction_id))
+    }
+
+    /// Removes all entries in the cache and db, besides prefixes themselves.
+    fn delete_all(&mut self) -> StoreResult<()> {
+        self.access.delete_all(DirectDbWriter::new(&self.db))
+    }
+}
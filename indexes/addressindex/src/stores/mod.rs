@@ -0,0 +1,3 @@
+mod blocks;
+mod history;
+pub mod store_manager;
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use waglayla_consensus_core::tx::{ScriptPublicKey, ScriptPublicKeys};
+use waglayla_core::trace;
+use waglayla_database::{
+    prelude::{CachePolicy, StoreResult, WriteBatch, DB},
+    registry::DatabaseStorePrefixes,
+};
+use waglayla_hashes::Hash;
+use waglayla_index_core::sync_points::{DbIndexSyncPointStore, IndexSyncPointStore, IndexSyncPointStoreReader};
+
+use crate::{
+    model::{AddressHistoryPage, AddressTransaction},
+    stores::{
+        blocks::{AddressIndexBlock, AddressIndexBlocksStore, AddressIndexBlocksStoreReader, DbAddressIndexBlocksStore},
+        history::{AddressHistoryStore, AddressHistoryStoreReader, DbAddressHistoryStore},
+    },
+    IDENT,
+};
+
+/// Gathers the addressindex stores.
+///
+/// All mutating methods write into a [`WriteBatch`] which the caller commits with [`Store::write_batch`] once the
+/// whole update is gathered, so that a crash never leaves an update partly written.
+#[derive(Clone)]
+pub struct Store {
+    db: Arc<DB>,
+    sink_store: DbIndexSyncPointStore,
+    pruning_point_store: DbIndexSyncPointStore,
+    blocks_store: DbAddressIndexBlocksStore,
+    history_store: DbAddressHistoryStore,
+}
+
+impl Store {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self {
+            db: db.clone(),
+            sink_store: DbIndexSyncPointStore::new(db.clone(), DatabaseStorePrefixes::AddressIndexSink),
+            pruning_point_store: DbIndexSyncPointStore::new(db.clone(), DatabaseStorePrefixes::AddressIndexPruningPoint),
+            blocks_store: DbAddressIndexBlocksStore::new(db.clone(), CachePolicy::Empty),
+            history_store: DbAddressHistoryStore::new(db, CachePolicy::Empty),
+        }
+    }
+
+    /// Atomically commits all the writes gathered in `batch`
+    pub fn write_batch(&self, batch: WriteBatch) -> StoreResult<()> {
+        Ok(self.db.write(batch)?)
+    }
+
+    /// Merges the histories of the queried script public keys into a single page ordered by accepting DAA score.
+    pub fn get_address_history(
+        &self,
+        script_public_keys: ScriptPublicKeys,
+        from_daa_score: u64,
+        limit: usize,
+    ) -> StoreResult<AddressHistoryPage> {
+        let limit = limit.max(1);
+        let mut transactions = Vec::new();
+        // Highest DAA score up to which the merged history is known to be complete
+        let mut complete_up_to: Option<u64> = None;
+        for script_public_key in script_public_keys.into_iter() {
+            let (history, exhausted) = self.history_store.get_history(&script_public_key, from_daa_score, limit)?;
+            if !exhausted {
+                let last_daa_score = history.last().map_or(from_daa_score, |transaction| transaction.accepting_block_daa_score);
+                complete_up_to = Some(complete_up_to.map_or(last_daa_score, |daa_score| daa_score.min(last_daa_score)));
+            }
+            transactions.extend(history.into_iter().map(|transaction| (script_public_key.clone(), transaction)));
+        }
+        transactions.sort_by_key(|(_, transaction)| (transaction.accepting_block_daa_score, transaction.transaction_id));
+
+        // A script public key which was not exhausted returned all its transactions up to the DAA score it stopped at
+        let mut next_daa_score = None;
+        if let Some(daa_score) = complete_up_to {
+            transactions.truncate(transactions.partition_point(|(_, transaction)| transaction.accepting_block_daa_score <= daa_score));
+            next_daa_score = Some(daa_score + 1);
+        }
+
+        // Cut the page at the DAA score of the `limit`-th transaction, never splitting the transactions of a chain block
+        if transactions.len() > limit {
+            let last_daa_score = transactions[limit - 1].1.accepting_block_daa_score;
+            let len = transactions.partition_point(|(_, transaction)| transaction.accepting_block_daa_score <= last_daa_score);
+            if len < transactions.len() {
+                transactions.truncate(len);
+                next_daa_score = Some(last_daa_score + 1);
+            }
+        }
+        Ok(AddressHistoryPage { transactions, next_daa_score })
+    }
+
+    /// Indexes the address history entries of the transactions accepted by a chain block.
+    pub fn add_chain_block_history(
+        &mut self,
+        batch: &mut WriteBatch,
+        block_hash: Hash,
+        daa_score: u64,
+        history: Vec<(ScriptPublicKey, AddressTransaction)>,
+    ) -> StoreResult<()> {
+        let mut entries = Vec::with_capacity(history.len());
+        for (script_public_key, transaction) in history.into_iter() {
+            entries.push((script_public_key.clone(), transaction.transaction_id));
+            self.history_store.set(batch, &script_public_key, transaction)?;
+        }
+        self.blocks_store.set(batch, block_hash, Arc::new(AddressIndexBlock::new(daa_score, entries)))
+    }
+
+    /// Drops the address history entries of a block which was removed from the selected chain.
+    pub fn remove_chain_block_history(&mut self, batch: &mut WriteBatch, block_hash: Hash) -> StoreResult<()> {
+        if let Some(block) = self.blocks_store.get(block_hash)? {
+            for (script_public_key, transaction_id) in block.entries.iter() {
+                self.history_store.delete(batch, script_public_key, block.daa_score, *transaction_id)?;
+            }
+            self.blocks_store.delete(batch, block_hash)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the history of all chain blocks with a DAA score lower than `daa_score`, returning the number of pruned blocks.
+    pub fn prune_below_daa_score(&mut self, batch: &mut WriteBatch, daa_score: u64) -> StoreResult<usize> {
+        let block_hashes = self.blocks_store.get_blocks_below_daa_score(daa_score)?;
+        for block_hash in block_hashes.iter().copied() {
+            self.remove_chain_block_history(batch, block_hash)?;
+        }
+        Ok(block_hashes.len())
+    }
+
+    pub fn get_sink(&self) -> StoreResult<Hash> {
+        self.sink_store.get()
+    }
+
+    pub fn set_sink(&mut self, batch: &mut WriteBatch, sink: Hash) -> StoreResult<()> {
+        self.sink_store.set_batch(batch, sink)
+    }
+
+    pub fn get_pruning_point(&self) -> StoreResult<Hash> {
+        self.pruning_point_store.get()
+    }
+
+    pub fn set_pruning_point(&mut self, batch: &mut WriteBatch, pruning_point: Hash) -> StoreResult<()> {
+        self.pruning_point_store.set_batch(batch, pruning_point)
+    }
+
+    /// Resets the addressindex database:
+    pub fn delete_all(&mut self) -> StoreResult<()> {
+        trace!("[{0}] attempting to clear addressindex database...", IDENT);
+
+        // Clear all
+        self.sink_store.remove()?;
+        self.pruning_point_store.remove()?;
+        self.blocks_store.delete_all()?;
+        self.history_store.delete_all()?;
+
+        trace!("[{0}] clearing addressindex database - success!", IDENT);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use waglayla_consensusmanager::spawn_blocking;
+use waglayla_database::prelude::StoreResult;
+use waglayla_hashes::Hash;
+use parking_lot::RwLock;
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{errors::ChainIndexResult, model::ChainBlock};
+
+///Chainindex API targeted at retrieval calls.
+pub trait ChainIndexApi: Send + Sync + Debug {
+    /// Retrieve the first chain block with a DAA score of at least `daa_score`, that is the chain block
+    /// whose mergeset brought the DAA score of the selected chain to `daa_score` or beyond.
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_chain_block_by_daa_score(&self, daa_score: u64) -> StoreResult<Option<ChainBlock>>;
+
+    /// Retrieve the first chain block, in selected chain order, with a timestamp of at least `timestamp`.
+    ///
+    /// Note:
+    /// 1) Use a read lock when accessing this method
+    /// 2) block timestamps are only roughly ordered along the selected chain, so a later chain block
+    ///    may have a lower timestamp than the returned one.
+    fn get_chain_block_by_timestamp(&self, timestamp: u64) -> StoreResult<Option<ChainBlock>>;
+
+    /// Retrieve up to `limit` chain blocks with a DAA score within `from_daa_score..=to_daa_score`, ordered by DAA score.
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_chain_blocks(&self, from_daa_score: u64, to_daa_score: u64, limit: usize) -> StoreResult<Vec<ChainBlock>>;
+
+    /// Retrieve the sink the chainindex was last synced with (used for testing purposes).
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_chainindex_sink(&self) -> StoreResult<Hash>;
+
+    /// Checks if the chainindex's db is synced with consensus.
+    ///
+    /// Note:
+    /// 1) Use a read lock when accessing this method
+    /// 2) due to potential sync-gaps is_synced is unreliable while consensus is actively resolving virtual states.
+    fn is_synced(&self) -> ChainIndexResult<bool>;
+
+    /// Update the chain blocks with the given virtual chain changes,
+    /// and drop the chain blocks below the pruning point.
+    ///
+    /// Note: Use a write lock when accessing this method
+    fn update_via_virtual_chain_changed(
+        &mut self,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+    ) -> ChainIndexResult<()>;
+
+    /// Resync the chainindex from the consensus db
+    ///
+    /// Note: Use a write lock when accessing this method
+    fn resync(&mut self) -> ChainIndexResult<()>;
+}
+
+/// Async proxy for the chain block index
+#[derive(Debug, Clone)]
+pub struct ChainIndexProxy {
+    inner: Arc<RwLock<dyn ChainIndexApi>>,
+}
+
+impl ChainIndexProxy {
+    pub fn new(inner: Arc<RwLock<dyn ChainIndexApi>>) -> Self {
+        Self { inner }
+    }
+
+    pub async fn get_chain_block_by_daa_score(self, daa_score: u64) -> StoreResult<Option<ChainBlock>> {
+        spawn_blocking(move || self.inner.read().get_chain_block_by_daa_score(daa_score)).await.unwrap()
+    }
+
+    pub async fn get_chain_block_by_timestamp(self, timestamp: u64) -> StoreResult<Option<ChainBlock>> {
+        spawn_blocking(move || self.inner.read().get_chain_block_by_timestamp(timestamp)).await.unwrap()
+    }
+
+    pub async fn get_chain_blocks(self, from_daa_score: u64, to_daa_score: u64, limit: usize) -> StoreResult<Vec<ChainBlock>> {
+        spawn_blocking(move || self.inner.read().get_chain_blocks(from_daa_score, to_daa_score, limit)).await.unwrap()
+    }
+
+    pub async fn update_via_virtual_chain_changed(
+        self,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+    ) -> ChainIndexResult<()> {
+        spawn_blocking(move || {
+            self.inner.write().update_via_virtual_chain_changed(added_chain_block_hashes, removed_chain_block_hashes)
+        })
+        .await
+        .unwrap()
+    }
+}
@@ -0,0 +1,3 @@
+use crate::IDENT;
+
+waglayla_index_core::index_errors!(ChainIndex, ChainIndexError, ChainIndexResult, IDENT);
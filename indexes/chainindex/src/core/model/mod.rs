@@ -0,0 +1,15 @@
+use waglayla_hashes::Hash;
+
+/// A block of the selected chain, along with the DAA score and timestamp it is indexed by.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub struct ChainBlock {
+    pub hash: Hash,
+    pub daa_score: u64,
+    pub timestamp: u64,
+}
+
+impl ChainBlock {
+    pub fn new(hash: Hash, daa_score: u64, timestamp: u64) -> Self {
+        Self { hash, daa_score, timestamp }
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{api::ChainIndexApi, errors::ChainIndexResult, model::ChainBlock, stores::store_manager::Store, IDENT};
+use waglayla_consensus_core::api::ConsensusApi;
+use waglayla_consensusmanager::ConsensusManager;
+use waglayla_core::{info, trace};
+use waglayla_database::prelude::{StoreResult, WriteBatch, DB};
+use waglayla_hashes::Hash;
+use waglayla_index_core::{reset::IndexConsensusResetHandler, sync_points::is_synced};
+use parking_lot::RwLock;
+use std::{fmt::Debug, sync::Arc};
+
+/// ChainIndex indexes the blocks of the selected chain by DAA score and by timestamp.
+/// Only the chain from the consensus pruning point is kept, older entries are dropped as the pruning point advances.
+/// Note: The ChainIndex struct by itself is not thread safe, only correct usage of the supplied RwLock via `new` makes it so.
+/// please follow guidelines found in the comments under `chainindex::core::api::ChainIndexApi` for proper thread safety.
+pub struct ChainIndex {
+    consensus_manager: Arc<ConsensusManager>,
+    store: Store,
+}
+
+impl ChainIndex {
+    /// Creates a new [`ChainIndex`] within a [`RwLock`]
+    pub fn new(consensus_manager: Arc<ConsensusManager>, db: Arc<DB>) -> ChainIndexResult<Arc<RwLock<Self>>> {
+        let mut chainindex = Self { consensus_manager: consensus_manager.clone(), store: Store::new(db) };
+        if !chainindex.is_synced()? {
+            chainindex.resync()?;
+        }
+        let chainindex = Arc::new(RwLock::new(chainindex));
+        consensus_manager
+            .register_consensus_reset_handler(Arc::new(IndexConsensusResetHandler::new(Arc::downgrade(&chainindex), Self::resync)));
+        Ok(chainindex)
+    }
+
+    fn get_chain_block(consensus: &dyn ConsensusApi, block_hash: Hash) -> ChainIndexResult<ChainBlock> {
+        let header = consensus.get_header(block_hash)?;
+        Ok(ChainBlock::new(block_hash, header.daa_score, header.timestamp))
+    }
+
+    /// Drops the chain blocks below `pruning_point` and records it as the new chainindex pruning point.
+    fn prune(&mut self, consensus: &dyn ConsensusApi, pruning_point: Hash) -> ChainIndexResult<()> {
+        let pruning_point_daa_score = consensus.get_header(pruning_point)?.daa_score;
+        let mut batch = WriteBatch::default();
+        let pruned = self.store.prune_below_daa_score(&mut batch, pruning_point_daa_score)?;
+        self.store.set_pruning_point(&mut batch, pruning_point)?;
+        self.store.write_batch(batch)?;
+        trace!("[{0}] pruned {1} chain blocks below pruning point {2}", IDENT, pruned, pruning_point);
+        Ok(())
+    }
+}
+
+impl ChainIndexApi for ChainIndex {
+    /// Retrieve the first chain block reaching `daa_score` from the chainindex db.
+    fn get_chain_block_by_daa_score(&self, daa_score: u64) -> StoreResult<Option<ChainBlock>> {
+        trace!("[{0}] retrieving the chain block at DAA score {1}", IDENT, daa_score);
+
+        self.store.get_chain_block_by_daa_score(daa_score)
+    }
+
+    /// Retrieve the first chain block reaching `timestamp` from the chainindex db.
+    fn get_chain_block_by_timestamp(&self, timestamp: u64) -> StoreResult<Option<ChainBlock>> {
+        trace!("[{0}] retrieving the chain block at timestamp {1}", IDENT, timestamp);
+
+        self.store.get_chain_block_by_timestamp(timestamp)
+    }
+
+    /// Retrieve a DAA score range of chain blocks from the chainindex db.
+    fn get_chain_blocks(&self, from_daa_score: u64, to_daa_score: u64, limit: usize) -> StoreResult<Vec<ChainBlock>> {
+        trace!("[{0}] retrieving up to {1} chain blocks from DAA score {2} to {3}", IDENT, limit, from_daa_score, to_daa_score);
+
+        self.store.get_chain_blocks(from_daa_score, to_daa_score, limit)
+    }
+
+    /// Retrieve the stored sink of the chainindex.
+    fn get_chainindex_sink(&self) -> StoreResult<Hash> {
+        trace!("[{0}] retrieving sink", IDENT);
+
+        self.store.get_sink()
+    }
+
+    /// Checks to see if the [ChainIndex] is sync'd. This is done via comparing the chainindex committed sink and pruning point with those of the consensus database.
+    ///
+    /// **Note:** Due to sync gaps between the chainindex and consensus, this function is only reliable while consensus is not processing new blocks.
+    fn is_synced(&self) -> ChainIndexResult<bool> {
+        trace!("[{0}] checking sync status...", IDENT);
+
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        let res = is_synced(self.store.get_sink(), self.store.get_pruning_point(), &*session)?;
+        trace!("[{0}] sync status is {1}", IDENT, res);
+        Ok(res)
+    }
+
+    /// Updates the [ChainIndex] via the virtual chain changes supplied:
+    /// 1) Drops the removed chain blocks and indexes the added chain blocks.
+    /// 2) Commits the new sink, and prunes the index if the consensus pruning point moved.
+    fn update_via_virtual_chain_changed(
+        &mut self,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+    ) -> ChainIndexResult<()> {
+        trace!("[{0}] updating...", IDENT);
+        trace!("[{0}] adding {1} chain blocks", IDENT, added_chain_block_hashes.len());
+        trace!("[{0}] removing {1} chain blocks", IDENT, removed_chain_block_hashes.len());
+
+        let consensus = self.consensus_manager.consensus();
+        let session = consensus.unguarded_session_blocking();
+
+        // The chain changes and the new sink are committed at once, the pruning follows in a batch of its own
+        let mut batch = WriteBatch::default();
+        for block_hash in removed_chain_block_hashes.iter().copied() {
+            self.store.remove_chain_block(&mut batch, Self::get_chain_block(&*session, block_hash)?)?;
+        }
+        // The added chain extends the selected parent of its first block, which remains indexed
+        let mut max_timestamp = match added_chain_block_hashes.first().copied() {
+            Some(block_hash) => {
+                let selected_parent = Self::get_chain_block(&*session, session.get_ghostdag_data(block_hash)?.selected_parent)?;
+                self.store.get_chain_max_timestamp(selected_parent)?.unwrap_or_default()
+            }
+            None => 0,
+        };
+        for block_hash in added_chain_block_hashes.iter().copied() {
+            let chain_block = Self::get_chain_block(&*session, block_hash)?;
+            max_timestamp = max_timestamp.max(chain_block.timestamp);
+            self.store.add_chain_block(&mut batch, chain_block, max_timestamp)?;
+        }
+
+        // Commit the new sink
+        if let Some(sink) = added_chain_block_hashes.last().copied() {
+            self.store.set_sink(&mut batch, sink)?;
+        }
+        self.store.write_batch(batch)?;
+
+        // Follow the consensus pruning point
+        let pruning_point = session.pruning_point();
+        if self.store.get_pruning_point()? != pruning_point {
+            self.prune(&*session, pruning_point)?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes and reinstates the chainindex database, syncing it from scratch via the consensus database.
+    ///
+    /// **Notes:**
+    /// 1) Only the selected chain from the pruning point to the sink is indexed.
+    /// 2) resyncing while consensus notifies of new chain changes, may result in a corrupted db.
+    fn resync(&mut self) -> ChainIndexResult<()> {
+        info!("Resyncing the chainindex...");
+
+        self.store.delete_all()?;
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        let pruning_point = session.pruning_point();
+        let sink = session.get_sink();
+        let chain_path = session.get_virtual_chain_from_block(pruning_point)?;
+        trace!("[{0}] resyncing {1} chain blocks from consensus db", IDENT, chain_path.added.len() + 1);
+
+        let mut max_timestamp = 0;
+        for block_hash in std::iter::once(pruning_point).chain(chain_path.added.iter().copied()) {
+            let chain_block = Self::get_chain_block(&*session, block_hash)?;
+            max_timestamp = max_timestamp.max(chain_block.timestamp);
+            let mut batch = WriteBatch::default();
+            self.store.add_chain_block(&mut batch, chain_block, max_timestamp)?;
+            self.store.write_batch(batch)?;
+        }
+
+        // Commit to the remaining stores.
+
+        trace!("[{0}] committing consensus sink {1} and pruning point {2} from consensus db", IDENT, sink, pruning_point);
+        let mut batch = WriteBatch::default();
+        self.store.set_sink(&mut batch, sink)?;
+        self.store.set_pruning_point(&mut batch, pruning_point)?;
+        self.store.write_batch(batch)?;
+
+        Ok(())
+    }
+}
+
+impl Debug for ChainIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChainIndex").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{api::ChainIndexApi, model::ChainBlock, ChainIndex};
+    use waglayla_consensus::{config::Config, consensus::test_consensus::TestConsensus, params::DEVNET_PARAMS};
+    use waglayla_consensus_core::api::ConsensusApi;
+    use waglayla_consensusmanager::ConsensusManager;
+    use waglayla_database::create_temp_db;
+    use waglayla_database::prelude::ConnBuilder;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_chainindex() {
+        waglayla_core::log::try_init_logger("INFO");
+
+        // Initialize a test consensus with a short selected chain: genesis <- 1 <- 2 <- 3 <- 4
+        let (_chainindex_db_lifetime, chainindex_db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let config = Config::new(DEVNET_PARAMS);
+        let tc = Arc::new(TestConsensus::new(&config));
+        let wait_handles = tc.init();
+        tc.add_utxo_valid_block_with_parents(1.into(), vec![config.genesis.hash], vec![]).await.unwrap();
+        for i in 2..=4u64 {
+            tc.add_utxo_valid_block_with_parents(i.into(), vec![(i - 1).into()], vec![]).await.unwrap();
+        }
+
+        // Sync the chainindex from scratch.
+        let consensus_manager = Arc::new(ConsensusManager::from_consensus(tc.consensus_clone()));
+        let chainindex = ChainIndex::new(consensus_manager, chainindex_db).unwrap();
+        assert!(chainindex.read().is_synced().expect("expected bool"));
+        assert_eq!(chainindex.read().get_chainindex_sink().expect("expected sink"), 4.into());
+
+        let chain = [config.genesis.hash, 1.into(), 2.into(), 3.into(), 4.into()]
+            .into_iter()
+            .map(|hash| {
+                let header = tc.get_header(hash).unwrap();
+                ChainBlock::new(hash, header.daa_score, header.timestamp)
+            })
+            .collect::<Vec<_>>();
+
+        // DAA score lookups resolve to the first chain block reaching the DAA score.
+        for chain_block in chain.iter() {
+            assert_eq!(chainindex.read().get_chain_block_by_daa_score(chain_block.daa_score).unwrap(), Some(*chain_block));
+        }
+        assert_eq!(chainindex.read().get_chain_block_by_daa_score(chain[4].daa_score + 1).unwrap(), None);
+
+        // Timestamp lookups resolve to the first chain block reaching the requested timestamp.
+        let expected = chain.iter().find(|x| x.timestamp >= chain[3].timestamp).copied();
+        assert_eq!(chainindex.read().get_chain_block_by_timestamp(chain[3].timestamp).unwrap(), expected);
+        let last_timestamp = chain.iter().map(|x| x.timestamp).max().unwrap();
+        assert_eq!(chainindex.read().get_chain_block_by_timestamp(last_timestamp + 1).unwrap(), None);
+
+        // Range queries are inclusive, ordered by DAA score and bounded by the limit.
+        assert_eq!(chainindex.read().get_chain_blocks(chain[1].daa_score, chain[3].daa_score, 100).unwrap(), chain[1..=3].to_vec());
+        assert_eq!(chainindex.read().get_chain_blocks(0, u64::MAX, 2).unwrap(), chain[..2].to_vec());
+
+        // Removing block 4 from the selected chain drops it from the index.
+        chainindex.write().update_via_virtual_chain_changed(Arc::new(vec![]), Arc::new(vec![4.into()])).unwrap();
+        assert_eq!(chainindex.read().get_chain_block_by_daa_score(chain[4].daa_score).unwrap(), None);
+        assert_eq!(chainindex.read().get_chain_blocks(0, u64::MAX, 100).unwrap(), chain[..4].to_vec());
+
+        tc.shutdown(wait_handles);
+    }
+}
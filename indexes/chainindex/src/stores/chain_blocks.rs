@@ -0,0 +1,378 @@
+use std::{fmt::Display, mem::size_of, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use waglayla_database::{
+    prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, StoreError, StoreResult, StoreResultExtensions, WriteBatch, DB},
+    registry::DatabaseStorePrefixes,
+};
+use waglayla_hashes::{Hash, HASH_SIZE, ZERO_HASH};
+use waglayla_utils::mem_size::MemSizeEstimator;
+
+use crate::model::ChainBlock;
+
+/// Size of the [ChainBlockByDaaScoreKey] in bytes.
+pub const CHAIN_BLOCK_BY_DAA_SCORE_KEY_SIZE: usize = size_of::<u64>() + HASH_SIZE;
+
+/// Key ordering chain blocks by DAA score.
+/// Consists of 8 bytes of big endian DAA score, followed by 32 bytes of block [Hash].
+#[derive(Eq, Hash, PartialEq, Debug, Copy, Clone)]
+struct ChainBlockByDaaScoreKey([u8; CHAIN_BLOCK_BY_DAA_SCORE_KEY_SIZE]);
+
+impl ChainBlockByDaaScoreKey {
+    fn new(daa_score: u64, block_hash: Hash) -> Self {
+        let mut bytes = [0; CHAIN_BLOCK_BY_DAA_SCORE_KEY_SIZE];
+        bytes[..size_of::<u64>()].copy_from_slice(&daa_score.to_be_bytes());
+        bytes[size_of::<u64>()..].copy_from_slice(&block_hash.as_bytes());
+        Self(bytes)
+    }
+
+    fn from_slice(bytes: &[u8]) -> Self {
+        Self(<[u8; CHAIN_BLOCK_BY_DAA_SCORE_KEY_SIZE]>::try_from(bytes).unwrap())
+    }
+
+    fn daa_score(&self) -> u64 {
+        u64::from_be_bytes(self.0[..size_of::<u64>()].try_into().unwrap())
+    }
+
+    fn block_hash(&self) -> Hash {
+        Hash::from_slice(&self.0[size_of::<u64>()..])
+    }
+}
+
+impl Display for ChainBlockByDaaScoreKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.daa_score(), self.block_hash())
+    }
+}
+
+impl AsRef<[u8]> for ChainBlockByDaaScoreKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Size of the [ChainBlockByMaxTimestampKey] in bytes.
+pub const CHAIN_BLOCK_BY_MAX_TIMESTAMP_KEY_SIZE: usize = size_of::<u64>() + CHAIN_BLOCK_BY_DAA_SCORE_KEY_SIZE;
+
+/// Key ordering chain blocks by the maximum timestamp of the chain up to and including them, and then by DAA score.
+/// Consists of 8 bytes of big endian maximum timestamp, followed by a [ChainBlockByDaaScoreKey].
+///
+/// The maximum timestamp never decreases along the chain, so this ordering matches the DAA score ordering.
+#[derive(Eq, Hash, PartialEq, Debug, Copy, Clone)]
+struct ChainBlockByMaxTimestampKey([u8; CHAIN_BLOCK_BY_MAX_TIMESTAMP_KEY_SIZE]);
+
+impl ChainBlockByMaxTimestampKey {
+    fn new(max_timestamp: u64, daa_score: u64, block_hash: Hash) -> Self {
+        let mut bytes = [0; CHAIN_BLOCK_BY_MAX_TIMESTAMP_KEY_SIZE];
+        bytes[..size_of::<u64>()].copy_from_slice(&max_timestamp.to_be_bytes());
+        bytes[size_of::<u64>()..].copy_from_slice(ChainBlockByDaaScoreKey::new(daa_score, block_hash).as_ref());
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for ChainBlockByMaxTimestampKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// The timestamp of a chain block, along with the maximum timestamp of the chain up to and including it.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct ChainBlockTimestamps {
+    timestamp: u64,
+    max_timestamp: u64,
+}
+
+impl MemSizeEstimator for ChainBlockTimestamps {}
+
+/// Reader API for `ChainBlocksStore`.
+pub trait ChainBlocksStoreReader {
+    /// Returns the chain block with the lowest DAA score of at least `daa_score`.
+    fn get_first_at_daa_score(&self, daa_score: u64) -> StoreResult<Option<ChainBlock>>;
+
+    /// Returns the chain block with the lowest DAA score among those with a timestamp of at least `timestamp`.
+    ///
+    /// Chain block timestamps are not monotonic along the selected chain, so this is not necessarily
+    /// the chain block with the lowest timestamp of at least `timestamp`.
+    fn get_first_at_timestamp(&self, timestamp: u64) -> StoreResult<Option<ChainBlock>>;
+
+    /// Returns up to `limit` chain blocks with a DAA score within `from_daa_score..=to_daa_score`, ordered by DAA score.
+    fn get_range(&self, from_daa_score: u64, to_daa_score: u64, limit: usize) -> StoreResult<Vec<ChainBlock>>;
+
+    /// Returns the maximum timestamp of the chain up to and including the given indexed chain block.
+    fn get_max_timestamp(&self, daa_score: u64, block_hash: Hash) -> StoreResult<Option<u64>>;
+}
+
+pub trait ChainBlocksStore: ChainBlocksStoreReader {
+    /// Indexes a chain block extending the indexed chain, `max_timestamp` being the maximum timestamp
+    /// of the chain up to and including it.
+    fn insert(&mut self, batch: &mut WriteBatch, chain_block: ChainBlock, max_timestamp: u64) -> StoreResult<()>;
+    fn delete(&mut self, batch: &mut WriteBatch, chain_block: ChainBlock) -> StoreResult<()>;
+    /// Removes all chain blocks with a DAA score lower than `daa_score`, returning the number of pruned blocks.
+    fn delete_below_daa_score(&mut self, batch: &mut WriteBatch, daa_score: u64) -> StoreResult<usize>;
+    fn delete_all(&mut self) -> StoreResult<()>;
+}
+
+/// A DB + cache implementation of `ChainBlocksStore` trait, holding the chain blocks keyed by DAA score
+/// (mapped to their timestamps) and keyed by the maximum timestamp of the chain up to them (mapped to their timestamp).
+#[derive(Clone)]
+pub struct DbChainBlocksStore {
+    db: Arc<DB>,
+    by_daa_score_access: CachedDbAccess<ChainBlockByDaaScoreKey, ChainBlockTimestamps>,
+    by_max_timestamp_access: CachedDbAccess<ChainBlockByMaxTimestampKey, u64>,
+}
+
+impl DbChainBlocksStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self {
+            db: Arc::clone(&db),
+            by_daa_score_access: CachedDbAccess::new(
+                db.clone(),
+                CachePolicy::Empty,
+                DatabaseStorePrefixes::ChainIndexBlocksByDaaScore.into(),
+            ),
+            by_max_timestamp_access: CachedDbAccess::new(
+                db,
+                CachePolicy::Empty,
+                DatabaseStorePrefixes::ChainIndexBlocksByMaxTimestamp.into(),
+            ),
+        }
+    }
+
+    fn write_entries(&mut self, batch: &mut WriteBatch, chain_block: ChainBlock, max_timestamp: u64) -> StoreResult<()> {
+        let ChainBlock { hash, daa_score, timestamp } = chain_block;
+        self.by_max_timestamp_access.write(
+            BatchDbWriter::new(batch),
+            ChainBlockByMaxTimestampKey::new(max_timestamp, daa_score, hash),
+            timestamp,
+        )?;
+        self.by_daa_score_access.write(
+            BatchDbWriter::new(batch),
+            ChainBlockByDaaScoreKey::new(daa_score, hash),
+            ChainBlockTimestamps { timestamp, max_timestamp },
+        )
+    }
+}
+
+impl ChainBlocksStoreReader for DbChainBlocksStore {
+    fn get_first_at_daa_score(&self, daa_score: u64) -> StoreResult<Option<ChainBlock>> {
+        Ok(self.get_range(daa_score, u64::MAX, 1)?.pop())
+    }
+
+    fn get_first_at_timestamp(&self, timestamp: u64) -> StoreResult<Option<ChainBlock>> {
+        // The first block in chain order whose chain has reached `timestamp` is the first reaching it itself.
+        // The zero DAA score and hash make the seek key lower than any key of a block with this maximum timestamp.
+        let seek_key = ChainBlockByMaxTimestampKey::new(timestamp, 0, ZERO_HASH);
+        match self.by_max_timestamp_access.seek_iterator(None, Some(seek_key), 1, false).next() {
+            Some(res) => {
+                let (key, block_timestamp) = res.map_err(|err| StoreError::DataInconsistency(err.to_string()))?;
+                let key = ChainBlockByDaaScoreKey::from_slice(&key[size_of::<u64>()..]);
+                if block_timestamp < timestamp {
+                    return Err(StoreError::DataInconsistency(format!(
+                        "chain block at DAA score {} is keyed by a maximum timestamp not reached by the indexed chain",
+                        key.daa_score()
+                    )));
+                }
+                Ok(Some(ChainBlock::new(key.block_hash(), key.daa_score(), block_timestamp)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn get_range(&self, from_daa_score: u64, to_daa_score: u64, limit: usize) -> StoreResult<Vec<ChainBlock>> {
+        let seek_key = ChainBlockByDaaScoreKey::new(from_daa_score, ZERO_HASH);
+        let mut chain_blocks = Vec::new();
+        for res in self.by_daa_score_access.seek_iterator(None, Some(seek_key), limit, false) {
+            let (key, timestamps) = res.map_err(|err| StoreError::DataInconsistency(err.to_string()))?;
+            let key = ChainBlockByDaaScoreKey::from_slice(&key);
+            if key.daa_score() > to_daa_score {
+                break;
+            }
+            chain_blocks.push(ChainBlock::new(key.block_hash(), key.daa_score(), timestamps.timestamp));
+        }
+        Ok(chain_blocks)
+    }
+
+    fn get_max_timestamp(&self, daa_score: u64, block_hash: Hash) -> StoreResult<Option<u64>> {
+        Ok(self.by_daa_score_access.read(ChainBlockByDaaScoreKey::new(daa_score, block_hash)).unwrap_option().map(|x| x.max_timestamp))
+    }
+}
+
+impl ChainBlocksStore for DbChainBlocksStore {
+    fn insert(&mut self, batch: &mut WriteBatch, chain_block: ChainBlock, max_timestamp: u64) -> StoreResult<()> {
+        self.write_entries(batch, chain_block, max_timestamp.max(chain_block.timestamp))
+    }
+
+    fn delete(&mut self, batch: &mut WriteBatch, chain_block: ChainBlock) -> StoreResult<()> {
+        let ChainBlock { hash, daa_score, .. } = chain_block;
+        let Some(max_timestamp) = self.get_max_timestamp(daa_score, hash)? else {
+            return Ok(());
+        };
+        self.by_max_timestamp_access
+            .delete(BatchDbWriter::new(batch), ChainBlockByMaxTimestampKey::new(max_timestamp, daa_score, hash))?;
+        self.by_daa_score_access.delete(BatchDbWriter::new(batch), ChainBlockByDaaScoreKey::new(daa_score, hash))
+    }
+
+    fn delete_below_daa_score(&mut self, batch: &mut WriteBatch, daa_score: u64) -> StoreResult<usize> {
+        if daa_score == 0 {
+            return Ok(0);
+        }
+        let pruned = self.get_range(0, daa_score - 1, usize::MAX)?;
+        for chain_block in pruned.iter().copied() {
+            self.delete(batch, chain_block)?;
+        }
+
+        // The remaining blocks may be keyed by a maximum timestamp set by a pruned block. Their maximum is
+        // recomputed over the remaining chain, until reaching a block whose recorded maximum is unaffected.
+        // The pruned blocks all precede them, so the deletions pending in `batch` do not affect these reads.
+        let mut max_timestamp = 0;
+        let mut rekeyed = vec![];
+        let seek_key = ChainBlockByDaaScoreKey::new(daa_score, ZERO_HASH);
+        for res in self.by_daa_score_access.seek_iterator(None, Some(seek_key), usize::MAX, false) {
+            let (key, timestamps) = res.map_err(|err| StoreError::DataInconsistency(err.to_string()))?;
+            max_timestamp = max_timestamp.max(timestamps.timestamp);
+            if max_timestamp == timestamps.max_timestamp {
+                break;
+            }
+            let key = ChainBlockByDaaScoreKey::from_slice(&key);
+            rekeyed.push((ChainBlock::new(key.block_hash(), key.daa_score(), timestamps.timestamp), max_timestamp));
+        }
+        for (chain_block, max_timestamp) in rekeyed {
+            self.delete(batch, chain_block)?;
+            self.write_entries(batch, chain_block, max_timestamp)?;
+        }
+        Ok(pruned.len())
+    }
+
+    /// Removes all entries in the cache and db, besides prefixes themselves.
+    fn delete_all(&mut self) -> StoreResult<()> {
+        let mut batch = WriteBatch::default();
+        self.by_max_timestamp_access.delete_all(BatchDbWriter::new(&mut batch))?;
+        self.by_daa_score_access.delete_all(BatchDbWriter::new(&mut batch))?;
+        Ok(self.db.write(batch)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        path::Path,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    use waglayla_database::{
+        create_temp_db,
+        prelude::{ConnBuilder, DbError, DbIterator, DbSlice, MemoryBackend, StorageBackend},
+    };
+
+    /// A memory backend counting the entries read through its iterators
+    struct CountingBackend {
+        inner: MemoryBackend,
+        iterated: Arc<AtomicUsize>,
+    }
+
+    impl StorageBackend for CountingBackend {
+        fn get(&self, key: &[u8]) -> Result<Option<DbSlice<'_>>, DbError> {
+            self.inner.get(key)
+        }
+
+        fn put(&self, key: &[u8], value: &[u8]) -> Result<(), DbError> {
+            self.inner.put(key, value)
+        }
+
+        fn delete(&self, key: &[u8]) -> Result<(), DbError> {
+            self.inner.delete(key)
+        }
+
+        fn write(&self, batch: WriteBatch) -> Result<(), DbError> {
+            self.inner.write(batch)
+        }
+
+        fn prefix_iterator<'a>(&'a self, prefix: &[u8], seek_from: Option<&[u8]>) -> DbIterator<'a> {
+            let iterated = self.iterated.clone();
+            DbIterator::new(self.inner.prefix_iterator(prefix, seek_from).inspect(move |_| {
+                iterated.fetch_add(1, Ordering::Relaxed);
+            }))
+        }
+
+        fn path(&self) -> &Path {
+            self.inner.path()
+        }
+    }
+
+    #[test]
+    fn test_get_first_at_timestamp() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let mut store = DbChainBlocksStore::new(db.clone());
+
+        // Timestamps are out of order along the chain: the block at DAA score 20 has a later
+        // timestamp than its successor at DAA score 30.
+        let chain = [
+            ChainBlock::new(1.into(), 10, 1_000),
+            ChainBlock::new(2.into(), 20, 1_500),
+            ChainBlock::new(3.into(), 30, 1_200),
+            ChainBlock::new(4.into(), 40, 1_600),
+        ];
+        let mut batch = WriteBatch::default();
+        let mut max_timestamp = 0;
+        for chain_block in chain.iter().copied() {
+            max_timestamp = max_timestamp.max(chain_block.timestamp);
+            store.insert(&mut batch, chain_block, max_timestamp).unwrap();
+        }
+        db.write(batch).unwrap();
+
+        // The first block in chain order reaching the timestamp is returned, even if a later block has a closer timestamp
+        assert_eq!(store.get_first_at_timestamp(1_100).unwrap(), Some(chain[1]));
+        assert_eq!(store.get_first_at_timestamp(1_200).unwrap(), Some(chain[1]));
+        assert_eq!(store.get_first_at_timestamp(1_500).unwrap(), Some(chain[1]));
+        assert_eq!(store.get_first_at_timestamp(1_501).unwrap(), Some(chain[3]));
+        assert_eq!(store.get_first_at_timestamp(0).unwrap(), Some(chain[0]));
+        assert_eq!(store.get_first_at_timestamp(1_601).unwrap(), None);
+
+        // Deleting the first match falls back to the next block in chain order
+        let mut batch = WriteBatch::default();
+        store.delete(&mut batch, chain[1]).unwrap();
+        db.write(batch).unwrap();
+        assert_eq!(store.get_first_at_timestamp(1_100).unwrap(), Some(chain[2]));
+    }
+
+    #[test]
+    fn test_get_first_at_timestamp_reads_a_single_entry() {
+        let iterated = Arc::new(AtomicUsize::new(0));
+        let db = Arc::new(DB::with_backend(CountingBackend { inner: MemoryBackend::default(), iterated: iterated.clone() }));
+        let mut store = DbChainBlocksStore::new(db.clone());
+
+        // A long chain in which an early block has a timestamp far ahead of its successors
+        let mut batch = WriteBatch::default();
+        let mut max_timestamp = 0;
+        for daa_score in 1..=10_000u64 {
+            let timestamp = if daa_score == 10 { 5_000_000 } else { daa_score * 1_000 };
+            max_timestamp = max_timestamp.max(timestamp);
+            store.insert(&mut batch, ChainBlock::new(daa_score.into(), daa_score, timestamp), max_timestamp).unwrap();
+        }
+        db.write(batch).unwrap();
+
+        let lookup = |store: &DbChainBlocksStore, timestamp| {
+            iterated.store(0, Ordering::Relaxed);
+            let chain_block = store.get_first_at_timestamp(timestamp).unwrap();
+            assert!(iterated.load(Ordering::Relaxed) <= 1, "the lookup of timestamp {timestamp} walked the index");
+            chain_block.map(|chain_block| chain_block.daa_score)
+        };
+        assert_eq!(lookup(&store, 0), Some(1));
+        assert_eq!(lookup(&store, 9_000), Some(9));
+        assert_eq!(lookup(&store, 10_000), Some(10));
+        assert_eq!(lookup(&store, 5_000_000), Some(10));
+        assert_eq!(lookup(&store, 5_000_001), Some(5_001));
+        assert_eq!(lookup(&store, 10_000_000), Some(10_000));
+        assert_eq!(lookup(&store, 10_000_001), None);
+
+        // Pruning the block holding the maximum re-keys its successors, so lookups remain a single read
+        let mut batch = WriteBatch::default();
+        assert_eq!(store.delete_below_daa_score(&mut batch, 11).unwrap(), 10);
+        db.write(batch).unwrap();
+        assert_eq!(lookup(&store, 0), Some(11));
+        assert_eq!(lookup(&store, 4_000_000), Some(4_000));
+        assert_eq!(lookup(&store, 5_000_001), Some(5_001));
+        assert_eq!(store.get_range(0, u64::MAX, usize::MAX).unwrap().len(), 9_990);
+    }
+}
@@ -0,0 +1,2 @@
+mod chain_blocks;
+pub mod store_manager;
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use waglayla_core::trace;
+use waglayla_database::{
+    prelude::{StoreResult, WriteBatch, DB},
+    registry::DatabaseStorePrefixes,
+};
+use waglayla_hashes::Hash;
+use waglayla_index_core::sync_points::{DbIndexSyncPointStore, IndexSyncPointStore, IndexSyncPointStoreReader};
+
+use crate::{
+    model::ChainBlock,
+    stores::chain_blocks::{ChainBlocksStore, ChainBlocksStoreReader, DbChainBlocksStore},
+    IDENT,
+};
+
+/// Gathers the chainindex stores.
+///
+/// All mutating methods write into a [`WriteBatch`] which the caller commits with [`Store::write_batch`] once the
+/// whole update is gathered, so that a crash never leaves an update partly written.
+#[derive(Clone)]
+pub struct Store {
+    db: Arc<DB>,
+    sink_store: DbIndexSyncPointStore,
+    pruning_point_store: DbIndexSyncPointStore,
+    chain_blocks_store: DbChainBlocksStore,
+}
+
+impl Store {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self {
+            db: db.clone(),
+            sink_store: DbIndexSyncPointStore::new(db.clone(), DatabaseStorePrefixes::ChainIndexSink),
+            pruning_point_store: DbIndexSyncPointStore::new(db.clone(), DatabaseStorePrefixes::ChainIndexPruningPoint),
+            chain_blocks_store: DbChainBlocksStore::new(db),
+        }
+    }
+
+    /// Atomically commits all the writes gathered in `batch`
+    pub fn write_batch(&self, batch: WriteBatch) -> StoreResult<()> {
+        Ok(self.db.write(batch)?)
+    }
+
+    pub fn get_chain_block_by_daa_score(&self, daa_score: u64) -> StoreResult<Option<ChainBlock>> {
+        self.chain_blocks_store.get_first_at_daa_score(daa_score)
+    }
+
+    pub fn get_chain_block_by_timestamp(&self, timestamp: u64) -> StoreResult<Option<ChainBlock>> {
+        self.chain_blocks_store.get_first_at_timestamp(timestamp)
+    }
+
+    pub fn get_chain_blocks(&self, from_daa_score: u64, to_daa_score: u64, limit: usize) -> StoreResult<Vec<ChainBlock>> {
+        self.chain_blocks_store.get_range(from_daa_score, to_daa_score, limit)
+    }
+
+    /// Returns the maximum timestamp of the chain up to and including the given indexed chain block.
+    pub fn get_chain_max_timestamp(&self, chain_block: ChainBlock) -> StoreResult<Option<u64>> {
+        self.chain_blocks_store.get_max_timestamp(chain_block.daa_score, chain_block.hash)
+    }
+
+    /// Indexes a chain block extending the indexed chain, `max_timestamp` being the maximum timestamp
+    /// of the chain up to and including it.
+    pub fn add_chain_block(&mut self, batch: &mut WriteBatch, chain_block: ChainBlock, max_timestamp: u64) -> StoreResult<()> {
+        self.chain_blocks_store.insert(batch, chain_block, max_timestamp)
+    }
+
+    pub fn remove_chain_block(&mut self, batch: &mut WriteBatch, chain_block: ChainBlock) -> StoreResult<()> {
+        self.chain_blocks_store.delete(batch, chain_block)
+    }
+
+    /// Removes all chain blocks with a DAA score lower than `daa_score`, returning the number of pruned blocks.
+    pub fn prune_below_daa_score(&mut self, batch: &mut WriteBatch, daa_score: u64) -> StoreResult<usize> {
+        self.chain_blocks_store.delete_below_daa_score(batch, daa_score)
+    }
+
+    pub fn get_sink(&self) -> StoreResult<Hash> {
+        self.sink_store.get()
+    }
+
+    pub fn set_sink(&mut self, batch: &mut WriteBatch, sink: Hash) -> StoreResult<()> {
+        self.sink_store.set_batch(batch, sink)
+    }
+
+    pub fn get_pruning_point(&self) -> StoreResult<Hash> {
+        self.pruning_point_store.get()
+    }
+
+    pub fn set_pruning_point(&mut self, batch: &mut WriteBatch, pruning_point: Hash) -> StoreResult<()> {
+        self.pruning_point_store.set_batch(batch, pruning_point)
+    }
+
+    /// Resets the chainindex database:
+    pub fn delete_all(&mut self) -> StoreResult<()> {
+        trace!("[{0}] attempting to clear chainindex database...", IDENT);
+
+        // Clear all
+        self.sink_store.remove()?;
+        self.pruning_point_store.remove()?;
+        self.chain_blocks_store.delete_all()?;
+
+        trace!("[{0}] clearing chainindex database - success!", IDENT);
+
+        Ok(())
+    }
+}
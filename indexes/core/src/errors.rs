@@ -0,0 +1,24 @@
+/// Defines the error and result types of an index synced with consensus, prefixing
+/// the error messages with the index identifier.
+///
+/// Usage: `index_errors!(TxIndex, TxIndexError, TxIndexResult, IDENT);`
+#[macro_export]
+macro_rules! index_errors {
+    ($index:ident, $error:ident, $result:ident, $ident:path) => {
+        #[doc = concat!("Errors originating from the [`", stringify!($index), "`].")]
+        #[derive(thiserror::Error, Debug)]
+        pub enum $error {
+            #[error("[{}]: {0}", $ident)]
+            StoreAccessError(#[from] waglayla_database::prelude::StoreError),
+
+            #[error("[{}]: {0}", $ident)]
+            ConsensusError(#[from] waglayla_consensus_core::errors::consensus::ConsensusError),
+
+            #[error("[{}]: {0}", $ident)]
+            DBResetError(#[from] std::io::Error),
+        }
+
+        #[doc = concat!("Results originating from the [`", stringify!($index), "`].")]
+        pub type $result<T> = Result<T, $error>;
+    };
+}
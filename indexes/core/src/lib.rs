@@ -2,3 +2,10 @@ pub mod connection;
 pub mod indexed_utxos;
 pub mod notification;
 pub mod notifier;
+
+#[cfg(feature = "store")]
+pub mod errors;
+#[cfg(feature = "store")]
+pub mod reset;
+#[cfg(feature = "store")]
+pub mod sync_points;
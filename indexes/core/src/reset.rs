@@ -0,0 +1,24 @@
+use std::{fmt::Debug, sync::Weak};
+
+use parking_lot::RwLock;
+use waglayla_consensusmanager::ConsensusResetHandler;
+
+/// Resyncs an index from the consensus database when consensus is reset.
+pub struct IndexConsensusResetHandler<T, E> {
+    index: Weak<RwLock<T>>,
+    resync: fn(&mut T) -> Result<(), E>,
+}
+
+impl<T, E> IndexConsensusResetHandler<T, E> {
+    pub fn new(index: Weak<RwLock<T>>, resync: fn(&mut T) -> Result<(), E>) -> Self {
+        Self { index, resync }
+    }
+}
+
+impl<T: Send + Sync, E: Debug> ConsensusResetHandler for IndexConsensusResetHandler<T, E> {
+    fn handle_consensus_reset(&self) {
+        if let Some(index) = self.index.upgrade() {
+            (self.resync)(&mut index.write()).unwrap();
+        }
+    }
+}
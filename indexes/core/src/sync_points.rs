@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use waglayla_consensus_core::api::ConsensusApi;
+use waglayla_database::{
+    prelude::{BatchDbWriter, CachedDbItem, DirectDbWriter, StoreError, StoreResult, WriteBatch, DB},
+    registry::DatabaseStorePrefixes,
+};
+use waglayla_hashes::Hash;
+
+/// Reader API for `IndexSyncPointStore`.
+pub trait IndexSyncPointStoreReader {
+    fn get(&self) -> StoreResult<Hash>;
+}
+
+pub trait IndexSyncPointStore: IndexSyncPointStoreReader {
+    fn set(&mut self, hash: Hash) -> StoreResult<()>;
+    fn remove(&mut self) -> StoreResult<()>;
+}
+
+/// A DB + cache implementation of `IndexSyncPointStore` trait, holding a single consensus
+/// block hash (the sink or the pruning point) an index was last synced with.
+#[derive(Clone)]
+pub struct DbIndexSyncPointStore {
+    db: Arc<DB>,
+    access: CachedDbItem<Hash>,
+}
+
+impl DbIndexSyncPointStore {
+    pub fn new(db: Arc<DB>, prefix: DatabaseStorePrefixes) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbItem::new(db, prefix.into()) }
+    }
+
+    pub fn set_batch(&mut self, batch: &mut WriteBatch, hash: Hash) -> StoreResult<()> {
+        self.access.write(BatchDbWriter::new(batch), &hash)
+    }
+}
+
+impl IndexSyncPointStoreReader for DbIndexSyncPointStore {
+    fn get(&self) -> StoreResult<Hash> {
+        self.access.read()
+    }
+}
+
+impl IndexSyncPointStore for DbIndexSyncPointStore {
+    fn set(&mut self, hash: Hash) -> StoreResult<()> {
+        self.access.write(DirectDbWriter::new(&self.db), &hash)
+    }
+
+    fn remove(&mut self) -> StoreResult<()> {
+        self.access.remove(DirectDbWriter::new(&self.db))
+    }
+}
+
+/// Checks whether an index is synced with consensus by comparing the sink and the pruning point the index
+/// was last synced with to those of consensus. An index missing any of them, i.e. an empty one, is not synced.
+pub fn is_synced(sink: StoreResult<Hash>, pruning_point: StoreResult<Hash>, consensus: &dyn ConsensusApi) -> StoreResult<bool> {
+    match (sink, pruning_point) {
+        (Ok(sink), Ok(pruning_point)) => Ok(sink == consensus.get_sink() && pruning_point == consensus.pruning_point()),
+        (Err(StoreError::KeyNotFound(_)), _) | (_, Err(StoreError::KeyNotFound(_))) => Ok(false),
+        (Err(other_store_error), _) | (_, Err(other_store_error)) => Err(other_store_error),
+    }
+}
@@ -1,4 +1,7 @@
+use waglayla_addressindex::errors::AddressIndexError;
+use waglayla_chainindex::errors::ChainIndexError;
 use waglayla_notify::events::EventType;
+use waglayla_txindex::errors::TxIndexError;
 use waglayla_utxoindex::errors::UtxoIndexError;
 use thiserror::Error;
 
@@ -7,6 +10,15 @@ pub enum IndexError {
     #[error("{0}")]
     UtxoIndexError(#[from] UtxoIndexError),
 
+    #[error("{0}")]
+    TxIndexError(#[from] TxIndexError),
+
+    #[error("{0}")]
+    AddressIndexError(#[from] AddressIndexError),
+
+    #[error("{0}")]
+    ChainIndexError(#[from] ChainIndexError),
+
     #[error("event type {0:?} is not supported")]
     NotSupported(EventType),
 }
@@ -3,8 +3,10 @@ use crate::{
     IDENT,
 };
 use async_trait::async_trait;
+use waglayla_addressindex::api::AddressIndexProxy;
+use waglayla_chainindex::api::ChainIndexProxy;
 use waglayla_consensus_notify::{notification as consensus_notification, notification::Notification as ConsensusNotification};
-use waglayla_core::{debug, trace};
+use waglayla_core::{debug, trace, warn};
 use waglayla_index_core::notification::{Notification, PruningPointUtxoSetOverrideNotification, UtxosChangedNotification};
 use waglayla_notify::{
     collector::{Collector, CollectorNotificationReceiver},
@@ -13,6 +15,7 @@ use waglayla_notify::{
     notification::Notification as NotificationTrait,
     notifier::DynNotify,
 };
+use waglayla_txindex::api::TxIndexProxy;
 use waglayla_utils::triggers::SingleTrigger;
 use waglayla_utxoindex::api::UtxoIndexProxy;
 use std::sync::{
@@ -21,15 +24,27 @@ use std::sync::{
 };
 
 /// Processor processes incoming consensus UtxosChanged and PruningPointUtxoSetOverride
-/// notifications submitting them to a UtxoIndex.
+/// notifications submitting them to a UtxoIndex, consensus BlockAdded and
+/// VirtualChainChanged notifications submitting them to a TxIndex, and consensus
+/// VirtualChainChanged notifications submitting them to an AddressIndex and a ChainIndex.
 ///
 /// It also acts as a [`Collector`], converting the incoming consensus notifications
 /// into their pending local versions and relaying them to a local notifier.
+/// Notifications consumed by the TxIndex, the AddressIndex or the ChainIndex only are not relayed.
 #[derive(Debug)]
 pub struct Processor {
     /// An optional UTXO indexer
     utxoindex: Option<UtxoIndexProxy>,
 
+    /// An optional transaction indexer
+    txindex: Option<TxIndexProxy>,
+
+    /// An optional address history indexer
+    addressindex: Option<AddressIndexProxy>,
+
+    /// An optional chain block indexer
+    chainindex: Option<ChainIndexProxy>,
+
     recv_channel: CollectorNotificationReceiver<ConsensusNotification>,
 
     /// Has this collector been started?
@@ -39,9 +54,18 @@ pub struct Processor {
 }
 
 impl Processor {
-    pub fn new(utxoindex: Option<UtxoIndexProxy>, recv_channel: CollectorNotificationReceiver<ConsensusNotification>) -> Self {
+    pub fn new(
+        utxoindex: Option<UtxoIndexProxy>,
+        txindex: Option<TxIndexProxy>,
+        addressindex: Option<AddressIndexProxy>,
+        chainindex: Option<ChainIndexProxy>,
+        recv_channel: CollectorNotificationReceiver<ConsensusNotification>,
+    ) -> Self {
         Self {
             utxoindex,
+            txindex,
+            addressindex,
+            chainindex,
             recv_channel,
             collect_shutdown: Arc::new(SingleTrigger::new()),
             is_started: Arc::new(AtomicBool::new(false)),
@@ -58,12 +82,13 @@ impl Processor {
 
             while let Ok(notification) = self.recv_channel.recv().await {
                 match self.process_notification(notification).await {
-                    Ok(notification) => match notifier.notify(notification) {
+                    Ok(Some(notification)) => match notifier.notify(notification) {
                         Ok(_) => (),
                         Err(err) => {
                             trace!("[Index processor] notification sender error: {err:?}");
                         }
                     },
+                    Ok(None) => (),
                     Err(err) => {
                         trace!("[Index processor] error while processing a consensus notification: {err:?}");
                     }
@@ -76,13 +101,21 @@ impl Processor {
         });
     }
 
-    async fn process_notification(self: &Arc<Self>, notification: ConsensusNotification) -> IndexResult<Notification> {
+    async fn process_notification(self: &Arc<Self>, notification: ConsensusNotification) -> IndexResult<Option<Notification>> {
         match notification {
             ConsensusNotification::UtxosChanged(utxos_changed) => {
-                Ok(Notification::UtxosChanged(self.process_utxos_changed(utxos_changed).await?))
+                Ok(Some(Notification::UtxosChanged(self.process_utxos_changed(utxos_changed).await?)))
             }
             ConsensusNotification::PruningPointUtxoSetOverride(_) => {
-                Ok(Notification::PruningPointUtxoSetOverride(PruningPointUtxoSetOverrideNotification {}))
+                Ok(Some(Notification::PruningPointUtxoSetOverride(PruningPointUtxoSetOverrideNotification {})))
+            }
+            ConsensusNotification::BlockAdded(block_added) => {
+                self.process_block_added(block_added).await?;
+                Ok(None)
+            }
+            ConsensusNotification::VirtualChainChanged(virtual_chain_changed) => {
+                self.process_virtual_chain_changed(virtual_chain_changed).await?;
+                Ok(None)
             }
             _ => Err(IndexError::NotSupported(notification.event_type())),
         }
@@ -106,6 +139,78 @@ impl Processor {
         Err(IndexError::NotSupported(EventType::UtxosChanged))
     }
 
+    async fn process_block_added(self: &Arc<Self>, notification: consensus_notification::BlockAddedNotification) -> IndexResult<()> {
+        trace!("[{IDENT}]: processing BlockAdded notification for block {}", notification.block.hash());
+        if let Some(txindex) = self.txindex.clone() {
+            return Ok(txindex.update_via_block_added(notification.block).await?);
+        };
+        Err(IndexError::NotSupported(EventType::BlockAdded))
+    }
+
+    /// Updates every index following the virtual chain, so that a failing index does not hold the others back.
+    /// Each failure is logged and the first one is returned.
+    async fn process_virtual_chain_changed(
+        self: &Arc<Self>,
+        notification: consensus_notification::VirtualChainChangedNotification,
+    ) -> IndexResult<()> {
+        trace!("[{IDENT}]: processing {:?}", notification);
+        if self.txindex.is_none() && self.addressindex.is_none() && self.chainindex.is_none() {
+            return Err(IndexError::NotSupported(EventType::VirtualChainChanged));
+        }
+        let mut first_error: Option<IndexError> = None;
+        if let Some(txindex) = self.txindex.clone() {
+            debug!(
+                "IDXPRC, Updating the txindex with {} added and {} removed chain blocks",
+                notification.added_chain_block_hashes.len(),
+                notification.removed_chain_block_hashes.len()
+            );
+            if let Err(err) = txindex
+                .update_via_virtual_chain_changed(
+                    notification.added_chain_block_hashes.clone(),
+                    notification.removed_chain_block_hashes.clone(),
+                    notification.added_chain_blocks_acceptance_data.clone(),
+                )
+                .await
+            {
+                warn!("IDXPRC, Failed to update the txindex: {}", err);
+                first_error.get_or_insert(err.into());
+            }
+        }
+        if let Some(addressindex) = self.addressindex.clone() {
+            debug!(
+                "IDXPRC, Updating the addressindex with {} added and {} removed chain blocks",
+                notification.added_chain_block_hashes.len(),
+                notification.removed_chain_block_hashes.len()
+            );
+            if let Err(err) = addressindex
+                .update_via_virtual_chain_changed(
+                    notification.added_chain_block_hashes.clone(),
+                    notification.removed_chain_block_hashes.clone(),
+                    notification.added_chain_blocks_acceptance_data,
+                )
+                .await
+            {
+                warn!("IDXPRC, Failed to update the addressindex: {}", err);
+                first_error.get_or_insert(err.into());
+            }
+        }
+        if let Some(chainindex) = self.chainindex.clone() {
+            debug!(
+                "IDXPRC, Updating the chainindex with {} added and {} removed chain blocks",
+                notification.added_chain_block_hashes.len(),
+                notification.removed_chain_block_hashes.len()
+            );
+            if let Err(err) = chainindex
+                .update_via_virtual_chain_changed(notification.added_chain_block_hashes, notification.removed_chain_block_hashes)
+                .await
+            {
+                warn!("IDXPRC, Failed to update the chainindex: {}", err);
+                first_error.get_or_insert(err.into());
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
     async fn join_collecting_task(&self) -> Result<()> {
         trace!("[Index processor] joining");
         self.collect_shutdown.listener.clone().await;
@@ -160,7 +265,7 @@ mod tests {
             tc.init();
             let consensus_manager = Arc::new(ConsensusManager::from_consensus(tc.consensus_clone()));
             let utxoindex = Some(UtxoIndexProxy::new(UtxoIndex::new(consensus_manager, utxoindex_db).unwrap()));
-            let processor = Arc::new(Processor::new(utxoindex, consensus_receiver));
+            let processor = Arc::new(Processor::new(utxoindex, None, None, None, consensus_receiver));
             let (processor_sender, processor_receiver) = unbounded();
             let notifier = Arc::new(NotifyMock::new(processor_sender));
             processor.clone().start(notifier);
@@ -1,4 +1,6 @@
 use crate::{processor::Processor, IDENT};
+use waglayla_addressindex::api::AddressIndexProxy;
+use waglayla_chainindex::api::ChainIndexProxy;
 use waglayla_consensus_notify::{
     connection::ConsensusChannelConnection, notification::Notification as ConsensusNotification, notifier::ConsensusNotifier,
 };
@@ -11,9 +13,10 @@ use waglayla_notify::{
     connection::ChannelType,
     events::{EventSwitches, EventType},
     listener::ListenerLifespan,
-    scope::{PruningPointUtxoSetOverrideScope, UtxosChangedScope},
+    scope::{BlockAddedScope, PruningPointUtxoSetOverrideScope, UtxosChangedScope, VirtualChainChangedScope},
     subscription::{context::SubscriptionContext, MutationPolicies, UtxosChangedMutationPolicy},
 };
+use waglayla_txindex::api::TxIndexProxy;
 use waglayla_utils::{channel::Channel, triggers::SingleTrigger};
 use waglayla_utxoindex::api::UtxoIndexProxy;
 use std::sync::Arc;
@@ -22,6 +25,9 @@ const INDEX_SERVICE: &str = IDENT;
 
 pub struct IndexService {
     utxoindex: Option<UtxoIndexProxy>,
+    txindex: Option<TxIndexProxy>,
+    addressindex: Option<AddressIndexProxy>,
+    chainindex: Option<ChainIndexProxy>,
     notifier: Arc<IndexNotifier>,
     shutdown: SingleTrigger,
 }
@@ -31,6 +37,9 @@ impl IndexService {
         consensus_notifier: &Arc<ConsensusNotifier>,
         subscription_context: SubscriptionContext,
         utxoindex: Option<UtxoIndexProxy>,
+        txindex: Option<TxIndexProxy>,
+        addressindex: Option<AddressIndexProxy>,
+        chainindex: Option<ChainIndexProxy>,
     ) -> Self {
         // This notifier UTXOs subscription granularity to consensus notifier
         let policies = MutationPolicies::new(UtxosChangedMutationPolicy::Wildcard);
@@ -45,7 +54,13 @@ impl IndexService {
         // Prepare the index-processor notifier
         // No subscriber is defined here because the subscription are manually created during the construction and never changed after that.
         let events: EventSwitches = [EventType::UtxosChanged, EventType::PruningPointUtxoSetOverride].as_ref().into();
-        let collector = Arc::new(Processor::new(utxoindex.clone(), consensus_notify_channel.receiver()));
+        let collector = Arc::new(Processor::new(
+            utxoindex.clone(),
+            txindex.clone(),
+            addressindex.clone(),
+            chainindex.clone(),
+            consensus_notify_channel.receiver(),
+        ));
         let notifier = Arc::new(IndexNotifier::new(INDEX_SERVICE, events, vec![collector], vec![], subscription_context, 1, policies));
 
         // Manually subscribe to index-processor related event types
@@ -55,8 +70,24 @@ impl IndexService {
         consensus_notifier
             .try_start_notify(consensus_notify_listener_id, PruningPointUtxoSetOverrideScope::default().into())
             .expect("the subscription always succeeds");
-
-        Self { utxoindex, notifier, shutdown: SingleTrigger::default() }
+        if txindex.is_some() {
+            // The txindex needs the bodies of new blocks
+            consensus_notifier
+                .try_start_notify(consensus_notify_listener_id, BlockAddedScope::default().into())
+                .expect("the subscription always succeeds");
+        }
+        if txindex.is_some() || addressindex.is_some() || chainindex.is_some() {
+            // The txindex and the addressindex also need the acceptance data of new chain blocks
+            let include_accepted_transaction_ids = txindex.is_some() || addressindex.is_some();
+            consensus_notifier
+                .try_start_notify(
+                    consensus_notify_listener_id,
+                    VirtualChainChangedScope::new(include_accepted_transaction_ids).into(),
+                )
+                .expect("the subscription always succeeds");
+        }
+
+        Self { utxoindex, txindex, addressindex, chainindex, notifier, shutdown: SingleTrigger::default() }
     }
 
     pub fn notifier(&self) -> Arc<IndexNotifier> {
@@ -66,6 +97,18 @@ impl IndexService {
     pub fn utxoindex(&self) -> Option<UtxoIndexProxy> {
         self.utxoindex.clone()
     }
+
+    pub fn txindex(&self) -> Option<TxIndexProxy> {
+        self.txindex.clone()
+    }
+
+    pub fn addressindex(&self) -> Option<AddressIndexProxy> {
+        self.addressindex.clone()
+    }
+
+    pub fn chainindex(&self) -> Option<ChainIndexProxy> {
+        self.chainindex.clone()
+    }
 }
 
 impl AsyncService for IndexService {
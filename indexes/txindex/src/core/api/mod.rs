@@ -0,0 +1,88 @@
+use waglayla_consensus_core::{acceptance_data::AcceptanceData, block::Block, tx::TransactionId};
+use waglayla_consensusmanager::spawn_blocking;
+use waglayla_database::prelude::StoreResult;
+use waglayla_hashes::Hash;
+use parking_lot::RwLock;
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{errors::TxIndexResult, model::TxIndexEntry};
+
+///Txindex API targeted at retrieval calls.
+pub trait TxIndexApi: Send + Sync + Debug {
+    /// Retrieve the including blocks and the accepting chain block of a transaction from the txindex db.
+    ///
+    /// Returns `None` if the transaction is unknown to the index, or if its blocks were pruned.
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_transaction_entry(&self, transaction_id: TransactionId) -> StoreResult<Option<TxIndexEntry>>;
+
+    /// Retrieve the sink the txindex was last synced with (used for testing purposes).
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_txindex_sink(&self) -> StoreResult<Hash>;
+
+    /// Checks if the txindex's db is synced with consensus.
+    ///
+    /// Note:
+    /// 1) Use a read lock when accessing this method
+    /// 2) due to potential sync-gaps is_synced is unreliable while consensus is actively resolving virtual states.
+    fn is_synced(&self) -> TxIndexResult<bool>;
+
+    /// Index the transactions included in a newly added block.
+    ///
+    /// Note: Use a write lock when accessing this method
+    fn update_via_block_added(&mut self, block: Block) -> TxIndexResult<()>;
+
+    /// Update the transaction acceptances with the given virtual chain changes,
+    /// and drop the data of blocks falling below the pruning point.
+    ///
+    /// Note: Use a write lock when accessing this method
+    fn update_via_virtual_chain_changed(
+        &mut self,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+    ) -> TxIndexResult<()>;
+
+    /// Resync the txindex from the consensus db
+    ///
+    /// Note: Use a write lock when accessing this method
+    fn resync(&mut self) -> TxIndexResult<()>;
+}
+
+/// Async proxy for the transaction index
+#[derive(Debug, Clone)]
+pub struct TxIndexProxy {
+    inner: Arc<RwLock<dyn TxIndexApi>>,
+}
+
+impl TxIndexProxy {
+    pub fn new(inner: Arc<RwLock<dyn TxIndexApi>>) -> Self {
+        Self { inner }
+    }
+
+    pub async fn get_transaction_entry(self, transaction_id: TransactionId) -> StoreResult<Option<TxIndexEntry>> {
+        spawn_blocking(move || self.inner.read().get_transaction_entry(transaction_id)).await.unwrap()
+    }
+
+    pub async fn update_via_block_added(self, block: Block) -> TxIndexResult<()> {
+        spawn_blocking(move || self.inner.write().update_via_block_added(block)).await.unwrap()
+    }
+
+    pub async fn update_via_virtual_chain_changed(
+        self,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+    ) -> TxIndexResult<()> {
+        spawn_blocking(move || {
+            self.inner.write().update_via_virtual_chain_changed(
+                added_chain_block_hashes,
+                removed_chain_block_hashes,
+                added_chain_blocks_acceptance_data,
+            )
+        })
+        .await
+        .unwrap()
+    }
+}
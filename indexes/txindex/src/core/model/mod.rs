@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use waglayla_consensus_core::tx::TransactionId;
+use waglayla_hashes::Hash;
+use waglayla_utils::mem_size::MemSizeEstimator;
+
+/// A block including a transaction, along with the position of the transaction within the block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxInclusion {
+    pub block_hash: Hash,
+    pub index_within_block: u32,
+}
+
+impl TxInclusion {
+    pub fn new(block_hash: Hash, index_within_block: u32) -> Self {
+        Self { block_hash, index_within_block }
+    }
+}
+
+/// The chain block which accepted a transaction, along with the mergeset block the accepted transaction was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxAcceptance {
+    pub accepting_block_hash: Hash,
+    pub including_block_hash: Hash,
+    pub index_within_block: u32,
+}
+
+impl TxAcceptance {
+    pub fn new(accepting_block_hash: Hash, including_block_hash: Hash, index_within_block: u32) -> Self {
+        Self { accepting_block_hash, including_block_hash, index_within_block }
+    }
+}
+
+impl MemSizeEstimator for TxAcceptance {}
+
+/// All indexed data about a single transaction.
+///
+/// A transaction can be included in several blocks of the DAG but is accepted by at most one chain block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxIndexEntry {
+    pub transaction_id: TransactionId,
+    pub inclusions: Vec<TxInclusion>,
+    pub acceptance: Option<TxAcceptance>,
+}
@@ -0,0 +1,282 @@
+use crate::{api::TxIndexApi, errors::TxIndexResult, model::TxIndexEntry, stores::store_manager::Store, IDENT};
+use waglayla_consensus_core::{acceptance_data::AcceptanceData, api::ConsensusApi, block::Block, tx::TransactionId};
+use waglayla_consensusmanager::ConsensusManager;
+use waglayla_core::{info, trace, warn};
+use waglayla_database::prelude::{StoreResult, WriteBatch, DB};
+use waglayla_hashes::Hash;
+use waglayla_index_core::{reset::IndexConsensusResetHandler, sync_points::is_synced};
+use parking_lot::RwLock;
+use std::{fmt::Debug, sync::Arc};
+
+const RESYNC_CHUNK_SIZE: usize = 256; // Chain blocks per acceptance data request; each of them can merge many blocks with full bodies.
+
+/// TxIndex indexes transactions by [`TransactionId`], keeping the blocks including them and the chain block accepting them.
+/// Data belonging to blocks below the consensus pruning point is dropped as the pruning point advances.
+/// Note: The TxIndex struct by itself is not thread safe, only correct usage of the supplied RwLock via `new` makes it so.
+/// please follow guidelines found in the comments under `txindex::core::api::TxIndexApi` for proper thread safety.
+pub struct TxIndex {
+    consensus_manager: Arc<ConsensusManager>,
+    store: Store,
+}
+
+impl TxIndex {
+    /// Creates a new [`TxIndex`] within a [`RwLock`]
+    pub fn new(consensus_manager: Arc<ConsensusManager>, db: Arc<DB>) -> TxIndexResult<Arc<RwLock<Self>>> {
+        let mut txindex = Self { consensus_manager: consensus_manager.clone(), store: Store::new(db) };
+        if !txindex.is_synced()? {
+            txindex.resync()?;
+        }
+        let txindex = Arc::new(RwLock::new(txindex));
+        consensus_manager
+            .register_consensus_reset_handler(Arc::new(IndexConsensusResetHandler::new(Arc::downgrade(&txindex), Self::resync)));
+        Ok(txindex)
+    }
+
+    /// Indexes the transactions of a block fetched from consensus, skipping blocks whose body is not available.
+    fn index_block_body(&mut self, consensus: &dyn ConsensusApi, block_hash: Hash) -> TxIndexResult<()> {
+        if self.store.get_block(block_hash)?.is_some_and(|block| !block.included_transaction_ids.is_empty()) {
+            return Ok(());
+        }
+        match consensus.get_block(block_hash) {
+            Ok(block) => {
+                let mut batch = WriteBatch::default();
+                self.store.add_block_transactions(
+                    &mut batch,
+                    block_hash,
+                    block.header.daa_score,
+                    block.transactions.iter().map(|tx| tx.id()).collect(),
+                )?;
+                self.store.write_batch(batch)?;
+            }
+            Err(err) => trace!("[{0}] skipping the body of block {1}: {2}", IDENT, block_hash, err),
+        }
+        Ok(())
+    }
+
+    /// Drops the data of all blocks below `pruning_point` and records it as the new txindex pruning point.
+    fn prune(&mut self, consensus: &dyn ConsensusApi, pruning_point: Hash) -> TxIndexResult<()> {
+        let pruning_point_daa_score = consensus.get_header(pruning_point)?.daa_score;
+        let mut batch = WriteBatch::default();
+        let pruned = self.store.prune_below_daa_score(&mut batch, pruning_point_daa_score)?;
+        self.store.set_pruning_point(&mut batch, pruning_point)?;
+        self.store.write_batch(batch)?;
+        trace!("[{0}] pruned {1} blocks below pruning point {2}", IDENT, pruned, pruning_point);
+        Ok(())
+    }
+}
+
+impl TxIndexApi for TxIndex {
+    /// Retrieve the indexed data of a transaction from the txindex db.
+    fn get_transaction_entry(&self, transaction_id: TransactionId) -> StoreResult<Option<TxIndexEntry>> {
+        trace!("[{0}] retrieving transaction {1}", IDENT, transaction_id);
+
+        let inclusions = self.store.get_inclusions(transaction_id)?;
+        let acceptance = self.store.get_acceptance(transaction_id)?;
+        if inclusions.is_empty() && acceptance.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(TxIndexEntry { transaction_id, inclusions: inclusions.as_ref().clone(), acceptance }))
+    }
+
+    /// Retrieve the stored sink of the txindex.
+    fn get_txindex_sink(&self) -> StoreResult<Hash> {
+        trace!("[{0}] retrieving sink", IDENT);
+
+        self.store.get_sink()
+    }
+
+    /// Checks to see if the [TxIndex] is sync'd. This is done via comparing the txindex committed sink and pruning point with those of the consensus database.
+    ///
+    /// **Note:** Due to sync gaps between the txindex and consensus, this function is only reliable while consensus is not processing new blocks.
+    fn is_synced(&self) -> TxIndexResult<bool> {
+        trace!("[{0}] checking sync status...", IDENT);
+
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        let res = is_synced(self.store.get_sink(), self.store.get_pruning_point(), &*session)?;
+        trace!("[{0}] sync status is {1}", IDENT, res);
+        Ok(res)
+    }
+
+    /// Updates the [TxIndex] with the transactions of a newly added block.
+    fn update_via_block_added(&mut self, block: Block) -> TxIndexResult<()> {
+        trace!("[{0}] adding {1} transactions of block {2}", IDENT, block.transactions.len(), block.hash());
+
+        let mut batch = WriteBatch::default();
+        self.store.add_block_transactions(
+            &mut batch,
+            block.hash(),
+            block.header.daa_score,
+            block.transactions.iter().map(|tx| tx.id()).collect(),
+        )?;
+        self.store.write_batch(batch)?;
+        Ok(())
+    }
+
+    /// Updates the [TxIndex] via the virtual chain changes supplied:
+    /// 1) Drops the acceptances of removed chain blocks and saves those of added chain blocks.
+    /// 2) Commits the new sink, and prunes the index if the consensus pruning point moved.
+    fn update_via_virtual_chain_changed(
+        &mut self,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+    ) -> TxIndexResult<()> {
+        trace!("[{0}] updating...", IDENT);
+        trace!("[{0}] adding {1} chain blocks", IDENT, added_chain_block_hashes.len());
+        trace!("[{0}] removing {1} chain blocks", IDENT, removed_chain_block_hashes.len());
+
+        let consensus = self.consensus_manager.consensus();
+        let session = consensus.unguarded_session_blocking();
+
+        // The chain changes and the new sink are committed at once, the pruning follows in a batch of its own
+        let mut batch = WriteBatch::default();
+        for block_hash in removed_chain_block_hashes.iter().copied() {
+            self.store.remove_chain_block_acceptance(&mut batch, block_hash)?;
+        }
+
+        // The acceptance data is expected along with the notification, but fall back to consensus if it was left out
+        let added_chain_blocks_acceptance_data = if added_chain_blocks_acceptance_data.len() == added_chain_block_hashes.len() {
+            added_chain_blocks_acceptance_data
+        } else {
+            Arc::new(session.get_blocks_acceptance_data(&added_chain_block_hashes)?)
+        };
+        for (block_hash, acceptance_data) in added_chain_block_hashes.iter().copied().zip(added_chain_blocks_acceptance_data.iter()) {
+            let daa_score = match self.store.get_block(block_hash)? {
+                Some(block) => block.daa_score,
+                None => session.get_header(block_hash)?.daa_score,
+            };
+            self.store.add_chain_block_acceptance(&mut batch, block_hash, daa_score, acceptance_data)?;
+        }
+
+        // Commit the new sink
+        if let Some(sink) = added_chain_block_hashes.last().copied() {
+            self.store.set_sink(&mut batch, sink)?;
+        }
+        self.store.write_batch(batch)?;
+
+        // Follow the consensus pruning point
+        let pruning_point = session.pruning_point();
+        if self.store.get_pruning_point()? != pruning_point {
+            self.prune(&*session, pruning_point)?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes and reinstates the txindex database, syncing it from scratch via the consensus database.
+    ///
+    /// **Notes:**
+    /// 1) Only the selected chain from the pruning point to the sink and the blocks not yet merged by it are indexed.
+    /// 2) resyncing while consensus notifies of new blocks and chain changes, may result in a corrupted db.
+    fn resync(&mut self) -> TxIndexResult<()> {
+        info!("Resyncing the txindex...");
+
+        self.store.delete_all()?;
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        let pruning_point = session.pruning_point();
+        let sink = session.get_sink();
+        let chain_path = session.get_virtual_chain_from_block(pruning_point)?;
+        trace!("[{0}] resyncing {1} chain blocks from consensus db", IDENT, chain_path.added.len());
+
+        for chunk in chain_path.added.chunks(RESYNC_CHUNK_SIZE) {
+            let acceptance_data = session.get_blocks_acceptance_data(chunk)?;
+            for (chain_block_hash, acceptance_data) in chunk.iter().copied().zip(acceptance_data.iter()) {
+                for mergeset_block_data in acceptance_data.iter() {
+                    self.index_block_body(&*session, mergeset_block_data.block_hash)?;
+                }
+                let daa_score = session.get_header(chain_block_hash)?.daa_score;
+                let mut batch = WriteBatch::default();
+                self.store.add_chain_block_acceptance(&mut batch, chain_block_hash, daa_score, acceptance_data)?;
+                self.store.write_batch(batch)?;
+            }
+        }
+
+        // Blocks which are not merged by the sink yet are only indexed as including blocks.
+        match session.get_anticone(sink) {
+            Ok(anticone) => {
+                for block_hash in anticone {
+                    self.index_block_body(&*session, block_hash)?;
+                }
+            }
+            Err(err) => warn!("[{0}] could not index blocks in the anticone of the sink: {1}", IDENT, err),
+        }
+
+        // Commit to the remaining stores.
+
+        trace!("[{0}] committing consensus sink {1} and pruning point {2} from consensus db", IDENT, sink, pruning_point);
+        let mut batch = WriteBatch::default();
+        self.store.set_sink(&mut batch, sink)?;
+        self.store.set_pruning_point(&mut batch, pruning_point)?;
+        self.store.write_batch(batch)?;
+
+        Ok(())
+    }
+}
+
+impl Debug for TxIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TxIndex").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::TxIndexApi,
+        model::{TxAcceptance, TxInclusion},
+        TxIndex,
+    };
+    use waglayla_consensus::{config::Config, consensus::test_consensus::TestConsensus, params::DEVNET_PARAMS};
+    use waglayla_consensus_core::{api::ConsensusApi, tx::TransactionId};
+    use waglayla_consensusmanager::ConsensusManager;
+    use waglayla_database::create_temp_db;
+    use waglayla_database::prelude::ConnBuilder;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_txindex() {
+        waglayla_core::log::try_init_logger("INFO");
+
+        // Initialize a test consensus with a short selected chain: genesis <- 1 <- 2 <- 3
+        let (_txindex_db_lifetime, txindex_db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let config = Config::new(DEVNET_PARAMS);
+        let tc = Arc::new(TestConsensus::new(&config));
+        let wait_handles = tc.init();
+        tc.add_utxo_valid_block_with_parents(1.into(), vec![config.genesis.hash], vec![]).await.unwrap();
+        tc.add_utxo_valid_block_with_parents(2.into(), vec![1.into()], vec![]).await.unwrap();
+        tc.add_utxo_valid_block_with_parents(3.into(), vec![2.into()], vec![]).await.unwrap();
+
+        // Sync the txindex from scratch.
+        let consensus_manager = Arc::new(ConsensusManager::from_consensus(tc.consensus_clone()));
+        let txindex = TxIndex::new(consensus_manager, txindex_db).unwrap();
+        assert!(txindex.read().is_synced().expect("expected bool"));
+        assert_eq!(txindex.read().get_txindex_sink().expect("expected sink"), 3.into());
+
+        // The coinbase of block 2 is included by block 2 and accepted by its selected child 3.
+        let coinbase_id = tc.get_block(2.into()).unwrap().transactions[0].id();
+        let entry = txindex.read().get_transaction_entry(coinbase_id).unwrap().expect("expected the transaction to be indexed");
+        assert_eq!(entry.inclusions, vec![TxInclusion::new(2.into(), 0)]);
+        assert_eq!(entry.acceptance, Some(TxAcceptance::new(3.into(), 2.into(), 0)));
+
+        // The coinbase of the sink is included but not accepted yet.
+        let sink_coinbase_id = tc.get_block(3.into()).unwrap().transactions[0].id();
+        let entry = txindex.read().get_transaction_entry(sink_coinbase_id).unwrap().expect("expected the transaction to be indexed");
+        assert_eq!(entry.inclusions, vec![TxInclusion::new(3.into(), 0)]);
+        assert_eq!(entry.acceptance, None);
+
+        // Unknown transactions are not found.
+        assert!(txindex.read().get_transaction_entry(TransactionId::from_bytes([1; 32])).unwrap().is_none());
+
+        // Removing block 3 from the selected chain drops the acceptances it carried, but not the inclusions.
+        txindex.write().update_via_virtual_chain_changed(Arc::new(vec![]), Arc::new(vec![3.into()]), Arc::new(vec![])).unwrap();
+        let entry = txindex.read().get_transaction_entry(coinbase_id).unwrap().expect("expected the transaction to be indexed");
+        assert_eq!(entry.inclusions, vec![TxInclusion::new(2.into(), 0)]);
+        assert_eq!(entry.acceptance, None);
+
+        tc.shutdown(wait_handles);
+    }
+}
@@ -0,0 +1,3 @@
+mod blocks;
+pub mod store_manager;
+mod transactions;
@@ -0,0 +1,177 @@
+use std::{collections::HashMap, sync::Arc};
+
+use waglayla_consensus_core::{acceptance_data::AcceptanceData, tx::TransactionId};
+use waglayla_core::trace;
+use waglayla_database::{
+    prelude::{CachePolicy, StoreResult, WriteBatch, DB},
+    registry::DatabaseStorePrefixes,
+};
+use waglayla_hashes::Hash;
+use waglayla_index_core::sync_points::{DbIndexSyncPointStore, IndexSyncPointStore, IndexSyncPointStoreReader};
+
+use crate::{
+    model::{TxAcceptance, TxInclusion},
+    stores::{
+        blocks::{DbTxIndexBlocksStore, TxIndexBlock, TxIndexBlocksStore, TxIndexBlocksStoreReader},
+        transactions::{
+            DbTxAcceptancesStore, DbTxInclusionsStore, TxAcceptancesStore, TxAcceptancesStoreReader, TxInclusionsStore,
+            TxInclusionsStoreReader,
+        },
+    },
+    IDENT,
+};
+
+/// Gathers the txindex stores.
+///
+/// All mutating methods write into a [`WriteBatch`] which the caller commits with [`Store::write_batch`] once the
+/// whole update is gathered, so that a crash never leaves an update partly written. Since reads go to the DB,
+/// a batch should not hold several read-modify-write operations on the same key.
+#[derive(Clone)]
+pub struct Store {
+    db: Arc<DB>,
+    sink_store: DbIndexSyncPointStore,
+    pruning_point_store: DbIndexSyncPointStore,
+    blocks_store: DbTxIndexBlocksStore,
+    inclusions_store: DbTxInclusionsStore,
+    acceptances_store: DbTxAcceptancesStore,
+}
+
+impl Store {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self {
+            db: db.clone(),
+            sink_store: DbIndexSyncPointStore::new(db.clone(), DatabaseStorePrefixes::TxIndexSink),
+            pruning_point_store: DbIndexSyncPointStore::new(db.clone(), DatabaseStorePrefixes::TxIndexPruningPoint),
+            blocks_store: DbTxIndexBlocksStore::new(db.clone(), CachePolicy::Empty),
+            inclusions_store: DbTxInclusionsStore::new(db.clone(), CachePolicy::Empty),
+            acceptances_store: DbTxAcceptancesStore::new(db, CachePolicy::Empty),
+        }
+    }
+
+    /// Atomically commits all the writes gathered in `batch`
+    pub fn write_batch(&self, batch: WriteBatch) -> StoreResult<()> {
+        Ok(self.db.write(batch)?)
+    }
+
+    pub fn get_inclusions(&self, transaction_id: TransactionId) -> StoreResult<Arc<Vec<TxInclusion>>> {
+        self.inclusions_store.get(transaction_id)
+    }
+
+    pub fn get_acceptance(&self, transaction_id: TransactionId) -> StoreResult<Option<TxAcceptance>> {
+        self.acceptances_store.get(transaction_id)
+    }
+
+    pub fn get_block(&self, block_hash: Hash) -> StoreResult<Option<Arc<TxIndexBlock>>> {
+        self.blocks_store.get(block_hash)
+    }
+
+    /// Indexes the transactions included in a block. Does nothing if the block body was already indexed.
+    pub fn add_block_transactions(
+        &mut self,
+        batch: &mut WriteBatch,
+        block_hash: Hash,
+        daa_score: u64,
+        transaction_ids: Vec<TransactionId>,
+    ) -> StoreResult<()> {
+        let mut block = self.blocks_store.get(block_hash)?.map_or_else(|| TxIndexBlock::new(daa_score), |block| (*block).clone());
+        if !block.included_transaction_ids.is_empty() {
+            return Ok(());
+        }
+        for (index, transaction_id) in transaction_ids.iter().copied().enumerate() {
+            self.inclusions_store.add_inclusion(batch, transaction_id, TxInclusion::new(block_hash, index as u32))?;
+        }
+        block.included_transaction_ids = transaction_ids;
+        self.blocks_store.set(batch, block_hash, Arc::new(block))
+    }
+
+    /// Indexes the transactions accepted by a chain block.
+    pub fn add_chain_block_acceptance(
+        &mut self,
+        batch: &mut WriteBatch,
+        block_hash: Hash,
+        daa_score: u64,
+        acceptance_data: &AcceptanceData,
+    ) -> StoreResult<()> {
+        let mut accepted_transaction_ids = Vec::with_capacity(acceptance_data.iter().map(|x| x.accepted_transactions.len()).sum());
+        for mergeset_block_data in acceptance_data.iter() {
+            for entry in mergeset_block_data.accepted_transactions.iter() {
+                self.acceptances_store.set_acceptance(
+                    batch,
+                    entry.transaction_id,
+                    TxAcceptance::new(block_hash, mergeset_block_data.block_hash, entry.index_within_block),
+                )?;
+                accepted_transaction_ids.push(entry.transaction_id);
+            }
+        }
+        let mut block = self.blocks_store.get(block_hash)?.map_or_else(|| TxIndexBlock::new(daa_score), |block| (*block).clone());
+        block.accepted_transaction_ids = accepted_transaction_ids;
+        self.blocks_store.set(batch, block_hash, Arc::new(block))
+    }
+
+    /// Drops the acceptances of a block which was removed from the selected chain.
+    pub fn remove_chain_block_acceptance(&mut self, batch: &mut WriteBatch, block_hash: Hash) -> StoreResult<()> {
+        if let Some(block) = self.blocks_store.get(block_hash)? {
+            for transaction_id in block.accepted_transaction_ids.iter().copied() {
+                self.acceptances_store.remove_acceptance_by_block(batch, transaction_id, block_hash)?;
+            }
+            let mut block = (*block).clone();
+            block.accepted_transaction_ids.clear();
+            self.blocks_store.set(batch, block_hash, Arc::new(block))?;
+        }
+        Ok(())
+    }
+
+    /// Removes all data of indexed blocks with a DAA score lower than `daa_score`, returning the number of pruned blocks.
+    pub fn prune_below_daa_score(&mut self, batch: &mut WriteBatch, daa_score: u64) -> StoreResult<usize> {
+        let block_hashes = self.blocks_store.get_blocks_below_daa_score(daa_score)?;
+        // A transaction may be included by several pruned blocks, so its inclusions are rewritten only once
+        let mut pruned_inclusions: HashMap<TransactionId, Vec<Hash>> = HashMap::new();
+        for block_hash in block_hashes.iter().copied() {
+            if let Some(block) = self.blocks_store.get(block_hash)? {
+                for transaction_id in block.included_transaction_ids.iter().copied() {
+                    pruned_inclusions.entry(transaction_id).or_default().push(block_hash);
+                }
+                for transaction_id in block.accepted_transaction_ids.iter().copied() {
+                    self.acceptances_store.remove_acceptance_by_block(batch, transaction_id, block_hash)?;
+                }
+                self.blocks_store.delete(batch, block_hash)?;
+            }
+        }
+        for (transaction_id, block_hashes) in pruned_inclusions {
+            self.inclusions_store.remove_inclusions_from_blocks(batch, transaction_id, &block_hashes)?;
+        }
+        Ok(block_hashes.len())
+    }
+
+    pub fn get_sink(&self) -> StoreResult<Hash> {
+        self.sink_store.get()
+    }
+
+    pub fn set_sink(&mut self, batch: &mut WriteBatch, sink: Hash) -> StoreResult<()> {
+        self.sink_store.set_batch(batch, sink)
+    }
+
+    pub fn get_pruning_point(&self) -> StoreResult<Hash> {
+        self.pruning_point_store.get()
+    }
+
+    pub fn set_pruning_point(&mut self, batch: &mut WriteBatch, pruning_point: Hash) -> StoreResult<()> {
+        self.pruning_point_store.set_batch(batch, pruning_point)
+    }
+
+    /// Resets the txindex database:
+    pub fn delete_all(&mut self) -> StoreResult<()> {
+        trace!("[{0}] attempting to clear txindex database...", IDENT);
+
+        // Clear all
+        self.sink_store.remove()?;
+        self.pruning_point_store.remove()?;
+        self.blocks_store.delete_all()?;
+        self.inclusions_store.delete_all()?;
+        self.acceptances_store.delete_all()?;
+
+        trace!("[{0}] clearing txindex database - success!", IDENT);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use waglayla_consensus_core::tx::TransactionId;
+use waglayla_hashes::Hash;
+use waglayla_database::{
+    prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, DirectDbWriter, StoreError, StoreResult, WriteBatch, DB},
+    registry::DatabaseStorePrefixes,
+};
+
+use crate::model::{TxAcceptance, TxInclusion};
+
+/// Reader API for `TxInclusionsStore`.
+pub trait TxInclusionsStoreReader {
+    /// Returns the blocks including `transaction_id`, or an empty vector if no such block is indexed.
+    fn get(&self, transaction_id: TransactionId) -> StoreResult<Arc<Vec<TxInclusion>>>;
+}
+
+pub trait TxInclusionsStore: TxInclusionsStoreReader {
+    fn add_inclusion(&mut self, batch: &mut WriteBatch, transaction_id: TransactionId, inclusion: TxInclusion) -> StoreResult<()>;
+    /// Removes the inclusions of `transaction_id` by any of `block_hashes`
+    fn remove_inclusions_from_blocks(
+        &mut self,
+        batch: &mut WriteBatch,
+        transaction_id: TransactionId,
+        block_hashes: &[Hash],
+    ) -> StoreResult<()>;
+    fn delete_all(&mut self) -> StoreResult<()>;
+}
+
+/// A DB + cache implementation of `TxInclusionsStore` trait
+#[derive(Clone)]
+pub struct DbTxInclusionsStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<TransactionId, Arc<Vec<TxInclusion>>>,
+}
+
+impl DbTxInclusionsStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::TxIndexInclusions.into()) }
+    }
+}
+
+impl TxInclusionsStoreReader for DbTxInclusionsStore {
+    fn get(&self, transaction_id: TransactionId) -> StoreResult<Arc<Vec<TxInclusion>>> {
+        match self.access.read(transaction_id) {
+            Ok(inclusions) => Ok(inclusions),
+            Err(StoreError::KeyNotFound(_)) => Ok(Default::default()),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl TxInclusionsStore for DbTxInclusionsStore {
+    fn add_inclusion(&mut self, batch: &mut WriteBatch, transaction_id: TransactionId, inclusion: TxInclusion) -> StoreResult<()> {
+        let mut inclusions = self.get(transaction_id)?.as_ref().clone();
+        if inclusions.contains(&inclusion) {
+            return Ok(());
+        }
+        inclusions.push(inclusion);
+        self.access.write(BatchDbWriter::new(batch), transaction_id, Arc::new(inclusions))
+    }
+
+    fn remove_inclusions_from_blocks(
+        &mut self,
+        batch: &mut WriteBatch,
+        transaction_id: TransactionId,
+        block_hashes: &[Hash],
+    ) -> StoreResult<()> {
+        let mut inclusions = self.get(transaction_id)?.as_ref().clone();
+        inclusions.retain(|inclusion| !block_hashes.contains(&inclusion.block_hash));
+        if inclusions.is_empty() {
+            self.access.delete(BatchDbWriter::new(batch), transaction_id)
+        } else {
+            self.access.write(BatchDbWriter::new(batch), transaction_id, Arc::new(inclusions))
+        }
+    }
+
+    /// Removes all entries in the cache and db, besides prefixes themselves.
+    fn delete_all(&mut self) -> StoreResult<()> {
+        self.access.delete_all(DirectDbWriter::new(&self.db))
+    }
+}
+
+/// Reader API for `TxAcceptancesStore`.
+pub trait TxAcceptancesStoreReader {
+    /// Returns the acceptance of `transaction_id` by a chain block, if any is indexed.
+    fn get(&self, transaction_id: TransactionId) -> StoreResult<Option<TxAcceptance>>;
+}
+
+pub trait TxAcceptancesStore: TxAcceptancesStoreReader {
+    fn set_acceptance(&mut self, batch: &mut WriteBatch, transaction_id: TransactionId, acceptance: TxAcceptance) -> StoreResult<()>;
+    fn remove_acceptance_by_block(
+        &mut self,
+        batch: &mut WriteBatch,
+        transaction_id: TransactionId,
+        accepting_block_hash: Hash,
+    ) -> StoreResult<()>;
+    fn delete_all(&mut self) -> StoreResult<()>;
+}
+
+/// A DB + cache implementation of `TxAcceptancesStore` trait
+#[derive(Clone)]
+pub struct DbTxAcceptancesStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<TransactionId, TxAcceptance>,
+}
+
+impl DbTxAcceptancesStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::TxIndexAcceptances.into()) }
+    }
+}
+
+impl TxAcceptancesStoreReader for DbTxAcceptancesStore {
+    fn get(&self, transaction_id: TransactionId) -> StoreResult<Option<TxAcceptance>> {
+        match self.access.read(transaction_id) {
+            Ok(acceptance) => Ok(Some(acceptance)),
+            Err(StoreError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl TxAcceptancesStore for DbTxAcceptancesStore {
+    fn set_acceptance(&mut self, batch: &mut WriteBatch, transaction_id: TransactionId, acceptance: TxAcceptance) -> StoreResult<()> {
+        self.access.write(BatchDbWriter::new(batch), transaction_id, acceptance)
+    }
+
+    /// Removes the acceptance of `transaction_id` only if it was accepted by `accepting_block_hash`,
+    /// so a later acceptance by another chain block is never overwritten by a stale removal.
+    fn remove_acceptance_by_block(
+        &mut self,
+        batch: &mut WriteBatch,
+        transaction_id: TransactionId,
+        accepting_block_hash: Hash,
+    ) -> StoreResult<()> {
+        match self.get(transaction_id)? {
+            Some(acceptance) if acceptance.accepting_block_hash == accepting_block_hash => {
+                self.access.delete(BatchDbWriter::new(batch), transaction_id)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes all entries in the cache and db, besides prefixes themselves.
+    fn delete_all(&mut self) -> StoreResult<()> {
+        self.access.delete_all(DirectDbWriter::new(&self.db))
+    }
+}
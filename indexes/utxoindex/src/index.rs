@@ -12,6 +12,7 @@ use waglayla_core::{info, trace};
 use waglayla_database::prelude::{StoreError, StoreResult, DB};
 use waglayla_hashes::Hash;
 use waglayla_index_core::indexed_utxos::BalanceByScriptPublicKey;
+use waglayla_muhash::Blake2Hash;
 use waglayla_utils::arc::ArcExtensions;
 use parking_lot::RwLock;
 use std::{
@@ -40,6 +41,22 @@ impl UtxoIndex {
         consensus_manager.register_consensus_reset_handler(Arc::new(UtxoIndexConsensusResetHandler::new(Arc::downgrade(&utxoindex))));
         Ok(utxoindex)
     }
+
+    /// Reads the consensus tips the utxoindex database was last synced to, without requiring a running consensus.
+    /// Returns `None` if the database was never synced. Meant for offline inspection of the database.
+    pub fn read_synced_tips(db: Arc<DB>) -> UtxoIndexResult<Option<Arc<BlockHashSet>>> {
+        match Store::new(db).get_tips() {
+            Ok(tips) => Ok(Some(tips)),
+            Err(StoreError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(UtxoIndexError::StoreAccessError(err)),
+        }
+    }
+
+    /// Computes the MuHash of all UTXO entries in the utxoindex database, without requiring a running consensus.
+    /// When the index is synced, this is expected to equal the UTXO commitment of the consensus virtual UTXO set.
+    pub fn read_utxo_commitment(db: Arc<DB>) -> UtxoIndexResult<Blake2Hash> {
+        Ok(Store::new(db).get_utxo_commitment()?)
+    }
 }
 
 impl UtxoIndexApi for UtxoIndex {
@@ -1,13 +1,15 @@
 use crate::core::model::{CompactUtxoCollection, CompactUtxoEntry, UtxoSetByScriptPublicKey};
 
+use waglayla_consensus_core::muhash::MuHashExtensions;
 use waglayla_consensus_core::tx::{
-    ScriptPublicKey, ScriptPublicKeyVersion, ScriptPublicKeys, ScriptVec, TransactionIndexType, TransactionOutpoint,
+    ScriptPublicKey, ScriptPublicKeyVersion, ScriptPublicKeys, ScriptVec, TransactionIndexType, TransactionOutpoint, UtxoEntry,
 };
 use waglayla_core::debug;
-use waglayla_database::prelude::{CachePolicy, CachedDbAccess, DirectDbWriter, StoreResult, DB};
+use waglayla_database::prelude::{CachePolicy, CachedDbAccess, DirectDbWriter, StoreError, StoreResult, DB};
 use waglayla_database::registry::DatabaseStorePrefixes;
 use waglayla_hashes::Hash;
 use waglayla_index_core::indexed_utxos::BalanceByScriptPublicKey;
+use waglayla_muhash::{Blake2Hash, MuHash};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fmt::Display;
@@ -112,6 +114,10 @@ impl UtxoEntryFullAccessKey {
     pub fn extract_outpoint(&self) -> TransactionOutpoint {
         TransactionOutpoint::from(TransactionOutpointKey(self.0[(self.0.len() - TRANSACTION_OUTPOINT_KEY_SIZE)..].try_into().unwrap()))
     }
+
+    pub fn extract_script_public_key(&self) -> ScriptPublicKey {
+        ScriptPublicKeyBucket(self.0[..(self.0.len() - TRANSACTION_OUTPOINT_KEY_SIZE)].to_vec()).into()
+    }
 }
 
 impl AsRef<[u8]> for UtxoEntryFullAccessKey {
@@ -127,6 +133,9 @@ pub trait UtxoSetByScriptPublicKeyStoreReader {
     fn get_utxos_from_script_public_keys(&self, script_public_keys: ScriptPublicKeys) -> StoreResult<UtxoSetByScriptPublicKey>;
     fn get_balance_from_script_public_keys(&self, script_public_keys: ScriptPublicKeys) -> StoreResult<BalanceByScriptPublicKey>;
     fn get_all_outpoints(&self) -> StoreResult<HashSet<TransactionOutpoint>>; // This can have a big memory footprint, so it should be used only for tests.
+    /// Computes the MuHash of all indexed UTXO entries, comparable to the consensus UTXO set commitment.
+    /// This iterates the full index, so it should only be used for offline verification.
+    fn get_utxo_commitment(&self) -> StoreResult<Blake2Hash>;
 }
 
 pub trait UtxoSetByScriptPublicKeyStore: UtxoSetByScriptPublicKeyStoreReader {
@@ -204,6 +213,22 @@ impl UtxoSetByScriptPublicKeyStoreReader for DbUtxoSetByScriptPublicKeyStore {
             self.access.iterator().map(|res| UtxoEntryFullAccessKey(Arc::new(res.unwrap().0.to_vec())).extract_outpoint()),
         ))
     }
+
+    fn get_utxo_commitment(&self) -> StoreResult<Blake2Hash> {
+        let mut multiset = MuHash::new();
+        for res in self.access.iterator() {
+            let (key, compact_entry) = res.map_err(|err| StoreError::DataInconsistency(err.to_string()))?;
+            let key = UtxoEntryFullAccessKey(Arc::new(key.to_vec()));
+            let entry = UtxoEntry::new(
+                compact_entry.amount,
+                key.extract_script_public_key(),
+                compact_entry.block_daa_score,
+                compact_entry.is_coinbase,
+            );
+            multiset.add_utxo(&key.extract_outpoint(), &entry);
+        }
+        Ok(multiset.finalize())
+    }
 }
 
 impl UtxoSetByScriptPublicKeyStore for DbUtxoSetByScriptPublicKeyStore {
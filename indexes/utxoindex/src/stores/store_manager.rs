@@ -6,6 +6,7 @@ use waglayla_consensus_core::{
 };
 use waglayla_core::trace;
 use waglayla_database::prelude::{CachePolicy, StoreResult, DB};
+use waglayla_muhash::Blake2Hash;
 use waglayla_index_core::indexed_utxos::BalanceByScriptPublicKey;
 
 use crate::{
@@ -95,6 +96,10 @@ impl Store {
         self.utxoindex_tips_store.get()
     }
 
+    pub fn get_utxo_commitment(&self) -> StoreResult<Blake2Hash> {
+        self.utxos_by_script_public_key_store.get_utxo_commitment()
+    }
+
     pub fn set_tips(&mut self, tips: BlockHashSet, try_reset_on_err: bool) -> StoreResult<()> {
         let res = self.utxoindex_tips_store.set_tips(tips);
         if try_reset_on_err && res.is_err() {
@@ -1,5 +1,6 @@
 pub mod data;
 pub mod error;
+pub mod openmetrics;
 pub mod result;
 
 pub use data::{Metric, MetricGroup, MetricsData, MetricsSnapshot};
@@ -85,7 +86,7 @@ impl Metrics {
                         current_metrics_data = MetricsData::new(unixtime_as_millis_f64());
 
                         if let Some(rpc) = this.rpc() {
-                            if let Err(err) = this.sample_metrics(rpc.clone(), &mut current_metrics_data).await {
+                            if let Err(err) = Self::sample_metrics(rpc.clone(), &mut current_metrics_data).await {
                                 log_trace!("Metrics::sample_metrics() error: {}", err);
                             }
                         }
@@ -114,7 +115,7 @@ impl Metrics {
 
     // --- samplers
 
-    async fn sample_metrics(self: &Arc<Self>, rpc: Arc<dyn RpcApi>, data: &mut MetricsData) -> Result<()> {
+    pub async fn sample_metrics(rpc: Arc<dyn RpcApi>, data: &mut MetricsData) -> Result<()> {
         let GetMetricsResponse { server_time: _, consensus_metrics, connection_metrics, bandwidth_metrics, process_metrics } =
             rpc.get_metrics(true, true, true, true).await?;
 
@@ -0,0 +1,404 @@
+//!
+//! OpenMetrics text exposition of node metrics, suitable for scraping by Prometheus and compatible tooling.
+//!
+
+use crate::{data::MetricsData, result::Result, Metrics};
+use waglayla_rpc_core::{api::rpc::RpcApi, RpcPeerInfo};
+use std::{fmt::Write, sync::Arc};
+use workflow_core::time::unixtime_as_millis_f64;
+
+/// Content type of the OpenMetrics text exposition format
+pub const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Prefix of all exported metric family names
+const NAMESPACE: &str = "waglayla";
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Info,
+}
+
+impl MetricKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Info => "info",
+        }
+    }
+
+    /// Suffix which the OpenMetrics format requires on sample names of this kind
+    fn sample_suffix(&self) -> &'static str {
+        match self {
+            MetricKind::Counter => "_total",
+            MetricKind::Gauge => "",
+            MetricKind::Info => "_info",
+        }
+    }
+}
+
+pub type Labels<'a> = &'a [(&'a str, &'a str)];
+
+/// Incrementally builds an OpenMetrics text exposition
+#[derive(Default)]
+pub struct OpenMetricsEncoder {
+    text: String,
+}
+
+impl OpenMetricsEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes a metric family along with all its samples. `unit`, if provided, must also be the suffix of `name`.
+    pub fn family<'a>(
+        &mut self,
+        name: &str,
+        kind: MetricKind,
+        unit: Option<&str>,
+        help: &str,
+        samples: impl IntoIterator<Item = (Labels<'a>, f64)>,
+    ) -> &mut Self {
+        let name = format!("{NAMESPACE}_{name}");
+        writeln!(self.text, "# TYPE {name} {}", kind.as_str()).unwrap();
+        if let Some(unit) = unit {
+            debug_assert!(name.ends_with(unit));
+            writeln!(self.text, "# UNIT {name} {unit}").unwrap();
+        }
+        writeln!(self.text, "# HELP {name} {}", escape(help, false)).unwrap();
+        for (labels, value) in samples {
+            self.text.push_str(&name);
+            self.text.push_str(kind.sample_suffix());
+            if !labels.is_empty() {
+                self.text.push('{');
+                for (i, (label, label_value)) in labels.iter().enumerate() {
+                    if i > 0 {
+                        self.text.push(',');
+                    }
+                    write!(self.text, "{label}=\"{}\"", escape(label_value, true)).unwrap();
+                }
+                self.text.push('}');
+            }
+            writeln!(self.text, " {}", format_value(value)).unwrap();
+        }
+        self
+    }
+
+    /// Writes a metric family holding a single unlabeled sample
+    pub fn single(&mut self, name: &str, kind: MetricKind, unit: Option<&str>, help: &str, value: f64) -> &mut Self {
+        self.family(name, kind, unit, help, [(&[][..], value)])
+    }
+
+    /// Terminates the exposition and returns its text
+    pub fn finish(mut self) -> String {
+        self.text.push_str("# EOF\n");
+        self.text
+    }
+}
+
+fn escape(s: &str, quote: bool) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '"' if quote => escaped.push_str("\\\""),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Samples node metrics and connected peers through `rpc` and encodes them in the OpenMetrics text format
+pub async fn sample_openmetrics(rpc: Arc<dyn RpcApi>) -> Result<String> {
+    let mut data = MetricsData::new(unixtime_as_millis_f64());
+    Metrics::sample_metrics(rpc.clone(), &mut data).await?;
+    let peers = rpc.get_connected_peer_info().await?.peer_info;
+    Ok(encode_openmetrics(&data, &peers))
+}
+
+/// Encodes node metrics and per-peer p2p stats in the OpenMetrics text format
+pub fn encode_openmetrics(data: &MetricsData, peers: &[RpcPeerInfo]) -> String {
+    use MetricKind::*;
+
+    let mut encoder = OpenMetricsEncoder::new();
+
+    // --- process (perf monitor)
+    encoder
+        .single(
+            "node_resident_set_size_bytes",
+            Gauge,
+            Some("bytes"),
+            "Resident set size of the node process",
+            data.node_resident_set_size_bytes as f64,
+        )
+        .single(
+            "node_virtual_memory_size_bytes",
+            Gauge,
+            Some("bytes"),
+            "Virtual memory size of the node process",
+            data.node_virtual_memory_size_bytes as f64,
+        )
+        .single("node_cpu_cores", Gauge, None, "Number of CPU cores available to the node", data.node_cpu_cores as f64)
+        .single(
+            "node_cpu_usage",
+            Gauge,
+            None,
+            "CPU usage of the node process summed over all cores (1 = one fully used core)",
+            data.node_cpu_usage as f64,
+        )
+        .single("node_file_handles", Gauge, None, "Number of open file handles", data.node_file_handles as f64)
+        .family(
+            "node_disk_io_bytes",
+            Counter,
+            Some("bytes"),
+            "Bytes read from and written to disk by the node process",
+            [
+                (&[("direction", "read")][..], data.node_disk_io_read_bytes as f64),
+                (&[("direction", "write")][..], data.node_disk_io_write_bytes as f64),
+            ],
+        )
+        .family(
+            "node_disk_io_bytes_per_second",
+            Gauge,
+            None,
+            "Disk throughput of the node process as measured by the performance monitor",
+            [
+                (&[("direction", "read")][..], data.node_disk_io_read_per_sec as f64),
+                (&[("direction", "write")][..], data.node_disk_io_write_per_sec as f64),
+            ],
+        );
+
+    // --- connections
+    encoder
+        .family(
+            "node_rpc_live_connections",
+            Gauge,
+            None,
+            "Live wRPC connections",
+            [
+                (&[("encoding", "borsh")][..], data.node_borsh_live_connections as f64),
+                (&[("encoding", "json")][..], data.node_json_live_connections as f64),
+            ],
+        )
+        .family(
+            "node_rpc_connection_attempts",
+            Counter,
+            None,
+            "wRPC connection attempts",
+            [
+                (&[("encoding", "borsh")][..], data.node_borsh_connection_attempts as f64),
+                (&[("encoding", "json")][..], data.node_json_connection_attempts as f64),
+            ],
+        )
+        .family(
+            "node_rpc_handshake_failures",
+            Counter,
+            None,
+            "wRPC handshake failures",
+            [
+                (&[("encoding", "borsh")][..], data.node_borsh_handshake_failures as f64),
+                (&[("encoding", "json")][..], data.node_json_handshake_failures as f64),
+            ],
+        )
+        .single("node_active_peers", Gauge, None, "Active p2p peers", data.node_active_peers as f64);
+
+    // --- bandwidth
+    let bandwidth = [
+        ("borsh", data.node_borsh_bytes_tx, data.node_borsh_bytes_rx),
+        ("json", data.node_json_bytes_tx, data.node_json_bytes_rx),
+        ("p2p", data.node_p2p_bytes_tx, data.node_p2p_bytes_rx),
+        ("grpc", data.node_grpc_user_bytes_tx, data.node_grpc_user_bytes_rx),
+    ];
+    let bandwidth_labels = bandwidth
+        .map(|(interface, _, _)| ([("interface", interface), ("direction", "tx")], [("interface", interface), ("direction", "rx")]));
+    encoder.family(
+        "node_network_bytes",
+        Counter,
+        Some("bytes"),
+        "Bytes transferred by the node per interface and direction",
+        bandwidth
+            .iter()
+            .zip(bandwidth_labels.iter())
+            .flat_map(|((_, tx, rx), (tx_labels, rx_labels))| [(&tx_labels[..], *tx as f64), (&rx_labels[..], *rx as f64)]),
+    );
+
+    // --- consensus processing
+    encoder
+        .single("node_blocks_submitted", Counter, None, "Blocks submitted to consensus", data.node_blocks_submitted_count as f64)
+        .single("node_headers_processed", Counter, None, "Headers processed by consensus", data.node_headers_processed_count as f64)
+        .single(
+            "node_dependencies_processed",
+            Counter,
+            None,
+            "Block dependencies processed by consensus",
+            data.node_dependencies_processed_count as f64,
+        )
+        .single("node_bodies_processed", Counter, None, "Block bodies processed by consensus", data.node_bodies_processed_count as f64)
+        .single(
+            "node_transactions_processed",
+            Counter,
+            None,
+            "Transactions processed by consensus",
+            data.node_transactions_processed_count as f64,
+        )
+        .single(
+            "node_chain_blocks_processed",
+            Counter,
+            None,
+            "Chain blocks processed by consensus",
+            data.node_chain_blocks_processed_count as f64,
+        )
+        .single("node_mass_processed", Counter, None, "Transaction mass processed by consensus", data.node_mass_processed_count as f64)
+        .single("node_database_blocks", Gauge, None, "Blocks stored in the consensus database", data.node_database_blocks_count as f64)
+        .single(
+            "node_database_headers",
+            Gauge,
+            None,
+            "Headers stored in the consensus database",
+            data.node_database_headers_count as f64,
+        );
+
+    // --- network
+    encoder
+        .single("network_mempool_size", Gauge, None, "Transactions in the mempool", data.network_mempool_size as f64)
+        .single("network_tip_hashes", Gauge, None, "DAG tips", data.network_tip_hashes_count as f64)
+        .single("network_difficulty", Gauge, None, "Network difficulty", data.network_difficulty)
+        .single(
+            "network_past_median_time_seconds",
+            Gauge,
+            Some("seconds"),
+            "Past median time of the virtual block",
+            data.network_past_median_time as f64 / 1000.0,
+        )
+        .single(
+            "network_virtual_parent_hashes",
+            Gauge,
+            None,
+            "Parents of the virtual block",
+            data.network_virtual_parent_hashes_count as f64,
+        )
+        .single("network_virtual_daa_score", Gauge, None, "DAA score of the virtual block", data.network_virtual_daa_score as f64);
+
+    // --- per-peer p2p stats
+    let peer_labels = peers
+        .iter()
+        .map(|peer| {
+            (
+                peer.id.to_string(),
+                peer.address.to_string(),
+                if peer.is_outbound { "outbound" } else { "inbound" },
+                peer.user_agent.clone(),
+                peer.advertised_protocol_version.to_string(),
+                peer.is_ibd_peer.to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let peer_ids = peer_labels
+        .iter()
+        .map(|(id, address, direction, ..)| [("id", id.as_str()), ("address", address.as_str()), ("direction", *direction)])
+        .collect::<Vec<_>>();
+    let peer_info = peer_labels
+        .iter()
+        .map(|(id, address, direction, user_agent, protocol_version, is_ibd_peer)| {
+            [
+                ("id", id.as_str()),
+                ("address", address.as_str()),
+                ("direction", *direction),
+                ("user_agent", user_agent.as_str()),
+                ("protocol_version", protocol_version.as_str()),
+                ("ibd_peer", is_ibd_peer.as_str()),
+            ]
+        })
+        .collect::<Vec<_>>();
+    encoder
+        .family("peer", Info, None, "Connected p2p peer", peer_info.iter().map(|labels| (&labels[..], 1.0)))
+        .family(
+            "peer_last_ping_duration_seconds",
+            Gauge,
+            Some("seconds"),
+            "Duration of the last ping to the peer",
+            peer_ids.iter().zip(peers).map(|(labels, peer)| (&labels[..], peer.last_ping_duration as f64 / 1000.0)),
+        )
+        .family(
+            "peer_time_offset_seconds",
+            Gauge,
+            Some("seconds"),
+            "Clock offset of the peer relative to the node",
+            peer_ids.iter().zip(peers).map(|(labels, peer)| (&labels[..], peer.time_offset as f64 / 1000.0)),
+        )
+        .family(
+            "peer_connected_duration_seconds",
+            Gauge,
+            Some("seconds"),
+            "Time since the connection to the peer was established",
+            peer_ids.iter().zip(peers).map(|(labels, peer)| (&labels[..], peer.time_connected as f64 / 1000.0)),
+        )
+        .family(
+            "peer_misbehaviour_score",
+            Gauge,
+            None,
+            "Misbehaviour score of the peer",
+            peer_ids.iter().zip(peers).map(|(labels, peer)| (&labels[..], peer.misbehaviour_score as f64)),
+        );
+
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openmetrics_encoding() {
+        let mut encoder = OpenMetricsEncoder::new();
+        encoder
+            .single("node_resident_set_size_bytes", MetricKind::Gauge, Some("bytes"), "Resident set size", 1024.0)
+            .family(
+                "node_network_bytes",
+                MetricKind::Counter,
+                Some("bytes"),
+                "Network \\ bytes",
+                [(&[("interface", "p2p"), ("direction", "tx")][..], 10.0), (&[("interface", "a\"b"), ("direction", "rx")][..], 0.5)],
+            )
+            .single("node_cpu_usage", MetricKind::Gauge, None, "CPU", f64::NAN);
+        let expected = "\
+# TYPE waglayla_node_resident_set_size_bytes gauge
+# UNIT waglayla_node_resident_set_size_bytes bytes
+# HELP waglayla_node_resident_set_size_bytes Resident set size
+waglayla_node_resident_set_size_bytes 1024
+# TYPE waglayla_node_network_bytes counter
+# UNIT waglayla_node_network_bytes bytes
+# HELP waglayla_node_network_bytes Network \\\\ bytes
+waglayla_node_network_bytes_total{interface=\"p2p\",direction=\"tx\"} 10
+waglayla_node_network_bytes_total{interface=\"a\\\"b\",direction=\"rx\"} 0.5
+# TYPE waglayla_node_cpu_usage gauge
+# HELP waglayla_node_cpu_usage CPU
+waglayla_node_cpu_usage NaN
+# EOF
+";
+        assert_eq!(encoder.finish(), expected);
+    }
+
+    #[test]
+    fn test_encode_metrics_data() {
+        let data = MetricsData { node_p2p_bytes_rx: 42, network_virtual_daa_score: 7, ..Default::default() };
+        let text = encode_openmetrics(&data, &[]);
+        assert!(text.contains("waglayla_node_network_bytes_total{interface=\"p2p\",direction=\"rx\"} 42\n"));
+        assert!(text.contains("waglayla_network_virtual_daa_score 7\n"));
+        assert!(text.contains("# TYPE waglayla_peer info\n"));
+        assert!(text.ends_with("# EOF\n"));
+    }
+}
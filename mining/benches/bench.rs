@@ -1,6 +1,18 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use waglayla_mining::model::topological_index::TopologicalIndex;
-use std::collections::{hash_set::Iter, HashMap, HashSet};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{thread_rng, Rng};
+use waglayla_consensus_core::{
+    block::TemplateTransactionSelector,
+    subnets::SUBNETWORK_ID_NATIVE,
+    tx::{Transaction, TransactionInput, TransactionOutpoint},
+};
+use waglayla_hashes::Hash;
+use waglayla_mining::{
+    model::topological_index::TopologicalIndex, CandidateTransaction, FeerateTransactionKey, Frontier, Policy, TransactionsSelector,
+};
+use std::{
+    collections::{hash_set::Iter, HashMap, HashSet},
+    sync::Arc,
+};
 
 #[derive(Default)]
 pub struct Dag<T>
@@ -68,5 +80,76 @@ pub fn bench_compare_topological_index_fns(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_compare_topological_index_fns);
+fn generate_unique_tx(i: u64) -> Arc<Transaction> {
+    let input = TransactionInput::new(TransactionOutpoint::new(Hash::from_u64_word(i), 0), vec![], 0, 0);
+    Arc::new(Transaction::new(0, vec![input], vec![], 0, SUBNETWORK_ID_NATIVE, 0, vec![]))
+}
+
+/// Generates ready transactions with random feerates, where about 0.1% of them pay a much higher fee
+fn generate_candidates(count: u64) -> Vec<CandidateTransaction> {
+    let mut rng = thread_rng();
+    (0..count)
+        .map(|i| {
+            let fee = if i % 1000 == 0 { 1_000_000 } else { rng.gen_range(1..10_000) };
+            CandidateTransaction { tx: generate_unique_tx(i), calculated_fee: fee, calculated_mass: 1650 }
+        })
+        .collect()
+}
+
+fn build_frontier(candidates: &[CandidateTransaction]) -> Frontier {
+    let mut frontier = Frontier::default();
+    for item in candidates.iter() {
+        frontier.insert(FeerateTransactionKey::new(item.calculated_fee, item.calculated_mass, item.tx.clone()));
+    }
+    frontier
+}
+
+pub fn bench_compare_template_selectors(c: &mut Criterion) {
+    let mut group = c.benchmark_group("template selectors");
+    group.sample_size(10);
+    let policy = Policy::new(500_000);
+    for count in [1_000u64, 10_000, 100_000, 500_000] {
+        let candidates = generate_candidates(count);
+        let frontier = build_frontier(&candidates);
+        // Both benches include the cost of collecting the candidates from the mempool state
+        group.bench_with_input(BenchmarkId::new("rebalancing selector", count), &candidates, |b, candidates| {
+            b.iter(|| {
+                let mut selector = TransactionsSelector::new(policy.clone(), candidates.clone());
+                black_box(TemplateTransactionSelector::select_transactions(&mut selector))
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("frontier selector", count), &frontier, |b, frontier| {
+            b.iter(|| {
+                let mut selector = frontier.build_selector(&policy, usize::MAX);
+                black_box(selector.select_transactions())
+            })
+        });
+    }
+    group.finish();
+}
+
+pub fn bench_frontier_updates(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frontier updates");
+    let candidates = generate_candidates(100_000);
+    let mut frontier = build_frontier(&candidates);
+    let keys = candidates
+        .iter()
+        .step_by(100)
+        .map(|item| FeerateTransactionKey::new(item.calculated_fee, item.calculated_mass, item.tx.clone()))
+        .collect::<Vec<_>>();
+    group.bench_function("remove and insert 1000 keys out of 100K", |b| {
+        b.iter(|| {
+            for key in keys.iter() {
+                frontier.remove(key);
+            }
+            for key in keys.iter().cloned() {
+                frontier.insert(key);
+            }
+            black_box(frontier.total_weight())
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_compare_topological_index_fns, bench_compare_template_selectors, bench_frontier_updates);
 criterion_main!(benches);
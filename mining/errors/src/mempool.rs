@@ -75,6 +75,21 @@ pub enum RuleError {
 
     #[error("Rejected tx {0} from mempool due to incomputable storage mass")]
     RejectStorageMassIncomputable(TransactionId),
+
+    #[error("replace by fee found no double spending transaction in the mempool")]
+    RejectRbfNoDoubleSpend,
+
+    #[error("replace by fee found more than one double spending transaction in the mempool")]
+    RejectRbfTooManyDoubleSpendingTransactions,
+
+    #[error("replacement transaction {0} has a fee rate of {1} which is not higher than the fee rate {2} of double spending transaction {3}")]
+    RejectRbfInsufficientFeerate(TransactionId, f64, f64, TransactionId),
+
+    #[error("replacement transaction {0} spends an output of transaction {1} which it would replace")]
+    RejectRbfSpendsReplacedTransaction(TransactionId, TransactionId),
+
+    #[error("orphan transaction {0} cannot replace mempool transactions")]
+    RejectRbfOrphan(TransactionId),
 }
 
 impl From<NonStandardError> for RuleError {
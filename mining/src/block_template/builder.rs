@@ -1,8 +1,7 @@
-use super::{errors::BuilderResult, policy::Policy};
-use crate::{block_template::selector::TransactionsSelector, model::candidate_tx::CandidateTransaction};
+use super::errors::BuilderResult;
 use waglayla_consensus_core::{
     api::ConsensusApi,
-    block::{BlockTemplate, TemplateBuildMode},
+    block::{BlockTemplate, TemplateBuildMode, TemplateTransactionSelector},
     coinbase::MinerData,
     merkle::calc_hash_merkle_root,
     tx::COINBASE_TRANSACTION_INDEX,
@@ -12,14 +11,11 @@ use waglayla_core::{
     time::{unix_now, Stopwatch},
 };
 
-pub(crate) struct BlockTemplateBuilder {
-    policy: Policy,
-}
+pub(crate) struct BlockTemplateBuilder {}
 
 impl BlockTemplateBuilder {
-    pub(crate) fn new(max_block_mass: u64) -> Self {
-        let policy = Policy::new(max_block_mass);
-        Self { policy }
+    pub(crate) fn new() -> Self {
+        Self {}
     }
 
     /// BuildBlockTemplate creates a block template for a miner to consume
@@ -89,12 +85,11 @@ impl BlockTemplateBuilder {
         &self,
         consensus: &dyn ConsensusApi,
         miner_data: &MinerData,
-        transactions: Vec<CandidateTransaction>,
+        selector: Box<dyn TemplateTransactionSelector>,
         build_mode: TemplateBuildMode,
     ) -> BuilderResult<BlockTemplate> {
         let _sw = Stopwatch::<20>::with_threshold("build_block_template op");
-        debug!("Considering {} transactions for a new block template", transactions.len());
-        let selector = Box::new(TransactionsSelector::new(self.policy.clone(), transactions));
+        debug!("Building a new block template");
         Ok(consensus.build_block_template(miner_data.clone(), selector, build_mode)?)
     }
 
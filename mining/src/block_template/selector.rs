@@ -18,7 +18,7 @@ use waglayla_consensus_core::{
 /// candidate transactions should be. A smaller alpha makes the distribution
 /// more uniform. ALPHA is used when determining a candidate transaction's
 /// initial p value.
-const ALPHA: i32 = 3;
+pub(crate) const ALPHA: i32 = 3;
 
 /// REBALANCE_THRESHOLD is the percentage of candidate transactions under which
 /// we don't rebalance. Rebalancing is a heavy operation so we prefer to avoid
@@ -28,7 +28,7 @@ const ALPHA: i32 = 3;
 /// if REBALANCE_THRESHOLD is 0.95, there's a 1-in-20 chance of collision.
 const REBALANCE_THRESHOLD: f64 = 0.95;
 
-pub(crate) struct TransactionsSelector {
+pub struct TransactionsSelector {
     policy: Policy,
     /// Transaction store
     transactions: Vec<CandidateTransaction>,
@@ -53,7 +53,7 @@ pub(crate) struct TransactionsSelector {
 }
 
 impl TransactionsSelector {
-    pub(crate) fn new(policy: Policy, mut transactions: Vec<CandidateTransaction>) -> Self {
+    pub fn new(policy: Policy, mut transactions: Vec<CandidateTransaction>) -> Self {
         let _sw = Stopwatch::<100>::with_threshold("TransactionsSelector::new op");
         // Sort the transactions by subnetwork_id.
         transactions.sort_by(|a, b| a.tx.subnetwork_id.cmp(&b.tx.subnetwork_id));
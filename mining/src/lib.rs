@@ -8,6 +8,7 @@ use mempool::tx::Priority;
 mod block_template;
 pub(crate) mod cache;
 pub mod errors;
+pub mod feerate;
 pub mod manager;
 mod manager_tests;
 pub mod mempool;
@@ -17,6 +18,10 @@ pub mod monitor;
 #[cfg(test)]
 pub mod testutils;
 
+pub use block_template::{policy::Policy, selector::TransactionsSelector};
+pub use mempool::model::frontier::{feerate_key::FeerateTransactionKey, search_tree::SearchTree, Frontier};
+pub use model::candidate_tx::CandidateTransaction;
+
 pub struct MiningCounters {
     pub creation_time: Instant,
 
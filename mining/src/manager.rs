@@ -2,19 +2,21 @@ use crate::{
     block_template::{builder::BlockTemplateBuilder, errors::BuilderError},
     cache::BlockTemplateCache,
     errors::MiningManagerResult,
+    feerate::FeerateEstimations,
     mempool::{
         config::Config,
         model::tx::{MempoolTransaction, TxRemovalReason},
         populate_entries_and_try_validate::{
             populate_mempool_transactions_in_parallel, validate_mempool_transaction, validate_mempool_transactions_in_parallel,
         },
-        tx::{Orphan, Priority},
+        tx::{Orphan, Priority, RbfPolicy},
+        validate_and_insert_transaction::TransactionPostValidation,
         Mempool,
     },
     model::{
-        candidate_tx::CandidateTransaction,
         owner_txs::{GroupedOwnerTransactions, ScriptPublicKeySet},
         topological_sort::IntoIterTopologically,
+        tx_insert::TransactionInsertion,
         tx_query::TransactionQuery,
     },
     MempoolCountersSnapshot, MiningCounters, P2pTxCountSample,
@@ -22,13 +24,14 @@ use crate::{
 use itertools::Itertools;
 use waglayla_consensus_core::{
     api::ConsensusApi,
-    block::{BlockTemplate, TemplateBuildMode},
+    block::{BlockTemplate, TemplateBuildMode, TemplateTransactionSelector},
     coinbase::MinerData,
     errors::{block::RuleError as BlockRuleError, tx::TxRuleError},
     tx::{MutableTransaction, Transaction, TransactionId, TransactionOutput},
 };
 use waglayla_consensusmanager::{spawn_blocking, ConsensusProxy};
 use waglayla_core::{debug, error, info, time::Stopwatch, warn};
+use waglayla_hashes::Hash;
 use waglayla_mining_errors::{manager::MiningManagerError, mempool::RuleError};
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -103,14 +106,14 @@ impl MiningManager {
         loop {
             attempts += 1;
 
-            let transactions = self.block_candidate_transactions();
-            let block_template_builder = BlockTemplateBuilder::new(self.config.maximum_mass_per_block);
+            let selector = self.build_selector();
+            let block_template_builder = BlockTemplateBuilder::new();
             let build_mode = if attempts < self.config.maximum_build_block_template_attempts {
                 TemplateBuildMode::Standard
             } else {
                 TemplateBuildMode::Infallible
             };
-            match block_template_builder.build_block_template(consensus, miner_data, transactions, build_mode) {
+            match block_template_builder.build_block_template(consensus, miner_data, selector, build_mode) {
                 Ok(block_template) => {
                     let block_template = cache_lock.set_immutable_cached_template(block_template);
                     match attempts {
@@ -193,10 +196,25 @@ impl MiningManager {
         }
     }
 
-    pub(crate) fn block_candidate_transactions(&self) -> Vec<CandidateTransaction> {
+    /// Builds a transaction selector over the current mempool ready transactions
+    pub(crate) fn build_selector(&self) -> Box<dyn TemplateTransactionSelector> {
+        self.mempool.read().build_selector()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn block_candidate_transactions(&self) -> Vec<crate::model::candidate_tx::CandidateTransaction> {
         self.mempool.read().block_candidate_transactions()
     }
 
+    /// Returns realtime feerate estimations based on internal mempool state
+    pub fn get_realtime_feerate_estimations(&self) -> FeerateEstimations {
+        let (estimator, minimum_feerate) = {
+            let mempool = self.mempool.read();
+            (mempool.build_feerate_estimator(), self.config.minimum_feerate())
+        };
+        estimator.calc_estimations(minimum_feerate)
+    }
+
     /// Clears the block template cache, forcing the next call to get_block_template to build a new block template.
     #[cfg(test)]
     pub(crate) fn clear_block_template(&self) {
@@ -205,13 +223,16 @@ impl MiningManager {
 
     #[cfg(test)]
     pub(crate) fn block_template_builder(&self) -> BlockTemplateBuilder {
-        BlockTemplateBuilder::new(self.config.maximum_mass_per_block)
+        BlockTemplateBuilder::new()
     }
 
     /// validate_and_insert_transaction validates the given transaction, and
     /// adds it to the set of known transactions that have not yet been
     /// added to any block.
     ///
+    /// Depending on `rbf_policy`, the transaction may replace the mempool transactions it double spends
+    /// (see [`RbfPolicy`]). The replaced and evicted transactions are reported in the returned insertion.
+    ///
     /// The returned transactions are clones of objects owned by the mempool.
     pub fn validate_and_insert_transaction(
         &self,
@@ -219,8 +240,9 @@ impl MiningManager {
         transaction: Transaction,
         priority: Priority,
         orphan: Orphan,
-    ) -> MiningManagerResult<Vec<Arc<Transaction>>> {
-        self.validate_and_insert_mutable_transaction(consensus, MutableTransaction::from_tx(transaction), priority, orphan)
+        rbf_policy: RbfPolicy,
+    ) -> MiningManagerResult<TransactionInsertion> {
+        self.validate_and_insert_mutable_transaction(consensus, MutableTransaction::from_tx(transaction), priority, orphan, rbf_policy)
     }
 
     /// Exposed only for tests. Ordinary users should call `validate_and_insert_transaction` instead
@@ -230,29 +252,30 @@ impl MiningManager {
         transaction: MutableTransaction,
         priority: Priority,
         orphan: Orphan,
-    ) -> MiningManagerResult<Vec<Arc<Transaction>>> {
+        rbf_policy: RbfPolicy,
+    ) -> MiningManagerResult<TransactionInsertion> {
         // read lock on mempool
-        let mut transaction = self.mempool.read().pre_validate_and_populate_transaction(consensus, transaction)?;
+        let mut transaction = self.mempool.read().pre_validate_and_populate_transaction(consensus, transaction, rbf_policy)?;
         // no lock on mempool
         let validation_result = validate_mempool_transaction(consensus, &mut transaction);
         // write lock on mempool
         let mut mempool = self.mempool.write();
-        if let Some(accepted_transaction) =
-            mempool.post_validate_and_insert_transaction(consensus, validation_result, transaction, priority, orphan)?
-        {
-            let unorphaned_transactions = mempool.get_unorphaned_transactions_after_accepted_transaction(&accepted_transaction);
-            drop(mempool);
-
-            // The capacity used here may be exceeded since accepted unorphaned transaction may themselves unorphan other transactions.
-            let mut accepted_transactions = Vec::with_capacity(unorphaned_transactions.len() + 1);
-            // We include the original accepted transaction as well
-            accepted_transactions.push(accepted_transaction);
-            accepted_transactions.extend(self.validate_and_insert_unorphaned_transactions(consensus, unorphaned_transactions));
-            self.counters.increase_tx_counts(1, priority);
-
-            Ok(accepted_transactions)
-        } else {
-            Ok(vec![])
+        match mempool.post_validate_and_insert_transaction(consensus, validation_result, transaction, priority, orphan, rbf_policy)? {
+            TransactionPostValidation { replacement, accepted: Some(accepted_transaction) } => {
+                let unorphaned_transactions = mempool.get_unorphaned_transactions_after_accepted_transaction(&accepted_transaction);
+                drop(mempool);
+
+                // The capacity used here may be exceeded since accepted unorphaned transaction may themselves unorphan other transactions.
+                let mut accepted_transactions = Vec::with_capacity(unorphaned_transactions.len() + 1);
+                // We include the original accepted transaction as well
+                accepted_transactions.push(accepted_transaction);
+                accepted_transactions.extend(self.validate_and_insert_unorphaned_transactions(consensus, unorphaned_transactions));
+                self.counters.increase_tx_counts(1, priority);
+
+                let (replaced, evicted) = replacement.map_or((None, vec![]), |x| (Some(x.replaced), x.evicted));
+                Ok(TransactionInsertion::new(replaced, evicted, accepted_transactions))
+            }
+            TransactionPostValidation { replacement: _, accepted: None } => Ok(TransactionInsertion::default()),
         }
     }
 
@@ -297,13 +320,14 @@ impl MiningManager {
                         transaction,
                         priority,
                         Orphan::Forbidden,
+                        RbfPolicy::Forbidden,
                     ) {
-                        Ok(Some(accepted_transaction)) => {
+                        Ok(TransactionPostValidation { replacement: _, accepted: Some(accepted_transaction) }) => {
                             accepted_transactions.push(accepted_transaction.clone());
                             self.counters.increase_tx_counts(1, priority);
                             mempool.get_unorphaned_transactions_after_accepted_transaction(&accepted_transaction)
                         }
-                        Ok(None) => vec![],
+                        Ok(TransactionPostValidation { replacement: _, accepted: None }) => vec![],
                         Err(err) => {
                             debug!("Failed to unorphan transaction {0} due to rule error: {1}", orphan_id, err);
                             vec![]
@@ -344,7 +368,7 @@ impl MiningManager {
             let mempool = self.mempool.read();
             let txs = chunk.filter_map(|tx| {
                 let transaction_id = tx.id();
-                match mempool.pre_validate_and_populate_transaction(consensus, tx) {
+                match mempool.pre_validate_and_populate_transaction(consensus, tx, RbfPolicy::Forbidden) {
                     Ok(tx) => Some(tx),
                     Err(RuleError::RejectAlreadyAccepted(transaction_id)) => {
                         debug!("Ignoring already accepted transaction {}", transaction_id);
@@ -386,13 +410,20 @@ impl MiningManager {
             let mut mempool = self.mempool.write();
             let txs = chunk.flat_map(|(transaction, validation_result)| {
                 let transaction_id = transaction.id();
-                match mempool.post_validate_and_insert_transaction(consensus, validation_result, transaction, priority, orphan) {
-                    Ok(Some(accepted_transaction)) => {
+                match mempool.post_validate_and_insert_transaction(
+                    consensus,
+                    validation_result,
+                    transaction,
+                    priority,
+                    orphan,
+                    RbfPolicy::Forbidden,
+                ) {
+                    Ok(TransactionPostValidation { replacement: _, accepted: Some(accepted_transaction) }) => {
                         insert_results.push(Ok(accepted_transaction.clone()));
                         self.counters.increase_tx_counts(1, priority);
                         mempool.get_unorphaned_transactions_after_accepted_transaction(&accepted_transaction)
                     }
-                    Ok(None) => {
+                    Ok(TransactionPostValidation { replacement: _, accepted: None }) => {
                         // Either orphaned or already existing in the mempool
                         vec![]
                     }
@@ -441,6 +472,12 @@ impl MiningManager {
         self.mempool.read().has_transaction(transaction_id, query)
     }
 
+    /// Returns the mempool transactions matching the short ids of the compact block with hash `block_hash`, by position.
+    /// A position is `None` if the mempool holds no transaction or more than a single transaction with its short id.
+    pub fn get_transactions_by_short_ids(&self, block_hash: &Hash, short_ids: &[u64]) -> Vec<Option<Arc<Transaction>>> {
+        self.mempool.read().get_transactions_by_short_ids(block_hash, short_ids)
+    }
+
     pub fn get_all_transactions(&self, query: TransactionQuery) -> (Vec<MutableTransaction>, Vec<MutableTransaction>) {
         const TRANSACTION_CHUNK_SIZE: usize = 1000;
         // read lock on mempool by transaction chunks
@@ -756,9 +793,16 @@ impl MiningManagerProxy {
         consensus.clone().spawn_blocking(move |c| self.inner.get_block_template(c, &miner_data)).await
     }
 
+    /// Returns realtime feerate estimations based on internal mempool state
+    pub async fn get_realtime_feerate_estimations(self) -> FeerateEstimations {
+        spawn_blocking(move || self.inner.get_realtime_feerate_estimations()).await.unwrap()
+    }
+
     /// Validates a transaction and adds it to the set of known transactions that have not yet been
     /// added to any block.
     ///
+    /// Depending on `rbf_policy`, the transaction may replace the mempool transactions it double spends.
+    ///
     /// The returned transactions are clones of objects owned by the mempool.
     pub async fn validate_and_insert_transaction(
         self,
@@ -766,8 +810,12 @@ impl MiningManagerProxy {
         transaction: Transaction,
         priority: Priority,
         orphan: Orphan,
-    ) -> MiningManagerResult<Vec<Arc<Transaction>>> {
-        consensus.clone().spawn_blocking(move |c| self.inner.validate_and_insert_transaction(c, transaction, priority, orphan)).await
+        rbf_policy: RbfPolicy,
+    ) -> MiningManagerResult<TransactionInsertion> {
+        consensus
+            .clone()
+            .spawn_blocking(move |c| self.inner.validate_and_insert_transaction(c, transaction, priority, orphan, rbf_policy))
+            .await
     }
 
     /// Validates a batch of transactions, handling iteratively only the independent ones, and
@@ -824,6 +872,12 @@ impl MiningManagerProxy {
         spawn_blocking(move || self.inner.has_transaction(&transaction_id, query)).await.unwrap()
     }
 
+    /// Returns the mempool transactions matching the short ids of the compact block with hash `block_hash`, by position.
+    /// A position is `None` if the mempool holds no transaction or more than a single transaction with its short id.
+    pub async fn get_transactions_by_short_ids(self, block_hash: Hash, short_ids: Vec<u64>) -> Vec<Option<Arc<Transaction>>> {
+        spawn_blocking(move || self.inner.get_transactions_by_short_ids(&block_hash, &short_ids)).await.unwrap()
+    }
+
     pub async fn transaction_count(self, query: TransactionQuery) -> usize {
         spawn_blocking(move || self.inner.transaction_count(query)).await.unwrap()
     }
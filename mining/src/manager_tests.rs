@@ -1,13 +1,13 @@
 #[cfg(test)]
 mod tests {
     use crate::{
-        block_template::builder::BlockTemplateBuilder,
+        block_template::{builder::BlockTemplateBuilder, policy::Policy, selector::TransactionsSelector},
         errors::{MiningManagerError, MiningManagerResult},
         manager::MiningManager,
         mempool::{
             config::{Config, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE},
             errors::RuleError,
-            tx::{Orphan, Priority},
+            tx::{Orphan, Priority, RbfPolicy},
         },
         model::{candidate_tx::CandidateTransaction, tx_query::TransactionQuery},
         testutils::consensus_mock::ConsensusMock,
@@ -20,6 +20,7 @@ mod tests {
         coinbase::MinerData,
         constants::{MAX_TX_IN_SEQUENCE_NUM, SOMPI_PER_WAGLAYLA, TX_VERSION},
         errors::tx::{TxResult, TxRuleError},
+        hashing::tx::short_id,
         mass::transaction_estimated_serialized_size,
         subnets::SUBNETWORK_ID_NATIVE,
         tx::{
@@ -52,6 +53,7 @@ mod tests {
                 transaction.clone(),
                 Priority::Low,
                 Orphan::Allowed,
+                RbfPolicy::Forbidden,
             );
             assert!(result.is_ok(), "inserting a valid transaction failed");
         }
@@ -100,6 +102,7 @@ mod tests {
             transaction_not_an_orphan.clone(),
             Priority::Low,
             Orphan::Allowed,
+            RbfPolicy::Forbidden,
         );
         assert!(result.is_ok(), "inserting the child transaction {} into the mempool failed", transaction_not_an_orphan.id());
         let (transactions_from_pool, _) = mining_manager.get_all_transactions(TransactionQuery::TransactionsOnly);
@@ -132,6 +135,7 @@ mod tests {
             transaction.tx.as_ref().clone(),
             Priority::Low,
             Orphan::Allowed,
+            RbfPolicy::Forbidden,
         ));
 
         assert_eq!(
@@ -158,6 +162,7 @@ mod tests {
             transaction.clone(),
             Priority::Low,
             Orphan::Allowed,
+            RbfPolicy::Forbidden,
         );
         assert!(result.is_ok(), "mempool should have accepted a valid transaction but did not");
 
@@ -167,6 +172,7 @@ mod tests {
             transaction.tx.as_ref().clone(),
             Priority::Low,
             Orphan::Allowed,
+            RbfPolicy::Forbidden,
         );
         assert!(result.is_err(), "mempool should refuse a double submit of the same transaction but accepts it");
         if let Err(MiningManagerError::MempoolError(RuleError::RejectDuplicate(transaction_id))) = result {
@@ -200,8 +206,13 @@ mod tests {
             transaction.id()
         );
 
-        let result =
-            mining_manager.validate_and_insert_transaction(consensus.as_ref(), transaction.clone(), Priority::Low, Orphan::Allowed);
+        let result = mining_manager.validate_and_insert_transaction(
+            consensus.as_ref(),
+            transaction.clone(),
+            Priority::Low,
+            Orphan::Allowed,
+            RbfPolicy::Forbidden,
+        );
         assert!(result.is_ok(), "the mempool should accept a valid transaction when it is able to populate its UTXO entries");
 
         let mut double_spending_transaction = transaction.clone();
@@ -217,6 +228,7 @@ mod tests {
             double_spending_transaction.clone(),
             Priority::Low,
             Orphan::Allowed,
+            RbfPolicy::Forbidden,
         );
         assert!(result.is_err(), "mempool should refuse a double spend transaction but accepts it");
         if let Err(MiningManagerError::MempoolError(RuleError::RejectDoubleSpendInMempool(_, transaction_id))) = result {
@@ -235,6 +247,197 @@ mod tests {
         }
     }
 
+    // test_replace_by_fee_in_mempool verifies that a transaction double spending some mempool transactions replaces them,
+    // along with their redeemers, only if allowed by the RBF policy and if paying a strictly higher fee rate.
+    #[test]
+    fn test_replace_by_fee_in_mempool() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        let funding_tx = create_transaction_without_input(vec![600 * SOMPI_PER_WAGLAYLA]);
+        consensus.add_transaction(funding_tx.clone(), 1);
+        let parent_tx = create_transaction(&funding_tx, 2 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let child_tx = create_transaction(&parent_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        for tx in [&parent_tx, &child_tx] {
+            let result = mining_manager.validate_and_insert_transaction(
+                consensus.as_ref(),
+                tx.clone(),
+                Priority::High,
+                Orphan::Forbidden,
+                RbfPolicy::Forbidden,
+            );
+            assert!(result.is_ok(), "the mempool should accept the valid transaction {}", tx.id());
+        }
+
+        // A mandatory replacement must double spend a mempool transaction
+        let unrelated_tx = create_child_and_parent_txs_and_add_parent_to_consensus(&consensus);
+        let result = mining_manager.validate_and_insert_transaction(
+            consensus.as_ref(),
+            unrelated_tx.clone(),
+            Priority::High,
+            Orphan::Forbidden,
+            RbfPolicy::Mandatory,
+        );
+        assert!(
+            matches!(result, Err(MiningManagerError::MempoolError(RuleError::RejectRbfNoDoubleSpend))),
+            "a mandatory replacement without double spend should be rejected but got {:?}",
+            result.map(|x| x.accepted)
+        );
+
+        // A replacement must pay a strictly higher fee rate than the transaction it double spends
+        let low_fee_tx = create_transaction(&funding_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        for rbf_policy in [RbfPolicy::Forbidden, RbfPolicy::Allowed, RbfPolicy::Mandatory] {
+            let result = mining_manager.validate_and_insert_transaction(
+                consensus.as_ref(),
+                low_fee_tx.clone(),
+                Priority::High,
+                Orphan::Forbidden,
+                rbf_policy,
+            );
+            match result {
+                Err(MiningManagerError::MempoolError(RuleError::RejectDoubleSpendInMempool(_, transaction_id)))
+                    if rbf_policy == RbfPolicy::Forbidden =>
+                {
+                    assert_eq!(parent_tx.id(), transaction_id);
+                }
+                Err(MiningManagerError::MempoolError(RuleError::RejectRbfInsufficientFeerate(_, _, _, transaction_id)))
+                    if rbf_policy != RbfPolicy::Forbidden =>
+                {
+                    assert_eq!(parent_tx.id(), transaction_id);
+                }
+                _ => {
+                    panic!("a low fee double spend with {:?} should be rejected but got {:?}", rbf_policy, result.map(|x| x.accepted))
+                }
+            }
+        }
+        assert!(mining_manager.has_transaction(&parent_tx.id(), TransactionQuery::TransactionsOnly));
+        assert!(mining_manager.has_transaction(&child_tx.id(), TransactionQuery::TransactionsOnly));
+
+        // A higher fee replacement evicts the double spent transaction and its redeemers
+        let high_fee_tx = create_transaction(&funding_tx, 3 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let result = mining_manager.validate_and_insert_transaction(
+            consensus.as_ref(),
+            high_fee_tx.clone(),
+            Priority::High,
+            Orphan::Forbidden,
+            RbfPolicy::Mandatory,
+        );
+        assert!(result.is_ok(), "the mempool should accept a higher fee replacement");
+        let insertion = result.unwrap();
+        assert_eq!(Some(parent_tx.id()), insertion.replaced.map(|x| x.id()));
+        let mut expected_evicted = vec![parent_tx.id(), child_tx.id()];
+        expected_evicted.sort();
+        assert_eq!(expected_evicted, insertion.evicted);
+        assert_eq!(vec![high_fee_tx.id()], insertion.accepted.iter().map(|x| x.id()).collect::<Vec<_>>());
+        let (transactions_from_pool, _) = mining_manager.get_all_transactions(TransactionQuery::TransactionsOnly);
+        assert_eq!(vec![high_fee_tx.id()], transactions_from_pool.iter().map(|x| x.id()).collect::<Vec<_>>());
+
+        // An optional replacement without double spend is a regular insertion
+        let result = mining_manager.validate_and_insert_transaction(
+            consensus.as_ref(),
+            unrelated_tx.clone(),
+            Priority::High,
+            Orphan::Forbidden,
+            RbfPolicy::Allowed,
+        );
+        assert!(result.is_ok(), "the mempool should accept a transaction without double spend");
+        let insertion = result.unwrap();
+        assert!(insertion.replaced.is_none() && insertion.evicted.is_empty());
+
+        // A transaction double spending two mempool transactions may only replace both optionally
+        let mut double_replacement_tx = create_transaction(&funding_tx, 4 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        double_replacement_tx.inputs.push(unrelated_tx.inputs[0].clone());
+        double_replacement_tx.finalize();
+        let result = mining_manager.validate_and_insert_transaction(
+            consensus.as_ref(),
+            double_replacement_tx.clone(),
+            Priority::High,
+            Orphan::Forbidden,
+            RbfPolicy::Mandatory,
+        );
+        assert!(
+            matches!(result, Err(MiningManagerError::MempoolError(RuleError::RejectRbfTooManyDoubleSpendingTransactions))),
+            "a mandatory replacement of two transactions should be rejected but got {:?}",
+            result.map(|x| x.accepted)
+        );
+        let mut expected_ids = vec![high_fee_tx.id(), unrelated_tx.id()];
+        expected_ids.sort();
+        let (transactions_from_pool, _) = mining_manager.get_all_transactions(TransactionQuery::TransactionsOnly);
+        let mut ids = transactions_from_pool.iter().map(|x| x.id()).collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(expected_ids, ids, "a rejected replacement should leave the mempool unchanged");
+        let result = mining_manager.validate_and_insert_transaction(
+            consensus.as_ref(),
+            double_replacement_tx.clone(),
+            Priority::High,
+            Orphan::Forbidden,
+            RbfPolicy::Allowed,
+        );
+        assert!(result.is_ok(), "the mempool should accept an optional replacement of two transactions");
+        assert_eq!(expected_ids, result.unwrap().evicted);
+        let (transactions_from_pool, _) = mining_manager.get_all_transactions(TransactionQuery::TransactionsOnly);
+        assert_eq!(vec![double_replacement_tx.id()], transactions_from_pool.iter().map(|x| x.id()).collect::<Vec<_>>());
+    }
+
+    // test_replace_by_fee_in_full_mempool verifies that a replacement is accepted into a mempool filled with
+    // unremovable transactions since the replaced transaction frees its slot, and that a rejected replacement
+    // leaves the full mempool unchanged.
+    #[test]
+    fn test_replace_by_fee_in_full_mempool() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let mut config = Config::build_default(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS);
+        // Limit the transaction pool to 2 transactions
+        config.maximum_transaction_count = 2;
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::with_config(config, None, counters);
+
+        let funding_tx = create_transaction_without_input(vec![600 * SOMPI_PER_WAGLAYLA]);
+        consensus.add_transaction(funding_tx.clone(), 1);
+        let parent_tx = create_transaction(&funding_tx, 2 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let unrelated_tx = create_child_and_parent_txs_and_add_parent_to_consensus(&consensus);
+        for tx in [&parent_tx, &unrelated_tx] {
+            let result = mining_manager.validate_and_insert_transaction(
+                consensus.as_ref(),
+                tx.clone(),
+                Priority::High,
+                Orphan::Forbidden,
+                RbfPolicy::Forbidden,
+            );
+            assert!(result.is_ok(), "the mempool should accept the valid transaction {}", tx.id());
+        }
+
+        let low_fee_tx = create_transaction(&funding_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let result = mining_manager.validate_and_insert_transaction(
+            consensus.as_ref(),
+            low_fee_tx,
+            Priority::High,
+            Orphan::Forbidden,
+            RbfPolicy::Mandatory,
+        );
+        assert!(
+            matches!(result, Err(MiningManagerError::MempoolError(RuleError::RejectRbfInsufficientFeerate(..)))),
+            "a low fee replacement should be rejected but got {:?}",
+            result.map(|x| x.accepted)
+        );
+        assert!(mining_manager.has_transaction(&parent_tx.id(), TransactionQuery::TransactionsOnly));
+        assert_eq!(2, mining_manager.transaction_count(TransactionQuery::TransactionsOnly));
+
+        let high_fee_tx = create_transaction(&funding_tx, 3 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let result = mining_manager.validate_and_insert_transaction(
+            consensus.as_ref(),
+            high_fee_tx.clone(),
+            Priority::High,
+            Orphan::Forbidden,
+            RbfPolicy::Mandatory,
+        );
+        assert!(result.is_ok(), "the full mempool should accept a higher fee replacement but got {:?}", result.map(|x| x.accepted));
+        assert_eq!(vec![parent_tx.id()], result.unwrap().evicted);
+        assert!(mining_manager.has_transaction(&high_fee_tx.id(), TransactionQuery::TransactionsOnly));
+        assert!(mining_manager.has_transaction(&unrelated_tx.id(), TransactionQuery::TransactionsOnly));
+        assert_eq!(2, mining_manager.transaction_count(TransactionQuery::TransactionsOnly));
+    }
+
     // test_handle_new_block_transactions verifies that all the transactions in the block were successfully removed from the mempool.
     #[test]
     fn test_handle_new_block_transactions() {
@@ -250,6 +453,7 @@ mod tests {
                 transaction.tx.as_ref().clone(),
                 Priority::Low,
                 Orphan::Allowed,
+                RbfPolicy::Forbidden,
             );
             assert!(result.is_ok(), "the insertion of a new valid transaction in the mempool failed");
         }
@@ -308,6 +512,7 @@ mod tests {
             transaction_in_the_mempool.tx.as_ref().clone(),
             Priority::Low,
             Orphan::Allowed,
+            RbfPolicy::Forbidden,
         );
         assert!(result.is_ok());
 
@@ -340,8 +545,13 @@ mod tests {
         assert_eq!(parent_txs.len(), TX_PAIRS_COUNT);
         assert_eq!(child_txs.len(), TX_PAIRS_COUNT);
         for orphan in child_txs.iter() {
-            let result =
-                mining_manager.validate_and_insert_transaction(consensus.as_ref(), orphan.clone(), Priority::Low, Orphan::Allowed);
+            let result = mining_manager.validate_and_insert_transaction(
+                consensus.as_ref(),
+                orphan.clone(),
+                Priority::Low,
+                Orphan::Allowed,
+                RbfPolicy::Forbidden,
+            );
             assert!(result.is_ok(), "the mempool should accept the valid orphan transaction {}", orphan.id());
         }
         let (populated_txs, orphans) = mining_manager.get_all_transactions(TransactionQuery::All);
@@ -485,10 +695,15 @@ mod tests {
         );
 
         // Add the remaining parent transaction into the mempool
-        let result =
-            mining_manager.validate_and_insert_transaction(consensus.as_ref(), parent_txs[0].clone(), Priority::Low, Orphan::Allowed);
+        let result = mining_manager.validate_and_insert_transaction(
+            consensus.as_ref(),
+            parent_txs[0].clone(),
+            Priority::Low,
+            Orphan::Allowed,
+            RbfPolicy::Forbidden,
+        );
         assert!(result.is_ok(), "the insertion of the remaining parent transaction in the mempool failed");
-        let unorphaned_txs = result.unwrap();
+        let unorphaned_txs = result.unwrap().accepted;
         let (populated_txs, orphans) = mining_manager.get_all_transactions(TransactionQuery::All);
         assert_eq!(
             unorphaned_txs.len(), SKIPPED_TXS + 1,
@@ -592,8 +807,13 @@ mod tests {
 
         // Try submit children while rejecting orphans
         for (tx, test) in child_txs.iter().zip(tests.iter()) {
-            let result =
-                mining_manager.validate_and_insert_transaction(consensus.as_ref(), tx.clone(), test.priority, Orphan::Forbidden);
+            let result = mining_manager.validate_and_insert_transaction(
+                consensus.as_ref(),
+                tx.clone(),
+                test.priority,
+                Orphan::Forbidden,
+                RbfPolicy::Forbidden,
+            );
             assert!(result.is_err(), "mempool should reject an orphan transaction with {:?} when asked to do so", test.priority);
             if let Err(MiningManagerError::MempoolError(RuleError::RejectDisallowedOrphan(transaction_id))) = result {
                 assert_eq!(
@@ -613,8 +833,13 @@ mod tests {
 
         // Try submit children while accepting orphans
         for (tx, test) in child_txs.iter().zip(tests.iter()) {
-            let result =
-                mining_manager.validate_and_insert_transaction(consensus.as_ref(), tx.clone(), test.priority, Orphan::Allowed);
+            let result = mining_manager.validate_and_insert_transaction(
+                consensus.as_ref(),
+                tx.clone(),
+                test.priority,
+                Orphan::Allowed,
+                RbfPolicy::Forbidden,
+            );
             assert_eq!(
                 test.should_enter_orphan_pool,
                 result.is_ok(),
@@ -622,8 +847,11 @@ mod tests {
                 test.name,
                 test.insert_result()
             );
-            if let Ok(unorphaned_txs) = result {
-                assert!(unorphaned_txs.is_empty(), "mempool should unorphan no transaction since it only contains orphans");
+            if let Ok(transaction_insertion) = result {
+                assert!(
+                    transaction_insertion.accepted.is_empty(),
+                    "mempool should unorphan no transaction since it only contains orphans"
+                );
             } else if let Err(MiningManagerError::MempoolError(RuleError::RejectOrphanPoolIsFull(pool_len, config_len))) = result {
                 assert_eq!(
                     (config.maximum_orphan_transaction_count as usize, config.maximum_orphan_transaction_count),
@@ -642,10 +870,15 @@ mod tests {
 
         // Submit all the parents
         for (i, (tx, test)) in parent_txs.iter().zip(tests.iter()).enumerate() {
-            let result =
-                mining_manager.validate_and_insert_transaction(consensus.as_ref(), tx.clone(), test.priority, Orphan::Allowed);
+            let result = mining_manager.validate_and_insert_transaction(
+                consensus.as_ref(),
+                tx.clone(),
+                test.priority,
+                Orphan::Allowed,
+                RbfPolicy::Forbidden,
+            );
             assert!(result.is_ok(), "mempool should accept a valid transaction with {:?} when asked to do so", test.priority,);
-            let unorphaned_txs = result.as_ref().unwrap();
+            let unorphaned_txs = &result.as_ref().unwrap().accepted;
             assert_eq!(
                 test.should_unorphan,
                 unorphaned_txs.len() > 1,
@@ -682,8 +915,13 @@ mod tests {
 
         // Add to mempool a transaction that spends child_tx_2 (as high priority)
         let spending_tx = create_transaction(&child_tx_2, 1_000);
-        let result =
-            mining_manager.validate_and_insert_transaction(consensus.as_ref(), spending_tx.clone(), Priority::High, Orphan::Allowed);
+        let result = mining_manager.validate_and_insert_transaction(
+            consensus.as_ref(),
+            spending_tx.clone(),
+            Priority::High,
+            Orphan::Allowed,
+            RbfPolicy::Forbidden,
+        );
         assert!(result.is_ok(), "the insertion in the mempool of the spending transaction failed");
 
         // Revalidate, to make sure spending_tx is still valid
@@ -725,6 +963,109 @@ mod tests {
         assert!(orphan_txs.is_empty(), "orphan pool should be empty");
     }
 
+    // test_feerate_estimations verifies that the feerate estimations reflect the ready transactions of the mempool.
+    #[test]
+    fn test_feerate_estimations() {
+        const TX_COUNT: u32 = 10;
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+        let minimum_feerate = DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE as f64 / 1000.0;
+
+        // An empty mempool should suggest the minimum feerate for all buckets
+        let estimations = mining_manager.get_realtime_feerate_estimations();
+        for bucket in estimations.ordered_buckets() {
+            assert_eq!(minimum_feerate, bucket.feerate, "an empty mempool should suggest the minimum feerate");
+        }
+
+        let transactions_to_insert = (0..TX_COUNT).map(|i| create_transaction_with_utxo_entry(i, 0)).collect::<Vec<_>>();
+        for transaction in transactions_to_insert.iter() {
+            let result = mining_manager.validate_and_insert_mutable_transaction(
+                consensus.as_ref(),
+                transaction.clone(),
+                Priority::Low,
+                Orphan::Allowed,
+                RbfPolicy::Forbidden,
+            );
+            assert!(result.is_ok(), "inserting a valid transaction failed");
+        }
+
+        let estimations = mining_manager.get_realtime_feerate_estimations();
+        let buckets = estimations.ordered_buckets();
+        assert!(buckets.iter().all(|x| x.feerate >= minimum_feerate), "no bucket should go below the minimum feerate");
+        for (higher, lower) in buckets.iter().zip(buckets.iter().skip(1)) {
+            assert!(higher.feerate >= lower.feerate, "buckets should be ordered by descending feerate");
+            assert!(higher.estimated_seconds <= lower.estimated_seconds, "buckets should be ordered by ascending inclusion time");
+        }
+    }
+
+    // test_get_transactions_by_short_ids verifies that compact block short ids are resolved to mempool transactions.
+    #[test]
+    fn test_get_transactions_by_short_ids() {
+        const TX_COUNT: u32 = 10;
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+        let transactions_to_insert = (0..TX_COUNT).map(|i| create_transaction_with_utxo_entry(i, 0)).collect::<Vec<_>>();
+        for transaction in transactions_to_insert.iter() {
+            let result = mining_manager.validate_and_insert_mutable_transaction(
+                consensus.as_ref(),
+                transaction.clone(),
+                Priority::Low,
+                Orphan::Allowed,
+                RbfPolicy::Forbidden,
+            );
+            assert!(result.is_ok(), "inserting a valid transaction failed");
+        }
+
+        // Request every other mempool transaction along with a transaction unknown to the mempool
+        let block_hash = Hash::from_u64_word(1);
+        let unknown_transaction = create_transaction_with_utxo_entry(TX_COUNT, 0);
+        let requested = transactions_to_insert.iter().step_by(2).chain(std::iter::once(&unknown_transaction)).collect::<Vec<_>>();
+        let short_ids = requested.iter().map(|tx| short_id(&tx.id(), &block_hash)).collect::<Vec<_>>();
+        let transactions = mining_manager.get_transactions_by_short_ids(&block_hash, &short_ids);
+        assert_eq!(requested.len(), transactions.len());
+        for (requested, transaction) in requested.iter().zip(transactions.iter()).take(requested.len() - 1) {
+            assert_eq!(Some(requested.id()), transaction.as_ref().map(|tx| tx.id()), "mempool transaction was not resolved");
+        }
+        assert!(transactions.last().unwrap().is_none(), "unknown transaction should not be resolved");
+    }
+
+    // test_get_transactions_by_short_ids_in_large_mempool verifies that the short ids of a full compact block are
+    // resolved against a large mempool, every compact block requiring a scan of the whole mempool.
+    #[test]
+    fn test_get_transactions_by_short_ids_in_large_mempool() {
+        const TX_COUNT: u32 = 50_000;
+        const BLOCK_TX_COUNT: usize = 500;
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mut config = Config::build_default(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS);
+        config.maximum_transaction_count = TX_COUNT as u64;
+        let mining_manager = MiningManager::with_config(config, None, counters);
+        let transactions_to_insert = (0..TX_COUNT).map(|i| create_transaction_with_utxo_entry(i, 0)).collect::<Vec<_>>();
+        for transaction in transactions_to_insert.iter() {
+            let result = mining_manager.validate_and_insert_mutable_transaction(
+                consensus.as_ref(),
+                transaction.clone(),
+                Priority::Low,
+                Orphan::Forbidden,
+                RbfPolicy::Forbidden,
+            );
+            assert!(result.is_ok(), "inserting a valid transaction failed");
+        }
+        assert_eq!(TX_COUNT as usize, mining_manager.transaction_count(TransactionQuery::TransactionsOnly));
+
+        let block_hash = Hash::from_u64_word(1);
+        let step = TX_COUNT as usize / BLOCK_TX_COUNT;
+        let requested = transactions_to_insert.iter().step_by(step).collect::<Vec<_>>();
+        let short_ids = requested.iter().map(|tx| short_id(&tx.id(), &block_hash)).collect::<Vec<_>>();
+        let transactions = mining_manager.get_transactions_by_short_ids(&block_hash, &short_ids);
+        assert_eq!(BLOCK_TX_COUNT, transactions.len());
+        for (requested, transaction) in requested.iter().zip(transactions.iter()) {
+            assert_eq!(Some(requested.id()), transaction.as_ref().map(|tx| tx.id()), "mempool transaction was not resolved");
+        }
+    }
+
     // test_modify_block_template verifies that modifying a block template changes coinbase data correctly.
     #[test]
     fn test_modify_block_template() {
@@ -737,11 +1078,21 @@ mod tests {
         let (parent_txs, child_txs) = create_arrays_of_parent_and_children_transactions(&consensus, TX_PAIRS_COUNT);
 
         for (parent_tx, child_tx) in parent_txs.iter().zip(child_txs.iter()) {
-            let result =
-                mining_manager.validate_and_insert_transaction(consensus.as_ref(), parent_tx.clone(), Priority::Low, Orphan::Allowed);
+            let result = mining_manager.validate_and_insert_transaction(
+                consensus.as_ref(),
+                parent_tx.clone(),
+                Priority::Low,
+                Orphan::Allowed,
+                RbfPolicy::Forbidden,
+            );
             assert!(result.is_ok(), "the mempool should accept the valid parent transaction {}", parent_tx.id());
-            let result =
-                mining_manager.validate_and_insert_transaction(consensus.as_ref(), child_tx.clone(), Priority::Low, Orphan::Allowed);
+            let result = mining_manager.validate_and_insert_transaction(
+                consensus.as_ref(),
+                child_tx.clone(),
+                Priority::Low,
+                Orphan::Allowed,
+                RbfPolicy::Forbidden,
+            );
             assert!(result.is_ok(), "the mempool should accept the valid child transaction {}", parent_tx.id());
         }
 
@@ -848,7 +1199,8 @@ mod tests {
 
         // Build a fresh template for coinbase2 as a reference
         let builder = mining_manager.block_template_builder();
-        let result = builder.build_block_template(consensus, &miner_data_2, transactions, TemplateBuildMode::Standard);
+        let selector = Box::new(TransactionsSelector::new(Policy::new(MAX_BLOCK_MASS), transactions));
+        let result = builder.build_block_template(consensus, &miner_data_2, selector, TemplateBuildMode::Standard);
         assert!(result.is_ok(), "build block template failed for miner data 2");
         let expected_template = result.unwrap();
 
@@ -940,7 +1292,10 @@ mod tests {
         // Make the funding amounts always different so that funding txs have different ids
         (0..count)
             .map(|i| {
-                create_parent_and_children_transactions(consensus, vec![500 * SOMPI_PER_WAGLAYLA, 3_000 * SOMPI_PER_WAGLAYLA + i as u64])
+                create_parent_and_children_transactions(
+                    consensus,
+                    vec![500 * SOMPI_PER_WAGLAYLA, 3_000 * SOMPI_PER_WAGLAYLA + i as u64],
+                )
             })
             .unzip()
     }
@@ -47,6 +47,7 @@ pub struct Config {
     pub minimum_relay_transaction_fee: u64,
     pub minimum_standard_transaction_version: u16,
     pub maximum_standard_transaction_version: u16,
+    pub network_blocks_per_second: u64,
 }
 
 impl Config {
@@ -70,6 +71,7 @@ impl Config {
         minimum_relay_transaction_fee: u64,
         minimum_standard_transaction_version: u16,
         maximum_standard_transaction_version: u16,
+        network_blocks_per_second: u64,
     ) -> Self {
         Self {
             maximum_transaction_count,
@@ -90,6 +92,7 @@ impl Config {
             minimum_relay_transaction_fee,
             minimum_standard_transaction_version,
             maximum_standard_transaction_version,
+            network_blocks_per_second,
         }
     }
 
@@ -118,9 +121,16 @@ impl Config {
             minimum_relay_transaction_fee: DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE,
             minimum_standard_transaction_version: DEFAULT_MINIMUM_STANDARD_TRANSACTION_VERSION,
             maximum_standard_transaction_version: DEFAULT_MAXIMUM_STANDARD_TRANSACTION_VERSION,
+            network_blocks_per_second: 1000 / target_milliseconds_per_block,
         }
     }
 
+    /// Returns the minimum standard fee/mass ratio currently required by the mempool
+    pub(crate) fn minimum_feerate(&self) -> f64 {
+        // The parameter minimum_relay_transaction_fee is in sompi/kg units so divide by 1000 to get sompi/gram
+        self.minimum_relay_transaction_fee as f64 / 1000.0
+    }
+
     pub fn apply_ram_scale(mut self, ram_scale: f64) -> Self {
         self.maximum_transaction_count = (self.maximum_transaction_count as f64 * ram_scale.min(1.0)) as u64; // Allow only scaling down
         self
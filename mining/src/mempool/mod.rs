@@ -1,6 +1,6 @@
 use crate::{
+    feerate::{FeerateEstimator, FeerateEstimatorArgs},
     model::{
-        candidate_tx::CandidateTransaction,
         owner_txs::{GroupedOwnerTransactions, ScriptPublicKeySet},
         tx_query::TransactionQuery,
     },
@@ -12,9 +12,17 @@ use self::{
     model::{accepted_transactions::AcceptedTransactions, orphan_pool::OrphanPool, pool::Pool, transactions_pool::TransactionsPool},
     tx::Priority,
 };
-use waglayla_consensus_core::tx::{MutableTransaction, TransactionId};
+use waglayla_consensus_core::{
+    block::TemplateTransactionSelector,
+    hashing::tx::short_id,
+    tx::{MutableTransaction, Transaction, TransactionId},
+};
 use waglayla_core::time::Stopwatch;
-use std::sync::Arc;
+use waglayla_hashes::Hash;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 pub(crate) mod check_transaction_standard;
 pub mod config;
@@ -23,6 +31,7 @@ pub(crate) mod handle_new_block_transactions;
 pub(crate) mod model;
 pub(crate) mod populate_entries_and_try_validate;
 pub(crate) mod remove_transaction;
+pub(crate) mod replace_by_fee;
 pub(crate) mod validate_and_insert_transaction;
 
 /// Mempool contains transactions intended to be inserted into a block and mined.
@@ -111,23 +120,49 @@ impl Mempool {
         count
     }
 
-    pub(crate) fn block_candidate_transactions(&self) -> Vec<CandidateTransaction> {
+    /// Returns the transactions matching the short ids of a compact block with hash `block_hash`, by position.
+    /// A position is `None` if no transaction or more than a single transaction match its short id.
+    pub(crate) fn get_transactions_by_short_ids(&self, block_hash: &Hash, short_ids: &[u64]) -> Vec<Option<Arc<Transaction>>> {
+        let positions: HashMap<u64, usize> = short_ids.iter().enumerate().map(|(position, &short_id)| (short_id, position)).collect();
+        let mut transactions = vec![None; short_ids.len()];
+        let mut ambiguous = HashSet::new();
+        for transaction in self.transaction_pool.all().values().chain(self.orphan_pool.all().values()) {
+            if let Some(&position) = positions.get(&short_id(&transaction.id(), block_hash)) {
+                if transactions[position].replace(transaction.mtx.tx.clone()).is_some() {
+                    ambiguous.insert(position);
+                }
+            }
+        }
+        for position in ambiguous {
+            transactions[position] = None;
+        }
+        transactions
+    }
+
+    #[cfg(test)]
+    pub(crate) fn block_candidate_transactions(&self) -> Vec<crate::model::candidate_tx::CandidateTransaction> {
         let _sw = Stopwatch::<10>::with_threshold("block_candidate_transactions op");
         self.transaction_pool.all_ready_transactions()
     }
 
+    pub(crate) fn build_selector(&self) -> Box<dyn TemplateTransactionSelector> {
+        let _sw = Stopwatch::<10>::with_threshold("build_selector op");
+        self.transaction_pool.build_selector()
+    }
+
+    pub(crate) fn build_feerate_estimator(&self) -> FeerateEstimator {
+        let _sw = Stopwatch::<10>::with_threshold("build_feerate_estimator op");
+        let args = FeerateEstimatorArgs::new(self.config.network_blocks_per_second, self.config.maximum_mass_per_block);
+        self.transaction_pool.build_feerate_estimator(args)
+    }
+
     pub(crate) fn all_transaction_ids_with_priority(&self, priority: Priority) -> Vec<TransactionId> {
         let _sw = Stopwatch::<15>::with_threshold("all_transaction_ids_with_priority op");
         self.transaction_pool.all_transaction_ids_with_priority(priority)
     }
 
     pub(crate) fn update_revalidated_transaction(&mut self, transaction: MutableTransaction) -> bool {
-        if let Some(tx) = self.transaction_pool.get_mut(&transaction.id()) {
-            tx.mtx = transaction;
-            true
-        } else {
-            false
-        }
+        self.transaction_pool.update_revalidated_transaction(transaction)
     }
 
     pub(crate) fn has_accepted_transaction(&self, transaction_id: &TransactionId) -> bool {
@@ -158,4 +193,53 @@ pub mod tx {
         Forbidden,
         Allowed,
     }
+
+    /// Replace by Fee (RBF) policy
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum RbfPolicy {
+        /// ### RBF is forbidden
+        ///
+        /// Inserts the incoming transaction.
+        ///
+        /// Conditions of success:
+        ///
+        /// - no double spend
+        ///
+        /// If conditions are not met, leaves the mempool unchanged and fails with a double spend error.
+        Forbidden,
+
+        /// ### RBF may occur
+        ///
+        /// Identifies double spends in mempool and their owning transactions checking in order every input of the incoming
+        /// transaction.
+        ///
+        /// Removes all mempool transactions owning double spends, along with their redeemers, and inserts the incoming
+        /// transaction.
+        ///
+        /// Conditions of success:
+        ///
+        /// - on absence of double spends, always succeeds
+        /// - on double spends, the incoming transaction has a strictly higher fee/mass ratio than every mempool transaction
+        ///   owning a double spend
+        ///
+        /// If conditions are not met, leaves the mempool unchanged and fails with a double spend or a tx fee/mass too low error.
+        Allowed,
+
+        /// ### RBF must occur
+        ///
+        /// Identifies double spends in mempool and their owning transactions checking in order every input of the incoming
+        /// transaction.
+        ///
+        /// Removes the mempool transaction owning the double spends, along with its redeemers, and inserts the incoming
+        /// transaction.
+        ///
+        /// Conditions of success:
+        ///
+        /// - at least one double spend
+        /// - all double spends belong to the same mempool transaction
+        /// - the incoming transaction has a strictly higher fee/mass ratio than the mempool double spending transaction
+        ///
+        /// If conditions are not met, leaves the mempool unchanged and fails with a double spend or a tx fee/mass too low error.
+        Mandatory,
+    }
 }
@@ -0,0 +1,76 @@
+use crate::{block_template::selector::ALPHA, mempool::model::tx::MempoolTransaction};
+use std::sync::Arc;
+use waglayla_consensus_core::tx::{Transaction, TransactionId};
+
+/// A ready transaction keyed by its feerate, as stored in the mempool frontier.
+///
+/// Keys are ordered by feerate and then by transaction id, so that keys are unique
+/// even if several transactions pay the exact same feerate.
+#[derive(Clone, Debug)]
+pub struct FeerateTransactionKey {
+    pub fee: u64,
+    pub mass: u64,
+    id: TransactionId,
+
+    /// We store the transaction in the key so that the frontier can build selectors
+    /// without any reference to the transactions pool
+    pub tx: Arc<Transaction>,
+}
+
+impl FeerateTransactionKey {
+    pub fn new(fee: u64, mass: u64, tx: Arc<Transaction>) -> Self {
+        assert!(mass > 0, "mass is expected to be set for ready transactions");
+        Self { fee, mass, id: tx.id(), tx }
+    }
+
+    pub fn id(&self) -> TransactionId {
+        self.id
+    }
+
+    pub fn feerate(&self) -> f64 {
+        self.fee as f64 / self.mass as f64
+    }
+
+    /// The selection weight of this key, i.e. `feerate^ALPHA`
+    pub fn weight(&self) -> f64 {
+        self.feerate().powi(ALPHA)
+    }
+}
+
+impl Eq for FeerateTransactionKey {}
+
+impl PartialEq for FeerateTransactionKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl PartialOrd for FeerateTransactionKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FeerateTransactionKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.feerate().total_cmp(&other.feerate()).then(self.id.cmp(&other.id))
+    }
+}
+
+impl From<&MempoolTransaction> for FeerateTransactionKey {
+    fn from(tx: &MempoolTransaction) -> Self {
+        Self::new(tx.mtx.calculated_fee.expect("fee is expected to be populated"), tx.mtx.tx.mass(), tx.mtx.tx.clone())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use waglayla_consensus_core::subnets::SUBNETWORK_ID_NATIVE;
+
+    /// Builds a key with the given fee and mass, and a unique transaction id derived from `seed`
+    pub(crate) fn build_feerate_key(fee: u64, mass: u64, seed: u64) -> FeerateTransactionKey {
+        let tx = Transaction::new(0, vec![], vec![], seed, SUBNETWORK_ID_NATIVE, 0, vec![]);
+        FeerateTransactionKey::new(fee, mass, Arc::new(tx))
+    }
+}
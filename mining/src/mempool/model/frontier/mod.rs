@@ -0,0 +1,297 @@
+use crate::{
+    block_template::{policy::Policy, selector::TransactionsSelector},
+    feerate::{FeerateEstimator, FeerateEstimatorArgs},
+    model::candidate_tx::CandidateTransaction,
+};
+
+use feerate_key::FeerateTransactionKey;
+use itertools::Itertools;
+use rand::Rng;
+use search_tree::SearchTree;
+use selectors::{SequenceSelector, SequenceSelectorInput, SequenceSelectorTransaction, TakeAllSelector};
+use std::collections::HashSet;
+use waglayla_consensus_core::block::TemplateTransactionSelector;
+
+pub mod feerate_key;
+pub mod search_tree;
+pub mod selectors;
+
+/// If the frontier total mass is above this multiple of the block mass limit, block templates
+/// are built by sampling the frontier. Below it, sampling would suffer from too many collisions
+/// (i.e., re-drawing already sampled keys) and we fall back to the rebalancing selector.
+const COLLISION_FACTOR: u64 = 4;
+
+/// Sampling is stopped after this number of consecutive collisions, since it indicates
+/// that most of the remaining weight belongs to already sampled keys.
+const MAX_CONSECUTIVE_COLLISIONS: usize = 64;
+
+/// Management of the transaction pool frontier, that is, the set of transactions in
+/// the transaction pool which have no mempool ancestors and are essentially ready
+/// to enter the next block template.
+///
+/// The frontier is indexed by feerate in a [`SearchTree`] augmented with `feerate^ALPHA` weights,
+/// so that it can be sampled according to the same weighted random distribution as the
+/// rebalancing transactions selector, in sublinear time.
+#[derive(Default)]
+pub struct Frontier {
+    /// Frontier transactions sorted by feerate order and searchable for weight sampling
+    search_tree: SearchTree,
+
+    /// Total masses: Σ_{tx in frontier} tx.mass
+    total_mass: u64,
+}
+
+impl Frontier {
+    pub fn total_weight(&self) -> f64 {
+        self.search_tree.total_weight()
+    }
+
+    pub fn total_mass(&self) -> u64 {
+        self.total_mass
+    }
+
+    pub fn len(&self) -> usize {
+        self.search_tree.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn insert(&mut self, key: FeerateTransactionKey) -> bool {
+        let mass = key.mass;
+        if self.search_tree.insert(key) {
+            self.total_mass += mass;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn remove(&mut self, key: &FeerateTransactionKey) -> bool {
+        let mass = key.mass;
+        if self.search_tree.remove(key) {
+            self.total_mass -= mass;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Iterates the frontier keys by ascending feerate order
+    pub fn ascending_iter(&self) -> impl Iterator<Item = &FeerateTransactionKey> {
+        self.search_tree.ascending_iter()
+    }
+
+    /// Iterates the frontier keys by descending feerate order
+    pub fn descending_iter(&self) -> impl Iterator<Item = &FeerateTransactionKey> {
+        self.search_tree.descending_iter()
+    }
+
+    /// Samples the frontier in-place based on the provided policy and returns a sequence of transactions.
+    ///
+    /// Keys are drawn without replacement with probability proportional to their weight, until the sampled
+    /// mass covers one and a half blocks or `max_candidates` keys were drawn. The extra half block leaves room
+    /// for replacing transactions which might be rejected by consensus during block template building.
+    ///
+    /// Each draw costs `O(log n)`. In order to keep collisions rare, the sampled weight range is shrunk
+    /// whenever its top-most keys (which hold the largest weights) have all been sampled already.
+    pub fn sample_inplace<R: Rng>(&self, rng: &mut R, policy: &Policy, max_candidates: usize) -> SequenceSelectorInput {
+        let mut sequence = SequenceSelectorInput::new();
+        let mut sampled = HashSet::new();
+        let mass_target = policy.max_block_mass.saturating_add(policy.max_block_mass / 2);
+        let mut sampled_mass = 0u64;
+        let mut consecutive_collisions = 0;
+
+        // The highest key of the sampled range which was not sampled yet
+        let mut top_iter = self.search_tree.descending_iter();
+        let mut top = top_iter.next();
+        let mut range = self.total_weight();
+
+        while let Some(top_key) = top {
+            if sampled_mass >= mass_target || sequence.len() >= max_candidates || range <= 0.0 {
+                break;
+            }
+            let query = rng.gen::<f64>() * range;
+            let key = self.search_tree.search(query).expect("the tree is not empty");
+            if !sampled.insert(key.id()) {
+                consecutive_collisions += 1;
+                if consecutive_collisions > MAX_CONSECUTIVE_COLLISIONS {
+                    break;
+                }
+                continue;
+            }
+            consecutive_collisions = 0;
+            sequence.push(SequenceSelectorTransaction::new(key.tx.clone(), key.mass));
+            sampled_mass += key.mass;
+
+            if key.id() == top_key.id() {
+                // Exclude the sampled top-most keys from the range
+                top = top_iter.by_ref().find(|k| !sampled.contains(&k.id()));
+                range = top.map_or(0.0, |k| self.search_tree.prefix_weight(k) + k.weight());
+            }
+        }
+        sequence
+    }
+
+    /// Dynamically builds a transaction selector based on the specific state of the ready transactions frontier.
+    ///
+    /// At most `max_candidates` transactions are handed to the selector. When the frontier holds more, the
+    /// non-sampling selectors only consider the top `max_candidates` transactions by feerate.
+    ///
+    /// The logic is divided into three cases:
+    ///     1. The frontier is small and can fit entirely into a block: perform no sampling and return
+    ///        a [`TakeAllSelector`]
+    ///     2. The frontier has at least ~4x the capacity of a block: sample it and return a [`SequenceSelector`]
+    ///     3. The frontier has 1-4x the capacity of a block: fall back to the rebalancing transactions selector,
+    ///        which selects without replacement and is hence not subject to collisions
+    pub fn build_selector(&self, policy: &Policy, max_candidates: usize) -> Box<dyn TemplateTransactionSelector> {
+        if self.total_mass <= policy.max_block_mass {
+            let transactions =
+                self.descending_iter().take(max_candidates).map(|k| SequenceSelectorTransaction::new(k.tx.clone(), k.mass)).collect();
+            Box::new(TakeAllSelector::new(transactions, policy.clone()))
+        } else if self.total_mass > policy.max_block_mass.saturating_mul(COLLISION_FACTOR) {
+            let mut rng = rand::thread_rng();
+            Box::new(SequenceSelector::new(self.sample_inplace(&mut rng, policy, max_candidates), policy.clone()))
+        } else {
+            let mut transactions = self.descending_iter().take(max_candidates).map(CandidateTransaction::from_key).collect_vec();
+            transactions.reverse();
+            Box::new(TransactionsSelector::new(policy.clone(), transactions))
+        }
+    }
+
+    /// Builds a feerate estimator based on internal state of the ready transactions frontier
+    pub fn build_feerate_estimator(&self, args: FeerateEstimatorArgs) -> FeerateEstimator {
+        let average_transaction_mass = if self.is_empty() { 0.0 } else { self.total_mass as f64 / self.len() as f64 };
+        FeerateEstimator::from_aggregates(&args, self.total_weight(), average_transaction_mass)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use feerate_key::tests::build_feerate_key;
+    use itertools::Itertools;
+    use rand::thread_rng;
+    use std::collections::HashMap;
+
+    fn build_frontier(len: u64, mass: u64) -> (Frontier, Vec<FeerateTransactionKey>) {
+        let mut rng = thread_rng();
+        let mut frontier = Frontier::default();
+        let keys = (0..len).map(|i| build_feerate_key(rng.gen_range(mass..mass * 100), mass, i)).collect_vec();
+        for key in keys.iter().cloned() {
+            assert!(frontier.insert(key));
+        }
+        (frontier, keys)
+    }
+
+    #[test]
+    fn test_frontier_insert_remove() {
+        let (mut frontier, keys) = build_frontier(1000, 2000);
+        assert_eq!(1000, frontier.len());
+        assert_eq!(1000 * 2000, frontier.total_mass());
+        assert!(!frontier.insert(keys[0].clone()));
+        assert_eq!(1000 * 2000, frontier.total_mass());
+        for key in keys.iter() {
+            assert!(frontier.remove(key));
+        }
+        assert!(!frontier.remove(&keys[0]));
+        assert!(frontier.is_empty());
+        assert_eq!(0, frontier.total_mass());
+    }
+
+    #[test]
+    fn test_sample_inplace() {
+        let (frontier, _) = build_frontier(10_000, 2000);
+        let policy = Policy::new(500_000);
+        let sequence = frontier.sample_inplace(&mut thread_rng(), &policy, usize::MAX);
+        let sampled_mass: u64 = sequence.iter().map(|item| item.mass).sum();
+        assert!(sampled_mass >= policy.max_block_mass);
+        assert!(sampled_mass < policy.max_block_mass * 2);
+        assert_eq!(sequence.len(), sequence.iter().map(|item| item.tx.id()).unique().count(), "sampled txs should be unique");
+    }
+
+    #[test]
+    fn test_sample_inplace_with_heavy_top() {
+        // A few keys holding almost all the weight should be sampled first without causing a collision storm
+        let mut frontier = Frontier::default();
+        for i in 0..10_000 {
+            frontier.insert(build_feerate_key(2000, 2000, i));
+        }
+        for i in 10_000..10_020 {
+            frontier.insert(build_feerate_key(2000 * 1000, 2000, i));
+        }
+        let policy = Policy::new(500_000);
+        let sequence = frontier.sample_inplace(&mut thread_rng(), &policy, usize::MAX);
+        let sampled_mass: u64 = sequence.iter().map(|item| item.mass).sum();
+        assert!(sampled_mass >= policy.max_block_mass);
+        let heavy_count = sequence.iter().take(20).filter(|item| item.tx.lock_time >= 10_000).count();
+        assert_eq!(20, heavy_count, "the top-weight txs are expected to be sampled first");
+    }
+
+    #[test]
+    fn test_sample_distribution() {
+        // Sampling a single key many times should follow the weight distribution
+        let mut frontier = Frontier::default();
+        let keys = [1u64, 2, 3].iter().enumerate().map(|(i, &f)| build_feerate_key(f * 1000, 1000, i as u64)).collect_vec();
+        for key in keys.iter().cloned() {
+            frontier.insert(key);
+        }
+        let policy = Policy::new(1000);
+        let mut rng = thread_rng();
+        let mut counts: HashMap<_, u64> = HashMap::new();
+        const ROUNDS: u64 = 36_000;
+        for _ in 0..ROUNDS {
+            let sequence = frontier.sample_inplace(&mut rng, &policy, usize::MAX);
+            *counts.entry(sequence[0].tx.id()).or_default() += 1;
+        }
+        // Expected probabilities are 1/36, 8/36 and 27/36 respectively
+        for (key, expected) in keys.iter().zip([1.0, 8.0, 27.0]) {
+            let observed = counts[&key.id()] as f64 / ROUNDS as f64;
+            assert!((observed - expected / 36.0).abs() < 0.02, "observed: {observed}, expected: {}", expected / 36.0);
+        }
+    }
+
+    #[test]
+    fn test_build_selector() {
+        let policy = Policy::new(500_000);
+        for (len, expected_len) in [(100, 100), (400, 250), (2_000, 250)] {
+            let (frontier, _) = build_frontier(len, 2000);
+            let mut selector = frontier.build_selector(&policy, usize::MAX);
+            let selected = selector.select_transactions();
+            assert_eq!(expected_len, selected.len());
+            assert!(selector.is_successful());
+        }
+    }
+
+    #[test]
+    fn test_build_selector_max_candidates() {
+        let policy = Policy::new(500_000);
+        for len in [100, 400, 2_000] {
+            let (frontier, _) = build_frontier(len, 2000);
+            let mut selector = frontier.build_selector(&policy, 50);
+            let selected = selector.select_transactions();
+            assert_eq!(50, selected.len());
+        }
+
+        // The take-all selector should keep the highest feerate transactions
+        let (frontier, _) = build_frontier(100, 2000);
+        let top = frontier.descending_iter().take(10).map(|k| k.id()).collect::<HashSet<_>>();
+        let mut selector = frontier.build_selector(&policy, 10);
+        let selected = selector.select_transactions().iter().map(|tx| tx.id()).collect::<HashSet<_>>();
+        assert_eq!(top, selected);
+    }
+
+    #[test]
+    fn test_feerate_estimator_matches_transactions() {
+        let (frontier, keys) = build_frontier(1000, 2000);
+        let args = FeerateEstimatorArgs::new(1, 500_000);
+        let from_frontier = frontier.build_feerate_estimator(args).calc_estimations(1.0);
+        let args = FeerateEstimatorArgs::new(1, 500_000);
+        let from_transactions =
+            FeerateEstimator::from_transactions(&args, keys.iter().map(|k| (k.feerate(), k.mass))).calc_estimations(1.0);
+        for (a, b) in from_frontier.ordered_buckets().into_iter().zip(from_transactions.ordered_buckets()) {
+            assert!((a.feerate - b.feerate).abs() <= a.feerate * 1e-9);
+        }
+    }
+}
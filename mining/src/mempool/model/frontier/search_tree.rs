@@ -0,0 +1,325 @@
+use super::feerate_key::FeerateTransactionKey;
+use std::cmp::Ordering;
+
+type Link = Option<Box<Node>>;
+
+struct Node {
+    key: FeerateTransactionKey,
+    /// Random heap priority keeping the tree balanced in expectation
+    priority: u64,
+    /// Number of keys in the subtree rooted at this node
+    size: usize,
+    /// Sum of the weights of all keys in the subtree rooted at this node
+    weight: f64,
+    left: Link,
+    right: Link,
+}
+
+impl Node {
+    fn new(key: FeerateTransactionKey, priority: u64) -> Box<Self> {
+        let weight = key.weight();
+        Box::new(Self { key, priority, size: 1, weight, left: None, right: None })
+    }
+
+    /// Recomputes the subtree aggregates from the children. Aggregates are always rebuilt
+    /// from scratch (rather than incrementally adjusted) so that no floating point
+    /// error accumulates in the weights over time.
+    fn update(&mut self) {
+        self.size = 1 + size(&self.left) + size(&self.right);
+        self.weight = self.key.weight() + weight(&self.left) + weight(&self.right);
+    }
+}
+
+#[inline]
+fn size(link: &Link) -> usize {
+    link.as_ref().map_or(0, |n| n.size)
+}
+
+#[inline]
+fn weight(link: &Link) -> f64 {
+    link.as_ref().map_or(0.0, |n| n.weight)
+}
+
+/// Splits the subtree into keys strictly lower than `key` and keys greater or equal to `key`
+fn split(link: Link, key: &FeerateTransactionKey) -> (Link, Link) {
+    match link {
+        None => (None, None),
+        Some(mut node) => {
+            if node.key < *key {
+                let (left, right) = split(node.right.take(), key);
+                node.right = left;
+                node.update();
+                (Some(node), right)
+            } else {
+                let (left, right) = split(node.left.take(), key);
+                node.left = right;
+                node.update();
+                (left, Some(node))
+            }
+        }
+    }
+}
+
+/// Merges two subtrees, assuming all keys of `left` are strictly lower than all keys of `right`
+fn merge(left: Link, right: Link) -> Link {
+    match (left, right) {
+        (None, right) => right,
+        (left, None) => left,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.right = merge(left.right.take(), Some(right));
+                left.update();
+                Some(left)
+            } else {
+                right.left = merge(Some(left), right.left.take());
+                right.update();
+                Some(right)
+            }
+        }
+    }
+}
+
+fn remove(link: &mut Link, key: &FeerateTransactionKey) -> bool {
+    let ordering = match link.as_ref() {
+        None => return false,
+        Some(node) => key.cmp(&node.key),
+    };
+    match ordering {
+        Ordering::Equal => {
+            let node = link.take().unwrap();
+            *link = merge(node.left, node.right);
+            true
+        }
+        Ordering::Less | Ordering::Greater => {
+            let node = link.as_mut().unwrap();
+            let removed = if ordering == Ordering::Less { remove(&mut node.left, key) } else { remove(&mut node.right, key) };
+            if removed {
+                node.update();
+            }
+            removed
+        }
+    }
+}
+
+/// A search tree of feerate keys augmented with subtree weights.
+///
+/// Keys are kept in feerate order, and every node holds the aggregated weight (`feerate^ALPHA`) of its subtree.
+/// This allows to locate the key covering any point of the cumulative weight space in logarithmic time,
+/// which is the building block of weighted random sampling over the mempool frontier.
+///
+/// The tree is implemented as a treap, hence all operations run in expected `O(log n)`.
+#[derive(Default)]
+pub struct SearchTree {
+    root: Link,
+}
+
+impl SearchTree {
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// The total weight of all keys in the tree
+    pub fn total_weight(&self) -> f64 {
+        weight(&self.root)
+    }
+
+    pub fn contains(&self, key: &FeerateTransactionKey) -> bool {
+        let mut link = &self.root;
+        while let Some(node) = link {
+            match key.cmp(&node.key) {
+                Ordering::Less => link = &node.left,
+                Ordering::Greater => link = &node.right,
+                Ordering::Equal => return true,
+            }
+        }
+        false
+    }
+
+    /// Inserts a key into the tree. Returns false if the key was already present.
+    pub fn insert(&mut self, key: FeerateTransactionKey) -> bool {
+        if self.contains(&key) {
+            return false;
+        }
+        let (left, right) = split(self.root.take(), &key);
+        self.root = merge(merge(left, Some(Node::new(key, rand::random()))), right);
+        true
+    }
+
+    /// Removes a key from the tree. Returns false if the key was not present.
+    pub fn remove(&mut self, key: &FeerateTransactionKey) -> bool {
+        remove(&mut self.root, key)
+    }
+
+    /// Returns the key whose weight range contains `query`, where each key covers the range
+    /// `[prefix_weight(key), prefix_weight(key) + key.weight())` of the cumulative weight space.
+    ///
+    /// Queries out of `[0, total_weight)` are clamped to the first or last key respectively.
+    pub fn search(&self, mut query: f64) -> Option<&FeerateTransactionKey> {
+        let mut node = self.root.as_deref()?;
+        loop {
+            let left_weight = weight(&node.left);
+            if query < left_weight {
+                if let Some(left) = node.left.as_deref() {
+                    node = left;
+                    continue;
+                }
+                return Some(&node.key);
+            }
+            query -= left_weight;
+            let key_weight = node.key.weight();
+            if query < key_weight {
+                return Some(&node.key);
+            }
+            query -= key_weight;
+            match node.right.as_deref() {
+                Some(right) => node = right,
+                // Can only be reached due to floating point rounding or an out of range query
+                None => return Some(&node.key),
+            }
+        }
+    }
+
+    /// Returns the total weight of all keys strictly lower than `key`
+    pub fn prefix_weight(&self, key: &FeerateTransactionKey) -> f64 {
+        let mut link = &self.root;
+        let mut prefix = 0.0;
+        while let Some(node) = link {
+            if node.key < *key {
+                prefix += weight(&node.left) + node.key.weight();
+                link = &node.right;
+            } else {
+                link = &node.left;
+            }
+        }
+        prefix
+    }
+
+    /// The key with the lowest feerate
+    pub fn first(&self) -> Option<&FeerateTransactionKey> {
+        self.ascending_iter().next()
+    }
+
+    /// The key with the highest feerate
+    pub fn last(&self) -> Option<&FeerateTransactionKey> {
+        self.descending_iter().next()
+    }
+
+    /// Iterates the keys by ascending feerate order
+    pub fn ascending_iter(&self) -> Iter<'_, false> {
+        Iter::new(&self.root)
+    }
+
+    /// Iterates the keys by descending feerate order
+    pub fn descending_iter(&self) -> Iter<'_, true> {
+        Iter::new(&self.root)
+    }
+}
+
+/// An in-order iterator over the tree keys. Iterates in descending order if `REVERSE` is set.
+pub struct Iter<'a, const REVERSE: bool> {
+    stack: Vec<&'a Node>,
+}
+
+impl<'a, const REVERSE: bool> Iter<'a, REVERSE> {
+    fn new(root: &'a Link) -> Self {
+        let mut iter = Self { stack: Vec::new() };
+        iter.push_path(root);
+        iter
+    }
+
+    /// Pushes the path leading to the first key (in iteration order) of the subtree
+    fn push_path(&mut self, mut link: &'a Link) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = if REVERSE { &node.right } else { &node.left };
+        }
+    }
+}
+
+impl<'a, const REVERSE: bool> Iterator for Iter<'a, REVERSE> {
+    type Item = &'a FeerateTransactionKey;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_path(if REVERSE { &node.left } else { &node.right });
+        Some(&node.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mempool::model::frontier::feerate_key::tests::build_feerate_key;
+    use itertools::Itertools;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test_search_tree_insert_remove() {
+        let mut rng = thread_rng();
+        let mut tree = SearchTree::new();
+        let keys = (0..2000).map(|i| build_feerate_key(rng.gen_range(1..10_000_000), rng.gen_range(1000..100_000), i)).collect_vec();
+        for key in keys.iter().cloned() {
+            assert!(tree.insert(key));
+        }
+        assert!(!tree.insert(keys[0].clone()), "duplicate keys should be rejected");
+        assert_eq!(keys.len(), tree.len());
+
+        let sorted = keys.iter().cloned().sorted().collect_vec();
+        assert_eq!(sorted, tree.ascending_iter().cloned().collect_vec());
+        assert_eq!(sorted.iter().rev().cloned().collect_vec(), tree.descending_iter().cloned().collect_vec());
+        assert_eq!(sorted.first(), tree.first());
+        assert_eq!(sorted.last(), tree.last());
+
+        let expected_weight: f64 = keys.iter().map(|k| k.weight()).sum();
+        assert!((tree.total_weight() - expected_weight).abs() <= expected_weight * 1e-9);
+
+        for key in keys.iter().step_by(2) {
+            assert!(tree.remove(key));
+            assert!(!tree.remove(key), "a removed key should not be found again");
+        }
+        assert_eq!(keys.len() / 2, tree.len());
+        let expected_weight: f64 = keys.iter().skip(1).step_by(2).map(|k| k.weight()).sum();
+        assert!((tree.total_weight() - expected_weight).abs() <= expected_weight * 1e-9);
+        for key in keys.iter().skip(1).step_by(2) {
+            assert!(tree.contains(key));
+        }
+
+        for key in keys.iter().skip(1).step_by(2) {
+            assert!(tree.remove(key));
+        }
+        assert!(tree.is_empty());
+        assert_eq!(0.0, tree.total_weight());
+    }
+
+    #[test]
+    fn test_search_tree_search() {
+        let mut rng = thread_rng();
+        let mut tree = SearchTree::new();
+        let keys = (0..500).map(|i| build_feerate_key(rng.gen_range(1..1_000_000), rng.gen_range(1000..10_000), i)).collect_vec();
+        for key in keys.iter().cloned() {
+            tree.insert(key);
+        }
+        let sorted = keys.into_iter().sorted().collect_vec();
+        let mut prefix = 0.0;
+        for key in sorted.iter() {
+            let key_prefix = tree.prefix_weight(key);
+            assert!((key_prefix - prefix).abs() <= tree.total_weight() * 1e-9);
+            // Query the middle of the key range to avoid rounding issues at the edges
+            assert_eq!(Some(key), tree.search(key_prefix + key.weight() / 2.0));
+            prefix += key.weight();
+        }
+
+        // Out of range queries are clamped
+        assert_eq!(sorted.first(), tree.search(-1.0));
+        assert_eq!(sorted.last(), tree.search(tree.total_weight() * 2.0));
+        assert_eq!(None, SearchTree::new().search(0.0));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::block_template::policy::Policy;
+use std::{collections::HashMap, sync::Arc};
+use waglayla_consensus_core::{
+    block::TemplateTransactionSelector,
+    tx::{Transaction, TransactionId},
+};
+
+/// A transaction along with its mass, as sampled from the mempool frontier
+pub struct SequenceSelectorTransaction {
+    pub tx: Arc<Transaction>,
+    pub mass: u64,
+}
+
+impl SequenceSelectorTransaction {
+    pub fn new(tx: Arc<Transaction>, mass: u64) -> Self {
+        Self { tx, mass }
+    }
+}
+
+/// An ordered sequence of transactions to be selected by a [`SequenceSelector`]
+pub type SequenceSelectorInput = Vec<SequenceSelectorTransaction>;
+
+/// Tracks the selected mass and the rejections of a selection episode, shared by the frontier selectors
+#[derive(Default)]
+struct SelectionState {
+    /// Transactions returned by the most recent `select_transactions` call
+    selected_vec: Vec<(TransactionId, u64)>,
+    /// Lazily built from `selected_vec` only when there are actual rejections
+    selected_map: Option<HashMap<TransactionId, u64>>,
+    total_selected_mass: u64,
+    overall_candidates: usize,
+    overall_rejections: usize,
+}
+
+impl SelectionState {
+    fn reset_batch(&mut self) {
+        self.selected_vec.clear();
+        self.selected_map = None;
+    }
+
+    fn select(&mut self, tx: &Transaction, mass: u64) {
+        self.selected_vec.push((tx.id(), mass));
+        self.total_selected_mass += mass;
+        self.overall_candidates += 1;
+    }
+
+    fn reject(&mut self, tx_id: TransactionId) {
+        let selected_vec = &self.selected_vec;
+        let selected_map = self.selected_map.get_or_insert_with(|| selected_vec.iter().copied().collect());
+        let mass = selected_map.remove(&tx_id).expect("only previously selected txs can be rejected (and only once)");
+        self.total_selected_mass -= mass;
+        self.overall_rejections += 1;
+    }
+
+    fn is_successful(&self, policy: &Policy) -> bool {
+        const SUFFICIENT_MASS_THRESHOLD: f64 = 0.8;
+        const LOW_REJECTION_FRACTION: f64 = 0.2;
+
+        // We consider the operation successful if either mass occupation is above 80% or rejection rate is below 20%
+        self.overall_rejections == 0
+            || (self.total_selected_mass as f64) > policy.max_block_mass as f64 * SUFFICIENT_MASS_THRESHOLD
+            || (self.overall_rejections as f64) < self.overall_candidates as f64 * LOW_REJECTION_FRACTION
+    }
+}
+
+/// A selector which selects transactions in the order of a pre-sampled sequence.
+///
+/// The sequence is expected to be sampled from the mempool frontier according to the
+/// weighted random distribution, with enough spare mass to refill the block after rejections.
+pub struct SequenceSelector {
+    input_sequence: SequenceSelectorInput,
+    /// Position of the next transaction to consider in the input sequence
+    cursor: usize,
+    policy: Policy,
+    state: SelectionState,
+}
+
+impl SequenceSelector {
+    pub fn new(input_sequence: SequenceSelectorInput, policy: Policy) -> Self {
+        Self { input_sequence, cursor: 0, policy, state: Default::default() }
+    }
+}
+
+impl TemplateTransactionSelector for SequenceSelector {
+    fn select_transactions(&mut self) -> Vec<Transaction> {
+        self.state.reset_batch();
+        let mut transactions = Vec::new();
+        while self.cursor < self.input_sequence.len() {
+            let item = &self.input_sequence[self.cursor];
+            self.cursor += 1;
+            // Skip transactions which would exceed the block mass, a lighter one might still fit
+            if self.state.total_selected_mass.saturating_add(item.mass) > self.policy.max_block_mass {
+                continue;
+            }
+            self.state.select(&item.tx, item.mass);
+            // These transactions leave the selector so we clone
+            transactions.push(item.tx.as_ref().clone());
+        }
+        transactions
+    }
+
+    fn reject_selection(&mut self, tx_id: TransactionId) {
+        self.state.reject(tx_id);
+    }
+
+    fn is_successful(&self) -> bool {
+        self.state.is_successful(&self.policy)
+    }
+}
+
+/// A selector returning all transactions at once. Used when the whole mempool frontier fits in a block.
+pub struct TakeAllSelector {
+    transactions: Vec<SequenceSelectorTransaction>,
+    policy: Policy,
+    state: SelectionState,
+}
+
+impl TakeAllSelector {
+    pub fn new(transactions: Vec<SequenceSelectorTransaction>, policy: Policy) -> Self {
+        Self { transactions, policy, state: Default::default() }
+    }
+}
+
+impl TemplateTransactionSelector for TakeAllSelector {
+    fn select_transactions(&mut self) -> Vec<Transaction> {
+        self.state.reset_batch();
+        // Drain on the first call so that following calls return an empty batch
+        let transactions = std::mem::take(&mut self.transactions);
+        transactions
+            .into_iter()
+            .map(|item| {
+                self.state.select(&item.tx, item.mass);
+                item.tx.as_ref().clone()
+            })
+            .collect()
+    }
+
+    fn reject_selection(&mut self, tx_id: TransactionId) {
+        self.state.reject(tx_id);
+    }
+
+    fn is_successful(&self) -> bool {
+        self.state.is_successful(&self.policy)
+    }
+}
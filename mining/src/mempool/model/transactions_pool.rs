@@ -1,24 +1,28 @@
 use crate::{
+    block_template::policy::Policy,
+    feerate::{FeerateEstimator, FeerateEstimatorArgs},
     mempool::{
         config::Config,
         errors::{RuleError, RuleResult},
         model::{
+            frontier::{feerate_key::FeerateTransactionKey, Frontier},
             map::MempoolTransactionCollection,
             pool::{Pool, TransactionsEdges},
-            tx::MempoolTransaction,
+            tx::{DoubleSpend, MempoolTransaction},
             utxo_set::MempoolUtxoSet,
         },
         tx::Priority,
     },
-    model::{candidate_tx::CandidateTransaction, topological_index::TopologicalIndex},
+    model::topological_index::TopologicalIndex,
 };
 use waglayla_consensus_core::{
+    block::TemplateTransactionSelector,
     tx::TransactionId,
     tx::{MutableTransaction, TransactionOutpoint},
 };
 use waglayla_core::{time::unix_now, trace, warn};
 use std::{
-    collections::{hash_map::Keys, hash_set::Iter, HashSet},
+    collections::{hash_map::Keys, hash_set::Iter},
     sync::Arc,
 };
 
@@ -43,6 +47,8 @@ use std::{
 ///   of low-priority transactions sorted by fee rates. This design might eventually
 ///   prove to be sub-optimal, in which case an index should be implemented, probably
 ///   requiring smart pointers eventually or an indirection stage too.
+/// - The ready transactions are indexed by feerate in a [Frontier], which allows
+///   block templates to sample them in sublinear time.
 pub(crate) struct TransactionsPool {
     /// Mempool config
     config: Arc<Config>,
@@ -54,7 +60,7 @@ pub(crate) struct TransactionsPool {
     /// Transactions dependencies formed by outputs present in pool - successor relations.
     chained_transactions: TransactionsEdges,
     /// Transactions with no parents in the mempool -- ready to be inserted into a block template
-    ready_transactions: HashSet<TransactionId>,
+    ready_transactions: Frontier,
 
     last_expire_scan_daa_score: u64,
     /// last expire scan time in milliseconds
@@ -105,7 +111,7 @@ impl TransactionsPool {
         let parents = self.get_parent_transaction_ids_in_pool(&transaction.mtx);
         self.parent_transactions.insert(id, parents.clone());
         if parents.is_empty() {
-            self.ready_transactions.insert(id);
+            self.ready_transactions.insert((&transaction).into());
         }
         for parent_id in parents {
             let entry = self.chained_transactions.entry(parent_id).or_default();
@@ -133,17 +139,18 @@ impl TransactionsPool {
                 if let Some(parents) = self.parent_transactions.get_mut(chain) {
                     parents.remove(transaction_id);
                     if parents.is_empty() {
-                        self.ready_transactions.insert(*chain);
+                        let tx = self.all_transactions.get(chain).unwrap();
+                        self.ready_transactions.insert(tx.into());
                     }
                 }
             }
         }
         self.parent_transactions.remove(transaction_id);
         self.chained_transactions.remove(transaction_id);
-        self.ready_transactions.remove(transaction_id);
 
         // Remove the transaction itself
         let removed_tx = self.all_transactions.remove(transaction_id).ok_or(RuleError::RejectMissingTransaction(*transaction_id))?;
+        self.ready_transactions.remove(&FeerateTransactionKey::from(&removed_tx));
 
         // TODO: consider using `self.parent_transactions.get(transaction_id)`
         // The tradeoff to consider is whether it might be possible that a parent tx exists in the pool
@@ -157,21 +164,49 @@ impl TransactionsPool {
         Ok(removed_tx)
     }
 
+    /// Replaces the inner mutable transaction of a revalidated pool transaction.
+    /// Since its fee might have changed, the frontier is updated accordingly.
+    pub(crate) fn update_revalidated_transaction(&mut self, transaction: MutableTransaction) -> bool {
+        if let Some(tx) = self.all_transactions.get_mut(&transaction.id()) {
+            let was_ready = self.ready_transactions.remove(&FeerateTransactionKey::from(&*tx));
+            tx.mtx = transaction;
+            if was_ready {
+                self.ready_transactions.insert(FeerateTransactionKey::from(&*tx));
+            }
+            true
+        } else {
+            false
+        }
+    }
+
     pub(crate) fn ready_transaction_count(&self) -> usize {
         self.ready_transactions.len()
     }
 
     /// all_ready_transactions returns all fully populated mempool transactions having no parents in the mempool.
     /// These transactions are ready for being inserted in a block template.
-    pub(crate) fn all_ready_transactions(&self) -> Vec<CandidateTransaction> {
+    #[cfg(test)]
+    pub(crate) fn all_ready_transactions(&self) -> Vec<crate::model::candidate_tx::CandidateTransaction> {
         // The returned transactions are leaving the mempool so they are cloned
         self.ready_transactions
-            .iter()
+            .descending_iter()
             .take(self.config.maximum_ready_transaction_count as usize)
-            .map(|id| CandidateTransaction::from_mutable(&self.all_transactions.get(id).unwrap().mtx))
+            .map(crate::model::candidate_tx::CandidateTransaction::from_key)
             .collect()
     }
 
+    /// Dynamically builds a transaction selector based on the specific state of the ready transactions frontier
+    pub(crate) fn build_selector(&self) -> Box<dyn TemplateTransactionSelector> {
+        self.ready_transactions
+            .build_selector(&Policy::new(self.config.maximum_mass_per_block), self.config.maximum_ready_transaction_count as usize)
+    }
+
+    /// Builds a feerate estimator reflecting the ready transactions, which are the actual
+    /// candidates of the block template transaction selection.
+    pub(crate) fn build_feerate_estimator(&self, args: FeerateEstimatorArgs) -> FeerateEstimator {
+        self.ready_transactions.build_feerate_estimator(args)
+    }
+
     /// Is the mempool transaction identified by `transaction_id` unchained, thus having no successor?
     pub(crate) fn transaction_is_unchained(&self, transaction_id: &TransactionId) -> bool {
         if self.all_transactions.contains_key(transaction_id) {
@@ -192,12 +227,17 @@ impl TransactionsPool {
         &self,
         free_slots: usize,
         transaction: &MutableTransaction,
+        excluded: &[TransactionId],
     ) -> RuleResult<Vec<TransactionId>> {
         assert!(free_slots > 0);
         // Returns a vector of transactions to be removed that the caller has to remove actually.
         // The caller is golang validateAndInsertTransaction equivalent.
         // This behavior differs from golang impl.
-        let trim_size = self.len() + free_slots - usize::min(self.len() + free_slots, self.config.maximum_transaction_count as usize);
+        //
+        // The `excluded` transactions are about to be removed by the caller (i.e. replaced by fee) so they
+        // neither count in the pool size nor are candidates for removal.
+        let len = self.len() - excluded.len();
+        let trim_size = len + free_slots - usize::min(len + free_slots, self.config.maximum_transaction_count as usize);
         let mut transactions_to_remove = Vec::with_capacity(trim_size);
         if trim_size > 0 {
             // TODO: consider introducing an index on all_transactions low-priority items instead.
@@ -205,10 +245,12 @@ impl TransactionsPool {
             // Sorting this vector here may be sub-optimal compared with maintaining a sorted
             // index of all_transactions low-priority items if the proportion of low-priority txs
             // in all_transactions is important.
-            let low_priority_txs = self
-                .all_transactions
-                .values()
-                .filter(|x| x.priority == Priority::Low && self.transaction_is_unchained(&x.id()) && !x.is_parent_of(transaction));
+            let low_priority_txs = self.all_transactions.values().filter(|x| {
+                x.priority == Priority::Low
+                    && self.transaction_is_unchained(&x.id())
+                    && !x.is_parent_of(transaction)
+                    && !excluded.contains(&x.id())
+            });
 
             if trim_size == 1 {
                 // This is the most likely case. Here we just search the minimum, thus avoiding the need to sort altogether.
@@ -227,7 +269,7 @@ impl TransactionsPool {
         }
 
         // An error is returned if the mempool is filled with high priority and other unremovable transactions.
-        let tx_count = self.len() + free_slots - transactions_to_remove.len();
+        let tx_count = len + free_slots - transactions_to_remove.len();
         if tx_count as u64 > self.config.maximum_transaction_count {
             let err = RuleError::RejectMempoolIsFull(tx_count - free_slots, self.config.maximum_transaction_count);
             warn!("{}", err.to_string());
@@ -249,6 +291,23 @@ impl TransactionsPool {
         self.utxo_set.check_double_spends(transaction)
     }
 
+    pub(crate) fn get_double_spend_transaction_ids(&self, transaction: &MutableTransaction) -> Vec<DoubleSpend> {
+        self.utxo_set.get_double_spend_transaction_ids(transaction)
+    }
+
+    pub(crate) fn get_double_spend_owner<'a>(&'a self, double_spend: &DoubleSpend) -> RuleResult<&'a MempoolTransaction> {
+        match self.get(&double_spend.owner_id) {
+            Some(transaction) => Ok(transaction),
+            None => {
+                // This case should never arise in the first place.
+                // Anyway, in case it does, if a double spent transaction id is found but the matching
+                // transaction cannot be located in the mempool a replacement is no longer possible
+                // so a double spend error is returned.
+                Err(double_spend.into())
+            }
+        }
+    }
+
     pub(crate) fn collect_expired_low_priority_transactions(&mut self, virtual_daa_score: u64) -> Vec<TransactionId> {
         let now = unix_now();
         if virtual_daa_score < self.last_expire_scan_daa_score + self.config.transaction_expire_scan_interval_daa_score
@@ -300,8 +359,4 @@ impl Pool for TransactionsPool {
     fn chained(&self) -> &TransactionsEdges {
         &self.chained_transactions
     }
-
-    fn get_mut(&mut self, transaction_id: &TransactionId) -> Option<&mut MempoolTransaction> {
-        self.all_transactions.get_mut(transaction_id)
-    }
 }
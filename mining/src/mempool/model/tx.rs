@@ -1,5 +1,9 @@
 use crate::mempool::tx::Priority;
-use waglayla_consensus_core::{tx::MutableTransaction, tx::TransactionId};
+use waglayla_consensus_core::{
+    tx::MutableTransaction,
+    tx::{TransactionId, TransactionOutpoint},
+};
+use waglayla_mining_errors::mempool::RuleError;
 use std::{
     cmp::Ordering,
     fmt::{Display, Formatter},
@@ -53,6 +57,31 @@ impl PartialEq for MempoolTransaction {
     }
 }
 
+/// An outpoint spent by an incoming transaction which is already spent by a mempool transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DoubleSpend {
+    pub(crate) outpoint: TransactionOutpoint,
+    pub(crate) owner_id: TransactionId,
+}
+
+impl DoubleSpend {
+    pub(crate) fn new(outpoint: TransactionOutpoint, owner_id: TransactionId) -> Self {
+        Self { outpoint, owner_id }
+    }
+}
+
+impl From<DoubleSpend> for RuleError {
+    fn from(value: DoubleSpend) -> Self {
+        RuleError::RejectDoubleSpendInMempool(value.outpoint, value.owner_id)
+    }
+}
+
+impl From<&DoubleSpend> for RuleError {
+    fn from(value: &DoubleSpend) -> Self {
+        RuleError::RejectDoubleSpendInMempool(value.outpoint, value.owner_id)
+    }
+}
+
 #[derive(PartialEq, Eq)]
 pub(crate) enum TxRemovalReason {
     Muted,
@@ -63,6 +92,7 @@ pub(crate) enum TxRemovalReason {
     DoubleSpend,
     InvalidInBlockTemplate,
     RevalidationWithMissingOutpoints,
+    ReplacedByFee,
 }
 
 impl TxRemovalReason {
@@ -76,6 +106,7 @@ impl TxRemovalReason {
             TxRemovalReason::DoubleSpend => "double spend",
             TxRemovalReason::InvalidInBlockTemplate => "invalid in block template",
             TxRemovalReason::RevalidationWithMissingOutpoints => "revalidation with missing outpoints",
+            TxRemovalReason::ReplacedByFee => "replaced by fee",
         }
     }
 
@@ -1,7 +1,7 @@
 use crate::{
     mempool::{
-        errors::{RuleError, RuleResult},
-        model::map::OutpointIndex,
+        errors::RuleResult,
+        model::{map::OutpointIndex, tx::DoubleSpend},
     },
     model::TransactionIdSet,
 };
@@ -70,14 +70,40 @@ impl MempoolUtxoSet {
 
     /// Make sure no other transaction in the mempool is already spending an output which one of this transaction inputs spends
     pub(crate) fn check_double_spends(&self, transaction: &MutableTransaction) -> RuleResult<()> {
+        match self.get_first_double_spend(transaction) {
+            Some(double_spend) => Err(double_spend.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the first double spend found while checking in order every input of `transaction`
+    pub(crate) fn get_first_double_spend(&self, transaction: &MutableTransaction) -> Option<DoubleSpend> {
         let transaction_id = transaction.id();
-        for input in transaction.tx.inputs.iter() {
-            if let Some(existing_transaction_id) = self.get_outpoint_owner_id(&input.previous_outpoint) {
-                if *existing_transaction_id != transaction_id {
-                    return Err(RuleError::RejectDoubleSpendInMempool(input.previous_outpoint, *existing_transaction_id));
-                }
+        transaction.tx.inputs.iter().find_map(|input| match self.get_outpoint_owner_id(&input.previous_outpoint) {
+            Some(existing_transaction_id) if *existing_transaction_id != transaction_id => {
+                Some(DoubleSpend::new(input.previous_outpoint, *existing_transaction_id))
             }
-        }
-        Ok(())
+            _ => None,
+        })
+    }
+
+    /// Returns the double spends of `transaction`, keeping only the first one of every distinct owning mempool transaction
+    ///
+    /// The returned vector is ordered by the inputs of `transaction`.
+    pub(crate) fn get_double_spend_transaction_ids(&self, transaction: &MutableTransaction) -> Vec<DoubleSpend> {
+        let transaction_id = transaction.id();
+        let mut owner_ids = TransactionIdSet::new();
+        transaction
+            .tx
+            .inputs
+            .iter()
+            .filter_map(|input| match self.get_outpoint_owner_id(&input.previous_outpoint) {
+                Some(existing_transaction_id) if *existing_transaction_id != transaction_id => {
+                    Some(DoubleSpend::new(input.previous_outpoint, *existing_transaction_id))
+                }
+                _ => None,
+            })
+            .filter(|double_spend| owner_ids.insert(double_spend.owner_id))
+            .collect()
     }
 }
@@ -0,0 +1,160 @@
+use crate::mempool::{
+    errors::{RuleError, RuleResult},
+    model::{
+        pool::Pool,
+        tx::{DoubleSpend, MempoolTransaction, TxRemovalReason},
+    },
+    tx::RbfPolicy,
+    Mempool,
+};
+use waglayla_consensus_core::tx::{MutableTransaction, Transaction, TransactionId};
+use std::sync::Arc;
+
+/// The outcome of a successful replace by fee (RBF)
+pub(crate) struct TransactionReplacement {
+    /// The mempool transaction owning the first double spend of the incoming transaction
+    pub(crate) replaced: Arc<Transaction>,
+
+    /// The ids of all the transactions evicted from the transaction pool, that is all the double spending
+    /// transactions and their redeemers
+    pub(crate) evicted: Vec<TransactionId>,
+
+    /// The ids of the mempool transactions owning a double spend of the incoming transaction
+    double_spend_owners: Vec<TransactionId>,
+}
+
+impl Mempool {
+    /// Checks the replace by fee (RBF) constraints of an incoming transaction for a policy, ignoring fees
+    ///
+    /// Returns the fee/mass threshold the incoming transaction will have to exceed if some mempool transactions
+    /// are expected to be replaced.
+    ///
+    /// This is called before the incoming transaction is validated, i.e. before its fee is known, so that
+    /// transactions not matching the policy get rejected early.
+    pub(crate) fn get_replace_by_fee_constraint(
+        &self,
+        transaction: &MutableTransaction,
+        rbf_policy: RbfPolicy,
+    ) -> RuleResult<Option<f64>> {
+        match rbf_policy {
+            RbfPolicy::Forbidden => {
+                self.transaction_pool.check_double_spends(transaction)?;
+                Ok(None)
+            }
+
+            RbfPolicy::Allowed => {
+                let double_spends = self.transaction_pool.get_double_spend_transaction_ids(transaction);
+                if double_spends.is_empty() {
+                    return Ok(None);
+                }
+                let mut feerate_threshold = 0f64;
+                for double_spend in double_spends.iter() {
+                    // The threshold is the highest fee rate of all the transactions to be replaced
+                    feerate_threshold = feerate_threshold.max(self.get_double_spend_feerate(double_spend)?);
+                }
+                Ok(Some(feerate_threshold))
+            }
+
+            RbfPolicy::Mandatory => {
+                let double_spends = self.transaction_pool.get_double_spend_transaction_ids(transaction);
+                match double_spends.len() {
+                    0 => Err(RuleError::RejectRbfNoDoubleSpend),
+                    1 => Ok(Some(self.get_double_spend_feerate(&double_spends[0])?)),
+                    _ => Err(RuleError::RejectRbfTooManyDoubleSpendingTransactions),
+                }
+            }
+        }
+    }
+
+    /// Validates replace by fee (RBF) for a fully validated incoming transaction and a policy
+    ///
+    /// On success, returns the replacement outcome, if any, listing all the transactions owning a double spend
+    /// of `transaction` along with their redeemers. The mempool is left unchanged so that the caller can run
+    /// its remaining checks before applying the replacement with [`Mempool::apply_replace_by_fee`].
+    pub(crate) fn validate_replace_by_fee(
+        &self,
+        transaction: &MutableTransaction,
+        rbf_policy: RbfPolicy,
+    ) -> RuleResult<Option<TransactionReplacement>> {
+        let double_spends = match rbf_policy {
+            RbfPolicy::Forbidden => {
+                self.transaction_pool.check_double_spends(transaction)?;
+                return Ok(None);
+            }
+            RbfPolicy::Allowed => {
+                let double_spends = self.transaction_pool.get_double_spend_transaction_ids(transaction);
+                if double_spends.is_empty() {
+                    return Ok(None);
+                }
+                double_spends
+            }
+            RbfPolicy::Mandatory => {
+                let double_spends = self.transaction_pool.get_double_spend_transaction_ids(transaction);
+                match double_spends.len() {
+                    0 => return Err(RuleError::RejectRbfNoDoubleSpend),
+                    1 => double_spends,
+                    _ => return Err(RuleError::RejectRbfTooManyDoubleSpendingTransactions),
+                }
+            }
+        };
+
+        // Validate the incoming transaction against all double spends before applying any consequence
+        let replaced = self.validate_double_spending_transaction(transaction, &double_spends[0])?.mtx.tx.clone();
+        for double_spend in double_spends.iter().skip(1) {
+            self.validate_double_spending_transaction(transaction, double_spend)?;
+        }
+
+        // Collect the transactions to be evicted and make sure the incoming transaction does not depend on any of them
+        let mut evicted = Vec::new();
+        for double_spend in double_spends.iter() {
+            evicted.push(double_spend.owner_id);
+            evicted.extend(self.transaction_pool.get_redeemer_ids_in_pool(&double_spend.owner_id));
+        }
+        if let Some(input) = transaction.tx.inputs.iter().find(|input| evicted.contains(&input.previous_outpoint.transaction_id)) {
+            return Err(RuleError::RejectRbfSpendsReplacedTransaction(transaction.id(), input.previous_outpoint.transaction_id));
+        }
+        // A redeemer may be shared by several double spending transactions
+        evicted.sort();
+        evicted.dedup();
+
+        let mut double_spend_owners = double_spends.iter().map(|double_spend| double_spend.owner_id).collect::<Vec<_>>();
+        double_spend_owners.sort();
+        double_spend_owners.dedup();
+
+        Ok(Some(TransactionReplacement { replaced, evicted, double_spend_owners }))
+    }
+
+    /// Applies a replacement returned by [`Mempool::validate_replace_by_fee`], removing from the mempool all the
+    /// transactions owning a double spend of `transaction` along with their redeemers.
+    pub(crate) fn apply_replace_by_fee(
+        &mut self,
+        transaction: &MutableTransaction,
+        replacement: &TransactionReplacement,
+    ) -> RuleResult<()> {
+        let transaction_id = transaction.id();
+        for owner_id in replacement.double_spend_owners.iter() {
+            self.remove_transaction(owner_id, true, TxRemovalReason::ReplacedByFee, format!(" by {}", transaction_id).as_str())?;
+        }
+        Ok(())
+    }
+
+    fn get_double_spend_feerate(&self, double_spend: &DoubleSpend) -> RuleResult<f64> {
+        Ok(self.transaction_pool.get_double_spend_owner(double_spend)?.fee_rate())
+    }
+
+    /// Validates that `transaction` pays a strictly higher fee/mass ratio than the mempool transaction owning `double_spend`
+    fn validate_double_spending_transaction<'a>(
+        &'a self,
+        transaction: &MutableTransaction,
+        double_spend: &DoubleSpend,
+    ) -> RuleResult<&'a MempoolTransaction> {
+        let owner = self.transaction_pool.get_double_spend_owner(double_spend)?;
+        let feerate = transaction.calculated_feerate().expect("the incoming transaction is expected to be fully validated");
+        let owner_feerate = owner.fee_rate();
+        if feerate > owner_feerate {
+            Ok(owner)
+        } else {
+            Err(RuleError::RejectRbfInsufficientFeerate(transaction.id(), feerate, owner_feerate, owner.id()))
+        }
+    }
+}
@@ -4,7 +4,8 @@ use crate::mempool::{
         pool::Pool,
         tx::{MempoolTransaction, TxRemovalReason},
     },
-    tx::{Orphan, Priority},
+    replace_by_fee::TransactionReplacement,
+    tx::{Orphan, Priority, RbfPolicy},
     Mempool,
 };
 use waglayla_consensus_core::{
@@ -15,17 +16,29 @@ use waglayla_consensus_core::{
 use waglayla_core::{debug, info};
 use std::sync::Arc;
 
+/// The outcome of the post validation and insertion of a transaction
+#[derive(Default)]
+pub(crate) struct TransactionPostValidation {
+    /// The mempool transactions replaced by the incoming transaction, if any
+    pub(crate) replacement: Option<TransactionReplacement>,
+
+    /// The incoming transaction if it was accepted into the transaction pool
+    pub(crate) accepted: Option<Arc<Transaction>>,
+}
+
 impl Mempool {
     pub(crate) fn pre_validate_and_populate_transaction(
         &self,
         consensus: &dyn ConsensusApi,
         mut transaction: MutableTransaction,
+        rbf_policy: RbfPolicy,
     ) -> RuleResult<MutableTransaction> {
         self.validate_transaction_unacceptance(&transaction)?;
         // Populate mass in the beginning, it will be used in multiple places throughout the validation and insertion.
         transaction.calculated_compute_mass = Some(consensus.calculate_transaction_compute_mass(&transaction.tx));
         self.validate_transaction_in_isolation(&transaction)?;
-        self.transaction_pool.check_double_spends(&transaction)?;
+        // The fee is still unknown at this stage so only the double spend part of the RBF policy can be checked
+        self.get_replace_by_fee_constraint(&transaction, rbf_policy)?;
         self.populate_mempool_entries(&mut transaction);
         Ok(transaction)
     }
@@ -37,7 +50,8 @@ impl Mempool {
         transaction: MutableTransaction,
         priority: Priority,
         orphan: Orphan,
-    ) -> RuleResult<Option<Arc<Transaction>>> {
+        rbf_policy: RbfPolicy,
+    ) -> RuleResult<TransactionPostValidation> {
         let transaction_id = transaction.id();
 
         // First check if the transaction was not already added to the mempool.
@@ -46,13 +60,13 @@ impl Mempool {
         // concurrently.
         if self.transaction_pool.has(&transaction_id) {
             debug!("Transaction {0} is not post validated since already in the mempool", transaction_id);
-            return Ok(None);
+            return Ok(TransactionPostValidation::default());
         }
 
         self.validate_transaction_unacceptance(&transaction)?;
 
         // Re-check double spends since validate_and_insert_transaction is no longer atomic
-        self.transaction_pool.check_double_spends(&transaction)?;
+        let feerate_threshold = self.get_replace_by_fee_constraint(&transaction, rbf_policy)?;
 
         match validation_result {
             Ok(_) => {}
@@ -60,8 +74,12 @@ impl Mempool {
                 if orphan == Orphan::Forbidden {
                     return Err(RuleError::RejectDisallowedOrphan(transaction_id));
                 }
+                // The fee of an orphan is unknown so it cannot be compared to the fee of the transactions it double spends
+                if feerate_threshold.is_some() {
+                    return Err(RuleError::RejectRbfOrphan(transaction_id));
+                }
                 self.orphan_pool.try_add_orphan(consensus.get_virtual_daa_score(), transaction, priority)?;
-                return Ok(None);
+                return Ok(TransactionPostValidation::default());
             }
             Err(err) => {
                 return Err(err);
@@ -70,15 +88,25 @@ impl Mempool {
 
         self.validate_transaction_in_context(&transaction)?;
 
-        // Before adding the transaction, check if there is room in the pool
-        self.transaction_pool.limit_transaction_count(1, &transaction)?.iter().try_for_each(|x| {
+        // Check double spends and the transactions to be replaced if the RBF policy allows or requires it
+        let replacement = self.validate_replace_by_fee(&transaction, rbf_policy)?;
+
+        // Before changing the mempool, check if there is room in the pool once the replaced transactions are gone
+        let evicted = replacement.as_ref().map_or(&[][..], |replacement| replacement.evicted.as_slice());
+        let transactions_to_remove = self.transaction_pool.limit_transaction_count(1, &transaction, evicted)?;
+
+        // All checks passed, the mempool can now be changed
+        if let Some(replacement) = replacement.as_ref() {
+            self.apply_replace_by_fee(&transaction, replacement)?;
+        }
+        transactions_to_remove.iter().try_for_each(|x| {
             self.remove_transaction(x, true, TxRemovalReason::MakingRoom, format!(" for {}", transaction_id).as_str())
         })?;
 
         // Add the transaction to the mempool as a MempoolTransaction and return a clone of the embedded Arc<Transaction>
         let accepted_transaction =
             self.transaction_pool.add_transaction(transaction, consensus.get_virtual_daa_score(), priority)?.mtx.tx.clone();
-        Ok(Some(accepted_transaction))
+        Ok(TransactionPostValidation { replacement, accepted: Some(accepted_transaction) })
     }
 
     /// Validates that the transaction wasn't already accepted into the DAG
@@ -0,0 +1,23 @@
+use waglayla_consensus_core::tx::{Transaction, TransactionId};
+use std::sync::Arc;
+
+/// The result of inserting a transaction into the mempool
+#[derive(Default)]
+pub struct TransactionInsertion {
+    /// The mempool transaction replaced by the inserted transaction, if any
+    pub replaced: Option<Arc<Transaction>>,
+
+    /// The ids of all the transactions evicted from the mempool by a replace by fee, that is the replaced
+    /// transactions and their redeemers
+    pub evicted: Vec<TransactionId>,
+
+    /// The transactions accepted into the mempool, that is the inserted transaction followed by
+    /// the transactions it unorphaned
+    pub accepted: Vec<Arc<Transaction>>,
+}
+
+impl TransactionInsertion {
+    pub fn new(replaced: Option<Arc<Transaction>>, evicted: Vec<TransactionId>, accepted: Vec<Arc<Transaction>>) -> Self {
+        Self { replaced, evicted, accepted }
+    }
+}
@@ -1,9 +1,10 @@
 use crate::flowcontext::{
+    dandelion::Dandelion,
     orphans::{OrphanBlocksPool, OrphanOutput},
     process_queue::ProcessQueue,
     transactions::TransactionsSpread,
 };
-use crate::{v5, v6};
+use crate::{v5, v6, v7};
 use async_trait::async_trait;
 use futures::future::join_all;
 use waglayla_addressmanager::AddressManager;
@@ -14,7 +15,7 @@ use waglayla_consensus_core::config::Config;
 use waglayla_consensus_core::errors::block::RuleError;
 use waglayla_consensus_core::tx::{Transaction, TransactionId};
 use waglayla_consensus_notify::{
-    notification::{Notification, PruningPointUtxoSetOverrideNotification},
+    notification::{Notification, PruningPointUtxoSetOverrideNotification, TransactionsEvictedNotification},
     root::ConsensusNotificationRoot,
 };
 use waglayla_consensusmanager::{BlockProcessingBatch, ConsensusInstance, ConsensusManager, ConsensusProxy};
@@ -26,19 +27,21 @@ use waglayla_core::{
 use waglayla_core::{time::unix_now, warn};
 use waglayla_hashes::Hash;
 use waglayla_mining::manager::MiningManagerProxy;
-use waglayla_mining::mempool::tx::{Orphan, Priority};
+use waglayla_mining::mempool::tx::{Orphan, Priority, RbfPolicy};
+use waglayla_mining::model::tx_query::TransactionQuery;
 use waglayla_notify::notifier::Notify;
 use waglayla_p2p_lib::{
     common::ProtocolError,
     convert::model::version::Version,
     make_message,
     pb::{waglaylad_message::Payload, InvRelayBlockMessage},
-    ConnectionInitializer, Hub, WaglayladHandshake, PeerKey, PeerProperties, Router,
+    ConnectionInitializer, Hub, MisbehaviourScore, NodeIdentity, WaglayladHandshake, PeerKey, PeerProperties, Router,
 };
 use waglayla_utils::iter::IterExtensions;
 use waglayla_utils::networking::PeerId;
 use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::time::Instant;
 use std::{collections::hash_map::Entry, fmt::Display};
 use std::{
@@ -57,8 +60,8 @@ use tokio::sync::{
 use tokio_stream::{wrappers::UnboundedReceiverStream, StreamExt};
 use uuid::Uuid;
 
-/// The P2P protocol version. Currently the only one supported.
-const PROTOCOL_VERSION: u32 = 8;
+/// The P2P protocol version. Peers of older supported versions are served with the flows of their version.
+const PROTOCOL_VERSION: u32 = 9;
 
 /// See `check_orphan_resolution_range`
 const BASELINE_ORPHAN_RESOLUTION_RANGE: u32 = 5;
@@ -210,12 +213,14 @@ impl BlockEventLogger {
 
 pub struct FlowContextInner {
     pub node_id: PeerId,
+    identity: Option<NodeIdentity>,
     pub consensus_manager: Arc<ConsensusManager>,
     pub config: Arc<Config>,
     hub: Hub,
     orphans_pool: AsyncRwLock<OrphanBlocksPool>,
     shared_block_requests: Arc<Mutex<HashMap<Hash, RequestScopeMetadata>>>,
     transactions_spread: AsyncRwLock<TransactionsSpread>,
+    dandelion: Option<Arc<Dandelion>>,
     shared_transaction_requests: Arc<Mutex<HashMap<TransactionId, RequestScopeMetadata>>>,
     is_ibd_running: Arc<AtomicBool>,
     ibd_metadata: Arc<RwLock<Option<IbdMetadata>>>,
@@ -225,6 +230,9 @@ pub struct FlowContextInner {
     pub(crate) tick_service: Arc<TickService>,
     notification_root: Arc<ConsensusNotificationRoot>,
 
+    // Misbehaviour scores of recently connected peers by IP, carried over to new connections
+    recent_misbehaviour: Mutex<HashMap<IpAddr, MisbehaviourScore>>,
+
     // Special sampling logger used only for high-bps networks where logs must be throttled
     block_event_logger: Option<BlockEventLogger>,
 
@@ -303,6 +311,7 @@ impl FlowContext {
         mining_manager: MiningManagerProxy,
         tick_service: Arc<TickService>,
         notification_root: Arc<ConsensusNotificationRoot>,
+        identity: Option<NodeIdentity>,
     ) -> Self {
         let hub = Hub::new();
 
@@ -314,10 +323,12 @@ impl FlowContext {
         Self {
             inner: Arc::new(FlowContextInner {
                 node_id: Uuid::new_v4().into(),
+                identity,
                 consensus_manager,
                 orphans_pool: AsyncRwLock::new(OrphanBlocksPool::new(max_orphans)),
                 shared_block_requests: Arc::new(Mutex::new(HashMap::new())),
                 transactions_spread: AsyncRwLock::new(TransactionsSpread::new(hub.clone())),
+                dandelion: config.dandelion.then(|| Arc::new(Dandelion::new(hub.clone()))),
                 shared_transaction_requests: Arc::new(Mutex::new(HashMap::new())),
                 is_ibd_running: Default::default(),
                 ibd_metadata: Default::default(),
@@ -327,6 +338,7 @@ impl FlowContext {
                 mining_manager,
                 tick_service,
                 notification_root,
+                recent_misbehaviour: Default::default(),
                 block_event_logger: if config.bps() > 1 { Some(BlockEventLogger::new(config.bps() as usize)) } else { None },
                 orphan_resolution_range,
                 max_orphans,
@@ -365,6 +377,38 @@ impl FlowContext {
         self.connection_manager.read().clone()
     }
 
+    /// Adds `score` points to the misbehaviour score of the peer. If the accumulated score reaches the configured
+    /// ban threshold, the peer IP is banned for the configured ban duration
+    pub async fn add_misbehaviour(&self, router: &Arc<Router>, score: u32, reason: impl Display) {
+        let total = router.increase_misbehaviour_score(score);
+        if score > 0 {
+            debug!("P2P, misbehaviour score of peer {} increased by {} to {} ({})", router, score, total, reason);
+        }
+        let ip = router.net_address().ip();
+        {
+            let mut recent_misbehaviour = self.recent_misbehaviour.lock();
+            let now = Instant::now();
+            recent_misbehaviour.retain(|_, misbehaviour| misbehaviour.current(now) > 0);
+            if total > 0 {
+                recent_misbehaviour.insert(ip, router.misbehaviour());
+            }
+        }
+        if self.config.ban_threshold == 0 || total < self.config.ban_threshold || !router.mark_banned() {
+            return;
+        }
+        self.recent_misbehaviour.lock().remove(&ip);
+        if let Some(connection_manager) = self.connection_manager() {
+            if connection_manager.is_whitelisted(ip) || router.identity_key().is_some_and(|key| connection_manager.is_pinned(ip, &key))
+            {
+                debug!("P2P, not banning whitelisted peer {} despite its misbehaviour score reaching {}", router, total);
+                return;
+            }
+            let duration = Duration::from_secs(self.config.ban_duration);
+            warn!("Banning peer {} for {:?} after its misbehaviour score reached {} ({})", router, duration, total, reason);
+            connection_manager.ban(ip.into(), duration, format!("misbehaviour score reached {total}: {reason}")).await;
+        }
+    }
+
     pub fn consensus(&self) -> ConsensusInstance {
         self.consensus_manager.consensus()
     }
@@ -386,6 +430,16 @@ impl FlowContext {
         }
     }
 
+    /// Marks IBD as running for the duration of a snapshot import. There is no syncing peer in this case
+    pub fn try_set_snapshot_import_running(&self) -> Option<IbdRunningGuard> {
+        if self.is_ibd_running.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+            self.ibd_metadata.write().take();
+            Some(IbdRunningGuard { indicator: self.is_ibd_running.clone() })
+        } else {
+            None
+        }
+    }
+
     pub fn is_ibd_running(&self) -> bool {
         self.is_ibd_running.load(Ordering::SeqCst)
     }
@@ -580,7 +634,11 @@ impl FlowContext {
                     tokio::spawn(async move {
                         mining_manager.revalidate_high_priority_transactions(&consensus_clone, tx).await;
                     });
-                    while let Some(transactions) = rx.recv().await {
+                    while let Some(mut transactions) = rx.recv().await {
+                        // Transactions under embargo are still travelling along the Dandelion stem
+                        if let Some(dandelion) = context.dandelion() {
+                            dandelion.remove_embargoed(&mut transactions);
+                        }
                         let _ = context
                             .broadcast_transactions(
                                 transactions,
@@ -618,16 +676,43 @@ impl FlowContext {
         transaction: Transaction,
         orphan: Orphan,
     ) -> Result<(), ProtocolError> {
-        let accepted_transactions =
-            self.mining_manager().clone().validate_and_insert_transaction(consensus, transaction, Priority::High, orphan).await?;
-        self.broadcast_transactions(
-            accepted_transactions.iter().map(|x| x.id()),
-            false, // RPC transactions are considered high priority, so we don't want to throttle them
-        )
-        .await;
+        let transaction_insertion = self
+            .mining_manager()
+            .clone()
+            .validate_and_insert_transaction(consensus, transaction, Priority::High, orphan, RbfPolicy::Forbidden)
+            .await?;
+        self.relay_stem_transactions(&transaction_insertion.accepted, None).await;
         Ok(())
     }
 
+    /// Replaces the mempool transactions double spent by `transaction` if the latter pays a strictly higher fee rate,
+    /// the replacement being mandatory or optional depending on `rbf_policy`.
+    ///
+    /// On success, the evicted transactions are reported through a `TransactionsEvicted` notification
+    /// and the replaced transaction, if any, is returned.
+    pub async fn submit_rpc_transaction_replacement(
+        &self,
+        consensus: &ConsensusProxy,
+        transaction: Transaction,
+        rbf_policy: RbfPolicy,
+    ) -> Result<Option<Arc<Transaction>>, ProtocolError> {
+        let transaction_id = transaction.id();
+        let transaction_insertion = self
+            .mining_manager()
+            .clone()
+            .validate_and_insert_transaction(consensus, transaction, Priority::High, Orphan::Forbidden, rbf_policy)
+            .await?;
+        self.relay_stem_transactions(&transaction_insertion.accepted, None).await;
+        if !transaction_insertion.evicted.is_empty() {
+            // Notifications from the flow context might be ignored if the inner channel is already closing
+            let _ = self.notification_root.notify(Notification::TransactionsEvicted(TransactionsEvictedNotification::new(
+                transaction_id,
+                Arc::new(transaction_insertion.evicted),
+            )));
+        }
+        Ok(transaction_insertion.replaced)
+    }
+
     /// Returns true if the time has come for running the task cleaning mempool transactions.
     async fn should_run_mempool_scanning_task(&self) -> bool {
         self.transactions_spread.write().await.should_run_mempool_scanning_task()
@@ -654,13 +739,67 @@ impl FlowContext {
     pub async fn broadcast_transactions<I: IntoIterator<Item = TransactionId>>(&self, transaction_ids: I, should_throttle: bool) {
         self.transactions_spread.write().await.broadcast_transactions(transaction_ids, should_throttle).await
     }
+
+    /// Returns the Dandelion relay state, if Dandelion relay is enabled
+    pub fn dandelion(&self) -> Option<&Arc<Dandelion>> {
+        self.dandelion.as_ref()
+    }
+
+    /// Relays transactions which were submitted to this node or received along a Dandelion stem from `source`.
+    ///
+    /// With Dandelion relay enabled, the transactions are forwarded to the stem peer and only broadcast to all peers if
+    /// they are not seen diffused by the time their embargo expires. Otherwise, or if there is no stem peer, they are
+    /// broadcast right away.
+    pub async fn relay_stem_transactions(&self, transactions: &[Arc<Transaction>], source: Option<PeerKey>) {
+        let transaction_ids = transactions.iter().map(|tx| tx.id()).collect::<Vec<_>>();
+        let dandelion = match self.dandelion.as_ref() {
+            Some(dandelion) if dandelion.stem(transactions, source).await => dandelion.clone(),
+            _ => {
+                // RPC transactions are considered high priority, so we do not throttle relayed transactions
+                self.broadcast_transactions(transaction_ids, false).await;
+                return;
+            }
+        };
+
+        let context = self.clone();
+        tokio::spawn(async move {
+            let expired = dandelion.expire_embargoes(transaction_ids).await;
+            // Transactions which left the mempool in the meantime are not announced, since peers penalize announcements
+            // of unknown transactions
+            let mut transactions_to_broadcast = Vec::with_capacity(expired.len());
+            for transaction_id in expired {
+                if context.mining_manager().clone().has_transaction(transaction_id, TransactionQuery::TransactionsOnly).await {
+                    transactions_to_broadcast.push(transaction_id);
+                }
+            }
+            if !transactions_to_broadcast.is_empty() {
+                debug!("Dandelion embargo expired for {} transactions, broadcasting them", transactions_to_broadcast.len());
+                context.broadcast_transactions(transactions_to_broadcast, false).await;
+            }
+        });
+    }
 }
 
 #[async_trait]
 impl ConnectionInitializer for FlowContext {
     async fn initialize_connection(&self, router: Arc<Router>) -> Result<(), ProtocolError> {
         // Build the handshake object and subscribe to handshake messages
-        let mut handshake = WaglayladHandshake::new(&router);
+        let mut handshake = WaglayladHandshake::new(&router, self.identity.as_ref());
+
+        // Reject banned peers. Note that permanent connection requests are never considered banned
+        if let Some(connection_manager) = self.connection_manager() {
+            if connection_manager.is_banned(&router.net_address()).await {
+                return Err(ProtocolError::OtherOwned(format!("peer {} is banned", router.net_address().ip())));
+            }
+            // A peer with a pinned identity key must authenticate with it, even if its keys were stripped in transit
+            if let Some(identity_key) = connection_manager.pinned_identity_key(router.net_address().ip()) {
+                handshake.expect_identity_key(identity_key);
+            }
+        }
+
+        // Carry over the misbehaviour score of recent connections from the same IP, so that disconnecting is no escape from a ban
+        let misbehaviour = self.recent_misbehaviour.lock().get(&router.net_address().ip()).copied();
+        router.set_misbehaviour(misbehaviour.unwrap_or_else(|| MisbehaviourScore::new(self.config.ban_score_decay)));
 
         // We start the router receive loop only after we registered to handshake routes
         router.start();
@@ -697,10 +836,15 @@ impl ConnectionInitializer for FlowContext {
         }
 
         debug!("protocol versions - self: {}, peer: {}", PROTOCOL_VERSION, peer_version.protocol_version);
+        debug!("P2P, connection with peer {} is {}", router, if router.is_encrypted() { "encrypted" } else { "not encrypted" });
+        if let Some(identity_key) = router.identity_key() {
+            info!("Peer {} authenticated with identity key {}", router, identity_key);
+        }
 
         // Register all flows according to version
         let (flows, applied_protocol_version) = match peer_version.protocol_version {
-            v if v >= PROTOCOL_VERSION => (v6::register(self.clone(), router.clone()), PROTOCOL_VERSION),
+            v if v >= PROTOCOL_VERSION => (v7::register(self.clone(), router.clone()), PROTOCOL_VERSION),
+            8 => (v6::register(self.clone(), router.clone()), 8),
             5 => (v5::register(self.clone(), router.clone()), 5),
             v => return Err(ProtocolError::VersionMismatch(PROTOCOL_VERSION, v)),
         };
@@ -723,7 +867,7 @@ impl ConnectionInitializer for FlowContext {
 
         // Launch all flows. Note we launch only after the ready signal was exchanged
         for flow in flows {
-            flow.launch();
+            flow.launch(self.clone());
         }
 
         if router.is_outbound() || peer_version.address.is_some() {
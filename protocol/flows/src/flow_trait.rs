@@ -1,3 +1,4 @@
+use crate::flow_context::FlowContext;
 use waglayla_core::warn;
 use waglayla_p2p_lib::{common::ProtocolError, Router};
 use waglayla_utils::any::type_name_short;
@@ -16,7 +17,7 @@ where
 
     async fn start(&mut self) -> Result<(), ProtocolError>;
 
-    fn launch(mut self: Box<Self>) {
+    fn launch(mut self: Box<Self>, ctx: FlowContext) {
         tokio::spawn(async move {
             let res = self.start().await;
             if let Err(err) = res {
@@ -25,6 +26,9 @@ where
                     if router.close().await || !err.is_connection_closed_error() {
                         warn!("{} flow error: {}, disconnecting from peer {}.", self.name(), err, router);
                     }
+                    // Note: this is called even for zero-score errors so that a threshold crossed
+                    // by the router itself (e.g. on routing errors) is also acted upon
+                    ctx.add_misbehaviour(&router, err.misbehaviour_score(), &err).await;
                 }
             }
         });
@@ -0,0 +1,287 @@
+//!
+//! Dandelion transaction relay. Instead of being announced to all peers right away, transactions submitted to
+//! the node are first forwarded along a stem: a path of randomly chosen peers, each of which passes them on to its
+//! own stem peer until one of them diffuses (fluffs) them to the whole network. An observer can then no longer
+//! link a transaction to its origin by looking at which node announced it first.
+//!
+//! Every node along the stem embargoes the transactions it forwarded. If they are not seen diffused by the time
+//! the embargo expires, for instance because a stem peer dropped them, the node diffuses them itself.
+//!
+
+use parking_lot::Mutex;
+use rand::{seq::IteratorRandom, thread_rng, Rng};
+use waglayla_consensus_core::tx::{Transaction, TransactionId};
+use waglayla_p2p_lib::{
+    make_message,
+    pb::{waglaylad_message::Payload, StemTransactionsMessage},
+    Hub, Peer, PeerKey,
+};
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The first protocol version supporting stem transaction relay
+const STEM_PROTOCOL_VERSION: u32 = 9;
+
+/// The probability for a node receiving stem transactions to diffuse them rather than forwarding them along
+/// the stem, resulting in an expected stem length of 5 hops
+const FLUFF_PROBABILITY: f64 = 0.2;
+
+/// The minimum time to wait for forwarded transactions to be diffused before diffusing them ourselves.
+/// A random delay of up to half this time is added to every embargo
+const EMBARGO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The time after which a new stem peer is selected
+const STEM_EPOCH: Duration = Duration::from_secs(600);
+
+struct StemPeer {
+    key: PeerKey,
+    selection_time: Instant,
+}
+
+pub struct Dandelion {
+    hub: Hub,
+    embargo_timeout: Duration,
+    stem_peer: Mutex<Option<StemPeer>>,
+    embargoes: Mutex<HashSet<TransactionId>>,
+}
+
+impl Dandelion {
+    pub fn new(hub: Hub) -> Self {
+        Self::with_embargo_timeout(hub, EMBARGO_TIMEOUT)
+    }
+
+    fn with_embargo_timeout(hub: Hub, embargo_timeout: Duration) -> Self {
+        Self { hub, embargo_timeout, stem_peer: Default::default(), embargoes: Default::default() }
+    }
+
+    /// Returns true if transactions received along the stem should be diffused to all peers instead of being
+    /// forwarded further
+    pub fn should_fluff(&self) -> bool {
+        thread_rng().gen_bool(FLUFF_PROBABILITY)
+    }
+
+    /// Only outbound peers are eligible as stem peers, since inbound connections can be cheaply made by an observer
+    /// trying to get on the stem of many nodes
+    fn is_stem_candidate(peer: &Peer) -> bool {
+        let properties = peer.properties();
+        peer.is_outbound() && properties.protocol_version >= STEM_PROTOCOL_VERSION && !properties.disable_relay_tx
+    }
+
+    /// Returns the stem peer of the current epoch, selecting a new one if the epoch ended or the peer disconnected.
+    /// Returns `None` if there is no eligible peer or if the stem peer is `source`, the peer the transactions were
+    /// received from
+    fn stem_peer(&self, source: Option<PeerKey>) -> Option<PeerKey> {
+        let mut stem_peer = self.stem_peer.lock();
+        let now = Instant::now();
+        let is_valid = stem_peer.as_ref().is_some_and(|peer| now < peer.selection_time + STEM_EPOCH && self.hub.has_peer(peer.key));
+        if !is_valid {
+            *stem_peer = self
+                .hub
+                .active_peers()
+                .into_iter()
+                .filter(Self::is_stem_candidate)
+                .choose(&mut thread_rng())
+                .map(|peer| StemPeer { key: peer.key(), selection_time: now });
+        }
+        stem_peer.as_ref().map(|peer| peer.key).filter(|key| Some(*key) != source)
+    }
+
+    /// Forwards `transactions` to the stem peer and embargoes them. Returns false if the transactions could not be
+    /// forwarded, in which case the caller is expected to diffuse them
+    pub async fn stem(&self, transactions: &[Arc<Transaction>], source: Option<PeerKey>) -> bool {
+        if transactions.is_empty() {
+            return true;
+        }
+        let Some(stem_peer) = self.stem_peer(source) else {
+            return false;
+        };
+        // Embargo before sending, so that the transactions cannot be seen diffused before being embargoed
+        self.embargoes.lock().extend(transactions.iter().map(|tx| tx.id()));
+        let msg = make_message!(
+            Payload::StemTransactions,
+            StemTransactionsMessage { transactions: transactions.iter().map(|tx| (&**tx).into()).collect() }
+        );
+        if matches!(self.hub.send(stem_peer, msg).await, Ok(true)) {
+            return true;
+        }
+        self.lift_embargoes(transactions.iter().map(|tx| tx.id()));
+        false
+    }
+
+    /// Lifts the embargo of transactions which were seen diffused by the network
+    pub fn lift_embargoes(&self, transaction_ids: impl IntoIterator<Item = TransactionId>) {
+        let mut embargoes = self.embargoes.lock();
+        if embargoes.is_empty() {
+            return;
+        }
+        transaction_ids.into_iter().for_each(|id| {
+            embargoes.remove(&id);
+        });
+    }
+
+    pub fn is_embargoed(&self, transaction_id: &TransactionId) -> bool {
+        self.embargoes.lock().contains(transaction_id)
+    }
+
+    /// Removes the transactions under embargo from `transaction_ids`, since these must not be announced while
+    /// still travelling along the stem
+    pub fn remove_embargoed(&self, transaction_ids: &mut Vec<TransactionId>) {
+        let embargoes = self.embargoes.lock();
+        transaction_ids.retain(|id| !embargoes.contains(id));
+    }
+
+    /// Waits for the embargo of `transaction_ids` to expire and returns the transactions which were not seen
+    /// diffused in the meantime and thus must be diffused by this node
+    pub async fn expire_embargoes(&self, transaction_ids: Vec<TransactionId>) -> Vec<TransactionId> {
+        let jitter = thread_rng().gen_range(0..=self.embargo_timeout.as_millis() as u64 / 2);
+        tokio::time::sleep(self.embargo_timeout + Duration::from_millis(jitter)).await;
+        let mut embargoes = self.embargoes.lock();
+        transaction_ids.into_iter().filter(|id| embargoes.remove(id)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use waglayla_consensus_core::{
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::{TransactionInput, TransactionOutpoint},
+    };
+    use waglayla_p2p_lib::{
+        common::ProtocolError, pb::VersionMessage, Adaptor, ConnectionInitializer, PeerProperties, Router, WaglayladHandshake,
+        WaglayladMessagePayloadType,
+    };
+    use waglayla_utils::networking::NetAddress;
+    use std::str::FromStr;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+    use uuid::Uuid;
+
+    /// Performs the handshake and reports the number of transactions of every received stem message
+    struct StemInitializer {
+        sender: UnboundedSender<usize>,
+    }
+
+    #[async_trait]
+    impl ConnectionInitializer for StemInitializer {
+        async fn initialize_connection(&self, router: Arc<Router>) -> Result<(), ProtocolError> {
+            let mut handshake = WaglayladHandshake::new(&router, None);
+            router.start();
+            let self_version_message = VersionMessage {
+                protocol_version: STEM_PROTOCOL_VERSION,
+                id: Vec::from(Uuid::new_v4().as_ref()),
+                network: "waglayla-simnet".to_string(),
+                ..Default::default()
+            };
+            let peer_version_message = handshake.handshake(self_version_message).await?;
+            router.set_properties(Arc::new(PeerProperties {
+                protocol_version: peer_version_message.protocol_version,
+                ..Default::default()
+            }));
+
+            let mut stem_route = router.subscribe(vec![WaglayladMessagePayloadType::StemTransactions]);
+            let sender = self.sender.clone();
+            tokio::spawn(async move {
+                while let Some(msg) = stem_route.recv().await {
+                    if let Some(Payload::StemTransactions(msg)) = msg.payload {
+                        let _ = sender.send(msg.transactions.len());
+                    }
+                }
+            });
+
+            handshake.exchange_ready_messages().await?;
+            Ok(())
+        }
+    }
+
+    fn stem_initializer() -> (Arc<StemInitializer>, UnboundedReceiver<usize>) {
+        let (sender, receiver) = unbounded_channel();
+        (Arc::new(StemInitializer { sender }), receiver)
+    }
+
+    fn create_transaction(i: u64) -> Arc<Transaction> {
+        let outpoint = TransactionOutpoint::new(TransactionId::from_u64_word(i), 0);
+        Arc::new(Transaction::new(0, vec![TransactionInput::new(outpoint, vec![], 0, 1)], vec![], 0, SUBNETWORK_ID_NATIVE, 0, vec![]))
+    }
+
+    /// Waits for the hub to hold exactly `count` active peers, since peers are registered by the hub event loop
+    async fn wait_for_active_peers(hub: &Hub, count: usize) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while hub.active_peers().len() != count {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("expected {count} active peers, found {}", hub.active_peers().len()));
+    }
+
+    /// Receives the next stem message of a peer, if any arrives within a short delay
+    async fn recv_stem(receiver: &mut UnboundedReceiver<usize>, delay: Duration) -> Option<usize> {
+        tokio::time::timeout(delay, receiver.recv()).await.ok().flatten()
+    }
+
+    #[tokio::test]
+    async fn test_stem_relay() {
+        // A node connected to three outbound peers
+        let hub = Hub::new();
+        let (initializer, _) = stem_initializer();
+        let adaptor = Adaptor::client_only(hub.clone(), initializer, Default::default(), Default::default());
+        let mut peers = Vec::new();
+        for _ in 0..3 {
+            let (initializer, receiver) = stem_initializer();
+            let address = NetAddress::from_str("[::1]:0").unwrap();
+            let peer_adaptor =
+                Adaptor::bidirectional(address, Hub::new(), initializer, Default::default(), Default::default()).unwrap();
+            let peer_address = peer_adaptor.local_address().unwrap();
+            adaptor.connect_peer(peer_address.to_string()).await.expect("peer connection failed");
+            peers.push((peer_adaptor, receiver));
+        }
+        wait_for_active_peers(&hub, 3).await;
+
+        // Transactions are forwarded along a single stem, which remains the same within the epoch
+        let dandelion = Dandelion::with_embargo_timeout(hub.clone(), Duration::from_millis(200));
+        let transactions = (0..4).map(create_transaction).collect::<Vec<_>>();
+        assert!(dandelion.stem(&transactions[..2], None).await);
+        assert!(dandelion.stem(&transactions[2..], None).await);
+        let stem_peer = dandelion.stem_peer(None).unwrap();
+        let stem_address = hub.active_peers().into_iter().find(|peer| peer.key() == stem_peer).unwrap().net_address();
+        let (stem_receivers, other_receivers): (Vec<_>, Vec<_>) =
+            peers.iter_mut().partition(|(peer_adaptor, _)| peer_adaptor.local_address().unwrap().port() == stem_address.port());
+        assert_eq!(stem_receivers.len(), 1);
+        for (_, receiver) in stem_receivers {
+            assert_eq!(recv_stem(receiver, Duration::from_secs(10)).await, Some(2));
+            assert_eq!(recv_stem(receiver, Duration::from_secs(10)).await, Some(2));
+        }
+        // The stem peer is fixed within the epoch, so no other peer is sent any transactions
+        for (_, receiver) in other_receivers {
+            assert_eq!(receiver.try_recv().ok(), None, "transactions must be sent to a single stem peer");
+        }
+
+        // Transactions are never sent back along the stem to the peer they came from
+        assert!(!dandelion.stem(&transactions[..1], Some(stem_peer)).await);
+
+        // Transactions seen diffused are released from the embargo, the others must be diffused once it expires
+        assert!(transactions.iter().all(|tx| dandelion.is_embargoed(&tx.id())));
+        dandelion.lift_embargoes([transactions[0].id(), transactions[1].id()]);
+        let mut unembargoed = transactions.iter().map(|tx| tx.id()).collect::<Vec<_>>();
+        dandelion.remove_embargoed(&mut unembargoed);
+        assert_eq!(unembargoed, vec![transactions[0].id(), transactions[1].id()]);
+        let expired = dandelion.expire_embargoes(transactions.iter().map(|tx| tx.id()).collect()).await;
+        assert_eq!(expired, vec![transactions[2].id(), transactions[3].id()]);
+        assert!(!transactions.iter().any(|tx| dandelion.is_embargoed(&tx.id())));
+
+        // Without stem peers transactions cannot be stemmed
+        adaptor.terminate_all_peers().await;
+        wait_for_active_peers(&hub, 0).await;
+        assert!(!dandelion.stem(&transactions, None).await);
+        assert!(!transactions.iter().any(|tx| dandelion.is_embargoed(&tx.id())));
+
+        adaptor.close().await;
+        for (peer_adaptor, _) in peers {
+            peer_adaptor.close().await;
+        }
+    }
+}
@@ -2,5 +2,7 @@ pub mod flow_context;
 pub mod flow_trait;
 pub mod flowcontext;
 pub mod service;
+pub mod snapshot;
 pub mod v5;
 pub mod v6;
+pub mod v7;
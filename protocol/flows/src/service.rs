@@ -1,16 +1,20 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use waglayla_addressmanager::NetAddress;
 use waglayla_connectionmanager::ConnectionManager;
 use waglayla_core::{
-    task::service::{AsyncService, AsyncServiceFuture},
-    trace,
+    error,
+    task::service::{AsyncService, AsyncServiceError, AsyncServiceFuture},
+    trace, warn,
 };
-use waglayla_p2p_lib::Adaptor;
-use waglayla_utils::triggers::SingleTrigger;
+use waglayla_p2p_lib::{Adaptor, PinnedPeer, ProxyConfig};
+use waglayla_utils::{networking::IpSubnet, triggers::SingleTrigger};
 use waglayla_utils_tower::counters::TowerConnectionCounters;
 
-use crate::flow_context::FlowContext;
+use crate::{
+    flow_context::FlowContext,
+    snapshot::{export_snapshot, import_snapshot},
+};
 
 const P2P_CORE_SERVICE: &str = "p2p-service";
 
@@ -21,10 +25,16 @@ pub struct P2pService {
     listen: NetAddress,
     outbound_target: usize,
     inbound_limit: usize,
+    whitelist: Vec<IpSubnet>,
+    pinned_peers: Vec<PinnedPeer>,
+    proxy: Option<NetAddress>,
+    onion_proxy: Option<NetAddress>,
     dns_seeders: &'static [&'static str],
     default_port: u16,
     shutdown: SingleTrigger,
     counters: Arc<TowerConnectionCounters>,
+    import_snapshot: Option<PathBuf>,
+    export_snapshot: Option<PathBuf>,
 }
 
 impl P2pService {
@@ -35,9 +45,15 @@ impl P2pService {
         listen: NetAddress,
         outbound_target: usize,
         inbound_limit: usize,
+        whitelist: Vec<IpSubnet>,
+        pinned_peers: Vec<PinnedPeer>,
+        proxy: Option<NetAddress>,
+        onion_proxy: Option<NetAddress>,
         dns_seeders: &'static [&'static str],
         default_port: u16,
         counters: Arc<TowerConnectionCounters>,
+        import_snapshot: Option<PathBuf>,
+        export_snapshot: Option<PathBuf>,
     ) -> Self {
         Self {
             flow_context,
@@ -47,9 +63,15 @@ impl P2pService {
             listen,
             outbound_target,
             inbound_limit,
+            whitelist,
+            pinned_peers,
+            proxy,
+            onion_proxy,
             dns_seeders,
             default_port,
             counters,
+            import_snapshot,
+            export_snapshot,
         }
     }
 }
@@ -65,13 +87,21 @@ impl AsyncService for P2pService {
         // Prepare a shutdown signal receiver
         let shutdown_signal = self.shutdown.listener.clone();
 
-        let p2p_adaptor =
-            Adaptor::bidirectional(self.listen, self.flow_context.hub().clone(), self.flow_context.clone(), self.counters.clone())
-                .unwrap();
+        let proxy_config = ProxyConfig::new(self.proxy.map(Into::into), self.onion_proxy.map(Into::into));
+        let p2p_adaptor = Adaptor::bidirectional(
+            self.listen,
+            self.flow_context.hub().clone(),
+            self.flow_context.clone(),
+            self.counters.clone(),
+            proxy_config,
+        )
+        .unwrap();
         let connection_manager = ConnectionManager::new(
             p2p_adaptor.clone(),
             self.outbound_target,
             self.inbound_limit,
+            self.whitelist.clone(),
+            self.pinned_peers.clone(),
             self.dns_seeders,
             self.default_port,
             self.flow_context.address_manager.clone(),
@@ -82,8 +112,22 @@ impl AsyncService for P2pService {
 
         // Launch the service and wait for a shutdown signal
         Box::pin(async move {
+            // Snapshots are processed before requesting outbound connections. Inbound peers cannot
+            // trigger IBD meanwhile since the import holds the IBD running indicator
+            if let Some(path) = self.import_snapshot.clone() {
+                if let Err(err) = import_snapshot(&self.flow_context, path).await {
+                    error!("Snapshot import failed: {}", err);
+                    return Err(AsyncServiceError::Service(format!("snapshot import failed: {err}")));
+                }
+            }
+            if let Some(path) = self.export_snapshot.clone() {
+                if let Err(err) = export_snapshot(&self.flow_context, path).await {
+                    warn!("Snapshot export failed: {}", err);
+                }
+            }
+
             for peer_address in self.connect_peers.iter().cloned().chain(self.add_peers.iter().cloned()) {
-                connection_manager.add_connection_request(peer_address.into(), true).await;
+                connection_manager.add_connection_request(peer_address, true).await;
             }
 
             // Keep the P2P server running until a service shutdown signal is received
@@ -0,0 +1,455 @@
+//!
+//! Consensus snapshots: a single file holding the pruning point proof, the past pruning points, the pruning point
+//! anticone along with its trusted data, the headers above the pruning point and the pruning point UTXO set.
+//!
+//! Snapshot entries are the very same protobuf messages exchanged during IBD with headers proof, and an imported
+//! snapshot is validated by the same code path as a p2p-received one (see [`crate::v5::ibd::headers_proof`]).
+//! The file is therefore self-verifying: the proof is validated against the local consensus and the UTXO set
+//! must match the UTXO commitment of the proven pruning point.
+//!
+
+use crate::{
+    flow_context::FlowContext,
+    v5::{
+        ibd::{headers_proof, HeadersChunk, UtxosetChunk},
+        request_headers::RequestHeadersFlow,
+    },
+};
+use futures::future::try_join_all;
+use itertools::Itertools;
+use prost::Message;
+use waglayla_consensus_core::{
+    api::{BlockValidationFuture, ConsensusApi},
+    block::Block,
+    errors::{block::RuleError, consensus::ConsensusError},
+    pruning::{PruningPointProof, PruningPointsList},
+    BlockHashMap,
+};
+use waglayla_consensusmanager::{spawn_blocking, StagingConsensus};
+use waglayla_core::{debug, info};
+use waglayla_hashes::Hash;
+use waglayla_muhash::MuHash;
+use waglayla_p2p_lib::{
+    common::ProtocolError,
+    convert::{error::ConversionError, model::trusted::TrustedDataPackage},
+    make_message,
+    pb::{
+        self, waglaylad_message::Payload, BlockHeadersMessage, BlockWithTrustedDataV4Message, DoneBlocksWithTrustedDataMessage,
+        DoneHeadersMessage, DonePruningPointUtxoSetChunksMessage, PruningPointProofMessage, PruningPointUtxoSetChunkMessage,
+        PruningPointsMessage, TrustedDataMessage,
+    },
+};
+use std::{
+    cmp::max,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, BufReader},
+};
+
+/// Magic bytes opening every snapshot file
+const SNAPSHOT_MAGIC: [u8; 8] = *b"WAGSNAP\0";
+
+/// Version of the snapshot file layout
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Matches the maximum p2p message size, since snapshot entries are p2p messages
+const MAX_ENTRY_SIZE: usize = 1024 * 1024 * 1024; // 1GB
+
+/// Number of UTXOs per snapshot chunk, matching the chunks served over p2p
+const UTXO_CHUNK_SIZE: usize = 1000;
+
+/// Upper bound of the buffer preallocated for an entry, so that a corrupted length prefix cannot
+/// trigger a large allocation; bigger entries grow the buffer as their bytes are actually read
+const ENTRY_PREALLOCATION_SIZE: usize = 1024 * 1024; // 1MB
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    #[error("snapshot I/O error: {0}")]
+    IoError(#[from] io::Error),
+
+    #[error("failed decoding snapshot entry: {0}")]
+    DecodeError(#[from] prost::DecodeError),
+
+    #[error("invalid snapshot file: {0}")]
+    InvalidFormat(String),
+
+    #[error("a snapshot can only be exported after the first pruning point was reached")]
+    GenesisPruningPoint,
+
+    #[error("the pruning point moved while exporting the snapshot")]
+    PruningPointMoved,
+
+    #[error("cannot import a snapshot while IBD is running")]
+    IbdRunning,
+
+    #[error("{0}")]
+    ConversionError(#[from] ConversionError),
+
+    #[error("{0}")]
+    ProtocolError(#[from] ProtocolError),
+
+    #[error("{0}")]
+    ConsensusError(#[from] ConsensusError),
+
+    #[error("{0}")]
+    RuleError(#[from] RuleError),
+}
+
+pub type SnapshotResult<T> = std::result::Result<T, SnapshotError>;
+
+/// Reads the next snapshot entry, expecting it to hold the given payload type
+macro_rules! read_payload {
+    ($reader:expr, $pattern:path) => {
+        match $reader.read().await? {
+            $pattern(inner) => inner,
+            payload => return Err(ProtocolError::UnexpectedMessage(stringify!($pattern), Some((&payload).into())).into()),
+        }
+    };
+}
+
+struct SnapshotWriter {
+    writer: BufWriter<std::fs::File>,
+}
+
+impl SnapshotWriter {
+    fn create(path: &Path) -> SnapshotResult<Self> {
+        let mut writer = BufWriter::new(std::fs::File::create(path)?);
+        writer.write_all(&SNAPSHOT_MAGIC)?;
+        writer.write_all(&SNAPSHOT_VERSION.to_le_bytes())?;
+        Ok(Self { writer })
+    }
+
+    fn write(&mut self, msg: pb::WaglayladMessage) -> SnapshotResult<()> {
+        let bytes = msg.encode_to_vec();
+        if bytes.len() > MAX_ENTRY_SIZE {
+            return Err(SnapshotError::InvalidFormat(format!("entry of {} bytes exceeds the maximum entry size", bytes.len())));
+        }
+        self.writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&bytes)?;
+        Ok(())
+    }
+
+    fn finish(self) -> SnapshotResult<()> {
+        let file = self.writer.into_inner().map_err(|err| err.into_error())?;
+        file.sync_all()?;
+        Ok(())
+    }
+}
+
+struct SnapshotReader {
+    reader: BufReader<File>,
+}
+
+impl SnapshotReader {
+    async fn open(path: &Path) -> SnapshotResult<Self> {
+        let mut reader = BufReader::new(File::open(path).await?);
+        let mut magic = [0u8; SNAPSHOT_MAGIC.len()];
+        reader.read_exact(&mut magic).await?;
+        if magic != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::InvalidFormat("not a snapshot file".to_string()));
+        }
+        let version = reader.read_u32_le().await?;
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::InvalidFormat(format!("unsupported snapshot version {version}")));
+        }
+        Ok(Self { reader })
+    }
+
+    async fn read(&mut self) -> SnapshotResult<Payload> {
+        let len = self.reader.read_u32_le().await? as usize;
+        if len > MAX_ENTRY_SIZE {
+            return Err(SnapshotError::InvalidFormat(format!("entry of {len} bytes exceeds the maximum entry size")));
+        }
+        let mut bytes = Vec::with_capacity(len.min(ENTRY_PREALLOCATION_SIZE));
+        (&mut self.reader).take(len as u64).read_to_end(&mut bytes).await?;
+        if bytes.len() < len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        pb::WaglayladMessage::decode(bytes.as_slice())?
+            .payload
+            .ok_or_else(|| SnapshotError::InvalidFormat("snapshot entry has no payload".to_string()))
+    }
+}
+
+/// Exports a snapshot of current consensus to `path`. Returns the pruning point of the snapshot.
+pub async fn export_snapshot(ctx: &FlowContext, path: PathBuf) -> SnapshotResult<Hash> {
+    const MAX_BLOCKS: usize = 1 << 10;
+    const MAX_EXPORT_ATTEMPTS: usize = 3;
+    // Internal consensus logic requires that `max_blocks > mergeset_size_limit`
+    let max_blocks = max(MAX_BLOCKS, ctx.config.mergeset_size_limit as usize + 1);
+    let genesis = ctx.config.genesis.hash;
+
+    // A consensus session does not prevent the pruning point from moving while writing, in which case the export is retried
+    let mut attempt = 1;
+    loop {
+        let session = ctx.consensus().session().await;
+        let attempt_path = path.clone();
+        match session.spawn_blocking(move |c| write_snapshot(c, &attempt_path, genesis, max_blocks)).await {
+            Err(SnapshotError::PruningPointMoved | SnapshotError::ConsensusError(ConsensusError::UnexpectedPruningPoint))
+                if attempt < MAX_EXPORT_ATTEMPTS =>
+            {
+                info!("The pruning point moved while exporting the snapshot, retrying ({}/{})", attempt + 1, MAX_EXPORT_ATTEMPTS);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn write_snapshot(consensus: &dyn ConsensusApi, path: &Path, genesis: Hash, max_blocks: usize) -> SnapshotResult<Hash> {
+    let pruning_point = consensus.pruning_point();
+    if pruning_point == genesis {
+        return Err(SnapshotError::GenesisPruningPoint);
+    }
+
+    let mut writer = SnapshotWriter::create(path)?;
+
+    let proof = consensus.get_pruning_point_proof();
+    writer.write(make_message!(
+        Payload::PruningPointProof,
+        PruningPointProofMessage { headers: proof.iter().map(|headers| headers.into()).collect() }
+    ))?;
+
+    let pp_headers = consensus.pruning_point_headers();
+    writer.write(make_message!(
+        Payload::PruningPoints,
+        PruningPointsMessage { headers: pp_headers.into_iter().map(|header| <pb::BlockHeader>::from(&*header)).collect() }
+    ))?;
+
+    let trusted_data = consensus.get_pruning_point_anticone_and_trusted_data()?;
+    let daa_window = &trusted_data.daa_window_blocks;
+    let ghostdag_data = &trusted_data.ghostdag_blocks;
+    writer.write(make_message!(
+        Payload::TrustedData,
+        TrustedDataMessage {
+            daa_window: daa_window.iter().map(|daa_block| daa_block.into()).collect_vec(),
+            ghostdag_data: ghostdag_data.iter().map(|gd| gd.into()).collect_vec()
+        }
+    ))?;
+
+    let daa_window_hash_to_index =
+        BlockHashMap::from_iter(daa_window.iter().enumerate().map(|(i, trusted_header)| (trusted_header.header.hash, i)));
+    let ghostdag_data_hash_to_index =
+        BlockHashMap::from_iter(ghostdag_data.iter().enumerate().map(|(i, trusted_gd)| (trusted_gd.hash, i)));
+    for &hash in trusted_data.anticone.iter() {
+        let daa_window_indices =
+            consensus.get_daa_window(hash)?.into_iter().map(|hash| *daa_window_hash_to_index.get(&hash).unwrap() as u64).collect_vec();
+        let ghostdag_data_indices = consensus
+            .get_trusted_block_associated_ghostdag_data_block_hashes(hash)?
+            .into_iter()
+            .map(|hash| *ghostdag_data_hash_to_index.get(&hash).unwrap() as u64)
+            .collect_vec();
+        let block = consensus.get_block(hash)?;
+        writer.write(make_message!(
+            Payload::BlockWithTrustedDataV4,
+            BlockWithTrustedDataV4Message { block: Some((&block).into()), daa_window_indices, ghostdag_data_indices }
+        ))?;
+    }
+    writer.write(make_message!(Payload::DoneBlocksWithTrustedData, DoneBlocksWithTrustedDataMessage {}))?;
+    debug!("Wrote the pruning point anticone ({} blocks) to the snapshot", trusted_data.anticone.len());
+
+    let (mut low, high) = (pruning_point, consensus.get_sink());
+    let mut headers_count = 0;
+    while low != high {
+        let (block_headers, last) = RequestHeadersFlow::get_headers_between(consensus, low, high, max_blocks)?;
+        headers_count += block_headers.len();
+        low = last;
+        writer.write(make_message!(Payload::BlockHeaders, BlockHeadersMessage { block_headers }))?;
+    }
+    writer.write(make_message!(Payload::DoneHeaders, DoneHeadersMessage {}))?;
+    debug!("Wrote {} headers to the snapshot", headers_count);
+
+    let mut from_outpoint = None;
+    let mut utxos_count = 0;
+    loop {
+        let pruning_point_utxos =
+            consensus.get_pruning_point_utxos(pruning_point, from_outpoint, UTXO_CHUNK_SIZE, utxos_count != 0)?;
+        utxos_count += pruning_point_utxos.len();
+        writer.write(make_message!(
+            Payload::PruningPointUtxoSetChunk,
+            PruningPointUtxoSetChunkMessage {
+                outpoint_and_utxo_entry_pairs: pruning_point_utxos
+                    .iter()
+                    .map(|(outpoint, entry)| (outpoint, entry).into())
+                    .collect_vec()
+            }
+        ))?;
+        if pruning_point_utxos.len() < UTXO_CHUNK_SIZE {
+            break;
+        }
+        from_outpoint = Some(pruning_point_utxos.last().expect("not empty by prev condition").0);
+    }
+    writer.write(make_message!(Payload::DonePruningPointUtxoSetChunks, DonePruningPointUtxoSetChunksMessage {}))?;
+    debug!("Wrote {} pruning point UTXOs to the snapshot", utxos_count);
+
+    // The written proof, headers and UTXO set are only consistent if they were all read for the same pruning point
+    if consensus.pruning_point() != pruning_point {
+        return Err(SnapshotError::PruningPointMoved);
+    }
+    writer.finish()?;
+    info!("Exported snapshot with pruning point {} to {}", pruning_point, path.display());
+    Ok(pruning_point)
+}
+
+/// Imports the snapshot at `path` into a staging consensus, validating it exactly as IBD with headers proof
+/// does, and commits the staging consensus on success. Returns the pruning point of the snapshot.
+pub async fn import_snapshot(ctx: &FlowContext, path: PathBuf) -> SnapshotResult<Hash> {
+    let Some(_guard) = ctx.try_set_snapshot_import_running() else {
+        return Err(SnapshotError::IbdRunning);
+    };
+
+    let mut reader = SnapshotReader::open(&path).await?;
+    info!("Starting snapshot import from {}", path.display());
+
+    let staging = ctx.consensus_manager.new_staging_consensus();
+    match import_into_staging(ctx, &staging, &mut reader).await {
+        Ok(pruning_point) => {
+            spawn_blocking(|| staging.commit()).await.unwrap();
+            info!("Snapshot with pruning point {} was imported successfully. Committed staging consensus.", pruning_point);
+            ctx.on_pruning_point_utxoset_override();
+            Ok(pruning_point)
+        }
+        Err(e) => {
+            info!("Snapshot import from {} was unsuccessful ({})", path.display(), e);
+            staging.cancel();
+            Err(e)
+        }
+    }
+}
+
+async fn import_into_staging(ctx: &FlowContext, staging: &StagingConsensus, reader: &mut SnapshotReader) -> SnapshotResult<Hash> {
+    let staging_session = staging.session().await;
+
+    let proof: PruningPointProof = read_payload!(reader, Payload::PruningPointProof).try_into()?;
+    debug!("read proof with overall {} headers", proof.iter().map(|l| l.len()).sum::<usize>());
+    let (proof, pruning_point) = headers_proof::validate_pruning_proof(ctx, proof).await?;
+
+    let pruning_points: PruningPointsList = read_payload!(reader, Payload::PruningPoints).try_into()?;
+    headers_proof::validate_pruning_points(ctx, pruning_point, &pruning_points).await?;
+
+    let pkg: TrustedDataPackage = read_payload!(reader, Payload::TrustedData).try_into()?;
+    debug!("read trusted data with {} daa entries and {} ghostdag entries", pkg.daa_window.len(), pkg.ghostdag_window.len());
+    let mut entries = Vec::new();
+    loop {
+        match reader.read().await? {
+            Payload::BlockWithTrustedDataV4(payload) => entries.push(headers_proof::validate_trusted_entry(payload.try_into()?)?),
+            Payload::DoneBlocksWithTrustedData(_) => break,
+            payload => {
+                return Err(ProtocolError::UnexpectedMessage(
+                    "Payload::BlockWithTrustedDataV4 | Payload::DoneBlocksWithTrustedData",
+                    Some((&payload).into()),
+                )
+                .into())
+            }
+        }
+    }
+    let mut entries = entries.into_iter();
+    let pruning_point_entry = headers_proof::validate_pruning_point_entry(entries.next(), pruning_point)?;
+    let trusted_set = pkg.build_trusted_subdag(std::iter::once(pruning_point_entry).chain(entries).collect())?;
+    headers_proof::apply_pruning_proof_and_trusted_set(ctx, &staging_session, proof, pruning_points, trusted_set).await?;
+
+    let mut headers_count = 0;
+    loop {
+        match reader.read().await? {
+            Payload::BlockHeaders(payload) => {
+                let chunk: HeadersChunk = payload.try_into()?;
+                headers_count += chunk.len();
+                let jobs: Vec<BlockValidationFuture> = chunk
+                    .into_iter()
+                    .map(|h| staging_session.validate_and_insert_block(Block::from_header_arc(h)).virtual_state_task)
+                    .collect();
+                try_join_all(jobs).await?;
+                debug!("Processed {} snapshot headers", headers_count);
+            }
+            Payload::DoneHeaders(_) => break,
+            payload => {
+                return Err(
+                    ProtocolError::UnexpectedMessage("Payload::BlockHeaders | Payload::DoneHeaders", Some((&payload).into())).into()
+                )
+            }
+        }
+    }
+    info!("Done processing {} snapshot headers", headers_count);
+
+    staging_session.async_validate_pruning_points().await?;
+    headers_proof::validate_staging_timestamps(&ctx.consensus().session().await, &staging_session).await?;
+
+    let mut multiset = MuHash::new();
+    loop {
+        match reader.read().await? {
+            Payload::PruningPointUtxoSetChunk(payload) => {
+                let chunk: UtxosetChunk = payload.try_into()?;
+                multiset = headers_proof::append_pruning_point_utxoset_chunk(&staging_session, chunk, multiset).await;
+            }
+            Payload::DonePruningPointUtxoSetChunks(_) => break,
+            payload => {
+                return Err(ProtocolError::UnexpectedMessage(
+                    "Payload::PruningPointUtxoSetChunk | Payload::DonePruningPointUtxoSetChunks",
+                    Some((&payload).into()),
+                )
+                .into())
+            }
+        }
+    }
+    headers_proof::import_pruning_point_utxoset(&staging_session, pruning_point, multiset).await?;
+
+    Ok(pruning_point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_snapshot_entries_roundtrip() {
+        let path = std::env::temp_dir().join(format!("waglayla-snapshot-test-{}", uuid::Uuid::new_v4()));
+
+        let mut writer = SnapshotWriter::create(&path).unwrap();
+        writer.write(make_message!(Payload::DoneHeaders, DoneHeadersMessage {})).unwrap();
+        writer.write(make_message!(Payload::DonePruningPointUtxoSetChunks, DonePruningPointUtxoSetChunksMessage {})).unwrap();
+        writer.finish().unwrap();
+
+        let mut reader = SnapshotReader::open(&path).await.unwrap();
+        assert!(matches!(reader.read().await.unwrap(), Payload::DoneHeaders(_)));
+        assert!(matches!(reader.read().await.unwrap(), Payload::DonePruningPointUtxoSetChunks(_)));
+        assert!(matches!(reader.read().await, Err(SnapshotError::IoError(_))));
+
+        std::fs::write(&path, b"NOTASNAPSHOT").unwrap();
+        assert!(matches!(SnapshotReader::open(&path).await, Err(SnapshotError::InvalidFormat(_))));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_truncated_entry() {
+        let path = std::env::temp_dir().join(format!("waglayla-snapshot-test-{}", uuid::Uuid::new_v4()));
+
+        // An entry claiming the maximum size but holding a few bytes only must fail without allocating its claimed size
+        let mut bytes = SNAPSHOT_MAGIC.to_vec();
+        bytes.extend(SNAPSHOT_VERSION.to_le_bytes());
+        bytes.extend((MAX_ENTRY_SIZE as u32).to_le_bytes());
+        bytes.extend([0u8; 16]);
+        std::fs::write(&path, bytes).unwrap();
+
+        let mut reader = SnapshotReader::open(&path).await.unwrap();
+        match reader.read().await {
+            Err(SnapshotError::IoError(err)) => assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof),
+            res => panic!("expected an unexpected EOF error, got {res:?}"),
+        }
+
+        // Entries above the maximum size are rejected before reading their bytes
+        let mut bytes = SNAPSHOT_MAGIC.to_vec();
+        bytes.extend(SNAPSHOT_VERSION.to_le_bytes());
+        bytes.extend((MAX_ENTRY_SIZE as u32 + 1).to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+
+        let mut reader = SnapshotReader::open(&path).await.unwrap();
+        assert!(matches!(reader.read().await, Err(SnapshotError::InvalidFormat(_))));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
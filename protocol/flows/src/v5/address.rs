@@ -7,7 +7,6 @@ use waglayla_p2p_lib::{
     pb::{waglaylad_message::Payload, AddressesMessage, RequestAddressesMessage},
     IncomingRoute, Router,
 };
-use waglayla_utils::networking::IpAddress;
 use rand::seq::SliceRandom;
 use std::sync::Arc;
 
@@ -44,18 +43,18 @@ impl ReceiveAddressesFlow {
         self.router
             .enqueue(make_message!(
                 Payload::RequestAddresses,
-                RequestAddressesMessage { include_all_subnetworks: false, subnetwork_id: None }
+                RequestAddressesMessage { include_all_subnetworks: false, subnetwork_id: None, include_onion_addresses: true }
             ))
             .await?;
 
         let msg = dequeue_with_timeout!(self.incoming_route, Payload::Addresses)?;
-        let address_list: Vec<(IpAddress, u16)> = msg.try_into()?;
+        let address_list: Vec<NetAddress> = msg.try_into()?;
         if address_list.len() > MAX_ADDRESSES_RECEIVE {
             return Err(ProtocolError::OtherOwned(format!("address count {} exceeded {}", address_list.len(), MAX_ADDRESSES_RECEIVE)));
         }
         let mut amgr_lock = self.ctx.address_manager.lock();
-        for (ip, port) in address_list {
-            amgr_lock.add_address(NetAddress::new(ip, port))
+        for address in address_list {
+            amgr_lock.add_address(address)
         }
 
         Ok(())
@@ -86,12 +85,17 @@ impl SendAddressesFlow {
 
     async fn start_impl(&mut self) -> Result<(), ProtocolError> {
         loop {
-            dequeue!(self.incoming_route, Payload::RequestAddresses)?;
-            let addresses = self.ctx.address_manager.lock().iterate_addresses().collect_vec();
-            let address_list = addresses
-                .choose_multiple(&mut rand::thread_rng(), MAX_ADDRESSES_SEND)
-                .map(|addr| (addr.ip, addr.port).into())
-                .collect();
+            let request = dequeue!(self.incoming_route, Payload::RequestAddresses)?;
+            // Onion addresses are only sent to peers which signal they support them
+            let addresses = self
+                .ctx
+                .address_manager
+                .lock()
+                .iterate_addresses()
+                .filter(|addr| request.include_onion_addresses || !addr.is_onion())
+                .collect_vec();
+            let address_list =
+                addresses.choose_multiple(&mut rand::thread_rng(), MAX_ADDRESSES_SEND).map(|addr| (*addr).into()).collect();
             self.router.enqueue(make_message!(Payload::Addresses, AddressesMessage { address_list })).await?;
         }
     }
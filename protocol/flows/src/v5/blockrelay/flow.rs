@@ -3,14 +3,20 @@ use crate::{
     flow_trait::Flow,
     flowcontext::orphans::OrphanOutput,
 };
-use waglayla_consensus_core::{api::BlockValidationFutures, block::Block, blockstatus::BlockStatus, errors::block::RuleError};
+use waglayla_consensus_core::{
+    api::BlockValidationFutures, block::Block, blockstatus::BlockStatus, errors::block::RuleError, tx::Transaction,
+};
 use waglayla_consensusmanager::{BlockProcessingBatch, ConsensusProxy};
 use waglayla_core::debug;
 use waglayla_hashes::Hash;
 use waglayla_p2p_lib::{
     common::ProtocolError,
+    convert::model::compact::{CompactBlock, PartialBlock},
     dequeue, dequeue_with_timeout, make_message, make_request,
-    pb::{waglaylad_message::Payload, InvRelayBlockMessage, RequestBlockLocatorMessage, RequestRelayBlocksMessage},
+    pb::{
+        waglaylad_message::Payload, InvRelayBlockMessage, RequestBlockLocatorMessage, RequestBlockTransactionsMessage,
+        RequestCompactBlocksMessage, RequestRelayBlocksMessage,
+    },
     IncomingRoute, Router, SharedIncomingRoute,
 };
 use waglayla_utils::channel::{JobSender, JobTrySendError as TrySendError};
@@ -63,6 +69,8 @@ pub struct HandleRelayInvsFlow {
     msg_route: IncomingRoute,
     /// A channel sender for sending blocks to be handled by the IBD flow (of this peer)
     ibd_sender: JobSender<Block>,
+    /// Indicates whether relay blocks are requested as compact blocks
+    compact_blocks: bool,
 }
 
 #[async_trait::async_trait]
@@ -84,7 +92,13 @@ impl HandleRelayInvsFlow {
         msg_route: IncomingRoute,
         ibd_sender: JobSender<Block>,
     ) -> Self {
-        Self { ctx, router, invs_route: TwoWayIncomingRoute::new(invs_route), msg_route, ibd_sender }
+        Self { ctx, router, invs_route: TwoWayIncomingRoute::new(invs_route), msg_route, ibd_sender, compact_blocks: false }
+    }
+
+    /// Requests relay blocks as compact blocks, filled in from the mempool. Requires a peer supporting compact block relay
+    pub fn with_compact_blocks(mut self) -> Self {
+        self.compact_blocks = true;
+        self
     }
 
     async fn start_impl(&mut self) -> Result<(), ProtocolError> {
@@ -97,7 +111,7 @@ impl HandleRelayInvsFlow {
                 None | Some(BlockStatus::StatusHeaderOnly) => {} // Continue processing this missing inv
                 Some(BlockStatus::StatusInvalid) => {
                     // Report a protocol error
-                    return Err(ProtocolError::OtherOwned(format!("sent inv of an invalid block {}", inv.hash)));
+                    return Err(ProtocolError::MisbehavingPeer(format!("sent inv of an invalid block {}", inv.hash)));
                 }
                 _ => {
                     // Block is already known, skip to next inv
@@ -228,6 +242,12 @@ impl HandleRelayInvsFlow {
         let Some(request_scope) = self.ctx.try_adding_block_request(requested_hash) else {
             return Ok(None);
         };
+        if self.compact_blocks {
+            if let Some(block) = self.request_compact_block(requested_hash, request_id).await? {
+                return Ok(Some((block, request_scope)));
+            }
+            debug!("Compact block {} could not be reconstructed, requesting the full block", requested_hash);
+        }
         self.router
             .enqueue(make_request!(
                 Payload::RequestRelayBlocks,
@@ -244,6 +264,56 @@ impl HandleRelayInvsFlow {
         }
     }
 
+    /// Requests a compact block and fills in its transactions from the mempool, requesting only the missing ones from
+    /// the peer. Returns `None` if the filled transactions do not match the block, e.g. due to a short id collision.
+    async fn request_compact_block(&mut self, requested_hash: Hash, request_id: u32) -> Result<Option<Block>, ProtocolError> {
+        self.router
+            .enqueue(make_request!(
+                Payload::RequestCompactBlocks,
+                RequestCompactBlocksMessage { hashes: vec![requested_hash.into()] },
+                request_id
+            ))
+            .await?;
+        let msg = dequeue_with_timeout!(self.msg_route, Payload::CompactBlock)?;
+        let compact_block: CompactBlock = msg.try_into()?;
+        if compact_block.hash() != requested_hash {
+            return Err(ProtocolError::OtherOwned(format!(
+                "requested compact block {} but got block {}",
+                requested_hash,
+                compact_block.hash()
+            )));
+        }
+
+        let mempool_transactions =
+            self.ctx.mining_manager().clone().get_transactions_by_short_ids(requested_hash, compact_block.short_ids.clone()).await;
+        let mut partial_block = PartialBlock::new(compact_block, mempool_transactions)?;
+        let missing_indexes = partial_block.missing_indexes();
+        if !missing_indexes.is_empty() {
+            self.router
+                .enqueue(make_request!(
+                    Payload::RequestBlockTransactions,
+                    RequestBlockTransactionsMessage {
+                        block_hash: Some(requested_hash.into()),
+                        indexes: missing_indexes.iter().map(|&index| index as u32).collect(),
+                    },
+                    request_id
+                ))
+                .await?;
+            let msg = dequeue_with_timeout!(self.msg_route, Payload::BlockTransactions)?;
+            let (block_hash, transactions): (Hash, Vec<Transaction>) = msg.try_into()?;
+            if block_hash != requested_hash {
+                return Err(ProtocolError::OtherOwned(format!(
+                    "requested transactions of block {} but got transactions of block {}",
+                    requested_hash, block_hash
+                )));
+            }
+            partial_block.fill_missing(transactions)?;
+        }
+
+        let storage_mass_activated = partial_block.header().daa_score > self.ctx.config.storage_mass_activation_daa_score;
+        Ok(partial_block.into_block(storage_mass_activated))
+    }
+
     /// Process the orphan block. Returns `Some(BlockProcessingBatch)` if the block has no missing roots, where
     /// the batch includes ancestor blocks and their consensus processing batch. This indicates a retry is recommended.
     async fn process_orphan(
@@ -11,7 +11,6 @@ use waglayla_consensus_core::{
     block::Block,
     header::Header,
     pruning::{PruningPointProof, PruningPointsList},
-    BlockHashSet,
 };
 use waglayla_consensusmanager::{spawn_blocking, ConsensusProxy, StagingConsensus};
 use waglayla_core::{debug, info, time::unix_now, warn};
@@ -28,13 +27,10 @@ use waglayla_p2p_lib::{
     IncomingRoute, Router,
 };
 use waglayla_utils::channel::JobReceiver;
-use std::{
-    sync::Arc,
-    time::{Duration, Instant},
-};
+use std::{sync::Arc, time::Duration};
 use tokio::time::sleep;
 
-use super::{progress::ProgressReporter, HeadersChunk, PruningPointUtxosetChunkStream, IBD_BATCH_SIZE};
+use super::{headers_proof, progress::ProgressReporter, HeadersChunk, PruningPointUtxosetChunkStream, IBD_BATCH_SIZE};
 
 /// Flow for managing IBD - Initial Block Download
 pub struct IbdFlow {
@@ -68,7 +64,6 @@ struct QueueChunkOutput {
     daa_score: u64,
     timestamp: u64,
 }
-// TODO: define a peer banning strategy
 
 impl IbdFlow {
     pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute, relay_receiver: JobReceiver<Block>) -> Self {
@@ -221,7 +216,7 @@ impl IbdFlow {
         let pruning_point = self.sync_and_validate_pruning_proof(&staging_session).await?;
         self.sync_headers(&staging_session, syncer_virtual_selected_parent, pruning_point, relay_block).await?;
         staging_session.async_validate_pruning_points().await?;
-        self.validate_staging_timestamps(&self.ctx.consensus().session().await, &staging_session).await?;
+        headers_proof::validate_staging_timestamps(&self.ctx.consensus().session().await, &staging_session).await?;
         self.sync_pruning_point_utxoset(&staging_session, pruning_point).await?;
         Ok(())
     }
@@ -234,23 +229,7 @@ impl IbdFlow {
         let proof: PruningPointProof = msg.try_into()?;
         debug!("received proof with overall {} headers", proof.iter().map(|l| l.len()).sum::<usize>());
 
-        // Get a new session for current consensus (non staging)
-        let consensus = self.ctx.consensus().session().await;
-
-        // The proof is validated in the context of current consensus
-        let proof = consensus.clone().spawn_blocking(move |c| c.validate_pruning_proof(&proof).map(|()| proof)).await?;
-
-        let proof_pruning_point = proof[0].last().expect("was just ensured by validation").hash;
-
-        if proof_pruning_point == self.ctx.config.genesis.hash {
-            return Err(ProtocolError::Other("the proof pruning point is the genesis block"));
-        }
-
-        if proof_pruning_point == consensus.async_pruning_point().await {
-            return Err(ProtocolError::Other("the proof pruning point is the same as the current pruning point"));
-        }
-
-        drop(consensus);
+        let (proof, proof_pruning_point) = headers_proof::validate_pruning_proof(&self.ctx, proof).await?;
 
         self.router
             .enqueue(make_message!(Payload::RequestPruningPointAndItsAnticone, RequestPruningPointAndItsAnticoneMessage {}))
@@ -259,101 +238,22 @@ impl IbdFlow {
         let msg = dequeue_with_timeout!(self.incoming_route, Payload::PruningPoints)?;
         let pruning_points: PruningPointsList = msg.try_into()?;
 
-        if pruning_points.is_empty() || pruning_points.last().unwrap().hash != proof_pruning_point {
-            return Err(ProtocolError::Other("the proof pruning point is not equal to the last pruning point in the list"));
-        }
-
-        if pruning_points.first().unwrap().hash != self.ctx.config.genesis.hash {
-            return Err(ProtocolError::Other("the first pruning point in the list is expected to be genesis"));
-        }
-
-        // Check if past pruning points violate finality of current consensus
-        if self.ctx.consensus().session().await.async_are_pruning_points_violating_finality(pruning_points.clone()).await {
-            // TODO: consider performing additional actions on finality conflicts in addition to disconnecting from the peer (e.g., banning, rpc notification)
-            return Err(ProtocolError::Other("pruning points are violating finality"));
-        }
+        headers_proof::validate_pruning_points(&self.ctx, proof_pruning_point, &pruning_points).await?;
 
         let msg = dequeue_with_timeout!(self.incoming_route, Payload::TrustedData)?;
         let pkg: TrustedDataPackage = msg.try_into()?;
         debug!("received trusted data with {} daa entries and {} ghostdag entries", pkg.daa_window.len(), pkg.ghostdag_window.len());
 
         let mut entry_stream = TrustedEntryStream::new(&self.router, &mut self.incoming_route);
-        let Some(pruning_point_entry) = entry_stream.next().await? else {
-            return Err(ProtocolError::Other("got `done` message before receiving the pruning point"));
-        };
-
-        if pruning_point_entry.block.hash() != proof_pruning_point {
-            return Err(ProtocolError::Other("the proof pruning point is not equal to the expected trusted entry"));
-        }
+        let pruning_point_entry = headers_proof::validate_pruning_point_entry(entry_stream.next().await?, proof_pruning_point)?;
 
         let mut entries = vec![pruning_point_entry];
         while let Some(entry) = entry_stream.next().await? {
             entries.push(entry);
         }
 
-        let mut trusted_set = pkg.build_trusted_subdag(entries)?;
-
-        if self.ctx.config.enable_sanity_checks {
-            let con = self.ctx.consensus().unguarded_session_blocking();
-            trusted_set = staging
-                .clone()
-                .spawn_blocking(move |c| {
-                    let ref_proof = proof.clone();
-                    c.apply_pruning_proof(proof, &trusted_set)?;
-                    c.import_pruning_points(pruning_points);
-
-                    info!("Building the proof which was just applied (sanity test)");
-                    let built_proof = c.get_pruning_point_proof();
-                    let mut mismatch_detected = false;
-                    for (i, (ref_level, built_level)) in ref_proof.iter().zip(built_proof.iter()).enumerate() {
-                        if ref_level.iter().map(|h| h.hash).collect::<BlockHashSet>()
-                            != built_level.iter().map(|h| h.hash).collect::<BlockHashSet>()
-                        {
-                            mismatch_detected = true;
-                            warn!("Locally built proof for level {} does not match the applied one", i);
-                        }
-                    }
-                    if mismatch_detected {
-                        info!("Validating the locally built proof (sanity test fallback #2)");
-                        // Note: the proof is validated in the context of *current* consensus
-                        if let Err(err) = con.validate_pruning_proof(&built_proof) {
-                            panic!("Locally built proof failed validation: {}", err);
-                        }
-                        info!("Locally built proof was validated successfully");
-                    } else {
-                        info!("Proof was locally built successfully");
-                    }
-                    Result::<_, ProtocolError>::Ok(trusted_set)
-                })
-                .await?;
-        } else {
-            trusted_set = staging
-                .clone()
-                .spawn_blocking(move |c| {
-                    c.apply_pruning_proof(proof, &trusted_set)?;
-                    c.import_pruning_points(pruning_points);
-                    Result::<_, ProtocolError>::Ok(trusted_set)
-                })
-                .await?;
-        }
-
-        // TODO: add logs to staging commit process
-
-        info!("Starting to process {} trusted blocks", trusted_set.len());
-        let mut last_time = Instant::now();
-        let mut last_index: usize = 0;
-        for (i, tb) in trusted_set.into_iter().enumerate() {
-            let now = Instant::now();
-            let passed = now.duration_since(last_time);
-            if passed > Duration::from_secs(1) {
-                info!("Processed {} trusted blocks in the last {:.2}s (total {})", i - last_index, passed.as_secs_f64(), i);
-                last_time = now;
-                last_index = i;
-            }
-            // TODO: queue and join in batches
-            staging.validate_and_insert_trusted_block(tb).virtual_state_task.await?;
-        }
-        info!("Done processing trusted blocks");
+        let trusted_set = pkg.build_trusted_subdag(entries)?;
+        headers_proof::apply_pruning_proof_and_trusted_set(&self.ctx, staging, proof, pruning_points, trusted_set).await?;
         Ok(proof_pruning_point)
     }
 
@@ -458,25 +358,6 @@ impl IbdFlow {
         }
     }
 
-    async fn validate_staging_timestamps(
-        &self,
-        consensus: &ConsensusProxy,
-        staging_consensus: &ConsensusProxy,
-    ) -> Result<(), ProtocolError> {
-        let staging_hst = staging_consensus.async_get_header(staging_consensus.async_get_headers_selected_tip().await).await.unwrap();
-        let current_hst = consensus.async_get_header(consensus.async_get_headers_selected_tip().await).await.unwrap();
-        // If staging is behind current or within 10 minutes ahead of it, then something is wrong and we reject the IBD
-        if staging_hst.timestamp < current_hst.timestamp || staging_hst.timestamp - current_hst.timestamp < 600_000 {
-            Err(ProtocolError::OtherOwned(format!(
-                "The difference between the timestamp of the current selected tip ({}) and the 
-staging selected tip ({}) is too small or negative. Aborting IBD...",
-                current_hst.timestamp, staging_hst.timestamp
-            )))
-        } else {
-            Ok(())
-        }
-    }
-
     async fn sync_pruning_point_utxoset(&mut self, consensus: &ConsensusProxy, pruning_point: Hash) -> Result<(), ProtocolError> {
         self.router
             .enqueue(make_message!(
@@ -487,15 +368,9 @@ staging selected tip ({}) is too small or negative. Aborting IBD...",
         let mut chunk_stream = PruningPointUtxosetChunkStream::new(&self.router, &mut self.incoming_route);
         let mut multiset = MuHash::new();
         while let Some(chunk) = chunk_stream.next().await? {
-            multiset = consensus
-                .clone()
-                .spawn_blocking(move |c| {
-                    c.append_imported_pruning_point_utxos(&chunk, &mut multiset);
-                    multiset
-                })
-                .await;
+            multiset = headers_proof::append_pruning_point_utxoset_chunk(consensus, chunk, multiset).await;
         }
-        consensus.clone().spawn_blocking(move |c| c.import_pruning_point_utxo_set(pruning_point, multiset)).await?;
+        headers_proof::import_pruning_point_utxoset(consensus, pruning_point, multiset).await?;
         Ok(())
     }
 
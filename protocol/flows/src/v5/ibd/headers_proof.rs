@@ -0,0 +1,207 @@
+//!
+//! Validation and staging logic of IBD with headers proof. The logic is shared by the p2p IBD flow
+//! and by offline snapshot import, so that data from both sources is validated in exactly the same way.
+//!
+
+use crate::flow_context::FlowContext;
+use waglayla_consensus_core::{
+    pruning::{PruningPointProof, PruningPointsList},
+    trusted::TrustedBlock,
+    BlockHashSet,
+};
+use waglayla_consensusmanager::ConsensusProxy;
+use waglayla_core::{info, warn};
+use waglayla_hashes::Hash;
+use waglayla_muhash::MuHash;
+use waglayla_p2p_lib::{common::ProtocolError, convert::model::trusted::TrustedDataEntry};
+use std::time::{Duration, Instant};
+
+use super::UtxosetChunk;
+
+/// Validates the pruning point proof in the context of the current (non staging) consensus.
+/// Returns the proof along with the pruning point it proves.
+pub(crate) async fn validate_pruning_proof(
+    ctx: &FlowContext,
+    proof: PruningPointProof,
+) -> Result<(PruningPointProof, Hash), ProtocolError> {
+    // Get a new session for current consensus (non staging)
+    let consensus = ctx.consensus().session().await;
+
+    // The proof is validated in the context of current consensus
+    let proof = consensus.clone().spawn_blocking(move |c| c.validate_pruning_proof(&proof).map(|()| proof)).await?;
+
+    let proof_pruning_point = proof[0].last().expect("was just ensured by validation").hash;
+
+    if proof_pruning_point == ctx.config.genesis.hash {
+        return Err(ProtocolError::Other("the proof pruning point is the genesis block"));
+    }
+
+    if proof_pruning_point == consensus.async_pruning_point().await {
+        return Err(ProtocolError::Other("the proof pruning point is the same as the current pruning point"));
+    }
+
+    Ok((proof, proof_pruning_point))
+}
+
+/// Validates the list of past pruning points accompanying a pruning point proof
+pub(crate) async fn validate_pruning_points(
+    ctx: &FlowContext,
+    proof_pruning_point: Hash,
+    pruning_points: &PruningPointsList,
+) -> Result<(), ProtocolError> {
+    if pruning_points.is_empty() || pruning_points.last().unwrap().hash != proof_pruning_point {
+        return Err(ProtocolError::Other("the proof pruning point is not equal to the last pruning point in the list"));
+    }
+
+    if pruning_points.first().unwrap().hash != ctx.config.genesis.hash {
+        return Err(ProtocolError::Other("the first pruning point in the list is expected to be genesis"));
+    }
+
+    // Check if past pruning points violate finality of current consensus
+    if ctx.consensus().session().await.async_are_pruning_points_violating_finality(pruning_points.clone()).await {
+        // TODO: consider performing additional actions on finality conflicts in addition to disconnecting from the peer (e.g., banning, rpc notification)
+        return Err(ProtocolError::Other("pruning points are violating finality"));
+    }
+
+    Ok(())
+}
+
+/// Verifies that a trusted entry carries a full block
+pub(crate) fn validate_trusted_entry(entry: TrustedDataEntry) -> Result<TrustedDataEntry, ProtocolError> {
+    if entry.block.is_header_only() {
+        Err(ProtocolError::OtherOwned(format!("trusted entry block {} is header only", entry.block.hash())))
+    } else {
+        Ok(entry)
+    }
+}
+
+/// Verifies that the first trusted entry is the proof pruning point
+pub(crate) fn validate_pruning_point_entry(
+    pruning_point_entry: Option<TrustedDataEntry>,
+    proof_pruning_point: Hash,
+) -> Result<TrustedDataEntry, ProtocolError> {
+    let Some(pruning_point_entry) = pruning_point_entry else {
+        return Err(ProtocolError::Other("got `done` message before receiving the pruning point"));
+    };
+
+    if pruning_point_entry.block.hash() != proof_pruning_point {
+        return Err(ProtocolError::Other("the proof pruning point is not equal to the expected trusted entry"));
+    }
+
+    Ok(pruning_point_entry)
+}
+
+/// Applies the validated proof and pruning points to the staging consensus and processes the trusted set
+pub(crate) async fn apply_pruning_proof_and_trusted_set(
+    ctx: &FlowContext,
+    staging: &ConsensusProxy,
+    proof: PruningPointProof,
+    pruning_points: PruningPointsList,
+    mut trusted_set: Vec<TrustedBlock>,
+) -> Result<(), ProtocolError> {
+    if ctx.config.enable_sanity_checks {
+        let con = ctx.consensus().unguarded_session_blocking();
+        trusted_set = staging
+            .clone()
+            .spawn_blocking(move |c| {
+                let ref_proof = proof.clone();
+                c.apply_pruning_proof(proof, &trusted_set)?;
+                c.import_pruning_points(pruning_points);
+
+                info!("Building the proof which was just applied (sanity test)");
+                let built_proof = c.get_pruning_point_proof();
+                let mut mismatch_detected = false;
+                for (i, (ref_level, built_level)) in ref_proof.iter().zip(built_proof.iter()).enumerate() {
+                    if ref_level.iter().map(|h| h.hash).collect::<BlockHashSet>()
+                        != built_level.iter().map(|h| h.hash).collect::<BlockHashSet>()
+                    {
+                        mismatch_detected = true;
+                        warn!("Locally built proof for level {} does not match the applied one", i);
+                    }
+                }
+                if mismatch_detected {
+                    info!("Validating the locally built proof (sanity test fallback #2)");
+                    // Note: the proof is validated in the context of *current* consensus
+                    if let Err(err) = con.validate_pruning_proof(&built_proof) {
+                        panic!("Locally built proof failed validation: {}", err);
+                    }
+                    info!("Locally built proof was validated successfully");
+                } else {
+                    info!("Proof was locally built successfully");
+                }
+                Result::<_, ProtocolError>::Ok(trusted_set)
+            })
+            .await?;
+    } else {
+        trusted_set = staging
+            .clone()
+            .spawn_blocking(move |c| {
+                c.apply_pruning_proof(proof, &trusted_set)?;
+                c.import_pruning_points(pruning_points);
+                Result::<_, ProtocolError>::Ok(trusted_set)
+            })
+            .await?;
+    }
+
+    // TODO: add logs to staging commit process
+
+    info!("Starting to process {} trusted blocks", trusted_set.len());
+    let mut last_time = Instant::now();
+    let mut last_index: usize = 0;
+    for (i, tb) in trusted_set.into_iter().enumerate() {
+        let now = Instant::now();
+        let passed = now.duration_since(last_time);
+        if passed > Duration::from_secs(1) {
+            info!("Processed {} trusted blocks in the last {:.2}s (total {})", i - last_index, passed.as_secs_f64(), i);
+            last_time = now;
+            last_index = i;
+        }
+        // TODO: queue and join in batches
+        staging.validate_and_insert_trusted_block(tb).virtual_state_task.await?;
+    }
+    info!("Done processing trusted blocks");
+    Ok(())
+}
+
+pub(crate) async fn validate_staging_timestamps(
+    consensus: &ConsensusProxy,
+    staging_consensus: &ConsensusProxy,
+) -> Result<(), ProtocolError> {
+    let staging_hst = staging_consensus.async_get_header(staging_consensus.async_get_headers_selected_tip().await).await.unwrap();
+    let current_hst = consensus.async_get_header(consensus.async_get_headers_selected_tip().await).await.unwrap();
+    // If staging is behind current or within 10 minutes ahead of it, then something is wrong and we reject the IBD
+    if staging_hst.timestamp < current_hst.timestamp || staging_hst.timestamp - current_hst.timestamp < 600_000 {
+        Err(ProtocolError::OtherOwned(format!(
+            "The difference between the timestamp of the current selected tip ({}) and the 
+staging selected tip ({}) is too small or negative. Aborting IBD...",
+            current_hst.timestamp, staging_hst.timestamp
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Appends a chunk of pruning point UTXOs to the staging consensus, returning the updated multiset
+pub(crate) async fn append_pruning_point_utxoset_chunk(
+    consensus: &ConsensusProxy,
+    chunk: UtxosetChunk,
+    mut multiset: MuHash,
+) -> MuHash {
+    consensus
+        .clone()
+        .spawn_blocking(move |c| {
+            c.append_imported_pruning_point_utxos(&chunk, &mut multiset);
+            multiset
+        })
+        .await
+}
+
+/// Completes the pruning point UTXO set import. Fails if `multiset` does not match the pruning point UTXO commitment
+pub(crate) async fn import_pruning_point_utxoset(
+    consensus: &ConsensusProxy,
+    pruning_point: Hash,
+    multiset: MuHash,
+) -> Result<(), ProtocolError> {
+    consensus.clone().spawn_blocking(move |c| c.import_pruning_point_utxo_set(pruning_point, multiset)).await?;
+    Ok(())
+}
@@ -1,4 +1,5 @@
 mod flow;
+pub(crate) mod headers_proof;
 mod negotiate;
 mod progress;
 mod streams;
@@ -21,6 +21,8 @@ use waglayla_p2p_lib::{
 use std::sync::Arc;
 use tokio::time::timeout;
 
+use super::headers_proof::validate_trusted_entry;
+
 pub const IBD_BATCH_SIZE: usize = 99;
 
 pub struct TrustedEntryStream<'a, 'b> {
@@ -41,11 +43,7 @@ impl<'a, 'b> TrustedEntryStream<'a, 'b> {
                     match msg.payload {
                         Some(Payload::BlockWithTrustedDataV4(payload)) => {
                             let entry: TrustedDataEntry = payload.try_into()?;
-                            if entry.block.is_header_only() {
-                                Err(ProtocolError::OtherOwned(format!("trusted entry block {} is header only", entry.block.hash())))
-                            } else {
-                                Ok(Some(entry))
-                            }
+                            validate_trusted_entry(entry).map(Some)
                         }
                         Some(Payload::DoneBlocksWithTrustedData(_)) => {
                             debug!("trusted entry stream completed after {} items", self.i);
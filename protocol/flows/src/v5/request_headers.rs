@@ -80,7 +80,7 @@ impl RequestHeadersFlow {
 
     /// Helper function to get a bunch of headers between `low` and `high` and to parse them into pb structs.
     /// Returns the hash of the highest block obtained, to be used as `low` for the next call
-    fn get_headers_between(
+    pub(crate) fn get_headers_between(
         consensus: &dyn ConsensusApi,
         low: Hash,
         high: Hash,
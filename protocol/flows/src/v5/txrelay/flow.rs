@@ -16,7 +16,7 @@ use waglayla_mining::{
     P2pTxCountSample,
 };
 use waglayla_p2p_lib::{
-    common::{ProtocolError, DEFAULT_TIMEOUT},
+    common::{ProtocolError, DEFAULT_TIMEOUT, UNKNOWN_TRANSACTION_MISBEHAVIOUR_SCORE},
     dequeue, make_message,
     pb::{waglaylad_message::Payload, RequestTransactionsMessage, TransactionNotFoundMessage},
     IncomingRoute, Router,
@@ -113,6 +113,11 @@ impl RelayTransactionsFlow {
                 return Err(ProtocolError::Other("Number of invs in tx inv message is over the limit"));
             }
 
+            // Announced transactions were diffused by the network, so there is no need to diffuse them ourselves
+            if let Some(dandelion) = self.ctx.dandelion() {
+                dandelion.lift_embargoes(inv.iter().copied());
+            }
+
             let session = self.ctx.consensus().unguarded_session();
 
             // Transaction relay is disabled if the node is out of sync and thus not mining
@@ -211,8 +216,18 @@ impl RelayTransactionsFlow {
                     request.req, transaction_id
                 )));
             }
-            if let Response::Transaction(transaction) = response {
-                transactions.push(transaction);
+            match response {
+                Response::Transaction(transaction) => transactions.push(transaction),
+                Response::NotFound(transaction_id) => {
+                    // The peer announced a transaction it cannot provide
+                    self.ctx
+                        .add_misbehaviour(
+                            &self.router,
+                            UNKNOWN_TRANSACTION_MISBEHAVIOUR_SCORE,
+                            format!("unknown transaction {} was announced", transaction_id),
+                        )
+                        .await
+                }
             }
         }
         let insert_results = self
@@ -226,7 +241,6 @@ impl RelayTransactionsFlow {
             match res {
                 Ok(_) => {}
                 Err(MiningManagerError::MempoolError(RuleError::RejectInvalid(transaction_id))) => {
-                    // TODO: discuss a banning process
                     return Err(ProtocolError::MisbehavingPeer(format!("rejected invalid transaction {}", transaction_id)));
                 }
                 Err(MiningManagerError::MempoolError(RuleError::RejectSpamTransaction(_)))
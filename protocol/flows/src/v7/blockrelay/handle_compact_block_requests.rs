@@ -0,0 +1,47 @@
+use crate::{flow_context::FlowContext, flow_trait::Flow};
+use waglayla_core::debug;
+use waglayla_p2p_lib::{
+    common::ProtocolError, convert::model::compact::CompactBlock, dequeue_with_request_id, make_response,
+    pb::waglaylad_message::Payload, IncomingRoute, Router,
+};
+use std::sync::Arc;
+
+/// Serves relay blocks in compact form, where transactions expected to be held by the requester are replaced by short ids
+pub struct HandleCompactBlockRequests {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for HandleCompactBlockRequests {
+    fn router(&self) -> Option<Arc<Router>> {
+        Some(self.router.clone())
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        self.start_impl().await
+    }
+}
+
+impl HandleCompactBlockRequests {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+
+    async fn start_impl(&mut self) -> Result<(), ProtocolError> {
+        loop {
+            let (msg, request_id) = dequeue_with_request_id!(self.incoming_route, Payload::RequestCompactBlocks)?;
+            let hashes: Vec<_> = msg.try_into()?;
+
+            let session = self.ctx.consensus().unguarded_session();
+
+            for hash in hashes {
+                let block = session.async_get_block(hash).await?;
+                let compact_block = CompactBlock::from_block(&block);
+                self.router.enqueue(make_response!(Payload::CompactBlock, (&compact_block).into(), request_id)).await?;
+                debug!("relayed compact block with hash {} to peer {}", hash, self.router);
+            }
+        }
+    }
+}
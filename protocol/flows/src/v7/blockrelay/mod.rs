@@ -0,0 +1,2 @@
+pub mod handle_block_transactions_requests;
+pub mod handle_compact_block_requests;
@@ -0,0 +1,74 @@
+use crate::{flow_context::FlowContext, flow_trait::Flow};
+use waglayla_consensus_core::tx::Transaction;
+use waglayla_mining::{
+    errors::MiningManagerError,
+    mempool::{
+        errors::RuleError,
+        tx::{Orphan, Priority},
+    },
+};
+use waglayla_p2p_lib::{common::ProtocolError, dequeue, pb::waglaylad_message::Payload, IncomingRoute, Router};
+use std::sync::Arc;
+
+/// Flow listening to StemTransactions messages. The transactions are added to the mempool and then either forwarded
+/// further along the Dandelion stem or diffused to all peers.
+pub struct ReceiveStemTransactionsFlow {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for ReceiveStemTransactionsFlow {
+    fn router(&self) -> Option<Arc<Router>> {
+        Some(self.router.clone())
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        self.start_impl().await
+    }
+}
+
+impl ReceiveStemTransactionsFlow {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+
+    async fn start_impl(&mut self) -> Result<(), ProtocolError> {
+        loop {
+            let transactions: Vec<Transaction> = dequeue!(self.incoming_route, Payload::StemTransactions)?.try_into()?;
+
+            let session = self.ctx.consensus().unguarded_session();
+
+            // Transaction relay is disabled if the node is out of sync and thus not mining
+            if !session.async_is_nearly_synced().await {
+                continue;
+            }
+
+            // Stem transactions are sent only once their inputs are known to the sender, hence orphans are not accepted
+            let insert_results = self
+                .ctx
+                .mining_manager()
+                .clone()
+                .validate_and_insert_transaction_batch(&session, transactions, Priority::Low, Orphan::Forbidden)
+                .await;
+
+            let mut accepted = Vec::with_capacity(insert_results.len());
+            for res in insert_results {
+                match res {
+                    Ok(tx) => accepted.push(tx),
+                    Err(MiningManagerError::MempoolError(RuleError::RejectInvalid(transaction_id))) => {
+                        return Err(ProtocolError::MisbehavingPeer(format!("rejected invalid stem transaction {}", transaction_id)));
+                    }
+                    Err(_) => {}
+                }
+            }
+
+            if self.ctx.dandelion().is_some_and(|dandelion| !dandelion.should_fluff()) {
+                self.ctx.relay_stem_transactions(&accepted, Some(self.router.key())).await;
+            } else {
+                self.ctx.broadcast_transactions(accepted.iter().map(|tx| tx.id()), false).await;
+            }
+        }
+    }
+}
@@ -8,7 +8,8 @@ async fn main() {
     waglayla_core::log::init_logger(None, "debug");
     // [0] - init p2p-adaptor
     let initializer = Arc::new(EchoFlowInitializer::new());
-    let adaptor = waglayla_p2p_lib::Adaptor::client_only(waglayla_p2p_lib::Hub::new(), initializer, Default::default());
+    let adaptor =
+        waglayla_p2p_lib::Adaptor::client_only(waglayla_p2p_lib::Hub::new(), initializer, Default::default(), Default::default());
     // [1] - connect 128 peers + flows
     let ip_port = String::from("[::1]:50051");
     for i in 0..1 {
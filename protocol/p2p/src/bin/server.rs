@@ -10,7 +10,14 @@ async fn main() {
     // [0] - init p2p-adaptor - server side
     let ip_port = NetAddress::from_str("[::1]:50051").unwrap();
     let initializer = Arc::new(EchoFlowInitializer::new());
-    let adaptor = waglayla_p2p_lib::Adaptor::bidirectional(ip_port, waglayla_p2p_lib::Hub::new(), initializer, Default::default()).unwrap();
+    let adaptor = waglayla_p2p_lib::Adaptor::bidirectional(
+        ip_port,
+        waglayla_p2p_lib::Hub::new(),
+        initializer,
+        Default::default(),
+        Default::default(),
+    )
+    .unwrap();
     // [1] - connect to a few peers
     let ip_port = String::from("[::1]:12111");
     for i in 0..1 {
@@ -7,6 +7,18 @@ use thiserror::Error;
 /// Default P2P communication timeout
 pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120); // 2 minutes
 
+/// Misbehaviour score of a peer sending invalid blocks or an invalid pruning proof
+pub const INVALID_DATA_MISBEHAVIOUR_SCORE: u32 = 50;
+
+/// Misbehaviour score of a peer violating the P2P protocol (malformed or unexpected messages, invalid transactions)
+pub const PROTOCOL_VIOLATION_MISBEHAVIOUR_SCORE: u32 = 20;
+
+/// Misbehaviour score of a peer failing to respond in time, e.g. stalling IBD
+pub const STALLING_MISBEHAVIOUR_SCORE: u32 = 10;
+
+/// Misbehaviour score of a peer announcing a transaction it cannot provide when requested
+pub const UNKNOWN_TRANSACTION_MISBEHAVIOUR_SCORE: u32 = 1;
+
 #[derive(Error, Debug, Clone)]
 pub enum ProtocolError {
     #[error("timeout expired after {0:?}")]
@@ -89,6 +101,19 @@ impl ProtocolError {
         !matches!(self, Self::ConnectionClosed | Self::OutgoingRouteCapacityReached(_))
     }
 
+    /// The misbehaviour score a peer is charged with for causing this error. Errors which
+    /// do not necessarily indicate a misbehaving peer have a zero score
+    pub fn misbehaviour_score(&self) -> u32 {
+        match self {
+            Self::RuleError(_) | Self::PruningImportError(_) => INVALID_DATA_MISBEHAVIOUR_SCORE,
+            Self::MisbehavingPeer(_) | Self::ConversionError(_) | Self::UnexpectedMessage(..) | Self::NoRouteForMessageType(_) => {
+                PROTOCOL_VIOLATION_MISBEHAVIOUR_SCORE
+            }
+            Self::Timeout(_) => STALLING_MISBEHAVIOUR_SCORE,
+            _ => 0,
+        }
+    }
+
     pub fn to_reject_message(&self) -> String {
         match self {
             Self::LoopbackConnection(_) => LOOPBACK_CONNECTION_MESSAGE.to_owned(),
@@ -1,6 +1,7 @@
-use super::{error::ConversionError, option::TryIntoOptionEx};
+use super::{error::ConversionError, model::compact::CompactBlock, option::TryIntoOptionEx};
 use crate::pb as protowire;
-use waglayla_consensus_core::{block::Block, tx::Transaction};
+use waglayla_consensus_core::{block::Block, header::Header, tx::Transaction};
+use std::sync::Arc;
 
 // ----------------------------------------------------------------------------
 // consensus_core to protowire
@@ -12,6 +13,20 @@ impl From<&Block> for protowire::BlockMessage {
     }
 }
 
+impl From<&CompactBlock> for protowire::CompactBlockMessage {
+    fn from(block: &CompactBlock) -> Self {
+        Self {
+            header: Some(block.header.as_ref().into()),
+            short_ids: block.short_ids.clone(),
+            prefilled_transactions: block
+                .prefilled_transactions
+                .iter()
+                .map(|(index, tx)| protowire::PrefilledTransaction { index: *index as u32, transaction: Some(tx.into()) })
+                .collect(),
+        }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // protowire to consensus_core
 // ----------------------------------------------------------------------------
@@ -26,3 +41,20 @@ impl TryFrom<protowire::BlockMessage> for Block {
         ))
     }
 }
+
+impl TryFrom<protowire::CompactBlockMessage> for CompactBlock {
+    type Error = ConversionError;
+
+    fn try_from(block: protowire::CompactBlockMessage) -> Result<Self, Self::Error> {
+        let header: Header = block.header.try_into_ex()?;
+        Ok(Self::new(
+            Arc::new(header),
+            block.short_ids,
+            block
+                .prefilled_transactions
+                .into_iter()
+                .map(|p| Ok((p.index as usize, p.transaction.try_into_ex()?)))
+                .collect::<Result<Vec<(usize, Transaction)>, Self::Error>>()?,
+        ))
+    }
+}
@@ -10,10 +10,10 @@ use crate::pb as protowire;
 use waglayla_consensus_core::{
     header::Header,
     pruning::{PruningPointProof, PruningPointsList},
-    tx::{TransactionId, TransactionOutpoint, UtxoEntry},
+    tx::{Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
 };
 use waglayla_hashes::Hash;
-use waglayla_utils::networking::{IpAddress, PeerId};
+use waglayla_utils::networking::{NetAddress, PeerId};
 
 use std::sync::Arc;
 
@@ -33,6 +33,9 @@ impl From<Version> for protowire::VersionMessage {
             disable_relay_tx: item.disable_relay_tx,
             subnetwork_id: item.subnetwork_id.map(|x| x.into()),
             network: item.network.clone(),
+            // Set by the handshake
+            encryption_key: vec![],
+            identity_key: vec![],
         }
     }
 }
@@ -159,6 +162,41 @@ impl TryFrom<protowire::RequestRelayBlocksMessage> for Vec<Hash> {
     }
 }
 
+impl TryFrom<protowire::RequestCompactBlocksMessage> for Vec<Hash> {
+    type Error = ConversionError;
+
+    fn try_from(msg: protowire::RequestCompactBlocksMessage) -> Result<Self, Self::Error> {
+        msg.hashes.into_iter().map(|v| v.try_into()).collect()
+    }
+}
+
+impl TryFrom<protowire::RequestBlockTransactionsMessage> for (Hash, Vec<usize>) {
+    type Error = ConversionError;
+
+    fn try_from(msg: protowire::RequestBlockTransactionsMessage) -> Result<Self, Self::Error> {
+        Ok((msg.block_hash.try_into_ex()?, msg.indexes.into_iter().map(|index| index as usize).collect()))
+    }
+}
+
+impl TryFrom<protowire::BlockTransactionsMessage> for (Hash, Vec<Transaction>) {
+    type Error = ConversionError;
+
+    fn try_from(msg: protowire::BlockTransactionsMessage) -> Result<Self, Self::Error> {
+        Ok((
+            msg.block_hash.try_into_ex()?,
+            msg.transactions.into_iter().map(|v| v.try_into()).collect::<Result<Vec<Transaction>, Self::Error>>()?,
+        ))
+    }
+}
+
+impl TryFrom<protowire::StemTransactionsMessage> for Vec<Transaction> {
+    type Error = ConversionError;
+
+    fn try_from(msg: protowire::StemTransactionsMessage) -> Result<Self, Self::Error> {
+        msg.transactions.into_iter().map(|v| v.try_into()).collect()
+    }
+}
+
 impl TryFrom<protowire::RequestIbdBlocksMessage> for Vec<Hash> {
     type Error = ConversionError;
 
@@ -175,7 +213,7 @@ impl TryFrom<protowire::BlockLocatorMessage> for Vec<Hash> {
     }
 }
 
-impl TryFrom<protowire::AddressesMessage> for Vec<(IpAddress, u16)> {
+impl TryFrom<protowire::AddressesMessage> for Vec<NetAddress> {
     type Error = ConversionError;
 
     fn try_from(msg: protowire::AddressesMessage) -> Result<Self, Self::Error> {
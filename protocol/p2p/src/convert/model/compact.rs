@@ -0,0 +1,179 @@
+//!
+//! Model structures of compact block relay. A relay block is sent with its transactions replaced by short ids,
+//! the receiver fills in the transactions it holds in its mempool and requests only the missing ones from the peer.
+//!
+
+use waglayla_consensus_core::{
+    block::Block, hashing::tx::short_id, header::Header, merkle::calc_hash_merkle_root_with_options, tx::Transaction,
+};
+use waglayla_hashes::Hash;
+use std::{collections::HashMap, sync::Arc};
+
+use crate::common::ProtocolError;
+
+/// A block where transactions are identified by short ids, except for the prefilled ones
+pub struct CompactBlock {
+    pub header: Arc<Header>,
+
+    /// Short ids of the transactions which are not prefilled, by block order
+    pub short_ids: Vec<u64>,
+
+    /// Transactions sent in full along with their index within the block, by block order
+    pub prefilled_transactions: Vec<(usize, Transaction)>,
+}
+
+impl CompactBlock {
+    pub fn new(header: Arc<Header>, short_ids: Vec<u64>, prefilled_transactions: Vec<(usize, Transaction)>) -> Self {
+        Self { header, short_ids, prefilled_transactions }
+    }
+
+    /// Builds the compact form of `block`. The coinbase transaction, which is never held by the mempool, is prefilled
+    /// along with any transactions whose short ids collide within the block
+    pub fn from_block(block: &Block) -> Self {
+        let hash = block.hash();
+        let short_ids: Vec<u64> = block.transactions.iter().map(|tx| short_id(&tx.id(), &hash)).collect();
+        let mut counts: HashMap<u64, usize> = HashMap::with_capacity(short_ids.len());
+        short_ids.iter().for_each(|short_id| *counts.entry(*short_id).or_default() += 1);
+
+        let mut compact_short_ids = Vec::with_capacity(short_ids.len());
+        let mut prefilled_transactions = Vec::new();
+        for (index, (tx, short_id)) in block.transactions.iter().zip(short_ids).enumerate() {
+            if tx.is_coinbase() || counts[&short_id] > 1 {
+                prefilled_transactions.push((index, tx.clone()));
+            } else {
+                compact_short_ids.push(short_id);
+            }
+        }
+        Self::new(block.header.clone(), compact_short_ids, prefilled_transactions)
+    }
+
+    pub fn hash(&self) -> Hash {
+        self.header.hash
+    }
+}
+
+/// A compact block whose transactions are being filled in
+pub struct PartialBlock {
+    header: Arc<Header>,
+    transactions: Vec<Option<Transaction>>,
+}
+
+impl PartialBlock {
+    /// Fills the compact block with `mempool_transactions`, which hold the mempool transactions matching the
+    /// short ids of the compact block by position
+    pub fn new(compact_block: CompactBlock, mempool_transactions: Vec<Option<Arc<Transaction>>>) -> Result<Self, ProtocolError> {
+        let CompactBlock { header, short_ids, prefilled_transactions } = compact_block;
+        assert_eq!(short_ids.len(), mempool_transactions.len(), "a mempool lookup is expected for every short id");
+
+        let transactions_count = short_ids.len() + prefilled_transactions.len();
+        let mut transactions = Vec::with_capacity(transactions_count);
+        let mut prefilled_transactions = prefilled_transactions.into_iter().peekable();
+        let mut mempool_transactions = mempool_transactions.into_iter();
+        for index in 0..transactions_count {
+            if let Some((_, tx)) = prefilled_transactions.next_if(|(prefilled_index, _)| *prefilled_index == index) {
+                transactions.push(Some(tx));
+            } else if let Some(tx) = mempool_transactions.next() {
+                transactions.push(tx.map(|tx| (*tx).clone()));
+            } else {
+                break;
+            }
+        }
+        if transactions.len() != transactions_count {
+            return Err(ProtocolError::OtherOwned(format!(
+                "compact block {} has prefilled transaction indexes which are unordered or out of range",
+                header.hash
+            )));
+        }
+        Ok(Self { header, transactions })
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns the indexes of the transactions which were not found in the mempool
+    pub fn missing_indexes(&self) -> Vec<usize> {
+        self.transactions.iter().enumerate().filter_map(|(index, tx)| tx.is_none().then_some(index)).collect()
+    }
+
+    /// Fills the missing transactions with `transactions`, ordered by their indexes
+    pub fn fill_missing(&mut self, transactions: Vec<Transaction>) -> Result<(), ProtocolError> {
+        let missing_indexes = self.missing_indexes();
+        if missing_indexes.len() != transactions.len() {
+            return Err(ProtocolError::OtherOwned(format!(
+                "requested {} transactions of block {} but got {}",
+                missing_indexes.len(),
+                self.header.hash,
+                transactions.len()
+            )));
+        }
+        for (index, tx) in missing_indexes.into_iter().zip(transactions) {
+            self.transactions[index] = Some(tx);
+        }
+        Ok(())
+    }
+
+    /// Returns the full block if all transactions were filled and they match the merkle root committed to by the header.
+    /// Returns `None` otherwise, for instance when a mempool transaction collides with the short id of a block transaction.
+    pub fn into_block(self, storage_mass_activated: bool) -> Option<Block> {
+        let transactions: Vec<Transaction> = self.transactions.into_iter().collect::<Option<_>>()?;
+        if calc_hash_merkle_root_with_options(transactions.iter(), storage_mass_activated) != self.header.hash_merkle_root {
+            return None;
+        }
+        Some(Block::from_arcs(self.header, Arc::new(transactions)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use waglayla_consensus_core::{
+        merkle::calc_hash_merkle_root,
+        subnets::{SUBNETWORK_ID_COINBASE, SUBNETWORK_ID_NATIVE},
+        tx::{TransactionId, TransactionInput, TransactionOutpoint},
+    };
+
+    fn create_transaction(i: u64, subnetwork_id: waglayla_consensus_core::subnets::SubnetworkId) -> Transaction {
+        let outpoint = TransactionOutpoint::new(TransactionId::from_u64_word(i), 0);
+        Transaction::new(0, vec![TransactionInput::new(outpoint, vec![], 0, 1)], vec![], 0, subnetwork_id, 0, vec![])
+    }
+
+    fn create_block(transactions: Vec<Transaction>) -> Block {
+        let mut header = Header::from_precomputed_hash(Hash::from_u64_word(1), vec![]);
+        header.hash_merkle_root = calc_hash_merkle_root(transactions.iter());
+        Block::new(header, transactions)
+    }
+
+    #[test]
+    fn test_compact_block_reconstruction() {
+        let transactions = std::iter::once(create_transaction(0, SUBNETWORK_ID_COINBASE))
+            .chain((1..6).map(|i| create_transaction(i, SUBNETWORK_ID_NATIVE)))
+            .collect::<Vec<_>>();
+        let block = create_block(transactions.clone());
+        let compact_block = CompactBlock::from_block(&block);
+        assert_eq!(compact_block.prefilled_transactions.iter().map(|(index, _)| *index).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(compact_block.short_ids.len(), 5);
+
+        // The mempool holds all transactions but the ones at block indexes 2 and 4
+        let mempool_transactions =
+            (1..6).map(|i| (i % 2 == 1).then(|| Arc::new(transactions[i].clone()))).collect::<Vec<Option<Arc<Transaction>>>>();
+        let mut partial_block = PartialBlock::new(compact_block, mempool_transactions).unwrap();
+        assert_eq!(partial_block.missing_indexes(), vec![2, 4]);
+        assert!(partial_block.fill_missing(vec![transactions[2].clone()]).is_err());
+        partial_block.fill_missing(vec![transactions[2].clone(), transactions[4].clone()]).unwrap();
+        assert!(partial_block.missing_indexes().is_empty());
+        let reconstructed = partial_block.into_block(false).unwrap();
+        assert_eq!(reconstructed.hash(), block.hash());
+        assert_eq!(*reconstructed.transactions, *block.transactions);
+
+        // A mempool transaction colliding with the short id of a block transaction fails the merkle root check
+        let compact_block = CompactBlock::from_block(&block);
+        let mut mempool_transactions = (1..6).map(|i| Some(Arc::new(transactions[i].clone()))).collect::<Vec<_>>();
+        mempool_transactions[0] = Some(Arc::new(create_transaction(100, SUBNETWORK_ID_NATIVE)));
+        assert!(PartialBlock::new(compact_block, mempool_transactions).unwrap().into_block(false).is_none());
+
+        // Prefilled indexes must be ordered and within range
+        let compact_block = CompactBlock::new(block.header.clone(), vec![], vec![(1, transactions[1].clone())]);
+        assert!(PartialBlock::new(compact_block, vec![]).is_err());
+    }
+}
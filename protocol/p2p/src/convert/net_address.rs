@@ -7,7 +7,7 @@ use super::error::ConversionError;
 use crate::pb as protowire;
 
 use itertools::Itertools;
-use waglayla_utils::networking::{IpAddress, NetAddress};
+use waglayla_utils::networking::{IpAddress, NetAddress, OnionAddress};
 
 // ----------------------------------------------------------------------------
 // consensus_core to protowire
@@ -23,13 +23,18 @@ impl From<(IpAddress, u16)> for protowire::NetAddress {
                 IpAddr::V6(ip) => ip.octets().to_vec(),
             },
             port: port as u32,
+            onion: vec![],
         }
     }
 }
 
 impl From<NetAddress> for protowire::NetAddress {
     fn from(item: NetAddress) -> Self {
-        (item.ip, item.port).into()
+        let mut addr: Self = (item.ip, item.port).into();
+        if let Some(onion) = item.onion {
+            addr.onion = onion.public_key().to_vec();
+        }
+        addr
     }
 }
 
@@ -63,14 +68,19 @@ impl TryFrom<protowire::NetAddress> for NetAddress {
     type Error = ConversionError;
 
     fn try_from(item: protowire::NetAddress) -> Result<Self, Self::Error> {
-        let (ip, port) = item.try_into()?;
-        Ok(NetAddress::new(ip, port))
+        if item.onion.is_empty() {
+            let (ip, port) = item.try_into()?;
+            return Ok(NetAddress::new(ip, port));
+        }
+        // The IP of onion addresses is derived from the public key, so the transmitted one is ignored
+        let public_key = <[u8; 32]>::try_from(item.onion.as_slice())?;
+        Ok(NetAddress::new_onion(OnionAddress::new(public_key), item.port.try_into()?))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use waglayla_utils::networking::IpAddress;
+    use waglayla_utils::networking::{IpAddress, NetAddress, OnionAddress};
 
     use crate::pb;
     use std::{
@@ -80,14 +90,22 @@ mod tests {
 
     #[test]
     fn test_netaddress() {
-        let net_addr_ipv4 = pb::NetAddress { timestamp: 0, ip: hex::decode("6a0a8af0").unwrap(), port: 123 };
+        let net_addr_ipv4 = pb::NetAddress { timestamp: 0, ip: hex::decode("6a0a8af0").unwrap(), port: 123, onion: vec![] };
         let ipv4 = Ipv4Addr::from_str("106.10.138.240").unwrap().into();
         assert_eq!(<(IpAddress, u16)>::try_from(net_addr_ipv4.clone()).unwrap(), (ipv4, 123u16));
         assert_eq!(pb::NetAddress::from((ipv4, 123u16)), net_addr_ipv4);
 
-        let net_addr_ipv6 = pb::NetAddress { timestamp: 0, ip: hex::decode("20010db885a3000000008a2e03707334").unwrap(), port: 456 };
+        let net_addr_ipv6 =
+            pb::NetAddress { timestamp: 0, ip: hex::decode("20010db885a3000000008a2e03707334").unwrap(), port: 456, onion: vec![] };
         let ipv6 = Ipv6Addr::from_str("2001:0db8:85a3:0000:0000:8a2e:0370:7334").unwrap().into();
         assert_eq!(<(IpAddress, u16)>::try_from(net_addr_ipv6.clone()).unwrap(), (ipv6, 456u16));
         assert_eq!(pb::NetAddress::from((ipv6, 456u16)), net_addr_ipv6);
+
+        let onion = OnionAddress::from_str("duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion").unwrap();
+        let onion_addr = NetAddress::new_onion(onion, 789);
+        let net_addr_onion = pb::NetAddress::from(onion_addr);
+        assert_eq!(net_addr_onion.onion, onion.public_key().to_vec());
+        assert_eq!(<(IpAddress, u16)>::try_from(net_addr_onion.clone()).unwrap(), (onion.mapped_ip(), 789u16));
+        assert_eq!(NetAddress::try_from(net_addr_onion).unwrap(), onion_addr);
     }
 }
@@ -1,9 +1,10 @@
 use crate::common::ProtocolError;
 use crate::core::hub::Hub;
 use crate::ConnectionError;
-use crate::{core::connection_handler::ConnectionHandler, Router};
+use crate::{core::connection_handler::ConnectionHandler, core::proxy::ProxyConfig, Router};
 use waglayla_utils::networking::NetAddress;
 use waglayla_utils_tower::counters::TowerConnectionCounters;
+use std::net::SocketAddr;
 use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Duration;
@@ -33,6 +34,9 @@ pub struct Adaptor {
     /// If a server was started, it will get cleaned up when this sender is dropped or invoked
     _server_termination: Option<OneshotSender<()>>,
 
+    /// The address the server is listening on, if a server was started
+    local_address: Option<SocketAddr>,
+
     /// An object for creating new outbound connections as well as handling new connections coming from a server
     connection_handler: ConnectionHandler,
 
@@ -41,14 +45,20 @@ pub struct Adaptor {
 }
 
 impl Adaptor {
-    pub(crate) fn new(server_termination: Option<OneshotSender<()>>, connection_handler: ConnectionHandler, hub: Hub) -> Self {
-        Self { _server_termination: server_termination, connection_handler, hub }
+    pub(crate) fn new(server: Option<(OneshotSender<()>, SocketAddr)>, connection_handler: ConnectionHandler, hub: Hub) -> Self {
+        let (server_termination, local_address) = server.unzip();
+        Self { _server_termination: server_termination, local_address, connection_handler, hub }
     }
 
     /// Creates a P2P adaptor with only client-side support. Typical Waglayla nodes should use `Adaptor::bidirectional`
-    pub fn client_only(hub: Hub, initializer: Arc<dyn ConnectionInitializer>, counters: Arc<TowerConnectionCounters>) -> Arc<Self> {
+    pub fn client_only(
+        hub: Hub,
+        initializer: Arc<dyn ConnectionInitializer>,
+        counters: Arc<TowerConnectionCounters>,
+        proxy_config: ProxyConfig,
+    ) -> Arc<Self> {
         let (hub_sender, hub_receiver) = mpsc_channel(Self::hub_channel_size());
-        let connection_handler = ConnectionHandler::new(hub_sender, initializer.clone(), counters);
+        let connection_handler = ConnectionHandler::new(hub_sender, initializer.clone(), counters, proxy_config);
         let adaptor = Arc::new(Adaptor::new(None, connection_handler, hub));
         adaptor.hub.clone().start_event_loop(hub_receiver, initializer);
         adaptor
@@ -60,11 +70,12 @@ impl Adaptor {
         hub: Hub,
         initializer: Arc<dyn ConnectionInitializer>,
         counters: Arc<TowerConnectionCounters>,
+        proxy_config: ProxyConfig,
     ) -> Result<Arc<Self>, ConnectionError> {
         let (hub_sender, hub_receiver) = mpsc_channel(Self::hub_channel_size());
-        let connection_handler = ConnectionHandler::new(hub_sender, initializer.clone(), counters);
-        let server_termination = connection_handler.serve(serve_address)?;
-        let adaptor = Arc::new(Adaptor::new(Some(server_termination), connection_handler, hub));
+        let connection_handler = ConnectionHandler::new(hub_sender, initializer.clone(), counters, proxy_config);
+        let server = connection_handler.serve(serve_address)?;
+        let adaptor = Arc::new(Adaptor::new(Some(server), connection_handler, hub));
         adaptor.hub.clone().start_event_loop(hub_receiver, initializer);
         Ok(adaptor)
     }
@@ -84,6 +95,16 @@ impl Adaptor {
         self.connection_handler.connect_with_retry(peer_address, retry_attempts, retry_interval).await.map(|r| r.key())
     }
 
+    /// Returns the address the server is listening on, or `None` for a client-only adaptor
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        self.local_address
+    }
+
+    /// Returns whether `address` can be connected to with the configured proxies
+    pub fn is_reachable(&self, address: &NetAddress) -> bool {
+        self.connection_handler.is_reachable(address)
+    }
+
     /// Terminates all peers and cleans up any additional async resources
     pub async fn close(&self) {
         self.terminate_all_peers().await;
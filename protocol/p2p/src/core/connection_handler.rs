@@ -1,5 +1,6 @@
 use crate::common::ProtocolError;
 use crate::core::hub::HubEvent;
+use crate::core::proxy::{socks5_connect, ProxyConfig};
 use crate::pb::{
     p2p_client::P2pClient as ProtoP2pClient, p2p_server::P2p as ProtoP2p, p2p_server::P2pServer as ProtoP2pServer, WaglayladMessage,
 };
@@ -11,24 +12,30 @@ use waglayla_utils_tower::{
     counters::TowerConnectionCounters,
     middleware::{measure_request_body_size_layer, CountBytesBody, MapResponseBodyLayer, ServiceBuilder},
 };
-use std::net::ToSocketAddrs;
+use std::net::{SocketAddr, ToSocketAddrs};
 use std::pin::Pin;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
+use tokio::net::TcpListener;
 use tokio::sync::mpsc::{channel as mpsc_channel, Sender as MpscSender};
 use tokio::sync::oneshot::{channel as oneshot_channel, Sender as OneshotSender};
-use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
 use tokio_stream::StreamExt;
 use tonic::codegen::Body;
-use tonic::transport::{Error as TonicError, Server as TonicServer};
+use tonic::transport::{Error as TonicError, Server as TonicServer, Uri};
 use tonic::{Request, Response, Status as TonicStatus, Streaming};
+use tower::service_fn;
 
 #[derive(Error, Debug)]
 pub enum ConnectionError {
     #[error("missing socket address")]
     NoAddress,
 
+    #[error("cannot connect to onion peers without an onion proxy")]
+    NoOnionProxy,
+
     #[error("{0}")]
     IoError(#[from] std::io::Error),
 
@@ -52,6 +59,7 @@ pub struct ConnectionHandler {
     hub_sender: MpscSender<HubEvent>,
     initializer: Arc<dyn ConnectionInitializer>,
     counters: Arc<TowerConnectionCounters>,
+    proxy_config: ProxyConfig,
 }
 
 impl ConnectionHandler {
@@ -59,15 +67,28 @@ impl ConnectionHandler {
         hub_sender: MpscSender<HubEvent>,
         initializer: Arc<dyn ConnectionInitializer>,
         counters: Arc<TowerConnectionCounters>,
+        proxy_config: ProxyConfig,
     ) -> Self {
-        Self { hub_sender, initializer, counters }
+        Self { hub_sender, initializer, counters, proxy_config }
+    }
+
+    /// Returns whether `address` can be connected to with the configured proxies
+    pub(crate) fn is_reachable(&self, address: &NetAddress) -> bool {
+        self.proxy_config.is_reachable(address)
     }
 
-    /// Launches a P2P server listener loop
-    pub(crate) fn serve(&self, serve_address: NetAddress) -> Result<OneshotSender<()>, ConnectionError> {
+    /// Launches a P2P server listener loop. Returns the loop termination sender along with the address the listener
+    /// was bound to, which differs from `serve_address` when binding to port 0
+    pub(crate) fn serve(&self, serve_address: NetAddress) -> Result<(OneshotSender<()>, SocketAddr), ConnectionError> {
         let (termination_sender, termination_receiver) = oneshot_channel::<()>();
         let connection_handler = self.clone();
-        info!("P2P Server starting on: {}", serve_address);
+
+        // The listener is bound before spawning the server so that binding errors are reported to the caller
+        let listener = std::net::TcpListener::bind(SocketAddr::from(serve_address))?;
+        listener.set_nonblocking(true)?;
+        let listener = TcpListener::from_std(listener)?;
+        let local_address = listener.local_addr()?;
+        info!("P2P Server starting on: {}", local_address);
 
         let bytes_tx = self.counters.bytes_tx.clone();
         let bytes_rx = self.counters.bytes_rx.clone();
@@ -83,30 +104,53 @@ impl ConnectionHandler {
                 .layer(measure_request_body_size_layer(bytes_rx, |b| b))
                 .layer(MapResponseBodyLayer::new(move |body| CountBytesBody::new(body, bytes_tx.clone())))
                 .add_service(proto_server)
-                .serve_with_shutdown(serve_address.into(), termination_receiver.map(drop))
+                .serve_with_incoming_shutdown(TcpListenerStream::new(listener), termination_receiver.map(drop))
                 .await;
 
             match serve_result {
-                Ok(_) => info!("P2P Server stopped: {}", serve_address),
-                Err(err) => panic!("P2P, Server {serve_address} stopped with error: {err:?}"),
+                Ok(_) => info!("P2P Server stopped: {}", local_address),
+                Err(err) => panic!("P2P, Server {local_address} stopped with error: {err:?}"),
             }
         });
-        Ok(termination_sender)
+        Ok((termination_sender, local_address))
     }
 
     /// Connect to a new peer
     pub(crate) async fn connect(&self, peer_address: String) -> Result<Arc<Router>, ConnectionError> {
-        let Some(socket_address) = peer_address.to_socket_addrs()?.next() else {
-            return Err(ConnectionError::NoAddress);
+        // Onion addresses cannot be resolved locally, so they are reachable through the onion proxy only
+        let (socket_address, host, proxy) = match NetAddress::from_str(&peer_address).ok().filter(NetAddress::is_onion) {
+            Some(net_address) => {
+                let Some(proxy) = self.proxy_config.onion else {
+                    return Err(ConnectionError::NoOnionProxy);
+                };
+                (SocketAddr::from(net_address), net_address.onion.unwrap().to_string(), Some(proxy))
+            }
+            None => {
+                let Some(socket_address) = peer_address.to_socket_addrs()?.next() else {
+                    return Err(ConnectionError::NoAddress);
+                };
+                (socket_address, socket_address.ip().to_string(), self.proxy_config.proxy)
+            }
         };
         let peer_address = format!("http://{}", peer_address); // Add scheme prefix as required by Tonic
 
-        let channel = tonic::transport::Endpoint::new(peer_address)?
-            .timeout(Duration::from_millis(Self::communication_timeout()))
-            .connect_timeout(Duration::from_millis(Self::connect_timeout()))
-            .tcp_keepalive(Some(Duration::from_millis(Self::keep_alive())))
-            .connect()
-            .await?;
+        let endpoint = tonic::transport::Endpoint::new(peer_address)?.timeout(Duration::from_millis(Self::communication_timeout()));
+        let channel = match proxy {
+            Some(proxy) => {
+                let port = socket_address.port();
+                endpoint
+                    .connect_timeout(Duration::from_millis(Self::proxy_connect_timeout()))
+                    .connect_with_connector(service_fn(move |_: Uri| socks5_connect(proxy, host.clone(), port)))
+                    .await?
+            }
+            None => {
+                endpoint
+                    .connect_timeout(Duration::from_millis(Self::connect_timeout()))
+                    .tcp_keepalive(Some(Duration::from_millis(Self::keep_alive())))
+                    .connect()
+                    .await?
+            }
+        };
 
         let channel = ServiceBuilder::new()
             .layer(MapResponseBodyLayer::new(move |body| CountBytesBody::new(body, self.counters.bytes_rx.clone())))
@@ -195,6 +239,11 @@ impl ConnectionHandler {
     fn connect_timeout() -> u64 {
         1_000
     }
+
+    /// Establishing a connection through a proxy (a Tor circuit in particular) takes considerably longer
+    fn proxy_connect_timeout() -> u64 {
+        10_000
+    }
 }
 
 #[tonic::async_trait]
@@ -0,0 +1,304 @@
+//!
+//! Opportunistic encryption and authentication of P2P connections.
+//!
+//! Both peers send an ephemeral secp256k1 public key in their version message and derive a pair of directional
+//! ChaCha20-Poly1305 session keys through ECDH. All messages following the version exchange are then sent encrypted.
+//! A node may additionally hold a static identity key. It proves ownership of the key by signing the ephemeral keys of
+//! the session within its verack message, which lets operators pin and authenticate the identity keys of known peers.
+//!
+
+use crate::common::ProtocolError;
+use chacha20poly1305::{aead::Aead, ChaCha20Poly1305, KeyInit};
+use secp256k1::{ecdh::SharedSecret, schnorr::Signature, Keypair, Message, PublicKey, SECP256K1};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fmt::{Debug, Display},
+    fs,
+    io::{self, Write},
+    net::{AddrParseError, IpAddr},
+    path::Path,
+    str::FromStr,
+};
+use thiserror::Error;
+use waglayla_utils::networking::{IpAddress, IpSubnet};
+
+/// The public part of a node identity key, advertised to peers during the handshake
+pub type IdentityKey = secp256k1::XOnlyPublicKey;
+
+/// Errors of parsing a [`PinnedPeer`]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PinnedPeerError {
+    #[error("pinned peer must have the form `PUBKEY@IP`")]
+    MissingAddress,
+
+    #[error("invalid identity key: {0}")]
+    InvalidKey(#[from] secp256k1::Error),
+
+    #[error("invalid IP address: {0}")]
+    InvalidAddress(#[from] AddrParseError),
+}
+
+/// An identity key pinned to the IP address of a known peer. Connections with that address must
+/// authenticate with the key, so that the peer cannot be impersonated by stripping its keys in transit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PinnedPeer {
+    pub key: IdentityKey,
+    pub ip: IpAddress,
+}
+
+impl PinnedPeer {
+    pub fn new(key: IdentityKey, ip: IpAddress) -> Self {
+        Self { key, ip }
+    }
+
+    /// Returns whether `ip` is the address of this peer
+    pub fn matches(&self, ip: IpAddr) -> bool {
+        IpSubnet::from(self.ip).contains(&ip)
+    }
+}
+
+impl FromStr for PinnedPeer {
+    type Err = PinnedPeerError;
+
+    /// Parses a `PUBKEY@IP` string
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, ip) = s.split_once('@').ok_or(PinnedPeerError::MissingAddress)?;
+        Ok(Self::new(IdentityKey::from_str(key)?, IpAddress::from_str(ip)?))
+    }
+}
+
+impl Display for PinnedPeer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.key, self.ip)
+    }
+}
+
+impl Serialize for PinnedPeer {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PinnedPeer {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <String as Deserialize>::deserialize(deserializer)?;
+        PinnedPeer::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Domain separation tag of the signed handshake transcript
+const TRANSCRIPT_DOMAIN: &[u8] = b"waglayla-p2p-handshake";
+
+/// The static identity of a node
+#[derive(Clone)]
+pub struct NodeIdentity {
+    keypair: Keypair,
+}
+
+impl NodeIdentity {
+    pub fn new(keypair: Keypair) -> Self {
+        Self { keypair }
+    }
+
+    pub fn generate() -> Self {
+        Self::new(Keypair::new_global(&mut rand::thread_rng()))
+    }
+
+    /// Loads the hex encoded identity secret key stored at `path`, or stores a newly generated one if the file does not exist
+    pub fn load_or_create(path: &Path) -> io::Result<Self> {
+        if path.exists() {
+            let secret = fs::read_to_string(path)?;
+            let keypair =
+                Keypair::from_seckey_str_global(secret.trim()).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            return Ok(Self::new(keypair));
+        }
+
+        let identity = Self::generate();
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(path)?;
+        writeln!(file, "{}", identity.keypair.display_secret())?;
+        Ok(identity)
+    }
+
+    pub fn public_key(&self) -> IdentityKey {
+        self.keypair.x_only_public_key().0
+    }
+
+    fn sign(&self, message: &Message) -> Signature {
+        SECP256K1.sign_schnorr(message, &self.keypair)
+    }
+}
+
+impl Debug for NodeIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the secret key
+        f.debug_struct("NodeIdentity").field("public_key", &self.public_key()).finish()
+    }
+}
+
+/// The ephemeral key of a single connection handshake
+pub(crate) struct KeyExchange {
+    keypair: Keypair,
+}
+
+impl KeyExchange {
+    pub fn new() -> Self {
+        Self { keypair: Keypair::new_global(&mut rand::thread_rng()) }
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.keypair.public_key()
+    }
+
+    /// Derives the ciphers for sending messages to and for receiving messages from the owner of `peer_key`
+    pub fn derive_ciphers(&self, peer_key: &PublicKey) -> (MessageCipher, MessageCipher) {
+        let shared_secret = SharedSecret::new(peer_key, &self.keypair.secret_key());
+        let public_key = self.public_key();
+        let send = MessageCipher::new(Self::session_key(&shared_secret, &public_key, peer_key));
+        let receive = MessageCipher::new(Self::session_key(&shared_secret, peer_key, &public_key));
+        (send, receive)
+    }
+
+    /// The key of messages sent by the owner of `sender_key`
+    fn session_key(shared_secret: &SharedSecret, sender_key: &PublicKey, receiver_key: &PublicKey) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(shared_secret.secret_bytes())
+            .chain_update(sender_key.serialize())
+            .chain_update(receiver_key.serialize())
+            .finalize()
+            .into()
+    }
+
+    /// Signs the ephemeral keys of both sides of the session with `identity`
+    pub fn sign_transcript(&self, identity: &NodeIdentity, peer_key: &PublicKey) -> Vec<u8> {
+        identity.sign(&transcript(&self.public_key(), peer_key)).as_ref().to_vec()
+    }
+
+    /// Verifies that `signature` was produced by the owner of `identity_key` over the ephemeral keys of both sides of the session
+    pub fn verify_transcript(&self, identity_key: &IdentityKey, peer_key: &PublicKey, signature: &[u8]) -> Result<(), ProtocolError> {
+        let signature = Signature::from_slice(signature).map_err(|_| ProtocolError::Other("malformed identity signature"))?;
+        SECP256K1
+            .verify_schnorr(&signature, &transcript(peer_key, &self.public_key()), identity_key)
+            .map_err(|_| ProtocolError::Other("peer identity authentication failed"))
+    }
+}
+
+/// The transcript signed by the owner of `signer_key`. The keys are ordered by role so that a signature is never valid for the opposite direction
+fn transcript(signer_key: &PublicKey, verifier_key: &PublicKey) -> Message {
+    let digest = Sha256::new()
+        .chain_update(TRANSCRIPT_DOMAIN)
+        .chain_update(signer_key.serialize())
+        .chain_update(verifier_key.serialize())
+        .finalize();
+    Message::from_digest(digest.into())
+}
+
+/// Encrypts or decrypts the messages of a single direction of a connection. Messages are numbered
+/// implicitly, hence they must be opened in the exact order they were sealed
+pub(crate) struct MessageCipher {
+    cipher: ChaCha20Poly1305,
+    counter: u64,
+}
+
+impl MessageCipher {
+    fn new(key: [u8; 32]) -> Self {
+        Self { cipher: ChaCha20Poly1305::new(&key.into()), counter: 0 }
+    }
+
+    fn next_nonce(&mut self) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        nonce[..8].copy_from_slice(&self.counter.to_le_bytes());
+        self.counter += 1;
+        nonce
+    }
+
+    pub fn seal(&mut self, plaintext: &[u8]) -> Vec<u8> {
+        let nonce = self.next_nonce();
+        self.cipher.encrypt(&nonce.into(), plaintext).expect("encryption with a valid key never fails")
+    }
+
+    pub fn open(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>, ProtocolError> {
+        let nonce = self.next_nonce();
+        self.cipher.decrypt(&nonce.into(), ciphertext).map_err(|_| ProtocolError::Other("failed to decrypt a message from the peer"))
+    }
+}
+
+impl Debug for MessageCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MessageCipher").field("counter", &self.counter).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_ciphers() {
+        let (alice, bob) = (KeyExchange::new(), KeyExchange::new());
+        let (mut alice_send, mut alice_receive) = alice.derive_ciphers(&bob.public_key());
+        let (mut bob_send, mut bob_receive) = bob.derive_ciphers(&alice.public_key());
+
+        for message in [b"first".as_slice(), b"", b"third"] {
+            assert_eq!(bob_receive.open(&alice_send.seal(message)).unwrap(), message);
+            assert_eq!(alice_receive.open(&bob_send.seal(message)).unwrap(), message);
+        }
+
+        // Each direction has a distinct key
+        let sealed = alice_send.seal(b"message");
+        assert!(alice_receive.open(&sealed).is_err());
+
+        // Tampered, replayed or reordered messages are rejected
+        let mut tampered = alice_send.seal(b"message");
+        tampered[0] ^= 1;
+        assert!(bob_receive.open(&sealed).is_ok());
+        assert!(bob_receive.open(&tampered).is_err());
+        let (first, second) = (alice_send.seal(b"first"), alice_send.seal(b"second"));
+        assert!(bob_receive.open(&second).is_err());
+        assert!(bob_receive.open(&first).is_err());
+    }
+
+    #[test]
+    fn test_transcript_signature() {
+        let (alice, bob) = (KeyExchange::new(), KeyExchange::new());
+        let identity = NodeIdentity::generate();
+        let signature = alice.sign_transcript(&identity, &bob.public_key());
+
+        assert!(bob.verify_transcript(&identity.public_key(), &alice.public_key(), &signature).is_ok());
+        // The signature is bound to the identity, to the session keys and to the direction
+        assert!(bob.verify_transcript(&NodeIdentity::generate().public_key(), &alice.public_key(), &signature).is_err());
+        assert!(bob.verify_transcript(&identity.public_key(), &KeyExchange::new().public_key(), &signature).is_err());
+        assert!(alice.verify_transcript(&identity.public_key(), &bob.public_key(), &signature).is_err());
+        assert!(bob.verify_transcript(&identity.public_key(), &alice.public_key(), &signature[1..]).is_err());
+    }
+
+    #[test]
+    fn test_pinned_peer_parsing() {
+        let key = NodeIdentity::generate().public_key();
+        let pinned = PinnedPeer::from_str(&format!("{key}@1.2.3.4")).unwrap();
+        assert_eq!(pinned, PinnedPeer::new(key, IpAddress::from_str("1.2.3.4").unwrap()));
+        assert_eq!(PinnedPeer::from_str(&pinned.to_string()).unwrap(), pinned);
+        assert!(pinned.matches("::ffff:1.2.3.4".parse().unwrap()));
+        assert!(!pinned.matches("1.2.3.5".parse().unwrap()));
+
+        assert_eq!(PinnedPeer::from_str(&key.to_string()), Err(PinnedPeerError::MissingAddress));
+        assert!(matches!(PinnedPeer::from_str("00@1.2.3.4"), Err(PinnedPeerError::InvalidKey(_))));
+        assert!(matches!(PinnedPeer::from_str(&format!("{key}@1.2.3")), Err(PinnedPeerError::InvalidAddress(_))));
+    }
+
+    #[test]
+    fn test_load_or_create_identity() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("identity.key");
+        let created = NodeIdentity::load_or_create(&path).unwrap();
+        let loaded = NodeIdentity::load_or_create(&path).unwrap();
+        assert_eq!(created.public_key(), loaded.public_key());
+
+        fs::write(&path, "not a key").unwrap();
+        assert!(NodeIdentity::load_or_create(&path).is_err());
+    }
+}
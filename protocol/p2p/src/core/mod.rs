@@ -1,6 +1,8 @@
 pub mod adaptor;
 pub mod connection_handler;
+pub mod encryption;
 pub mod hub;
 pub mod payload_type;
 pub mod peer;
+pub mod proxy;
 pub mod router;
@@ -46,6 +46,12 @@ pub enum WaglayladMessagePayloadType {
     IbdChainBlockLocator,
     RequestAntipast,
     RequestNextPruningPointAndItsAnticoneBlocks,
+    Encrypted,
+    CompactBlock,
+    RequestCompactBlocks,
+    RequestBlockTransactions,
+    BlockTransactions,
+    StemTransactions,
 }
 
 impl From<&WaglayladMessagePayload> for WaglayladMessagePayloadType {
@@ -100,6 +106,12 @@ impl From<&WaglayladMessagePayload> for WaglayladMessagePayloadType {
             WaglayladMessagePayload::RequestNextPruningPointAndItsAnticoneBlocks(_) => {
                 WaglayladMessagePayloadType::RequestNextPruningPointAndItsAnticoneBlocks
             }
+            WaglayladMessagePayload::Encrypted(_) => WaglayladMessagePayloadType::Encrypted,
+            WaglayladMessagePayload::CompactBlock(_) => WaglayladMessagePayloadType::CompactBlock,
+            WaglayladMessagePayload::RequestCompactBlocks(_) => WaglayladMessagePayloadType::RequestCompactBlocks,
+            WaglayladMessagePayload::RequestBlockTransactions(_) => WaglayladMessagePayloadType::RequestBlockTransactions,
+            WaglayladMessagePayload::BlockTransactions(_) => WaglayladMessagePayloadType::BlockTransactions,
+            WaglayladMessagePayload::StemTransactions(_) => WaglayladMessagePayloadType::StemTransactions,
         }
     }
 }
@@ -1,3 +1,4 @@
+use crate::core::encryption::IdentityKey;
 use waglayla_consensus_core::subnets::SubnetworkId;
 use waglayla_utils::networking::{IpAddress, PeerId};
 use std::{fmt::Display, net::SocketAddr, sync::Arc, time::Instant};
@@ -13,6 +14,47 @@ pub struct PeerProperties {
     pub time_offset: i64,
 }
 
+/// A peer misbehaviour score which decays linearly over time
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MisbehaviourScore {
+    /// The score as of `last_update`
+    value: f64,
+
+    /// Decay of the score in points per minute
+    decay_per_minute: f64,
+
+    /// Time of the last score update
+    last_update: Option<Instant>,
+}
+
+impl MisbehaviourScore {
+    pub fn new(decay_per_minute: u32) -> Self {
+        Self { decay_per_minute: decay_per_minute as f64, ..Default::default() }
+    }
+
+    fn decayed(&self, now: Instant) -> f64 {
+        match self.last_update {
+            Some(last_update) => {
+                let elapsed_minutes = now.saturating_duration_since(last_update).as_secs_f64() / 60.0;
+                (self.value - elapsed_minutes * self.decay_per_minute).max(0.0)
+            }
+            None => self.value,
+        }
+    }
+
+    /// Adds `points` to the score decayed up to `now` and returns the updated score
+    pub fn increase(&mut self, points: u32, now: Instant) -> u32 {
+        self.value = self.decayed(now) + points as f64;
+        self.last_update = Some(now);
+        self.current(now)
+    }
+
+    /// Returns the score decayed up to `now`
+    pub fn current(&self, now: Instant) -> u32 {
+        self.decayed(now).ceil() as u32
+    }
+}
+
 #[derive(Debug)]
 pub struct Peer {
     identity: PeerId,
@@ -21,6 +63,9 @@ pub struct Peer {
     connection_started: Instant,
     properties: Arc<PeerProperties>,
     last_ping_duration: u64,
+    misbehaviour_score: u32,
+    is_encrypted: bool,
+    identity_key: Option<IdentityKey>,
 }
 
 impl Peer {
@@ -31,8 +76,21 @@ impl Peer {
         connection_started: Instant,
         properties: Arc<PeerProperties>,
         last_ping_duration: u64,
+        misbehaviour_score: u32,
+        is_encrypted: bool,
+        identity_key: Option<IdentityKey>,
     ) -> Self {
-        Self { identity, net_address, is_outbound, connection_started, properties, last_ping_duration }
+        Self {
+            identity,
+            net_address,
+            is_outbound,
+            connection_started,
+            properties,
+            last_ping_duration,
+            misbehaviour_score,
+            is_encrypted,
+            identity_key,
+        }
     }
 
     /// Internal identity of this peer
@@ -65,6 +123,21 @@ impl Peer {
     pub fn last_ping_duration(&self) -> u64 {
         self.last_ping_duration
     }
+
+    /// The misbehaviour score of this peer at the time of the snapshot
+    pub fn misbehaviour_score(&self) -> u32 {
+        self.misbehaviour_score
+    }
+
+    /// Indicates whether the messages exchanged with this peer are encrypted
+    pub fn is_encrypted(&self) -> bool {
+        self.is_encrypted
+    }
+
+    /// The identity key the peer authenticated with during the handshake, if any
+    pub fn identity_key(&self) -> Option<IdentityKey> {
+        self.identity_key
+    }
 }
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
@@ -90,3 +163,21 @@ impl Display for PeerKey {
         write!(f, "{}+{}", self.identity, self.ip)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_misbehaviour_score_decay() {
+        let start = Instant::now();
+        let mut score = MisbehaviourScore::new(10);
+        assert_eq!(score.increase(50, start), 50);
+        assert_eq!(score.current(start + Duration::from_secs(60)), 40);
+        assert_eq!(score.increase(20, start + Duration::from_secs(120)), 50);
+        // The score never decays below zero
+        assert_eq!(score.current(start + Duration::from_secs(3600)), 0);
+        assert_eq!(score.increase(5, start + Duration::from_secs(3600)), 5);
+    }
+}
@@ -0,0 +1,146 @@
+use waglayla_utils::networking::NetAddress;
+use std::io;
+use std::net::SocketAddr;
+use tokio::net::TcpStream;
+use tokio_socks::tcp::Socks5Stream;
+
+/// SOCKS5 proxies through which outbound connections are routed
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ProxyConfig {
+    /// Proxy for connections to IP peers. Peers are dialed directly if not set
+    pub proxy: Option<SocketAddr>,
+
+    /// Proxy for connections to onion peers. Onion peers are unreachable if not set
+    pub onion: Option<SocketAddr>,
+}
+
+impl ProxyConfig {
+    pub fn new(proxy: Option<SocketAddr>, onion: Option<SocketAddr>) -> Self {
+        Self { proxy, onion }
+    }
+
+    /// Returns whether `address` can be connected to under this configuration
+    pub fn is_reachable(&self, address: &NetAddress) -> bool {
+        !address.is_onion() || self.onion.is_some()
+    }
+}
+
+/// Opens a TCP stream to `host:port` tunneled through the SOCKS5 proxy at `proxy`.
+/// Host names are passed on to the proxy as is, so they are never resolved locally.
+pub(crate) async fn socks5_connect(proxy: SocketAddr, host: String, port: u16) -> io::Result<TcpStream> {
+    let stream = Socks5Stream::connect(proxy, (host, port)).await.map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+    Ok(stream.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{echo::EchoFlowInitializer, Adaptor, Hub};
+    use std::{str::FromStr, sync::Arc, time::Duration};
+    use tokio::{
+        io::{copy_bidirectional, AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    };
+
+    /// Spawns a minimal SOCKS5 stand-in which accepts unauthenticated CONNECT requests and forwards all of
+    /// them to `forward_to`. The requested targets are reported through the returned receiver.
+    async fn spawn_socks5_stand_in(forward_to: SocketAddr) -> (SocketAddr, UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (sender, receiver) = unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_socks5_connection(stream, forward_to, sender.clone()));
+            }
+        });
+        (address, receiver)
+    }
+
+    async fn serve_socks5_connection(
+        mut stream: TcpStream,
+        forward_to: SocketAddr,
+        targets: UnboundedSender<String>,
+    ) -> io::Result<()> {
+        // Greeting: version, number of methods and the methods themselves
+        let mut header = [0u8; 2];
+        stream.read_exact(&mut header).await?;
+        let mut methods = vec![0u8; header[1] as usize];
+        stream.read_exact(&mut methods).await?;
+        stream.write_all(&[5, 0]).await?;
+
+        // Request: version, command, reserved, address type, address, port
+        let mut request = [0u8; 4];
+        stream.read_exact(&mut request).await?;
+        let host = match request[3] {
+            1 => {
+                let mut octets = [0u8; 4];
+                stream.read_exact(&mut octets).await?;
+                std::net::Ipv4Addr::from(octets).to_string()
+            }
+            3 => {
+                let mut name = vec![0u8; stream.read_u8().await? as usize];
+                stream.read_exact(&mut name).await?;
+                String::from_utf8(name).unwrap()
+            }
+            4 => {
+                let mut octets = [0u8; 16];
+                stream.read_exact(&mut octets).await?;
+                std::net::Ipv6Addr::from(octets).to_string()
+            }
+            atyp => panic!("unexpected address type {atyp}"),
+        };
+        let port = stream.read_u16().await?;
+        targets.send(format!("{host}:{port}")).unwrap();
+
+        let mut upstream = TcpStream::connect(forward_to).await?;
+        stream.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).await?;
+        copy_bidirectional(&mut stream, &mut upstream).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_connect_through_socks5_proxy() {
+        let address = NetAddress::from_str("127.0.0.1:50055").unwrap();
+        let server =
+            Adaptor::bidirectional(address, Hub::new(), Arc::new(EchoFlowInitializer::new()), Default::default(), Default::default())
+                .unwrap();
+        let (proxy, mut targets) = spawn_socks5_stand_in(address.into()).await;
+
+        // Without an onion proxy, onion peers cannot be dialed
+        let onion_peer = "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion:50055";
+        let direct_client =
+            Adaptor::client_only(Hub::new(), Arc::new(EchoFlowInitializer::new()), Default::default(), Default::default());
+        assert!(!direct_client.is_reachable(&NetAddress::from_str(onion_peer).unwrap()));
+        assert!(matches!(direct_client.connect_peer(onion_peer.to_string()).await, Err(crate::ConnectionError::NoOnionProxy)));
+
+        // The onion host name is handed to the proxy unresolved
+        let onion_client = Adaptor::client_only(
+            Hub::new(),
+            Arc::new(EchoFlowInitializer::new()),
+            Default::default(),
+            ProxyConfig::new(None, Some(proxy)),
+        );
+        onion_client.connect_peer(onion_peer.to_string()).await.expect("onion peer connection failed");
+        assert_eq!(targets.recv().await.unwrap(), onion_peer);
+
+        // IP peers are routed through the general proxy
+        let ip_client = Adaptor::client_only(
+            Hub::new(),
+            Arc::new(EchoFlowInitializer::new()),
+            Default::default(),
+            ProxyConfig::new(Some(proxy), None),
+        );
+        ip_client.connect_peer(address.to_string()).await.expect("proxied peer connection failed");
+        assert_eq!(targets.recv().await.unwrap(), address.to_string());
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(onion_client.active_peers().len(), 1);
+        assert_eq!(ip_client.active_peers().len(), 1);
+        assert_eq!(server.active_peers().len(), 2);
+
+        for adaptor in [onion_client, ip_client, direct_client, server] {
+            adaptor.close().await;
+        }
+    }
+}
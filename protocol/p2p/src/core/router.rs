@@ -1,16 +1,18 @@
+use crate::core::encryption::{IdentityKey, MessageCipher};
 use crate::core::hub::HubEvent;
-use crate::pb::RejectMessage;
+use crate::pb::{EncryptedMessage, RejectMessage};
 use crate::pb::{waglaylad_message::Payload as WaglayladMessagePayload, WaglayladMessage};
 use crate::{common::ProtocolError, WaglayladMessagePayloadType};
 use crate::{make_message, Peer};
 use waglayla_core::{debug, error, info, trace, warn};
 use waglayla_utils::networking::PeerId;
 use parking_lot::{Mutex, RwLock};
+use prost::Message;
 use seqlock::SeqLock;
 use std::fmt::{Debug, Display};
 use std::net::SocketAddr;
 use std::ops::{Deref, DerefMut};
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::time::Instant;
 use std::{collections::HashMap, sync::Arc};
 use tokio::select;
@@ -19,7 +21,7 @@ use tokio::sync::mpsc::{channel as mpsc_channel, Receiver as MpscReceiver, Sende
 use tokio::sync::oneshot::{channel as oneshot_channel, Sender as OneshotSender};
 use tonic::Streaming;
 
-use super::peer::{PeerKey, PeerProperties};
+use super::peer::{MisbehaviourScore, PeerKey, PeerProperties};
 
 pub struct IncomingRoute {
     rx: MpscReceiver<WaglayladMessage>,
@@ -102,6 +104,15 @@ struct RouterMutableState {
 
     /// Duration of the last ping to this peer
     last_ping_duration: u64,
+
+    /// Accumulated misbehaviour score of this peer
+    misbehaviour_score: MisbehaviourScore,
+
+    /// Indicates whether a ban was already issued for this peer due to its misbehaviour score
+    banned: bool,
+
+    /// The identity key the peer authenticated with during the handshake, if any
+    identity_key: Option<IdentityKey>,
 }
 
 impl RouterMutableState {
@@ -134,6 +145,15 @@ pub struct Router {
     /// The outgoing route for sending messages to this peer
     outgoing_route: MpscSender<WaglayladMessage>,
 
+    /// Cipher of outgoing messages, set once encryption was agreed upon during the handshake
+    send_cipher: Mutex<Option<MessageCipher>>,
+
+    /// Cipher of incoming messages, set once encryption was agreed upon during the handshake
+    receive_cipher: Mutex<Option<MessageCipher>>,
+
+    /// Indicates whether the handshake completed with encryption, after which plaintext messages are no longer accepted
+    is_encrypted: AtomicBool,
+
     /// A channel sender for internal event management. Used to send information from each router to a central hub object
     hub_sender: MpscSender<HubEvent>,
 
@@ -162,6 +182,9 @@ impl From<&Router> for Peer {
             router.connection_started,
             router.properties(),
             router.last_ping_duration(),
+            router.misbehaviour_score(),
+            router.is_encrypted(),
+            router.identity_key(),
         )
     }
 }
@@ -191,6 +214,9 @@ impl Router {
             routing_map_by_type: RwLock::new(HashMap::new()),
             routing_map_by_id: RwLock::new(HashMap::new()),
             outgoing_route,
+            send_cipher: Mutex::new(None),
+            receive_cipher: Mutex::new(None),
+            is_encrypted: AtomicBool::new(false),
             hub_sender,
             mutable_state: Mutex::new(RouterMutableState::new(Some(start_sender), Some(shutdown_sender))),
         });
@@ -211,10 +237,13 @@ impl Router {
 
                     res = incoming_stream.message() => match res {
                         Ok(Some(msg)) => {
-                            trace!("P2P msg: {:?}, router-id: {}, peer: {}", message_summary(&msg), router.identity(), router);
-                            match router.route_to_flow(msg) {
+                            match router.decrypt_incoming(msg).and_then(|msg| {
+                                trace!("P2P msg: {:?}, router-id: {}, peer: {}", message_summary(&msg), router.identity(), router);
+                                router.route_to_flow(msg)
+                            }) {
                                 Ok(()) => {},
                                 Err(e) => {
+                                    router.increase_misbehaviour_score(e.misbehaviour_score());
                                     match e {
                                         ProtocolError::IgnorableReject(reason) => debug!("P2P, got reject message: {} from peer: {}", reason, router),
                                         ProtocolError::Rejected(reason) => warn!("P2P, got reject message: {} from peer: {}", reason, router),
@@ -294,6 +323,74 @@ impl Router {
         self.mutable_state.lock().last_ping_duration
     }
 
+    /// Sets the misbehaviour score state of this peer, e.g. with the decay rate and the score carried over from previous connections
+    pub fn set_misbehaviour(&self, misbehaviour: MisbehaviourScore) {
+        self.mutable_state.lock().misbehaviour_score = misbehaviour;
+    }
+
+    /// A snapshot of the misbehaviour score state of this peer
+    pub fn misbehaviour(&self) -> MisbehaviourScore {
+        self.mutable_state.lock().misbehaviour_score
+    }
+
+    /// Increases the misbehaviour score of this peer by `points` and returns the updated (decayed) score
+    pub fn increase_misbehaviour_score(&self, points: u32) -> u32 {
+        self.mutable_state.lock().misbehaviour_score.increase(points, Instant::now())
+    }
+
+    /// The current (decayed) misbehaviour score of this peer
+    pub fn misbehaviour_score(&self) -> u32 {
+        self.mutable_state.lock().misbehaviour_score.current(Instant::now())
+    }
+
+    /// Marks this peer as banned due to misbehaviour. Returns true only on the first call, so that
+    /// a ban is issued once even if several flows observe the crossing of the threshold
+    pub fn mark_banned(&self) -> bool {
+        !std::mem::replace(&mut self.mutable_state.lock().banned, true)
+    }
+
+    /// Indicates whether the messages exchanged with this peer are encrypted
+    pub fn is_encrypted(&self) -> bool {
+        self.is_encrypted.load(Ordering::SeqCst)
+    }
+
+    /// The identity key the peer authenticated with during the handshake, if any
+    pub fn identity_key(&self) -> Option<IdentityKey> {
+        self.mutable_state.lock().identity_key
+    }
+
+    pub(crate) fn set_identity_key(&self, identity_key: IdentityKey) {
+        self.mutable_state.lock().identity_key = Some(identity_key);
+    }
+
+    /// Sets the cipher of incoming messages. Must be called before the peer may send its first encrypted message
+    pub(crate) fn set_receive_cipher(&self, cipher: MessageCipher) {
+        self.receive_cipher.lock().replace(cipher);
+    }
+
+    /// Sets the cipher of outgoing messages, so that all messages enqueued from now on are encrypted
+    pub(crate) fn set_send_cipher(&self, cipher: MessageCipher) {
+        self.send_cipher.lock().replace(cipher);
+        self.is_encrypted.store(true, Ordering::SeqCst);
+    }
+
+    /// Unwraps an incoming encrypted message. Plaintext messages pass as is only as long as encryption is not established
+    fn decrypt_incoming(&self, msg: WaglayladMessage) -> Result<WaglayladMessage, ProtocolError> {
+        match &msg.payload {
+            Some(WaglayladMessagePayload::Encrypted(encrypted)) => {
+                let mut receive_cipher = self.receive_cipher.lock();
+                let Some(cipher) = receive_cipher.as_mut() else {
+                    return Err(ProtocolError::Other("received an encrypted message before agreeing on encryption"));
+                };
+                let plaintext = cipher.open(&encrypted.ciphertext)?;
+                WaglayladMessage::decode(plaintext.as_slice())
+                    .map_err(|_| ProtocolError::Other("failed to decode a decrypted message"))
+            }
+            _ if self.is_encrypted() => Err(ProtocolError::Other("received a plaintext message on an encrypted connection")),
+            _ => Ok(msg),
+        }
+    }
+
     pub fn incoming_flow_baseline_channel_size() -> usize {
         256
     }
@@ -402,11 +499,21 @@ impl Router {
     /// Enqueues a locally-originated message to be sent to the network peer
     pub async fn enqueue(&self, msg: WaglayladMessage) -> Result<(), ProtocolError> {
         assert!(msg.payload.is_some(), "Waglayla P2P message should always have a value");
-        match self.outgoing_route.try_send(msg) {
-            Ok(_) => Ok(()),
-            Err(TrySendError::Closed(_)) => Err(ProtocolError::ConnectionClosed),
-            Err(TrySendError::Full(_)) => Err(ProtocolError::OutgoingRouteCapacityReached(self.to_string())),
+        // Messages are sealed and queued under the cipher lock so that they reach the peer in the order of their implicit nonces
+        let mut send_cipher = self.send_cipher.lock();
+        let permit = match self.outgoing_route.try_reserve() {
+            Ok(permit) => permit,
+            Err(TrySendError::Closed(_)) => return Err(ProtocolError::ConnectionClosed),
+            Err(TrySendError::Full(_)) => return Err(ProtocolError::OutgoingRouteCapacityReached(self.to_string())),
+        };
+        match send_cipher.as_mut() {
+            Some(cipher) => permit.send(make_message!(
+                WaglayladMessagePayload::Encrypted,
+                EncryptedMessage { ciphertext: cipher.seal(&msg.encode_to_vec()) }
+            )),
+            None => permit.send(msg),
         }
+        Ok(())
     }
 
     /// Based on the type of the protocol error, tries sending a reject message before shutting down the connection
@@ -1,6 +1,9 @@
 use crate::{
     common::ProtocolError,
-    core::adaptor::ConnectionInitializer,
+    core::{
+        adaptor::ConnectionInitializer,
+        encryption::{NodeIdentity, PinnedPeer},
+    },
     handshake::WaglayladHandshake,
     pb::{self, VersionMessage},
     IncomingRoute, WaglayladMessagePayloadType, Router,
@@ -64,6 +67,11 @@ impl EchoFlow {
             WaglayladMessagePayloadType::IbdChainBlockLocator,
             WaglayladMessagePayloadType::RequestAntipast,
             WaglayladMessagePayloadType::RequestNextPruningPointAndItsAnticoneBlocks,
+            WaglayladMessagePayloadType::CompactBlock,
+            WaglayladMessagePayloadType::RequestCompactBlocks,
+            WaglayladMessagePayloadType::RequestBlockTransactions,
+            WaglayladMessagePayloadType::BlockTransactions,
+            WaglayladMessagePayloadType::StemTransactions,
         ]);
         let mut echo_flow = EchoFlow { router, receiver };
         debug!("EchoFlow, start app-layer receiving loop");
@@ -88,7 +96,10 @@ impl EchoFlow {
 
 /// An example initializer, performing handshake and registering a simple echo flow
 #[derive(Default)]
-pub struct EchoFlowInitializer {}
+pub struct EchoFlowInitializer {
+    identity: Option<NodeIdentity>,
+    pinned_peers: Vec<PinnedPeer>,
+}
 
 fn build_dummy_version_message() -> VersionMessage {
     pb::VersionMessage {
@@ -101,12 +112,25 @@ fn build_dummy_version_message() -> VersionMessage {
         disable_relay_tx: false,
         subnetwork_id: None,
         network: "waglayla-mainnet".to_string(),
+        // Set by the handshake
+        encryption_key: vec![],
+        identity_key: vec![],
     }
 }
 
 impl EchoFlowInitializer {
     pub fn new() -> Self {
-        EchoFlowInitializer {}
+        EchoFlowInitializer { identity: None, pinned_peers: vec![] }
+    }
+
+    /// Builds an initializer authenticating to peers with `identity`
+    pub fn with_identity(identity: NodeIdentity) -> Self {
+        EchoFlowInitializer { identity: Some(identity), pinned_peers: vec![] }
+    }
+
+    /// Requires peers connecting from the address of a pinned peer to authenticate with its key
+    pub fn with_pinned_peers(self, pinned_peers: Vec<PinnedPeer>) -> Self {
+        EchoFlowInitializer { pinned_peers, ..self }
     }
 }
 
@@ -118,7 +142,10 @@ impl ConnectionInitializer for EchoFlowInitializer {
         //
 
         // Build the handshake object and subscribe to handshake messages
-        let mut handshake = WaglayladHandshake::new(&router);
+        let mut handshake = WaglayladHandshake::new(&router, self.identity.as_ref());
+        if let Some(pinned_peer) = self.pinned_peers.iter().find(|pinned_peer| pinned_peer.matches(router.net_address().ip())) {
+            handshake.expect_identity_key(pinned_peer.key);
+        }
 
         // We start the router receive loop only after we registered to handshake routes
         router.start();
@@ -148,19 +175,108 @@ mod tests {
     use std::{str::FromStr, time::Duration};
 
     use super::*;
-    use crate::{Adaptor, Hub};
+    use crate::{
+        dequeue_with_timeout, make_message,
+        pb::{waglaylad_message::Payload, ReadyMessage, VerackMessage},
+        Adaptor, Hub,
+    };
     use waglayla_core::debug;
-    use waglayla_utils::networking::NetAddress;
+    use waglayla_utils::networking::{IpAddress, NetAddress};
+
+    /// Performs the handshake like a peer whose encryption and identity keys were stripped from its version message in transit
+    struct StrippedKeysInitializer;
+
+    #[async_trait]
+    impl ConnectionInitializer for StrippedKeysInitializer {
+        async fn initialize_connection(&self, router: Arc<Router>) -> Result<(), ProtocolError> {
+            let mut version_receiver = router.subscribe(vec![WaglayladMessagePayloadType::Version]);
+            let mut verack_receiver = router.subscribe(vec![WaglayladMessagePayloadType::Verack]);
+            let mut ready_receiver = router.subscribe(vec![WaglayladMessagePayloadType::Ready]);
+            router.start();
+
+            router.enqueue(make_message!(Payload::Version, build_dummy_version_message())).await?;
+            dequeue_with_timeout!(version_receiver, Payload::Version, Duration::from_secs(4))?;
+            router.enqueue(make_message!(Payload::Verack, VerackMessage::default())).await?;
+            dequeue_with_timeout!(verack_receiver, Payload::Verack, Duration::from_secs(4))?;
+
+            EchoFlow::register(router.clone()).await;
+            router.enqueue(make_message!(Payload::Ready, ReadyMessage {})).await?;
+            dequeue_with_timeout!(ready_receiver, Payload::Ready, Duration::from_secs(8))?;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pinned_identity_key() {
+        let identity = NodeIdentity::generate();
+        let pinned_peer = PinnedPeer::new(identity.public_key(), IpAddress::from_str("::1").unwrap());
+        let pinning_initializer = EchoFlowInitializer::new().with_pinned_peers(vec![pinned_peer]);
+        let pinning_adaptor = Adaptor::bidirectional(
+            NetAddress::from_str("[::1]:0").unwrap(),
+            Hub::new(),
+            Arc::new(pinning_initializer),
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+        let plain_adaptor = Adaptor::bidirectional(
+            NetAddress::from_str("[::1]:0").unwrap(),
+            Hub::new(),
+            Arc::new(EchoFlowInitializer::new()),
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+        let pinning_address = pinning_adaptor.local_address().unwrap().to_string();
+        let plain_address = plain_adaptor.local_address().unwrap().to_string();
+
+        // A peer whose keys are stripped falls back to a plaintext session...
+        let stripped = Adaptor::client_only(Hub::new(), Arc::new(StrippedKeysInitializer), Default::default(), Default::default());
+        stripped.connect_peer(plain_address).await.expect("peer connection failed");
+
+        // ...which is rejected when the peer address has a pinned identity key
+        assert!(stripped.connect_peer(pinning_address.clone()).await.is_err());
+
+        // Only the pinned identity key authenticates the peer at the pinned address
+        let impostor = Adaptor::client_only(
+            Hub::new(),
+            Arc::new(EchoFlowInitializer::with_identity(NodeIdentity::generate())),
+            Default::default(),
+            Default::default(),
+        );
+        assert!(impostor.connect_peer(pinning_address.clone()).await.is_err());
+        let authenticated = Adaptor::client_only(
+            Hub::new(),
+            Arc::new(EchoFlowInitializer::with_identity(identity.clone())),
+            Default::default(),
+            Default::default(),
+        );
+        authenticated.connect_peer(pinning_address).await.expect("pinned peer connection failed");
+
+        for adaptor in [stripped, impostor, authenticated, pinning_adaptor, plain_adaptor] {
+            adaptor.close().await;
+        }
+    }
 
     #[tokio::test]
     async fn test_handshake() {
         waglayla_core::log::try_init_logger("debug");
 
         let address1 = NetAddress::from_str("[::1]:50053").unwrap();
-        let adaptor1 = Adaptor::bidirectional(address1, Hub::new(), Arc::new(EchoFlowInitializer::new()), Default::default()).unwrap();
+        let adaptor1 =
+            Adaptor::bidirectional(address1, Hub::new(), Arc::new(EchoFlowInitializer::new()), Default::default(), Default::default())
+                .unwrap();
 
         let address2 = NetAddress::from_str("[::1]:50054").unwrap();
-        let adaptor2 = Adaptor::bidirectional(address2, Hub::new(), Arc::new(EchoFlowInitializer::new()), Default::default()).unwrap();
+        let identity2 = NodeIdentity::generate();
+        let adaptor2 = Adaptor::bidirectional(
+            address2,
+            Hub::new(),
+            Arc::new(EchoFlowInitializer::with_identity(identity2.clone())),
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
 
         // Initiate the connection from `adaptor1` (outbound) to `adaptor2` (inbound)
         let peer2_id = adaptor1
@@ -181,6 +297,12 @@ mod tests {
         assert!(adaptor1_initial_peers[0].is_outbound());
         assert!(!adaptor2_initial_peers[0].is_outbound());
 
+        // Encryption is agreed upon by both sides, and only `adaptor2` authenticates with an identity key
+        assert!(adaptor1_initial_peers[0].is_encrypted());
+        assert!(adaptor2_initial_peers[0].is_encrypted());
+        assert_eq!(adaptor1_initial_peers[0].identity_key(), Some(identity2.public_key()));
+        assert_eq!(adaptor2_initial_peers[0].identity_key(), None);
+
         adaptor1.terminate(peer2_id).await;
         tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 
@@ -1,46 +1,81 @@
 use std::time::Duration;
 
+use crate::core::encryption::{IdentityKey, KeyExchange, MessageCipher, NodeIdentity};
 use crate::pb::{waglaylad_message::Payload, ReadyMessage, VerackMessage, VersionMessage};
 use crate::{common::ProtocolError, dequeue_with_timeout, make_message};
 use crate::{IncomingRoute, WaglayladMessagePayloadType, Router};
+use secp256k1::PublicKey;
 use waglayla_core::debug;
 
 /// Implements the Waglayla peer-to-peer handshake protocol
 pub struct WaglayladHandshake<'a> {
     router: &'a Router,
+    identity: Option<&'a NodeIdentity>,
+    expected_identity_key: Option<IdentityKey>,
+    key_exchange: KeyExchange,
     version_receiver: IncomingRoute,
     verack_receiver: IncomingRoute,
     ready_receiver: IncomingRoute,
 }
 
 impl<'a> WaglayladHandshake<'a> {
-    /// Builds the handshake object and subscribes to handshake messages
-    pub fn new(router: &'a Router) -> Self {
+    /// Builds the handshake object and subscribes to handshake messages. If `identity` is provided, the node
+    /// authenticates with it to peers which support encryption
+    pub fn new(router: &'a Router, identity: Option<&'a NodeIdentity>) -> Self {
         Self {
             router,
+            identity,
+            expected_identity_key: None,
+            key_exchange: KeyExchange::new(),
             version_receiver: router.subscribe(vec![WaglayladMessagePayloadType::Version]),
             verack_receiver: router.subscribe(vec![WaglayladMessagePayloadType::Verack]),
             ready_receiver: router.subscribe(vec![WaglayladMessagePayloadType::Ready]),
         }
     }
 
-    async fn receive_version_flow(router: &Router, version_receiver: &mut IncomingRoute) -> Result<VersionMessage, ProtocolError> {
+    /// Requires the peer to authenticate with `identity_key`, failing the handshake otherwise. Used for peers with a
+    /// pinned identity key, whose keys could otherwise be stripped in transit to fall back to an unauthenticated session
+    pub fn expect_identity_key(&mut self, identity_key: IdentityKey) {
+        self.expected_identity_key = Some(identity_key);
+    }
+
+    async fn receive_version_flow(
+        router: &Router,
+        version_receiver: &mut IncomingRoute,
+        key_exchange: &KeyExchange,
+        identity: Option<&NodeIdentity>,
+    ) -> Result<(VersionMessage, Option<(PublicKey, MessageCipher)>), ProtocolError> {
         debug!("starting receive version flow");
 
         let version_message = dequeue_with_timeout!(version_receiver, Payload::Version, Duration::from_secs(4))?;
         debug!("accepted version message: {version_message:?}");
 
-        let verack_message = make_message!(Payload::Verack, VerackMessage {});
-        router.enqueue(verack_message).await?;
-
-        Ok(version_message)
+        // Peers which do not send an encryption key (including older versions) keep communicating in plaintext
+        let mut verack_message = VerackMessage::default();
+        let session = if version_message.encryption_key.is_empty() {
+            None
+        } else {
+            let peer_key = PublicKey::from_slice(&version_message.encryption_key)
+                .map_err(|_| ProtocolError::Other("invalid encryption key in version message"))?;
+            let (send_cipher, receive_cipher) = key_exchange.derive_ciphers(&peer_key);
+            // The peer starts encrypting once it receives our verack, so the receive cipher must be in place before sending it
+            router.set_receive_cipher(receive_cipher);
+            if let Some(identity) = identity {
+                verack_message.identity_signature = key_exchange.sign_transcript(identity, &peer_key);
+            }
+            Some((peer_key, send_cipher))
+        };
+
+        router.enqueue(make_message!(Payload::Verack, verack_message)).await?;
+
+        Ok((version_message, session))
     }
 
     async fn send_version_flow(
         router: &Router,
         verack_receiver: &mut IncomingRoute,
         version_message: VersionMessage,
-    ) -> Result<(), ProtocolError> {
+    ) -> Result<VerackMessage, ProtocolError> {
         debug!("starting send version flow");
 
         debug!("sending version message: {version_message:?}");
@@ -50,7 +85,7 @@ impl<'a> WaglayladHandshake<'a> {
         let verack_message = dequeue_with_timeout!(verack_receiver, Payload::Verack, Duration::from_secs(4))?;
         debug!("accepted verack_message: {verack_message:?}");
 
-        Ok(())
+        Ok(verack_message)
     }
 
     /// Exchange `Ready` messages with the peer. This is the final step of the handshake protocol and should
@@ -67,14 +102,51 @@ impl<'a> WaglayladHandshake<'a> {
         Ok(())
     }
 
-    /// Performs the handshake with the peer, essentially exchanging version messages
-    pub async fn handshake(&mut self, self_version_message: VersionMessage) -> Result<VersionMessage, ProtocolError> {
+    /// Performs the handshake with the peer, essentially exchanging version messages. If both sides support it,
+    /// all messages following the handshake are encrypted and the identity key of the peer, if any, is authenticated
+    pub async fn handshake(&mut self, mut self_version_message: VersionMessage) -> Result<VersionMessage, ProtocolError> {
+        self_version_message.encryption_key = self.key_exchange.public_key().serialize().to_vec();
+        if let Some(identity) = self.identity {
+            self_version_message.identity_key = identity.public_key().serialize().to_vec();
+        }
+
         // Run both send and receive flows concurrently -- this is critical in order to avoid a handshake deadlock
         let (send_res, recv_res) = tokio::join!(
             Self::send_version_flow(self.router, &mut self.verack_receiver, self_version_message),
-            Self::receive_version_flow(self.router, &mut self.version_receiver)
+            Self::receive_version_flow(self.router, &mut self.version_receiver, &self.key_exchange, self.identity)
         );
-        send_res?;
-        recv_res
+        let peer_verack_message = send_res?;
+        let (peer_version_message, session) = recv_res?;
+
+        let Some((peer_key, send_cipher)) = session else {
+            if !peer_version_message.identity_key.is_empty() {
+                return Err(ProtocolError::Other("peer identity cannot be authenticated without encryption"));
+            }
+            debug!("P2P, peer {} does not support encryption", self.router);
+            self.check_expected_identity_key()?;
+            return Ok(peer_version_message);
+        };
+
+        // The peer set up its receive cipher before sending its verack, so it can decrypt everything we send from now on
+        self.router.set_send_cipher(send_cipher);
+
+        if !peer_version_message.identity_key.is_empty() {
+            let identity_key = IdentityKey::from_slice(&peer_version_message.identity_key)
+                .map_err(|_| ProtocolError::Other("invalid identity key in version message"))?;
+            self.key_exchange.verify_transcript(&identity_key, &peer_key, &peer_verack_message.identity_signature)?;
+            self.router.set_identity_key(identity_key);
+        }
+        self.check_expected_identity_key()?;
+
+        Ok(peer_version_message)
+    }
+
+    fn check_expected_identity_key(&self) -> Result<(), ProtocolError> {
+        match self.expected_identity_key {
+            Some(expected) if self.router.identity_key() != Some(expected) => {
+                Err(ProtocolError::Other("peer did not authenticate with its pinned identity key"))
+            }
+            _ => Ok(()),
+        }
     }
 }
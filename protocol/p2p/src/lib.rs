@@ -12,8 +12,10 @@ mod handshake;
 
 pub use crate::core::adaptor::{Adaptor, ConnectionInitializer};
 pub use crate::core::connection_handler::ConnectionError;
+pub use crate::core::encryption::{IdentityKey, NodeIdentity, PinnedPeer};
 pub use crate::core::hub::Hub;
 pub use crate::core::payload_type::WaglayladMessagePayloadType;
-pub use crate::core::peer::{Peer, PeerKey, PeerProperties};
+pub use crate::core::peer::{MisbehaviourScore, Peer, PeerKey, PeerProperties};
+pub use crate::core::proxy::ProxyConfig;
 pub use crate::core::router::{IncomingRoute, Router, SharedIncomingRoute, BLANK_ROUTE_ID};
 pub use handshake::WaglayladHandshake;
@@ -97,6 +97,7 @@ async fn new_rpc_client(subscription_context: &SubscriptionContext, address: &st
         false,
         Some(500_000),
         Default::default(),
+        None,
     )
     .await
     .unwrap()
@@ -126,6 +127,7 @@ async fn main() {
         false,
         Some(500_000),
         Default::default(),
+        None,
     )
     .await
     .unwrap();
@@ -0,0 +1,412 @@
+//!
+//! RPC authentication. RPC listeners may be configured with a set of accounts, each identified by a token
+//! or a username and password and granting a set of permissions. Clients which do not authenticate are
+//! granted the public permissions of the listener.
+//!
+
+use crate::{api::ops::RpcApiOps, RpcError, RpcResult};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, fmt, str::FromStr};
+
+/// A permission required for calling a group of RPC methods
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum RpcPermission {
+    /// Querying the node state and subscribing to notifications
+    Read = 0,
+    /// Submitting transactions to the mempool
+    SubmitTransaction,
+    /// Requesting block templates and submitting blocks
+    Mining,
+    /// Managing peers and the node itself
+    Admin,
+}
+
+impl RpcPermission {
+    pub const ALL: [RpcPermission; 4] =
+        [RpcPermission::Read, RpcPermission::SubmitTransaction, RpcPermission::Mining, RpcPermission::Admin];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RpcPermission::Read => "read",
+            RpcPermission::SubmitTransaction => "submit-transaction",
+            RpcPermission::Mining => "mining",
+            RpcPermission::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for RpcPermission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RpcPermission {
+    type Err = RpcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RpcPermission::ALL
+            .into_iter()
+            .find(|permission| permission.as_str() == s)
+            .ok_or_else(|| RpcError::General(format!("unknown RPC permission '{s}'")))
+    }
+}
+
+/// A set of [`RpcPermission`]s
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize)]
+pub struct RpcPermissions(u8);
+
+impl RpcPermissions {
+    pub const NONE: RpcPermissions = RpcPermissions(0);
+    pub const ALL: RpcPermissions = RpcPermissions(0b1111);
+
+    pub fn contains(&self, permission: RpcPermission) -> bool {
+        self.0 & (1 << permission as u8) != 0
+    }
+
+    pub fn insert(&mut self, permission: RpcPermission) {
+        self.0 |= 1 << permission as u8;
+    }
+
+    pub fn union(&self, other: RpcPermissions) -> RpcPermissions {
+        RpcPermissions(self.0 | other.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RpcPermission> + '_ {
+        RpcPermission::ALL.into_iter().filter(|permission| self.contains(*permission))
+    }
+
+    /// Returns an error if this set lacks the permission required by `op`
+    pub fn check(&self, op: RpcApiOps) -> RpcResult<()> {
+        match op.required_permission() {
+            Some(permission) if !self.contains(permission) => Err(RpcError::PermissionDenied(op, permission)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<RpcPermission> for RpcPermissions {
+    fn from(permission: RpcPermission) -> Self {
+        RpcPermissions(1 << permission as u8)
+    }
+}
+
+impl FromIterator<RpcPermission> for RpcPermissions {
+    fn from_iter<T: IntoIterator<Item = RpcPermission>>(iter: T) -> Self {
+        let mut permissions = RpcPermissions::NONE;
+        iter.into_iter().for_each(|permission| permissions.insert(permission));
+        permissions
+    }
+}
+
+impl fmt::Debug for RpcPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Formats as a comma-separated list of permissions, `none` for the empty set
+impl fmt::Display for RpcPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("none");
+        }
+        let permissions = self.iter().map(|permission| permission.as_str()).collect::<Vec<_>>();
+        f.write_str(&permissions.join(","))
+    }
+}
+
+/// Parses a comma-separated list of permissions, also accepting `all` and `none`
+impl FromStr for RpcPermissions {
+    type Err = RpcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "all" => Ok(RpcPermissions::ALL),
+            "none" => Ok(RpcPermissions::NONE),
+            s => s.split(',').map(|permission| permission.trim().parse()).collect(),
+        }
+    }
+}
+
+impl Serialize for RpcPermissions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for RpcPermissions {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <String as Deserialize>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Credentials presented by an RPC client
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcCredentials {
+    Token(String),
+    UserPassword { username: String, password: String },
+}
+
+impl RpcCredentials {
+    /// Parses the value of an HTTP `Authorization` header, either `Bearer <token>` or `Basic <base64(username:password)>`
+    pub fn from_authorization_header(value: &str) -> Option<Self> {
+        let (scheme, value) = value.trim().split_once(' ')?;
+        if scheme.eq_ignore_ascii_case("bearer") {
+            Some(RpcCredentials::Token(value.trim().to_string()))
+        } else if scheme.eq_ignore_ascii_case("basic") {
+            let decoded = String::from_utf8(STANDARD.decode(value.trim()).ok()?).ok()?;
+            let (username, password) = decoded.split_once(':')?;
+            Some(RpcCredentials::UserPassword { username: username.to_string(), password: password.to_string() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value of an HTTP `Authorization` header carrying these credentials
+    pub fn to_authorization_header(&self) -> String {
+        match self {
+            RpcCredentials::Token(token) => format!("Bearer {token}"),
+            RpcCredentials::UserPassword { username, password } => {
+                format!("Basic {}", STANDARD.encode(format!("{username}:{password}")))
+            }
+        }
+    }
+
+    fn digest(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        match self {
+            RpcCredentials::Token(token) => {
+                hasher.update([0]);
+                hasher.update(token.as_bytes());
+            }
+            RpcCredentials::UserPassword { username, password } => {
+                hasher.update([1]);
+                hasher.update((username.len() as u64).to_le_bytes());
+                hasher.update(username.as_bytes());
+                hasher.update(password.as_bytes());
+            }
+        }
+        hasher.finalize().into()
+    }
+}
+
+/// Secrets are never written to logs
+impl fmt::Debug for RpcCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpcCredentials::Token(_) => f.write_str("Token(***)"),
+            RpcCredentials::UserPassword { username, .. } => write!(f, "UserPassword({username}:***)"),
+        }
+    }
+}
+
+/// RPC credentials along with the permissions they grant
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RpcAccount {
+    pub credentials: RpcCredentials,
+    pub permissions: RpcPermissions,
+}
+
+impl RpcAccount {
+    pub fn new(credentials: RpcCredentials, permissions: RpcPermissions) -> Self {
+        Self { credentials, permissions }
+    }
+}
+
+/// Formats as `***:PERMISSIONS` or `USERNAME:***:PERMISSIONS`, secrets are never written to logs
+impl fmt::Display for RpcAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.credentials {
+            RpcCredentials::Token(_) => write!(f, "***:{}", self.permissions),
+            RpcCredentials::UserPassword { username, .. } => write!(f, "{}:***:{}", username, self.permissions),
+        }
+    }
+}
+
+/// Parses `TOKEN:PERMISSIONS` or `USERNAME:PASSWORD:PERMISSIONS`. The username ends at the first colon
+/// and the permissions start after the last one, so a password may contain colons but a username may not.
+///
+/// Errors never include the supplied value since it carries a secret.
+impl FromStr for RpcAccount {
+    type Err = RpcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || RpcError::General("invalid RPC account, expected TOKEN:PERMISSIONS or USERNAME:PASSWORD:PERMISSIONS".to_string());
+        let (secret, permissions) = s.rsplit_once(':').ok_or_else(invalid)?;
+        let permissions = permissions.parse().map_err(|_| {
+            RpcError::General(format!(
+                "invalid RPC account permissions, expected all, none or a comma separated list of {}",
+                RpcPermissions::ALL
+            ))
+        })?;
+        let credentials = match secret.split_once(':') {
+            Some((username, password)) if !username.is_empty() && !password.is_empty() => {
+                RpcCredentials::UserPassword { username: username.to_string(), password: password.to_string() }
+            }
+            None if !secret.is_empty() => RpcCredentials::Token(secret.to_string()),
+            _ => return Err(invalid()),
+        };
+        Ok(Self { credentials, permissions })
+    }
+}
+
+/// Authenticates RPC clients against the configured accounts. Only digests of the credentials are retained.
+#[derive(Clone, Debug)]
+pub struct RpcAuthenticator {
+    accounts: HashMap<[u8; 32], RpcPermissions>,
+    public_permissions: RpcPermissions,
+}
+
+impl RpcAuthenticator {
+    /// Creates an authenticator for `accounts`. Unless overridden by `public_permissions`, clients which do
+    /// not authenticate are granted all permissions if no account is configured and none otherwise.
+    pub fn new(accounts: Vec<RpcAccount>, public_permissions: Option<RpcPermissions>) -> Self {
+        let public_permissions =
+            public_permissions.unwrap_or(if accounts.is_empty() { RpcPermissions::ALL } else { RpcPermissions::NONE });
+        let mut map: HashMap<[u8; 32], RpcPermissions> = HashMap::with_capacity(accounts.len());
+        accounts.into_iter().for_each(|account| {
+            let permissions = map.entry(account.credentials.digest()).or_default();
+            *permissions = permissions.union(account.permissions);
+        });
+        Self { accounts: map, public_permissions }
+    }
+
+    /// Returns true if some account is configured
+    pub fn is_enabled(&self) -> bool {
+        !self.accounts.is_empty()
+    }
+
+    /// The permissions of clients which did not authenticate
+    pub fn public_permissions(&self) -> RpcPermissions {
+        self.public_permissions
+    }
+
+    /// Returns the permissions granted to `credentials`, including the public ones, or `None` if they match no account
+    pub fn authenticate(&self, credentials: &RpcCredentials) -> Option<RpcPermissions> {
+        self.accounts.get(&credentials.digest()).map(|permissions| permissions.union(self.public_permissions))
+    }
+}
+
+impl Default for RpcAuthenticator {
+    fn default() -> Self {
+        Self::new(vec![], None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permissions_parsing() {
+        let permissions: RpcPermissions = "read, mining".parse().unwrap();
+        assert!(permissions.contains(RpcPermission::Read));
+        assert!(permissions.contains(RpcPermission::Mining));
+        assert!(!permissions.contains(RpcPermission::SubmitTransaction));
+        assert!(!permissions.contains(RpcPermission::Admin));
+        assert_eq!(permissions.to_string(), "read,mining");
+        assert_eq!("all".parse::<RpcPermissions>().unwrap(), RpcPermissions::ALL);
+        assert_eq!("none".parse::<RpcPermissions>().unwrap(), RpcPermissions::NONE);
+        assert_eq!(RpcPermissions::ALL.to_string().parse::<RpcPermissions>().unwrap(), RpcPermissions::ALL);
+        assert!("read,write".parse::<RpcPermissions>().is_err());
+        assert!("".parse::<RpcPermissions>().is_err());
+
+        let json = serde_json::to_string(&permissions).unwrap();
+        assert_eq!(json, r#""read,mining""#);
+        assert_eq!(serde_json::from_str::<RpcPermissions>(&json).unwrap(), permissions);
+    }
+
+    #[test]
+    fn test_account_parsing() {
+        let account: RpcAccount = "s3cr3t:read,submit-transaction".parse().unwrap();
+        assert_eq!(account.credentials, RpcCredentials::Token("s3cr3t".to_string()));
+        assert_eq!(account.permissions, [RpcPermission::Read, RpcPermission::SubmitTransaction].into_iter().collect());
+        assert_eq!(account.to_string(), "***:read,submit-transaction");
+
+        let account: RpcAccount = "miner:pass:word:mining".parse().unwrap();
+        assert_eq!(
+            account.credentials,
+            RpcCredentials::UserPassword { username: "miner".to_string(), password: "pass:word".to_string() }
+        );
+        assert_eq!(account.permissions, RpcPermission::Mining.into());
+        assert_eq!(account.to_string(), "miner:***:mining");
+
+        // Errors do not echo the secret, even when it is mistaken for the permissions
+        for s in ["s3cr3t", "miner:s3cr3t", "miner:s3cr3t:superuser", "miner::read"] {
+            let err = s.parse::<RpcAccount>().unwrap_err().to_string();
+            assert!(!err.contains("s3cr3t") && !err.contains("superuser"), "{err}");
+        }
+
+        assert!("read".parse::<RpcAccount>().is_err());
+        assert!(":read".parse::<RpcAccount>().is_err());
+        assert!("user::read".parse::<RpcAccount>().is_err());
+        assert!("token:superuser".parse::<RpcAccount>().is_err());
+    }
+
+    #[test]
+    fn test_authentication() {
+        let authenticator = RpcAuthenticator::new(
+            vec!["reader:read".parse().unwrap(), "admin:pass:all".parse().unwrap(), "reader:mining".parse().unwrap()],
+            Some(RpcPermission::Read.into()),
+        );
+        assert!(authenticator.is_enabled());
+        assert_eq!(authenticator.public_permissions(), RpcPermission::Read.into());
+        assert_eq!(
+            authenticator.authenticate(&RpcCredentials::Token("reader".to_string())),
+            Some([RpcPermission::Read, RpcPermission::Mining].into_iter().collect())
+        );
+        assert_eq!(
+            authenticator.authenticate(&RpcCredentials::UserPassword { username: "admin".to_string(), password: "pass".to_string() }),
+            Some(RpcPermissions::ALL)
+        );
+        assert_eq!(
+            authenticator.authenticate(&RpcCredentials::UserPassword { username: "admin".to_string(), password: "wrong".to_string() }),
+            None
+        );
+        // A token is not mistaken for a username and password with the same characters
+        assert_eq!(authenticator.authenticate(&RpcCredentials::Token("admin:pass".to_string())), None);
+
+        assert_eq!(RpcAuthenticator::default().public_permissions(), RpcPermissions::ALL);
+        assert_eq!(RpcAuthenticator::new(vec!["t:read".parse().unwrap()], None).public_permissions(), RpcPermissions::NONE);
+    }
+
+    #[test]
+    fn test_authorization_header() {
+        let credentials = RpcCredentials::UserPassword { username: "user".to_string(), password: "p:w".to_string() };
+        let header = credentials.to_authorization_header();
+        assert_eq!(header, "Basic dXNlcjpwOnc=");
+        assert_eq!(RpcCredentials::from_authorization_header(&header), Some(credentials));
+        assert_eq!(RpcCredentials::from_authorization_header("bearer abc"), Some(RpcCredentials::Token("abc".to_string())));
+        assert_eq!(RpcCredentials::from_authorization_header("Basic !!!"), None);
+        assert_eq!(RpcCredentials::from_authorization_header("Digest abc"), None);
+        assert_eq!(RpcCredentials::from_authorization_header("abc"), None);
+    }
+
+    #[test]
+    fn test_permission_check() {
+        let permissions: RpcPermissions = "read,submit-transaction".parse().unwrap();
+        assert!(permissions.check(RpcApiOps::GetBlock).is_ok());
+        assert!(permissions.check(RpcApiOps::NotifyUtxosChanged).is_ok());
+        assert!(permissions.check(RpcApiOps::SubmitTransaction).is_ok());
+        assert!(permissions.check(RpcApiOps::SubmitBlock).is_err());
+        assert!(permissions.check(RpcApiOps::GetBlockTemplate).is_err());
+        assert!(permissions.check(RpcApiOps::NewBlockTemplateNotification).is_err());
+        assert!(permissions.check(RpcApiOps::Shutdown).is_err());
+        assert!(permissions.check(RpcApiOps::Ban).is_err());
+        assert!(permissions.check(RpcApiOps::AddPeer).is_err());
+        assert!(RpcPermissions::NONE.check(RpcApiOps::Authenticate).is_ok());
+        assert!(RpcPermissions::NONE.check(RpcApiOps::Ping).is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//!
+//! Rate limits and resource quotas of RPC clients.
+//!
+//! Every RPC method has a cost (see [`RpcApiOps::cost`]) which is charged against token buckets refilling at a
+//! configured rate, one per connection and one shared by all connections of a same IP address. Calls exceeding
+//! the limits are refused with a [`RpcError::RateLimitExceeded`] indicating when the client may retry.
+//!
+
+use crate::{api::ops::RpcApiOps, error::RpcError, RpcResult};
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    net::IpAddr,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use workflow_core::time::Instant;
+
+/// A rate of `rate` cost units per second with bursts of up to `burst` cost units
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RpcRateLimit {
+    pub rate: u32,
+    pub burst: u32,
+}
+
+impl RpcRateLimit {
+    pub fn new(rate: u32, burst: u32) -> Self {
+        Self { rate, burst }
+    }
+}
+
+impl Display for RpcRateLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.rate, self.burst)
+    }
+}
+
+impl FromStr for RpcRateLimit {
+    type Err = RpcError;
+
+    /// Parses `RATE[:BURST]`, the burst defaulting to the rate
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RpcError::General(format!("invalid RPC rate limit '{s}', expected RATE[:BURST]"));
+        let (rate, burst) = s.split_once(':').unwrap_or((s, s));
+        let rate = rate.trim().parse().map_err(|_| invalid())?;
+        let burst = burst.trim().parse().map_err(|_| invalid())?;
+        if rate == 0 || burst == 0 {
+            return Err(invalid());
+        }
+        Ok(Self { rate, burst })
+    }
+}
+
+/// A cost overriding the default cost of an RPC method
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RpcMethodCost {
+    pub op: RpcApiOps,
+    pub cost: u32,
+}
+
+impl Display for RpcMethodCost {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.op.as_str(), self.cost)
+    }
+}
+
+impl FromStr for RpcMethodCost {
+    type Err = RpcError;
+
+    /// Parses `METHOD:COST` where the method name may be written in any case, eg. `GetUtxosByAddresses:100`
+    /// or `get-utxos-by-addresses:100`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RpcError::General(format!("invalid RPC method cost '{s}', expected METHOD:COST"));
+        let (method, cost) = s.rsplit_once(':').ok_or_else(invalid)?;
+        let normalize = |name: &str| name.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_lowercase();
+        let method = normalize(method);
+        let op = RpcApiOps::list()
+            .into_iter()
+            .find(|op| normalize(op.as_str()) == method)
+            .ok_or_else(|| RpcError::General(format!("unknown RPC method in '{s}'")))?;
+        Ok(Self { op, cost: cost.trim().parse().map_err(|_| invalid())? })
+    }
+}
+
+/// The rate limits and quotas applying to the clients of an RPC server
+#[derive(Clone, Debug, Default)]
+pub struct RpcLimits {
+    /// Rate limit of every connection
+    pub connection_rate: Option<RpcRateLimit>,
+
+    /// Rate limit shared by the connections of an IP address
+    pub ip_rate: Option<RpcRateLimit>,
+
+    /// Maximum number of simultaneous connections from an IP address
+    pub max_ip_connections: Option<usize>,
+
+    /// Costs overriding the default method costs
+    pub costs: HashMap<RpcApiOps, u32>,
+}
+
+impl RpcLimits {
+    pub fn with_costs(mut self, costs: impl IntoIterator<Item = RpcMethodCost>) -> Self {
+        self.costs.extend(costs.into_iter().map(|RpcMethodCost { op, cost }| (op, cost)));
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.connection_rate.is_some() || self.ip_rate.is_some() || self.max_ip_connections.is_some()
+    }
+
+    pub fn cost(&self, op: RpcApiOps) -> u32 {
+        self.costs.get(&op).copied().unwrap_or_else(|| op.cost())
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    limit: RpcRateLimit,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: RpcRateLimit) -> Self {
+        Self { limit, tokens: limit.burst as f64, last_refill: Instant::now() }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.rate as f64).min(self.limit.burst as f64);
+        self.last_refill = now;
+    }
+
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.limit.burst as f64
+    }
+
+    /// Returns the time to wait until `cost` tokens are available. Costs exceeding the burst only require
+    /// the bucket to be full.
+    fn wait_time(&mut self, cost: u32, now: Instant) -> Option<Duration> {
+        self.refill(now);
+        let cost = cost.min(self.limit.burst) as f64;
+        (self.tokens < cost).then(|| Duration::from_secs_f64((cost - self.tokens) / self.limit.rate as f64))
+    }
+
+    fn consume(&mut self, cost: u32) {
+        self.tokens = (self.tokens - cost.min(self.limit.burst) as f64).max(0.0);
+    }
+}
+
+#[derive(Debug)]
+struct IpEntry {
+    connections: usize,
+    bucket: Option<TokenBucket>,
+}
+
+/// Enforces the [`RpcLimits`] of an RPC server, tracking the resources used by every IP address
+#[derive(Debug, Default)]
+pub struct RpcLimiter {
+    limits: RpcLimits,
+    ips: Mutex<HashMap<IpAddr, IpEntry>>,
+}
+
+impl RpcLimiter {
+    pub fn new(limits: RpcLimits) -> Self {
+        Self { limits, ips: Default::default() }
+    }
+
+    pub fn limits(&self) -> &RpcLimits {
+        &self.limits
+    }
+
+    /// Registers a new connection from `ip`, failing if the IP address has reached its connection quota
+    pub fn register(self: &Arc<Self>, ip: IpAddr) -> RpcResult<RpcConnectionLimiter> {
+        let mut ips = self.ips.lock().unwrap();
+        // Forget the IP addresses without connections which are no longer rate limited
+        let now = Instant::now();
+        ips.retain(|_, entry| entry.connections > 0 || entry.bucket.as_mut().is_some_and(|bucket| !bucket.is_full(now)));
+
+        let entry = ips.entry(ip).or_insert_with(|| IpEntry { connections: 0, bucket: self.limits.ip_rate.map(TokenBucket::new) });
+        if self.limits.max_ip_connections.is_some_and(|max| entry.connections >= max) {
+            return Err(RpcError::ConnectionQuotaExceeded(ip));
+        }
+        entry.connections += 1;
+        Ok(RpcConnectionLimiter {
+            limiter: self.clone(),
+            ip,
+            bucket: self.limits.connection_rate.map(|limit| Mutex::new(TokenBucket::new(limit))),
+        })
+    }
+
+    fn unregister(&self, ip: IpAddr) {
+        if let Some(entry) = self.ips.lock().unwrap().get_mut(&ip) {
+            entry.connections = entry.connections.saturating_sub(1);
+        }
+    }
+}
+
+/// The structured form of a [`RpcError::RateLimitExceeded`], for transports which cannot carry a [`RpcError`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct RpcRateLimitExceeded {
+    pub op: RpcApiOps,
+    pub retry_after_ms: u64,
+}
+
+impl From<RpcRateLimitExceeded> for RpcError {
+    fn from(value: RpcRateLimitExceeded) -> Self {
+        RpcError::RateLimitExceeded(value.op, value.retry_after_ms)
+    }
+}
+
+/// Charges the calls of a connection against its own rate limit and the one of its IP address. The connection
+/// is released from the quota of its IP address when dropped.
+#[derive(Debug)]
+pub struct RpcConnectionLimiter {
+    limiter: Arc<RpcLimiter>,
+    ip: IpAddr,
+    bucket: Option<Mutex<TokenBucket>>,
+}
+
+impl RpcConnectionLimiter {
+    /// Charges the cost of calling `op`, failing with a retry-after hint if it exceeds a rate limit
+    pub fn check(&self, op: RpcApiOps) -> RpcResult<()> {
+        let cost = self.limiter.limits.cost(op);
+        if cost == 0 {
+            return Ok(());
+        }
+        let now = Instant::now();
+        let mut bucket = self.bucket.as_ref().map(|bucket| bucket.lock().unwrap());
+        let mut ips = self.limiter.ips.lock().unwrap();
+        let mut ip_bucket = ips.get_mut(&self.ip).and_then(|entry| entry.bucket.as_mut());
+
+        // The call is charged only if both buckets hold enough tokens
+        let wait_time = [bucket.as_deref_mut(), ip_bucket.as_deref_mut()]
+            .into_iter()
+            .flatten()
+            .filter_map(|bucket| bucket.wait_time(cost, now))
+            .max();
+        if let Some(wait_time) = wait_time {
+            return Err(RpcError::RateLimitExceeded(op, wait_time.as_millis().max(1) as u64));
+        }
+        [bucket.as_deref_mut(), ip_bucket].into_iter().flatten().for_each(|bucket| bucket.consume(cost));
+        Ok(())
+    }
+
+    /// Returns true while the connection rate limit has not refilled the cost of the past calls
+    pub fn is_refilling(&self) -> bool {
+        self.bucket.as_ref().is_some_and(|bucket| !bucket.lock().unwrap().is_full(Instant::now()))
+    }
+}
+
+impl Drop for RpcConnectionLimiter {
+    fn drop(&mut self) {
+        self.limiter.unregister(self.ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_parsing() {
+        assert_eq!("10".parse::<RpcRateLimit>().unwrap(), RpcRateLimit::new(10, 10));
+        assert_eq!("10:50".parse::<RpcRateLimit>().unwrap(), RpcRateLimit::new(10, 50));
+        assert!("0".parse::<RpcRateLimit>().is_err());
+        assert!("10:".parse::<RpcRateLimit>().is_err());
+
+        let cost: RpcMethodCost = "get-utxos-by-addresses:100".parse().unwrap();
+        assert_eq!(cost, RpcMethodCost { op: RpcApiOps::GetUtxosByAddresses, cost: 100 });
+        assert_eq!(cost.to_string().parse::<RpcMethodCost>().unwrap(), cost);
+        assert_eq!("getBlocks:0".parse::<RpcMethodCost>().unwrap(), RpcMethodCost { op: RpcApiOps::GetBlocks, cost: 0 });
+        assert!("getNothing:1".parse::<RpcMethodCost>().is_err());
+        assert!("getBlocks".parse::<RpcMethodCost>().is_err());
+    }
+
+    #[test]
+    fn test_rate_limits() {
+        let limits = RpcLimits {
+            connection_rate: Some(RpcRateLimit::new(1, 20)),
+            ip_rate: Some(RpcRateLimit::new(1, 30)),
+            max_ip_connections: Some(2),
+            ..Default::default()
+        }
+        .with_costs([RpcMethodCost { op: RpcApiOps::GetInfo, cost: 0 }]);
+        let limiter = Arc::new(RpcLimiter::new(limits));
+        let ip = IpAddr::from([192, 168, 0, 1]);
+
+        // Each connection is limited by its own bucket
+        let first = limiter.register(ip).unwrap();
+        assert!(!first.is_refilling());
+        (0..20).for_each(|_| first.check(RpcApiOps::GetSink).unwrap());
+        assert!(first.is_refilling());
+        let err = first.check(RpcApiOps::GetSink).unwrap_err();
+        assert!(matches!(err, RpcError::RateLimitExceeded(RpcApiOps::GetSink, retry_after) if retry_after > 0 && retry_after <= 1000));
+
+        // Free methods are never limited
+        first.check(RpcApiOps::GetInfo).unwrap();
+
+        // Connections of a same IP address share its bucket, and methods costlier than a bucket burst are
+        // allowed when the buckets are full
+        let second = limiter.register(ip).unwrap();
+        (0..10).for_each(|_| second.check(RpcApiOps::GetSink).unwrap());
+        assert!(matches!(second.check(RpcApiOps::GetSink), Err(RpcError::RateLimitExceeded(_, _))));
+        let other = limiter.register(IpAddr::from([192, 168, 0, 2])).unwrap();
+        other.check(RpcApiOps::GetUtxosByAddresses).unwrap();
+        assert!(
+            matches!(other.check(RpcApiOps::GetUtxosByAddresses), Err(RpcError::RateLimitExceeded(_, retry_after)) if retry_after > 15000)
+        );
+
+        // IP addresses are limited in connections
+        assert!(matches!(limiter.register(ip), Err(RpcError::ConnectionQuotaExceeded(_))));
+        drop(second);
+        let third = limiter.register(ip).unwrap();
+
+        // The rate limit of an IP address survives the reconnection of its clients
+        drop(first);
+        drop(third);
+        let fourth = limiter.register(ip).unwrap();
+        assert!(matches!(fourth.check(RpcApiOps::GetSink), Err(RpcError::RateLimitExceeded(_, _))));
+    }
+}
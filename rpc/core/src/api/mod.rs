@@ -1,4 +1,6 @@
+pub mod auth;
 pub mod ctl;
+pub mod limits;
 pub mod notifications;
 pub mod ops;
 pub mod rpc;
@@ -44,6 +44,9 @@ pub enum Notification {
 
     #[display(fmt = "NewBlockTemplate notification")]
     NewBlockTemplate(NewBlockTemplateNotification),
+
+    #[display(fmt = "TransactionsEvicted notification: {} evicted transactions replaced by {}", "_0.evicted_transaction_ids.len()", "_0.replacement_transaction_id")]
+    TransactionsEvicted(TransactionsEvictedNotification),
 }
 }
 
@@ -60,6 +63,7 @@ impl Notification {
             Notification::VirtualDaaScoreChanged(v) => to_value(&v),
             Notification::SinkBlueScoreChanged(v) => to_value(&v),
             Notification::VirtualChainChanged(v) => to_value(&v),
+            Notification::TransactionsEvicted(v) => to_value(&v),
         }
     }
 }
@@ -1,3 +1,4 @@
+use crate::api::auth::RpcPermission;
 use borsh::{BorshDeserialize, BorshSerialize};
 use waglayla_notify::events::EventType;
 use serde::{Deserialize, Serialize};
@@ -13,7 +14,7 @@ use workflow_core::enums::Describe;
 /// or using Serde attributes. This applies only to RPC infrastructure that uses internal
 /// data structures and does not affect gRPC. gRPC should issue and handle its
 /// own versioning.
-pub const RPC_API_VERSION: [u16; 4] = [0, 1, 0, 0];
+pub const RPC_API_VERSION: [u16; 4] = [0, 2, 0, 0];
 
 #[derive(Describe, Clone, Copy, Debug, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -86,6 +87,24 @@ pub enum RpcApiOps {
     GetCoinSupply,
     /// Get DAA Score timestamp estimate
     GetDaaScoreTimestampEstimate,
+    /// Get realtime feerate estimations based on the current mempool state
+    GetFeeEstimate,
+    /// Submits a transaction replacing a mempool transaction it double spends by paying a higher fee rate
+    SubmitTransactionReplacement,
+    /// Get a confirmed transaction along with its including and accepting blocks (requires the node to run with --txindex)
+    GetTransaction,
+    /// Get the transactions paying to or spending from a set of addresses (requires the node to run with --addressindex)
+    GetTransactionsByAddresses,
+    /// Get the first chain block reaching a DAA score (requires the node to run with --chainindex)
+    GetChainBlockByDaaScore,
+    /// Get the first chain block reaching a timestamp (requires the node to run with --chainindex)
+    GetChainBlockByTimestamp,
+    /// Get the headers of the chain blocks within a DAA score range (requires the node to run with --chainindex)
+    GetChainBlockHeaders,
+    /// Get the list of banned IP addresses and subnets
+    GetBannedPeers,
+    /// Authenticate the connection, granting it the permissions of the presented credentials
+    Authenticate,
 
     // Subscription commands for starting/stopping notifications
     NotifyBlockAdded,
@@ -97,6 +116,7 @@ pub enum RpcApiOps {
     NotifyVirtualDaaScoreChanged,
     NotifyVirtualChainChanged,
     NotifySinkBlueScoreChanged,
+    NotifyTransactionsEvicted,
 
     // ~
     Subscribe,
@@ -114,6 +134,7 @@ pub enum RpcApiOps {
     VirtualDaaScoreChangedNotification,
     PruningPointUtxoSetOverrideNotification,
     NewBlockTemplateNotification,
+    TransactionsEvictedNotification,
 }
 
 impl RpcApiOps {
@@ -129,10 +150,48 @@ impl RpcApiOps {
                 | RpcApiOps::NotifyFinalityConflictResolved
                 | RpcApiOps::NotifySinkBlueScoreChanged
                 | RpcApiOps::NotifyVirtualDaaScoreChanged
+                | RpcApiOps::NotifyTransactionsEvicted
                 | RpcApiOps::Subscribe
                 | RpcApiOps::Unsubscribe
         )
     }
+
+    /// Returns the permission a client needs for calling this method or subscribing to this notification, if any
+    pub fn required_permission(&self) -> Option<RpcPermission> {
+        match self {
+            RpcApiOps::Authenticate => None,
+            RpcApiOps::SubmitTransaction | RpcApiOps::SubmitTransactionReplacement => Some(RpcPermission::SubmitTransaction),
+            RpcApiOps::SubmitBlock
+            | RpcApiOps::GetBlockTemplate
+            | RpcApiOps::NotifyNewBlockTemplate
+            | RpcApiOps::NewBlockTemplateNotification => Some(RpcPermission::Mining),
+            RpcApiOps::AddPeer
+            | RpcApiOps::Ban
+            | RpcApiOps::Unban
+            | RpcApiOps::GetBannedPeers
+            | RpcApiOps::ResolveFinalityConflict
+            | RpcApiOps::Shutdown => Some(RpcPermission::Admin),
+            _ => Some(RpcPermission::Read),
+        }
+    }
+
+    /// Returns the default cost of calling this method, charged against the rate limits of the client.
+    /// Methods scanning the UTXO set, the indexes or the DAG cost more than simple state queries.
+    pub fn cost(&self) -> u32 {
+        match self {
+            RpcApiOps::GetUtxosByAddresses | RpcApiOps::GetTransactionsByAddresses => 50,
+            RpcApiOps::GetVirtualChainFromBlock | RpcApiOps::GetBlocks => 20,
+            RpcApiOps::GetMempoolEntries
+            | RpcApiOps::GetMempoolEntriesByAddresses
+            | RpcApiOps::GetBalancesByAddresses
+            | RpcApiOps::GetHeaders
+            | RpcApiOps::GetChainBlockHeaders
+            | RpcApiOps::EstimateNetworkHashesPerSecond
+            | RpcApiOps::Authenticate => 10,
+            RpcApiOps::GetTransaction | RpcApiOps::GetBlock => 5,
+            _ => 1,
+        }
+    }
 }
 
 impl From<RpcApiOps> for u32 {
@@ -155,6 +214,7 @@ impl From<EventType> for RpcApiOps {
             EventType::VirtualDaaScoreChanged => RpcApiOps::VirtualDaaScoreChangedNotification,
             EventType::PruningPointUtxoSetOverride => RpcApiOps::PruningPointUtxoSetOverrideNotification,
             EventType::NewBlockTemplate => RpcApiOps::NewBlockTemplateNotification,
+            EventType::TransactionsEvicted => RpcApiOps::TransactionsEvictedNotification,
         }
     }
 }
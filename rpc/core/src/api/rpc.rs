@@ -12,6 +12,12 @@ use std::sync::Arc;
 
 pub const MAX_SAFE_WINDOW_SIZE: u32 = 10_000;
 
+/// Maximum number of transactions returned by a single `GetTransactionsByAddresses` call
+pub const MAX_ADDRESS_TRANSACTIONS_PAGE_SIZE: u32 = 10_000;
+
+/// Maximum number of headers returned by a single `GetChainBlockHeaders` call
+pub const MAX_CHAIN_BLOCK_HEADERS_PAGE_SIZE: u32 = 1_000;
+
 /// Client RPC Api
 ///
 /// The [`RpcApi`] trait defines RPC calls taking a request message as unique parameter.
@@ -132,6 +138,22 @@ pub trait RpcApi: Sync + Send + AnySync {
     }
     async fn submit_transaction_call(&self, request: SubmitTransactionRequest) -> RpcResult<SubmitTransactionResponse>;
 
+    /// Submits a transaction replacing the mempool transactions it double spends by paying a higher fee rate,
+    /// the replacement being mandatory or optional depending on `mode`.
+    ///
+    /// Returns the id of the submitted transaction along with the replaced transaction, if any.
+    async fn submit_transaction_replacement(
+        &self,
+        transaction: RpcTransaction,
+        mode: RpcReplacementMode,
+    ) -> RpcResult<SubmitTransactionReplacementResponse> {
+        self.submit_transaction_replacement_call(SubmitTransactionReplacementRequest { transaction, mode }).await
+    }
+    async fn submit_transaction_replacement_call(
+        &self,
+        request: SubmitTransactionReplacementRequest,
+    ) -> RpcResult<SubmitTransactionReplacementResponse>;
+
     /// Requests information about a specific block.
     async fn get_block(&self, hash: RpcHash, include_transactions: bool) -> RpcResult<RpcBlock> {
         Ok(self.get_block_call(GetBlockRequest::new(hash, include_transactions)).await?.block)
@@ -237,15 +259,15 @@ pub trait RpcApi: Sync + Send + AnySync {
     }
     async fn get_sink_blue_score_call(&self, request: GetSinkBlueScoreRequest) -> RpcResult<GetSinkBlueScoreResponse>;
 
-    /// Bans the given ip.
-    async fn ban(&self, ip: RpcIpAddress) -> RpcResult<()> {
-        self.ban_call(BanRequest::new(ip)).await?;
+    /// Bans the given ip or subnet for `duration` seconds, or for the node's configured ban duration if not specified.
+    async fn ban(&self, ip: RpcIpSubnet, duration: Option<u64>, reason: Option<String>) -> RpcResult<()> {
+        self.ban_call(BanRequest::new(ip, duration, reason)).await?;
         Ok(())
     }
     async fn ban_call(&self, request: BanRequest) -> RpcResult<BanResponse>;
 
-    /// Unbans the given ip.
-    async fn unban(&self, ip: RpcIpAddress) -> RpcResult<()> {
+    /// Unbans the given ip or subnet.
+    async fn unban(&self, ip: RpcIpSubnet) -> RpcResult<()> {
         self.unban_call(UnbanRequest::new(ip)).await?;
         Ok(())
     }
@@ -304,6 +326,77 @@ pub trait RpcApi: Sync + Send + AnySync {
         request: GetDaaScoreTimestampEstimateRequest,
     ) -> RpcResult<GetDaaScoreTimestampEstimateResponse>;
 
+    /// Returns feerate estimations for the priority, normal and low inclusion buckets.
+    async fn get_fee_estimate(&self) -> RpcResult<RpcFeeEstimate> {
+        Ok(self.get_fee_estimate_call(GetFeeEstimateRequest {}).await?.estimate)
+    }
+    async fn get_fee_estimate_call(&self, request: GetFeeEstimateRequest) -> RpcResult<GetFeeEstimateResponse>;
+
+    /// Requests a confirmed transaction by id, along with the blocks including it and the chain block accepting it.
+    ///
+    /// The node must run with the `--txindex` argument.
+    async fn get_transaction(&self, transaction_id: RpcTransactionId) -> RpcResult<GetTransactionResponse> {
+        self.get_transaction_call(GetTransactionRequest::new(transaction_id)).await
+    }
+    async fn get_transaction_call(&self, request: GetTransactionRequest) -> RpcResult<GetTransactionResponse>;
+
+    /// Requests a page of the transactions paying to or spending from the given addresses, starting at `from_daa_score`.
+    ///
+    /// The node must run with the `--addressindex` argument.
+    async fn get_transactions_by_addresses(
+        &self,
+        addresses: Vec<RpcAddress>,
+        from_daa_score: u64,
+        limit: u32,
+    ) -> RpcResult<GetTransactionsByAddressesResponse> {
+        self.get_transactions_by_addresses_call(GetTransactionsByAddressesRequest::new(addresses, from_daa_score, limit)).await
+    }
+    async fn get_transactions_by_addresses_call(
+        &self,
+        request: GetTransactionsByAddressesRequest,
+    ) -> RpcResult<GetTransactionsByAddressesResponse>;
+
+    /// Requests the header of the first chain block with a DAA score of at least `daa_score`.
+    ///
+    /// The node must run with the `--chainindex` argument.
+    async fn get_chain_block_by_daa_score(&self, daa_score: u64) -> RpcResult<GetChainBlockByDaaScoreResponse> {
+        self.get_chain_block_by_daa_score_call(GetChainBlockByDaaScoreRequest::new(daa_score)).await
+    }
+    async fn get_chain_block_by_daa_score_call(
+        &self,
+        request: GetChainBlockByDaaScoreRequest,
+    ) -> RpcResult<GetChainBlockByDaaScoreResponse>;
+
+    /// Requests the header of the first chain block, in selected chain order, with a timestamp of at least `timestamp`.
+    ///
+    /// The node must run with the `--chainindex` argument.
+    async fn get_chain_block_by_timestamp(&self, timestamp: u64) -> RpcResult<GetChainBlockByTimestampResponse> {
+        self.get_chain_block_by_timestamp_call(GetChainBlockByTimestampRequest::new(timestamp)).await
+    }
+    async fn get_chain_block_by_timestamp_call(
+        &self,
+        request: GetChainBlockByTimestampRequest,
+    ) -> RpcResult<GetChainBlockByTimestampResponse>;
+
+    /// Requests the headers of the chain blocks with a DAA score within `from_daa_score..=to_daa_score`.
+    ///
+    /// The node must run with the `--chainindex` argument.
+    async fn get_chain_block_headers(
+        &self,
+        from_daa_score: u64,
+        to_daa_score: u64,
+        limit: u32,
+    ) -> RpcResult<GetChainBlockHeadersResponse> {
+        self.get_chain_block_headers_call(GetChainBlockHeadersRequest::new(from_daa_score, to_daa_score, limit)).await
+    }
+    async fn get_chain_block_headers_call(&self, request: GetChainBlockHeadersRequest) -> RpcResult<GetChainBlockHeadersResponse>;
+
+    /// Requests the list of banned IP addresses and subnets along with their ban expiry and reason.
+    async fn get_banned_peers(&self) -> RpcResult<Vec<RpcBannedPeer>> {
+        Ok(self.get_banned_peers_call(GetBannedPeersRequest {}).await?.banned_peers)
+    }
+    async fn get_banned_peers_call(&self, request: GetBannedPeersRequest) -> RpcResult<GetBannedPeersResponse>;
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
 
@@ -1,7 +1,8 @@
 use crate::{
     convert::utxo::utxo_set_into_rpc, BlockAddedNotification, FinalityConflictNotification, FinalityConflictResolvedNotification,
     NewBlockTemplateNotification, Notification, PruningPointUtxoSetOverrideNotification, RpcAcceptedTransactionIds,
-    SinkBlueScoreChangedNotification, UtxosChangedNotification, VirtualChainChangedNotification, VirtualDaaScoreChangedNotification,
+    SinkBlueScoreChangedNotification, TransactionsEvictedNotification, UtxosChangedNotification, VirtualChainChangedNotification,
+    VirtualDaaScoreChangedNotification,
 };
 use waglayla_consensus_notify::notification as consensus_notify;
 use waglayla_index_core::notification as index_notify;
@@ -29,6 +30,7 @@ impl From<&consensus_notify::Notification> for Notification {
             consensus_notify::Notification::VirtualDaaScoreChanged(msg) => Notification::VirtualDaaScoreChanged(msg.into()),
             consensus_notify::Notification::PruningPointUtxoSetOverride(msg) => Notification::PruningPointUtxoSetOverride(msg.into()),
             consensus_notify::Notification::NewBlockTemplate(msg) => Notification::NewBlockTemplate(msg.into()),
+            consensus_notify::Notification::TransactionsEvicted(msg) => Notification::TransactionsEvicted(msg.into()),
         }
     }
 }
@@ -110,6 +112,15 @@ impl From<&consensus_notify::NewBlockTemplateNotification> for NewBlockTemplateN
     }
 }
 
+impl From<&consensus_notify::TransactionsEvictedNotification> for TransactionsEvictedNotification {
+    fn from(item: &consensus_notify::TransactionsEvictedNotification) -> Self {
+        Self {
+            replacement_transaction_id: item.replacement_transaction_id,
+            evicted_transaction_ids: item.evicted_transaction_ids.clone(),
+        }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // index to rpc_core
 // ----------------------------------------------------------------------------
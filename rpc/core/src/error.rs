@@ -7,9 +7,12 @@ use workflow_core::channel::ChannelError;
 use pyo3::{exceptions::PyException, PyErr};
 
 use waglayla_consensus_core::{subnets::SubnetworkConversionError, tx::TransactionId};
-use waglayla_utils::networking::IpAddress;
+use waglayla_utils::networking::IpSubnet;
 
-use crate::{api::ctl::RpcState, RpcHash, RpcTransactionId, SubmitBlockRejectReason};
+use crate::{
+    api::{auth::RpcPermission, ctl::RpcState, ops::RpcApiOps},
+    RpcHash, RpcTransactionId, SubmitBlockRejectReason,
+};
 
 #[derive(Clone, Debug, Error)]
 pub enum RpcError {
@@ -31,6 +34,9 @@ pub enum RpcError {
     #[error("Ip address parsing error {0}")]
     ParseIpAddressError(#[from] AddrParseError),
 
+    #[error("Ip subnet parsing error {0}")]
+    ParseIpSubnetError(#[from] ipnet::AddrParseError),
+
     #[error("Wrong rpc api version format")]
     RpcApiVersionFormatError,
 
@@ -64,6 +70,15 @@ pub enum RpcError {
     #[error("Method unavailable. Run the node with the --utxoindex argument.")]
     NoUtxoIndex,
 
+    #[error("Method unavailable. Run the node with the --txindex argument.")]
+    NoTxIndex,
+
+    #[error("Method unavailable. Run the node with the --addressindex argument.")]
+    NoAddressIndex,
+
+    #[error("Method unavailable. Run the node with the --chainindex argument.")]
+    NoChainIndex,
+
     #[error("Method unavailable. No connection manager is currently available.")]
     NoConnectionManager,
 
@@ -76,11 +91,32 @@ pub enum RpcError {
     #[error("Method unavailable in safe mode. Run the node with --unsaferpc argument.")]
     UnavailableInSafeMode,
 
-    #[error("Cannot ban IP {0} because it has some permanent connection.")]
-    IpHasPermanentConnection(IpAddress),
+    #[error("Cannot ban {0} because it has some permanent connection.")]
+    IpHasPermanentConnection(IpSubnet),
+
+    #[error("Cannot ban {0} because it is whitelisted.")]
+    IpIsWhitelisted(IpSubnet),
+
+    #[error("Cannot ban subnet {0}, its prefix must be at least /{1}.")]
+    SubnetTooBroad(IpSubnet, u8),
 
     #[error("IP {0} is not registered as banned.")]
-    IpIsNotBanned(IpAddress),
+    IpIsNotBanned(IpSubnet),
+
+    #[error("Method {0:?} requires the '{1}' permission.")]
+    PermissionDenied(RpcApiOps, RpcPermission),
+
+    #[error("RPC authentication failed.")]
+    AuthenticationFailed,
+
+    #[error("RPC credentials are only accepted over TLS.")]
+    CredentialsRequireTls,
+
+    #[error("Method {0:?} exceeds the RPC rate limit, retry after {1} ms.")]
+    RateLimitExceeded(RpcApiOps, u64),
+
+    #[error("Too many RPC connections from {0}.")]
+    ConnectionQuotaExceeded(std::net::IpAddr),
 
     #[error("Block was not submitted: {0}")]
     SubmitBlockError(SubmitBlockRejectReason),
@@ -134,6 +170,16 @@ pub enum RpcError {
     ConsensusClient(#[from] waglayla_consensus_client::error::Error),
 }
 
+impl RpcError {
+    /// Returns the delay to wait for before retrying the call, in milliseconds, if it exceeded the RPC rate limit
+    pub fn retry_after_ms(&self) -> Option<u64> {
+        match self {
+            RpcError::RateLimitExceeded(_, retry_after_ms) => Some(*retry_after_ms),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(not(target_family = "wasm"))]
 impl From<RpcError> for PyErr {
     fn from(err: RpcError) -> PyErr {
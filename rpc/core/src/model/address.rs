@@ -1,4 +1,4 @@
-use crate::{RpcTransactionOutpoint, RpcUtxoEntry};
+use crate::{RpcHash, RpcTransactionId, RpcTransactionOutpoint, RpcUtxoEntry};
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
@@ -55,3 +55,17 @@ pub struct RpcBalancesByAddressesEntry {
     /// Balance of `address` if available
     pub balance: Option<u64>,
 }
+
+/// Represents a transaction paying to, or spending from, an address, returned by the `GetTransactionsByAddresses` RPC.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAddressTransaction {
+    pub address: RpcAddress,
+    pub transaction_id: RpcTransactionId,
+    pub accepting_block_hash: RpcHash,
+    pub accepting_block_daa_score: u64,
+    /// Outputs of the transaction paying to `address`
+    pub received: Vec<RpcUtxosByAddressesEntry>,
+    /// Previous outputs of `address` spent by the inputs of the transaction
+    pub spent: Vec<RpcUtxosByAddressesEntry>,
+}
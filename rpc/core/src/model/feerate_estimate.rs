@@ -0,0 +1,86 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcFeerateBucket {
+    /// The fee/mass ratio estimated to be required for inclusion time <= estimated_seconds
+    pub feerate: f64,
+
+    /// The estimated inclusion time for a transaction with fee/mass = feerate
+    pub estimated_seconds: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcFeeEstimate {
+    /// *Top-priority* feerate bucket. Provides an estimation of the feerate required for sub-second DAG inclusion.
+    pub priority_bucket: RpcFeerateBucket,
+
+    /// A vector of *normal* priority feerate values. The first value of this vector is guaranteed to exist and
+    /// provide an estimation for sub-*minute* DAG inclusion. All other values will have shorter estimation
+    /// times than all `low_bucket` values. Therefore by chaining `[priority] | normal | low` and interpolating
+    /// between them, one can compose a complete feerate function on the client side.
+    pub normal_buckets: Vec<RpcFeerateBucket>,
+
+    /// A vector of *low* priority feerate values. The first value of this vector is guaranteed to
+    /// exist and provide an estimation for sub-*hour* DAG inclusion.
+    pub low_buckets: Vec<RpcFeerateBucket>,
+}
+
+impl RpcFeeEstimate {
+    pub fn ordered_buckets(&self) -> Vec<RpcFeerateBucket> {
+        std::iter::once(self.priority_bucket)
+            .chain(self.normal_buckets.iter().copied())
+            .chain(self.low_buckets.iter().copied())
+            .collect()
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "wasm32-sdk")] {
+        use wasm_bindgen::prelude::*;
+
+        #[wasm_bindgen(typescript_custom_section)]
+        const TS_FEE_ESTIMATE: &'static str = r#"
+            /**
+             *
+             *
+             * @category Node RPC
+             */
+            export interface IFeerateBucket {
+                /**
+                 * The fee/mass ratio estimated to be required for inclusion time <= estimated_seconds
+                 */
+                feerate : number;
+                /**
+                 * The estimated inclusion time for a transaction with fee/mass = feerate
+                 */
+                estimatedSeconds : number;
+            }
+
+            /**
+             *
+             *
+             * @category Node RPC
+             */
+            export interface IFeeEstimate {
+                /**
+                 * *Top-priority* feerate bucket. Provides an estimation of the feerate required for sub-second DAG inclusion.
+                 */
+                priorityBucket : IFeerateBucket;
+                /**
+                 * A vector of *normal* priority feerate values. The first value of this vector is guaranteed to exist and
+                 * provide an estimation for sub-*minute* DAG inclusion. All other values will have shorter estimation
+                 * times than all `lowBuckets` values.
+                 */
+                normalBuckets : IFeerateBucket[];
+                /**
+                 * A vector of *low* priority feerate values. The first value of this vector is guaranteed to
+                 * exist and provide an estimation for sub-*hour* DAG inclusion.
+                 */
+                lowBuckets : IFeerateBucket[];
+            }
+        "#;
+    }
+}
@@ -1,3 +1,4 @@
+use crate::api::auth::{RpcCredentials, RpcPermissions};
 use crate::model::*;
 use borsh::{BorshDeserialize, BorshSerialize};
 use waglayla_consensus_core::api::stats::BlockCount;
@@ -230,8 +231,9 @@ pub struct GetPeerAddressesRequest {}
 pub struct GetPeerAddressesResponse {
     #[pyo3(get)]
     pub known_addresses: Vec<RpcPeerAddress>,
+    /// The banned subnets, single banned IPs being full-length subnets
     #[pyo3(get)]
-    pub banned_addresses: Vec<RpcIpAddress>,
+    pub banned_addresses: Vec<RpcIpSubnet>,
 }
 
 #[cfg(target_family = "wasm")]
@@ -239,11 +241,12 @@ pub struct GetPeerAddressesResponse {
 #[serde(rename_all = "camelCase")]
 pub struct GetPeerAddressesResponse {
     pub known_addresses: Vec<RpcPeerAddress>,
-    pub banned_addresses: Vec<RpcIpAddress>,
+    /// The banned subnets, single banned IPs being full-length subnets
+    pub banned_addresses: Vec<RpcIpSubnet>,
 }
 
 impl GetPeerAddressesResponse {
-    pub fn new(known_addresses: Vec<RpcPeerAddress>, banned_addresses: Vec<RpcIpAddress>) -> Self {
+    pub fn new(known_addresses: Vec<RpcPeerAddress>, banned_addresses: Vec<RpcIpSubnet>) -> Self {
         Self { known_addresses, banned_addresses }
     }
 }
@@ -395,6 +398,55 @@ impl SubmitTransactionResponse {
     }
 }
 
+/// Replace by fee mode of a [`SubmitTransactionReplacementRequest`]
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcReplacementMode {
+    /// The transaction must double spend exactly one mempool transaction and replace it
+    #[default]
+    Mandatory,
+    /// The transaction replaces all the mempool transactions it double spends, if any, and is
+    /// otherwise submitted as a regular transaction
+    Optional,
+}
+
+/// SubmitTransactionReplacementRequest submits a transaction replacing the mempool transactions it double
+/// spends, provided it pays a strictly higher fee rate than each of them. The replaced transactions and all
+/// their descendants get evicted from the mempool.
+///
+/// In the mandatory mode, the transaction must double spend exactly one mempool transaction. In the optional
+/// mode, it may double spend any number of mempool transactions, including none.
+///
+/// See: TransactionsEvictedNotification
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitTransactionReplacementRequest {
+    pub transaction: RpcTransaction,
+    #[serde(default)]
+    pub mode: RpcReplacementMode,
+}
+
+impl SubmitTransactionReplacementRequest {
+    pub fn new(transaction: RpcTransaction, mode: RpcReplacementMode) -> Self {
+        Self { transaction, mode }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitTransactionReplacementResponse {
+    pub transaction_id: RpcTransactionId,
+    /// The mempool transaction owning the first double spend of the submitted transaction,
+    /// none if an optional replacement did not double spend any mempool transaction
+    pub replaced_transaction: Option<RpcTransaction>,
+}
+
+impl SubmitTransactionReplacementResponse {
+    pub fn new(transaction_id: RpcTransactionId, replaced_transaction: Option<RpcTransaction>) -> Self {
+        Self { transaction_id, replaced_transaction }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetSubnetworkRequest {
@@ -735,12 +787,19 @@ impl GetUtxosByAddressesResponse {
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BanRequest {
-    pub ip: RpcIpAddress,
+    /// IP address or subnet in CIDR notation
+    pub ip: RpcIpSubnet,
+    /// Ban duration in seconds. The node's configured ban duration is used if not specified
+    #[serde(default)]
+    pub duration: Option<u64>,
+    /// Reason recorded along with the ban. A generic reason is recorded if not specified
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 impl BanRequest {
-    pub fn new(ip: RpcIpAddress) -> Self {
-        Self { ip }
+    pub fn new(ip: RpcIpSubnet, duration: Option<u64>, reason: Option<String>) -> Self {
+        Self { ip, duration, reason }
     }
 }
 
@@ -751,11 +810,12 @@ pub struct BanResponse {}
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UnbanRequest {
-    pub ip: RpcIpAddress,
+    /// IP address or subnet in CIDR notation, as it was banned
+    pub ip: RpcIpSubnet,
 }
 
 impl UnbanRequest {
-    pub fn new(ip: RpcIpAddress) -> Self {
+    pub fn new(ip: RpcIpSubnet) -> Self {
         Self { ip }
     }
 }
@@ -1163,6 +1223,214 @@ impl GetDaaScoreTimestampEstimateResponse {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFeeEstimateRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFeeEstimateResponse {
+    pub estimate: RpcFeeEstimate,
+}
+
+/// GetTransactionRequest looks up a transaction by id in the transaction index.
+/// The node must run with the `--txindex` argument.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionRequest {
+    pub transaction_id: RpcTransactionId,
+}
+
+impl GetTransactionRequest {
+    pub fn new(transaction_id: RpcTransactionId) -> Self {
+        Self { transaction_id }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionResponse {
+    pub transaction: RpcTransaction,
+    /// Hashes of all the blocks including the transaction
+    pub including_block_hashes: Vec<RpcHash>,
+    /// Hash of the chain block accepting the transaction, if it was accepted
+    pub accepting_block_hash: Option<RpcHash>,
+}
+
+impl GetTransactionResponse {
+    pub fn new(transaction: RpcTransaction, including_block_hashes: Vec<RpcHash>, accepting_block_hash: Option<RpcHash>) -> Self {
+        Self { transaction, including_block_hashes, accepting_block_hash }
+    }
+}
+
+/// GetTransactionsByAddressesRequest pages through the transactions paying to or spending from the given addresses,
+/// ordered by accepting DAA score. Only the history above the pruning point is available.
+/// The node must run with the `--addressindex` argument.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionsByAddressesRequest {
+    pub addresses: Vec<RpcAddress>,
+    /// The lowest accepting DAA score to return transactions from
+    pub from_daa_score: u64,
+    /// The maximum number of transactions to return. The transactions accepted by the
+    /// same chain block are never split over two pages, so this can be slightly exceeded.
+    pub limit: u32,
+}
+
+impl GetTransactionsByAddressesRequest {
+    pub fn new(addresses: Vec<RpcAddress>, from_daa_score: u64, limit: u32) -> Self {
+        Self { addresses, from_daa_score, limit }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionsByAddressesResponse {
+    pub transactions: Vec<RpcAddressTransaction>,
+    /// The `from_daa_score` of the next page, or `None` if this is the last page
+    pub next_daa_score: Option<u64>,
+}
+
+impl GetTransactionsByAddressesResponse {
+    pub fn new(transactions: Vec<RpcAddressTransaction>, next_daa_score: Option<u64>) -> Self {
+        Self { transactions, next_daa_score }
+    }
+}
+
+/// GetChainBlockByDaaScoreRequest looks up the first chain block with a DAA score of at least `daa_score`,
+/// that is the chain block whose mergeset brought the selected chain to this DAA score.
+/// Only the chain blocks above the pruning point are available.
+/// The node must run with the `--chainindex` argument.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChainBlockByDaaScoreRequest {
+    pub daa_score: u64,
+}
+
+impl GetChainBlockByDaaScoreRequest {
+    pub fn new(daa_score: u64) -> Self {
+        Self { daa_score }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChainBlockByDaaScoreResponse {
+    /// Header of the chain block, or `None` if the selected chain did not reach the DAA score yet
+    pub header: Option<RpcHeader>,
+}
+
+impl GetChainBlockByDaaScoreResponse {
+    pub fn new(header: Option<RpcHeader>) -> Self {
+        Self { header }
+    }
+}
+
+/// GetChainBlockByTimestampRequest looks up the first chain block, in selected chain order, with a timestamp
+/// of at least `timestamp`. Block timestamps are only roughly ordered along the selected chain, so a later
+/// chain block may have a lower timestamp than the returned one.
+/// Only the chain blocks above the pruning point are available.
+/// The node must run with the `--chainindex` argument.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChainBlockByTimestampRequest {
+    /// Timestamp in milliseconds
+    pub timestamp: u64,
+}
+
+impl GetChainBlockByTimestampRequest {
+    pub fn new(timestamp: u64) -> Self {
+        Self { timestamp }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChainBlockByTimestampResponse {
+    /// Header of the chain block, or `None` if no chain block reached the timestamp yet
+    pub header: Option<RpcHeader>,
+}
+
+impl GetChainBlockByTimestampResponse {
+    pub fn new(header: Option<RpcHeader>) -> Self {
+        Self { header }
+    }
+}
+
+/// GetChainBlockHeadersRequest requests the headers of the chain blocks with a DAA score
+/// within `from_daa_score..=to_daa_score`, ordered by DAA score.
+/// Only the chain blocks above the pruning point are available.
+/// The node must run with the `--chainindex` argument.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChainBlockHeadersRequest {
+    pub from_daa_score: u64,
+    pub to_daa_score: u64,
+    /// The maximum number of headers to return
+    pub limit: u32,
+}
+
+impl GetChainBlockHeadersRequest {
+    pub fn new(from_daa_score: u64, to_daa_score: u64, limit: u32) -> Self {
+        Self { from_daa_score, to_daa_score, limit }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChainBlockHeadersResponse {
+    pub headers: Vec<RpcHeader>,
+}
+
+impl GetChainBlockHeadersResponse {
+    pub fn new(headers: Vec<RpcHeader>) -> Self {
+        Self { headers }
+    }
+}
+
+/// GetBannedPeersRequest requests the list of currently banned IP addresses and subnets
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBannedPeersRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBannedPeersResponse {
+    pub banned_peers: Vec<RpcBannedPeer>,
+}
+
+impl GetBannedPeersResponse {
+    pub fn new(banned_peers: Vec<RpcBannedPeer>) -> Self {
+        Self { banned_peers }
+    }
+}
+
+/// AuthenticateRequest presents credentials to the server, granting their permissions to the connection
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticateRequest {
+    pub credentials: RpcCredentials,
+}
+
+impl AuthenticateRequest {
+    pub fn new(credentials: RpcCredentials) -> Self {
+        Self { credentials }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticateResponse {
+    /// The permissions of the connection after authentication
+    pub permissions: RpcPermissions,
+}
+
+impl AuthenticateResponse {
+    pub fn new(permissions: RpcPermissions) -> Self {
+        Self { permissions }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Subscriptions & notifications
 // ----------------------------------------------------------------------------
@@ -1471,6 +1739,39 @@ pub struct NotifyNewBlockTemplateResponse {}
 #[serde(rename_all = "camelCase")]
 pub struct NewBlockTemplateNotification {}
 
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TransactionsEvictedNotification
+
+/// NotifyTransactionsEvictedRequest registers this connection for transactionsEvicted notifications.
+///
+/// See: TransactionsEvictedNotification
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyTransactionsEvictedRequest {
+    pub command: Command,
+}
+
+impl NotifyTransactionsEvictedRequest {
+    pub fn new(command: Command) -> Self {
+        Self { command }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyTransactionsEvictedResponse {}
+
+/// TransactionsEvictedNotification is sent whenever mempool transactions get evicted because
+/// a replacement transaction paying a higher fee rate was accepted.
+///
+/// See: NotifyTransactionsEvictedRequest
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsEvictedNotification {
+    pub replacement_transaction_id: RpcTransactionId,
+    pub evicted_transaction_ids: Arc<Vec<RpcTransactionId>>,
+}
+
 ///
 ///  wRPC response for RpcApiOps::Subscribe request
 ///
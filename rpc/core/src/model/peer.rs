@@ -1,5 +1,5 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use waglayla_utils::networking::{ContextualNetAddress, IpAddress, NetAddress, PeerId};
+use waglayla_utils::networking::{ContextualNetAddress, IpAddress, IpSubnet, NetAddress, PeerId};
 use serde::{Deserialize, Serialize};
 
 #[cfg(not(target_family = "wasm"))]
@@ -7,6 +7,7 @@ use pyo3::pyclass;
 
 pub type RpcNodeId = PeerId;
 pub type RpcIpAddress = IpAddress;
+pub type RpcIpSubnet = IpSubnet;
 pub type RpcPeerAddress = NetAddress;
 pub type RpcContextualPeerAddress = ContextualNetAddress;
 
@@ -34,6 +35,8 @@ pub struct RpcPeerInfo {
     pub time_connected: u64, // NOTE: i64 in gRPC protowire
     #[pyo3(get)]
     pub is_ibd_peer: bool,
+    #[pyo3(get)]
+    pub misbehaviour_score: u32,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -50,4 +53,16 @@ pub struct RpcPeerInfo {
     pub advertised_protocol_version: u32,
     pub time_connected: u64, // NOTE: i64 in gRPC protowire
     pub is_ibd_peer: bool,
+    pub misbehaviour_score: u32,
+}
+
+/// A banned subnet along with the time its ban expires and the reason it was banned for
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBannedPeer {
+    /// The banned IP address or subnet
+    pub subnet: RpcIpSubnet,
+    /// Unix time (in milliseconds) at which the ban expires
+    pub expiry: u64,
+    pub reason: String,
 }
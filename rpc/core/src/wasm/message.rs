@@ -478,6 +478,38 @@ try_from! ( args: GetSyncStatusResponse, IGetSyncStatusResponse, {
     Ok(to_value(&args)?.into())
 });
 
+// ---
+
+declare! {
+    IGetFeeEstimateRequest,
+    r#"
+    /**
+     * @category Node RPC
+     */
+    export interface IGetFeeEstimateRequest { }
+    "#,
+}
+
+try_from! ( args: IGetFeeEstimateRequest, GetFeeEstimateRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetFeeEstimateResponse,
+    r#"
+    /**
+     * @category Node RPC
+     */
+    export interface IGetFeeEstimateResponse {
+        estimate : IFeeEstimate;
+    }
+    "#,
+}
+
+try_from! ( args: GetFeeEstimateResponse, IGetFeeEstimateResponse, {
+    Ok(to_value(&args)?.into())
+});
+
 /*
     Interfaces for methods with arguments
 */
@@ -528,9 +560,17 @@ declare! {
      */
     export interface IBanRequest {
         /**
-         * IPv4 or IPv6 address to ban.
+         * IPv4 or IPv6 address, or subnet in CIDR notation, to ban.
          */
         ip : string;
+        /**
+         * Ban duration in seconds. The node's configured ban duration is used if not specified.
+         */
+        duration? : bigint;
+        /**
+         * Reason recorded along with the ban.
+         */
+        reason? : string;
     }
     "#,
 }
@@ -1057,6 +1097,47 @@ try_from! ( args: GetMempoolEntryResponse, IGetMempoolEntryResponse, {
 
 // ---
 
+declare! {
+    IGetTransactionRequest,
+    r#"
+    /**
+     * Look up a confirmed transaction by id.
+     * Requires the node to run with the `--txindex` argument.
+     * 
+     * @category Node RPC
+     */
+    export interface IGetTransactionRequest {
+        transactionId : HexString;
+    }
+    "#,
+}
+
+try_from! ( args: IGetTransactionRequest, GetTransactionRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetTransactionResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface IGetTransactionResponse {
+        transaction : ITransaction;
+        includingBlockHashes : HexString[];
+        acceptingBlockHash? : HexString;
+    }
+    "#,
+}
+
+try_from! ( args: GetTransactionResponse, IGetTransactionResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
 declare! {
     IGetSubnetworkRequest,
     r#"
@@ -1145,6 +1226,230 @@ try_from! ( args: GetUtxosByAddressesResponse, IGetUtxosByAddressesResponse, {
 
 // ---
 
+declare! {
+    IGetTransactionsByAddressesRequest,
+    r#"
+    /**
+     * Request a page of the transactions paying to or spending from a set of addresses,
+     * ordered by accepting DAA score. Pass the `nextDaaScore` of a response as the
+     * `fromDaaScore` of the following request to iterate over the history.
+     * Requires the node to run with the `--addressindex` argument.
+     * 
+     * @category Node RPC
+     */
+    export interface IGetTransactionsByAddressesRequest {
+        addresses : Address[] | string[];
+        fromDaaScore : bigint;
+        limit : number;
+    }
+    "#,
+}
+
+try_from! ( args: IGetTransactionsByAddressesRequest, GetTransactionsByAddressesRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetTransactionsByAddressesResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface IGetTransactionsByAddressesResponse {
+        transactions : {
+            address : Address;
+            transactionId : HexString;
+            acceptingBlockHash : HexString;
+            acceptingBlockDaaScore : bigint;
+            received : IUtxoEntry[];
+            spent : IUtxoEntry[];
+        }[];
+        nextDaaScore? : bigint;
+    }
+    "#,
+}
+
+try_from! ( args: GetTransactionsByAddressesResponse, IGetTransactionsByAddressesResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IGetChainBlockByDaaScoreRequest,
+    r#"
+    /**
+     * Request the header of the first chain block with a DAA score of at least `daaScore`.
+     * Requires the node to run with the `--chainindex` argument.
+     * 
+     * @category Node RPC
+     */
+    export interface IGetChainBlockByDaaScoreRequest {
+        daaScore : bigint;
+    }
+    "#,
+}
+
+try_from! ( args: IGetChainBlockByDaaScoreRequest, GetChainBlockByDaaScoreRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetChainBlockByDaaScoreResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface IGetChainBlockByDaaScoreResponse {
+        header? : IHeader;
+    }
+    "#,
+}
+
+try_from! ( args: GetChainBlockByDaaScoreResponse, IGetChainBlockByDaaScoreResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IGetChainBlockByTimestampRequest,
+    r#"
+    /**
+     * Request the header of the first chain block with a timestamp of at least `timestamp` (in milliseconds).
+     * Requires the node to run with the `--chainindex` argument.
+     * 
+     * @category Node RPC
+     */
+    export interface IGetChainBlockByTimestampRequest {
+        timestamp : bigint;
+    }
+    "#,
+}
+
+try_from! ( args: IGetChainBlockByTimestampRequest, GetChainBlockByTimestampRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetChainBlockByTimestampResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface IGetChainBlockByTimestampResponse {
+        header? : IHeader;
+    }
+    "#,
+}
+
+try_from! ( args: GetChainBlockByTimestampResponse, IGetChainBlockByTimestampResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IGetChainBlockHeadersRequest,
+    r#"
+    /**
+     * Request the headers of the chain blocks with a DAA score between `fromDaaScore`
+     * and `toDaaScore` (inclusive), ordered by DAA score.
+     * Requires the node to run with the `--chainindex` argument.
+     * 
+     * @category Node RPC
+     */
+    export interface IGetChainBlockHeadersRequest {
+        fromDaaScore : bigint;
+        toDaaScore : bigint;
+        limit : number;
+    }
+    "#,
+}
+
+try_from! ( args: IGetChainBlockHeadersRequest, GetChainBlockHeadersRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetChainBlockHeadersResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface IGetChainBlockHeadersResponse {
+        headers : IHeader[];
+    }
+    "#,
+}
+
+try_from! ( args: GetChainBlockHeadersResponse, IGetChainBlockHeadersResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IGetBannedPeersRequest,
+    r#"
+    /**
+     * Request the list of banned IP addresses and subnets.
+     * 
+     * @category Node RPC
+     */
+    export interface IGetBannedPeersRequest { }
+    "#,
+}
+
+try_from! ( args: IGetBannedPeersRequest, GetBannedPeersRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetBannedPeersResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface IGetBannedPeersResponse {
+        bannedPeers : IBannedPeer[];
+    }
+
+    /**
+     * A banned IP address or subnet.
+     * 
+     * @category Node RPC
+     */
+    export interface IBannedPeer {
+        /**
+         * IPv4 or IPv6 address, or subnet in CIDR notation.
+         */
+        subnet : string;
+        /**
+         * Unix time (in milliseconds) at which the ban expires.
+         */
+        expiry : bigint;
+        reason : string;
+    }
+    "#,
+}
+
+try_from! ( args: GetBannedPeersResponse, IGetBannedPeersResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
 declare! {
     IGetVirtualChainFromBlockRequest,
     r#"
@@ -1347,6 +1652,68 @@ try_from! ( args: SubmitTransactionResponse, ISubmitTransactionResponse, {
 
 // ---
 
+declare! {
+    ISubmitTransactionReplacementRequest,
+    // "ISubmitTransactionReplacementRequest | Transaction",
+    r#"
+    /**
+     * Submit a transaction replacing the mempool transactions it double spends.
+     * The replacement must pay a strictly higher fee rate than every replaced transaction.
+     * 
+     * @category Node RPC
+     */
+    export interface ISubmitTransactionReplacementRequest {
+        transaction : Transaction,
+        /**
+         * In the `"mandatory"` mode (default), the transaction must double spend exactly one
+         * mempool transaction. In the `"optional"` mode, it may double spend any number of
+         * mempool transactions, including none.
+         */
+        mode? : "mandatory" | "optional",
+    }
+    "#,
+}
+
+try_from! ( args: ISubmitTransactionReplacementRequest, SubmitTransactionReplacementRequest, {
+    let (transaction, mode) = if let Some(transaction) = args.try_get_value("transaction")? {
+        let mode = args.try_get_value("mode")?.map(from_value).transpose()?.unwrap_or_default();
+        (transaction, mode)
+    } else {
+        (args.into(), RpcReplacementMode::default())
+    };
+
+    let request = if let Ok(transaction) = Transaction::try_owned_from(&transaction) {
+        SubmitTransactionReplacementRequest {
+            transaction : transaction.into(),
+            mode,
+        }
+    } else {
+        from_value(transaction)?
+    };
+    Ok(request)
+});
+
+declare! {
+    ISubmitTransactionReplacementResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface ISubmitTransactionReplacementResponse {
+        transactionId : HexString;
+        replacedTransaction?: ITransaction;
+    }
+    "#,
+}
+
+try_from! ( args: SubmitTransactionReplacementResponse, ISubmitTransactionReplacementResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
 declare! {
     IUnbanRequest,
     r#"
@@ -1357,7 +1724,7 @@ declare! {
      */
     export interface IUnbanRequest {
         /**
-         * IPv4 or IPv6 address to unban.
+         * IPv4 or IPv6 address, or subnet in CIDR notation, to unban.
          */
         ip : string;
     }
@@ -12,6 +12,9 @@ pub enum Error {
     #[error("GRPC invalid address schema {0}")]
     GrpcAddressSchema(String),
 
+    #[error("GRPC TLS connection to {0} requires CA certificates")]
+    MissingTlsCertificates(String),
+
     #[error("GRPC client error {0}")]
     TonicStatus(#[from] tonic::Status),
 
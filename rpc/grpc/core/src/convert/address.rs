@@ -1,6 +1,7 @@
 use crate::protowire;
 use crate::{from, try_from};
-use waglayla_rpc_core::RpcError;
+use waglayla_rpc_core::{RpcError, RpcHash};
+use std::str::FromStr;
 
 // ----------------------------------------------------------------------------
 // rpc_core to protowire
@@ -10,6 +11,17 @@ from!(item: &waglayla_rpc_core::RpcBalancesByAddressesEntry, protowire::RpcBalan
     Self { address: (&item.address).into(), balance: item.balance.unwrap_or_default(), error: None }
 });
 
+from!(item: &waglayla_rpc_core::RpcAddressTransaction, protowire::RpcAddressTransaction, {
+    Self {
+        address: (&item.address).into(),
+        transaction_id: item.transaction_id.to_string(),
+        accepting_block_hash: item.accepting_block_hash.to_string(),
+        accepting_block_daa_score: item.accepting_block_daa_score,
+        received: item.received.iter().map(|x| x.into()).collect(),
+        spent: item.spent.iter().map(|x| x.into()).collect(),
+    }
+});
+
 // ----------------------------------------------------------------------------
 // protowire to rpc_core
 // ----------------------------------------------------------------------------
@@ -18,3 +30,14 @@ try_from!(item: &protowire::RpcBalancesByAddressesEntry, waglayla_rpc_core::RpcB
     let balance = if item.error.is_some() { None } else { Some(item.balance) };
     Self { address: item.address.as_str().try_into()?, balance }
 });
+
+try_from!(item: &protowire::RpcAddressTransaction, waglayla_rpc_core::RpcAddressTransaction, {
+    Self {
+        address: item.address.as_str().try_into()?,
+        transaction_id: RpcHash::from_str(&item.transaction_id)?,
+        accepting_block_hash: RpcHash::from_str(&item.accepting_block_hash)?,
+        accepting_block_daa_score: item.accepting_block_daa_score,
+        received: item.received.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+        spent: item.spent.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+    }
+});
@@ -1,15 +1,29 @@
 use crate::from;
 use crate::protowire;
+use waglayla_rpc_core::api::ops::RpcApiOps;
+use workflow_core::enums::Describe;
 
 // ----------------------------------------------------------------------------
 // rpc_core to protowire
 // ----------------------------------------------------------------------------
 
-from!(item: waglayla_rpc_core::RpcError, protowire::RpcError, { Self { message: item.to_string() } });
-from!(item: &waglayla_rpc_core::RpcError, protowire::RpcError, { Self { message: item.to_string() } });
+from!(item: waglayla_rpc_core::RpcError, protowire::RpcError, { (&item).into() });
+from!(item: &waglayla_rpc_core::RpcError, protowire::RpcError, {
+    match item {
+        waglayla_rpc_core::RpcError::RateLimitExceeded(op, retry_after_ms) => {
+            Self { message: item.to_string(), retry_after_ms: *retry_after_ms, method: op.as_str().to_string() }
+        }
+        _ => Self { message: item.to_string(), ..Default::default() },
+    }
+});
 
 // ----------------------------------------------------------------------------
 // protowire to rpc_core
 // ----------------------------------------------------------------------------
 
-from!(item: &protowire::RpcError, waglayla_rpc_core::RpcError, { waglayla_rpc_core::RpcError::from(item.message.to_string()) });
+from!(item: &protowire::RpcError, waglayla_rpc_core::RpcError, {
+    match RpcApiOps::from_str(&item.method) {
+        Some(op) if item.retry_after_ms > 0 => waglayla_rpc_core::RpcError::RateLimitExceeded(op, item.retry_after_ms),
+        _ => waglayla_rpc_core::RpcError::from(item.message.to_string()),
+    }
+});
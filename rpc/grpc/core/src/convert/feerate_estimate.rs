@@ -0,0 +1,39 @@
+use crate::protowire;
+use crate::{from, try_from};
+use waglayla_rpc_core::RpcError;
+
+// ----------------------------------------------------------------------------
+// rpc_core to protowire
+// ----------------------------------------------------------------------------
+
+from!(item: &waglayla_rpc_core::RpcFeerateBucket, protowire::RpcFeerateBucket, {
+    Self { feerate: item.feerate, estimated_seconds: item.estimated_seconds }
+});
+
+from!(item: &waglayla_rpc_core::RpcFeeEstimate, protowire::RpcFeeEstimate, {
+    Self {
+        priority_bucket: Some((&item.priority_bucket).into()),
+        normal_buckets: item.normal_buckets.iter().map(|b| b.into()).collect(),
+        low_buckets: item.low_buckets.iter().map(|b| b.into()).collect(),
+    }
+});
+
+// ----------------------------------------------------------------------------
+// protowire to rpc_core
+// ----------------------------------------------------------------------------
+
+try_from!(item: &protowire::RpcFeerateBucket, waglayla_rpc_core::RpcFeerateBucket, {
+    Self { feerate: item.feerate, estimated_seconds: item.estimated_seconds }
+});
+
+try_from!(item: &protowire::RpcFeeEstimate, waglayla_rpc_core::RpcFeeEstimate, {
+    Self {
+        priority_bucket: item
+            .priority_bucket
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("RpcFeeEstimate".to_string(), "priority_bucket".to_string()))?
+            .try_into()?,
+        normal_buckets: item.normal_buckets.iter().map(|b| b.try_into()).collect::<Result<Vec<_>, _>>()?,
+        low_buckets: item.low_buckets.iter().map(|b| b.try_into()).collect::<Result<Vec<_>, _>>()?,
+    }
+});
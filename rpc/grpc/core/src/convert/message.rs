@@ -18,12 +18,14 @@
 //!
 //! The SubmitBlockResponse is a notable exception to this general rule.
 
-use crate::protowire::{self, submit_block_response_message::RejectReason};
+use crate::protowire::{
+    self, submit_block_response_message::RejectReason, submit_transaction_replacement_request_message::ReplacementMode,
+};
 use waglayla_consensus_core::network::NetworkId;
 use waglayla_core::debug;
 use waglayla_notify::subscription::Command;
 use waglayla_rpc_core::{
-    RpcContextualPeerAddress, RpcError, RpcExtraData, RpcHash, RpcIpAddress, RpcNetworkType, RpcPeerAddress, RpcResult,
+    RpcContextualPeerAddress, RpcError, RpcExtraData, RpcHash, RpcIpSubnet, RpcNetworkType, RpcPeerAddress, RpcResult,
     SubmitBlockRejectReason, SubmitBlockReport,
 };
 use std::str::FromStr;
@@ -136,6 +138,13 @@ from!(item: &waglayla_rpc_core::SubmitBlockReport, RejectReason, {
     }
 });
 
+from!(item: &waglayla_rpc_core::RpcReplacementMode, ReplacementMode, {
+    match item {
+        waglayla_rpc_core::RpcReplacementMode::Mandatory => ReplacementMode::Mandatory,
+        waglayla_rpc_core::RpcReplacementMode::Optional => ReplacementMode::Optional,
+    }
+});
+
 from!(item: &waglayla_rpc_core::SubmitBlockRequest, protowire::SubmitBlockRequestMessage, {
     Self { block: Some((&item.block).into()), allow_non_daa_blocks: item.allow_non_daa_blocks }
 });
@@ -192,6 +201,11 @@ from!(item: &waglayla_rpc_core::NotifyNewBlockTemplateRequest, protowire::Notify
 });
 from!(RpcResult<&waglayla_rpc_core::NotifyNewBlockTemplateResponse>, protowire::NotifyNewBlockTemplateResponseMessage);
 
+from!(item: &waglayla_rpc_core::NotifyTransactionsEvictedRequest, protowire::NotifyTransactionsEvictedRequestMessage, {
+    Self { command: item.command.into() }
+});
+from!(RpcResult<&waglayla_rpc_core::NotifyTransactionsEvictedResponse>, protowire::NotifyTransactionsEvictedResponseMessage);
+
 // ~~~
 
 from!(&waglayla_rpc_core::GetCurrentNetworkRequest, protowire::GetCurrentNetworkRequestMessage);
@@ -248,6 +262,17 @@ from!(item: RpcResult<&waglayla_rpc_core::SubmitTransactionResponse>, protowire:
     Self { transaction_id: item.transaction_id.to_string(), error: None }
 });
 
+from!(item: &waglayla_rpc_core::SubmitTransactionReplacementRequest, protowire::SubmitTransactionReplacementRequestMessage, {
+    Self { transaction: Some((&item.transaction).into()), mode: ReplacementMode::from(&item.mode) as i32 }
+});
+from!(item: RpcResult<&waglayla_rpc_core::SubmitTransactionReplacementResponse>, protowire::SubmitTransactionReplacementResponseMessage, {
+    Self {
+        transaction_id: item.transaction_id.to_string(),
+        replaced_transaction: item.replaced_transaction.as_ref().map(|x| x.into()),
+        error: None,
+    }
+});
+
 from!(item: &waglayla_rpc_core::GetSubnetworkRequest, protowire::GetSubnetworkRequestMessage, {
     Self { subnetwork_id: item.subnetwork_id.to_string() }
 });
@@ -352,7 +377,9 @@ from!(item: RpcResult<&waglayla_rpc_core::GetSinkBlueScoreResponse>, protowire::
     Self { blue_score: item.blue_score, error: None }
 });
 
-from!(item: &waglayla_rpc_core::BanRequest, protowire::BanRequestMessage, { Self { ip: item.ip.to_string() } });
+from!(item: &waglayla_rpc_core::BanRequest, protowire::BanRequestMessage, {
+    Self { ip: item.ip.to_string(), duration: item.duration.unwrap_or_default(), reason: item.reason.clone().unwrap_or_default() }
+});
 from!(_item: RpcResult<&waglayla_rpc_core::BanResponse>, protowire::BanResponseMessage, { Self { error: None } });
 
 from!(item: &waglayla_rpc_core::UnbanRequest, protowire::UnbanRequestMessage, { Self { ip: item.ip.to_string() } });
@@ -394,6 +421,61 @@ from!(item: RpcResult<&waglayla_rpc_core::GetDaaScoreTimestampEstimateResponse>,
     Self { timestamps: item.timestamps.clone(), error: None }
 });
 
+from!(&waglayla_rpc_core::GetFeeEstimateRequest, protowire::GetFeeEstimateRequestMessage);
+from!(item: RpcResult<&waglayla_rpc_core::GetFeeEstimateResponse>, protowire::GetFeeEstimateResponseMessage, {
+    Self { estimate: Some((&item.estimate).into()), error: None }
+});
+
+from!(item: &waglayla_rpc_core::GetTransactionRequest, protowire::GetTransactionRequestMessage, {
+    Self { transaction_id: item.transaction_id.to_string() }
+});
+from!(item: RpcResult<&waglayla_rpc_core::GetTransactionResponse>, protowire::GetTransactionResponseMessage, {
+    Self {
+        transaction: Some((&item.transaction).into()),
+        including_block_hashes: item.including_block_hashes.iter().map(|x| x.to_string()).collect(),
+        accepting_block_hash: item.accepting_block_hash.map(|x| x.to_string()).unwrap_or_default(),
+        error: None,
+    }
+});
+
+from!(item: &waglayla_rpc_core::GetTransactionsByAddressesRequest, protowire::GetTransactionsByAddressesRequestMessage, {
+    Self { addresses: item.addresses.iter().map(|x| x.into()).collect(), from_daa_score: item.from_daa_score, limit: item.limit }
+});
+from!(item: RpcResult<&waglayla_rpc_core::GetTransactionsByAddressesResponse>, protowire::GetTransactionsByAddressesResponseMessage, {
+    debug!("GRPC, Creating GetTransactionsByAddresses message with {} transactions", item.transactions.len());
+    Self {
+        transactions: item.transactions.iter().map(|x| x.into()).collect(),
+        next_daa_score: item.next_daa_score.unwrap_or_default(),
+        error: None,
+    }
+});
+
+from!(item: &waglayla_rpc_core::GetChainBlockByDaaScoreRequest, protowire::GetChainBlockByDaaScoreRequestMessage, {
+    Self { daa_score: item.daa_score }
+});
+from!(item: RpcResult<&waglayla_rpc_core::GetChainBlockByDaaScoreResponse>, protowire::GetChainBlockByDaaScoreResponseMessage, {
+    Self { header: item.header.as_ref().map(protowire::RpcBlockHeader::from), error: None }
+});
+
+from!(item: &waglayla_rpc_core::GetChainBlockByTimestampRequest, protowire::GetChainBlockByTimestampRequestMessage, {
+    Self { timestamp: item.timestamp }
+});
+from!(item: RpcResult<&waglayla_rpc_core::GetChainBlockByTimestampResponse>, protowire::GetChainBlockByTimestampResponseMessage, {
+    Self { header: item.header.as_ref().map(protowire::RpcBlockHeader::from), error: None }
+});
+
+from!(item: &waglayla_rpc_core::GetChainBlockHeadersRequest, protowire::GetChainBlockHeadersRequestMessage, {
+    Self { from_daa_score: item.from_daa_score, to_daa_score: item.to_daa_score, limit: item.limit }
+});
+from!(item: RpcResult<&waglayla_rpc_core::GetChainBlockHeadersResponse>, protowire::GetChainBlockHeadersResponseMessage, {
+    Self { headers: item.headers.iter().map(protowire::RpcBlockHeader::from).collect(), error: None }
+});
+
+from!(&waglayla_rpc_core::GetBannedPeersRequest, protowire::GetBannedPeersRequestMessage);
+from!(item: RpcResult<&waglayla_rpc_core::GetBannedPeersResponse>, protowire::GetBannedPeersResponseMessage, {
+    Self { banned_peers: item.banned_peers.iter().map(protowire::RpcBannedPeer::from).collect(), error: None }
+});
+
 from!(&waglayla_rpc_core::PingRequest, protowire::PingRequestMessage);
 from!(RpcResult<&waglayla_rpc_core::PingResponse>, protowire::PingResponseMessage);
 
@@ -482,6 +564,13 @@ from!(RpcResult<&waglayla_rpc_core::NotifySinkBlueScoreChangedResponse>, protowi
 // protowire to rpc_core
 // ----------------------------------------------------------------------------
 
+from!(item: ReplacementMode, waglayla_rpc_core::RpcReplacementMode, {
+    match item {
+        ReplacementMode::Mandatory => waglayla_rpc_core::RpcReplacementMode::Mandatory,
+        ReplacementMode::Optional => waglayla_rpc_core::RpcReplacementMode::Optional,
+    }
+});
+
 from!(item: RejectReason, waglayla_rpc_core::SubmitBlockReport, {
     match item {
         RejectReason::None => waglayla_rpc_core::SubmitBlockReport::Success,
@@ -576,6 +665,11 @@ try_from!(item: &protowire::NotifyNewBlockTemplateRequestMessage, waglayla_rpc_c
 });
 try_from!(&protowire::NotifyNewBlockTemplateResponseMessage, RpcResult<waglayla_rpc_core::NotifyNewBlockTemplateResponse>);
 
+try_from!(item: &protowire::NotifyTransactionsEvictedRequestMessage, waglayla_rpc_core::NotifyTransactionsEvictedRequest, {
+    Self { command: item.command.into() }
+});
+try_from!(&protowire::NotifyTransactionsEvictedResponseMessage, RpcResult<waglayla_rpc_core::NotifyTransactionsEvictedResponse>);
+
 // ~~~
 
 try_from!(&protowire::GetCurrentNetworkRequestMessage, waglayla_rpc_core::GetCurrentNetworkRequest);
@@ -590,7 +684,7 @@ try_from!(&protowire::GetPeerAddressesRequestMessage, waglayla_rpc_core::GetPeer
 try_from!(item: &protowire::GetPeerAddressesResponseMessage, RpcResult<waglayla_rpc_core::GetPeerAddressesResponse>, {
     Self {
         known_addresses: item.addresses.iter().map(RpcPeerAddress::try_from).collect::<Result<Vec<_>, _>>()?,
-        banned_addresses: item.banned_addresses.iter().map(RpcIpAddress::try_from).collect::<Result<Vec<_>, _>>()?,
+        banned_addresses: item.banned_addresses.iter().map(RpcIpSubnet::try_from).collect::<Result<Vec<_>, _>>()?,
     }
 });
 
@@ -647,6 +741,25 @@ try_from!(item: &protowire::SubmitTransactionResponseMessage, RpcResult<waglayla
     Self { transaction_id: RpcHash::from_str(&item.transaction_id)? }
 });
 
+try_from!(item: &protowire::SubmitTransactionReplacementRequestMessage, waglayla_rpc_core::SubmitTransactionReplacementRequest, {
+    Self {
+        transaction: item
+            .transaction
+            .as_ref()
+            .ok_or_else(|| {
+                RpcError::MissingRpcFieldError("SubmitTransactionReplacementRequestMessage".to_string(), "transaction".to_string())
+            })?
+            .try_into()?,
+        mode: ReplacementMode::try_from(item.mode).map_err(|_| RpcError::PrimitiveToEnumConversionError)?.into(),
+    }
+});
+try_from!(item: &protowire::SubmitTransactionReplacementResponseMessage, RpcResult<waglayla_rpc_core::SubmitTransactionReplacementResponse>, {
+    Self {
+        transaction_id: RpcHash::from_str(&item.transaction_id)?,
+        replaced_transaction: item.replaced_transaction.as_ref().map(waglayla_rpc_core::RpcTransaction::try_from).transpose()?,
+    }
+});
+
 try_from!(item: &protowire::GetSubnetworkRequestMessage, waglayla_rpc_core::GetSubnetworkRequest, {
     Self { subnetwork_id: waglayla_rpc_core::RpcSubnetworkId::from_str(&item.subnetwork_id)? }
 });
@@ -746,10 +859,16 @@ try_from!(item: &protowire::GetSinkBlueScoreResponseMessage, RpcResult<waglayla_
     Self { blue_score: item.blue_score }
 });
 
-try_from!(item: &protowire::BanRequestMessage, waglayla_rpc_core::BanRequest, { Self { ip: RpcIpAddress::from_str(&item.ip)? } });
+try_from!(item: &protowire::BanRequestMessage, waglayla_rpc_core::BanRequest, {
+    Self {
+        ip: RpcIpSubnet::from_str(&item.ip)?,
+        duration: (item.duration > 0).then_some(item.duration),
+        reason: (!item.reason.is_empty()).then(|| item.reason.clone()),
+    }
+});
 try_from!(&protowire::BanResponseMessage, RpcResult<waglayla_rpc_core::BanResponse>);
 
-try_from!(item: &protowire::UnbanRequestMessage, waglayla_rpc_core::UnbanRequest, { Self { ip: RpcIpAddress::from_str(&item.ip)? } });
+try_from!(item: &protowire::UnbanRequestMessage, waglayla_rpc_core::UnbanRequest, { Self { ip: RpcIpSubnet::from_str(&item.ip)? } });
 try_from!(&protowire::UnbanResponseMessage, RpcResult<waglayla_rpc_core::UnbanResponse>);
 
 try_from!(item: &protowire::EstimateNetworkHashesPerSecondRequestMessage, waglayla_rpc_core::EstimateNetworkHashesPerSecondRequest, {
@@ -791,6 +910,76 @@ try_from!(item: &protowire::GetDaaScoreTimestampEstimateResponseMessage, RpcResu
     Self { timestamps: item.timestamps.clone() }
 });
 
+try_from!(&protowire::GetFeeEstimateRequestMessage, waglayla_rpc_core::GetFeeEstimateRequest);
+try_from!(item: &protowire::GetFeeEstimateResponseMessage, RpcResult<waglayla_rpc_core::GetFeeEstimateResponse>, {
+    Self {
+        estimate: item
+            .estimate
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("GetFeeEstimateResponseMessage".to_string(), "estimate".to_string()))?
+            .try_into()?,
+    }
+});
+
+try_from!(item: &protowire::GetTransactionRequestMessage, waglayla_rpc_core::GetTransactionRequest, {
+    Self { transaction_id: RpcHash::from_str(&item.transaction_id)? }
+});
+try_from!(item: &protowire::GetTransactionResponseMessage, RpcResult<waglayla_rpc_core::GetTransactionResponse>, {
+    Self {
+        transaction: item
+            .transaction
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("GetTransactionResponseMessage".to_string(), "transaction".to_string()))?
+            .try_into()?,
+        including_block_hashes: item.including_block_hashes.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()?,
+        accepting_block_hash: if item.accepting_block_hash.is_empty() {
+            None
+        } else {
+            Some(RpcHash::from_str(&item.accepting_block_hash)?)
+        },
+    }
+});
+
+try_from!(item: &protowire::GetTransactionsByAddressesRequestMessage, waglayla_rpc_core::GetTransactionsByAddressesRequest, {
+    Self {
+        addresses: item.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()?,
+        from_daa_score: item.from_daa_score,
+        limit: item.limit,
+    }
+});
+try_from!(item: &protowire::GetTransactionsByAddressesResponseMessage, RpcResult<waglayla_rpc_core::GetTransactionsByAddressesResponse>, {
+    Self {
+        transactions: item.transactions.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+        next_daa_score: if item.next_daa_score == 0 { None } else { Some(item.next_daa_score) },
+    }
+});
+
+try_from!(item: &protowire::GetChainBlockByDaaScoreRequestMessage, waglayla_rpc_core::GetChainBlockByDaaScoreRequest, {
+    Self { daa_score: item.daa_score }
+});
+try_from!(item: &protowire::GetChainBlockByDaaScoreResponseMessage, RpcResult<waglayla_rpc_core::GetChainBlockByDaaScoreResponse>, {
+    Self { header: item.header.as_ref().map(waglayla_rpc_core::RpcHeader::try_from).transpose()? }
+});
+
+try_from!(item: &protowire::GetChainBlockByTimestampRequestMessage, waglayla_rpc_core::GetChainBlockByTimestampRequest, {
+    Self { timestamp: item.timestamp }
+});
+try_from!(item: &protowire::GetChainBlockByTimestampResponseMessage, RpcResult<waglayla_rpc_core::GetChainBlockByTimestampResponse>, {
+    Self { header: item.header.as_ref().map(waglayla_rpc_core::RpcHeader::try_from).transpose()? }
+});
+
+try_from!(item: &protowire::GetChainBlockHeadersRequestMessage, waglayla_rpc_core::GetChainBlockHeadersRequest, {
+    Self { from_daa_score: item.from_daa_score, to_daa_score: item.to_daa_score, limit: item.limit }
+});
+try_from!(item: &protowire::GetChainBlockHeadersResponseMessage, RpcResult<waglayla_rpc_core::GetChainBlockHeadersResponse>, {
+    Self { headers: item.headers.iter().map(waglayla_rpc_core::RpcHeader::try_from).collect::<Result<Vec<_>, _>>()? }
+});
+
+try_from!(&protowire::GetBannedPeersRequestMessage, waglayla_rpc_core::GetBannedPeersRequest);
+try_from!(item: &protowire::GetBannedPeersResponseMessage, RpcResult<waglayla_rpc_core::GetBannedPeersResponse>, {
+    Self { banned_peers: item.banned_peers.iter().map(waglayla_rpc_core::RpcBannedPeer::try_from).collect::<Result<Vec<_>, _>>()? }
+});
+
 try_from!(&protowire::PingRequestMessage, waglayla_rpc_core::PingRequest);
 try_from!(&protowire::PingResponseMessage, RpcResult<waglayla_rpc_core::PingResponse>);
 
@@ -917,6 +1106,7 @@ mod tests {
                     reject_reason: RejectReason::BlockInvalid as i32,
                     error: Some(protowire::RpcError {
                         message: RpcError::SubmitBlockError(SubmitBlockRejectReason::BlockInvalid).to_string(),
+                        ..Default::default()
                     }),
                 },
             ),
@@ -926,6 +1116,7 @@ mod tests {
                     reject_reason: RejectReason::IsInIbd as i32,
                     error: Some(protowire::RpcError {
                         message: RpcError::SubmitBlockError(SubmitBlockRejectReason::IsInIBD).to_string(),
+                        ..Default::default()
                     }),
                 },
             ),
@@ -935,6 +1126,7 @@ mod tests {
                     reject_reason: RejectReason::None as i32, // This rpc core reject reason has no matching protowire variant
                     error: Some(protowire::RpcError {
                         message: RpcError::SubmitBlockError(SubmitBlockRejectReason::RouteIsFull).to_string(),
+                        ..Default::default()
                     }),
                 },
             ),
@@ -1,6 +1,7 @@
 pub mod address;
 pub mod block;
 pub mod error;
+pub mod feerate_estimate;
 pub mod header;
 pub mod waglaylad;
 pub mod mempool;
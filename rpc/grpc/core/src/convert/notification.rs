@@ -6,8 +6,8 @@ use crate::protowire::{
     NotifyPruningPointUtxoSetOverrideResponseMessage, NotifyUtxosChangedRequestMessage, NotifyUtxosChangedResponseMessage,
     PruningPointUtxoSetOverrideNotificationMessage, SinkBlueScoreChangedNotificationMessage,
     StopNotifyingPruningPointUtxoSetOverrideRequestMessage, StopNotifyingPruningPointUtxoSetOverrideResponseMessage,
-    StopNotifyingUtxosChangedRequestMessage, StopNotifyingUtxosChangedResponseMessage, UtxosChangedNotificationMessage,
-    VirtualChainChangedNotificationMessage, VirtualDaaScoreChangedNotificationMessage,
+    StopNotifyingUtxosChangedRequestMessage, StopNotifyingUtxosChangedResponseMessage, TransactionsEvictedNotificationMessage,
+    UtxosChangedNotificationMessage, VirtualChainChangedNotificationMessage, VirtualDaaScoreChangedNotificationMessage,
 };
 use crate::{from, try_from};
 use waglayla_notify::subscription::Command;
@@ -34,6 +34,7 @@ from!(item: &waglayla_rpc_core::Notification, Payload, {
         Notification::PruningPointUtxoSetOverride(ref notification) => {
             Payload::PruningPointUtxoSetOverrideNotification(notification.into())
         }
+        Notification::TransactionsEvicted(ref notification) => Payload::TransactionsEvictedNotification(notification.into()),
     }
 });
 
@@ -74,6 +75,13 @@ from!(item: &waglayla_rpc_core::VirtualDaaScoreChangedNotification, VirtualDaaSc
 
 from!(&waglayla_rpc_core::PruningPointUtxoSetOverrideNotification, PruningPointUtxoSetOverrideNotificationMessage);
 
+from!(item: &waglayla_rpc_core::TransactionsEvictedNotification, TransactionsEvictedNotificationMessage, {
+    Self {
+        replacement_transaction_id: item.replacement_transaction_id.to_string(),
+        evicted_transaction_ids: item.evicted_transaction_ids.iter().map(|x| x.to_string()).collect(),
+    }
+});
+
 from!(item: Command, RpcNotifyCommand, {
     match item {
         Command::Start => RpcNotifyCommand::NotifyStart,
@@ -117,6 +125,7 @@ try_from!(item: &Payload, waglayla_rpc_core::Notification, {
         Payload::PruningPointUtxoSetOverrideNotification(ref notification) => {
             Notification::PruningPointUtxoSetOverride(notification.try_into()?)
         }
+        Payload::TransactionsEvictedNotification(ref notification) => Notification::TransactionsEvicted(notification.try_into()?),
         _ => Err(RpcError::UnsupportedFeature)?,
     }
 });
@@ -171,6 +180,15 @@ try_from!(item: &VirtualDaaScoreChangedNotificationMessage, waglayla_rpc_core::V
 
 try_from!(&PruningPointUtxoSetOverrideNotificationMessage, waglayla_rpc_core::PruningPointUtxoSetOverrideNotification);
 
+try_from!(item: &TransactionsEvictedNotificationMessage, waglayla_rpc_core::TransactionsEvictedNotification, {
+    Self {
+        replacement_transaction_id: RpcHash::from_str(&item.replacement_transaction_id)?,
+        evicted_transaction_ids: Arc::new(
+            item.evicted_transaction_ids.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()?,
+        ),
+    }
+});
+
 from!(item: RpcNotifyCommand, Command, {
     match item {
         RpcNotifyCommand::NotifyStart => Command::Start,
@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use crate::protowire;
 use crate::{from, try_from};
-use waglayla_rpc_core::{RpcError, RpcNodeId, RpcPeerAddress};
+use waglayla_rpc_core::{RpcError, RpcIpSubnet, RpcNodeId, RpcPeerAddress};
 
 // ----------------------------------------------------------------------------
 // rpc_core to protowire
@@ -19,11 +19,16 @@ from!(item: &waglayla_rpc_core::RpcPeerInfo, protowire::GetConnectedPeerInfoMess
         advertised_protocol_version: item.advertised_protocol_version,
         time_connected: item.time_connected as i64,
         is_ibd_peer: item.is_ibd_peer,
+        misbehaviour_score: item.misbehaviour_score,
     }
 });
 
 from!(item: &waglayla_rpc_core::RpcPeerAddress, protowire::GetPeerAddressesKnownAddressMessage, { Self { addr: item.to_string() } });
-from!(item: &waglayla_rpc_core::RpcIpAddress, protowire::GetPeerAddressesKnownAddressMessage, { Self { addr: item.to_string() } });
+from!(item: &waglayla_rpc_core::RpcIpSubnet, protowire::GetPeerAddressesKnownAddressMessage, { Self { addr: item.to_string() } });
+
+from!(item: &waglayla_rpc_core::RpcBannedPeer, protowire::RpcBannedPeer, {
+    Self { subnet: item.subnet.to_string(), expiry: item.expiry, reason: item.reason.clone() }
+});
 
 // ----------------------------------------------------------------------------
 // protowire to rpc_core
@@ -40,8 +45,13 @@ try_from!(item: &protowire::GetConnectedPeerInfoMessage, waglayla_rpc_core::RpcP
         advertised_protocol_version: item.advertised_protocol_version,
         time_connected: item.time_connected as u64,
         is_ibd_peer: item.is_ibd_peer,
+        misbehaviour_score: item.misbehaviour_score,
     }
 });
 
 try_from!(item: &protowire::GetPeerAddressesKnownAddressMessage, waglayla_rpc_core::RpcPeerAddress, { Self::from_str(&item.addr)? });
-try_from!(item: &protowire::GetPeerAddressesKnownAddressMessage, waglayla_rpc_core::RpcIpAddress, { Self::from_str(&item.addr)? });
+try_from!(item: &protowire::GetPeerAddressesKnownAddressMessage, waglayla_rpc_core::RpcIpSubnet, { Self::from_str(&item.addr)? });
+
+try_from!(item: &protowire::RpcBannedPeer, waglayla_rpc_core::RpcBannedPeer, {
+    Self { subnet: RpcIpSubnet::from_str(&item.subnet)?, expiry: item.expiry, reason: item.reason.clone() }
+});
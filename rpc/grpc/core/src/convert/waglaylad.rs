@@ -57,6 +57,14 @@ pub mod waglaylad_request_convert {
     impl_into_waglaylad_request!(GetServerInfo);
     impl_into_waglaylad_request!(GetSyncStatus);
     impl_into_waglaylad_request!(GetDaaScoreTimestampEstimate);
+    impl_into_waglaylad_request!(GetFeeEstimate);
+    impl_into_waglaylad_request!(SubmitTransactionReplacement);
+    impl_into_waglaylad_request!(GetTransaction);
+    impl_into_waglaylad_request!(GetTransactionsByAddresses);
+    impl_into_waglaylad_request!(GetChainBlockByDaaScore);
+    impl_into_waglaylad_request!(GetChainBlockByTimestamp);
+    impl_into_waglaylad_request!(GetChainBlockHeaders);
+    impl_into_waglaylad_request!(GetBannedPeers);
 
     impl_into_waglaylad_request!(NotifyBlockAdded);
     impl_into_waglaylad_request!(NotifyNewBlockTemplate);
@@ -66,6 +74,7 @@ pub mod waglaylad_request_convert {
     impl_into_waglaylad_request!(NotifyVirtualDaaScoreChanged);
     impl_into_waglaylad_request!(NotifyVirtualChainChanged);
     impl_into_waglaylad_request!(NotifySinkBlueScoreChanged);
+    impl_into_waglaylad_request!(NotifyTransactionsEvicted);
 
     macro_rules! impl_into_waglaylad_request {
         ($name:tt) => {
@@ -188,6 +197,14 @@ pub mod waglaylad_response_convert {
     impl_into_waglaylad_response!(GetServerInfo);
     impl_into_waglaylad_response!(GetSyncStatus);
     impl_into_waglaylad_response!(GetDaaScoreTimestampEstimate);
+    impl_into_waglaylad_response!(GetFeeEstimate);
+    impl_into_waglaylad_response!(SubmitTransactionReplacement);
+    impl_into_waglaylad_response!(GetTransaction);
+    impl_into_waglaylad_response!(GetTransactionsByAddresses);
+    impl_into_waglaylad_response!(GetChainBlockByDaaScore);
+    impl_into_waglaylad_response!(GetChainBlockByTimestamp);
+    impl_into_waglaylad_response!(GetChainBlockHeaders);
+    impl_into_waglaylad_response!(GetBannedPeers);
 
     impl_into_waglaylad_notify_response!(NotifyBlockAdded);
     impl_into_waglaylad_notify_response!(NotifyNewBlockTemplate);
@@ -197,6 +214,7 @@ pub mod waglaylad_response_convert {
     impl_into_waglaylad_notify_response!(NotifyVirtualDaaScoreChanged);
     impl_into_waglaylad_notify_response!(NotifyVirtualChainChanged);
     impl_into_waglaylad_notify_response!(NotifySinkBlueScoreChanged);
+    impl_into_waglaylad_notify_response!(NotifyTransactionsEvicted);
 
     impl_into_waglaylad_notify_response!(NotifyUtxosChanged, StopNotifyingUtxosChanged);
     impl_into_waglaylad_notify_response!(NotifyPruningPointUtxoSetOverride, StopNotifyingPruningPointUtxoSetOverride);
@@ -1,5 +1,5 @@
 use crate::protowire::{waglaylad_request::Payload as RequestPayload, waglaylad_response::Payload as ResponsePayload, *};
-use waglayla_rpc_core::RpcError;
+use waglayla_rpc_core::{api::ops::RpcApiOps, RpcError};
 use workflow_core::enums::Describe;
 
 macro_rules! payload_type_enum {
@@ -81,6 +81,14 @@ pub enum WaglayladPayloadOps {
     GetServerInfo,
     GetSyncStatus,
     GetDaaScoreTimestampEstimate,
+    GetFeeEstimate,
+    SubmitTransactionReplacement,
+    GetTransaction,
+    GetTransactionsByAddresses,
+    GetChainBlockByDaaScore,
+    GetChainBlockByTimestamp,
+    GetChainBlockHeaders,
+    GetBannedPeers,
 
     // Subscription commands for starting/stopping notifications
     NotifyBlockAdded,
@@ -91,6 +99,7 @@ pub enum WaglayladPayloadOps {
     NotifyPruningPointUtxoSetOverride,
     NotifyVirtualDaaScoreChanged,
     NotifyVirtualChainChanged,
+    NotifyTransactionsEvicted,
 
     // Legacy stop subscription commands
     StopNotifyingUtxosChanged,
@@ -101,3 +110,64 @@ pub enum WaglayladPayloadOps {
     // The conversion from a notification ResponsePayload into WaglayladPayloadOps fails.
 }
 }
+
+/// Maps an op to its RPC core counterpart, legacy stop subscription commands mapping to the matching subscription command
+impl From<WaglayladPayloadOps> for RpcApiOps {
+    fn from(item: WaglayladPayloadOps) -> Self {
+        match item {
+            WaglayladPayloadOps::SubmitBlock => RpcApiOps::SubmitBlock,
+            WaglayladPayloadOps::GetBlockTemplate => RpcApiOps::GetBlockTemplate,
+            WaglayladPayloadOps::GetCurrentNetwork => RpcApiOps::GetCurrentNetwork,
+            WaglayladPayloadOps::GetBlock => RpcApiOps::GetBlock,
+            WaglayladPayloadOps::GetBlocks => RpcApiOps::GetBlocks,
+            WaglayladPayloadOps::GetInfo => RpcApiOps::GetInfo,
+            WaglayladPayloadOps::Shutdown => RpcApiOps::Shutdown,
+            WaglayladPayloadOps::GetPeerAddresses => RpcApiOps::GetPeerAddresses,
+            WaglayladPayloadOps::GetSink => RpcApiOps::GetSink,
+            WaglayladPayloadOps::GetMempoolEntry => RpcApiOps::GetMempoolEntry,
+            WaglayladPayloadOps::GetMempoolEntries => RpcApiOps::GetMempoolEntries,
+            WaglayladPayloadOps::GetConnectedPeerInfo => RpcApiOps::GetConnectedPeerInfo,
+            WaglayladPayloadOps::AddPeer => RpcApiOps::AddPeer,
+            WaglayladPayloadOps::SubmitTransaction => RpcApiOps::SubmitTransaction,
+            WaglayladPayloadOps::GetSubnetwork => RpcApiOps::GetSubnetwork,
+            WaglayladPayloadOps::GetVirtualChainFromBlock => RpcApiOps::GetVirtualChainFromBlock,
+            WaglayladPayloadOps::GetBlockCount => RpcApiOps::GetBlockCount,
+            WaglayladPayloadOps::GetBlockDagInfo => RpcApiOps::GetBlockDagInfo,
+            WaglayladPayloadOps::ResolveFinalityConflict => RpcApiOps::ResolveFinalityConflict,
+            WaglayladPayloadOps::GetHeaders => RpcApiOps::GetHeaders,
+            WaglayladPayloadOps::GetUtxosByAddresses => RpcApiOps::GetUtxosByAddresses,
+            WaglayladPayloadOps::GetBalanceByAddress => RpcApiOps::GetBalanceByAddress,
+            WaglayladPayloadOps::GetBalancesByAddresses => RpcApiOps::GetBalancesByAddresses,
+            WaglayladPayloadOps::GetSinkBlueScore => RpcApiOps::GetSinkBlueScore,
+            WaglayladPayloadOps::Ban => RpcApiOps::Ban,
+            WaglayladPayloadOps::Unban => RpcApiOps::Unban,
+            WaglayladPayloadOps::EstimateNetworkHashesPerSecond => RpcApiOps::EstimateNetworkHashesPerSecond,
+            WaglayladPayloadOps::GetMempoolEntriesByAddresses => RpcApiOps::GetMempoolEntriesByAddresses,
+            WaglayladPayloadOps::GetCoinSupply => RpcApiOps::GetCoinSupply,
+            WaglayladPayloadOps::Ping => RpcApiOps::Ping,
+            WaglayladPayloadOps::GetMetrics => RpcApiOps::GetMetrics,
+            WaglayladPayloadOps::GetServerInfo => RpcApiOps::GetServerInfo,
+            WaglayladPayloadOps::GetSyncStatus => RpcApiOps::GetSyncStatus,
+            WaglayladPayloadOps::GetDaaScoreTimestampEstimate => RpcApiOps::GetDaaScoreTimestampEstimate,
+            WaglayladPayloadOps::GetFeeEstimate => RpcApiOps::GetFeeEstimate,
+            WaglayladPayloadOps::SubmitTransactionReplacement => RpcApiOps::SubmitTransactionReplacement,
+            WaglayladPayloadOps::GetTransaction => RpcApiOps::GetTransaction,
+            WaglayladPayloadOps::GetTransactionsByAddresses => RpcApiOps::GetTransactionsByAddresses,
+            WaglayladPayloadOps::GetChainBlockByDaaScore => RpcApiOps::GetChainBlockByDaaScore,
+            WaglayladPayloadOps::GetChainBlockByTimestamp => RpcApiOps::GetChainBlockByTimestamp,
+            WaglayladPayloadOps::GetChainBlockHeaders => RpcApiOps::GetChainBlockHeaders,
+            WaglayladPayloadOps::GetBannedPeers => RpcApiOps::GetBannedPeers,
+            WaglayladPayloadOps::NotifyBlockAdded => RpcApiOps::NotifyBlockAdded,
+            WaglayladPayloadOps::NotifyNewBlockTemplate => RpcApiOps::NotifyNewBlockTemplate,
+            WaglayladPayloadOps::NotifyFinalityConflict => RpcApiOps::NotifyFinalityConflict,
+            WaglayladPayloadOps::NotifyUtxosChanged => RpcApiOps::NotifyUtxosChanged,
+            WaglayladPayloadOps::NotifySinkBlueScoreChanged => RpcApiOps::NotifySinkBlueScoreChanged,
+            WaglayladPayloadOps::NotifyPruningPointUtxoSetOverride => RpcApiOps::NotifyPruningPointUtxoSetOverride,
+            WaglayladPayloadOps::NotifyVirtualDaaScoreChanged => RpcApiOps::NotifyVirtualDaaScoreChanged,
+            WaglayladPayloadOps::NotifyVirtualChainChanged => RpcApiOps::NotifyVirtualChainChanged,
+            WaglayladPayloadOps::NotifyTransactionsEvicted => RpcApiOps::NotifyTransactionsEvicted,
+            WaglayladPayloadOps::StopNotifyingUtxosChanged => RpcApiOps::NotifyUtxosChanged,
+            WaglayladPayloadOps::StopNotifyingPruningPointUtxoSetOverride => RpcApiOps::NotifyPruningPointUtxoSetOverride,
+        }
+    }
+}
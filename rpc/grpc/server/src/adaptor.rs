@@ -1,8 +1,12 @@
-use crate::{connection_handler::ConnectionHandler, manager::Manager};
+use crate::{connection_handler::ConnectionHandler, error::GrpcServerResult, manager::Manager};
 use waglayla_core::debug;
 use waglayla_notify::{notifier::Notifier, subscription::context::SubscriptionContext};
-use waglayla_rpc_core::{api::rpc::DynRpcService, notify::connection::ChannelConnection, Notification, RpcResult};
-use waglayla_utils::networking::NetAddress;
+use waglayla_rpc_core::{
+    api::{auth::RpcAuthenticator, limits::RpcLimiter, rpc::DynRpcService},
+    notify::connection::ChannelConnection,
+    Notification, RpcResult,
+};
+use waglayla_utils::{networking::NetAddress, tls::TlsServerConfig};
 use waglayla_utils_tower::counters::TowerConnectionCounters;
 use std::{ops::Deref, sync::Arc};
 use tokio::sync::{mpsc::channel as mpsc_channel, oneshot::Sender as OneshotSender};
@@ -31,6 +35,8 @@ impl Adaptor {
         Self { _server_termination: server_termination, connection_handler, manager, serve_address }
     }
 
+    /// Starts a gRPC server on `serve_address`. Returns an error if the TLS configuration is invalid.
+    #[allow(clippy::too_many_arguments)]
     pub fn server(
         serve_address: NetAddress,
         network_bps: u64,
@@ -40,7 +46,10 @@ impl Adaptor {
         subscription_context: SubscriptionContext,
         broadcasters: usize,
         counters: Arc<TowerConnectionCounters>,
-    ) -> Arc<Self> {
+        authenticator: Arc<RpcAuthenticator>,
+        limiter: Arc<RpcLimiter>,
+        tls: Option<TlsServerConfig>,
+    ) -> GrpcServerResult<Arc<Self>> {
         let (manager_sender, manager_receiver) = mpsc_channel(Self::manager_channel_size());
         let connection_handler = ConnectionHandler::new(
             network_bps,
@@ -50,12 +59,14 @@ impl Adaptor {
             subscription_context,
             broadcasters,
             counters,
+            authenticator,
+            limiter,
         );
-        let server_termination = connection_handler.serve(serve_address);
+        let server_termination = connection_handler.serve(serve_address, tls)?;
         let adaptor = Arc::new(Adaptor::new(Some(server_termination), connection_handler, manager, serve_address));
         adaptor.manager.clone().start_event_loop(manager_receiver);
         adaptor.start();
-        adaptor
+        Ok(adaptor)
     }
 
     pub fn serve_address(&self) -> NetAddress {
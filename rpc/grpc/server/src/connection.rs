@@ -21,7 +21,10 @@ use waglayla_notify::{
     listener::{ListenerId, ListenerLifespan},
     notifier::Notifier,
 };
-use waglayla_rpc_core::Notification;
+use waglayla_rpc_core::{
+    api::{auth::RpcPermissions, limits::RpcConnectionLimiter},
+    Notification,
+};
 use parking_lot::Mutex;
 use std::{
     collections::{hash_map::Entry, HashMap},
@@ -79,6 +82,12 @@ struct Inner {
     /// The server RPC core service and notifier
     server_context: ServerContext,
 
+    /// The RPC methods this client is allowed to call
+    permissions: RpcPermissions,
+
+    /// The rate limiter metering the RPC calls of this client
+    limiter: RpcConnectionLimiter,
+
     /// Used for managing connection mutable state
     mutable_state: Mutex<InnerMutableState>,
 
@@ -175,7 +184,19 @@ impl Router {
             debug!("GRPC, Route to handler got empty payload, client: {}", connection);
             return Err(GrpcServerError::InvalidRequestPayload);
         }
-        let rpc_op = request.payload.as_ref().unwrap().into();
+        let rpc_op: WaglayladPayloadOps = request.payload.as_ref().unwrap().into();
+        if let Err(err) = connection.permissions().check(rpc_op.into()) {
+            debug!("GRPC, Route to handler refused {:?} request: {}, client: {}", rpc_op, err, connection);
+            let response = WaglayladResponse { id: request.id, payload: Some(rpc_op.to_error_response(err)) };
+            connection.enqueue(response).await?;
+            return Ok(());
+        }
+        if let Err(err) = connection.limiter().check(rpc_op.into()) {
+            debug!("GRPC, Route to handler throttled {:?} request: {}, client: {}", rpc_op, err, connection);
+            let response = WaglayladResponse { id: request.id, payload: Some(rpc_op.to_error_response(err)) };
+            connection.enqueue(response).await?;
+            return Ok(());
+        }
         let route = self.get_or_subscribe(connection, rpc_op);
         match route.policy {
             RoutingPolicy::Enqueue => match route.send(request).await {
@@ -220,6 +241,8 @@ impl Connection {
         manager_sender: MpscSender<ManagerEvent>,
         mut incoming_stream: Streaming<WaglayladRequest>,
         outgoing_route: GrpcSender,
+        permissions: RpcPermissions,
+        limiter: RpcConnectionLimiter,
     ) -> Self {
         let (shutdown_sender, mut shutdown_receiver) = oneshot_channel();
         let mut router = Router::new(server_context.clone(), interface.clone());
@@ -230,6 +253,8 @@ impl Connection {
                 outgoing_route,
                 manager_sender,
                 server_context,
+                permissions,
+                limiter,
                 mutable_state: Mutex::new(InnerMutableState::new(Some(shutdown_sender))),
                 is_closed: AtomicBool::new(false),
             }),
@@ -314,6 +339,14 @@ impl Connection {
         self.inner.connection_id
     }
 
+    pub fn permissions(&self) -> RpcPermissions {
+        self.inner.permissions
+    }
+
+    pub fn limiter(&self) -> &RpcConnectionLimiter {
+        &self.inner.limiter
+    }
+
     pub fn notifier(&self) -> Arc<GrpcNotifier> {
         self.inner.server_context.notifier.clone()
     }
@@ -1,6 +1,7 @@
 use crate::{
     collector::{GrpcServiceCollector, GrpcServiceConverter},
     connection::Connection,
+    error::{GrpcServerError, GrpcServerResult},
     manager::{ManagerEvent, RegistrationRequest},
     request_handler::{factory::Factory, interface::Interface},
 };
@@ -22,11 +23,15 @@ use waglayla_notify::{
     subscription::{context::SubscriptionContext, MutationPolicies, UtxosChangedMutationPolicy},
 };
 use waglayla_rpc_core::{
-    api::rpc::DynRpcService,
+    api::{
+        auth::{RpcAuthenticator, RpcCredentials},
+        limits::RpcLimiter,
+        rpc::DynRpcService,
+    },
     notify::{channel::NotificationChannel, connection::ChannelConnection},
     Notification, RpcResult,
 };
-use waglayla_utils::networking::NetAddress;
+use waglayla_utils::{networking::NetAddress, tls::TlsServerConfig};
 use waglayla_utils_tower::{
     counters::TowerConnectionCounters,
     middleware::{measure_request_body_size_layer, CountBytesBody, MapResponseBodyLayer},
@@ -46,7 +51,14 @@ use tokio::{
     time::timeout,
 };
 use tokio_stream::{wrappers::ReceiverStream, StreamExt};
-use tonic::{codec::CompressionEncoding, transport::Server as TonicServer, Request, Response};
+use tonic::{
+    codec::CompressionEncoding,
+    transport::{
+        server::{TcpConnectInfo, TlsConnectInfo},
+        Certificate, Identity, Server as TonicServer, ServerTlsConfig,
+    },
+    Request, Response,
+};
 
 #[derive(Clone)]
 pub struct ServerContext {
@@ -54,11 +66,20 @@ pub struct ServerContext {
     pub core_service: DynRpcService,
     /// The notifier relaying RPC core notifications to connections
     pub notifier: Arc<Notifier<Notification, Connection>>,
+    /// The authenticator granting permissions to connections
+    pub authenticator: Arc<RpcAuthenticator>,
+    /// The rate limiter metering the RPC calls of connections
+    pub limiter: Arc<RpcLimiter>,
 }
 
 impl ServerContext {
-    pub fn new(core_service: DynRpcService, notifier: Arc<Notifier<Notification, Connection>>) -> Self {
-        Self { core_service, notifier }
+    pub fn new(
+        core_service: DynRpcService,
+        notifier: Arc<Notifier<Notification, Connection>>,
+        authenticator: Arc<RpcAuthenticator>,
+        limiter: Arc<RpcLimiter>,
+    ) -> Self {
+        Self { core_service, notifier, authenticator, limiter }
     }
 }
 
@@ -89,6 +110,8 @@ impl ConnectionHandler {
         subscription_context: SubscriptionContext,
         broadcasters: usize,
         counters: Arc<TowerConnectionCounters>,
+        authenticator: Arc<RpcAuthenticator>,
+        limiter: Arc<RpcLimiter>,
     ) -> Self {
         // This notifier UTXOs subscription granularity to rpc-core notifier
         let policies = MutationPolicies::new(UtxosChangedMutationPolicy::AddressSet);
@@ -114,19 +137,25 @@ impl ConnectionHandler {
             broadcasters,
             policies,
         ));
-        let server_context = ServerContext::new(core_service, notifier);
+        let server_context = ServerContext::new(core_service, notifier, authenticator, limiter);
         let interface = Arc::new(Factory::new_interface(server_context.clone(), network_bps));
         let running = Default::default();
 
         Self { manager_sender, server_context, interface, running, counters }
     }
 
-    /// Launches a gRPC server listener loop
-    pub(crate) fn serve(&self, serve_address: NetAddress) -> OneshotSender<()> {
+    /// Launches a gRPC server listener loop, serving TLS connections only if `tls` is provided.
+    /// Returns an error if the TLS configuration is invalid.
+    pub(crate) fn serve(&self, serve_address: NetAddress, tls: Option<TlsServerConfig>) -> GrpcServerResult<OneshotSender<()>> {
+        let mut server_builder = TonicServer::builder();
+        if let Some(tls) = tls.as_ref() {
+            server_builder = server_builder.tls_config(Self::tls_config(tls)).map_err(GrpcServerError::InvalidTlsConfig)?;
+        }
+
         let (termination_sender, termination_receiver) = oneshot_channel::<()>();
         let (signal_sender, signal_receiver) = oneshot_channel::<()>();
         let connection_handler = self.clone();
-        info!("GRPC Server starting on: {}", serve_address);
+        info!("GRPC Server starting on: {}{}", serve_address, if tls.is_some() { " (TLS)" } else { "" });
 
         let bytes_tx = self.counters.bytes_tx.clone();
         let bytes_rx = self.counters.bytes_rx.clone();
@@ -141,7 +170,7 @@ impl ConnectionHandler {
             // TODO: check whether we should set tcp_keepalive
             // const GRPC_KEEP_ALIVE_PING_INTERVAL: Duration = Duration::from_secs(5);
             // const GRPC_KEEP_ALIVE_PING_TIMEOUT: Duration = Duration::from_secs(120);
-            let serve_result = TonicServer::builder()
+            let serve_result = server_builder
                 // .http2_keepalive_interval(Some(GRPC_KEEP_ALIVE_PING_INTERVAL))
                 // .http2_keepalive_timeout(Some(GRPC_KEEP_ALIVE_PING_TIMEOUT))
                 .layer(measure_request_body_size_layer(bytes_rx, |b| b))
@@ -169,7 +198,15 @@ impl ConnectionHandler {
                 warn!("GRPC Server stopped forcefully on: {}", serve_address);
             }
         });
-        termination_sender
+        Ok(termination_sender)
+    }
+
+    fn tls_config(tls: &TlsServerConfig) -> ServerTlsConfig {
+        let config = ServerTlsConfig::new().identity(Identity::from_pem(&tls.cert, &tls.key));
+        match tls.client_ca.as_ref() {
+            Some(client_ca) => config.client_ca_root(Certificate::from_pem(client_ca)),
+            None => config,
+        }
     }
 
     #[inline(always)]
@@ -254,6 +291,32 @@ impl Rpc for ConnectionHandler {
 
         debug!("GRPC, Incoming message stream from {:?}", remote_address);
 
+        // Grant the permissions of the credentials found in the authorization header, if any, or the public ones otherwise.
+        // Credentials are only accepted over TLS or from a loopback address.
+        let is_tls = request.extensions().get::<TlsConnectInfo<TcpConnectInfo>>().is_some();
+        let permissions = match request.metadata().get("authorization") {
+            Some(_) if !is_tls && !remote_address.ip().is_loopback() => {
+                warn!("GRPC, refusing incoming message stream from {:?} - credentials sent without TLS", remote_address);
+                return Err(tonic::Status::new(tonic::Code::PermissionDenied, "RPC credentials are only accepted over TLS"));
+            }
+            Some(value) => value
+                .to_str()
+                .ok()
+                .and_then(RpcCredentials::from_authorization_header)
+                .and_then(|credentials| self.server_context.authenticator.authenticate(&credentials))
+                .ok_or_else(|| {
+                    warn!("GRPC, refusing incoming message stream from {:?} - authentication failed", remote_address);
+                    tonic::Status::new(tonic::Code::Unauthenticated, "Invalid RPC credentials")
+                })?,
+            None => self.server_context.authenticator.public_permissions(),
+        };
+
+        // Enforce the per IP connection quota and meter the calls of the connection
+        let limiter = self.server_context.limiter.register(remote_address.ip()).map_err(|err| {
+            warn!("GRPC, refusing incoming message stream from {:?} - {}", remote_address, err);
+            tonic::Status::new(tonic::Code::ResourceExhausted, err.to_string())
+        })?;
+
         // Build the in/out pipes
         let (outgoing_route, outgoing_receiver) = mpsc_channel(Self::outgoing_route_channel_size());
         let incoming_stream = request.into_inner();
@@ -266,6 +329,8 @@ impl Rpc for ConnectionHandler {
             self.manager_sender(),
             incoming_stream,
             outgoing_route,
+            permissions,
+            limiter,
         );
 
         // Try to get the connection registered into the central Manager
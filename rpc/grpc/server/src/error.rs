@@ -27,6 +27,9 @@ pub enum GrpcServerError {
 
     #[error("outgoing route capacity has been reached (client: {0})")]
     OutgoingRouteCapacityReached(String),
+
+    #[error("invalid TLS configuration: {0}")]
+    InvalidTlsConfig(tonic::transport::Error),
 }
 
 impl From<GrpcServerError> for waglayla_rpc_core::error::RpcError {
@@ -75,6 +75,14 @@ impl Factory {
                 GetServerInfo,
                 GetSyncStatus,
                 GetDaaScoreTimestampEstimate,
+                GetFeeEstimate,
+                SubmitTransactionReplacement,
+                GetTransaction,
+                GetTransactionsByAddresses,
+                GetChainBlockByDaaScore,
+                GetChainBlockByTimestamp,
+                GetChainBlockHeaders,
+                GetBannedPeers,
                 NotifyBlockAdded,
                 NotifyNewBlockTemplate,
                 NotifyFinalityConflict,
@@ -83,6 +91,7 @@ impl Factory {
                 NotifyPruningPointUtxoSetOverride,
                 NotifyVirtualDaaScoreChanged,
                 NotifyVirtualChainChanged,
+                NotifyTransactionsEvicted,
                 StopNotifyingUtxosChanged,
                 StopNotifyingPruningPointUtxoSetOverride,
             ]
@@ -2,11 +2,12 @@ use crate::{adaptor::Adaptor, manager::Manager};
 use waglayla_consensus_core::config::Config;
 use waglayla_core::{
     debug,
-    task::service::{AsyncService, AsyncServiceFuture},
+    task::service::{AsyncService, AsyncServiceError, AsyncServiceFuture},
     trace, warn,
 };
+use waglayla_rpc_core::api::{auth::RpcAuthenticator, limits::RpcLimiter};
 use waglayla_rpc_service::service::RpcCoreService;
-use waglayla_utils::{networking::NetAddress, triggers::SingleTrigger};
+use waglayla_utils::{networking::NetAddress, tls::TlsServerConfig, triggers::SingleTrigger};
 use waglayla_utils_tower::counters::TowerConnectionCounters;
 use std::sync::Arc;
 use triggered::Listener;
@@ -20,6 +21,9 @@ pub struct GrpcService {
     started: SingleTrigger,
     shutdown: SingleTrigger,
     counters: Arc<TowerConnectionCounters>,
+    authenticator: Arc<RpcAuthenticator>,
+    limiter: Arc<RpcLimiter>,
+    tls: Option<TlsServerConfig>,
 }
 
 impl GrpcService {
@@ -32,6 +36,9 @@ impl GrpcService {
         rpc_max_clients: usize,
         broadcasters: usize,
         counters: Arc<TowerConnectionCounters>,
+        authenticator: Arc<RpcAuthenticator>,
+        limiter: Arc<RpcLimiter>,
+        tls: Option<TlsServerConfig>,
     ) -> Self {
         Self {
             net_address: address,
@@ -42,6 +49,9 @@ impl GrpcService {
             started: Default::default(),
             shutdown: Default::default(),
             counters,
+            authenticator,
+            limiter,
+            tls,
         }
     }
 
@@ -71,7 +81,14 @@ impl AsyncService for GrpcService {
             self.core_service.subscription_context(),
             self.broadcasters,
             self.counters.clone(),
+            self.authenticator.clone(),
+            self.limiter.clone(),
+            self.tls.clone(),
         );
+        let grpc_adaptor = match grpc_adaptor {
+            Ok(grpc_adaptor) => grpc_adaptor,
+            Err(err) => return Box::pin(async move { Err(AsyncServiceError::Service(format!("gRPC server error: `{err}`"))) }),
+        };
 
         // Signal the server was started
         self.started.trigger.trigger();
@@ -1,10 +1,25 @@
 use super::rpc_core_mock::RpcCoreMock;
-use crate::{adaptor::Adaptor, manager::Manager};
+use crate::{
+    adaptor::Adaptor,
+    error::{GrpcServerError, GrpcServerResult},
+    manager::Manager,
+};
 use waglayla_core::info;
 use waglayla_grpc_client::GrpcClient;
 use waglayla_notify::scope::{NewBlockTemplateScope, Scope};
-use waglayla_rpc_core::api::rpc::RpcApi;
-use waglayla_utils::networking::{ContextualNetAddress, NetAddress};
+use waglayla_rpc_core::{
+    api::{
+        auth::RpcAuthenticator,
+        limits::{RpcLimiter, RpcLimits, RpcRateLimit},
+        ops::RpcApiOps,
+        rpc::RpcApi,
+    },
+    RpcError,
+};
+use waglayla_utils::{
+    networking::{ContextualNetAddress, NetAddress},
+    tls::{TlsClientConfig, TlsServerConfig},
+};
 use std::sync::Arc;
 
 #[tokio::test]
@@ -188,7 +203,169 @@ async fn test_client_server_notifications() {
     tokio::time::sleep(std::time::Duration::from_millis(50)).await;
 }
 
+#[tokio::test]
+async fn test_client_server_authentication() {
+    waglayla_core::log::try_init_logger("info, waglayla_grpc_core=trace, waglayla_grpc_server=trace, waglayla_grpc_client=trace");
+
+    // Create and start a fake core service
+    let rpc_core_service = Arc::new(RpcCoreMock::new());
+    rpc_core_service.start();
+
+    // Create and start a server granting read access to anonymous clients
+    let authenticator =
+        RpcAuthenticator::new(vec!["t0ken:admin".parse().unwrap(), "admin:p@ss:all".parse().unwrap()], Some("read".parse().unwrap()));
+    let server = create_server_with_authenticator(rpc_core_service.clone(), Arc::new(authenticator));
+    let port = server.serve_address().port;
+
+    // Calls lacking permission are refused while the connection remains open
+    let anonymous = create_client(server.serve_address()).await;
+    let err = anonymous.shutdown().await.unwrap_err();
+    assert!(err.to_string().contains("requires the 'admin' permission"), "unexpected error: {err}");
+    assert!(anonymous.get_info().await.is_ok());
+
+    // Authenticated calls reach the mock, which does not implement shutdown
+    let token_client = GrpcClient::connect(format!("grpc://t0ken@localhost:{port}")).await.unwrap();
+    let err = token_client.shutdown().await.unwrap_err();
+    assert!(!err.to_string().contains("permission"), "unexpected error: {err}");
+    let password_client = GrpcClient::connect(format!("grpc://admin:p@ss@localhost:{port}")).await.unwrap();
+    let err = password_client.shutdown().await.unwrap_err();
+    assert!(!err.to_string().contains("permission"), "unexpected error: {err}");
+
+    // Invalid credentials are refused at connection
+    assert!(GrpcClient::connect(format!("grpc://wrong@localhost:{port}")).await.is_err());
+    assert_eq!(server.active_connections().len(), 3);
+
+    for client in [anonymous, token_client, password_client] {
+        assert!(client.disconnect().await.is_ok(), "client failed to disconnect");
+    }
+
+    // Stop the fake service
+    rpc_core_service.join().await;
+
+    // Stop the server
+    assert!(server.stop().await.is_ok(), "error stopping the server");
+    drop(server);
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+}
+
+#[tokio::test]
+async fn test_client_server_rate_limits() {
+    waglayla_core::log::try_init_logger("info, waglayla_grpc_core=trace, waglayla_grpc_server=trace, waglayla_grpc_client=trace");
+
+    // Create and start a fake core service
+    let rpc_core_service = Arc::new(RpcCoreMock::new());
+    rpc_core_service.start();
+
+    // Create and start a server allowing 2 connections per IP and a burst of 100 call units per connection
+    let limits = RpcLimits { connection_rate: Some(RpcRateLimit::new(1, 100)), max_ip_connections: Some(2), ..Default::default() };
+    let server = create_server_with_args(rpc_core_service.clone(), Default::default(), Arc::new(RpcLimiter::new(limits)), None);
+
+    // Expensive calls are throttled while cheap calls still go through
+    let client = create_client(server.serve_address()).await;
+    let err = client.get_utxos_by_addresses(vec![]).await.unwrap_err();
+    assert_eq!(err.retry_after_ms(), None, "unexpected error: {err}");
+    let err = client.get_utxos_by_addresses(vec![]).await.unwrap_err();
+    assert!(
+        matches!(err, RpcError::RateLimitExceeded(RpcApiOps::GetUtxosByAddresses, retry_after_ms) if retry_after_ms > 0),
+        "unexpected error: {err}"
+    );
+    assert!(client.get_info().await.is_ok());
+
+    // Every connection has its own budget but the number of connections per IP is capped
+    let other_client = create_client(server.serve_address()).await;
+    let err = other_client.get_utxos_by_addresses(vec![]).await.unwrap_err();
+    assert_eq!(err.retry_after_ms(), None, "unexpected error: {err}");
+    assert!(GrpcClient::connect(format!("grpc://localhost:{}", server.serve_address().port)).await.is_err());
+
+    for client in [client, other_client] {
+        assert!(client.disconnect().await.is_ok(), "client failed to disconnect");
+    }
+
+    // Stop the fake service
+    rpc_core_service.join().await;
+
+    // Stop the server
+    assert!(server.stop().await.is_ok(), "error stopping the server");
+    drop(server);
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+}
+
+#[tokio::test]
+async fn test_client_server_tls() {
+    waglayla_core::log::try_init_logger("info, waglayla_grpc_core=trace, waglayla_grpc_server=trace, waglayla_grpc_client=trace");
+
+    const CA: &[u8] = include_bytes!("tls/ca.pem");
+    const SERVER_CERT: &[u8] = include_bytes!("tls/server.pem");
+    const SERVER_KEY: &[u8] = include_bytes!("tls/server.key");
+    const CLIENT_CERT: &[u8] = include_bytes!("tls/client.pem");
+    const CLIENT_KEY: &[u8] = include_bytes!("tls/client.key");
+
+    // Create and start a fake core service
+    let rpc_core_service = Arc::new(RpcCoreMock::new());
+    rpc_core_service.start();
+
+    // Clients verifying the server certificate with the CA connect over TLS
+    let tls = TlsServerConfig::new(SERVER_CERT.to_vec(), SERVER_KEY.to_vec(), None);
+    let server = create_server_with_args(rpc_core_service.clone(), Default::default(), Default::default(), Some(tls));
+    let url = format!("grpcs://localhost:{}", server.serve_address().port);
+    let client = GrpcClient::connect_with_tls(url.clone(), TlsClientConfig::new(CA.to_vec())).await.unwrap();
+    assert!(client.get_info().await.is_ok());
+    assert!(client.disconnect().await.is_ok(), "client failed to disconnect");
+
+    // Plaintext connections and TLS connections lacking the CA are refused
+    assert!(GrpcClient::connect(format!("grpc://localhost:{}", server.serve_address().port)).await.is_err());
+    assert!(GrpcClient::connect(url.clone()).await.is_err());
+    assert!(GrpcClient::connect_with_tls(url, TlsClientConfig::new(SERVER_CERT.to_vec())).await.is_err());
+    assert!(server.stop().await.is_ok(), "error stopping the server");
+    drop(server);
+
+    // Invalid configurations are reported instead of starting a server
+    let tls = TlsServerConfig::new(SERVER_CERT.to_vec(), b"not a key".to_vec(), None);
+    let result = try_create_server(rpc_core_service.clone(), Default::default(), Default::default(), Some(tls));
+    assert!(matches!(result, Err(GrpcServerError::InvalidTlsConfig(_))));
+
+    // Servers verifying client certificates refuse clients without a valid one
+    let tls = TlsServerConfig::new(SERVER_CERT.to_vec(), SERVER_KEY.to_vec(), Some(CA.to_vec()));
+    let server = create_server_with_args(rpc_core_service.clone(), Default::default(), Default::default(), Some(tls));
+    let url = format!("grpcs://localhost:{}", server.serve_address().port);
+    assert!(GrpcClient::connect_with_tls(url.clone(), TlsClientConfig::new(CA.to_vec())).await.is_err());
+    let tls = TlsClientConfig::new(CA.to_vec()).with_identity(CLIENT_CERT.to_vec(), CLIENT_KEY.to_vec());
+    let client = GrpcClient::connect_with_tls(url, tls).await.unwrap();
+    assert!(client.get_info().await.is_ok());
+    assert!(client.disconnect().await.is_ok(), "client failed to disconnect");
+
+    // Stop the fake service
+    rpc_core_service.join().await;
+
+    // Stop the server
+    assert!(server.stop().await.is_ok(), "error stopping the server");
+    drop(server);
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+}
+
 fn create_server(core_service: Arc<RpcCoreMock>) -> Arc<Adaptor> {
+    create_server_with_authenticator(core_service, Default::default())
+}
+
+fn create_server_with_authenticator(core_service: Arc<RpcCoreMock>, authenticator: Arc<RpcAuthenticator>) -> Arc<Adaptor> {
+    create_server_with_args(core_service, authenticator, Default::default(), None)
+}
+
+fn create_server_with_args(
+    core_service: Arc<RpcCoreMock>,
+    authenticator: Arc<RpcAuthenticator>,
+    limiter: Arc<RpcLimiter>,
+    tls: Option<TlsServerConfig>,
+) -> Arc<Adaptor> {
+    try_create_server(core_service, authenticator, limiter, tls).unwrap()
+}
+
+fn try_create_server(
+    core_service: Arc<RpcCoreMock>,
+    authenticator: Arc<RpcAuthenticator>,
+    limiter: Arc<RpcLimiter>,
+    tls: Option<TlsServerConfig>,
+) -> GrpcServerResult<Arc<Adaptor>> {
     let manager = Manager::new(128);
     Adaptor::server(
         get_free_net_address(),
@@ -199,6 +376,9 @@ fn create_server(core_service: Arc<RpcCoreMock>) -> Arc<Adaptor> {
         core_service.subscription_context(),
         3,
         Default::default(),
+        authenticator,
+        limiter,
+        tls,
     )
 }
 
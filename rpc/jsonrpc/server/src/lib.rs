@@ -0,0 +1,7 @@
+//! Waglayla JSON-RPC 2.0 over HTTP server (AsyncService) module
+pub mod protocol;
+pub mod router;
+pub mod service;
+
+#[cfg(test)]
+mod tests;
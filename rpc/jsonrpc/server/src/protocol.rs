@@ -0,0 +1,231 @@
+//!
+//! JSON-RPC 2.0 request and response envelopes, see <https://www.jsonrpc.org/specification>
+//!
+
+use waglayla_rpc_core::RpcError;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+use std::future::Future;
+
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// Maximum number of requests in a batch
+pub const MAX_BATCH_SIZE: usize = 100;
+
+/// Invalid JSON was received by the server
+pub const PARSE_ERROR: i64 = -32700;
+/// The JSON sent is not a valid request object
+pub const INVALID_REQUEST: i64 = -32600;
+/// The method does not exist or is not available
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// Invalid method parameters
+pub const INVALID_PARAMS: i64 = -32602;
+/// Internal JSON-RPC error
+pub const INTERNAL_ERROR: i64 = -32603;
+/// The RPC method call failed
+pub const RPC_ERROR: i64 = -32000;
+/// The caller lacks the permission required by the method
+pub const PERMISSION_DENIED: i64 = -32001;
+/// The caller exceeded its RPC rate limit, the `retryAfter` data member holding the delay to wait for in milliseconds
+pub const RATE_LIMIT_EXCEEDED: i64 = -32005;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: Option<Value>,
+    /// Absent for notifications, which get no response
+    #[serde(default, deserialize_with = "deserialize_id")]
+    pub id: Option<Value>,
+}
+
+/// Distinguishes a `null` id, which must be echoed back, from a missing one
+fn deserialize_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl JsonRpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), data: None }
+    }
+
+    pub fn parse_error(message: impl Into<String>) -> Self {
+        Self::new(PARSE_ERROR, message)
+    }
+
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self::new(INVALID_REQUEST, message)
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(METHOD_NOT_FOUND, format!("Method not found: {method}"))
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(INVALID_PARAMS, message)
+    }
+
+    pub fn internal_error(message: impl Into<String>) -> Self {
+        Self::new(INTERNAL_ERROR, message)
+    }
+}
+
+impl From<RpcError> for JsonRpcError {
+    fn from(err: RpcError) -> Self {
+        match err {
+            RpcError::RateLimitExceeded(_, retry_after) => {
+                Self { code: RATE_LIMIT_EXCEEDED, message: err.to_string(), data: Some(json!({ "retryAfter": retry_after })) }
+            }
+            RpcError::PermissionDenied(..) => Self::new(PERMISSION_DENIED, err.to_string()),
+            err => Self::new(RPC_ERROR, err.to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+    pub id: Value,
+}
+
+impl JsonRpcResponse {
+    pub fn new(id: Value, result: Result<Value, JsonRpcError>) -> Self {
+        match result {
+            Ok(result) => Self { jsonrpc: JSONRPC_VERSION.to_string(), result: Some(result), error: None, id },
+            Err(error) => Self::error(id, error),
+        }
+    }
+
+    pub fn error(id: Value, error: JsonRpcError) -> Self {
+        Self { jsonrpc: JSONRPC_VERSION.to_string(), result: None, error: Some(error), id }
+    }
+}
+
+/// Processes a JSON-RPC request or batch `body`, calling `dispatch` with the method and parameters of every valid request
+/// in order. Returns the response body, which is `None` when the body only holds notifications.
+pub async fn process<F, Fut>(body: &[u8], dispatch: F) -> Option<Value>
+where
+    F: Fn(String, Option<Value>) -> Fut,
+    Fut: Future<Output = Result<Value, JsonRpcError>>,
+{
+    let body: Value = match serde_json::from_slice(body) {
+        Ok(body) => body,
+        Err(err) => return Some(json!(JsonRpcResponse::error(Value::Null, JsonRpcError::parse_error(err.to_string())))),
+    };
+    match body {
+        Value::Array(requests) if requests.is_empty() => {
+            Some(json!(JsonRpcResponse::error(Value::Null, JsonRpcError::invalid_request("Empty batch"))))
+        }
+        Value::Array(requests) if requests.len() > MAX_BATCH_SIZE => {
+            let error = JsonRpcError::invalid_request(format!("Batch exceeds {MAX_BATCH_SIZE} requests"));
+            Some(json!(JsonRpcResponse::error(Value::Null, error)))
+        }
+        Value::Array(requests) => {
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in requests {
+                responses.extend(process_request(request, &dispatch).await);
+            }
+            (!responses.is_empty()).then(|| json!(responses))
+        }
+        request => process_request(request, &dispatch).await.map(|response| json!(response)),
+    }
+}
+
+async fn process_request<F, Fut>(request: Value, dispatch: &F) -> Option<JsonRpcResponse>
+where
+    F: Fn(String, Option<Value>) -> Fut,
+    Fut: Future<Output = Result<Value, JsonRpcError>>,
+{
+    let request: JsonRpcRequest = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(err) => return Some(JsonRpcResponse::error(Value::Null, JsonRpcError::invalid_request(err.to_string()))),
+    };
+    if request.jsonrpc != JSONRPC_VERSION {
+        let error = JsonRpcError::invalid_request(format!("Unsupported JSON-RPC version {}", request.jsonrpc));
+        return Some(JsonRpcResponse::error(request.id.unwrap_or_default(), error));
+    }
+    let result = dispatch(request.method, request.params).await;
+    request.id.map(|id| JsonRpcResponse::new(id, result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use waglayla_rpc_core::api::ops::RpcApiOps;
+
+    async fn echo(method: String, params: Option<Value>) -> Result<Value, JsonRpcError> {
+        match method.as_str() {
+            "echo" => Ok(params.unwrap_or_default()),
+            method => Err(JsonRpcError::method_not_found(method)),
+        }
+    }
+
+    async fn run(body: &str) -> Option<Value> {
+        process(body.as_bytes(), echo).await
+    }
+
+    #[tokio::test]
+    async fn test_process() {
+        // Single requests get a response echoing their id, even a null one
+        let response = run(r#"{"jsonrpc":"2.0","method":"echo","params":{"a":1},"id":7}"#).await.unwrap();
+        assert_eq!(response, json!({"jsonrpc":"2.0","result":{"a":1},"id":7}));
+        let response = run(r#"{"jsonrpc":"2.0","method":"echo","id":null}"#).await.unwrap();
+        assert_eq!(response, json!({"jsonrpc":"2.0","result":null,"id":null}));
+        let response = run(r#"{"jsonrpc":"2.0","method":"nope","id":"x"}"#).await.unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(response["id"], "x");
+
+        // Notifications get no response
+        assert!(run(r#"{"jsonrpc":"2.0","method":"echo"}"#).await.is_none());
+
+        // Malformed bodies and requests
+        assert_eq!(run("{").await.unwrap()["error"]["code"], PARSE_ERROR);
+        assert_eq!(run("[]").await.unwrap()["error"]["code"], INVALID_REQUEST);
+        assert_eq!(run(r#"{"method":"echo","id":1}"#).await.unwrap()["error"]["code"], INVALID_REQUEST);
+        let response = run(r#"{"jsonrpc":"1.0","method":"echo","id":1}"#).await.unwrap();
+        assert_eq!((response["error"]["code"].as_i64(), response["id"].as_i64()), (Some(INVALID_REQUEST), Some(1)));
+
+        // Batches get the responses of their requests in order, skipping notifications
+        let body = r#"[
+            {"jsonrpc":"2.0","method":"echo","params":1,"id":1},
+            {"jsonrpc":"2.0","method":"echo","params":2},
+            3,
+            {"jsonrpc":"2.0","method":"nope","id":4}
+        ]"#;
+        let responses = run(body).await.unwrap();
+        let responses = responses.as_array().unwrap();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0], json!({"jsonrpc":"2.0","result":1,"id":1}));
+        assert_eq!(responses[1]["error"]["code"], INVALID_REQUEST);
+        assert_eq!(responses[2]["error"]["code"], METHOD_NOT_FOUND);
+        assert!(run(r#"[{"jsonrpc":"2.0","method":"echo"}]"#).await.is_none());
+
+        // Batches are capped
+        let request = json!({"jsonrpc":"2.0","method":"echo","id":1});
+        let responses = run(&json!(vec![request.clone(); MAX_BATCH_SIZE]).to_string()).await.unwrap();
+        assert_eq!(responses.as_array().unwrap().len(), MAX_BATCH_SIZE);
+        let response = run(&json!(vec![request; MAX_BATCH_SIZE + 1]).to_string()).await.unwrap();
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+    }
+
+    #[test]
+    fn test_rpc_errors() {
+        let error: JsonRpcError = RpcError::RateLimitExceeded(RpcApiOps::GetBlocks, 250).into();
+        assert_eq!((error.code, error.data), (RATE_LIMIT_EXCEEDED, Some(json!({"retryAfter": 250}))));
+        let error: JsonRpcError = RpcError::AuthenticationFailed.into();
+        assert_eq!((error.code, error.data), (RPC_ERROR, None));
+    }
+}
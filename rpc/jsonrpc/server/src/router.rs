@@ -0,0 +1,123 @@
+use crate::protocol::{process, JsonRpcError};
+use waglayla_rpc_core::{
+    api::{auth::RpcPermissions, limits::RpcConnectionLimiter, ops::RpcApiOps, rpc::DynRpcService},
+    prelude::*,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Expands into a match of the given RPC ops calling the RPC service with the request deserialized from `$params`.
+/// Any other op is reported as not found.
+macro_rules! route {
+    ($service:expr, $op:expr, $method:expr, $params:expr, $authorize:expr, [$($name:ident),* $(,)?]) => {
+        paste::paste! {
+            match $op {
+                $(
+                    RpcApiOps::$name => {
+                        $authorize;
+                        let request: [<$name Request>] = params($params)?;
+                        let response = $service.[<$name:snake _call>](request).await?;
+                        serde_json::to_value(response).map_err(|err| JsonRpcError::internal_error(err.to_string()))
+                    }
+                )*
+                _ => Err(JsonRpcError::method_not_found($method)),
+            }
+        }
+    };
+}
+
+/// Dispatches JSON-RPC requests into an RPC service.
+///
+/// Methods are named after their [`RpcApiOps`] in camel case (ie. `getBlockDagInfo`) and take the fields of their request
+/// message as named parameters. Subscriptions require a persistent connection so are only served by gRPC and wRPC.
+#[derive(Clone)]
+pub struct Router {
+    service: DynRpcService,
+}
+
+impl Router {
+    pub fn new(service: DynRpcService) -> Self {
+        Self { service }
+    }
+
+    /// Handles a JSON-RPC request or batch `body` on behalf of a caller granted `permissions` and metered by `limiter`,
+    /// returning the response body if any
+    pub async fn handle(&self, body: &[u8], permissions: RpcPermissions, limiter: &RpcConnectionLimiter) -> Option<Value> {
+        process(body, |method, params| self.dispatch(method, params, permissions, limiter)).await
+    }
+
+    async fn dispatch(
+        &self,
+        method: String,
+        params: Option<Value>,
+        permissions: RpcPermissions,
+        limiter: &RpcConnectionLimiter,
+    ) -> Result<Value, JsonRpcError> {
+        let op: RpcApiOps =
+            serde_json::from_value(Value::String(method.clone())).map_err(|_| JsonRpcError::method_not_found(&method))?;
+        route!(
+            self.service,
+            op,
+            &method,
+            params,
+            {
+                permissions.check(op)?;
+                limiter.check(op)?;
+            },
+            [
+                AddPeer,
+                Ban,
+                EstimateNetworkHashesPerSecond,
+                GetBalanceByAddress,
+                GetBalancesByAddresses,
+                GetBannedPeers,
+                GetBlock,
+                GetBlockCount,
+                GetBlockDagInfo,
+                GetBlocks,
+                GetBlockTemplate,
+                GetChainBlockByDaaScore,
+                GetChainBlockByTimestamp,
+                GetChainBlockHeaders,
+                GetCoinSupply,
+                GetConnectedPeerInfo,
+                GetCurrentNetwork,
+                GetDaaScoreTimestampEstimate,
+                GetFeeEstimate,
+                GetHeaders,
+                GetInfo,
+                GetMempoolEntries,
+                GetMempoolEntriesByAddresses,
+                GetMempoolEntry,
+                GetMetrics,
+                GetPeerAddresses,
+                GetServerInfo,
+                GetSink,
+                GetSinkBlueScore,
+                GetSubnetwork,
+                GetSyncStatus,
+                GetTransaction,
+                GetTransactionsByAddresses,
+                GetUtxosByAddresses,
+                GetVirtualChainFromBlock,
+                Ping,
+                ResolveFinalityConflict,
+                Shutdown,
+                SubmitBlock,
+                SubmitTransaction,
+                SubmitTransactionReplacement,
+                Unban,
+            ]
+        )
+    }
+}
+
+/// Deserializes the named parameters of a request, which may be omitted for requests without fields
+fn params<T: DeserializeOwned>(params: Option<Value>) -> Result<T, JsonRpcError> {
+    let params = match params {
+        None | Some(Value::Null) => Value::Object(Default::default()),
+        Some(params @ Value::Object(_)) => params,
+        Some(_) => return Err(JsonRpcError::invalid_params("Parameters must be passed by name")),
+    };
+    serde_json::from_value(params).map_err(|err| JsonRpcError::invalid_params(err.to_string()))
+}
@@ -0,0 +1,285 @@
+use crate::{
+    protocol::{PERMISSION_DENIED, RATE_LIMIT_EXCEEDED},
+    router::Router,
+};
+use hyper::{
+    body::HttpBody,
+    header::{AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER, WWW_AUTHENTICATE},
+    server::{accept, conn::AddrStream},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde_json::Value;
+use waglayla_core::{
+    debug, info,
+    task::service::{AsyncService, AsyncServiceError, AsyncServiceFuture},
+    trace, warn,
+};
+use waglayla_rpc_core::{
+    api::{
+        auth::{RpcAuthenticator, RpcCredentials},
+        limits::{RpcConnectionLimiter, RpcLimiter},
+        rpc::DynRpcService,
+    },
+    RpcResult,
+};
+use waglayla_utils::{
+    networking::NetAddress,
+    tls::TlsServerConfig,
+    triggers::{Listener, SingleTrigger},
+};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    convert::Infallible,
+    io,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_rustls::{server::TlsStream, TlsAcceptor};
+
+/// Maximum size of a request body
+const MAX_REQUEST_SIZE: usize = 32 * 1024 * 1024; // 32MB
+
+/// Maximum duration of a TLS handshake
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Serves JSON-RPC 2.0 requests and batches POSTed over HTTP, or HTTPS when a TLS configuration is provided.
+///
+/// HTTP requests are stateless, so all the requests of an IP address are metered by the rate limiter as a single
+/// connection, which is released once its rate limit has refilled. Credentials are only accepted over TLS or from
+/// a loopback address.
+pub struct JsonRpcService {
+    net_address: NetAddress,
+    router: Router,
+    authenticator: Arc<RpcAuthenticator>,
+    limiter: Arc<RpcLimiter>,
+    clients: Mutex<HashMap<IpAddr, Arc<RpcConnectionLimiter>>>,
+    tls: Option<TlsServerConfig>,
+    shutdown: SingleTrigger,
+}
+
+impl JsonRpcService {
+    pub const IDENT: &'static str = "jsonrpc-service";
+
+    pub fn new(
+        net_address: NetAddress,
+        core_service: DynRpcService,
+        authenticator: Arc<RpcAuthenticator>,
+        limiter: Arc<RpcLimiter>,
+        tls: Option<TlsServerConfig>,
+    ) -> Self {
+        Self {
+            net_address,
+            router: Router::new(core_service),
+            authenticator,
+            limiter,
+            clients: Default::default(),
+            tls,
+            shutdown: Default::default(),
+        }
+    }
+
+    pub(crate) async fn handle(
+        self: Arc<Self>,
+        remote_address: SocketAddr,
+        request: Request<Body>,
+    ) -> Result<Response<Body>, Infallible> {
+        if request.method() != Method::POST {
+            return Ok(Self::status(StatusCode::METHOD_NOT_ALLOWED, "JSON-RPC requests must be POSTed"));
+        }
+
+        // Grant the permissions of the credentials found in the authorization header, if any, or the public ones otherwise
+        let permissions = match request.headers().get(AUTHORIZATION) {
+            Some(_) if self.tls.is_none() && !remote_address.ip().is_loopback() => {
+                warn!("JSON-RPC, refusing request from {} - credentials sent without TLS", remote_address);
+                return Ok(Self::status(StatusCode::FORBIDDEN, "RPC credentials are only accepted over TLS"));
+            }
+            Some(value) => {
+                match value
+                    .to_str()
+                    .ok()
+                    .and_then(RpcCredentials::from_authorization_header)
+                    .and_then(|credentials| self.authenticator.authenticate(&credentials))
+                {
+                    Some(permissions) => permissions,
+                    None => {
+                        warn!("JSON-RPC, refusing request from {} - authentication failed", remote_address);
+                        let mut response = Self::status(StatusCode::UNAUTHORIZED, "Invalid RPC credentials");
+                        response.headers_mut().insert(WWW_AUTHENTICATE, "Basic".parse().unwrap());
+                        return Ok(response);
+                    }
+                }
+            }
+            None => self.authenticator.public_permissions(),
+        };
+
+        // Enforce the per IP connection quota and meter the calls of the request
+        let limiter = match self.client_limiter(remote_address.ip()) {
+            Ok(limiter) => limiter,
+            Err(err) => {
+                debug!("JSON-RPC, refusing request from {} - {}", remote_address, err);
+                return Ok(Self::status(StatusCode::TOO_MANY_REQUESTS, &err.to_string()));
+            }
+        };
+
+        let Some(body) = Self::read_body(request.into_body()).await else {
+            return Ok(Self::status(StatusCode::PAYLOAD_TOO_LARGE, "Request body is too large or incomplete"));
+        };
+        let response = match self.router.handle(&body, permissions, &limiter).await {
+            Some(response) => {
+                let mut builder =
+                    Response::builder().status(Self::response_status(&response)).header(CONTENT_TYPE, "application/json");
+                if let Some(retry_after) = response["error"]["data"]["retryAfter"].as_u64() {
+                    builder = builder.header(RETRY_AFTER, retry_after.div_ceil(1000));
+                }
+                builder.body(Body::from(response.to_string())).unwrap()
+            }
+            None => Response::builder().status(StatusCode::NO_CONTENT).body(Body::empty()).unwrap(),
+        };
+        Ok(response)
+    }
+
+    /// Returns the limiter shared by the requests of `ip`, registering it as a connection of the IP address if needed
+    fn client_limiter(&self, ip: IpAddr) -> RpcResult<Arc<RpcConnectionLimiter>> {
+        let mut clients = self.clients.lock().unwrap();
+        // Release the IP addresses without pending requests whose rate limit has refilled
+        clients.retain(|_, limiter| Arc::strong_count(limiter) > 1 || limiter.is_refilling());
+        match clients.entry(ip) {
+            Entry::Occupied(entry) => Ok(entry.get().clone()),
+            Entry::Vacant(entry) => Ok(entry.insert(Arc::new(self.limiter.register(ip)?)).clone()),
+        }
+    }
+
+    /// Maps the error of a single request to an HTTP status, batch responses being always sent with 200 OK
+    fn response_status(response: &Value) -> StatusCode {
+        match response["error"]["code"].as_i64() {
+            Some(PERMISSION_DENIED) => StatusCode::FORBIDDEN,
+            Some(RATE_LIMIT_EXCEEDED) => StatusCode::TOO_MANY_REQUESTS,
+            _ => StatusCode::OK,
+        }
+    }
+
+    /// Reads the request body, returning `None` if it exceeds [`MAX_REQUEST_SIZE`] or fails to be received
+    async fn read_body(mut body: Body) -> Option<Vec<u8>> {
+        let mut bytes = Vec::new();
+        while let Some(chunk) = body.data().await {
+            let chunk = chunk.ok()?;
+            if bytes.len() + chunk.len() > MAX_REQUEST_SIZE {
+                return None;
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Some(bytes)
+    }
+
+    /// Accepts the connections of `listener` until `shutdown` is triggered, yielding them once their TLS handshake
+    /// completed. Handshakes are performed concurrently so a stalled client cannot block the others.
+    fn accept_tls(listener: TcpListener, acceptor: TlsAcceptor, shutdown: Listener) -> mpsc::UnboundedReceiver<TlsStream<TcpStream>> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            loop {
+                let (stream, remote_address) = tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok(accepted) => accepted,
+                        Err(err) => {
+                            debug!("JSON-RPC, failed accepting a connection: {}", err);
+                            continue;
+                        }
+                    },
+                    _ = shutdown.clone() => break,
+                };
+                let acceptor = acceptor.clone();
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(stream)) => {
+                            let _ = sender.send(stream);
+                        }
+                        Ok(Err(err)) => debug!("JSON-RPC, TLS handshake with {} failed: {}", remote_address, err),
+                        Err(_) => debug!("JSON-RPC, TLS handshake with {} timed out", remote_address),
+                    }
+                });
+            }
+        });
+        receiver
+    }
+
+    fn status(status: StatusCode, message: &str) -> Response<Body> {
+        Response::builder().status(status).body(Body::from(message.to_string())).unwrap()
+    }
+}
+
+impl AsyncService for JsonRpcService {
+    fn ident(self: Arc<Self>) -> &'static str {
+        Self::IDENT
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", Self::IDENT);
+
+        // Prepare a shutdown signal receiver
+        let shutdown_signal = self.shutdown.listener.clone();
+
+        Box::pin(async move {
+            let address: SocketAddr = self.net_address.into();
+            let bind_error = |err: &dyn std::fmt::Display| {
+                AsyncServiceError::Service(format!("failed binding {} to {}: {}", Self::IDENT, address, err))
+            };
+            let result = match self.tls.as_ref() {
+                None => {
+                    let service = self.clone();
+                    let make_service = make_service_fn(move |stream: &AddrStream| {
+                        let service = service.clone();
+                        let remote_address = stream.remote_addr();
+                        async move { Ok::<_, Infallible>(service_fn(move |request| service.clone().handle(remote_address, request))) }
+                    });
+                    let server = Server::try_bind(&address).map_err(|err| bind_error(&err))?.serve(make_service);
+                    info!("JSON-RPC requests are served on http://{}", address);
+
+                    // Keep the server running until a service shutdown signal is received
+                    server.with_graceful_shutdown(shutdown_signal).await
+                }
+                Some(tls) => {
+                    let acceptor = TlsAcceptor::from(Arc::new(tls.rustls_config().map_err(|err| bind_error(&err))?));
+                    let listener = TcpListener::bind(address).await.map_err(|err| bind_error(&err))?;
+                    let mut incoming = Self::accept_tls(listener, acceptor, shutdown_signal.clone());
+                    let service = self.clone();
+                    let make_service = make_service_fn(move |stream: &TlsStream<TcpStream>| {
+                        let service = service.clone();
+                        let remote_address = stream.get_ref().0.peer_addr();
+                        async move {
+                            let remote_address = remote_address?;
+                            Ok::<_, io::Error>(service_fn(move |request| service.clone().handle(remote_address, request)))
+                        }
+                    });
+                    let server = Server::builder(accept::poll_fn(move |cx| {
+                        incoming.poll_recv(cx).map(|stream| stream.map(Ok::<_, io::Error>))
+                    }))
+                    .serve(make_service);
+                    info!("JSON-RPC requests are served on https://{}", address);
+
+                    // Keep the server running until a service shutdown signal is received
+                    server.with_graceful_shutdown(shutdown_signal).await
+                }
+            };
+            result.map_err(|err| AsyncServiceError::Service(err.to_string()))
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", Self::IDENT);
+        self.shutdown.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            trace!("{} stopped", Self::IDENT);
+            Ok(())
+        })
+    }
+}
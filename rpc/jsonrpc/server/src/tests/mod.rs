@@ -0,0 +1,3 @@
+mod rpc_core_mock;
+
+mod service;
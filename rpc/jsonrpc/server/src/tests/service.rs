@@ -0,0 +1,161 @@
+use super::rpc_core_mock::RpcCoreMock;
+use crate::{protocol::*, router::Router, service::JsonRpcService};
+use hyper::{
+    header::{AUTHORIZATION, RETRY_AFTER, WWW_AUTHENTICATE},
+    Body, HeaderMap, Method, Request, StatusCode,
+};
+use serde_json::{json, Value};
+use waglayla_rpc_core::api::{
+    auth::{RpcAuthenticator, RpcCredentials, RpcPermission, RpcPermissions},
+    limits::{RpcLimiter, RpcLimits, RpcRateLimit},
+};
+use waglayla_utils::tls::TlsServerConfig;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+const LOCALHOST: [u8; 4] = [127, 0, 0, 1];
+const REMOTE: [u8; 4] = [192, 168, 0, 1];
+
+fn create_service(limiter: Arc<RpcLimiter>, tls: Option<TlsServerConfig>) -> Arc<JsonRpcService> {
+    let authenticator = RpcAuthenticator::new(vec!["admin:pass:all".parse().unwrap()], Some(RpcPermission::Read.into()));
+    Arc::new(JsonRpcService::new("127.0.0.1:0".parse().unwrap(), Arc::new(RpcCoreMock), Arc::new(authenticator), limiter, tls))
+}
+
+fn request(method: &str, id: u64) -> Value {
+    json!({"jsonrpc": "2.0", "method": method, "id": id})
+}
+
+fn credentials(username: &str, password: &str) -> String {
+    RpcCredentials::UserPassword { username: username.to_string(), password: password.to_string() }.to_authorization_header()
+}
+
+/// Sends an HTTP request to `service` from `ip`, returning the response status, headers and JSON body if any
+async fn send(
+    service: &Arc<JsonRpcService>,
+    method: Method,
+    ip: [u8; 4],
+    authorization: Option<&str>,
+    body: Value,
+) -> (StatusCode, HeaderMap, Value) {
+    let mut builder = Request::builder().method(method).uri("/");
+    if let Some(authorization) = authorization {
+        builder = builder.header(AUTHORIZATION, authorization);
+    }
+    let request = builder.body(Body::from(body.to_string())).unwrap();
+    let response = service.clone().handle(SocketAddr::new(IpAddr::from(ip), 12345), request).await.unwrap();
+    let (parts, body) = response.into_parts();
+    let body = hyper::body::to_bytes(body).await.unwrap();
+    (parts.status, parts.headers, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn test_http_methods() {
+    let service = create_service(Default::default(), None);
+
+    // Only POST requests are served
+    for method in [Method::GET, Method::PUT, Method::OPTIONS] {
+        let (status, _, _) = send(&service, method, LOCALHOST, None, request("ping", 1)).await;
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    let (status, _, body) = send(&service, Method::POST, LOCALHOST, None, request("getInfo", 1)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["result"]["p2pId"], "jsonrpc-mock");
+
+    // Notifications get an empty response
+    let (status, _, body) = send(&service, Method::POST, LOCALHOST, None, json!({"jsonrpc": "2.0", "method": "ping"})).await;
+    assert_eq!((status, body), (StatusCode::NO_CONTENT, Value::Null));
+}
+
+#[tokio::test]
+async fn test_authentication() {
+    let service = create_service(Default::default(), None);
+
+    // Invalid credentials are refused
+    let (status, headers, _) = send(&service, Method::POST, LOCALHOST, Some(&credentials("admin", "nope")), request("ping", 1)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(headers[WWW_AUTHENTICATE], "Basic");
+
+    // Public callers lack the admin permission
+    let (status, _, body) = send(&service, Method::POST, LOCALHOST, None, request("shutdown", 1)).await;
+    assert_eq!((status, body["error"]["code"].as_i64()), (StatusCode::FORBIDDEN, Some(PERMISSION_DENIED)));
+
+    // Valid credentials grant their permissions, the mock failing the call itself
+    let admin = credentials("admin", "pass");
+    let (status, _, body) = send(&service, Method::POST, LOCALHOST, Some(&admin), request("shutdown", 1)).await;
+    assert_eq!((status, body["error"]["code"].as_i64()), (StatusCode::OK, Some(RPC_ERROR)));
+
+    // Credentials are refused over plaintext from remote addresses, but accepted when the service is served with TLS
+    let (status, _, _) = send(&service, Method::POST, REMOTE, Some(&admin), request("shutdown", 1)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let service = create_service(Default::default(), Some(TlsServerConfig::new(vec![], vec![], None)));
+    let (status, _, body) = send(&service, Method::POST, REMOTE, Some(&admin), request("shutdown", 1)).await;
+    assert_eq!((status, body["error"]["code"].as_i64()), (StatusCode::OK, Some(RPC_ERROR)));
+}
+
+#[tokio::test]
+async fn test_rate_limits() {
+    let limits = RpcLimits { connection_rate: Some(RpcRateLimit::new(1, 2)), max_ip_connections: Some(1), ..Default::default() };
+    let limiter = Arc::new(RpcLimiter::new(limits));
+    let service = create_service(limiter.clone(), None);
+
+    // The requests of an IP address share a single rate limit
+    for id in 0..2 {
+        let (status, _, body) = send(&service, Method::POST, LOCALHOST, None, request("ping", id)).await;
+        assert_eq!((status, body["result"].clone()), (StatusCode::OK, json!({})));
+    }
+    let (status, headers, body) = send(&service, Method::POST, LOCALHOST, None, request("ping", 2)).await;
+    assert_eq!((status, body["error"]["code"].as_i64()), (StatusCode::TOO_MANY_REQUESTS, Some(RATE_LIMIT_EXCEEDED)));
+    assert_eq!(headers[RETRY_AFTER], "1");
+
+    // Every request of a batch is charged
+    let (status, _, body) =
+        send(&service, Method::POST, REMOTE, None, json!([request("ping", 1), request("ping", 2), request("ping", 3)])).await;
+    assert_eq!(status, StatusCode::OK);
+    let codes: Vec<_> = body.as_array().unwrap().iter().map(|response| response["error"]["code"].as_i64()).collect();
+    assert_eq!(codes, vec![None, None, Some(RATE_LIMIT_EXCEEDED)]);
+
+    // The HTTP requests of an IP address count as one connection against its quota
+    let other = [192, 168, 0, 2];
+    let connection = limiter.register(IpAddr::from(other)).unwrap();
+    let (status, _, _) = send(&service, Method::POST, other, None, request("ping", 1)).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    drop(connection);
+    let (status, _, _) = send(&service, Method::POST, other, None, request("ping", 1)).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_router_dispatch() {
+    let router = Router::new(Arc::new(RpcCoreMock));
+    let limiter = Arc::new(RpcLimiter::default()).register(IpAddr::from(LOCALHOST)).unwrap();
+    let handle = |body: Value, permissions: RpcPermissions| {
+        let router = router.clone();
+        let limiter = &limiter;
+        async move { router.handle(body.to_string().as_bytes(), permissions, limiter).await.unwrap() }
+    };
+
+    // Methods are named after their ops in camel case and take named parameters
+    let response = handle(request("getInfo", 1), RpcPermissions::ALL).await;
+    assert_eq!(response["result"]["mempoolSize"], 1234);
+    let response = handle(json!({"jsonrpc": "2.0", "method": "ping", "params": {}, "id": 1}), RpcPermissions::ALL).await;
+    assert_eq!(response["result"], json!({}));
+    let response = handle(json!({"jsonrpc": "2.0", "method": "ping", "params": [], "id": 1}), RpcPermissions::ALL).await;
+    assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    let response = handle(json!({"jsonrpc": "2.0", "method": "getBlock", "params": {"hash": 1}, "id": 1}), RpcPermissions::ALL).await;
+    assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+    // Unknown methods and subscriptions are not found
+    for method in ["nope", "GetInfo", "subscribe", "blockAddedNotification"] {
+        let response = handle(request(method, 1), RpcPermissions::ALL).await;
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND, "{method}");
+    }
+
+    // Calls are checked against the permissions of the caller
+    let response = handle(request("getInfo", 1), RpcPermissions::NONE).await;
+    assert_eq!(response["error"]["code"], PERMISSION_DENIED);
+    let response = handle(request("getInfo", 1), RpcPermission::Read.into()).await;
+    assert!(response["error"].is_null());
+}
@@ -53,6 +53,8 @@ impl ToTokens for RpcTable {
                     interface.method(#rpc_api_ops::#handler, method!(|server_ctx: #server_ctx_type, connection_ctx: #connection_ctx_type, request: #request_type| async move {
                         let verbose = server_ctx.verbose();
                         if verbose { workflow_log::log_info!("request: {:?}",request); }
+                        connection_ctx.check_permission(#rpc_api_ops::#handler).map_err(|e|ServerError::Text(e.to_string()))?;
+                        connection_ctx.check_rate_limit(#rpc_api_ops::#handler)?;
                         let response: #response_type = server_ctx.rpc_service(&connection_ctx).#fn_call(request).await
                             .map_err(|e|ServerError::Text(e.to_string()))?;
                         if verbose { workflow_log::log_info!("response: {:?}",response); }
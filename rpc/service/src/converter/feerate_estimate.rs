@@ -0,0 +1,27 @@
+use waglayla_mining::feerate::{FeerateBucket, FeerateEstimations};
+use waglayla_rpc_core::{RpcFeeEstimate, RpcFeerateBucket};
+
+pub trait FeeEstimateConverter {
+    type Output;
+    fn into_rpc(self) -> Self::Output;
+}
+
+impl FeeEstimateConverter for FeerateBucket {
+    type Output = RpcFeerateBucket;
+
+    fn into_rpc(self) -> Self::Output {
+        RpcFeerateBucket { feerate: self.feerate, estimated_seconds: self.estimated_seconds }
+    }
+}
+
+impl FeeEstimateConverter for FeerateEstimations {
+    type Output = RpcFeeEstimate;
+
+    fn into_rpc(self) -> Self::Output {
+        RpcFeeEstimate {
+            priority_bucket: self.priority_bucket.into_rpc(),
+            normal_buckets: self.normal_buckets.into_iter().map(FeeEstimateConverter::into_rpc).collect(),
+            low_buckets: self.low_buckets.into_iter().map(FeeEstimateConverter::into_rpc).collect(),
+        }
+    }
+}
@@ -1,9 +1,14 @@
 use async_trait::async_trait;
-use waglayla_consensus_core::config::Config;
+use waglayla_addressindex::model::{AddressHistoryPage, AddressHistoryUtxo};
+use waglayla_consensus_core::{config::Config, tx::ScriptPublicKey};
 use waglayla_index_core::indexed_utxos::UtxoSetByScriptPublicKey;
 use waglayla_index_core::notification::{self as index_notify, Notification as IndexNotification};
 use waglayla_notify::converter::Converter;
-use waglayla_rpc_core::{utxo_set_into_rpc, Notification, RpcUtxosByAddressesEntry, UtxosChangedNotification};
+use waglayla_rpc_core::{
+    utxo_set_into_rpc, Notification, RpcAddress, RpcAddressTransaction, RpcUtxoEntry, RpcUtxosByAddressesEntry,
+    UtxosChangedNotification,
+};
+use waglayla_txscript::extract_script_pub_key_address;
 use std::sync::Arc;
 
 /// Conversion of consensus_core to rpc_core structures
@@ -27,6 +32,38 @@ impl IndexConverter {
     pub fn get_utxos_by_addresses_entries(&self, item: &UtxoSetByScriptPublicKey) -> Vec<RpcUtxosByAddressesEntry> {
         utxo_set_into_rpc(item, Some(self.config.prefix()))
     }
+
+    pub fn get_address_transactions(&self, page: AddressHistoryPage) -> Vec<RpcAddressTransaction> {
+        page.transactions
+            .into_iter()
+            .filter_map(|(script_public_key, transaction)| {
+                let address = extract_script_pub_key_address(&script_public_key, self.config.prefix()).ok()?;
+                let into_rpc = |utxos: Vec<AddressHistoryUtxo>| {
+                    utxos.into_iter().map(|utxo| address_history_utxo_into_rpc(utxo, &address, &script_public_key)).collect()
+                };
+                Some(RpcAddressTransaction {
+                    transaction_id: transaction.transaction_id,
+                    accepting_block_hash: transaction.accepting_block_hash,
+                    accepting_block_daa_score: transaction.accepting_block_daa_score,
+                    received: into_rpc(transaction.received),
+                    spent: into_rpc(transaction.spent),
+                    address,
+                })
+            })
+            .collect()
+    }
+}
+
+fn address_history_utxo_into_rpc(
+    utxo: AddressHistoryUtxo,
+    address: &RpcAddress,
+    script_public_key: &ScriptPublicKey,
+) -> RpcUtxosByAddressesEntry {
+    RpcUtxosByAddressesEntry {
+        address: Some(address.clone()),
+        outpoint: utxo.outpoint,
+        utxo_entry: RpcUtxoEntry::new(utxo.amount, script_public_key.clone(), utxo.block_daa_score, utxo.is_coinbase),
+    }
 }
 
 #[async_trait]
@@ -25,6 +25,7 @@ impl ProtocolConverter {
             user_agent: properties.user_agent.clone(),
             advertised_protocol_version: properties.advertised_protocol_version,
             time_connected: peer.time_connected(),
+            misbehaviour_score: peer.misbehaviour_score(),
         }
     }
 
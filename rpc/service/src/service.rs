@@ -1,9 +1,12 @@
 //! Core server implementation for ClientAPI
 
 use super::collector::{CollectorFromConsensus, CollectorFromIndex};
+use crate::converter::feerate_estimate::FeeEstimateConverter;
 use crate::converter::{consensus::ConsensusConverter, index::IndexConverter, protocol::ProtocolConverter};
 use crate::service::NetworkType::{Mainnet, Testnet};
 use async_trait::async_trait;
+use waglayla_addressindex::api::AddressIndexProxy;
+use waglayla_chainindex::api::ChainIndexProxy;
 use waglayla_consensus_core::api::counters::ProcessingCounters;
 use waglayla_consensus_core::errors::block::RuleError;
 use waglayla_consensus_core::{
@@ -35,7 +38,10 @@ use waglayla_index_core::{
     notifier::IndexNotifier,
 };
 use waglayla_mining::model::tx_query::TransactionQuery;
-use waglayla_mining::{manager::MiningManagerProxy, mempool::tx::Orphan};
+use waglayla_mining::{
+    manager::MiningManagerProxy,
+    mempool::tx::{Orphan, RbfPolicy},
+};
 use waglayla_notify::listener::ListenerLifespan;
 use waglayla_notify::subscription::context::SubscriptionContext;
 use waglayla_notify::subscription::{MutationPolicies, UtxosChangedMutationPolicy};
@@ -54,7 +60,7 @@ use waglayla_perf_monitor::{counters::CountersSnapshot, Monitor as PerfMonitor};
 use waglayla_rpc_core::{
     api::{
         ops::RPC_API_VERSION,
-        rpc::{RpcApi, MAX_SAFE_WINDOW_SIZE},
+        rpc::{RpcApi, MAX_ADDRESS_TRANSACTIONS_PAGE_SIZE, MAX_CHAIN_BLOCK_HEADERS_PAGE_SIZE, MAX_SAFE_WINDOW_SIZE},
     },
     model::*,
     notify::connection::ChannelConnection,
@@ -63,11 +69,13 @@ use waglayla_rpc_core::{
 use waglayla_txscript::{extract_script_pub_key_address, pay_to_address_script};
 use waglayla_utils::{channel::Channel, triggers::SingleTrigger};
 use waglayla_utils_tower::counters::TowerConnectionCounters;
+use waglayla_txindex::api::TxIndexProxy;
 use waglayla_utxoindex::api::UtxoIndexProxy;
 use std::{
     collections::HashMap,
     iter::once,
     sync::{atomic::Ordering, Arc},
+    time::Duration,
     vec,
 };
 use tokio::join;
@@ -96,6 +104,9 @@ pub struct RpcCoreService {
     mining_manager: MiningManagerProxy,
     flow_context: Arc<FlowContext>,
     utxoindex: Option<UtxoIndexProxy>,
+    txindex: Option<TxIndexProxy>,
+    addressindex: Option<AddressIndexProxy>,
+    chainindex: Option<ChainIndexProxy>,
     config: Arc<Config>,
     consensus_converter: Arc<ConsensusConverter>,
     index_converter: Arc<IndexConverter>,
@@ -113,6 +124,10 @@ pub struct RpcCoreService {
 
 const RPC_CORE: &str = "rpc-core";
 
+/// Shortest subnet prefixes which can be banned via RPC, so that a single call cannot ban a large part of the network
+const MIN_BAN_PREFIX_LEN_IPV4: u8 = 8;
+const MIN_BAN_PREFIX_LEN_IPV6: u8 = 16;
+
 impl RpcCoreService {
     pub const IDENT: &'static str = "rpc-core-service";
 
@@ -125,6 +140,9 @@ impl RpcCoreService {
         flow_context: Arc<FlowContext>,
         subscription_context: SubscriptionContext,
         utxoindex: Option<UtxoIndexProxy>,
+        txindex: Option<TxIndexProxy>,
+        addressindex: Option<AddressIndexProxy>,
+        chainindex: Option<ChainIndexProxy>,
         config: Arc<Config>,
         core: Arc<Core>,
         processing_counters: Arc<ProcessingCounters>,
@@ -195,6 +213,9 @@ impl RpcCoreService {
             mining_manager,
             flow_context,
             utxoindex,
+            txindex,
+            addressindex,
+            chainindex,
             config,
             consensus_converter,
             index_converter,
@@ -506,6 +527,26 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         Ok(SubmitTransactionResponse::new(transaction_id))
     }
 
+    async fn submit_transaction_replacement_call(
+        &self,
+        request: SubmitTransactionReplacementRequest,
+    ) -> RpcResult<SubmitTransactionReplacementResponse> {
+        let transaction: Transaction = (&request.transaction).try_into()?;
+        let transaction_id = transaction.id();
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let rbf_policy = match request.mode {
+            RpcReplacementMode::Mandatory => RbfPolicy::Mandatory,
+            RpcReplacementMode::Optional => RbfPolicy::Allowed,
+        };
+        let replaced_transaction =
+            self.flow_context.submit_rpc_transaction_replacement(&session, transaction, rbf_policy).await.map_err(|err| {
+                let err = RpcError::RejectedTransaction(transaction_id, err.to_string());
+                debug!("{err}");
+                err
+            })?;
+        Ok(SubmitTransactionReplacementResponse::new(transaction_id, replaced_transaction.map(|tx| (&*tx).into())))
+    }
+
     async fn get_current_network_call(&self, _: GetCurrentNetworkRequest) -> RpcResult<GetCurrentNetworkResponse> {
         Ok(GetCurrentNetworkResponse::new(*self.config.net))
     }
@@ -647,6 +688,133 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         Ok(GetDaaScoreTimestampEstimateResponse::new(timestamps))
     }
 
+    async fn get_fee_estimate_call(&self, _request: GetFeeEstimateRequest) -> RpcResult<GetFeeEstimateResponse> {
+        let estimate = self.mining_manager.clone().get_realtime_feerate_estimations().await.into_rpc();
+        Ok(GetFeeEstimateResponse { estimate })
+    }
+
+    async fn get_transaction_call(&self, request: GetTransactionRequest) -> RpcResult<GetTransactionResponse> {
+        if !self.config.txindex {
+            return Err(RpcError::NoTxIndex);
+        }
+        let entry = self
+            .txindex
+            .clone()
+            .unwrap()
+            .get_transaction_entry(request.transaction_id)
+            .await
+            .map_err(|e| RpcError::General(e.to_string()))?
+            .ok_or(RpcError::TransactionNotFound(request.transaction_id))?;
+
+        // Read the transaction body from the block it was accepted from, falling back to any block including it
+        let session = self.consensus_manager.consensus().session().await;
+        let locations = entry
+            .acceptance
+            .iter()
+            .map(|x| (x.including_block_hash, x.index_within_block))
+            .chain(entry.inclusions.iter().map(|x| (x.block_hash, x.index_within_block)));
+        for (block_hash, index_within_block) in locations {
+            let Ok(block) = session.async_get_block(block_hash).await else { continue };
+            if let Some(transaction) = block.transactions.get(index_within_block as usize) {
+                let transaction = self.consensus_converter.get_transaction(&session, transaction, Some(&block.header), true);
+                return Ok(GetTransactionResponse::new(
+                    transaction,
+                    entry.inclusions.iter().map(|x| x.block_hash).collect(),
+                    entry.acceptance.map(|x| x.accepting_block_hash),
+                ));
+            }
+        }
+        Err(RpcError::TransactionNotFound(request.transaction_id))
+    }
+
+    async fn get_transactions_by_addresses_call(
+        &self,
+        request: GetTransactionsByAddressesRequest,
+    ) -> RpcResult<GetTransactionsByAddressesResponse> {
+        if !self.config.addressindex {
+            return Err(RpcError::NoAddressIndex);
+        }
+        let script_public_keys = request.addresses.iter().map(pay_to_address_script).collect();
+        let limit = request.limit.clamp(1, MAX_ADDRESS_TRANSACTIONS_PAGE_SIZE) as usize;
+        let page = self
+            .addressindex
+            .clone()
+            .unwrap()
+            .get_address_history(script_public_keys, request.from_daa_score, limit)
+            .await
+            .map_err(|e| RpcError::General(e.to_string()))?;
+        let next_daa_score = page.next_daa_score;
+        Ok(GetTransactionsByAddressesResponse::new(self.index_converter.get_address_transactions(page), next_daa_score))
+    }
+
+    async fn get_chain_block_by_daa_score_call(
+        &self,
+        request: GetChainBlockByDaaScoreRequest,
+    ) -> RpcResult<GetChainBlockByDaaScoreResponse> {
+        if !self.config.chainindex {
+            return Err(RpcError::NoChainIndex);
+        }
+        let chain_block = self
+            .chainindex
+            .clone()
+            .unwrap()
+            .get_chain_block_by_daa_score(request.daa_score)
+            .await
+            .map_err(|e| RpcError::General(e.to_string()))?;
+        let header = match chain_block {
+            Some(chain_block) => {
+                let session = self.consensus_manager.consensus().unguarded_session();
+                Some((*session.async_get_header(chain_block.hash).await?).clone())
+            }
+            None => None,
+        };
+        Ok(GetChainBlockByDaaScoreResponse::new(header))
+    }
+
+    async fn get_chain_block_by_timestamp_call(
+        &self,
+        request: GetChainBlockByTimestampRequest,
+    ) -> RpcResult<GetChainBlockByTimestampResponse> {
+        if !self.config.chainindex {
+            return Err(RpcError::NoChainIndex);
+        }
+        let chain_block = self
+            .chainindex
+            .clone()
+            .unwrap()
+            .get_chain_block_by_timestamp(request.timestamp)
+            .await
+            .map_err(|e| RpcError::General(e.to_string()))?;
+        let header = match chain_block {
+            Some(chain_block) => {
+                let session = self.consensus_manager.consensus().unguarded_session();
+                Some((*session.async_get_header(chain_block.hash).await?).clone())
+            }
+            None => None,
+        };
+        Ok(GetChainBlockByTimestampResponse::new(header))
+    }
+
+    async fn get_chain_block_headers_call(&self, request: GetChainBlockHeadersRequest) -> RpcResult<GetChainBlockHeadersResponse> {
+        if !self.config.chainindex {
+            return Err(RpcError::NoChainIndex);
+        }
+        let limit = request.limit.clamp(1, MAX_CHAIN_BLOCK_HEADERS_PAGE_SIZE) as usize;
+        let chain_blocks = self
+            .chainindex
+            .clone()
+            .unwrap()
+            .get_chain_blocks(request.from_daa_score, request.to_daa_score, limit)
+            .await
+            .map_err(|e| RpcError::General(e.to_string()))?;
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let mut headers = Vec::with_capacity(chain_blocks.len());
+        for chain_block in chain_blocks {
+            headers.push((*session.async_get_header(chain_block.hash).await?).clone());
+        }
+        Ok(GetChainBlockHeadersResponse::new(headers))
+    }
+
     async fn ping_call(&self, _: PingRequest) -> RpcResult<PingResponse> {
         Ok(PingResponse {})
     }
@@ -711,7 +879,7 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         }
         let peer_address = request.peer_address.normalize(self.config.net.default_p2p_port());
         if let Some(connection_manager) = self.flow_context.connection_manager() {
-            connection_manager.add_connection_request(peer_address.into(), request.is_permanent).await;
+            connection_manager.add_connection_request(peer_address, request.is_permanent).await;
         } else {
             return Err(RpcError::NoConnectionManager);
         }
@@ -719,7 +887,7 @@ NOTE: This error usually indicates an RPC conversion error between the node and
     }
 
     async fn get_peer_addresses_call(&self, _: GetPeerAddressesRequest) -> RpcResult<GetPeerAddressesResponse> {
-        let address_manager = self.flow_context.address_manager.lock();
+        let mut address_manager = self.flow_context.address_manager.lock();
         Ok(GetPeerAddressesResponse::new(address_manager.get_all_addresses(), address_manager.get_all_banned_addresses()))
     }
 
@@ -728,12 +896,20 @@ NOTE: This error usually indicates an RPC conversion error between the node and
             warn!("Ban RPC command called while node in safe RPC mode -- ignoring.");
             return Err(RpcError::UnavailableInSafeMode);
         }
+        let min_prefix_len = if request.ip.network().is_ipv4() { MIN_BAN_PREFIX_LEN_IPV4 } else { MIN_BAN_PREFIX_LEN_IPV6 };
+        if request.ip.prefix_len() < min_prefix_len {
+            return Err(RpcError::SubnetTooBroad(request.ip, min_prefix_len));
+        }
         if let Some(connection_manager) = self.flow_context.connection_manager() {
-            let ip = request.ip.into();
-            if connection_manager.ip_has_permanent_connection(ip).await {
+            if connection_manager.is_whitelisted_subnet(request.ip) {
+                return Err(RpcError::IpIsWhitelisted(request.ip));
+            }
+            if connection_manager.subnet_has_permanent_connection(request.ip).await {
                 return Err(RpcError::IpHasPermanentConnection(request.ip));
             }
-            connection_manager.ban(ip).await;
+            let duration = Duration::from_secs(request.duration.unwrap_or(self.config.ban_duration));
+            let reason = request.reason.unwrap_or_else(|| "banned via RPC".to_string());
+            connection_manager.ban(request.ip, duration, reason).await;
         } else {
             return Err(RpcError::NoConnectionManager);
         }
@@ -745,15 +921,21 @@ NOTE: This error usually indicates an RPC conversion error between the node and
             warn!("Unban RPC command called while node in safe RPC mode -- ignoring.");
             return Err(RpcError::UnavailableInSafeMode);
         }
-        let mut address_manager = self.flow_context.address_manager.lock();
-        if address_manager.is_banned(request.ip) {
-            address_manager.unban(request.ip)
-        } else {
+        if !self.flow_context.address_manager.lock().unban(request.ip) {
             return Err(RpcError::IpIsNotBanned(request.ip));
         }
         Ok(UnbanResponse {})
     }
 
+    async fn get_banned_peers_call(&self, _: GetBannedPeersRequest) -> RpcResult<GetBannedPeersResponse> {
+        let banned_subnets = self.flow_context.address_manager.lock().get_banned_subnets();
+        let banned_peers = banned_subnets
+            .into_iter()
+            .map(|(subnet, entry)| RpcBannedPeer { subnet, expiry: entry.expiry, reason: entry.reason })
+            .collect();
+        Ok(GetBannedPeersResponse::new(banned_peers))
+    }
+
     async fn get_connected_peer_info_call(&self, _: GetConnectedPeerInfoRequest) -> RpcResult<GetConnectedPeerInfoResponse> {
         let peers = self.flow_context.hub().active_peers();
         let peer_info = self.protocol_converter.get_peers_info(&peers);
@@ -7,20 +7,39 @@ use waglayla_notify::{
     subscription::{context::SubscriptionContext, MutationPolicies, UtxosChangedMutationPolicy},
 };
 use waglayla_rpc_core::{
-    api::ctl::RpcCtl,
+    api::{ctl::RpcCtl, limits::RpcRateLimitExceeded},
+    error::RpcError,
     notify::collector::{RpcCoreCollector, RpcCoreConverter},
 };
+#[cfg(not(target_arch = "wasm32"))]
+use crate::tls::TlsTunnel;
+#[cfg(not(target_arch = "wasm32"))]
+use waglayla_utils::tls::TlsClientConfig;
 pub use waglayla_rpc_macros::build_wrpc_client_interface;
+use borsh::BorshDeserialize;
 use std::fmt::Debug;
 use workflow_core::{channel::Multiplexer, runtime as application_runtime};
 use workflow_dom::utils::window;
-use workflow_rpc::client::Ctl as WrpcCtl;
+use workflow_rpc::client::{error::Error as ClientError, Ctl as WrpcCtl};
+use workflow_rpc::error::ServerError;
 pub use workflow_rpc::client::{
     ConnectOptions, ConnectResult, ConnectStrategy, Resolver as RpcResolver, ResolverResult, WebSocketConfig, WebSocketError,
 };
 
 type RpcClientNotifier = Arc<Notifier<Notification, ChannelConnection>>;
 
+/// Maps a failed wRPC call to a [`RpcError`], restoring the [`RpcError::RateLimitExceeded`]
+/// a Borsh server encodes as [`RpcRateLimitExceeded`] data.
+pub(crate) fn into_rpc_error(err: ClientError) -> RpcError {
+    match &err {
+        ClientError::RpcCall(ServerError::Data(data)) => match RpcRateLimitExceeded::try_from_slice(data) {
+            Ok(rate_limit_exceeded) => rate_limit_exceeded.into(),
+            Err(_) => RpcError::RpcSubsystem(err.to_string()),
+        },
+        _ => RpcError::RpcSubsystem(err.to_string()),
+    }
+}
+
 struct Inner {
     rpc_client: Arc<RpcClient<RpcApiOps>>,
     notification_relay_channel: Channel<Notification>,
@@ -39,6 +58,9 @@ struct Inner {
     resolver: Mutex<Option<Resolver>>,
     network_id: Mutex<Option<NetworkId>>,
     node_descriptor: Mutex<Option<Arc<NodeDescriptor>>>,
+    credentials: Mutex<Option<RpcCredentials>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    tls_tunnel: Mutex<Option<TlsTunnel>>,
 }
 
 impl Inner {
@@ -67,6 +89,7 @@ impl Inner {
             RpcApiOps::VirtualDaaScoreChangedNotification,
             RpcApiOps::PruningPointUtxoSetOverrideNotification,
             RpcApiOps::NewBlockTemplateNotification,
+            RpcApiOps::TransactionsEvictedNotification,
         ]
         .into_iter()
         .for_each(|notification_op| {
@@ -109,6 +132,9 @@ impl Inner {
             resolver: Mutex::new(resolver),
             network_id: Mutex::new(network_id),
             node_descriptor: Mutex::new(None),
+            credentials: Mutex::new(None),
+            #[cfg(not(target_arch = "wasm32"))]
+            tls_tunnel: Mutex::new(None),
         };
         Ok(client)
     }
@@ -121,7 +147,7 @@ impl Inner {
 
     /// Start sending notifications of some type to the client.
     async fn start_notify_to_client(&self, scope: Scope) -> RpcResult<()> {
-        let _response: SubscribeResponse = self.rpc_client.call(RpcApiOps::Subscribe, scope).await.map_err(|err| err.to_string())?;
+        let _response: SubscribeResponse = self.rpc_client.call(RpcApiOps::Subscribe, scope).await.map_err(into_rpc_error)?;
         Ok(())
     }
 
@@ -132,6 +158,12 @@ impl Inner {
         Ok(())
     }
 
+    async fn authenticate(&self, credentials: RpcCredentials) -> RpcResult<RpcPermissions> {
+        let response: AuthenticateResponse =
+            self.rpc_client.call(RpcApiOps::Authenticate, AuthenticateRequest::new(credentials)).await.map_err(into_rpc_error)?;
+        Ok(response.permissions)
+    }
+
     fn default_url(&self) -> Option<String> {
         self.default_url.lock().unwrap().clone()
     }
@@ -227,6 +259,15 @@ impl RpcResolver for Inner {
 
         self.rpc_ctl.set_descriptor(Some(url.clone()));
         self.set_current_url(Some(&url));
+
+        // Connect to TLS servers using custom CA roots through a tunnel
+        #[cfg(not(target_arch = "wasm32"))]
+        if url.starts_with("wss://") {
+            let tls_tunnel = self.tls_tunnel.lock().unwrap().clone();
+            if let Some(tls_tunnel) = tls_tunnel {
+                return tls_tunnel.open(&url).await.map_err(WebSocketError::custom);
+            }
+        }
         Ok(url)
     }
 }
@@ -355,6 +396,14 @@ impl WaglaylaRpcClient {
         Ok(())
     }
 
+    /// Sets the CA certificates verifying `wss://` servers, replacing the platform roots, and the optional client
+    /// identity presented to them. Takes effect on the next connection.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_tls_config(&self, tls: Option<TlsClientConfig>) -> Result<()> {
+        *self.inner.tls_tunnel.lock().unwrap() = tls.as_ref().map(TlsTunnel::new).transpose()?;
+        Ok(())
+    }
+
     pub fn node_descriptor(&self) -> Option<Arc<NodeDescriptor>> {
         self.inner.node_descriptor.lock().unwrap().clone()
     }
@@ -460,6 +509,14 @@ impl WaglaylaRpcClient {
         Ok(())
     }
 
+    /// Authenticates the connection, granting it the permissions of `credentials`, and returns the resulting
+    /// permissions of the connection. The credentials are presented again on every reconnection.
+    pub async fn authenticate(&self, credentials: RpcCredentials) -> RpcResult<RpcPermissions> {
+        let permissions = self.inner.authenticate(credentials.clone()).await?;
+        self.inner.credentials.lock().unwrap().replace(credentials);
+        Ok(permissions)
+    }
+
     pub fn notification_channel_receiver(&self) -> Receiver<Notification> {
         self.inner.notification_intake_channel.lock().unwrap().receiver.clone()
     }
@@ -539,6 +596,12 @@ impl WaglaylaRpcClient {
                         if let Ok(msg) = msg {
                             match msg {
                                 WrpcCtl::Connect => {
+                                    let credentials = inner.credentials.lock().unwrap().clone();
+                                    if let Some(credentials) = credentials {
+                                        if let Err(err) = inner.authenticate(credentials).await {
+                                            log_error!("WaglaylaRpcClient authentication error: {err}");
+                                        }
+                                    }
                                     inner.rpc_ctl.signal_open().await.expect("(WaglaylaRpcClient) rpc_ctl.signal_open() error");
                                 }
                                 WrpcCtl::Disconnect => {
@@ -597,6 +660,7 @@ impl RpcApi for WaglaylaRpcClient {
             GetCoinSupply,
             GetConnectedPeerInfo,
             GetDaaScoreTimestampEstimate,
+            GetFeeEstimate,
             GetServerInfo,
             GetCurrentNetwork,
             GetHeaders,
@@ -609,6 +673,12 @@ impl RpcApi for WaglaylaRpcClient {
             GetSink,
             GetSyncStatus,
             GetSubnetwork,
+            GetTransaction,
+            GetTransactionsByAddresses,
+            GetChainBlockByDaaScore,
+            GetChainBlockByTimestamp,
+            GetChainBlockHeaders,
+            GetBannedPeers,
             GetUtxosByAddresses,
             GetSinkBlueScore,
             GetVirtualChainFromBlock,
@@ -617,6 +687,7 @@ impl RpcApi for WaglaylaRpcClient {
             Shutdown,
             SubmitBlock,
             SubmitTransaction,
+            SubmitTransactionReplacement,
             Unban,
         ]
     );
@@ -655,3 +726,24 @@ impl RpcApi for WaglaylaRpcClient {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSerialize;
+
+    #[test]
+    fn test_into_rpc_error() {
+        let data = RpcRateLimitExceeded { op: RpcApiOps::GetBlock, retry_after_ms: 250 }.try_to_vec().unwrap();
+        let err = into_rpc_error(ClientError::RpcCall(ServerError::Data(data)));
+        assert!(matches!(err, RpcError::RateLimitExceeded(RpcApiOps::GetBlock, 250)));
+        assert_eq!(err.retry_after_ms(), Some(250));
+
+        let err = into_rpc_error(ClientError::RpcCall(ServerError::Data(vec![0xff])));
+        assert!(matches!(err, RpcError::RpcSubsystem(_)));
+        assert_eq!(err.retry_after_ms(), None);
+
+        let err = into_rpc_error(ClientError::RpcCall(ServerError::Text("not allowed".to_string())));
+        assert!(matches!(err, RpcError::RpcSubsystem(msg) if msg.contains("not allowed")));
+    }
+}
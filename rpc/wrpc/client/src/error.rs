@@ -61,6 +61,9 @@ pub enum Error {
 
     #[error(transparent)]
     NetworkId(#[from] waglayla_consensus_core::network::NetworkIdError),
+
+    #[error("TLS -> {0}")]
+    Tls(String),
 }
 
 impl Error {
@@ -18,6 +18,7 @@ pub use waglayla_notify::{
     subscriber::{Subscriber, SubscriptionManager},
 };
 pub use waglayla_rpc_core::{
+    api::auth::{RpcCredentials, RpcPermissions},
     api::ops::RpcApiOps,
     api::rpc::RpcApi,
     error::RpcResult,
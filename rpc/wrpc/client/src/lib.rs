@@ -7,3 +7,5 @@ pub mod node;
 pub mod parse;
 pub mod prelude;
 pub mod resolver;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tls;
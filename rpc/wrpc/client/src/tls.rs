@@ -0,0 +1,205 @@
+//!
+//! TLS connections to wRPC servers using custom CA roots. The native websocket transport only trusts the platform
+//! roots, so a `wss://` connection using a [`TlsClientConfig`] is made through a loopback tunnel relaying a single
+//! plaintext websocket connection to the server over TLS. The tunnel url holds a random token which the connection
+//! must present, so that no other local process can take over the tunnel.
+//!
+
+use crate::error::Error;
+use crate::parse::{parse_host, Host};
+use crate::result::Result;
+use waglayla_utils::tls::TlsClientConfig;
+use std::{io::Cursor, sync::Arc, time::Duration};
+use tokio::{
+    io::{copy_bidirectional, AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+use tokio_rustls::{
+    rustls::{Certificate, ClientConfig, PrivateKey, RootCertStore, ServerName},
+    TlsConnector,
+};
+use workflow_log::log_warn;
+
+/// Time allowed for the websocket transport to connect to an opened tunnel
+const TUNNEL_ACCEPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum length of the request line of the websocket handshake made through a tunnel
+const MAX_REQUEST_LINE_LEN: usize = 4096;
+
+#[derive(Clone)]
+pub struct TlsTunnel {
+    connector: TlsConnector,
+    domain: Option<String>,
+}
+
+impl TlsTunnel {
+    pub fn new(tls: &TlsClientConfig) -> Result<Self> {
+        let ca = tls.ca.as_ref().ok_or_else(|| Error::Tls("CA certificates are required".to_string()))?;
+        let mut roots = RootCertStore::empty();
+        let (added, _) = roots.add_parsable_certificates(&rustls_pemfile::certs(&mut Cursor::new(ca)).map_err(Error::custom)?);
+        if added == 0 {
+            return Err(Error::Tls("no valid CA certificate".to_string()));
+        }
+        let builder = ClientConfig::builder().with_safe_defaults().with_root_certificates(roots);
+        let config = match tls.identity.as_ref() {
+            Some((cert, key)) => {
+                let certs =
+                    rustls_pemfile::certs(&mut Cursor::new(cert)).map_err(Error::custom)?.into_iter().map(Certificate).collect();
+                builder.with_client_auth_cert(certs, private_key(key)?).map_err(|err| Error::Tls(err.to_string()))?
+            }
+            None => builder.with_no_client_auth(),
+        };
+        Ok(Self { connector: TlsConnector::from(Arc::new(config)), domain: tls.domain.clone() })
+    }
+
+    /// Opens a tunnel to the server of the `wss://` `url` and returns the loopback `ws://` url to connect to instead
+    pub async fn open(&self, url: &str) -> Result<String> {
+        let parsed = parse_host(url).map_err(|err| Error::UrlError(err.to_string()))?;
+        let remote_address = format!("{}:{}", parsed.host, parsed.port.unwrap_or(443));
+        let name = match (self.domain.as_ref(), &parsed.host) {
+            (Some(domain), _) => domain.clone(),
+            (None, Host::Ipv6(ip)) => ip.to_string(),
+            (None, host) => host.to_string(),
+        };
+        let server_name = ServerName::try_from(name.as_str()).map_err(|err| Error::Tls(format!("{name}: {err}")))?;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.map_err(Error::custom)?;
+        let token = format!("{:032x}", rand::random::<u128>());
+        let tunnel_url = format!("ws://{}/{token}{}", listener.local_addr().map_err(Error::custom)?, parsed.path);
+        let connector = self.connector.clone();
+        workflow_core::task::spawn(async move {
+            let Ok(Ok((mut stream, _))) = timeout(TUNNEL_ACCEPT_TIMEOUT, listener.accept()).await else {
+                return;
+            };
+            let relay = async {
+                let Some(request_line) = read_request_line(&mut stream, &token).await? else {
+                    log_warn!("wRPC TLS tunnel to {remote_address} refused a connection without its token");
+                    return Ok(());
+                };
+                let remote = TcpStream::connect(&remote_address).await?;
+                let mut remote = connector.connect(server_name, remote).await?;
+                remote.write_all(&request_line).await?;
+                copy_bidirectional(&mut stream, &mut remote).await.map(drop)
+            };
+            if let Err(err) = relay.await {
+                log_warn!("wRPC TLS connection to {remote_address} failed: {err}");
+            }
+        });
+        Ok(tunnel_url)
+    }
+}
+
+/// Reads the request line of the websocket handshake and returns it with the tunnel `token` removed from its path,
+/// or `None` if the request does not present the token
+async fn read_request_line(stream: &mut TcpStream, token: &str) -> std::io::Result<Option<Vec<u8>>> {
+    let mut request_line = Vec::new();
+    while !request_line.ends_with(b"\r\n") {
+        if request_line.len() >= MAX_REQUEST_LINE_LEN {
+            return Ok(None);
+        }
+        request_line.push(stream.read_u8().await?);
+    }
+    let prefix = format!("GET /{token}");
+    let Some(rest) = request_line.strip_prefix(prefix.as_bytes()).filter(|rest| rest.starts_with(b"/") || rest.starts_with(b" "))
+    else {
+        return Ok(None);
+    };
+    // An empty path is the root path
+    let path_start: &[u8] = if rest.starts_with(b" ") { b"GET /" } else { b"GET " };
+    Ok(Some([path_start, rest].concat()))
+}
+
+fn private_key(pem: &[u8]) -> Result<PrivateKey> {
+    rustls_pemfile::read_all(&mut Cursor::new(pem))
+        .map_err(Error::custom)?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::RSAKey(key) | rustls_pemfile::Item::PKCS8Key(key) | rustls_pemfile::Item::ECKey(key) => {
+                Some(PrivateKey(key))
+            }
+            _ => None,
+        })
+        .ok_or_else(|| Error::Tls("no private key found".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_rustls::{rustls::ServerConfig, TlsAcceptor};
+
+    const CA: &[u8] = include_bytes!("../testdata/tls/ca.pem");
+    const SERVER_CERT: &[u8] = include_bytes!("../testdata/tls/server.pem");
+    const SERVER_KEY: &[u8] = include_bytes!("../testdata/tls/server.key");
+
+    /// Starts a TLS server echoing the data of every connection and returns its port
+    async fn echo_server() -> std::io::Result<u16> {
+        let certs = rustls_pemfile::certs(&mut Cursor::new(SERVER_CERT))?.into_iter().map(Certificate).collect();
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(certs, private_key(SERVER_KEY).unwrap());
+        let acceptor = TlsAcceptor::from(Arc::new(config.unwrap()));
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let acceptor = acceptor.clone();
+                tokio::spawn(async move {
+                    if let Ok(stream) = acceptor.accept(stream).await {
+                        let (mut reader, mut writer) = tokio::io::split(stream);
+                        let _ = tokio::io::copy(&mut reader, &mut writer).await;
+                    }
+                });
+            }
+        });
+        Ok(port)
+    }
+
+    /// Sends a request for `path` through the tunnel and returns the data echoed back by the server
+    async fn echo(tunnel_url: &str, path: &str) -> Vec<u8> {
+        let address = tunnel_url.strip_prefix("ws://").unwrap().split('/').next().unwrap();
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream.write_all(format!("GET {path} HTTP/1.1\r\n").as_bytes()).await.unwrap();
+        let mut buf = vec![0; b"GET /path HTTP/1.1\r\n".len()];
+        match timeout(Duration::from_secs(5), stream.read_exact(&mut buf)).await.unwrap() {
+            Ok(_) => buf,
+            Err(_) => vec![],
+        }
+    }
+
+    /// Returns the path of the tunnel url, holding the tunnel token
+    fn tunnel_path(tunnel_url: &str) -> &str {
+        let address = tunnel_url.strip_prefix("ws://").unwrap();
+        &address[address.find('/').unwrap()..]
+    }
+
+    #[tokio::test]
+    async fn test_tls_tunnel() -> std::io::Result<()> {
+        let port = echo_server().await?;
+        let url = format!("wss://localhost:{port}/path");
+
+        // The tunnel relays the connection to servers verified by the CA, removing its token from the request
+        let tunnel = TlsTunnel::new(&TlsClientConfig::new(CA.to_vec())).unwrap();
+        let tunnel_url = tunnel.open(&url).await.unwrap();
+        assert!(tunnel_url.starts_with("ws://127.0.0.1:") && tunnel_url.ends_with("/path"));
+        assert_eq!(echo(&tunnel_url, tunnel_path(&tunnel_url)).await, b"GET /path HTTP/1.1\r\n");
+
+        // The connection must present the tunnel token
+        let tunnel_url = tunnel.open(&url).await.unwrap();
+        assert!(echo(&tunnel_url, "/path").await.is_empty());
+
+        // The server certificate must be valid for the expected domain and issued by the CA
+        let tunnel = TlsTunnel::new(&TlsClientConfig::new(CA.to_vec()).with_domain("example.com")).unwrap();
+        let tunnel_url = tunnel.open(&url).await.unwrap();
+        assert!(echo(&tunnel_url, tunnel_path(&tunnel_url)).await.is_empty());
+        let tunnel = TlsTunnel::new(&TlsClientConfig::new(SERVER_CERT.to_vec())).unwrap();
+        let tunnel_url = tunnel.open(&url).await.unwrap();
+        assert!(echo(&tunnel_url, tunnel_path(&tunnel_url)).await.is_empty());
+
+        // CA certificates are required
+        assert!(TlsTunnel::new(&TlsClientConfig::default()).is_err());
+        assert!(TlsTunnel::new(&TlsClientConfig::new(SERVER_KEY.to_vec())).is_err());
+        Ok(())
+    }
+}
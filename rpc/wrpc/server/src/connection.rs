@@ -6,7 +6,16 @@ use waglayla_notify::{
     notification::Notification as NotificationT,
     notifier::Notify,
 };
-use waglayla_rpc_core::{api::ops::RpcApiOps, notify::mode::NotificationMode, Notification};
+use waglayla_rpc_core::{
+    api::{
+        auth::RpcPermissions,
+        limits::{RpcConnectionLimiter, RpcRateLimitExceeded},
+        ops::RpcApiOps,
+    },
+    notify::mode::NotificationMode,
+    Notification, RpcError, RpcResult,
+};
+use borsh::BorshSerialize;
 use std::{
     fmt::{Debug, Display},
     sync::{Arc, Mutex},
@@ -50,6 +59,10 @@ struct ConnectionInner {
     pub grpc_client: Option<Arc<GrpcClient>>,
     // not using an atomic in case an Id will change type in the future...
     pub listener_id: Mutex<Option<ListenerId>>,
+    /// The RPC methods this connection is allowed to call
+    pub permissions: Mutex<RpcPermissions>,
+    /// The rate limiter metering the RPC calls of this connection
+    pub limiter: RpcConnectionLimiter,
 }
 
 impl ConnectionInner {
@@ -82,12 +95,20 @@ pub struct Connection {
 }
 
 impl Connection {
-    pub fn new(id: u64, peer: &SocketAddr, messenger: Arc<Messenger>, grpc_client: Option<Arc<GrpcClient>>) -> Connection {
+    pub fn new(
+        id: u64,
+        peer: &SocketAddr,
+        messenger: Arc<Messenger>,
+        grpc_client: Option<Arc<GrpcClient>>,
+        permissions: RpcPermissions,
+        limiter: RpcConnectionLimiter,
+    ) -> Connection {
         // If a GrpcClient is provided, it has to come configured in direct mode
         assert!(grpc_client.is_none() || grpc_client.as_ref().unwrap().notification_mode() == NotificationMode::Direct);
         // Should a gRPC client be provided, no listener_id is required for subscriptions so the listener id is set to default
         let listener_id = Mutex::new(grpc_client.clone().map(|_| ListenerId::default()));
-        Connection { inner: Arc::new(ConnectionInner { id, peer: *peer, messenger, grpc_client, listener_id }) }
+        let permissions = Mutex::new(permissions);
+        Connection { inner: Arc::new(ConnectionInner { id, peer: *peer, messenger, grpc_client, listener_id, permissions, limiter }) }
     }
 
     /// Obtain the connection id
@@ -120,6 +141,38 @@ impl Connection {
         self.inner.listener_id.lock().unwrap().replace(listener_id);
     }
 
+    pub fn permissions(&self) -> RpcPermissions {
+        *self.inner.permissions.lock().unwrap()
+    }
+
+    /// Adds `permissions` to the permissions of this connection, returning the resulting set
+    pub fn grant_permissions(&self, permissions: RpcPermissions) -> RpcPermissions {
+        let mut current = self.inner.permissions.lock().unwrap();
+        *current = current.union(permissions);
+        *current
+    }
+
+    /// Returns an error if this connection is not allowed to call `op`
+    pub fn check_permission(&self, op: RpcApiOps) -> RpcResult<()> {
+        self.permissions().check(op)
+    }
+
+    /// Returns an error if calling `op` exceeds the rate limits of this connection.
+    ///
+    /// Borsh connections get the delay to wait for as an encoded [`RpcRateLimitExceeded`] so the client
+    /// can map it back to a [`RpcError::RateLimitExceeded`]. JSON errors only carry a message.
+    pub fn check_rate_limit(&self, op: RpcApiOps) -> Result<(), ServerError> {
+        match self.inner.limiter.check(op) {
+            Ok(()) => Ok(()),
+            Err(RpcError::RateLimitExceeded(op, retry_after_ms)) if matches!(self.messenger().encoding(), Encoding::Borsh) => {
+                let data =
+                    RpcRateLimitExceeded { op, retry_after_ms }.try_to_vec().map_err(|err| ServerError::Text(err.to_string()))?;
+                Err(ServerError::Data(data))
+            }
+            Err(err) => Err(ServerError::Text(err.to_string())),
+        }
+    }
+
     pub fn peer(&self) -> &SocketAddr {
         &self.inner.peer
     }
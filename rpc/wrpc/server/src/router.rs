@@ -45,6 +45,7 @@ impl Router {
                 GetCoinSupply,
                 GetConnectedPeerInfo,
                 GetDaaScoreTimestampEstimate,
+                GetFeeEstimate,
                 GetServerInfo,
                 GetCurrentNetwork,
                 GetHeaders,
@@ -58,6 +59,12 @@ impl Router {
                 GetSink,
                 GetSubnetwork,
                 GetSyncStatus,
+                GetTransaction,
+                GetTransactionsByAddresses,
+                GetChainBlockByDaaScore,
+                GetChainBlockByTimestamp,
+                GetChainBlockHeaders,
+                GetBannedPeers,
                 GetUtxosByAddresses,
                 GetSinkBlueScore,
                 GetVirtualChainFromBlock,
@@ -66,14 +73,28 @@ impl Router {
                 Shutdown,
                 SubmitBlock,
                 SubmitTransaction,
+                SubmitTransactionReplacement,
                 Unban,
             ]
         );
 
+        interface.method(
+            RpcApiOps::Authenticate,
+            workflow_rpc::server::Method::new(move |manager: Server, connection: Connection, request: AuthenticateRequest| {
+                Box::pin(async move {
+                    connection.check_rate_limit(RpcApiOps::Authenticate)?;
+                    let permissions = manager.authenticate(&connection, &request.credentials).map_err(|err| err.to_string())?;
+                    Ok(AuthenticateResponse::new(permissions))
+                })
+            }),
+        );
+
         interface.method(
             RpcApiOps::Subscribe,
             workflow_rpc::server::Method::new(move |manager: Server, connection: Connection, scope: Scope| {
                 Box::pin(async move {
+                    connection.check_permission(scope.event_type().into()).map_err(|err| err.to_string())?;
+                    connection.check_rate_limit(RpcApiOps::Subscribe)?;
                     manager.start_notify(&connection, scope).await.map_err(|err| err.to_string())?;
                     Ok(SubscribeResponse::new(connection.id()))
                 })
@@ -3,6 +3,7 @@ use crate::{
     connection::Connection,
     result::Result,
     service::Options,
+    tls::TlsPeers,
 };
 use waglayla_grpc_client::GrpcClient;
 use waglayla_notify::{
@@ -15,9 +16,12 @@ use waglayla_notify::{
     subscription::{MutationPolicies, UtxosChangedMutationPolicy},
 };
 use waglayla_rpc_core::{
-    api::rpc::{DynRpcService, RpcApi},
+    api::{
+        auth::{RpcCredentials, RpcPermissions},
+        rpc::{DynRpcService, RpcApi},
+    },
     notify::{channel::NotificationChannel, connection::ChannelConnection, mode::NotificationMode},
-    Notification, RpcResult,
+    Notification, RpcError, RpcResult,
 };
 use waglayla_rpc_service::service::RpcCoreService;
 use std::{
@@ -43,6 +47,7 @@ struct ServerInner {
     pub sockets: Mutex<HashMap<u64, Connection>>,
     pub rpc_core: Option<RpcCore>,
     pub options: Arc<Options>,
+    pub tls_peers: TlsPeers,
 }
 
 #[derive(Clone)]
@@ -98,6 +103,7 @@ impl Server {
                 sockets: Mutex::new(HashMap::new()),
                 rpc_core,
                 options,
+                tls_peers: Default::default(),
             }),
         }
     }
@@ -111,6 +117,11 @@ impl Server {
 
     pub async fn connect(&self, peer: &SocketAddr, messenger: Arc<Messenger>) -> Result<Connection> {
         // log_trace!("WebSocket connected: {}", peer);
+        let peer = &self.inner.tls_peers.resolve(peer);
+        let limiter = self.inner.options.limiter.register(peer.ip()).map_err(|err| {
+            log_warn!("WebSocket {peer} refused: {err}");
+            WebSocketError::Other(err.to_string())
+        })?;
         let id = self.inner.next_connection_id.fetch_add(1, Ordering::SeqCst);
 
         let grpc_client = if let Some(grpc_proxy_address) = &self.inner.options.grpc_proxy_address {
@@ -126,6 +137,7 @@ impl Server {
                 true,
                 None,
                 Default::default(),
+                None,
             )
             .await
             .map_err(|e| WebSocketError::Other(e.to_string()))?;
@@ -134,7 +146,8 @@ impl Server {
         } else {
             None
         };
-        let connection = Connection::new(id, peer, messenger, grpc_client);
+        let permissions = self.inner.options.authenticator.public_permissions();
+        let connection = Connection::new(id, peer, messenger, grpc_client, permissions, limiter);
         if self.inner.options.grpc_proxy_address.is_some() {
             // log_trace!("starting gRPC");
             connection.grpc_client().start(Some(connection.grpc_client_notify_target())).await;
@@ -163,6 +176,11 @@ impl Server {
         // connection.close();
     }
 
+    /// The remote peers of the connections relayed by the TLS listener
+    pub fn tls_peers(&self) -> TlsPeers {
+        self.inner.tls_peers.clone()
+    }
+
     #[inline(always)]
     pub fn notifier(&self) -> Option<Arc<WrpcNotifier>> {
         self.inner.rpc_core.as_ref().map(|x| x.wrpc_notifier.clone())
@@ -198,6 +216,22 @@ impl Server {
         Ok(())
     }
 
+    /// Grants `connection` the permissions of `credentials`. Credentials are only accepted over TLS or from a loopback
+    /// address, all the connections being relayed by the TLS listener when it is enabled.
+    pub fn authenticate(&self, connection: &Connection, credentials: &RpcCredentials) -> RpcResult<RpcPermissions> {
+        if self.inner.options.tls.is_none() && !connection.peer().ip().is_loopback() {
+            log_warn!("WebSocket {} refused to authenticate - credentials sent without TLS", connection.peer());
+            return Err(RpcError::CredentialsRequireTls);
+        }
+        match self.inner.options.authenticator.authenticate(credentials) {
+            Some(permissions) => Ok(connection.grant_permissions(permissions)),
+            None => {
+                log_warn!("WebSocket {} failed to authenticate", connection.peer());
+                Err(RpcError::AuthenticationFailed)
+            }
+        }
+    }
+
     pub async fn stop_notify(&self, connection: &Connection, scope: Scope) -> RpcResult<()> {
         if let Some(listener_id) = connection.listener_id() {
             workflow_log::log_trace!("notification unsubscribe[0x{listener_id:x}] {scope:?}");
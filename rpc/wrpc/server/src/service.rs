@@ -1,13 +1,19 @@
-use crate::{connection::*, router::*, server::*};
+use crate::{
+    connection::*,
+    result::Result,
+    router::*,
+    server::*,
+    tls::{backend_address, TlsRelay},
+};
 use async_trait::async_trait;
 use waglayla_core::{
     info,
     task::service::{AsyncService, AsyncServiceError, AsyncServiceFuture},
     trace, warn,
 };
-use waglayla_rpc_core::api::ops::RpcApiOps;
+use waglayla_rpc_core::api::{auth::RpcAuthenticator, limits::RpcLimiter, ops::RpcApiOps};
 use waglayla_rpc_service::service::RpcCoreService;
-use waglayla_utils::triggers::SingleTrigger;
+use waglayla_utils::{tls::TlsServerConfig, triggers::SingleTrigger};
 use std::sync::Arc;
 use tokio::sync::oneshot::{channel as oneshot_channel, Sender as OneshotSender};
 use workflow_rpc::server::prelude::*;
@@ -20,11 +26,23 @@ pub struct Options {
     pub listen_address: String,
     pub grpc_proxy_address: Option<String>,
     pub verbose: bool,
+    pub authenticator: Arc<RpcAuthenticator>,
+    /// Meters the RPC calls of the connections
+    pub limiter: Arc<RpcLimiter>,
+    /// Serves TLS connections only if set
+    pub tls: Option<TlsServerConfig>,
 }
 
 impl Default for Options {
     fn default() -> Self {
-        Options { listen_address: "127.0.0.1:13110".to_owned(), verbose: false, grpc_proxy_address: None }
+        Options {
+            listen_address: "127.0.0.1:13110".to_owned(),
+            verbose: false,
+            grpc_proxy_address: None,
+            authenticator: Default::default(),
+            limiter: Default::default(),
+            tls: None,
+        }
     }
 }
 
@@ -83,6 +101,12 @@ impl RpcHandler for WaglaylaRpcHandler {
         Ok(connection)
     }
 
+    /// Behind the TLS relay, only the connections opened by the relay are accepted so that the loopback
+    /// listener cannot be used to bypass TLS and client authentication
+    fn accept(&self, peer: &SocketAddr) -> bool {
+        self.options.tls.is_none() || self.server.tls_peers().is_relayed(peer)
+    }
+
     /// Disconnect the websocket. Receives `Connection` (a.k.a `Self::Context`)
     /// before dropping it. This is the last chance to cleanup and resources owned by
     /// this connection. Delegate to Server.
@@ -128,20 +152,43 @@ impl WrpcService {
         WrpcService { options, server, rpc_handler, shutdown: SingleTrigger::default() }
     }
 
-    /// Start listening on the configured address (will panic if the socket listen() fails)
-    pub fn serve(self: Arc<Self>) -> OneshotSender<()> {
+    /// Start listening on the configured address. Returns an error if the TLS listener cannot be set up
+    /// (will panic if the socket listen() of the websocket server fails)
+    pub fn serve(self: Arc<Self>) -> Result<OneshotSender<()>> {
+        let mut listen_address = self.options.listen_address.clone();
+
+        // Bind the TLS relay first, the websocket server then listening on loopback behind it
+        let relay = match self.options.tls.as_ref() {
+            Some(tls) => {
+                let backend_address = backend_address()?;
+                let relay = TlsRelay::bind(&listen_address, backend_address, tls, self.rpc_handler.server.tls_peers())?;
+                info!("WRPC Server starting TLS on: {}", listen_address);
+                listen_address = backend_address.to_string();
+                Some(relay)
+            }
+            None => None,
+        };
+
         let (termination_sender, termination_receiver) = oneshot_channel::<()>();
-        let listen_address = self.options.listen_address.clone();
+        let (relay_termination_sender, relay_termination_receiver) = oneshot_channel::<()>();
         self.rpc_handler.server.start();
 
         // Spawn a task stopping the server on termination signal
         let service = self.clone();
         tokio::spawn(async move {
             let _ = termination_receiver.await;
+            let _ = relay_termination_sender.send(());
             service.server.stop().unwrap_or_else(|err| warn!("wRPC unable to signal shutdown: `{err}`"));
             service.server.join().await.unwrap_or_else(|err| warn!("wRPC error: `{err}"));
         });
 
+        // Spawn a task relaying TLS connections to the server
+        if let Some(relay) = relay {
+            tokio::spawn(relay.serve(async move {
+                let _ = relay_termination_receiver.await;
+            }));
+        }
+
         // Spawn a task running the server
         info!("WRPC Server starting on: {}", listen_address);
         tokio::spawn(async move {
@@ -153,7 +200,7 @@ impl WrpcService {
             }
         });
 
-        termination_sender
+        Ok(termination_sender)
     }
 }
 
@@ -170,11 +217,12 @@ impl AsyncService for WrpcService {
         // Prepare a shutdown signal receiver
         let shutdown_signal = self.shutdown.listener.clone();
 
-        // Run the server
-        trace!("{} running the wRPC server", WRPC_SERVER);
-        let terminate_server = self.clone().serve();
-
         Box::pin(async move {
+            // Run the server
+            trace!("{} running the wRPC server", WRPC_SERVER);
+            let terminate_server =
+                self.clone().serve().map_err(|err| AsyncServiceError::Service(format!("wRPC server error: `{err}`")))?;
+
             // Keep the gRPC server running until a service shutdown signal is received
             shutdown_signal.await;
 
@@ -0,0 +1,123 @@
+//!
+//! TLS termination of the wRPC listener. The websocket server only accepts plaintext connections, so when TLS
+//! is enabled it listens on an ephemeral loopback port while a [`TlsRelay`] accepts TLS connections on the public
+//! address and relays them to it. The relay records the remote peer of every relayed connection in [`TlsPeers`]
+//! before opening it, so the wRPC server can tell relayed clients apart and refuse any other loopback connection.
+//!
+
+use crate::result::Result;
+use waglayla_core::debug;
+use waglayla_utils::tls::TlsServerConfig;
+use std::{
+    collections::HashMap,
+    future::Future,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    io::copy_bidirectional,
+    net::{TcpListener, TcpSocket},
+};
+use tokio_rustls::TlsAcceptor;
+
+/// The remote peers of the relayed connections, keyed by the local address of their connection to the websocket server
+#[derive(Clone, Debug, Default)]
+pub struct TlsPeers(Arc<Mutex<HashMap<SocketAddr, SocketAddr>>>);
+
+impl TlsPeers {
+    /// Returns true if the connection accepted from `address` was opened by the relay
+    pub fn is_relayed(&self, address: &SocketAddr) -> bool {
+        self.0.lock().unwrap().contains_key(address)
+    }
+
+    /// Returns the remote peer of a connection accepted from `address`, which is `address` itself unless relayed
+    pub fn resolve(&self, address: &SocketAddr) -> SocketAddr {
+        self.0.lock().unwrap().get(address).copied().unwrap_or(*address)
+    }
+
+    fn insert(&self, address: SocketAddr, peer: SocketAddr) {
+        self.0.lock().unwrap().insert(address, peer);
+    }
+
+    fn remove(&self, address: &SocketAddr) {
+        self.0.lock().unwrap().remove(address);
+    }
+}
+
+pub struct TlsRelay {
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    backend_address: SocketAddr,
+    peers: TlsPeers,
+}
+
+impl TlsRelay {
+    /// Binds `listen_address` and prepares relaying the TLS connections to `backend_address`, recording them in `peers`
+    pub fn bind(listen_address: &str, backend_address: SocketAddr, tls: &TlsServerConfig, peers: TlsPeers) -> Result<Self> {
+        let acceptor = TlsAcceptor::from(Arc::new(tls.rustls_config()?));
+        let listener = std::net::TcpListener::bind(listen_address.replace("wrpc://", ""))?;
+        listener.set_nonblocking(true)?;
+        let listener = TcpListener::from_std(listener)?;
+        Ok(Self { listener, acceptor, backend_address, peers })
+    }
+
+    /// Relays incoming connections until `shutdown` completes
+    pub async fn serve(self, shutdown: impl Future<Output = ()>) {
+        tokio::pin!(shutdown);
+        loop {
+            let (stream, peer) = tokio::select! {
+                accepted = self.listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(err) => {
+                        debug!("WRPC TLS accept error: {err}");
+                        continue;
+                    }
+                },
+                _ = &mut shutdown => break,
+            };
+            let acceptor = self.acceptor.clone();
+            let backend_address = self.backend_address;
+            let peers = self.peers.clone();
+            tokio::spawn(async move {
+                let mut stream = match acceptor.accept(stream).await {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        debug!("WRPC TLS handshake with {peer} failed: {err}");
+                        return;
+                    }
+                };
+                // The relayed connection is recorded before being opened, since the server only accepts recorded ones
+                let (socket, relay_address) = match relay_socket() {
+                    Ok(socket) => socket,
+                    Err(err) => {
+                        debug!("WRPC TLS unable to relay {peer}: {err}");
+                        return;
+                    }
+                };
+                peers.insert(relay_address, peer);
+                match socket.connect(backend_address).await {
+                    Ok(mut backend) => {
+                        debug!("WRPC TLS relaying {peer} through {relay_address}");
+                        let _ = copy_bidirectional(&mut stream, &mut backend).await;
+                    }
+                    Err(err) => debug!("WRPC TLS unable to relay {peer} to {backend_address}: {err}"),
+                }
+                peers.remove(&relay_address);
+            });
+        }
+    }
+}
+
+/// Returns a free loopback address for the websocket server to listen on behind the TLS relay
+pub fn backend_address() -> Result<SocketAddr> {
+    let socket = std::net::TcpListener::bind("127.0.0.1:0")?;
+    Ok(socket.local_addr()?)
+}
+
+/// Returns a loopback socket bound to an ephemeral port, along with its address
+fn relay_socket() -> Result<(TcpSocket, SocketAddr)> {
+    let socket = TcpSocket::new_v4()?;
+    socket.bind(SocketAddr::from(([127, 0, 0, 1], 0)))?;
+    let address = socket.local_addr()?;
+    Ok((socket, address))
+}
@@ -912,6 +912,10 @@ build_wrpc_wasm_bindgen_subscriptions!([
     /// New block template notification event is produced when a new block
     /// template is generated for mining in the Waglayla BlockDAG.
     NewBlockTemplate,
+    /// Manage subscription for a transactions evicted notification event.
+    /// Transactions evicted notification event is produced when mempool
+    /// transactions are replaced by a transaction paying a higher fee rate.
+    TransactionsEvicted,
 ]);
 
 // Build RPC method invocation functions. This macro
@@ -953,6 +957,9 @@ build_wrpc_wasm_bindgen_interface!(
         /// network that the node can potentially connect to.
         /// Returned information: List of peer addresses.
         GetPeerAddresses,
+        /// Retrieves the list of banned IP addresses and subnets.
+        /// Returned information: List of banned subnets along with their ban expiry and reason.
+        GetBannedPeers,
         /// Retrieves various metrics and statistics related to the
         /// performance and status of the Waglayla node.
         /// Returned information: Memory usage, CPU usage, network activity.
@@ -979,6 +986,10 @@ build_wrpc_wasm_bindgen_interface!(
         /// Obtains basic information about the synchronization status of the Waglayla node.
         /// Returned information: Syncing status.
         GetSyncStatus,
+        /// Returns feerate estimations derived from the current mempool state.
+        /// Returned information: Priority, normal and low feerate buckets
+        /// along with their estimated inclusion times.
+        GetFeeEstimate,
     ],
     [
         // functions with `request` argument
@@ -1028,6 +1039,25 @@ build_wrpc_wasm_bindgen_interface!(
         /// Retrieves information about a subnetwork in the Waglayla BlockDAG.
         /// Returned information: Subnetwork information.
         GetSubnetwork,
+        /// Retrieves a confirmed transaction by transaction ID (requires the node to run with --txindex).
+        /// Returned information: The transaction, its including blocks and its accepting chain block.
+        GetTransaction,
+        /// Retrieves a page of the transactions paying to or spending from specific addresses
+        /// (requires the node to run with --addressindex).
+        /// Returned information: The transactions and the DAA score of the next page.
+        GetTransactionsByAddresses,
+        /// Retrieves the header of the first chain block with a DAA score of at least
+        /// the requested one (requires the node to run with --chainindex).
+        /// Returned information: The chain block header, if any.
+        GetChainBlockByDaaScore,
+        /// Retrieves the header of the first chain block with a timestamp of at least
+        /// the requested one (requires the node to run with --chainindex).
+        /// Returned information: The chain block header, if any.
+        GetChainBlockByTimestamp,
+        /// Retrieves the headers of the chain blocks within a DAA score range
+        /// (requires the node to run with --chainindex).
+        /// Returned information: List of chain block headers.
+        GetChainBlockHeaders,
         /// Retrieves unspent transaction outputs (UTXOs) associated with
         /// specific addresses.
         /// Returned information: List of UTXOs.
@@ -1044,6 +1074,10 @@ build_wrpc_wasm_bindgen_interface!(
         /// Submits a transaction to the Waglayla network.
         /// Returned information: None.
         SubmitTransaction,
+        /// Submits a transaction replacing a mempool transaction it double spends
+        /// by paying a higher fee rate.
+        /// Returned information: The id of the submitted transaction and the replaced transaction.
+        SubmitTransactionReplacement,
         /// Unbans a previously banned peer, allowing it to connect
         /// to the Waglayla node again.
         /// Returned information: None.
@@ -21,6 +21,7 @@ export enum RpcEventType {
     VirtualDaaScoreChanged = "virtual-daa-score-changed",
     PruningPointUtxoSetOverride = "pruning-point-utxo-set-override",
     NewBlockTemplate = "new-block-template",
+    TransactionsEvicted = "transactions-evicted",
 }
 
 /**
@@ -36,7 +37,8 @@ export type RpcEventData = IBlockAdded
     | ISinkBlueScoreChanged 
     | IVirtualDaaScoreChanged 
     | IPruningPointUtxoSetOverride 
-    | INewBlockTemplate;
+    | INewBlockTemplate 
+    | ITransactionsEvicted;
 
 /**
  * RPC notification event data map.
@@ -55,6 +57,7 @@ export type RpcEventMap = {
     "virtual-daa-score-changed" : IVirtualDaaScoreChanged,
     "pruning-point-utxo-set-override" : IPruningPointUtxoSetOverride,
     "new-block-template" : INewBlockTemplate,
+    "transactions-evicted" : ITransactionsEvicted,
 }
 
 /**
@@ -80,6 +83,7 @@ export type RpcEvent = {
  * {@link RpcClient.subscribeSinkBlueScoreChanged},
  * {@link RpcClient.subscribePruningPointUtxoSetOverride},
  * {@link RpcClient.subscribeNewBlockTemplate},
+ * {@link RpcClient.subscribeTransactionsEvicted},
  * 
  * @category Node RPC
  */
@@ -237,3 +241,19 @@ declare! {
     }
     "#,
 }
+
+declare! {
+    ITransactionsEvicted,
+    r#"
+    /**
+     * Transactions evicted notification event is produced when mempool
+     * transactions are replaced by a transaction paying a higher fee rate.
+     * 
+     * @category Node RPC
+     */
+    export interface ITransactionsEvicted {
+        replacementTransactionId : HexString;
+        evictedTransactionIds : HexString[];
+    }
+    "#,
+}
@@ -22,7 +22,7 @@ use waglayla_consensus_core::{
 use waglayla_consensus_notify::root::ConsensusNotificationRoot;
 use waglayla_core::{info, task::service::AsyncService, task::tick::TickService, time::unix_now, trace, warn};
 use waglayla_database::prelude::ConnBuilder;
-use waglayla_database::{create_temp_db, load_existing_db};
+use waglayla_database::{create_memory_db, create_temp_db, load_existing_db};
 use waglayla_hashes::Hash;
 use waglayla_perf_monitor::{builder::Builder, counters::CountersSnapshot};
 use waglayla_utils::fd_budget;
@@ -117,6 +117,11 @@ struct Args {
     rocksdb_files_limit: Option<i32>,
     #[arg(long)]
     rocksdb_mem_budget: Option<usize>,
+
+    /// Keep the simulation DBs in memory rather than in RocksDB. Faster and leaves nothing on disk,
+    /// but memory usage grows with the simulation size. The rocksdb arguments are ignored
+    #[arg(long, default_value_t = false, conflicts_with_all = ["input_dir", "output_dir"])]
+    in_memory_db: bool,
 }
 
 #[cfg(feature = "heap")]
@@ -234,6 +239,7 @@ fn main_impl(mut args: Args) {
                 args.rocksdb_stats_period_sec,
                 args.rocksdb_files_limit,
                 args.rocksdb_mem_budget,
+                args.in_memory_db,
             )
             .run(until);
         consensus.shutdown(handles);
@@ -246,7 +252,11 @@ fn main_impl(mut args: Args) {
     }
 
     // Benchmark the DAG validation time
-    let (_lifetime2, db2) = create_temp_db!(ConnBuilder::default().with_parallelism(num_cpus::get()).with_files_limit(default_fd));
+    let (_lifetime2, db2) = if args.in_memory_db {
+        create_memory_db!()
+    } else {
+        create_temp_db!(ConnBuilder::default().with_parallelism(num_cpus::get()).with_files_limit(default_fd))
+    };
     let (dummy_notification_sender, _) = unbounded();
     let notification_root = Arc::new(ConsensusNotificationRoot::new(dummy_notification_sender));
     let consensus2 = Arc::new(Consensus::new(
@@ -11,7 +11,7 @@ use waglayla_consensus::consensus::Consensus;
 use waglayla_consensus_core::block::Block;
 use waglayla_database::prelude::ConnBuilder;
 use waglayla_database::utils::DbLifetime;
-use waglayla_database::{create_permanent_db, create_temp_db};
+use waglayla_database::{create_memory_db, create_permanent_db, create_temp_db};
 use waglayla_utils::fd_budget;
 use waglayla_utils::sim::Simulation;
 
@@ -50,29 +50,34 @@ impl WaglaylaNetworkSimulator {
         rocksdb_stats_period_sec: Option<u32>,
         rocksdb_files_limit: Option<i32>,
         rocksdb_mem_budget: Option<usize>,
+        in_memory_db: bool,
     ) -> &mut Self {
         let secp = secp256k1::Secp256k1::new();
         let mut rng = rand::thread_rng();
         for i in 0..num_miners {
-            let mut builder = ConnBuilder::default().with_files_limit(fd_budget::limit() / 2 / num_miners as i32);
-            if let Some(rocksdb_files_limit) = rocksdb_files_limit {
-                builder = builder.with_files_limit(rocksdb_files_limit);
-            }
-            if let Some(rocksdb_mem_budget) = rocksdb_mem_budget {
-                builder = builder.with_mem_budget(rocksdb_mem_budget);
-            }
-            let (lifetime, db) = match (i == 0, &self.output_dir, rocksdb_stats, rocksdb_stats_period_sec) {
-                (true, Some(dir), true, Some(rocksdb_stats_period_sec)) => {
-                    create_permanent_db!(dir, builder.enable_stats().with_stats_period(rocksdb_stats_period_sec))
+            let (lifetime, db) = if in_memory_db {
+                create_memory_db!()
+            } else {
+                let mut builder = ConnBuilder::default().with_files_limit(fd_budget::limit() / 2 / num_miners as i32);
+                if let Some(rocksdb_files_limit) = rocksdb_files_limit {
+                    builder = builder.with_files_limit(rocksdb_files_limit);
                 }
-                (true, Some(dir), true, None) => create_permanent_db!(dir, builder.enable_stats()),
-                (true, Some(dir), false, _) => create_permanent_db!(dir, builder),
+                if let Some(rocksdb_mem_budget) = rocksdb_mem_budget {
+                    builder = builder.with_mem_budget(rocksdb_mem_budget);
+                }
+                match (i == 0, &self.output_dir, rocksdb_stats, rocksdb_stats_period_sec) {
+                    (true, Some(dir), true, Some(rocksdb_stats_period_sec)) => {
+                        create_permanent_db!(dir, builder.enable_stats().with_stats_period(rocksdb_stats_period_sec))
+                    }
+                    (true, Some(dir), true, None) => create_permanent_db!(dir, builder.enable_stats()),
+                    (true, Some(dir), false, _) => create_permanent_db!(dir, builder),
 
-                (_, _, true, Some(rocksdb_stats_period_sec)) => {
-                    create_temp_db!(builder.enable_stats().with_stats_period(rocksdb_stats_period_sec))
+                    (_, _, true, Some(rocksdb_stats_period_sec)) => {
+                        create_temp_db!(builder.enable_stats().with_stats_period(rocksdb_stats_period_sec))
+                    }
+                    (_, _, true, None) => create_temp_db!(builder.enable_stats()),
+                    (_, _, false, _) => create_temp_db!(builder),
                 }
-                (_, _, true, None) => create_temp_db!(builder.enable_stats()),
-                (_, _, false, _) => create_temp_db!(builder),
             };
 
             let (dummy_notification_sender, _) = unbounded();
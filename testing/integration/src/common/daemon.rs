@@ -48,6 +48,7 @@ impl ClientManager {
             false,
             Some(500_000),
             Default::default(),
+            None,
         )
         .await
         .unwrap()
@@ -71,6 +72,7 @@ impl ClientManager {
             false,
             Some(500_000),
             Default::default(),
+            None,
         )
         .await
         .unwrap()
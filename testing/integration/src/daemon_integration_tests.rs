@@ -7,15 +7,32 @@ use crate::common::{
 use waglayla_addresses::Address;
 use waglayla_alloc::init_allocator_with_default_settings;
 use waglayla_consensus::params::SIMNET_PARAMS;
+use waglayla_consensus_core::tx::{Transaction, TransactionId};
 use waglayla_consensusmanager::ConsensusManager;
 use waglayla_core::{task::runtime::AsyncRuntime, trace};
 use waglayla_grpc_client::GrpcClient;
 use waglayla_notify::scope::{BlockAddedScope, UtxosChangedScope, VirtualDaaScoreChangedScope};
 use waglayla_rpc_core::{api::rpc::RpcApi, Notification, RpcTransactionId};
 use waglayla_txscript::pay_to_address_script;
+use waglayla_p2p_lib::{
+    common::{ProtocolError, PROTOCOL_VIOLATION_MISBEHAVIOUR_SCORE},
+    make_message,
+    pb::{waglaylad_message::Payload, StemTransactionsMessage, VersionMessage},
+    Adaptor, ConnectionInitializer, Hub, Router, WaglayladHandshake, WaglayladMessagePayloadType,
+};
+use waglayla_utils::networking::NetAddress;
 use waglaylad_lib::args::Args;
+use async_trait::async_trait;
 use rand::thread_rng;
-use std::{sync::Arc, time::Duration};
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::mpsc::{unbounded_channel, UnboundedSender},
+    time::timeout,
+};
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn daemon_sanity_test() {
@@ -326,7 +343,9 @@ async fn daemon_utxos_propagation_test() {
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn daemon_cleaning_test() {
     init_allocator_with_default_settings();
-    waglayla_core::log::try_init_logger("info,waglayla_grpc_core=trace,waglayla_grpc_server=trace,waglayla_grpc_client=trace,waglayla_core=trace");
+    waglayla_core::log::try_init_logger(
+        "info,waglayla_grpc_core=trace,waglayla_grpc_server=trace,waglayla_grpc_client=trace,waglayla_core=trace",
+    );
     let args = Args { devnet: true, ..Default::default() };
     let consensus_manager;
     let async_runtime;
@@ -351,3 +370,227 @@ async fn daemon_cleaning_test() {
     assert_eq!(async_runtime.strong_count(), 0);
     assert_eq!(core.strong_count(), 0);
 }
+
+/// Performs the handshake as a protocol version 5 peer and then sends a stem message, for which
+/// version 5 peers have no route
+struct MisbehavingInitializer {
+    network: String,
+}
+
+#[async_trait]
+impl ConnectionInitializer for MisbehavingInitializer {
+    async fn initialize_connection(&self, router: Arc<Router>) -> Result<(), ProtocolError> {
+        let mut handshake = WaglayladHandshake::new(&router, None);
+        // Drain the messages sent by the node flows so that this side does not disconnect on its own
+        let mut incoming_route = router.subscribe(vec![
+            WaglayladMessagePayloadType::Addresses,
+            WaglayladMessagePayloadType::RequestAddresses,
+            WaglayladMessagePayloadType::Ping,
+            WaglayladMessagePayloadType::Pong,
+            WaglayladMessagePayloadType::InvRelayBlock,
+            WaglayladMessagePayloadType::InvTransactions,
+            WaglayladMessagePayloadType::RequestTransactions,
+            WaglayladMessagePayloadType::Reject,
+        ]);
+        tokio::spawn(async move { while incoming_route.recv().await.is_some() {} });
+        router.start();
+        let self_version_message = VersionMessage {
+            protocol_version: 5,
+            id: rand::random::<[u8; 16]>().to_vec(),
+            network: self.network.clone(),
+            ..Default::default()
+        };
+        handshake.handshake(self_version_message).await?;
+        handshake.exchange_ready_messages().await?;
+        router.enqueue(make_message!(Payload::StemTransactions, StemTransactionsMessage { transactions: vec![] })).await?;
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn daemon_misbehaviour_ban_test() {
+    init_allocator_with_default_settings();
+    waglayla_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        ban_threshold: PROTOCOL_VIOLATION_MISBEHAVIOUR_SCORE,
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut waglaylad1 = Daemon::new_random_with_args(args, total_fd_limit);
+    let rpc_client1 = waglaylad1.start().await;
+    assert!(rpc_client1.get_banned_peers().await.unwrap().is_empty());
+
+    // A single protocol violation makes the misbehaviour score of the peer cross the ban threshold
+    let initializer = Arc::new(MisbehavingInitializer { network: waglaylad1.network.to_prefixed() });
+    let adaptor = Adaptor::client_only(Hub::new(), initializer, Default::default(), Default::default());
+    adaptor
+        .connect_peer_with_retries(format!("127.0.0.1:{}", waglaylad1.p2p_port), 16, Duration::from_secs(1))
+        .await
+        .expect("peer connection failed");
+
+    let check_client = rpc_client1.clone();
+    wait_for(
+        50,
+        100,
+        move || {
+            async fn peer_banned(client: GrpcClient) -> bool {
+                let banned_peers = client.get_banned_peers().await.unwrap();
+                banned_peers.iter().any(|peer| {
+                    peer.subnet.single_address().is_some_and(|ip| ip.is_loopback())
+                        && peer.reason.contains("misbehaviour score reached")
+                })
+            }
+            Box::pin(peer_banned(check_client.clone()))
+        },
+        "the misbehaving peer was not banned",
+    )
+    .await;
+
+    // The banned peer cannot connect again
+    assert!(adaptor.connect_peer(format!("127.0.0.1:{}", waglaylad1.p2p_port)).await.is_err());
+
+    adaptor.close().await;
+    rpc_client1.disconnect().await.unwrap();
+    drop(rpc_client1);
+    waglaylad1.shutdown();
+}
+
+/// Transactions relayed by the node to a peer
+#[derive(Debug)]
+enum RelayedTransactions {
+    Stem(Vec<TransactionId>),
+    Inv(Vec<TransactionId>),
+}
+
+/// A peer supporting stem relay which reports the transactions relayed to it, without ever diffusing them
+struct StemPeerInitializer {
+    network: String,
+    sender: UnboundedSender<RelayedTransactions>,
+}
+
+#[async_trait]
+impl ConnectionInitializer for StemPeerInitializer {
+    async fn initialize_connection(&self, router: Arc<Router>) -> Result<(), ProtocolError> {
+        let mut handshake = WaglayladHandshake::new(&router, None);
+        // Drain the other messages sent by the node flows so that this side does not disconnect on its own
+        let mut incoming_route = router.subscribe(vec![
+            WaglayladMessagePayloadType::Addresses,
+            WaglayladMessagePayloadType::RequestAddresses,
+            WaglayladMessagePayloadType::Ping,
+            WaglayladMessagePayloadType::Pong,
+            WaglayladMessagePayloadType::InvRelayBlock,
+            WaglayladMessagePayloadType::RequestTransactions,
+            WaglayladMessagePayloadType::Reject,
+        ]);
+        tokio::spawn(async move { while incoming_route.recv().await.is_some() {} });
+        let mut relay_route =
+            router.subscribe(vec![WaglayladMessagePayloadType::StemTransactions, WaglayladMessagePayloadType::InvTransactions]);
+        let sender = self.sender.clone();
+        tokio::spawn(async move {
+            while let Some(msg) = relay_route.recv().await {
+                let relayed = match msg.payload {
+                    Some(Payload::StemTransactions(msg)) => {
+                        RelayedTransactions::Stem(Vec::<Transaction>::try_from(msg).unwrap().into_iter().map(|tx| tx.id()).collect())
+                    }
+                    Some(Payload::InvTransactions(msg)) => RelayedTransactions::Inv(msg.try_into().unwrap()),
+                    _ => continue,
+                };
+                let _ = sender.send(relayed);
+            }
+        });
+        router.start();
+        let self_version_message = VersionMessage {
+            protocol_version: 9,
+            id: rand::random::<[u8; 16]>().to_vec(),
+            network: self.network.clone(),
+            ..Default::default()
+        };
+        handshake.handshake(self_version_message).await?;
+        handshake.exchange_ready_messages().await?;
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn daemon_dandelion_relay_test() {
+    init_allocator_with_default_settings();
+    waglayla_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        utxoindex: true,
+        dandelion: true,
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let coinbase_maturity = SIMNET_PARAMS.coinbase_maturity;
+    let mut waglaylad1 = Daemon::new_random_with_args(args, total_fd_limit);
+    let rpc_client1 = waglaylad1.start().await;
+
+    // The only outbound peer of the node, and thus its stem peer
+    let (sender, mut receiver) = unbounded_channel();
+    let initializer = Arc::new(StemPeerInitializer { network: waglaylad1.network.to_prefixed(), sender });
+    let peer_address = NetAddress::from_str("127.0.0.1:0").unwrap();
+    let peer_adaptor = Adaptor::bidirectional(peer_address, Hub::new(), initializer, Default::default(), Default::default()).unwrap();
+    rpc_client1.add_peer(peer_adaptor.local_address().unwrap().to_string().try_into().unwrap(), true).await.unwrap();
+    let check_client = rpc_client1.clone();
+    wait_for(
+        50,
+        100,
+        move || {
+            async fn peer_connected(client: GrpcClient) -> bool {
+                client.get_connected_peer_info().await.unwrap().peer_info.len() == 1
+            }
+            Box::pin(peer_connected(check_client.clone()))
+        },
+        "the node did not connect to the stem peer",
+    )
+    .await;
+
+    // Mine until a coinbase output matures
+    let (miner_sk, miner_pk) = secp256k1::generate_keypair(&mut thread_rng());
+    let miner_address =
+        Address::new(waglaylad1.network.into(), waglayla_addresses::Version::PubKey, &miner_pk.x_only_public_key().0.serialize());
+    let miner_schnorr_key = secp256k1::Keypair::from_secret_key(secp256k1::SECP256K1, &miner_sk);
+    let mut client = ListeningClient::connect(&waglaylad1).await;
+    client.start_notify(BlockAddedScope {}.into()).await.unwrap();
+    client.start_notify(VirtualDaaScoreChangedScope {}.into()).await.unwrap();
+    let clients = vec![client];
+    for _ in 0..coinbase_maturity + 1 {
+        mine_block(miner_address.clone(), &rpc_client1, &clients).await;
+    }
+    let utxos = fetch_spendable_utxos(&rpc_client1, miner_address.clone(), coinbase_maturity).await;
+    let amount = utxos[0].1.amount / 2;
+    let tx = generate_tx(miner_schnorr_key, &utxos[0..1], amount, 1, &miner_address);
+
+    // A submitted transaction is forwarded to the stem peer only...
+    rpc_client1.submit_transaction((&tx).into(), false).await.unwrap();
+    let submission_time = Instant::now();
+    match timeout(Duration::from_secs(10), receiver.recv()).await.expect("the transaction was not stemmed") {
+        Some(RelayedTransactions::Stem(ids)) => assert_eq!(ids, vec![tx.id()]),
+        relayed => panic!("expected stem transactions, got {relayed:?}"),
+    }
+
+    // ...and, since the stem peer never diffuses it, the node diffuses it itself once the embargo expires
+    match timeout(Duration::from_secs(60), receiver.recv()).await.expect("the embargo did not expire") {
+        Some(RelayedTransactions::Inv(ids)) => assert_eq!(ids, vec![tx.id()]),
+        relayed => panic!("expected a transaction inv, got {relayed:?}"),
+    }
+    // The embargo timeout is 30 seconds
+    assert!(submission_time.elapsed() >= Duration::from_secs(30), "the transaction was diffused while under embargo");
+
+    peer_adaptor.close().await;
+    for x in clients.iter() {
+        x.disconnect().await.unwrap();
+        x.join().await.unwrap();
+    }
+    rpc_client1.disconnect().await.unwrap();
+    drop(rpc_client1);
+    waglaylad1.shutdown();
+}
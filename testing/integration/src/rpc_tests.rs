@@ -5,14 +5,14 @@ use futures_util::future::try_join_all;
 use waglayla_addresses::{Address, Prefix, Version};
 use waglayla_consensus::params::SIMNET_GENESIS;
 use waglayla_consensus_core::{constants::MAX_SOMPI, subnets::SubnetworkId, tx::Transaction};
-use waglayla_core::info;
+use waglayla_core::{info, time::unix_now};
 use waglayla_grpc_core::ops::WaglayladPayloadOps;
 use waglayla_hashes::Hash;
 use waglayla_notify::{
     connection::{ChannelConnection, ChannelType},
     scope::{
         BlockAddedScope, FinalityConflictScope, NewBlockTemplateScope, PruningPointUtxoSetOverrideScope, Scope,
-        SinkBlueScoreChangedScope, UtxosChangedScope, VirtualChainChangedScope, VirtualDaaScoreChangedScope,
+        SinkBlueScoreChangedScope, TransactionsEvictedScope, UtxosChangedScope, VirtualChainChangedScope, VirtualDaaScoreChangedScope,
     },
 };
 use waglayla_rpc_core::{api::rpc::RpcApi, model::*, Notification};
@@ -49,6 +49,9 @@ async fn sanity_test() {
         enable_unsynced_mining: true,
         block_template_cache_lifetime: Some(0),
         utxoindex: true,
+        txindex: true,
+        addressindex: true,
+        chainindex: true,
         unsafe_rpc: true,
         ..Default::default()
     };
@@ -275,14 +278,35 @@ async fn sanity_test() {
                     let ip = peer_address.normalize(1).ip;
 
                     let _ = rpc_client.add_peer_call(AddPeerRequest { peer_address, is_permanent: false }).await.unwrap();
-                    let _ = rpc_client.ban_call(BanRequest { ip }).await.unwrap();
+                    let reason = Some("test".to_string());
+                    let _ = rpc_client.ban_call(BanRequest { ip: ip.into(), duration: None, reason }).await.unwrap();
 
                     let response = rpc_client.get_peer_addresses_call(GetPeerAddressesRequest {}).await.unwrap();
-                    assert!(response.banned_addresses.contains(&ip));
+                    assert!(response.banned_addresses.contains(&ip.into()));
 
-                    let _ = rpc_client.unban_call(UnbanRequest { ip }).await.unwrap();
+                    let _ = rpc_client.unban_call(UnbanRequest { ip: ip.into() }).await.unwrap();
                     let response = rpc_client.get_peer_addresses_call(GetPeerAddressesRequest {}).await.unwrap();
-                    assert!(!response.banned_addresses.contains(&ip));
+                    assert!(!response.banned_addresses.contains(&ip.into()));
+                })
+            }
+
+            WaglayladPayloadOps::GetBannedPeers => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let subnet = RpcIpSubnet::from_str("9.10.0.0/16").unwrap();
+                    rpc_client.ban(subnet, Some(60), Some("test".to_string())).await.unwrap();
+
+                    let banned_peers = rpc_client.get_banned_peers().await.unwrap();
+                    let banned_peer = banned_peers.iter().find(|peer| peer.subnet == subnet).unwrap();
+                    assert_eq!(banned_peer.reason, "test");
+                    assert!(banned_peer.expiry > unix_now());
+
+                    // Subnet bans are reported with their prefix length
+                    let response = rpc_client.get_peer_addresses_call(GetPeerAddressesRequest {}).await.unwrap();
+                    assert!(response.banned_addresses.iter().any(|banned| *banned == subnet && banned.prefix_len() == 16));
+
+                    rpc_client.unban(subnet).await.unwrap();
+                    assert!(rpc_client.get_banned_peers().await.unwrap().iter().all(|peer| peer.subnet != subnet));
                 })
             }
 
@@ -301,6 +325,66 @@ async fn sanity_test() {
                 })
             }
 
+            WaglayladPayloadOps::SubmitTransactionReplacement => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // Build an erroneous transaction...
+                    let transaction = Transaction::new(0, vec![], vec![], 0, SubnetworkId::default(), 0, vec![]);
+                    let result = rpc_client.submit_transaction_replacement((&transaction).into(), RpcReplacementMode::Mandatory).await;
+                    // ...that gets rejected by the consensus
+                    assert!(result.is_err());
+                })
+            }
+
+            WaglayladPayloadOps::GetTransaction => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // The txindex knows nothing about a random transaction id
+                    let result = rpc_client.get_transaction(RpcTransactionId::from_bytes([0x5a; 32])).await;
+                    assert!(result.is_err());
+                })
+            }
+
+            WaglayladPayloadOps::GetTransactionsByAddresses => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let addresses = vec![Address::new(Prefix::Simnet, Version::PubKey, &[0u8; 32])];
+                    let response = rpc_client.get_transactions_by_addresses(addresses, 0, 100).await.unwrap();
+                    assert!(response.transactions.is_empty());
+                    assert!(response.next_daa_score.is_none());
+                })
+            }
+
+            WaglayladPayloadOps::GetChainBlockByDaaScore => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // The genesis is the first chain block
+                    let response = rpc_client.get_chain_block_by_daa_score(0).await.unwrap();
+                    assert!(response.header.is_some());
+                    let response = rpc_client.get_chain_block_by_daa_score(u64::MAX).await.unwrap();
+                    assert!(response.header.is_none());
+                })
+            }
+
+            WaglayladPayloadOps::GetChainBlockByTimestamp => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let response = rpc_client.get_chain_block_by_timestamp(0).await.unwrap();
+                    assert!(response.header.is_some());
+                    let response = rpc_client.get_chain_block_by_timestamp(u64::MAX).await.unwrap();
+                    assert!(response.header.is_none());
+                })
+            }
+
+            WaglayladPayloadOps::GetChainBlockHeaders => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let response = rpc_client.get_chain_block_headers(0, u64::MAX, 10).await.unwrap();
+                    assert!(!response.headers.is_empty());
+                    assert!(response.headers.windows(2).all(|w| w[0].daa_score < w[1].daa_score));
+                })
+            }
+
             WaglayladPayloadOps::GetSubnetwork => {
                 let rpc_client = client.clone();
                 tst!(op, {
@@ -546,6 +630,19 @@ async fn sanity_test() {
                 })
             }
 
+            WaglayladPayloadOps::GetFeeEstimate => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let response = rpc_client.get_fee_estimate().await.unwrap();
+                    info!("{:?}", response.priority_bucket);
+                    assert!(!response.normal_buckets.is_empty());
+                    assert!(!response.low_buckets.is_empty());
+                    for bucket in response.ordered_buckets() {
+                        info!("{:?}", bucket);
+                    }
+                })
+            }
+
             WaglayladPayloadOps::NotifyBlockAdded => {
                 let rpc_client = client.clone();
                 let id = listener_id;
@@ -607,6 +704,13 @@ async fn sanity_test() {
                         .unwrap();
                 })
             }
+            WaglayladPayloadOps::NotifyTransactionsEvicted => {
+                let rpc_client = client.clone();
+                let id = listener_id;
+                tst!(op, {
+                    rpc_client.start_notify(id, TransactionsEvictedScope {}.into()).await.unwrap();
+                })
+            }
             WaglayladPayloadOps::StopNotifyingUtxosChanged => {
                 let rpc_client = client.clone();
                 let id = listener_id;
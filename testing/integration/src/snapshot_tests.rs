@@ -0,0 +1,128 @@
+//!
+//! Consensus snapshot integration tests
+//!
+
+use async_channel::unbounded;
+use waglayla_addressmanager::AddressManager;
+use waglayla_alloc::init_allocator_with_default_settings;
+use waglayla_consensus::config::{Config, ConfigBuilder};
+use waglayla_consensus::consensus::factory::Factory as ConsensusFactory;
+use waglayla_consensus::consensus::test_consensus::{TestConsensus, TestConsensusFactory};
+use waglayla_consensus::params::MAINNET_PARAMS;
+use waglayla_consensus::pipeline::ProcessingCounters;
+use waglayla_consensus_core::api::ConsensusApi;
+use waglayla_consensus_notify::root::ConsensusNotificationRoot;
+use waglayla_consensusmanager::ConsensusManager;
+use waglayla_core::core::Core;
+use waglayla_core::signals::Shutdown;
+use waglayla_core::task::tick::TickService;
+use waglayla_database::create_temp_db;
+use waglayla_database::prelude::ConnBuilder;
+use waglayla_database::utils::{get_waglayla_tempdir, DbLifetime};
+use waglayla_mining::manager::{MiningManager, MiningManagerProxy};
+use waglayla_mining::MiningCounters;
+use waglayla_p2p_flows::flow_context::FlowContext;
+use waglayla_p2p_flows::snapshot::{export_snapshot, import_snapshot};
+use waglayla_txscript::caches::TxScriptCacheCounters;
+use std::sync::Arc;
+
+/// Builds a flow context over `consensus_manager`. The returned lifetime guards the address manager DB
+/// and must be dropped after the flow context.
+fn create_flow_context(consensus_manager: Arc<ConsensusManager>, config: &Arc<Config>) -> (DbLifetime, FlowContext) {
+    let (db_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+    let tick_service = Arc::new(TickService::default());
+    let (address_manager, _) = AddressManager::new(config.clone(), db, tick_service.clone());
+    let mining_manager = MiningManagerProxy::new(Arc::new(MiningManager::new(
+        config.target_time_per_block,
+        false,
+        config.max_block_mass,
+        None,
+        Arc::new(MiningCounters::default()),
+    )));
+    let (notification_send, _notification_recv) = unbounded();
+    let notification_root = Arc::new(ConsensusNotificationRoot::new(notification_send));
+    let flow_context =
+        FlowContext::new(consensus_manager, address_manager, config.clone(), mining_manager, tick_service, notification_root, None);
+    (db_lifetime, flow_context)
+}
+
+#[tokio::test]
+async fn snapshot_export_import_test() {
+    init_allocator_with_default_settings();
+    waglayla_core::log::try_init_logger("info");
+
+    // Shrink the pruning parameters so that the pruning point moves within a few hundred blocks
+    let config = Arc::new(
+        ConfigBuilder::new(MAINNET_PARAMS)
+            .skip_proof_of_work()
+            .edit_consensus_params(|p| {
+                p.ghostdag_k = 1;
+                p.mergeset_size_limit = 10;
+                p.finality_depth = 32;
+                p.merge_depth = 32;
+                p.pruning_proof_m = 16;
+                p.legacy_difficulty_window_size = 64;
+                p.min_difficulty_window_len = 64;
+                p.legacy_timestamp_deviation_tolerance = 16;
+                p.pruning_depth = p.anticone_finalization_depth();
+            })
+            .apply_args(|config| config.disable_upnp = true)
+            .build(),
+    );
+
+    // The exporting node, advancing its pruning point past genesis
+    let source = Arc::new(TestConsensus::new(&config));
+    let source_manager = Arc::new(ConsensusManager::new(Arc::new(TestConsensusFactory::new(source.clone()))));
+    let (_source_lifetime, source_context) = create_flow_context(source_manager.clone(), &config);
+
+    // The importing node, a fresh node with a real consensus factory supporting staging consensus
+    let db_tempdir = get_waglayla_tempdir();
+    let meta_db = ConnBuilder::default().with_db_path(db_tempdir.path().join("meta")).with_files_limit(5).build().unwrap();
+    let (notification_send, _notification_recv) = unbounded();
+    let target_factory = Arc::new(ConsensusFactory::new(
+        meta_db,
+        &config,
+        db_tempdir.path().join("consensus"),
+        4,
+        Arc::new(ConsensusNotificationRoot::new(notification_send)),
+        Arc::new(ProcessingCounters::default()),
+        Arc::new(TxScriptCacheCounters::default()),
+        200,
+    ));
+    let target_manager = Arc::new(ConsensusManager::new(target_factory));
+    let (_target_lifetime, target_context) = create_flow_context(target_manager.clone(), &config);
+
+    let core = Arc::new(Core::new());
+    core.bind(source_manager);
+    core.bind(target_manager.clone());
+    let joins = core.start();
+
+    source.add_utxo_valid_block_with_parents(1.into(), vec![config.genesis.hash], vec![]).await.unwrap();
+    let blocks_count = 3 * config.pruning_depth;
+    for i in 2..=blocks_count {
+        source.add_utxo_valid_block_with_parents(i.into(), vec![(i - 1).into()], vec![]).await.unwrap();
+    }
+    assert_ne!(source.pruning_point(), config.genesis.hash, "the pruning point is expected to move past genesis");
+
+    let path = db_tempdir.path().join("snapshot");
+    let exported_pruning_point = export_snapshot(&source_context, path.clone()).await.unwrap();
+    assert_eq!(exported_pruning_point, source.pruning_point());
+
+    let imported_pruning_point = import_snapshot(&target_context, path.clone()).await.unwrap();
+    assert_eq!(imported_pruning_point, exported_pruning_point);
+
+    // The committed staging consensus holds the snapshot pruning point, UTXO set and headers
+    let target = target_manager.consensus().session().await;
+    assert_eq!(target.async_pruning_point().await, exported_pruning_point);
+    assert_eq!(target.async_get_headers_selected_tip().await, source.get_headers_selected_tip());
+    let target_utxos = target.async_get_pruning_point_utxos(exported_pruning_point, None, usize::MAX, false).await.unwrap();
+    let source_utxos = source.get_pruning_point_utxos(exported_pruning_point, None, usize::MAX, false).unwrap();
+    assert_eq!(target_utxos, source_utxos);
+    drop(target);
+
+    // The same snapshot cannot be imported twice
+    assert!(import_snapshot(&target_context, path).await.is_err());
+
+    core.shutdown();
+    core.join(joins);
+}
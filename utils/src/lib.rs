@@ -187,6 +187,7 @@ pub mod serde_bytes_fixed;
 pub mod serde_bytes_fixed_ref;
 pub mod sim;
 pub mod sync;
+pub mod tls;
 pub mod triggers;
 pub mod vec;
 
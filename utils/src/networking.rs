@@ -7,8 +7,11 @@ use std::{
 
 // #![allow(dead_code)]
 use borsh::{BorshDeserialize, BorshSerialize};
-use ipnet::IpNet;
+use data_encoding::BASE32_NOPAD;
+use ipnet::{IpNet, Ipv4Net, PrefixLenError};
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use thiserror::Error;
 use uuid::Uuid;
 use wasm_bindgen::prelude::*;
 
@@ -229,11 +232,270 @@ impl BorshDeserialize for IpAddress {
     }
 }
 
+/// An IP subnet in CIDR notation, newtype of [IpNet].
+///
+/// The subnet is always kept in canonical form: host bits are zeroed and
+/// IPv4-mapped IPv6 subnets are represented as IPv4 subnets.
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub struct IpSubnet(IpNet);
+
+impl IpSubnet {
+    pub fn new(ip: IpAddr, prefix_len: u8) -> Result<Self, PrefixLenError> {
+        IpNet::new(ip, prefix_len).map(Self::from)
+    }
+
+    pub fn network(&self) -> IpAddress {
+        self.0.network().into()
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.0.prefix_len()
+    }
+
+    /// Returns whether `ip` belongs to this subnet. IPv4-mapped IPv6 addresses are matched as IPv4
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        self.0.contains(&canonical_ip(*ip))
+    }
+
+    /// Returns the only address of the subnet if it consists of a single address
+    pub fn single_address(&self) -> Option<IpAddress> {
+        (self.0.prefix_len() == self.0.max_prefix_len()).then(|| self.network())
+    }
+
+    /// Returns whether this subnet and `other` share some address
+    pub fn overlaps(&self, other: &IpSubnet) -> bool {
+        self.0.contains(&other.0.network()) || other.0.contains(&self.0.network())
+    }
+}
+
+fn canonical_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(ipv6) => ipv6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+        IpAddr::V4(_) => ip,
+    }
+}
+
+impl From<IpNet> for IpSubnet {
+    fn from(net: IpNet) -> Self {
+        let net = match net {
+            IpNet::V6(ipv6_net) if ipv6_net.prefix_len() >= 96 => match ipv6_net.addr().to_ipv4_mapped() {
+                Some(ipv4) => IpNet::V4(Ipv4Net::new(ipv4, ipv6_net.prefix_len() - 96).expect("prefix length is at most 32")),
+                None => net,
+            },
+            _ => net,
+        };
+        Self(net.trunc())
+    }
+}
+
+impl From<IpAddr> for IpSubnet {
+    fn from(ip: IpAddr) -> Self {
+        IpNet::from(canonical_ip(ip)).into()
+    }
+}
+
+impl From<IpAddress> for IpSubnet {
+    fn from(ip: IpAddress) -> Self {
+        ip.0.into()
+    }
+}
+
+impl From<IpSubnet> for IpNet {
+    fn from(value: IpSubnet) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for IpSubnet {
+    type Err = ipnet::AddrParseError;
+
+    /// Parses either a subnet in CIDR notation or a single IP address
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        IpNet::from_str(s).map(IpSubnet::from).or_else(|err| IpAddr::from_str(s).map(IpSubnet::from).map_err(|_| err))
+    }
+}
+
+impl Display for IpSubnet {
+    /// Single address subnets are displayed as a plain IP address
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.single_address() {
+            Some(ip) => ip.fmt(f),
+            None => self.0.fmt(f),
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl IntoPy<Py<PyAny>> for IpSubnet {
+    fn into_py(self, py: Python) -> Py<PyAny> {
+        self.to_string().into_py(py)
+    }
+}
+
+impl Serialize for IpSubnet {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for IpSubnet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <String as Deserialize>::deserialize(deserializer)?;
+        IpSubnet::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl BorshSerialize for IpSubnet {
+    fn serialize<W: borsh::maybestd::io::Write>(&self, writer: &mut W) -> ::core::result::Result<(), borsh::maybestd::io::Error> {
+        borsh::BorshSerialize::serialize(&self.network(), writer)?;
+        borsh::BorshSerialize::serialize(&self.prefix_len(), writer)?;
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for IpSubnet {
+    fn deserialize(buf: &mut &[u8]) -> ::core::result::Result<Self, borsh::maybestd::io::Error> {
+        let network: IpAddress = BorshDeserialize::deserialize(buf)?;
+        let prefix_len: u8 = BorshDeserialize::deserialize(buf)?;
+        IpSubnet::new(network.0, prefix_len)
+            .map_err(|err| borsh::maybestd::io::Error::new(borsh::maybestd::io::ErrorKind::InvalidInput, err.to_string()))
+    }
+}
+
+/// Errors of parsing an [`OnionAddress`]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum OnionAddressError {
+    #[error("onion address must end with `{}`", OnionAddress::TLD)]
+    MissingTld,
+
+    #[error("onion address is not valid base32")]
+    InvalidEncoding,
+
+    #[error("onion address has illegal length {0}")]
+    IllegalLength(usize),
+
+    #[error("onion address has unsupported version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("onion address checksum mismatch")]
+    BadChecksum,
+}
+
+/// A Tor v3 hidden service address, identified by the ed25519 public key of the service.
+///
+/// Since the key does not fit in an IP address, onion addresses are additionally mapped to
+/// an IPv6 address in the OnionCat range (see [`OnionAddress::mapped_ip`]), which serves as a
+/// stand-in wherever a peer must be keyed by IP (routers, bans, prefix buckets).
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct OnionAddress([u8; 32]);
+
+impl OnionAddress {
+    pub const TLD: &'static str = ".onion";
+
+    const VERSION: u8 = 3;
+    const CHECKSUM_LEN: usize = 2;
+    const ENCODED_LEN: usize = 32 + Self::CHECKSUM_LEN + 1;
+    const CHECKSUM_CONSTANT: &'static [u8] = b".onion checksum";
+
+    /// The OnionCat IPv6 prefix fd87:d87e:eb43::/48
+    const MAPPED_IP_PREFIX: [u8; 6] = [0xfd, 0x87, 0xd8, 0x7e, 0xeb, 0x43];
+
+    pub fn new(public_key: [u8; 32]) -> Self {
+        Self(public_key)
+    }
+
+    pub fn public_key(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Returns the IPv6 address representing this onion address, built from the OnionCat
+    /// prefix followed by the leading bytes of the public key
+    pub fn mapped_ip(&self) -> IpAddress {
+        let mut octets = [0u8; 16];
+        octets[..Self::MAPPED_IP_PREFIX.len()].copy_from_slice(&Self::MAPPED_IP_PREFIX);
+        octets[Self::MAPPED_IP_PREFIX.len()..].copy_from_slice(&self.0[..16 - Self::MAPPED_IP_PREFIX.len()]);
+        Ipv6Addr::from(octets).into()
+    }
+
+    /// Returns whether `ip` lies in the range used by [`OnionAddress::mapped_ip`]
+    pub fn is_mapped_ip(ip: &IpAddress) -> bool {
+        match ip.0 {
+            IpAddr::V6(ipv6) => ipv6.octets().starts_with(&Self::MAPPED_IP_PREFIX),
+            IpAddr::V4(_) => false,
+        }
+    }
+
+    fn checksum(public_key: &[u8; 32]) -> [u8; Self::CHECKSUM_LEN] {
+        let mut hasher = Sha3_256::new();
+        hasher.update(Self::CHECKSUM_CONSTANT);
+        hasher.update(public_key);
+        hasher.update([Self::VERSION]);
+        hasher.finalize()[..Self::CHECKSUM_LEN].try_into().unwrap()
+    }
+}
+
+impl FromStr for OnionAddress {
+    type Err = OnionAddressError;
+
+    /// Parses a `<base32(public key | checksum | version)>.onion` host name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let encoded = s.strip_suffix(Self::TLD).ok_or(OnionAddressError::MissingTld)?;
+        let decoded = BASE32_NOPAD.decode(encoded.to_ascii_uppercase().as_bytes()).map_err(|_| OnionAddressError::InvalidEncoding)?;
+        if decoded.len() != Self::ENCODED_LEN {
+            return Err(OnionAddressError::IllegalLength(decoded.len()));
+        }
+        let version = decoded[Self::ENCODED_LEN - 1];
+        if version != Self::VERSION {
+            return Err(OnionAddressError::UnsupportedVersion(version));
+        }
+        let public_key: [u8; 32] = decoded[..32].try_into().unwrap();
+        if decoded[32..32 + Self::CHECKSUM_LEN] != Self::checksum(&public_key) {
+            return Err(OnionAddressError::BadChecksum);
+        }
+        Ok(Self(public_key))
+    }
+}
+
+impl Display for OnionAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_LEN);
+        bytes.extend_from_slice(&self.0);
+        bytes.extend_from_slice(&Self::checksum(&self.0));
+        bytes.push(Self::VERSION);
+        write!(f, "{}{}", BASE32_NOPAD.encode(&bytes).to_ascii_lowercase(), Self::TLD)
+    }
+}
+
+impl Serialize for OnionAddress {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for OnionAddress {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <String as Deserialize>::deserialize(deserializer)?;
+        OnionAddress::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Splits `s` into an onion host and an optional port. Returns `None` if the host is not a valid onion address
+fn parse_onion_host(s: &str) -> Option<(OnionAddress, Option<u16>)> {
+    let (host, port) = match s.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port.parse().ok()?)),
+        None => (s, None),
+    };
+    Some((host.parse().ok()?, port))
+}
+
 /// A network address, equivalent of a [SocketAddr].
+///
+/// Onion addresses carry their [`OnionAddress`], with `ip` holding its [`OnionAddress::mapped_ip`].
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize, Debug, BorshSerialize, BorshDeserialize)]
 pub struct NetAddress {
     pub ip: IpAddress,
     pub port: u16,
+    pub onion: Option<OnionAddress>,
 }
 
 #[cfg(not(target_family = "wasm"))]
@@ -245,7 +507,20 @@ impl IntoPy<Py<PyAny>> for NetAddress {
 
 impl NetAddress {
     pub fn new(ip: IpAddress, port: u16) -> Self {
-        Self { ip, port }
+        Self { ip, port, onion: None }
+    }
+
+    pub fn new_onion(onion: OnionAddress, port: u16) -> Self {
+        Self { ip: onion.mapped_ip(), port, onion: Some(onion) }
+    }
+
+    pub fn is_onion(&self) -> bool {
+        self.onion.is_some()
+    }
+
+    /// Onion addresses are always considered publicly routable
+    pub fn is_publicly_routable(&self) -> bool {
+        self.is_onion() || self.ip.is_publicly_routable()
     }
 
     pub fn prefix_bucket(&self) -> PrefixBucket {
@@ -269,13 +544,19 @@ impl FromStr for NetAddress {
     type Err = AddrParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        SocketAddr::from_str(s).map(NetAddress::from)
+        match parse_onion_host(s) {
+            Some((onion, Some(port))) => Ok(Self::new_onion(onion, port)),
+            _ => SocketAddr::from_str(s).map(NetAddress::from),
+        }
     }
 }
 
 impl Display for NetAddress {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        SocketAddr::from(self.to_owned()).fmt(f)
+        match self.onion {
+            Some(onion) => write!(f, "{}:{}", onion, self.port),
+            None => SocketAddr::from(self.to_owned()).fmt(f),
+        }
     }
 }
 
@@ -286,29 +567,35 @@ impl Display for NetAddress {
 pub struct ContextualNetAddress {
     ip: IpAddress,
     port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    onion: Option<OnionAddress>,
 }
 
 impl ContextualNetAddress {
     fn new(ip: IpAddress, port: Option<u16>) -> Self {
-        Self { ip, port }
+        Self { ip, port, onion: None }
+    }
+
+    fn new_onion(onion: OnionAddress, port: Option<u16>) -> Self {
+        Self { ip: onion.mapped_ip(), port, onion: Some(onion) }
     }
 
     pub fn normalize(&self, default_port: u16) -> NetAddress {
-        NetAddress::new(self.ip, self.port.unwrap_or(default_port))
+        NetAddress { ip: self.ip, port: self.port.unwrap_or(default_port), onion: self.onion }
     }
 
     pub fn unspecified() -> Self {
-        Self { ip: IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)).into(), port: None }
+        Self::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)).into(), None)
     }
 
     pub fn loopback() -> Self {
-        Self { ip: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)).into(), port: None }
+        Self::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)).into(), None)
     }
 }
 
 impl From<NetAddress> for ContextualNetAddress {
     fn from(value: NetAddress) -> Self {
-        Self::new(value.ip, Some(value.port))
+        Self { ip: value.ip, port: Some(value.port), onion: value.onion }
     }
 }
 
@@ -316,6 +603,9 @@ impl FromStr for ContextualNetAddress {
     type Err = AddrParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((onion, port)) = parse_onion_host(s) {
+            return Ok(Self::new_onion(onion, port));
+        }
         match SocketAddr::from_str(s) {
             Ok(socket) => Ok(Self::new(socket.ip().into(), Some(socket.port()))),
             Err(_) => Ok(Self::new(IpAddress::from_str(s)?, None)),
@@ -341,9 +631,11 @@ impl TryFrom<String> for ContextualNetAddress {
 
 impl Display for ContextualNetAddress {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.port {
-            Some(port) => SocketAddr::new(self.ip.into(), port).fmt(f),
-            None => self.ip.fmt(f),
+        match (self.onion, self.port) {
+            (Some(onion), Some(port)) => write!(f, "{}:{}", onion, port),
+            (Some(onion), None) => onion.fmt(f),
+            (None, Some(port)) => SocketAddr::new(self.ip.into(), port).fmt(f),
+            (None, None) => self.ip.fmt(f),
         }
     }
 }
@@ -440,6 +732,67 @@ mod tests {
         assert_eq!(ip, ip2);
     }
 
+    #[test]
+    fn test_ip_subnet() {
+        let subnet = IpSubnet::from_str("10.1.2.3/16").unwrap();
+        assert_eq!(subnet.to_string(), "10.1.0.0/16");
+        assert!(subnet.contains(&IpAddr::from_str("10.1.255.1").unwrap()));
+        assert!(subnet.contains(&IpAddr::from_str("::ffff:10.1.0.7").unwrap()));
+        assert!(!subnet.contains(&IpAddr::from_str("10.2.0.1").unwrap()));
+        assert_eq!(subnet.single_address(), None);
+        assert!(subnet.overlaps(&IpSubnet::from_str("10.1.2.3").unwrap()));
+        assert!(subnet.overlaps(&IpSubnet::from_str("10.0.0.0/8").unwrap()));
+        assert!(!subnet.overlaps(&IpSubnet::from_str("10.2.0.0/16").unwrap()));
+        assert!(!subnet.overlaps(&IpSubnet::from_str("::/0").unwrap()));
+
+        // Plain addresses and IPv4-mapped subnets are canonicalized
+        let single = IpSubnet::from_str("::ffff:1.2.3.4").unwrap();
+        assert_eq!(single, IpSubnet::from_str("1.2.3.4/32").unwrap());
+        assert_eq!(single.to_string(), "1.2.3.4");
+        assert_eq!(single.single_address(), Some(IpAddress::from_str("1.2.3.4").unwrap()));
+        assert_eq!(IpSubnet::from_str("::ffff:1.2.0.0/112").unwrap(), IpSubnet::from_str("1.2.0.0/16").unwrap());
+        assert!(IpSubnet::from_str("1.2.3.4/33").is_err());
+        assert!(IpSubnet::from_str("not an ip").is_err());
+
+        let bin = subnet.try_to_vec().unwrap();
+        assert_eq!(subnet, BorshDeserialize::try_from_slice(&bin).unwrap());
+        let json = serde_json::to_string(&subnet).unwrap();
+        assert_eq!(json, r#""10.1.0.0/16""#);
+        assert_eq!(subnet, serde_json::from_str::<IpSubnet>(&json).unwrap());
+    }
+
+    #[test]
+    fn test_onion_address() {
+        let host = "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion";
+        let onion = OnionAddress::from_str(host).unwrap();
+        assert_eq!(onion.to_string(), host);
+        assert_eq!(OnionAddress::from_str(&host.to_ascii_uppercase().replace(".ONION", ".onion")).unwrap(), onion);
+        assert!(OnionAddress::is_mapped_ip(&onion.mapped_ip()));
+        assert!(!OnionAddress::is_mapped_ip(&IpAddress::from_str("fd00::1").unwrap()));
+
+        // Corrupting a single character breaks the checksum
+        let corrupted = host.replacen('d', "e", 1);
+        assert_eq!(OnionAddress::from_str(&corrupted), Err(OnionAddressError::BadChecksum));
+        assert_eq!(OnionAddress::from_str("duckduckgo.com"), Err(OnionAddressError::MissingTld));
+        assert_eq!(OnionAddress::from_str("abcdefgh.onion"), Err(OnionAddressError::IllegalLength(5)));
+
+        let addr = NetAddress::from_str(&format!("{host}:16111")).unwrap();
+        assert_eq!(addr, NetAddress::new_onion(onion, 16111));
+        assert_eq!(addr.ip, onion.mapped_ip());
+        assert!(addr.is_publicly_routable());
+        assert_eq!(addr.to_string(), format!("{host}:16111"));
+        assert!(NetAddress::from_str(host).is_err());
+
+        let contextual = ContextualNetAddress::from_str(host).unwrap();
+        assert_eq!(contextual.to_string(), host);
+        assert_eq!(contextual.normalize(16111), addr);
+
+        let json = serde_json::to_string(&addr).unwrap();
+        assert_eq!(addr, serde_json::from_str::<NetAddress>(&json).unwrap());
+        let bin = addr.try_to_vec().unwrap();
+        assert_eq!(addr, BorshDeserialize::try_from_slice(&bin).unwrap());
+    }
+
     #[test]
     fn test_peer_id_borsh() {
         // Tests for PeerId Borsh ser/deser since we manually implemented them
@@ -0,0 +1,118 @@
+//!
+//! TLS configurations shared by the RPC servers and clients. Certificates and keys are held PEM encoded, the
+//! actual TLS implementation being left to the crates using them, except for the rustls configuration of the
+//! servers terminating TLS themselves.
+//!
+
+use std::{fmt, fs, io, path::Path};
+
+/// TLS configuration of an RPC listener
+#[derive(Clone)]
+pub struct TlsServerConfig {
+    /// PEM encoded certificate chain of the server
+    pub cert: Vec<u8>,
+
+    /// PEM encoded private key of the server
+    pub key: Vec<u8>,
+
+    /// PEM encoded CA certificates used to verify client certificates. Clients are required to present a
+    /// certificate signed by one of them if set.
+    pub client_ca: Option<Vec<u8>>,
+}
+
+impl TlsServerConfig {
+    pub fn new(cert: Vec<u8>, key: Vec<u8>, client_ca: Option<Vec<u8>>) -> Self {
+        Self { cert, key, client_ca }
+    }
+
+    pub fn from_files(cert: impl AsRef<Path>, key: impl AsRef<Path>, client_ca: Option<impl AsRef<Path>>) -> io::Result<Self> {
+        let client_ca = client_ca.map(read_pem_file).transpose()?;
+        Ok(Self::new(read_pem_file(cert)?, read_pem_file(key)?, client_ca))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl TlsServerConfig {
+    /// Builds the rustls configuration of a listener terminating TLS itself
+    pub fn rustls_config(&self) -> io::Result<tokio_rustls::rustls::ServerConfig> {
+        use std::io::Cursor;
+        use tokio_rustls::rustls::{server::AllowAnyAuthenticatedClient, Certificate, PrivateKey, RootCertStore, ServerConfig};
+
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let builder = match self.client_ca.as_ref() {
+            Some(client_ca) => {
+                let mut roots = RootCertStore::empty();
+                let (added, _) = roots.add_parsable_certificates(&rustls_pemfile::certs(&mut Cursor::new(client_ca))?);
+                if added == 0 {
+                    return Err(invalid("no valid client CA certificate".to_string()));
+                }
+                builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+            }
+            None => builder.with_no_client_auth(),
+        };
+        let certs = rustls_pemfile::certs(&mut Cursor::new(&self.cert))?.into_iter().map(Certificate).collect();
+        let key = rustls_pemfile::read_all(&mut Cursor::new(&self.key))?
+            .into_iter()
+            .find_map(|item| match item {
+                rustls_pemfile::Item::RSAKey(key) | rustls_pemfile::Item::PKCS8Key(key) | rustls_pemfile::Item::ECKey(key) => {
+                    Some(PrivateKey(key))
+                }
+                _ => None,
+            })
+            .ok_or_else(|| invalid("no private key found".to_string()))?;
+        builder.with_single_cert(certs, key).map_err(|err| invalid(err.to_string()))
+    }
+}
+
+impl fmt::Debug for TlsServerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsServerConfig").field("client_auth", &self.client_ca.is_some()).finish_non_exhaustive()
+    }
+}
+
+/// TLS configuration of an RPC client
+#[derive(Clone, Default)]
+pub struct TlsClientConfig {
+    /// PEM encoded CA certificates used to verify the server certificate
+    pub ca: Option<Vec<u8>>,
+
+    /// PEM encoded certificate chain and private key presented to servers requiring client authentication
+    pub identity: Option<(Vec<u8>, Vec<u8>)>,
+
+    /// Name the server certificate is verified against, defaults to the host of the server URL
+    pub domain: Option<String>,
+}
+
+impl TlsClientConfig {
+    pub fn new(ca: Vec<u8>) -> Self {
+        Self { ca: Some(ca), ..Default::default() }
+    }
+
+    pub fn from_file(ca: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(read_pem_file(ca)?))
+    }
+
+    pub fn with_identity(self, cert: Vec<u8>, key: Vec<u8>) -> Self {
+        Self { identity: Some((cert, key)), ..self }
+    }
+
+    pub fn with_domain(self, domain: impl Into<String>) -> Self {
+        Self { domain: Some(domain.into()), ..self }
+    }
+}
+
+impl fmt::Debug for TlsClientConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsClientConfig")
+            .field("ca", &self.ca.is_some())
+            .field("identity", &self.identity.is_some())
+            .field("domain", &self.domain)
+            .finish()
+    }
+}
+
+fn read_pem_file(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+    let path = path.as_ref();
+    fs::read(path).map_err(|err| io::Error::new(err.kind(), format!("failed reading {}: {err}", path.display())))
+}
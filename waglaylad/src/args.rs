@@ -1,11 +1,16 @@
 use clap::{arg, Arg, ArgAction, Command};
 use waglayla_consensus_core::{
-    config::Config,
+    config::{Config, DEFAULT_BAN_DURATION, DEFAULT_BAN_SCORE_DECAY, DEFAULT_BAN_THRESHOLD},
     network::{NetworkId, NetworkType},
 };
 use waglayla_core::waglaylad_env::version;
 use waglayla_notify::address::tracker::Tracker;
-use waglayla_utils::networking::ContextualNetAddress;
+use waglayla_p2p_lib::PinnedPeer;
+use waglayla_rpc_core::api::{
+    auth::{RpcAccount, RpcPermissions},
+    limits::{RpcMethodCost, RpcRateLimit},
+};
+use waglayla_utils::networking::{ContextualNetAddress, IpSubnet};
 use waglayla_wrpc_server::address::WrpcNetAddress;
 use serde::Deserialize;
 use serde_with::{serde_as, DisplayFromStr};
@@ -38,6 +43,10 @@ pub struct Args {
     pub rpclisten_borsh: Option<WrpcNetAddress>,
     #[serde_as(as = "Option<DisplayFromStr>")]
     pub rpclisten_json: Option<WrpcNetAddress>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub rpclisten_http: Option<ContextualNetAddress>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub prometheus_listen: Option<ContextualNetAddress>,
     #[serde(rename = "unsaferpc")]
     pub unsafe_rpc: bool,
     pub wrpc_verbose: bool,
@@ -55,13 +64,61 @@ pub struct Args {
     #[serde(rename = "uacomment")]
     pub user_agent_comments: Vec<String>,
     pub utxoindex: bool,
+    pub txindex: bool,
+    pub addressindex: bool,
+    pub chainindex: bool,
     pub reset_db: bool,
+    pub check_db: bool,
+    pub repair_db: bool,
+    pub import_snapshot: Option<String>,
+    pub export_snapshot: Option<String>,
     #[serde(rename = "outpeers")]
     pub outbound_target: usize,
     #[serde(rename = "maxinpeers")]
     pub inbound_limit: usize,
+    #[serde(rename = "banthreshold")]
+    pub ban_threshold: u32,
+    #[serde(rename = "bandecay")]
+    pub ban_score_decay: u32,
+    #[serde(rename = "banduration")]
+    pub ban_duration: u64,
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub whitelist: Vec<IpSubnet>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub proxy: Option<ContextualNetAddress>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub onion: Option<ContextualNetAddress>,
+    #[serde(rename = "identitykey")]
+    pub identity_key: Option<String>,
+    #[serde(rename = "pinpeer")]
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub pinned_peers: Vec<PinnedPeer>,
+    pub dandelion: bool,
     #[serde(rename = "rpcmaxclients")]
     pub rpc_max_clients: usize,
+    #[serde(rename = "rpcauth")]
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub rpc_accounts: Vec<RpcAccount>,
+    #[serde(rename = "rpcpublic")]
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub rpc_public_permissions: Option<RpcPermissions>,
+    #[serde(rename = "rpccert")]
+    pub rpc_cert: Option<String>,
+    #[serde(rename = "rpckey")]
+    pub rpc_key: Option<String>,
+    #[serde(rename = "rpcclientca")]
+    pub rpc_client_ca: Option<String>,
+    #[serde(rename = "rpcratelimit")]
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub rpc_rate_limit: Option<RpcRateLimit>,
+    #[serde(rename = "rpciplimit")]
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub rpc_ip_rate_limit: Option<RpcRateLimit>,
+    #[serde(rename = "rpcmaxipclients")]
+    pub rpc_max_ip_clients: Option<usize>,
+    #[serde(rename = "rpccost")]
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub rpc_costs: Vec<RpcMethodCost>,
     pub max_tracked_addresses: usize,
     pub enable_unsynced_mining: bool,
     pub enable_mainnet_mining: bool,
@@ -101,13 +158,39 @@ impl Default for Args {
             no_log_files: false,
             rpclisten_borsh: None,
             rpclisten_json: None,
+            rpclisten_http: None,
             unsafe_rpc: false,
             async_threads: num_cpus::get(),
             utxoindex: false,
+            txindex: false,
+            addressindex: false,
+            chainindex: false,
             reset_db: false,
+            check_db: false,
+            repair_db: false,
+            import_snapshot: None,
+            export_snapshot: None,
             outbound_target: 8,
             inbound_limit: 128,
+            ban_threshold: DEFAULT_BAN_THRESHOLD,
+            ban_score_decay: DEFAULT_BAN_SCORE_DECAY,
+            ban_duration: DEFAULT_BAN_DURATION,
+            whitelist: vec![],
+            proxy: None,
+            onion: None,
+            identity_key: None,
+            pinned_peers: vec![],
+            dandelion: false,
             rpc_max_clients: 128,
+            rpc_accounts: vec![],
+            rpc_public_permissions: None,
+            rpc_cert: None,
+            rpc_key: None,
+            rpc_client_ca: None,
+            rpc_rate_limit: None,
+            rpc_ip_rate_limit: None,
+            rpc_max_ip_clients: None,
+            rpc_costs: vec![],
             max_tracked_addresses: 0,
             enable_unsynced_mining: false,
             enable_mainnet_mining: true,
@@ -119,6 +202,7 @@ impl Default for Args {
             sanity: false,
             logdir: None,
             rpclisten: None,
+            prometheus_listen: None,
             wrpc_verbose: false,
             log_level: "INFO".into(),
             connect_peers: vec![],
@@ -149,7 +233,13 @@ impl Default for Args {
 impl Args {
     pub fn apply_to_config(&self, config: &mut Config) {
         config.utxoindex = self.utxoindex;
+        config.txindex = self.txindex;
+        config.addressindex = self.addressindex;
+        config.chainindex = self.chainindex;
         config.disable_upnp = self.disable_upnp;
+        config.ban_threshold = self.ban_threshold;
+        config.ban_score_decay = self.ban_score_decay;
+        config.ban_duration = self.ban_duration;
         config.unsafe_rpc = self.unsafe_rpc;
         config.enable_unsynced_mining = self.enable_unsynced_mining;
         config.enable_mainnet_mining = self.enable_mainnet_mining;
@@ -160,6 +250,8 @@ impl Args {
         config.block_template_cache_lifetime = self.block_template_cache_lifetime;
         config.p2p_listen_address = self.listen.unwrap_or(ContextualNetAddress::unspecified());
         config.externalip = self.externalip.map(|v| v.normalize(config.default_p2p_port()));
+        config.disable_address_discovery = self.proxy.is_some();
+        config.dandelion = self.dandelion;
         config.ram_scale = self.ram_scale;
 
         #[cfg(feature = "devnet-prealloc")]
@@ -252,6 +344,22 @@ pub fn cli() -> Command {
                 .value_parser(clap::value_parser!(WrpcNetAddress))
                 .help("Interface:port to listen for wRPC JSON connections (default port: 14110, testnet: 14210)."),
         )
+        .arg(
+            Arg::new("rpclisten-http")
+                .long("rpclisten-http")
+                .value_name("IP[:PORT]")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(ContextualNetAddress))
+                .help("Interface:port to serve JSON-RPC 2.0 requests POSTed over HTTP, or HTTPS with --rpccert, on (default port: 17110, testnet: 17210)."),
+        )
+        .arg(
+            Arg::new("prometheus-listen")
+                .long("prometheus-listen")
+                .value_name("IP[:PORT]")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(ContextualNetAddress))
+                .help("Interface:port to serve Prometheus/OpenMetrics node metrics on /metrics (default port: 15110, testnet: 15210)."),
+        )
         .arg(arg!(--unsaferpc "Enable RPC commands which affect the state of the node"))
         .arg(
             Arg::new("connect-peers")
@@ -295,6 +403,72 @@ pub fn cli() -> Command {
                 .value_parser(clap::value_parser!(usize))
                 .help("Max number of inbound peers (default: 128)."),
         )
+        .arg(
+            Arg::new("banthreshold")
+                .long("banthreshold")
+                .value_name("banthreshold")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u32))
+                .help("Misbehaviour score at which a peer is banned, 0 disables automatic banning (default: 100)."),
+        )
+        .arg(
+            Arg::new("bandecay")
+                .long("bandecay")
+                .value_name("bandecay")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u32))
+                .help("Points per minute by which peer misbehaviour scores decay (default: 1)."),
+        )
+        .arg(
+            Arg::new("banduration")
+                .long("banduration")
+                .value_name("banduration")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .help("How long to ban misbehaving peers, in seconds (default: 86400)."),
+        )
+        .arg(
+            Arg::new("whitelist")
+                .long("whitelist")
+                .value_name("IP[/PREFIX]")
+                .action(ArgAction::Append)
+                .require_equals(true)
+                .value_parser(clap::value_parser!(IpSubnet))
+                .help("Add an IP network or IP that is exempt from bans and from the inbound peer limit (eg. 192.168.1.0/24 or ::1)."),
+        )
+        .arg(
+            Arg::new("proxy")
+                .long("proxy")
+                .value_name("IP[:PORT]")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(ContextualNetAddress))
+                .help("Connect to peers via a SOCKS5 proxy, hiding the node IP; disables DNS seeding (default port: 9050)."),
+        )
+        .arg(
+            Arg::new("onion")
+                .long("onion")
+                .value_name("IP[:PORT]")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(ContextualNetAddress))
+                .help("Connect to Tor onion peers via a SOCKS5 proxy (default: the --proxy address)."),
+        )
+        .arg(
+            Arg::new("identitykey")
+                .long("identitykey")
+                .value_name("FILE")
+                .require_equals(true)
+                .help("Authenticate to encrypted peers with the identity key stored in FILE, which is generated if it does not exist."),
+        )
+        .arg(
+            Arg::new("pinpeer")
+                .long("pinpeer")
+                .value_name("PUBKEY@IP")
+                .action(ArgAction::Append)
+                .require_equals(true)
+                .value_parser(clap::value_parser!(PinnedPeer))
+                .help("Require the peer at IP to authenticate with the given identity public key, and trust it, exempting it from bans and from the inbound peer limit."),
+        )
+        .arg(arg!(--dandelion "Relay transactions along a random stem of peers before diffusing them to the network, hiding their origin"))
         .arg(
             Arg::new("rpcmaxclients")
                 .long("rpcmaxclients")
@@ -303,7 +477,106 @@ pub fn cli() -> Command {
                 .value_parser(clap::value_parser!(usize))
                 .help("Max number of RPC clients for standard connections (default: 128)."),
         )
+        .arg(
+            Arg::new("rpcauth")
+                .long("rpcauth")
+                .value_name("TOKEN:PERMISSIONS|USER:PASSWORD:PERMISSIONS")
+                .action(ArgAction::Append)
+                .require_equals(true)
+                .value_parser(clap::value_parser!(RpcAccount))
+                .help("Add an RPC account identified by a token or a username and password and granted a comma-separated list of permissions among read, submit-transaction, mining and admin, or all (eg. s3cr3t:read,submit-transaction)."),
+        )
+        .arg(
+            Arg::new("rpcpublic")
+                .long("rpcpublic")
+                .value_name("PERMISSIONS")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(RpcPermissions))
+                .help("Permissions granted to RPC clients which do not authenticate (default: all if no --rpcauth account is set, none otherwise)."),
+        )
+        .arg(
+            Arg::new("rpccert")
+                .long("rpccert")
+                .value_name("FILE")
+                .requires("rpckey")
+                .require_equals(true)
+                .help("Serve RPC connections over TLS using the PEM encoded certificate chain stored in FILE."),
+        )
+        .arg(
+            Arg::new("rpckey")
+                .long("rpckey")
+                .value_name("FILE")
+                .requires("rpccert")
+                .require_equals(true)
+                .help("PEM encoded private key of the RPC TLS certificate."),
+        )
+        .arg(
+            Arg::new("rpcclientca")
+                .long("rpcclientca")
+                .value_name("FILE")
+                .requires("rpccert")
+                .require_equals(true)
+                .help("Require RPC clients to present a TLS certificate issued by one of the PEM encoded CA certificates stored in FILE."),
+        )
+        .arg(
+            Arg::new("rpcratelimit")
+                .long("rpcratelimit")
+                .value_name("RATE[:BURST]")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(RpcRateLimit))
+                .help("Limit every RPC connection to RATE call units per second, allowing bursts of up to BURST units (default: unlimited)."),
+        )
+        .arg(
+            Arg::new("rpciplimit")
+                .long("rpciplimit")
+                .value_name("RATE[:BURST]")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(RpcRateLimit))
+                .help("Limit the RPC connections of every IP address to a total of RATE call units per second, allowing bursts of up to BURST units (default: unlimited)."),
+        )
+        .arg(
+            Arg::new("rpcmaxipclients")
+                .long("rpcmaxipclients")
+                .value_name("rpcmaxipclients")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(usize))
+                .help("Max number of RPC clients connected from the same IP address (default: unlimited)."),
+        )
+        .arg(
+            Arg::new("rpccost")
+                .long("rpccost")
+                .value_name("METHOD:COST")
+                .action(ArgAction::Append)
+                .require_equals(true)
+                .value_parser(clap::value_parser!(RpcMethodCost))
+                .help("Override the number of call units charged by the rate limits for an RPC method (eg. GetUtxosByAddresses:100)."),
+        )
         .arg(arg!(--"reset-db" "Reset database before starting node. It's needed when switching between subnetworks."))
+        .arg(arg!(--"check-db" "Verify the consistency of the database in read-only mode, print a JSON report and exit without starting the node."))
+        .arg(
+            Arg::new("repair-db")
+                .long("repair-db")
+                .action(ArgAction::SetTrue)
+                .requires("check-db")
+                .help("Apply safe repairs for inconsistencies found by --check-db (currently: drop an out-of-sync UTXO index so it is rebuilt on the next start)."),
+        )
+        .arg(
+            Arg::new("import-snapshot")
+                .long("import-snapshot")
+                .value_name("import-snapshot")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(String))
+                .conflicts_with("export-snapshot")
+                .help("Import a consensus snapshot file on startup. The snapshot is validated exactly like a headers proof received during IBD."),
+        )
+        .arg(
+            Arg::new("export-snapshot")
+                .long("export-snapshot")
+                .value_name("export-snapshot")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(String))
+                .help("Export a consensus snapshot (pruning point proof, trusted data and UTXO set) to the given file on startup."),
+        )
         .arg(arg!(--"enable-unsynced-mining" "Allow the node to accept blocks from RPC while not synced (this flag is mainly used for testing)"))
         .arg(
             Arg::new("enable-mainnet-mining")
@@ -313,6 +586,9 @@ pub fn cli() -> Command {
                 .help("Allow mainnet mining (currently enabled by default while the flag is kept for backwards compatibility)"),
         )
         .arg(arg!(--utxoindex "Enable the UTXO index"))
+        .arg(arg!(--txindex "Enable the transaction index"))
+        .arg(arg!(--addressindex "Enable the address transaction history index"))
+        .arg(arg!(--chainindex "Enable the chain block index by DAA score and timestamp"))
         .arg(
             Arg::new("max-tracked-addresses")
                 .long("max-tracked-addresses")
@@ -418,6 +694,8 @@ impl Args {
             rpclisten: m.get_one::<ContextualNetAddress>("rpclisten").cloned().or(defaults.rpclisten),
             rpclisten_borsh: m.get_one::<WrpcNetAddress>("rpclisten-borsh").cloned().or(defaults.rpclisten_borsh),
             rpclisten_json: m.get_one::<WrpcNetAddress>("rpclisten-json").cloned().or(defaults.rpclisten_json),
+            rpclisten_http: m.get_one::<ContextualNetAddress>("rpclisten-http").cloned().or(defaults.rpclisten_http),
+            prometheus_listen: m.get_one::<ContextualNetAddress>("prometheus-listen").cloned().or(defaults.prometheus_listen),
             unsafe_rpc: arg_match_unwrap_or::<bool>(&m, "unsaferpc", defaults.unsafe_rpc),
             wrpc_verbose: false,
             log_level: arg_match_unwrap_or::<String>(&m, "log_level", defaults.log_level),
@@ -427,12 +705,37 @@ impl Args {
             listen: m.get_one::<ContextualNetAddress>("listen").cloned().or(defaults.listen),
             outbound_target: arg_match_unwrap_or::<usize>(&m, "outpeers", defaults.outbound_target),
             inbound_limit: arg_match_unwrap_or::<usize>(&m, "maxinpeers", defaults.inbound_limit),
+            ban_threshold: arg_match_unwrap_or::<u32>(&m, "banthreshold", defaults.ban_threshold),
+            ban_score_decay: arg_match_unwrap_or::<u32>(&m, "bandecay", defaults.ban_score_decay),
+            ban_duration: arg_match_unwrap_or::<u64>(&m, "banduration", defaults.ban_duration),
+            whitelist: arg_match_many_unwrap_or::<IpSubnet>(&m, "whitelist", defaults.whitelist),
+            proxy: m.get_one::<ContextualNetAddress>("proxy").cloned().or(defaults.proxy),
+            onion: m.get_one::<ContextualNetAddress>("onion").cloned().or(defaults.onion),
+            identity_key: m.get_one::<String>("identitykey").cloned().or(defaults.identity_key),
+            pinned_peers: arg_match_many_unwrap_or::<PinnedPeer>(&m, "pinpeer", defaults.pinned_peers),
+            dandelion: arg_match_unwrap_or::<bool>(&m, "dandelion", defaults.dandelion),
             rpc_max_clients: arg_match_unwrap_or::<usize>(&m, "rpcmaxclients", defaults.rpc_max_clients),
+            rpc_accounts: arg_match_many_unwrap_or::<RpcAccount>(&m, "rpcauth", defaults.rpc_accounts),
+            rpc_public_permissions: m.get_one::<RpcPermissions>("rpcpublic").cloned().or(defaults.rpc_public_permissions),
+            rpc_cert: m.get_one::<String>("rpccert").cloned().or(defaults.rpc_cert),
+            rpc_key: m.get_one::<String>("rpckey").cloned().or(defaults.rpc_key),
+            rpc_client_ca: m.get_one::<String>("rpcclientca").cloned().or(defaults.rpc_client_ca),
+            rpc_rate_limit: m.get_one::<RpcRateLimit>("rpcratelimit").cloned().or(defaults.rpc_rate_limit),
+            rpc_ip_rate_limit: m.get_one::<RpcRateLimit>("rpciplimit").cloned().or(defaults.rpc_ip_rate_limit),
+            rpc_max_ip_clients: m.get_one::<usize>("rpcmaxipclients").cloned().or(defaults.rpc_max_ip_clients),
+            rpc_costs: arg_match_many_unwrap_or::<RpcMethodCost>(&m, "rpccost", defaults.rpc_costs),
             max_tracked_addresses: arg_match_unwrap_or::<usize>(&m, "max-tracked-addresses", defaults.max_tracked_addresses),
             reset_db: arg_match_unwrap_or::<bool>(&m, "reset-db", defaults.reset_db),
+            check_db: arg_match_unwrap_or::<bool>(&m, "check-db", defaults.check_db),
+            repair_db: arg_match_unwrap_or::<bool>(&m, "repair-db", defaults.repair_db),
+            import_snapshot: m.get_one::<String>("import-snapshot").cloned().or(defaults.import_snapshot),
+            export_snapshot: m.get_one::<String>("export-snapshot").cloned().or(defaults.export_snapshot),
             enable_unsynced_mining: arg_match_unwrap_or::<bool>(&m, "enable-unsynced-mining", defaults.enable_unsynced_mining),
             enable_mainnet_mining: arg_match_unwrap_or::<bool>(&m, "enable-mainnet-mining", defaults.enable_mainnet_mining),
             utxoindex: arg_match_unwrap_or::<bool>(&m, "utxoindex", defaults.utxoindex),
+            txindex: arg_match_unwrap_or::<bool>(&m, "txindex", defaults.txindex),
+            addressindex: arg_match_unwrap_or::<bool>(&m, "addressindex", defaults.addressindex),
+            chainindex: arg_match_unwrap_or::<bool>(&m, "chainindex", defaults.chainindex),
             testnet: arg_match_unwrap_or::<bool>(&m, "testnet", defaults.testnet),
             testnet_suffix: arg_match_unwrap_or::<u32>(&m, "netsuffix", defaults.testnet_suffix),
             devnet: arg_match_unwrap_or::<bool>(&m, "devnet", defaults.devnet),
@@ -459,6 +762,17 @@ impl Args {
             prealloc_amount: arg_match_unwrap_or::<u64>(&m, "prealloc-amount", defaults.prealloc_amount),
         };
 
+        // The TLS files may be set in the config file as well, so their dependencies are checked once merged
+        let tls_error = match (&args.rpc_cert, &args.rpc_key, &args.rpc_client_ca) {
+            (Some(_), None, _) => Some("--rpccert requires --rpckey"),
+            (None, Some(_), _) => Some("--rpckey requires --rpccert"),
+            (None, None, Some(_)) => Some("--rpcclientca requires --rpccert and --rpckey"),
+            _ => None,
+        };
+        if let Some(tls_error) = tls_error {
+            return Err(clap::Error::raw(clap::error::ErrorKind::MissingRequiredArgument, tls_error.to_string()));
+        }
+
         if arg_match_unwrap_or::<bool>(&m, "enable-mainnet-mining", false) {
             println!("\nNOTE: The flag --enable-mainnet-mining is deprecated and defaults to true also w/o explicit setting\n")
         }
@@ -502,6 +816,7 @@ fn arg_match_many_unwrap_or<T: Clone + Send + Sync + 'static>(m: &clap::ArgMatch
                                             1 second (default: 24h0m0s)
       --banthreshold=                       Maximum allowed ban score before disconnecting and banning misbehaving
                                             peers. (default: 100)
+      --bandecay=                           Points per minute by which peer ban scores decay (default: 1)
       --whitelist=                          Add an IP network or IP that will not be banned. (eg. 192.168.1.0/24 or
                                             ::1)
       --rpclisten=                          Add an interface/port to listen for RPC connections (default port: 12110,
@@ -511,6 +826,7 @@ fn arg_match_many_unwrap_or<T: Clone + Send + Sync + 'static>(m: &clap::ArgMatch
       --rpckey=                             File containing the certificate key (default:
                                             /Users/aspect/Library/Application Support/Waglaylad/rpc.key)
       --rpcmaxclients=                      Max number of RPC clients for standard connections (default: 128)
+      --prometheus-listen=                  Interface/port to serve Prometheus/OpenMetrics node metrics on /metrics
       --rpcmaxwebsockets=                   Max number of RPC websocket connections (default: 25)
       --rpcmaxconcurrentreqs=               Max number of concurrent RPC requests that may be processed concurrently
                                             (default: 20)
@@ -521,6 +837,7 @@ fn arg_match_many_unwrap_or<T: Clone + Send + Sync + 'static>(m: &clap::ArgMatch
       --grpcseed=                           Hostname of gRPC server for seeding peers
       --externalip=                         Add an ip to the list of local addresses we claim to listen on to peers
       --proxy=                              Connect via SOCKS5 proxy (eg. 127.0.0.1:9050)
+      --onion=                              Connect to tor hidden services via SOCKS5 proxy (default: --proxy)
       --proxyuser=                          Username for proxy server
       --proxypass=                          Password for proxy server
       --dbtype=                             Database backend to use for the Block DAG
@@ -548,9 +865,16 @@ fn arg_match_many_unwrap_or<T: Clone + Send + Sync + 'static>(m: &clap::ArgMatch
                                             the active network.
       --reset-db                            Reset database before starting node. It's needed when switching between
                                             subnetworks.
+      --check-db                            Verify the consistency of the database, print a JSON report and exit
+      --repair-db                           Apply safe repairs for inconsistencies found by --check-db
+      --import-snapshot=                    Import a consensus snapshot file on startup
+      --export-snapshot=                    Export a consensus snapshot to the given file on startup
       --maxutxocachesize=                   Max size of loaded UTXO into ram from the disk in bytes (default:
                                             5000000000)
       --utxoindex                           Enable the UTXO index
+      --txindex                             Enable the transaction index
+      --addressindex                        Enable the address transaction history index
+      --chainindex                          Enable the chain block index by DAA score and timestamp
       --archival                            Run as an archival node: don't delete old block data when moving the
                                             pruning point (Warning: heavy disk usage)'
       --protocol-version=                   Use non default p2p protocol version (default: 5)
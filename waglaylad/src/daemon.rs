@@ -4,23 +4,38 @@ use async_channel::unbounded;
 use waglayla_consensus_core::{
     config::ConfigBuilder,
     errors::config::{ConfigError, ConfigResult},
+    BlockHashSet,
 };
 use waglayla_consensus_notify::{root::ConsensusNotificationRoot, service::NotifyService};
 use waglayla_core::{core::Core, info, trace};
 use waglayla_core::{waglaylad_env::version, task::tick::TickService};
-use waglayla_database::prelude::CachePolicy;
+use waglayla_database::prelude::{CachePolicy, StoreResultExtensions, DB};
 use waglayla_grpc_server::service::GrpcService;
+use waglayla_jsonrpc_server::service::JsonRpcService;
 use waglayla_notify::{address::tracker::Tracker, subscription::context::SubscriptionContext};
+use waglayla_rpc_core::api::{
+    auth::RpcAuthenticator,
+    limits::{RpcLimiter, RpcLimits},
+};
 use waglayla_rpc_service::service::RpcCoreService;
 use waglayla_txscript::caches::TxScriptCacheCounters;
-use waglayla_utils::networking::ContextualNetAddress;
+use waglayla_utils::{networking::ContextualNetAddress, tls::TlsServerConfig};
 use waglayla_utils_tower::counters::TowerConnectionCounters;
 
+use waglayla_addressindex::{api::AddressIndexProxy, AddressIndex};
+use waglayla_chainindex::{api::ChainIndexProxy, ChainIndex};
 use waglayla_addressmanager::AddressManager;
 use waglayla_consensus::{consensus::factory::Factory as ConsensusFactory, pipeline::ProcessingCounters};
 use waglayla_consensus::{
     consensus::factory::MultiConsensusManagementStore, model::stores::headers::DbHeadersStore, pipeline::monitor::ConsensusMonitor,
 };
+use waglayla_consensus::{
+    consensus::{
+        db_check::{ConsensusDbChecker, DbCheck, DEFAULT_MAX_REPORTED_ERRORS},
+        storage::ConsensusStorage,
+    },
+    model::stores::virtual_state::VirtualStateStoreReader,
+};
 use waglayla_consensusmanager::ConsensusManager;
 use waglayla_core::task::runtime::AsyncRuntime;
 use waglayla_index_processor::service::IndexService;
@@ -30,8 +45,10 @@ use waglayla_mining::{
     MiningCounters,
 };
 use waglayla_p2p_flows::{flow_context::FlowContext, service::P2pService};
+use waglayla_p2p_lib::NodeIdentity;
 
 use waglayla_perf_monitor::{builder::Builder as PerfMonitorBuilder, counters::CountersSnapshot};
+use waglayla_txindex::{api::TxIndexProxy, TxIndex};
 use waglayla_utxoindex::{api::UtxoIndexProxy, UtxoIndex};
 use waglayla_wrpc_server::service::{Options as WrpcServerOptions, WebSocketCounters as WrpcServerCounters, WrpcEncoding, WrpcService};
 
@@ -44,14 +61,18 @@ pub const DESIRED_DAEMON_SOFT_FD_LIMIT: u64 = 8 * 1024;
 /// this value may impact the database performance).
 pub const MINIMUM_DAEMON_SOFT_FD_LIMIT: u64 = 4 * 1024;
 
-use crate::args::Args;
+use crate::{args::Args, prometheus::PrometheusService};
 
 const DEFAULT_DATA_DIR: &str = "datadir";
 const CONSENSUS_DB: &str = "consensus";
 const UTXOINDEX_DB: &str = "utxoindex";
+const TXINDEX_DB: &str = "txindex";
+const ADDRESSINDEX_DB: &str = "addressindex";
+const CHAININDEX_DB: &str = "chainindex";
 const META_DB: &str = "meta";
 const META_DB_FILE_LIMIT: i32 = 5;
 const DEFAULT_LOG_DIR: &str = "logs";
+const DEFAULT_PROXY_PORT: u16 = 9050; // Default SOCKS port of Tor
 
 fn get_home_dir() -> PathBuf {
     #[cfg(target_os = "windows")]
@@ -171,6 +192,140 @@ impl Runtime {
     }
 }
 
+/// Open the node databases in read-only mode, verify their consistency and print a JSON report to stdout.
+/// If [`Args::repair_db`] is set, safe repairs are applied for the inconsistencies found.
+///
+/// Returns the process exit code: `0` if no unrepaired inconsistencies were found, `1` otherwise.
+pub fn check_db(args: &Args, fd_total_budget: i32) -> i32 {
+    if let Err(err) = validate_args(args) {
+        println!("{}", err);
+        return 1;
+    }
+
+    let network = args.network();
+    let config = Arc::new(
+        ConfigBuilder::new(network.into())
+            .adjust_perf_params_to_consensus_params()
+            .apply_args(|config| args.apply_to_config(config))
+            .build(),
+    );
+
+    let db_dir = get_app_dir_from_args(args).join(network.to_prefixed()).join(DEFAULT_DATA_DIR);
+    let meta_db_dir = db_dir.join(META_DB);
+    if !meta_db_dir.exists() {
+        println!("No database found at {}", db_dir.display());
+        return 1;
+    }
+
+    let meta_db = waglayla_database::prelude::ConnBuilder::default()
+        .with_db_path(meta_db_dir)
+        .with_files_limit(META_DB_FILE_LIMIT)
+        .with_read_only(true)
+        .build()
+        .unwrap();
+    let Some(dir_name) =
+        MultiConsensusManagementStore::new_uninitialized(meta_db).active_consensus_dir_name().unwrap_option().flatten()
+    else {
+        println!("Consensus is not initialized yet, nothing to check");
+        return 0;
+    };
+
+    let consensus_db = waglayla_database::prelude::ConnBuilder::default()
+        .with_db_path(db_dir.join(CONSENSUS_DB).join(dir_name))
+        .with_files_limit(fd_total_budget)
+        .with_read_only(true)
+        .build()
+        .unwrap();
+    let storage = ConsensusStorage::new(consensus_db, config.clone());
+    let mut report = ConsensusDbChecker::new(storage.clone(), &config).run();
+    let is_consensus_consistent = report.is_consistent();
+
+    // The UTXO index can always be rebuilt from a consistent consensus database
+    let mut is_utxoindex_repaired = false;
+    let utxoindex_db_dir = db_dir.join(UTXOINDEX_DB);
+    if utxoindex_db_dir.exists() {
+        let utxoindex_db = waglayla_database::prelude::ConnBuilder::default()
+            .with_db_path(utxoindex_db_dir.clone())
+            .with_files_limit(fd_total_budget * 10 / 100)
+            .with_read_only(true)
+            .build()
+            .unwrap();
+        let check = check_utxoindex(&storage, utxoindex_db);
+        let is_rebuild_needed = !check.is_consistent();
+        report.push(check);
+
+        if is_rebuild_needed && args.repair_db {
+            if is_consensus_consistent {
+                fs::remove_dir_all(&utxoindex_db_dir).unwrap();
+                report.repairs.push(format!(
+                    "Deleted the UTXO index at {} so that it is rebuilt from consensus on the next start with --utxoindex",
+                    utxoindex_db_dir.display()
+                ));
+                is_utxoindex_repaired = true;
+            } else {
+                report.repairs.push("Skipped rebuilding the UTXO index since the consensus database is inconsistent".to_owned());
+            }
+        }
+    }
+
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    if !is_consensus_consistent {
+        eprintln!(
+            "The consensus database is inconsistent and cannot be repaired in place. Restart the node with --reset-db to resync."
+        );
+        return 1;
+    }
+    if report.is_consistent() || is_utxoindex_repaired {
+        0
+    } else {
+        1
+    }
+}
+
+/// Verify that the UTXO index is synced to the consensus virtual state and that its content matches the virtual UTXO set
+fn check_utxoindex(storage: &ConsensusStorage, utxoindex_db: Arc<DB>) -> DbCheck {
+    let mut check = DbCheck::new("utxoindex", DEFAULT_MAX_REPORTED_ERRORS);
+    let virtual_state = match storage.virtual_stores.read().state.get() {
+        Ok(virtual_state) => virtual_state,
+        Err(err) => {
+            check.report_error(format_args!("failed reading the virtual state: {err}"));
+            return check;
+        }
+    };
+
+    match UtxoIndex::read_synced_tips(utxoindex_db.clone()) {
+        Ok(Some(tips)) => {
+            check.checked += 1;
+            if *tips != virtual_state.parents.iter().copied().collect::<BlockHashSet>() {
+                check.report_error("the index is not synced to the consensus virtual parents");
+                return check;
+            }
+        }
+        Ok(None) => {
+            check.report_error("the index was never synced");
+            return check;
+        }
+        Err(err) => {
+            check.report_error(err);
+            return check;
+        }
+    }
+
+    match UtxoIndex::read_utxo_commitment(utxoindex_db) {
+        Ok(commitment) => {
+            check.checked += 1;
+            let expected = virtual_state.multiset.clone().finalize();
+            if commitment != expected {
+                check.report_error(format_args!(
+                    "the index UTXO commitment {commitment} does not match the virtual UTXO commitment {expected}"
+                ));
+            }
+        }
+        Err(err) => check.report_error(err),
+    }
+    check
+}
+
 /// Create [`Core`] instance with supplied [`Args`].
 /// This function will automatically create a [`Runtime`]
 /// instance with the supplied [`Args`] and then
@@ -208,6 +363,27 @@ pub fn create_core_with_runtime(runtime: &Runtime, args: &Args, fd_total_budget:
     } else {
         0
     };
+    let tx_files_limit = if args.txindex {
+        let tx_files_limit = fd_remaining * 10 / 100;
+        fd_remaining -= tx_files_limit;
+        tx_files_limit
+    } else {
+        0
+    };
+    let address_files_limit = if args.addressindex {
+        let address_files_limit = fd_remaining * 10 / 100;
+        fd_remaining -= address_files_limit;
+        address_files_limit
+    } else {
+        0
+    };
+    let chain_files_limit = if args.chainindex {
+        let chain_files_limit = fd_remaining * 10 / 100;
+        fd_remaining -= chain_files_limit;
+        chain_files_limit
+    } else {
+        0
+    };
     // Make sure args forms a valid set of properties
     if let Err(err) = validate_args(args) {
         println!("{}", err);
@@ -243,6 +419,9 @@ pub fn create_core_with_runtime(runtime: &Runtime, args: &Args, fd_total_budget:
 
     let consensus_db_dir = db_dir.join(CONSENSUS_DB);
     let utxoindex_db_dir = db_dir.join(UTXOINDEX_DB);
+    let txindex_db_dir = db_dir.join(TXINDEX_DB);
+    let addressindex_db_dir = db_dir.join(ADDRESSINDEX_DB);
+    let chainindex_db_dir = db_dir.join(CHAININDEX_DB);
     let meta_db_dir = db_dir.join(META_DB);
 
     let mut is_db_reset_needed = args.reset_db;
@@ -262,6 +441,18 @@ do you confirm? (answer y/n or pass --yes to the Waglaylad command line to confi
         info!("Utxoindex Data directory {}", utxoindex_db_dir.display());
         fs::create_dir_all(utxoindex_db_dir.as_path()).unwrap();
     }
+    if args.txindex {
+        info!("Txindex Data directory {}", txindex_db_dir.display());
+        fs::create_dir_all(txindex_db_dir.as_path()).unwrap();
+    }
+    if args.addressindex {
+        info!("Addressindex Data directory {}", addressindex_db_dir.display());
+        fs::create_dir_all(addressindex_db_dir.as_path()).unwrap();
+    }
+    if args.chainindex {
+        info!("Chainindex Data directory {}", chainindex_db_dir.display());
+        fs::create_dir_all(chainindex_db_dir.as_path()).unwrap();
+    }
 
     // DB used for addresses store and for multi-consensus management
     let mut meta_db = waglayla_database::prelude::ConnBuilder::default()
@@ -305,7 +496,7 @@ do you confirm? (answer y/n or pass --yes to the Waglaylad command line to confi
     // Reset Condition: Need to reset if we're upgrading from waglaylad DB version
     // TEMP: upgrade from Alpha version or any version before this one
     if !is_db_reset_needed
-        && (meta_db.get_pinned(b"multi-consensus-metadata-key").is_ok_and(|r| r.is_some())
+        && (meta_db.get(b"multi-consensus-metadata-key").is_ok_and(|r| r.is_some())
             || MultiConsensusManagementStore::new(meta_db.clone()).should_upgrade().unwrap())
     {
         let msg =
@@ -334,6 +525,18 @@ do you confirm? (answer y/n or pass --yes to the Waglaylad command line to confi
             fs::create_dir_all(utxoindex_db_dir.as_path()).unwrap();
         }
 
+        if args.txindex {
+            fs::create_dir_all(txindex_db_dir.as_path()).unwrap();
+        }
+
+        if args.addressindex {
+            fs::create_dir_all(addressindex_db_dir.as_path()).unwrap();
+        }
+
+        if args.chainindex {
+            fs::create_dir_all(chainindex_db_dir.as_path()).unwrap();
+        }
+
         // Reopen the DB
         meta_db = waglayla_database::prelude::ConnBuilder::default()
             .with_db_path(meta_db_dir)
@@ -349,9 +552,13 @@ do you confirm? (answer y/n or pass --yes to the Waglaylad command line to confi
     let connect_peers = args.connect_peers.iter().map(|x| x.normalize(config.default_p2p_port())).collect::<Vec<_>>();
     let add_peers = args.add_peers.iter().map(|x| x.normalize(config.default_p2p_port())).collect();
     let p2p_server_addr = args.listen.unwrap_or(ContextualNetAddress::unspecified()).normalize(config.default_p2p_port());
+    // Onion peers are reached through the general proxy unless a dedicated onion proxy is set
+    let proxy = args.proxy.map(|x| x.normalize(DEFAULT_PROXY_PORT));
+    let onion_proxy = args.onion.map(|x| x.normalize(DEFAULT_PROXY_PORT)).or(proxy);
     // connect_peers means no DNS seeding and no outbound peers
     let outbound_target = if connect_peers.is_empty() { args.outbound_target } else { 0 };
-    let dns_seeders = if connect_peers.is_empty() && !args.disable_dns_seeding { config.dns_seeders } else { &[] };
+    // Seeders are resolved locally, so DNS seeding is skipped under a proxy as it would reveal the node IP
+    let dns_seeders = if connect_peers.is_empty() && !args.disable_dns_seeding && proxy.is_none() { config.dns_seeders } else { &[] };
 
     let grpc_server_addr = args.rpclisten.unwrap_or(ContextualNetAddress::loopback()).normalize(config.default_rpc_port());
 
@@ -403,15 +610,48 @@ do you confirm? (answer y/n or pass --yes to the Waglaylad command line to confi
     };
 
     let notify_service = Arc::new(NotifyService::new(notification_root.clone(), notification_recv, subscription_context.clone()));
-    let index_service: Option<Arc<IndexService>> = if args.utxoindex {
+    let index_service: Option<Arc<IndexService>> = if args.utxoindex || args.txindex || args.addressindex || args.chainindex {
         // Use only a single thread for none-consensus databases
-        let utxoindex_db = waglayla_database::prelude::ConnBuilder::default()
-            .with_db_path(utxoindex_db_dir)
-            .with_files_limit(utxo_files_limit)
-            .build()
-            .unwrap();
-        let utxoindex = UtxoIndexProxy::new(UtxoIndex::new(consensus_manager.clone(), utxoindex_db).unwrap());
-        let index_service = Arc::new(IndexService::new(&notify_service.notifier(), subscription_context.clone(), Some(utxoindex)));
+        let utxoindex = args.utxoindex.then(|| {
+            let utxoindex_db = waglayla_database::prelude::ConnBuilder::default()
+                .with_db_path(utxoindex_db_dir)
+                .with_files_limit(utxo_files_limit)
+                .build()
+                .unwrap();
+            UtxoIndexProxy::new(UtxoIndex::new(consensus_manager.clone(), utxoindex_db).unwrap())
+        });
+        let txindex = args.txindex.then(|| {
+            let txindex_db = waglayla_database::prelude::ConnBuilder::default()
+                .with_db_path(txindex_db_dir)
+                .with_files_limit(tx_files_limit)
+                .build()
+                .unwrap();
+            TxIndexProxy::new(TxIndex::new(consensus_manager.clone(), txindex_db).unwrap())
+        });
+        let addressindex = args.addressindex.then(|| {
+            let addressindex_db = waglayla_database::prelude::ConnBuilder::default()
+                .with_db_path(addressindex_db_dir)
+                .with_files_limit(address_files_limit)
+                .build()
+                .unwrap();
+            AddressIndexProxy::new(AddressIndex::new(consensus_manager.clone(), addressindex_db).unwrap())
+        });
+        let chainindex = args.chainindex.then(|| {
+            let chainindex_db = waglayla_database::prelude::ConnBuilder::default()
+                .with_db_path(chainindex_db_dir)
+                .with_files_limit(chain_files_limit)
+                .build()
+                .unwrap();
+            ChainIndexProxy::new(ChainIndex::new(consensus_manager.clone(), chainindex_db).unwrap())
+        });
+        let index_service = Arc::new(IndexService::new(
+            &notify_service.notifier(),
+            subscription_context.clone(),
+            utxoindex,
+            txindex,
+            addressindex,
+            chainindex,
+        ));
         Some(index_service)
     } else {
         None
@@ -429,6 +669,16 @@ do you confirm? (answer y/n or pass --yes to the Waglaylad command line to confi
         mining_counters,
     )));
 
+    let node_identity = args.identity_key.as_ref().map(|path| match NodeIdentity::load_or_create(path.as_ref()) {
+        Ok(identity) => {
+            info!("P2P identity key: {}", identity.public_key());
+            identity
+        }
+        Err(err) => {
+            println!("Failed loading the P2P identity key from {}: {}", path, err);
+            exit(1);
+        }
+    });
     let flow_context = Arc::new(FlowContext::new(
         consensus_manager.clone(),
         address_manager,
@@ -436,6 +686,7 @@ do you confirm? (answer y/n or pass --yes to the Waglaylad command line to confi
         mining_manager.clone(),
         tick_service.clone(),
         notification_root,
+        node_identity,
     ));
     let p2p_service = Arc::new(P2pService::new(
         flow_context.clone(),
@@ -444,9 +695,15 @@ do you confirm? (answer y/n or pass --yes to the Waglaylad command line to confi
         p2p_server_addr,
         outbound_target,
         args.inbound_limit,
+        args.whitelist.clone(),
+        args.pinned_peers.clone(),
+        proxy,
+        onion_proxy,
         dns_seeders,
         config.default_p2p_port(),
         p2p_tower_counters.clone(),
+        args.import_snapshot.as_ref().map(PathBuf::from),
+        args.export_snapshot.as_ref().map(PathBuf::from),
     ));
 
     let rpc_core_service = Arc::new(RpcCoreService::new(
@@ -456,7 +713,10 @@ do you confirm? (answer y/n or pass --yes to the Waglaylad command line to confi
         mining_manager,
         flow_context,
         subscription_context,
-        index_service.as_ref().map(|x| x.utxoindex().unwrap()),
+        index_service.as_ref().and_then(|x| x.utxoindex()),
+        index_service.as_ref().and_then(|x| x.txindex()),
+        index_service.as_ref().and_then(|x| x.addressindex()),
+        index_service.as_ref().and_then(|x| x.chainindex()),
         config.clone(),
         core.clone(),
         processing_counters,
@@ -466,6 +726,38 @@ do you confirm? (answer y/n or pass --yes to the Waglaylad command line to confi
         p2p_tower_counters.clone(),
         grpc_tower_counters.clone(),
     ));
+    let rpc_authenticator = Arc::new(RpcAuthenticator::new(args.rpc_accounts.clone(), args.rpc_public_permissions));
+    if rpc_authenticator.is_enabled() {
+        info!(
+            "RPC authentication enabled, unauthenticated clients are granted permissions: {}",
+            rpc_authenticator.public_permissions()
+        );
+    }
+    let rpc_limits = RpcLimits {
+        connection_rate: args.rpc_rate_limit,
+        ip_rate: args.rpc_ip_rate_limit,
+        max_ip_connections: args.rpc_max_ip_clients,
+        ..Default::default()
+    }
+    .with_costs(args.rpc_costs.iter().copied());
+    if rpc_limits.is_enabled() {
+        info!(
+            "RPC rate limits enabled, per connection: {}, per IP: {}, max connections per IP: {}",
+            rpc_limits.connection_rate.map_or("unlimited".to_string(), |rate| rate.to_string()),
+            rpc_limits.ip_rate.map_or("unlimited".to_string(), |rate| rate.to_string()),
+            rpc_limits.max_ip_connections.map_or("unlimited".to_string(), |max| max.to_string()),
+        );
+    }
+    let rpc_limiter = Arc::new(RpcLimiter::new(rpc_limits));
+    let rpc_tls = args.rpc_cert.as_ref().zip(args.rpc_key.as_ref()).map(|(cert, key)| {
+        match TlsServerConfig::from_files(cert, key, args.rpc_client_ca.as_ref()) {
+            Ok(tls) => tls,
+            Err(err) => {
+                println!("Failed loading the RPC TLS configuration: {}", err);
+                exit(1);
+            }
+        }
+    });
     let grpc_service_broadcasters: usize = 3; // TODO: add a command line argument or derive from other arg/config/host-related fields
     let grpc_service = if !args.disable_grpc {
         Some(Arc::new(GrpcService::new(
@@ -475,11 +767,31 @@ do you confirm? (answer y/n or pass --yes to the Waglaylad command line to confi
             args.rpc_max_clients,
             grpc_service_broadcasters,
             grpc_tower_counters,
+            rpc_authenticator.clone(),
+            rpc_limiter.clone(),
+            rpc_tls.clone(),
         )))
     } else {
         None
     };
 
+    let prometheus_service = args.prometheus_listen.as_ref().map(|listen_address| {
+        Arc::new(PrometheusService::new(
+            listen_address.normalize(network.network_type.default_prometheus_port()),
+            rpc_core_service.clone(),
+        ))
+    });
+
+    let jsonrpc_service = args.rpclisten_http.as_ref().map(|listen_address| {
+        Arc::new(JsonRpcService::new(
+            listen_address.normalize(network.network_type.default_http_rpc_port()),
+            rpc_core_service.clone(),
+            rpc_authenticator.clone(),
+            rpc_limiter.clone(),
+            rpc_tls.clone(),
+        ))
+    });
+
     // Create an async runtime and register the top-level async services
     let async_runtime = Arc::new(AsyncRuntime::new(args.async_threads));
     async_runtime.register(tick_service);
@@ -495,6 +807,12 @@ do you confirm? (answer y/n or pass --yes to the Waglaylad command line to confi
         async_runtime.register(grpc_service)
     }
     async_runtime.register(p2p_service);
+    if let Some(prometheus_service) = prometheus_service {
+        async_runtime.register(prometheus_service)
+    }
+    if let Some(jsonrpc_service) = jsonrpc_service {
+        async_runtime.register(jsonrpc_service)
+    }
     async_runtime.register(consensus_monitor);
     async_runtime.register(mining_monitor);
     async_runtime.register(perf_monitor);
@@ -515,6 +833,9 @@ do you confirm? (answer y/n or pass --yes to the Waglaylad command line to confi
                 WrpcServerOptions {
                     listen_address: listen_address.to_address(&network.network_type, &encoding).to_string(), // TODO: use a normalized ContextualNetAddress instead of a String
                     verbose: args.wrpc_verbose,
+                    authenticator: rpc_authenticator.clone(),
+                    limiter: rpc_limiter.clone(),
+                    tls: rpc_tls.clone(),
                     ..WrpcServerOptions::default()
                 },
             ))
@@ -2,14 +2,14 @@ extern crate waglayla_consensus;
 extern crate waglayla_core;
 extern crate waglayla_hashes;
 
-use std::sync::Arc;
+use std::{process::exit, sync::Arc};
 
 use waglayla_alloc::init_allocator_with_default_settings;
 use waglayla_core::{info, signals::Signals};
 use waglayla_utils::fd_budget;
 use waglaylad_lib::{
     args::parse_args,
-    daemon::{create_core, DESIRED_DAEMON_SOFT_FD_LIMIT, MINIMUM_DAEMON_SOFT_FD_LIMIT},
+    daemon::{check_db, create_core, DESIRED_DAEMON_SOFT_FD_LIMIT, MINIMUM_DAEMON_SOFT_FD_LIMIT},
 };
 
 #[cfg(feature = "heap")]
@@ -40,6 +40,10 @@ pub fn main() {
     }
 
     let fd_total_budget = fd_budget::limit() - args.rpc_max_clients as i32 - args.inbound_limit as i32 - args.outbound_target as i32;
+    if args.check_db {
+        exit(check_db(&args, fd_total_budget));
+    }
+
     let (core, _) = create_core(args, fd_total_budget);
 
     // Bind the keyboard signal to the core
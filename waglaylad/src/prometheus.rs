@@ -0,0 +1,87 @@
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use waglayla_core::{
+    info,
+    task::service::{AsyncService, AsyncServiceError, AsyncServiceFuture},
+    trace, warn,
+};
+use waglayla_metrics_core::openmetrics::{sample_openmetrics, OPENMETRICS_CONTENT_TYPE};
+use waglayla_rpc_core::api::rpc::RpcApi;
+use waglayla_utils::{networking::NetAddress, triggers::SingleTrigger};
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+/// Path on which the metrics are served
+const METRICS_PATH: &str = "/metrics";
+
+/// Serves node metrics over HTTP in the OpenMetrics text format
+pub struct PrometheusService {
+    net_address: NetAddress,
+    rpc: Arc<dyn RpcApi>,
+    shutdown: SingleTrigger,
+}
+
+impl PrometheusService {
+    pub const IDENT: &'static str = "prometheus-service";
+
+    pub fn new(net_address: NetAddress, rpc: Arc<dyn RpcApi>) -> Self {
+        Self { net_address, rpc, shutdown: Default::default() }
+    }
+
+    async fn handle(rpc: Arc<dyn RpcApi>, request: Request<Body>) -> Result<Response<Body>, Infallible> {
+        if request.method() != Method::GET || request.uri().path() != METRICS_PATH {
+            return Ok(Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty()).unwrap());
+        }
+        let response = match sample_openmetrics(rpc).await {
+            Ok(text) => Response::builder().header(CONTENT_TYPE, OPENMETRICS_CONTENT_TYPE).body(Body::from(text)).unwrap(),
+            Err(err) => {
+                warn!("{} failed sampling metrics: {}", Self::IDENT, err);
+                Response::builder().status(StatusCode::INTERNAL_SERVER_ERROR).body(Body::from(err.to_string())).unwrap()
+            }
+        };
+        Ok(response)
+    }
+}
+
+impl AsyncService for PrometheusService {
+    fn ident(self: Arc<Self>) -> &'static str {
+        Self::IDENT
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", Self::IDENT);
+
+        // Prepare a shutdown signal receiver
+        let shutdown_signal = self.shutdown.listener.clone();
+
+        Box::pin(async move {
+            let address: SocketAddr = self.net_address.into();
+            let rpc = self.rpc.clone();
+            let make_service = make_service_fn(move |_| {
+                let rpc = rpc.clone();
+                async move { Ok::<_, Infallible>(service_fn(move |request| Self::handle(rpc.clone(), request))) }
+            });
+            let server = Server::try_bind(&address)
+                .map_err(|err| AsyncServiceError::Service(format!("failed binding {} to {}: {}", Self::IDENT, address, err)))?
+                .serve(make_service);
+            info!("Prometheus metrics are served on http://{}{}", address, METRICS_PATH);
+
+            // Keep the server running until a service shutdown signal is received
+            server.with_graceful_shutdown(shutdown_signal).await.map_err(|err| AsyncServiceError::Service(err.to_string()))
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", Self::IDENT);
+        self.shutdown.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            trace!("{} stopped", Self::IDENT);
+            Ok(())
+        })
+    }
+}
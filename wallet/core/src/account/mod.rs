@@ -10,17 +10,21 @@ pub use kind::*;
 pub use variants::*;
 
 use crate::derivation::build_derivate_paths;
-use crate::derivation::AddressDerivationManagerTrait;
+use crate::derivation::{AddressDerivationManagerTrait, AddressDerivationMeta};
 use crate::imports::*;
 use crate::storage::account::AccountSettings;
 use crate::storage::AccountMetadata;
 use crate::storage::{PrvKeyData, PrvKeyDataId};
 use crate::tx::PaymentOutput;
-use crate::tx::{Fees, Generator, GeneratorSettings, GeneratorSummary, PaymentDestination, PendingTransaction, Signer};
+use crate::tx::{
+    FeeRatePriority, Fees, Generator, GeneratorSettings, GeneratorSummary, PaymentDestination, PendingTransaction, Pskt, Signer,
+};
 use crate::utxo::balance::{AtomicBalance, BalanceStrings};
 use crate::utxo::UtxoContextBinding;
 use waglayla_bip32::{ChildNumber, ExtendedPrivateKey, PrivateKey, PrivateKeyBytes};
 use waglayla_consensus_client::UtxoEntryReference;
+use waglayla_consensus_core::tx::TransactionOutpoint;
+use waglayla_txscript::extract_script_pub_key_address;
 use waglayla_wallet_keys::derivation::gen0::WalletDerivationManagerV0;
 use workflow_core::abortable::Abortable;
 
@@ -53,6 +57,7 @@ pub struct Inner {
     storage_key: AccountStorageKey,
     wallet: Arc<Wallet>,
     utxo_context: UtxoContext,
+    frozen_utxos: Mutex<Vec<TransactionOutpoint>>,
 }
 
 impl Inner {
@@ -60,7 +65,14 @@ impl Inner {
         let utxo_context = UtxoContext::new(wallet.utxo_processor(), UtxoContextBinding::AccountId(id));
 
         let context = Context { settings };
-        Inner { context: Mutex::new(context), id, storage_key, wallet: wallet.clone(), utxo_context: utxo_context.clone() }
+        Inner {
+            context: Mutex::new(context),
+            id,
+            storage_key,
+            wallet: wallet.clone(),
+            utxo_context: utxo_context.clone(),
+            frozen_utxos: Mutex::new(vec![]),
+        }
     }
 
     pub fn from_storage(wallet: &Arc<Wallet>, storage: &AccountStorage) -> Self {
@@ -74,6 +86,20 @@ impl Inner {
     pub fn store(&self) -> &Arc<dyn Interface> {
         self.wallet.store()
     }
+
+    pub fn frozen_utxos(&self) -> Vec<TransactionOutpoint> {
+        self.frozen_utxos.lock().unwrap().clone()
+    }
+
+    pub fn set_frozen_utxos(&self, frozen_utxos: Vec<TransactionOutpoint>) {
+        *self.frozen_utxos.lock().unwrap() = frozen_utxos;
+    }
+
+    /// Account metadata carrying the address derivation `indexes`
+    /// (of derivation capable accounts) and the frozen UTXOs.
+    pub fn metadata(&self, indexes: Option<AddressDerivationMeta>) -> AccountMetadata {
+        AccountMetadata { id: self.id, indexes, frozen_utxos: self.frozen_utxos() }
+    }
 }
 
 /// Generic wallet [`Account`] trait implementation used
@@ -232,9 +258,7 @@ pub trait Account: AnySync + Send + Sync + 'static {
                 join_all(futures).await.into_iter().collect::<Result<Vec<_>>>()?;
             }
             Err(_) => {
-                let mut address_set = HashSet::<Address>::new();
-                address_set.insert(self.receive_address()?);
-                address_set.insert(self.change_address()?);
+                let address_set = self.monitored_addresses()?.into_iter().collect::<HashSet<_>>();
 
                 let scan = Scan::new_with_address_set(address_set, &balance, current_daa_score);
                 scan.scan(self.utxo_context()).await?;
@@ -243,6 +267,9 @@ pub trait Account: AnySync + Send + Sync + 'static {
 
         self.utxo_context().update_balance().await?;
 
+        // the UTXO context now holds all the account UTXOs, frozen UTXOs missing from it have been spent
+        self.prune_frozen_utxos().await?;
+
         Ok(())
     }
 
@@ -254,6 +281,11 @@ pub trait Account: AnySync + Send + Sync + 'static {
 
     fn change_address(&self) -> Result<Address>;
 
+    /// Addresses scanned and monitored for UTXOs by accounts that are not derivation capable
+    fn monitored_addresses(&self) -> Result<Vec<Address>> {
+        Ok(vec![self.receive_address()?, self.change_address()?])
+    }
+
     /// Start Account service task
     async fn start(self: Arc<Self>) -> Result<()> {
         self.connect().await?;
@@ -284,6 +316,58 @@ pub trait Account: AnySync + Send + Sync + 'static {
 
     fn as_dyn_arc(self: Arc<Self>) -> Arc<dyn Account>;
 
+    /// UTXOs excluded from the automatic UTXO selection when creating transactions.
+    fn frozen_utxos(&self) -> Vec<TransactionOutpoint> {
+        self.inner().frozen_utxos()
+    }
+
+    /// Freeze account UTXOs, preventing them from being automatically selected
+    /// when creating transactions. Frozen UTXOs can still be spent by selecting
+    /// them explicitly. The frozen state is persisted in the account metadata.
+    async fn freeze_utxos(self: Arc<Self>, outpoints: &[TransactionOutpoint]) -> Result<()> {
+        let mut frozen_utxos = self.unspent_frozen_utxos();
+        for outpoint in outpoints {
+            if self.utxo_context().find(&(*outpoint).into()).is_none() {
+                return Err(Error::UtxoNotFound(*outpoint));
+            }
+            if !frozen_utxos.contains(outpoint) {
+                frozen_utxos.push(*outpoint);
+            }
+        }
+        self.inner().set_frozen_utxos(frozen_utxos);
+        self.store_frozen_utxos().await
+    }
+
+    /// Unfreeze UTXOs frozen with [`Account::freeze_utxos`].
+    async fn unfreeze_utxos(self: Arc<Self>, outpoints: &[TransactionOutpoint]) -> Result<()> {
+        let mut frozen_utxos = self.frozen_utxos();
+        frozen_utxos.retain(|frozen| !outpoints.contains(frozen));
+        self.inner().set_frozen_utxos(frozen_utxos);
+        self.store_frozen_utxos().await
+    }
+
+    /// Frozen UTXOs still present in the UTXO context, omitting the ones spent since they were frozen.
+    fn unspent_frozen_utxos(&self) -> Vec<TransactionOutpoint> {
+        let mut frozen_utxos = self.frozen_utxos();
+        frozen_utxos.retain(|outpoint| self.utxo_context().find(&(*outpoint).into()).is_some());
+        frozen_utxos
+    }
+
+    /// Removes the spent UTXOs from the frozen UTXOs, storing the account metadata if any was removed.
+    async fn prune_frozen_utxos(&self) -> Result<()> {
+        let frozen_utxos = self.unspent_frozen_utxos();
+        if frozen_utxos.len() != self.frozen_utxos().len() {
+            self.inner().set_frozen_utxos(frozen_utxos);
+            self.store_frozen_utxos().await?;
+        }
+        Ok(())
+    }
+
+    async fn store_frozen_utxos(&self) -> Result<()> {
+        let metadata = self.metadata()?.ok_or(Error::NotImplemented)?;
+        self.wallet().store().as_account_store()?.update_metadata(vec![metadata]).await
+    }
+
     /// Aggregate all account UTXOs into the change address.
     /// Also known as "compounding".
     async fn sweep(
@@ -316,11 +400,13 @@ pub trait Account: AnySync + Send + Sync + 'static {
 
     /// Send funds to a [`PaymentDestination`] comprised of one or multiple [`PaymentOutputs`](crate::tx::PaymentOutputs)
     /// or [`PaymentDestination::Change`] variant that will forward funds to the change address.
+    /// If `utxo_selection` is supplied, only the selected UTXOs are spent (see [`GeneratorSettings::try_with_utxo_selection`]).
     async fn send(
         self: Arc<Self>,
         destination: PaymentDestination,
         priority_fee_sompi: Fees,
         payload: Option<Vec<u8>>,
+        utxo_selection: Option<Vec<TransactionOutpoint>>,
         wallet_secret: Secret,
         payment_secret: Option<Secret>,
         abortable: &Abortable,
@@ -329,7 +415,11 @@ pub trait Account: AnySync + Send + Sync + 'static {
         let keydata = self.prv_key_data(wallet_secret).await?;
         let signer = Arc::new(Signer::new(self.clone().as_dyn_arc(), keydata, payment_secret));
 
-        let settings = GeneratorSettings::try_new_with_account(self.clone().as_dyn_arc(), destination, priority_fee_sompi, payload)?;
+        let mut settings =
+            GeneratorSettings::try_new_with_account(self.clone().as_dyn_arc(), destination, priority_fee_sompi, payload)?;
+        if let Some(utxo_selection) = utxo_selection {
+            settings = settings.try_with_utxo_selection(&utxo_selection)?;
+        }
 
         let generator = Generator::try_new(settings, Some(signer), Some(abortable))?;
 
@@ -348,6 +438,55 @@ pub trait Account: AnySync + Send + Sync + 'static {
         Ok((generator.summary(), ids))
     }
 
+    /// Create unsigned transactions sending funds to a [`PaymentDestination`] as [`Pskt`]s that need to be signed
+    /// by the account key holders (using [`Account::sign_pskt`]) before being submitted. Unlike [`Account::send`],
+    /// this does not require the account private keys, allowing transactions to be signed by offline wallets or
+    /// by the cosigners of multisig accounts.
+    async fn create_unsigned_transactions(
+        self: Arc<Self>,
+        destination: PaymentDestination,
+        priority_fee_sompi: Fees,
+        payload: Option<Vec<u8>>,
+        utxo_selection: Option<Vec<TransactionOutpoint>>,
+        abortable: &Abortable,
+    ) -> Result<(GeneratorSummary, Vec<Pskt>)> {
+        let mut settings =
+            GeneratorSettings::try_new_with_account(self.clone().as_dyn_arc(), destination, priority_fee_sompi, payload)?;
+        if let Some(utxo_selection) = utxo_selection {
+            settings = settings.try_with_utxo_selection(&utxo_selection)?;
+        }
+
+        let generator = Generator::try_new(settings, None, Some(abortable))?;
+        let prefix = self.wallet().address_prefix()?;
+
+        let mut stream = generator.stream();
+        let mut pskts = vec![];
+        while let Some(transaction) = stream.try_next().await? {
+            let mut pskt = Pskt::from_pending_transaction(&transaction)?;
+            for index in 0..pskt.inputs.len() {
+                let address = extract_script_pub_key_address(&pskt.inputs[index].utxo_entry.script_public_key, prefix)?;
+                if let Some(redeem_script) = self.redeem_script(&address)? {
+                    pskt.set_redeem_script(index, redeem_script)?;
+                }
+            }
+            pskts.push(pskt);
+            yield_executor().await;
+        }
+
+        Ok((generator.summary(), pskts))
+    }
+
+    /// Sign the inputs of a [`Pskt`] spending UTXOs of this account, returning the number of signatures added.
+    async fn sign_pskt(self: Arc<Self>, pskt: &mut Pskt, wallet_secret: Secret, payment_secret: Option<Secret>) -> Result<usize> {
+        let keydata = self.prv_key_data(wallet_secret).await?;
+        sign_pskt_with_keydata(self.as_derivation_capable()?, pskt, &[keydata], &payment_secret)
+    }
+
+    /// Redeem script of a pay-to-script-hash `address` belonging to this account
+    fn redeem_script(&self, _address: &Address) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
     async fn mnemonic(&self, wallet_secret: Secret) -> Result<String> {
         let keydata = self.prv_key_data(wallet_secret).await?;
         let mnemonic = keydata.as_mnemonic(None)?.unwrap();
@@ -450,9 +589,13 @@ pub trait Account: AnySync + Send + Sync + 'static {
         destination: PaymentDestination,
         priority_fee_sompi: Fees,
         payload: Option<Vec<u8>>,
+        utxo_selection: Option<Vec<TransactionOutpoint>>,
         abortable: &Abortable,
     ) -> Result<GeneratorSummary> {
-        let settings = GeneratorSettings::try_new_with_account(self.as_dyn_arc(), destination, priority_fee_sompi, payload)?;
+        let mut settings = GeneratorSettings::try_new_with_account(self.as_dyn_arc(), destination, priority_fee_sompi, payload)?;
+        if let Some(utxo_selection) = utxo_selection {
+            settings = settings.try_with_utxo_selection(&utxo_selection)?;
+        }
 
         let generator = Generator::try_new(settings, None, Some(abortable))?;
 
@@ -464,6 +607,34 @@ pub trait Account: AnySync + Send + Sync + 'static {
         Ok(generator.summary())
     }
 
+    /// Selects fees for sending to `destination` from the node fee estimate, such that
+    /// the final transaction pays the feerate of the `priority` bucket for its mass
+    /// (see [`Fees::from_fee_estimate`]).
+    async fn fees_from_fee_estimate(
+        self: Arc<Self>,
+        destination: PaymentDestination,
+        priority: FeeRatePriority,
+        payload: Option<Vec<u8>>,
+        utxo_selection: Option<Vec<TransactionOutpoint>>,
+        abortable: &Abortable,
+    ) -> Result<Fees> {
+        let fee_estimate = self.wallet().rpc_api().get_fee_estimate().await?;
+        // Priority fees alter the change output and hence the storage mass of the final
+        // transaction, so fees are re-evaluated until they cover the resulting mass.
+        let mut fees = Fees::SenderPays(0);
+        loop {
+            let summary =
+                self.clone().estimate(destination.clone(), fees.clone(), payload.clone(), utxo_selection.clone(), abortable).await?;
+            let mass =
+                summary.final_transaction_mass().ok_or_else(|| Error::custom("unable to estimate the final transaction mass"))?;
+            let required = Fees::from_fee_estimate(&fee_estimate, priority, mass);
+            if required.additional() <= fees.additional() {
+                return Ok(fees);
+            }
+            fees = required;
+        }
+    }
+
     fn as_derivation_capable(self: Arc<Self>) -> Result<Arc<dyn DerivationCapableAccount>> {
         Err(Error::AccountAddressDerivationCaps)
     }
@@ -669,6 +840,43 @@ pub trait DerivationCapableAccount: Account {
 
 downcast_sync!(dyn DerivationCapableAccount);
 
+/// Signs the inputs of `pskt` spending UTXOs of `account` with the keys derived from each of the supplied `keydata`.
+pub(crate) fn sign_pskt_with_keydata(
+    account: Arc<dyn DerivationCapableAccount>,
+    pskt: &mut Pskt,
+    keydata: &[PrvKeyData],
+    payment_secret: &Option<Secret>,
+) -> Result<usize> {
+    let prefix = account.wallet().address_prefix()?;
+    let derivation = account.derivation();
+
+    // index addresses up to the current derivation indexes as they may
+    // not have been scanned yet (i.e. when signing on an offline wallet)
+    for manager in [derivation.receive_address_manager(), derivation.change_address_manager()] {
+        manager.get_range(0..manager.index() + 1)?;
+    }
+
+    let addresses = pskt
+        .inputs
+        .iter()
+        .map(|input| extract_script_pub_key_address(&input.utxo_entry.script_public_key, prefix))
+        .collect::<std::result::Result<AHashSet<_>, _>>()?;
+    // inputs spending UTXOs of other accounts are left to their owners
+    let addresses = addresses.iter().filter(|address| derivation.addresses_indexes(&[address]).is_ok()).collect::<Vec<_>>();
+    let (receive, change) = derivation.addresses_indexes(&addresses)?;
+
+    let mut signatures = 0;
+    for keydata in keydata.iter() {
+        let private_keys = account.create_private_keys(keydata, payment_secret, &receive, &change)?;
+        let mut keys = private_keys.iter().map(|(_, key)| key.secret_bytes()).collect::<Vec<_>>();
+        let result = pskt.sign(&keys);
+        keys.zeroize();
+        signatures += result?;
+    }
+
+    Ok(signatures)
+}
+
 pub(crate) fn create_private_keys<'l>(
     account_kind: &AccountKind,
     cosigner_index: u32,
@@ -711,15 +919,28 @@ pub(crate) fn create_private_keys<'l>(
 #[cfg(test)]
 mod tests {
     use super::create_private_keys;
+    use super::watchonly::{WatchOnly, WatchOnlySource};
+    use super::Account;
     use super::ExtendedPrivateKey;
     use crate::imports::LEGACY_ACCOUNT_KIND;
+    use crate::result::Result;
+    use crate::tests::RpcCoreMock;
+    use crate::tx::{FeeRatePriority, PaymentDestination, PaymentOutput};
+    use crate::utils::waglayla_to_sompi;
+    use crate::wallet::Wallet;
     use waglayla_addresses::Address;
     use waglayla_addresses::Prefix;
     use waglayla_bip32::secp256k1::SecretKey;
     use waglayla_bip32::PrivateKey;
     use waglayla_bip32::SecretKeyExt;
+    use waglayla_consensus_core::network::{NetworkId, NetworkType};
+    use waglayla_consensus_core::tx::{TransactionId, TransactionOutpoint, UtxoEntry};
+    use waglayla_rpc_core::{RpcFeeEstimate, RpcFeerateBucket, RpcUtxosByAddressesEntry};
+    use waglayla_txscript::pay_to_address_script;
     use waglayla_wallet_keys::derivation::gen0::PubkeyDerivationManagerV0;
     use std::str::FromStr;
+    use std::sync::Arc;
+    use workflow_core::abortable::Abortable;
 
     fn gen0_receive_addresses() -> Vec<&'static str> {
         vec![
@@ -864,4 +1085,56 @@ mod tests {
             assert_eq!(bytes_str(&key.to_bytes()), change_keys[index], "change key at {index} failed");
         }
     }
+
+    #[tokio::test]
+    async fn test_account_fees_from_fee_estimate() -> Result<()> {
+        let network_id = NetworkId::with_suffix(NetworkType::Testnet, 10);
+        let rpc_mock = Arc::new(RpcCoreMock::new());
+        let wallet = Arc::new(Wallet::try_with_rpc(Some(rpc_mock.clone().into()), Wallet::resident_store()?, Some(network_id))?);
+        let processor = wallet.utxo_processor();
+        processor.mock_register_notification_listener().await?;
+        processor.mock_set_connected(true);
+        processor.handle_daa_score_change(1_000_000).await?;
+
+        // a watch-only account funded by two UTXOs of a single address
+        let prefix = Prefix::from(network_id);
+        let address = Address::new(prefix, waglayla_addresses::Version::PubKey, &[1; 32]);
+        let utxos = (1..=2u8)
+            .map(|n| RpcUtxosByAddressesEntry {
+                address: Some(address.clone()),
+                outpoint: TransactionOutpoint::new(TransactionId::from_bytes([n; 32]), 0),
+                utxo_entry: UtxoEntry::new(waglayla_to_sompi(n as f64), pay_to_address_script(&address), 0, false),
+            })
+            .collect();
+        rpc_mock.set_utxos(utxos);
+        let source = WatchOnlySource::try_from_addresses(vec![address])?;
+        let account = Arc::new(WatchOnly::try_new(&wallet, None, source, false).await?);
+        account.clone().scan(None, None).await?;
+
+        let recipient = Address::new(prefix, waglayla_addresses::Version::PubKey, &[2; 32]);
+        let destination: PaymentDestination = PaymentOutput::new(recipient, waglayla_to_sompi(2.5)).into();
+        let abortable = Abortable::default();
+
+        // fees can not be selected while the node provides no estimate
+        let fees = account.clone().fees_from_fee_estimate(destination.clone(), FeeRatePriority::Normal, None, None, &abortable).await;
+        assert!(fees.is_err());
+
+        let bucket = |feerate| RpcFeerateBucket { feerate, estimated_seconds: 0.0 };
+        rpc_mock.set_fee_estimate(RpcFeeEstimate {
+            priority_bucket: bucket(10.0),
+            normal_buckets: vec![bucket(4.0)],
+            low_buckets: vec![bucket(1.0)],
+        });
+
+        // the final transaction pays the feerate of the selected bucket for its mass
+        for (priority, feerate) in [(FeeRatePriority::Priority, 10), (FeeRatePriority::Normal, 4), (FeeRatePriority::Low, 1)] {
+            let fees = account.clone().fees_from_fee_estimate(destination.clone(), priority, None, None, &abortable).await?;
+            assert!(fees.sender_pays());
+            let summary = account.clone().estimate(destination.clone(), fees, None, None, &abortable).await?;
+            let mass = summary.final_transaction_mass().unwrap();
+            assert_eq!(summary.aggregated_fees, feerate * mass, "fees of a {priority:?} transaction");
+        }
+
+        Ok(())
+    }
 }
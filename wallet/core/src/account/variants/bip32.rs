@@ -208,7 +208,7 @@ impl Account for Bip32 {
     }
 
     fn metadata(&self) -> Result<Option<AccountMetadata>> {
-        let metadata = AccountMetadata::new(self.inner.id, self.derivation.address_derivation_meta());
+        let metadata = self.inner.metadata(Some(self.derivation.address_derivation_meta()));
         Ok(Some(metadata))
     }
 
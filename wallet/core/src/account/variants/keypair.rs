@@ -173,7 +173,7 @@ impl Account for Keypair {
     }
 
     fn metadata(&self) -> Result<Option<AccountMetadata>> {
-        Ok(None)
+        Ok(Some(self.inner.metadata(None)))
     }
 
     fn descriptor(&self) -> Result<AccountDescriptor> {
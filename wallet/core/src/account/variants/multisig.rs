@@ -2,9 +2,10 @@
 //! MultiSig account implementation.
 //!
 
-use crate::account::Inner;
+use crate::account::{sign_pskt_with_keydata, Inner};
 use crate::derivation::{AddressDerivationManager, AddressDerivationManagerTrait};
 use crate::imports::*;
+use crate::tx::Pskt;
 
 pub const MULTISIG_ACCOUNT_KIND: &str = "waglayla-multisig-standard";
 
@@ -181,8 +182,7 @@ impl Account for MultiSig {
     }
 
     fn sig_op_count(&self) -> u8 {
-        // TODO @maxim
-        1
+        self.xpub_keys.len() as u8
     }
 
     fn minimum_signatures(&self) -> u16 {
@@ -213,7 +213,7 @@ impl Account for MultiSig {
     }
 
     fn metadata(&self) -> Result<Option<AccountMetadata>> {
-        let metadata = AccountMetadata::new(self.inner.id, self.derivation.address_derivation_meta());
+        let metadata = self.inner.metadata(Some(self.derivation.address_derivation_meta()));
         Ok(Some(metadata))
     }
 
@@ -236,6 +236,29 @@ impl Account for MultiSig {
     fn as_derivation_capable(self: Arc<Self>) -> Result<Arc<dyn DerivationCapableAccount>> {
         Ok(self.clone())
     }
+
+    async fn sign_pskt(self: Arc<Self>, pskt: &mut Pskt, wallet_secret: Secret, payment_secret: Option<Secret>) -> Result<usize> {
+        // accounts without private keys are cosigned by other wallets only
+        let prv_key_data_ids = self.prv_key_data_ids.clone().ok_or(Error::AccountKindFeature)?;
+        let store = self.wallet().store().as_prv_key_data_store()?;
+        let mut keydata = Vec::with_capacity(prv_key_data_ids.len());
+        for prv_key_data_id in prv_key_data_ids.iter() {
+            let prv_key_data =
+                store.load_key_data(&wallet_secret, prv_key_data_id).await?.ok_or(Error::PrivateKeyNotFound(*prv_key_data_id))?;
+            keydata.push(prv_key_data);
+        }
+        sign_pskt_with_keydata(self, pskt, &keydata, &payment_secret)
+    }
+
+    fn redeem_script(&self, address: &Address) -> Result<Option<Vec<u8>>> {
+        let (receive, change) = self.derivation.get_addresses_indexes(&[address])?;
+        let redeem_script = match (receive.first(), change.first()) {
+            (Some((_, index)), _) => self.derivation.receive_address_manager().redeem_script(*index)?,
+            (_, Some((_, index))) => self.derivation.change_address_manager().redeem_script(*index)?,
+            _ => return Ok(None),
+        };
+        Ok(Some(redeem_script))
+    }
 }
 
 impl DerivationCapableAccount for MultiSig {
@@ -246,6 +269,10 @@ impl DerivationCapableAccount for MultiSig {
     fn account_index(&self) -> u64 {
         0
     }
+
+    fn cosigner_index(&self) -> u32 {
+        self.cosigner_index.unwrap_or(0) as u32
+    }
 }
 
 #[cfg(test)]
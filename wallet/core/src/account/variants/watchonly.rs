@@ -0,0 +1,456 @@
+//!
+//! Watch-only account implementation (tracks funds without holding private keys).
+//!
+
+use crate::account::Inner;
+use crate::derivation::{AddressDerivationManager, AddressDerivationManagerTrait, AddressDerivationMeta};
+use crate::imports::*;
+use waglayla_addresses::Version;
+use secp256k1::PublicKey;
+
+pub const WATCH_ONLY_ACCOUNT_KIND: &str = "waglayla-watch-only-standard";
+
+pub struct Ctor {}
+
+#[async_trait]
+impl Factory for Ctor {
+    fn name(&self) -> String {
+        "watch-only".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Waglayla Watch-Only Account".to_string()
+    }
+
+    async fn try_load(
+        &self,
+        wallet: &Arc<Wallet>,
+        storage: &AccountStorage,
+        meta: Option<Arc<AccountMetadata>>,
+    ) -> Result<Arc<dyn Account>> {
+        Ok(Arc::new(WatchOnly::try_load(wallet, storage, meta).await?))
+    }
+}
+
+/// Public data a [`WatchOnly`] account monitors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "kebab-case")]
+pub enum WatchOnlySource {
+    /// Extended public key of a BIP32 account, receive and change addresses are derived from it.
+    Xpub(ExtendedPublicKeySecp256k1),
+    /// Fixed set of pay-to-pubkey addresses.
+    Addresses(Vec<Address>),
+    /// Secp256k1 public key of a keypair account.
+    PublicKey(PublicKey),
+}
+
+impl WatchOnlySource {
+    const XPUB: u8 = 0;
+    const ADDRESSES: u8 = 1;
+    const PUBLIC_KEY: u8 = 2;
+
+    /// Creates an address list source, ordering the addresses so that the account id does not depend on their order.
+    /// Pay-to-script-hash addresses are rejected as spending them requires redeem scripts the account can not know about.
+    pub fn try_from_addresses(mut addresses: Vec<Address>) -> Result<Self> {
+        if addresses.is_empty() {
+            return Err(Error::custom("watch-only accounts require at least one address"));
+        }
+        if let Some(address) = addresses.iter().find(|address| address.version == Version::ScriptHash) {
+            return Err(Error::WatchOnlyScriptHashAddress(address.to_string()));
+        }
+        addresses.sort();
+        addresses.dedup();
+        Ok(Self::Addresses(addresses))
+    }
+}
+
+impl BorshSerialize for WatchOnlySource {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        match self {
+            WatchOnlySource::Xpub(xpub) => {
+                BorshSerialize::serialize(&Self::XPUB, writer)?;
+                BorshSerialize::serialize(xpub, writer)?;
+            }
+            WatchOnlySource::Addresses(addresses) => {
+                BorshSerialize::serialize(&Self::ADDRESSES, writer)?;
+                BorshSerialize::serialize(addresses, writer)?;
+            }
+            WatchOnlySource::PublicKey(public_key) => {
+                BorshSerialize::serialize(&Self::PUBLIC_KEY, writer)?;
+                BorshSerialize::serialize(public_key.serialize().as_slice(), writer)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for WatchOnlySource {
+    fn deserialize(buf: &mut &[u8]) -> IoResult<Self> {
+        let kind: u8 = BorshDeserialize::deserialize(buf)?;
+        match kind {
+            Self::XPUB => Ok(WatchOnlySource::Xpub(BorshDeserialize::deserialize(buf)?)),
+            Self::ADDRESSES => Ok(WatchOnlySource::Addresses(BorshDeserialize::deserialize(buf)?)),
+            Self::PUBLIC_KEY => {
+                let public_key_bytes: Vec<u8> = BorshDeserialize::deserialize(buf)?;
+                let public_key = PublicKey::from_slice(&public_key_bytes)
+                    .map_err(|_| IoError::new(IoErrorKind::Other, "Unable to deserialize watch-only account (invalid public key)"))?;
+                Ok(WatchOnlySource::PublicKey(public_key))
+            }
+            _ => Err(IoError::new(IoErrorKind::Other, format!("Unable to deserialize watch-only account (invalid source {kind})"))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub struct Payload {
+    pub source: WatchOnlySource,
+    pub ecdsa: bool,
+}
+
+impl Payload {
+    pub fn new(source: WatchOnlySource, ecdsa: bool) -> Self {
+        Self { source, ecdsa }
+    }
+
+    pub fn try_load(storage: &AccountStorage) -> Result<Self> {
+        Ok(Self::try_from_slice(storage.serialized.as_slice())?)
+    }
+}
+
+impl Storable for Payload {
+    const STORAGE_MAGIC: u32 = 0x48435457;
+    const STORAGE_VERSION: u32 = 0;
+}
+
+impl AccountStorable for Payload {}
+
+impl BorshSerialize for Payload {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        StorageHeader::new(Self::STORAGE_MAGIC, Self::STORAGE_VERSION).serialize(writer)?;
+
+        BorshSerialize::serialize(&self.source, writer)?;
+        BorshSerialize::serialize(&self.ecdsa, writer)?;
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for Payload {
+    fn deserialize(buf: &mut &[u8]) -> IoResult<Self> {
+        let StorageHeader { version: _, .. } =
+            StorageHeader::deserialize(buf)?.try_magic(Self::STORAGE_MAGIC)?.try_version(Self::STORAGE_VERSION)?;
+
+        let source = BorshDeserialize::deserialize(buf)?;
+        let ecdsa = BorshDeserialize::deserialize(buf)?;
+
+        Ok(Self { source, ecdsa })
+    }
+}
+
+/// Account tracking the UTXOs, balance and transaction history of public keys or addresses
+/// without any associated private key data. Transactions spending its funds are created
+/// as [`Pskt`](crate::tx::Pskt)s using [`Account::create_unsigned_transactions`] and have
+/// to be signed by the key holder.
+pub struct WatchOnly {
+    inner: Arc<Inner>,
+    source: WatchOnlySource,
+    ecdsa: bool,
+    derivation: Option<Arc<AddressDerivationManager>>,
+}
+
+impl WatchOnly {
+    pub async fn try_new(wallet: &Arc<Wallet>, name: Option<String>, source: WatchOnlySource, ecdsa: bool) -> Result<Self> {
+        let storable = Payload::new(source, ecdsa);
+        let settings = AccountSettings { name, ..Default::default() };
+        let (id, storage_key) = make_account_hashes(from_watch_only(&storable));
+        let inner = Arc::new(Inner::new(wallet, id, storage_key, settings));
+
+        let Payload { source, ecdsa } = storable;
+        let derivation = Self::create_derivation(wallet, &source, ecdsa, Default::default()).await?;
+
+        Ok(Self { inner, source, ecdsa, derivation })
+    }
+
+    pub async fn try_load(wallet: &Arc<Wallet>, storage: &AccountStorage, meta: Option<Arc<AccountMetadata>>) -> Result<Self> {
+        let storable = Payload::try_load(storage)?;
+        let inner = Arc::new(Inner::from_storage(wallet, storage));
+
+        let Payload { source, ecdsa } = storable;
+        let address_derivation_indexes = meta.and_then(|meta| meta.address_derivation_indexes()).unwrap_or_default();
+        let derivation = Self::create_derivation(wallet, &source, ecdsa, address_derivation_indexes).await?;
+
+        Ok(Self { inner, source, ecdsa, derivation })
+    }
+
+    async fn create_derivation(
+        wallet: &Arc<Wallet>,
+        source: &WatchOnlySource,
+        ecdsa: bool,
+        address_derivation_indexes: AddressDerivationMeta,
+    ) -> Result<Option<Arc<AddressDerivationManager>>> {
+        match source {
+            WatchOnlySource::Xpub(xpub) => {
+                let derivation = AddressDerivationManager::new(
+                    wallet,
+                    WATCH_ONLY_ACCOUNT_KIND.into(),
+                    &Arc::new(vec![xpub.clone()]),
+                    ecdsa,
+                    0,
+                    None,
+                    1,
+                    address_derivation_indexes,
+                )
+                .await?;
+                Ok(Some(derivation))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    pub fn source(&self) -> &WatchOnlySource {
+        &self.source
+    }
+
+    fn public_key_address(&self, public_key: &PublicKey) -> Result<Address> {
+        let prefix = self.inner().wallet.network_id()?.into();
+        if self.ecdsa {
+            Ok(Address::new(prefix, Version::PubKeyECDSA, &public_key.serialize()))
+        } else {
+            let (xonly_public_key, _) = public_key.x_only_public_key();
+            Ok(Address::new(prefix, Version::PubKey, &xonly_public_key.serialize()))
+        }
+    }
+}
+
+#[async_trait]
+impl Account for WatchOnly {
+    fn inner(&self) -> &Arc<Inner> {
+        &self.inner
+    }
+
+    fn account_kind(&self) -> AccountKind {
+        WATCH_ONLY_ACCOUNT_KIND.into()
+    }
+
+    fn prv_key_data_id(&self) -> Result<&PrvKeyDataId> {
+        Err(Error::WatchOnlyAccount)
+    }
+
+    fn as_dyn_arc(self: Arc<Self>) -> Arc<dyn Account> {
+        self
+    }
+
+    fn sig_op_count(&self) -> u8 {
+        1
+    }
+
+    fn minimum_signatures(&self) -> u16 {
+        1
+    }
+
+    fn receive_address(&self) -> Result<Address> {
+        if let Some(derivation) = &self.derivation {
+            return derivation.receive_address_manager().current_address();
+        }
+        match &self.source {
+            WatchOnlySource::Addresses(addresses) => {
+                addresses.first().cloned().ok_or_else(|| Error::custom("watch-only account has no addresses"))
+            }
+            WatchOnlySource::PublicKey(public_key) => self.public_key_address(public_key),
+            WatchOnlySource::Xpub(_) => unreachable!("xpub watch-only accounts always have an address derivation manager"),
+        }
+    }
+
+    /// Change of accounts without an xpub is returned to their first address.
+    fn change_address(&self) -> Result<Address> {
+        match &self.derivation {
+            Some(derivation) => derivation.change_address_manager().current_address(),
+            None => self.receive_address(),
+        }
+    }
+
+    fn monitored_addresses(&self) -> Result<Vec<Address>> {
+        match &self.source {
+            WatchOnlySource::Addresses(addresses) => Ok(addresses.clone()),
+            _ => Ok(vec![self.receive_address()?, self.change_address()?]),
+        }
+    }
+
+    fn to_storage(&self) -> Result<AccountStorage> {
+        let settings = self.context().settings.clone();
+        let storable = Payload::new(self.source.clone(), self.ecdsa);
+        let account_storage = AccountStorage::try_new(
+            WATCH_ONLY_ACCOUNT_KIND.into(),
+            self.id(),
+            self.storage_key(),
+            AssocPrvKeyDataIds::None,
+            settings,
+            storable,
+        )?;
+
+        Ok(account_storage)
+    }
+
+    fn metadata(&self) -> Result<Option<AccountMetadata>> {
+        let metadata = self.inner.metadata(self.derivation.as_ref().map(|derivation| derivation.address_derivation_meta()));
+        Ok(Some(metadata))
+    }
+
+    fn descriptor(&self) -> Result<AccountDescriptor> {
+        let descriptor = AccountDescriptor::new(
+            WATCH_ONLY_ACCOUNT_KIND.into(),
+            *self.id(),
+            self.name(),
+            AssocPrvKeyDataIds::None,
+            self.receive_address().ok(),
+            self.change_address().ok(),
+        )
+        .with_property(AccountDescriptorProperty::Ecdsa, self.ecdsa.into());
+
+        let descriptor = match (&self.source, &self.derivation) {
+            (WatchOnlySource::Xpub(xpub), Some(derivation)) => descriptor
+                .with_property(AccountDescriptorProperty::XpubKeys, Arc::new(vec![xpub.clone()]).into())
+                .with_property(AccountDescriptorProperty::DerivationMeta, derivation.address_derivation_meta().into()),
+            (WatchOnlySource::Addresses(addresses), _) => descriptor.with_property(
+                AccountDescriptorProperty::Other("Addresses".to_string()),
+                addresses.iter().map(|address| address.to_string()).collect::<Vec<_>>().join(", ").into(),
+            ),
+            (WatchOnlySource::PublicKey(public_key), _) => {
+                descriptor.with_property(AccountDescriptorProperty::Other("Public Key".to_string()), public_key.to_string().into())
+            }
+            _ => descriptor,
+        };
+
+        Ok(descriptor)
+    }
+
+    fn as_derivation_capable(self: Arc<Self>) -> Result<Arc<dyn DerivationCapableAccount>> {
+        if self.derivation.is_some() {
+            Ok(self.clone())
+        } else {
+            Err(Error::AccountKindFeature)
+        }
+    }
+}
+
+impl DerivationCapableAccount for WatchOnly {
+    fn derivation(&self) -> Arc<dyn AddressDerivationManagerTrait> {
+        // only xpub accounts are exposed as derivation capable (see `as_derivation_capable`)
+        self.derivation.clone().expect("watch-only account without an xpub is not derivation capable")
+    }
+
+    fn account_index(&self) -> u64 {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::tx::{Fees, PaymentOutput};
+    use crate::utils::waglayla_to_sompi;
+    use waglayla_addresses::Prefix;
+    use waglayla_consensus_core::network::NetworkType;
+    use waglayla_consensus_core::tx::{TransactionOutpoint, UtxoEntry};
+    use waglayla_rpc_core::RpcUtxosByAddressesEntry;
+    use waglayla_txscript::{extract_script_pub_key_address, pay_to_address_script};
+
+    #[test]
+    fn test_storage_watch_only() -> Result<()> {
+        let storable_in = Payload::new(WatchOnlySource::Xpub(make_xpub()), false);
+        let guard = StorageGuard::new(&storable_in);
+        let storable_out = guard.validate()?;
+        assert_eq!(storable_in.ecdsa, storable_out.ecdsa);
+        match (&storable_in.source, &storable_out.source) {
+            (WatchOnlySource::Xpub(xpub_in), WatchOnlySource::Xpub(xpub_out)) => assert_eq!(xpub_in, xpub_out),
+            _ => panic!("watch-only source mismatch"),
+        }
+
+        let public_key = make_xpub().public_key;
+        let storable_in = Payload::new(WatchOnlySource::PublicKey(public_key), true);
+        let guard = StorageGuard::new(&storable_in);
+        let storable_out = guard.validate()?;
+        assert_eq!(storable_in.ecdsa, storable_out.ecdsa);
+        assert!(matches!(storable_out.source, WatchOnlySource::PublicKey(key) if key == public_key));
+
+        let address = Address::new(Prefix::Mainnet, Version::PubKey, &public_key.x_only_public_key().0.serialize());
+        let source = WatchOnlySource::try_from_addresses(vec![address.clone(), address.clone()])?;
+        let storable_in = Payload::new(source, false);
+        let guard = StorageGuard::new(&storable_in);
+        let storable_out = guard.validate()?;
+        assert!(matches!(storable_out.source, WatchOnlySource::Addresses(addresses) if addresses == vec![address.clone()]));
+
+        let script_hash = Address::new(Prefix::Mainnet, Version::ScriptHash, &[0u8; 32]);
+        assert!(WatchOnlySource::try_from_addresses(vec![address, script_hash]).is_err());
+
+        Ok(())
+    }
+
+    /// Creates a watch-only account monitoring two addresses, holding 3 and 2 WALA,
+    /// along with an unrelated address holding 5 WALA in the mocked UTXO set
+    async fn make_watch_only_account() -> Result<(Arc<RpcCoreMock>, Arc<WatchOnly>, Vec<Address>, Address)> {
+        let network_id = NetworkId::with_suffix(NetworkType::Testnet, 10);
+        let rpc_mock = Arc::new(RpcCoreMock::new());
+        let wallet = Arc::new(Wallet::try_with_rpc(Some(rpc_mock.clone().into()), Wallet::resident_store()?, Some(network_id))?);
+        let processor = wallet.utxo_processor();
+        processor.mock_register_notification_listener().await?;
+        processor.mock_set_connected(true);
+        processor.handle_daa_score_change(1_000_000).await?;
+
+        let prefix = Prefix::from(network_id);
+        let addresses = (1..=2u8).map(|n| Address::new(prefix, Version::PubKey, &[n; 32])).collect::<Vec<_>>();
+        let unrelated = Address::new(prefix, Version::PubKey, &[3; 32]);
+        let utxos = [(&addresses[0], 3.0), (&addresses[1], 2.0), (&unrelated, 5.0)]
+            .into_iter()
+            .enumerate()
+            .map(|(index, (address, amount))| RpcUtxosByAddressesEntry {
+                address: Some(address.clone()),
+                outpoint: TransactionOutpoint::new(TransactionId::from_bytes([index as u8 + 1; 32]), 0),
+                utxo_entry: UtxoEntry::new(waglayla_to_sompi(amount), pay_to_address_script(address), 0, false),
+            })
+            .collect();
+        rpc_mock.set_utxos(utxos);
+
+        let account =
+            Arc::new(WatchOnly::try_new(&wallet, None, WatchOnlySource::try_from_addresses(addresses.clone())?, false).await?);
+        account.clone().scan(None, None).await?;
+        Ok((rpc_mock, account, addresses, unrelated))
+    }
+
+    #[tokio::test]
+    async fn test_watch_only_addresses_scan_and_unsigned_transactions() -> Result<()> {
+        let (rpc_mock, account, addresses, unrelated) = make_watch_only_account().await?;
+        let prefix = Prefix::from(account.wallet().network_id()?);
+
+        // the UTXOs are requested for the monitored addresses only
+        let monitored = account.monitored_addresses()?;
+        assert_eq!(monitored.len(), 2);
+        let requests = rpc_mock.utxos_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].iter().cloned().collect::<HashSet<_>>(), monitored.into_iter().collect::<HashSet<_>>());
+        assert_eq!(account.balance().map(|balance| balance.mature), Some(waglayla_to_sompi(5.0)));
+
+        // unsigned transactions spend the account UTXOs, change returning to the account
+        let destination = PaymentOutput::new(unrelated, waglayla_to_sompi(4.0));
+        let (summary, pskts) = account
+            .clone()
+            .create_unsigned_transactions(destination.into(), Fees::SenderPays(0), None, None, &Abortable::default())
+            .await?;
+        assert_eq!(pskts.len(), 1);
+        assert_eq!(summary.aggregated_utxos, 2);
+        let pskt = &pskts[0];
+        assert_eq!(pskt.inputs.len(), 2);
+        assert!(pskt.inputs.iter().all(|input| input.partial_signatures.is_empty()));
+        let spent = pskt
+            .inputs
+            .iter()
+            .map(|input| extract_script_pub_key_address(&input.utxo_entry.script_public_key, prefix))
+            .collect::<std::result::Result<HashSet<_>, _>>()?;
+        assert_eq!(spent, addresses.iter().cloned().collect::<HashSet<_>>());
+
+        Ok(())
+    }
+}
@@ -6,8 +6,10 @@
 //!
 
 use crate::imports::*;
+use crate::storage::transaction::{TransactionExportFilter, TransactionExportFormat};
 use crate::tx::{Fees, GeneratorSummary, PaymentDestination};
 use waglayla_addresses::Address;
+use waglayla_consensus_core::tx::TransactionOutpoint;
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
@@ -451,6 +453,8 @@ pub struct AccountsSendRequest {
     pub destination: PaymentDestination,
     pub priority_fee_sompi: Fees,
     pub payload: Option<Vec<u8>>,
+    /// UTXOs to spend instead of selecting UTXOs automatically (coin control)
+    pub utxo_selection: Option<Vec<TransactionOutpoint>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -541,6 +545,8 @@ pub struct AccountsEstimateRequest {
     pub destination: PaymentDestination,
     pub priority_fee_sompi: Fees,
     pub payload: Option<Vec<u8>>,
+    /// UTXOs to spend instead of selecting UTXOs automatically (coin control)
+    pub utxo_selection: Option<Vec<TransactionOutpoint>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -549,6 +555,55 @@ pub struct AccountsEstimateResponse {
     pub generator_summary: GeneratorSummary,
 }
 
+/// UTXO held by an account.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountUtxo {
+    pub outpoint: TransactionOutpoint,
+    pub address: Option<Address>,
+    pub amount: u64,
+    pub block_daa_score: u64,
+    pub is_coinbase: bool,
+    /// `false` if the UTXO is pending maturity and can not be spent yet
+    pub is_mature: bool,
+    /// `true` if the UTXO is excluded from the automatic UTXO selection
+    pub is_frozen: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsGetUtxosRequest {
+    pub account_id: AccountId,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsGetUtxosResponse {
+    pub utxos: Vec<AccountUtxo>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsFreezeUtxosRequest {
+    pub account_id: AccountId,
+    pub outpoints: Vec<TransactionOutpoint>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsFreezeUtxosResponse {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsUnfreezeUtxosRequest {
+    pub account_id: AccountId,
+    pub outpoints: Vec<TransactionOutpoint>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsUnfreezeUtxosResponse {}
+
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionsDataGetRequest {
@@ -600,6 +655,22 @@ pub struct TransactionsReplaceMetadataRequest {
 #[serde(rename_all = "camelCase")]
 pub struct TransactionsReplaceMetadataResponse {}
 
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsExportRequest {
+    /// Accounts to export the history of, all wallet accounts if not supplied
+    pub account_ids: Option<Vec<AccountId>>,
+    pub network_id: NetworkId,
+    pub filter: TransactionExportFilter,
+    pub format: TransactionExportFormat,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsExportResponse {
+    pub data: String,
+}
+
 // #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 // #[serde(rename_all = "camelCase")]
 // pub struct TransactionGetRequest {}
@@ -614,7 +685,55 @@ pub struct AddressBookEnumerateRequest {}
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct AddressBookEnumerateResponse {}
+pub struct AddressBookEnumerateResponse {
+    pub entries: Vec<AddressBookEntry>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressBookSearchRequest {
+    pub search: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressBookSearchResponse {
+    pub entries: Vec<AddressBookEntry>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressBookAddRequest {
+    pub wallet_secret: Secret,
+    pub entry: AddressBookEntry,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressBookAddResponse {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressBookEditRequest {
+    pub wallet_secret: Secret,
+    pub alias: String,
+    pub entry: AddressBookEntry,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressBookEditResponse {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressBookRemoveRequest {
+    pub wallet_secret: Secret,
+    pub alias: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressBookRemoveResponse {}
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
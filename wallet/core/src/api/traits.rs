@@ -427,6 +427,22 @@ pub trait WalletApi: Send + Sync + AnySync {
     /// an error.
     async fn accounts_estimate_call(self: Arc<Self>, request: AccountsEstimateRequest) -> Result<AccountsEstimateResponse>;
 
+    /// Returns the mature and pending UTXOs held by the account along with their
+    /// frozen state. See [`Self::accounts_freeze_utxos_call`].
+    async fn accounts_get_utxos_call(self: Arc<Self>, request: AccountsGetUtxosRequest) -> Result<AccountsGetUtxosResponse>;
+
+    /// Freezes account UTXOs (coin control). Frozen UTXOs are never selected
+    /// automatically when creating transactions, but can still be spent by
+    /// supplying them in the `utxo_selection` of [`AccountsSendRequest`].
+    /// The frozen state is persisted in the account metadata.
+    async fn accounts_freeze_utxos_call(self: Arc<Self>, request: AccountsFreezeUtxosRequest) -> Result<AccountsFreezeUtxosResponse>;
+
+    /// Unfreezes account UTXOs frozen by [`Self::accounts_freeze_utxos_call`].
+    async fn accounts_unfreeze_utxos_call(
+        self: Arc<Self>,
+        request: AccountsUnfreezeUtxosRequest,
+    ) -> Result<AccountsUnfreezeUtxosResponse>;
+
     /// Get a range of transaction records for a specific account id.
     async fn transactions_data_get_range(
         self: Arc<Self>,
@@ -463,10 +479,32 @@ pub trait WalletApi: Send + Sync + AnySync {
         request: TransactionsReplaceMetadataRequest,
     ) -> Result<TransactionsReplaceMetadataResponse>;
 
+    /// Exports the transaction history of the requested accounts as CSV or JSON,
+    /// including the records matching [`TransactionExportFilter`] only.
+    /// Use [`Wallet::export_transactions`](crate::wallet::Wallet::export_transactions)
+    /// to stream large exports.
+    async fn transactions_export_call(self: Arc<Self>, request: TransactionsExportRequest) -> Result<TransactionsExportResponse>;
+
+    /// Return all address book entries of the wallet.
     async fn address_book_enumerate_call(
         self: Arc<Self>,
         request: AddressBookEnumerateRequest,
     ) -> Result<AddressBookEnumerateResponse>;
+
+    /// Return address book entries whose alias, title or address contain the
+    /// search string (case-insensitive).
+    async fn address_book_search_call(self: Arc<Self>, request: AddressBookSearchRequest) -> Result<AddressBookSearchResponse>;
+
+    /// Add an entry to the address book. The alias must be unique within the
+    /// address book. The wallet is re-encrypted and stored using the supplied
+    /// `wallet_secret`.
+    async fn address_book_add_call(self: Arc<Self>, request: AddressBookAddRequest) -> Result<AddressBookAddResponse>;
+
+    /// Replace the address book entry identified by `alias`.
+    async fn address_book_edit_call(self: Arc<Self>, request: AddressBookEditRequest) -> Result<AddressBookEditResponse>;
+
+    /// Remove the address book entry identified by `alias`.
+    async fn address_book_remove_call(self: Arc<Self>, request: AddressBookRemoveRequest) -> Result<AddressBookRemoveResponse>;
 }
 
 /// alias for `Arc<dyn WalletApi + Send + Sync + 'static>`
@@ -104,10 +104,18 @@ impl WalletApi for WalletClient {
         AccountsAddresses,
         AccountsTransfer,
         AccountsEstimate,
+        AccountsGetUtxos,
+        AccountsFreezeUtxos,
+        AccountsUnfreezeUtxos,
         TransactionsDataGet,
         TransactionsReplaceNote,
         TransactionsReplaceMetadata,
+        TransactionsExport,
         AddressBookEnumerate,
+        AddressBookSearch,
+        AddressBookAdd,
+        AddressBookEdit,
+        AddressBookRemove,
     ]}
 }
 
@@ -184,10 +192,18 @@ impl WalletServer {
         AccountsAddresses,
         AccountsTransfer,
         AccountsEstimate,
+        AccountsGetUtxos,
+        AccountsFreezeUtxos,
+        AccountsUnfreezeUtxos,
         TransactionsDataGet,
         TransactionsReplaceNote,
         TransactionsReplaceMetadata,
+        TransactionsExport,
         AddressBookEnumerate,
+        AddressBookSearch,
+        AddressBookAdd,
+        AddressBookEdit,
+        AddressBookRemove,
     ]}
 }
 
@@ -106,6 +106,19 @@ impl AddressManager {
         create_address(self.minimum_signatures, keys, address_prefix, self.ecdsa, Some(self.account_kind))
     }
 
+    /// Returns the multisig redeem script of the address at `index`
+    pub fn redeem_script(&self, index: u32) -> Result<Vec<u8>> {
+        let keys = self
+            .pubkey_managers
+            .iter()
+            .map(|m| m.get_range(index..index + 1))
+            .collect::<waglayla_wallet_keys::result::Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        create_multisig_redeem_script(self.minimum_signatures, &keys, self.ecdsa)
+    }
+
     pub fn index(&self) -> u32 {
         self.inner().index
     }
@@ -439,17 +452,22 @@ pub trait AddressDerivationManagerTrait: AnySync + Send + Sync + 'static {
     ) -> Result<Vec<(Address, secp256k1::SecretKey)>>;
 }
 
+pub fn create_multisig_redeem_script(minimum_signatures: usize, keys: &[secp256k1::PublicKey], ecdsa: bool) -> Result<Vec<u8>> {
+    let script = if !ecdsa {
+        multisig_redeem_script(keys.iter().map(|pk| pk.x_only_public_key().0.serialize()), minimum_signatures)
+    } else {
+        multisig_redeem_script_ecdsa(keys.iter().map(|pk| pk.serialize()), minimum_signatures)
+    }?;
+    Ok(script)
+}
+
 pub fn create_multisig_address(
     minimum_signatures: usize,
     keys: Vec<secp256k1::PublicKey>,
     prefix: Prefix,
     ecdsa: bool,
 ) -> Result<Address> {
-    let script = if !ecdsa {
-        multisig_redeem_script(keys.iter().map(|pk| pk.x_only_public_key().0.serialize()), minimum_signatures)
-    } else {
-        multisig_redeem_script_ecdsa(keys.iter().map(|pk| pk.serialize()), minimum_signatures)
-    }?;
+    let script = create_multisig_redeem_script(minimum_signatures, &keys, ecdsa)?;
     let script_pub_key = pay_to_script_hash_script(&script);
     let address = extract_script_pub_key_address(&script_pub_key, prefix)?;
     Ok(address)
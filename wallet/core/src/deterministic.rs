@@ -2,7 +2,7 @@
 //! Deterministic byte sequence generation (used by Account ids).
 //!
 
-pub use crate::account::{bip32, keypair, legacy, multisig};
+pub use crate::account::{bip32, keypair, legacy, multisig, watchonly};
 use crate::encryption::sha256_hash;
 use crate::imports::*;
 use crate::storage::PrvKeyDataId;
@@ -161,6 +161,19 @@ pub(crate) fn from_keypair<const N: usize>(prv_key_data_id: &PrvKeyDataId, data:
     make_hashes(hashable)
 }
 
+/// Create deterministic hashes from watch-only account data.
+pub(crate) fn from_watch_only<const N: usize>(data: &watchonly::Payload) -> [Hash; N] {
+    let hashable: DeterministicHashData<[PrvKeyDataId; 0]> = DeterministicHashData {
+        account_kind: &watchonly::WATCH_ONLY_ACCOUNT_KIND.into(),
+        prv_key_data_ids: &None,
+        ecdsa: Some(data.ecdsa),
+        account_index: None,
+        secp256k1_public_key: None,
+        data: Some(data.source.try_to_vec().unwrap()),
+    };
+    make_hashes(hashable)
+}
+
 /// Create deterministic hashes from a public key.
 pub fn from_public_key<const N: usize>(account_kind: &AccountKind, public_key: &PublicKey) -> [Hash; N] {
     let hashable: DeterministicHashData<[PrvKeyDataId; 0]> = DeterministicHashData {
@@ -198,6 +198,18 @@ pub enum Error {
     #[error("Invalid account id: {0}")]
     InvalidAccountId(String),
 
+    #[error("Address book entry not found: {0}")]
+    AddressBookEntryNotFound(String),
+
+    #[error("Address book entry already exists: {0}")]
+    AddressBookEntryExists(String),
+
+    #[error("UTXO not found: {0}")]
+    UtxoNotFound(waglayla_consensus_core::tx::TransactionOutpoint),
+
+    #[error("UTXO is not mature: {0}")]
+    UtxoNotMature(waglayla_consensus_core::tx::TransactionOutpoint),
+
     #[error("Invalid id: {0}")]
     InvalidKeyDataId(String),
 
@@ -237,6 +249,12 @@ pub enum Error {
     #[error("Address derivation processing is not supported by this account type")]
     AccountAddressDerivationCaps,
 
+    #[error("Watch-only accounts do not hold private keys, please create an unsigned transaction instead")]
+    WatchOnlyAccount,
+
+    #[error("Watch-only accounts can not track pay-to-script-hash address {0}")]
+    WatchOnlyScriptHashAddress(String),
+
     #[error("{0}")]
     DowncastError(String),
 
@@ -326,6 +344,18 @@ pub enum Error {
 
     #[error(transparent)]
     Metrics(#[from] waglayla_metrics_core::error::Error),
+
+    #[error("Invalid PSKT: {0}")]
+    InvalidPskt(String),
+
+    #[error("PSKTs spend different transactions and can not be combined")]
+    PsktMismatch,
+
+    #[error("PSKT input {index} has {signatures} of {required} required signatures")]
+    PsktMissingSignatures { index: usize, signatures: usize, required: usize },
+
+    #[error("PSKT is not finalized")]
+    PsktNotFinalized,
 }
 
 impl From<Aborted> for Error {
@@ -32,6 +32,7 @@ pub fn factories() -> &'static FactoryMap {
             (LEGACY_ACCOUNT_KIND.into(), Arc::new(legacy::Ctor {})),
             (MULTISIG_ACCOUNT_KIND.into(), Arc::new(multisig::Ctor {})),
             (KEYPAIR_ACCOUNT_KIND.into(), Arc::new(keypair::Ctor {})),
+            (WATCH_ONLY_ACCOUNT_KIND.into(), Arc::new(watchonly::Ctor {})),
         ];
 
         let external = EXTERNAL.get_or_init(|| Mutex::new(AHashMap::new())).lock().unwrap().clone();
@@ -55,5 +56,8 @@ pub(crate) async fn try_load_account(
 ) -> Result<Arc<dyn Account>> {
     let factory = factories().get(&storage.kind).ok_or_else(|| Error::AccountFactoryNotFound(storage.kind))?;
 
-    factory.try_load(wallet, &storage, meta).await
+    let frozen_utxos = meta.as_ref().map(|meta| meta.frozen_utxos.clone()).unwrap_or_default();
+    let account = factory.try_load(wallet, &storage, meta).await?;
+    account.inner().set_frozen_utxos(frozen_utxos);
+    Ok(account)
 }
@@ -4,10 +4,126 @@
 
 use crate::imports::*;
 
-// TODO
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+/// Labelled contact stored in the encrypted wallet payload.
+/// The `alias` uniquely identifies the entry and can be used
+/// in place of the address when sending funds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AddressBookEntry {
     pub alias: String,
     pub title: String,
     pub address: Address,
+    /// Network the entry is restricted to, if any.
+    pub network_id: Option<NetworkId>,
+}
+
+impl AddressBookEntry {
+    const STORAGE_MAGIC: u32 = 0x4b4f4f42;
+    const STORAGE_VERSION: u32 = 0;
+
+    pub fn new(alias: String, title: String, address: Address, network_id: Option<NetworkId>) -> Self {
+        Self { alias, title, address, network_id }
+    }
+
+    /// Checks that the alias can not be mistaken for an address
+    /// and that the address belongs to the network of the entry.
+    pub fn validate(&self) -> Result<()> {
+        if self.alias.trim().is_empty() || self.alias.trim() != self.alias {
+            return Err(Error::custom("address book alias must not be empty or have leading or trailing whitespace"));
+        }
+        if Address::try_from(self.alias.as_str()).is_ok() {
+            return Err(Error::custom("address book alias must not be an address"));
+        }
+        if let Some(network_id) = self.network_id {
+            if self.address.prefix != network_id.into() {
+                return Err(Error::custom(format!("address {} does not belong to the {network_id} network", self.address)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Aliases are matched case-insensitively.
+    pub fn is_alias(&self, alias: &str) -> bool {
+        self.alias.to_lowercase() == alias.to_lowercase()
+    }
+
+    /// Case-insensitive match against the alias, the title or the address.
+    pub fn matches(&self, search: &str) -> bool {
+        let search = search.to_lowercase();
+        self.alias.to_lowercase().contains(&search)
+            || self.title.to_lowercase().contains(&search)
+            || self.address.to_string().contains(&search)
+    }
+
+    /// Returns `true` if the entry can be used on `network_id`.
+    pub fn is_available_on(&self, network_id: &NetworkId) -> bool {
+        self.network_id.map(|id| id == *network_id).unwrap_or_else(|| self.address.prefix == (*network_id).into())
+    }
+}
+
+impl BorshSerialize for AddressBookEntry {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        StorageHeader::new(Self::STORAGE_MAGIC, Self::STORAGE_VERSION).serialize(writer)?;
+        BorshSerialize::serialize(&self.alias, writer)?;
+        BorshSerialize::serialize(&self.title, writer)?;
+        BorshSerialize::serialize(&self.address, writer)?;
+        BorshSerialize::serialize(&self.network_id, writer)?;
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for AddressBookEntry {
+    fn deserialize(buf: &mut &[u8]) -> IoResult<Self> {
+        let StorageHeader { version: _, .. } =
+            StorageHeader::deserialize(buf)?.try_magic(Self::STORAGE_MAGIC)?.try_version(Self::STORAGE_VERSION)?;
+
+        let alias = BorshDeserialize::deserialize(buf)?;
+        let title = BorshDeserialize::deserialize(buf)?;
+        let address = BorshDeserialize::deserialize(buf)?;
+        let network_id = BorshDeserialize::deserialize(buf)?;
+
+        Ok(Self { alias, title, address, network_id })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use waglayla_addresses::{Prefix, Version};
+
+    fn address() -> Address {
+        Address::new(Prefix::Mainnet, Version::PubKey, &[7u8; 32])
+    }
+
+    fn entry(alias: &str, network_id: Option<NetworkId>) -> AddressBookEntry {
+        AddressBookEntry::new(alias.to_string(), "Exchange deposit".to_string(), address(), network_id)
+    }
+
+    #[test]
+    fn test_storage_address_book_entry() -> Result<()> {
+        let storable_in = entry("alice", Some(NetworkId::new(NetworkType::Mainnet)));
+        let guard = StorageGuard::new(&storable_in);
+        let storable_out = guard.validate()?;
+
+        assert_eq!(storable_in, storable_out);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_address_book_entry_validation() {
+        assert!(entry("alice", None).validate().is_ok());
+        assert!(entry("", None).validate().is_err());
+        assert!(entry(" alice", None).validate().is_err());
+        assert!(entry(&address().to_string(), None).validate().is_err());
+        assert!(entry("alice", Some(NetworkId::with_suffix(NetworkType::Testnet, 10))).validate().is_err());
+
+        let alice = entry("Alice", None);
+        assert!(alice.is_alias("alice"));
+        assert!(alice.matches("EXCHANGE"));
+        assert!(alice.is_available_on(&NetworkId::new(NetworkType::Mainnet)));
+        assert!(!alice.is_available_on(&NetworkId::with_suffix(NetworkType::Testnet, 10)));
+    }
 }
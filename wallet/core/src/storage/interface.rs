@@ -109,6 +109,20 @@ pub trait AddressBookStore: Send + Sync {
     async fn search(&self, _search: &str) -> Result<Vec<Arc<AddressBookEntry>>> {
         Err(Error::NotImplemented)
     }
+    async fn load_single(&self, _alias: &str) -> Result<Option<Arc<AddressBookEntry>>> {
+        Err(Error::NotImplemented)
+    }
+    /// Adds a new entry, failing if an entry with the same alias exists
+    async fn add(&self, _entry: AddressBookEntry) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
+    /// Replaces the entry with the `alias` (the alias itself may be changed)
+    async fn update(&self, _alias: &str, _entry: AddressBookEntry) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
+    async fn remove(&self, _alias: &str) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
 }
 
 pub struct TransactionRangeResult {
@@ -207,6 +221,9 @@ pub trait Interface: Send + Sync + AnySync {
     /// rename the currently open wallet (title or the filename)
     async fn rename(&self, wallet_secret: &Secret, title: Option<&str>, filename: Option<&str>) -> Result<()>;
 
+    /// check that the secret decrypts the currently open wallet
+    async fn check_secret(&self, wallet_secret: &Secret) -> Result<()>;
+
     /// change the secret of the currently open wallet
     async fn change_secret(&self, old_wallet_secret: &Secret, new_wallet_secret: &Secret) -> Result<()>;
 
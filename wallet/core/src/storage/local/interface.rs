@@ -359,6 +359,11 @@ impl Interface for LocalStore {
         Ok(())
     }
 
+    async fn check_secret(&self, wallet_secret: &Secret) -> Result<()> {
+        let _: Decrypted<PrvKeyDataMap> = self.inner()?.cache.read().unwrap().prv_key_data.decrypt(wallet_secret)?;
+        Ok(())
+    }
+
     /// change the secret of the currently open wallet
     async fn change_secret(&self, old_wallet_secret: &Secret, new_wallet_secret: &Secret) -> Result<()> {
         let inner = self.inner.lock().unwrap().clone().ok_or(Error::WalletNotOpen)?;
@@ -609,6 +614,10 @@ impl AccountStore for LocalStoreInner {
 
 #[async_trait]
 impl AddressBookStore for LocalStoreInner {
+    async fn is_empty(&self) -> Result<bool> {
+        Ok(self.cache.read().unwrap().address_book.is_empty())
+    }
+
     async fn iter(&self) -> Result<StorageStream<Arc<AddressBookEntry>>> {
         Ok(Box::pin(AddressBookEntryStream::new(self.cache.clone())))
     }
@@ -620,9 +629,93 @@ impl AddressBookStore for LocalStoreInner {
             .unwrap()
             .address_book
             .iter()
-            .filter_map(|entry| if entry.alias.contains(search) { Some(Arc::new(entry.clone())) } else { None })
+            .filter_map(|entry| if entry.matches(search) { Some(Arc::new(entry.clone())) } else { None })
             .collect();
 
         Ok(matches)
     }
+
+    async fn load_single(&self, alias: &str) -> Result<Option<Arc<AddressBookEntry>>> {
+        let cache = self.cache.read().unwrap();
+        Ok(cache.address_book.iter().find(|entry| entry.is_alias(alias)).map(|entry| Arc::new(entry.clone())))
+    }
+
+    async fn add(&self, entry: AddressBookEntry) -> Result<()> {
+        entry.validate()?;
+        let mut cache = self.cache.write().unwrap();
+        if cache.address_book.iter().any(|existing| existing.is_alias(&entry.alias)) {
+            return Err(Error::AddressBookEntryExists(entry.alias));
+        }
+        cache.address_book.push(entry);
+        self.set_modified(true);
+        Ok(())
+    }
+
+    async fn update(&self, alias: &str, entry: AddressBookEntry) -> Result<()> {
+        entry.validate()?;
+        let mut cache = self.cache.write().unwrap();
+        if cache.address_book.iter().any(|existing| existing.is_alias(&entry.alias) && !existing.is_alias(alias)) {
+            return Err(Error::AddressBookEntryExists(entry.alias));
+        }
+        let existing = cache
+            .address_book
+            .iter_mut()
+            .find(|existing| existing.is_alias(alias))
+            .ok_or_else(|| Error::AddressBookEntryNotFound(alias.to_string()))?;
+        *existing = entry;
+        self.set_modified(true);
+        Ok(())
+    }
+
+    async fn remove(&self, alias: &str) -> Result<()> {
+        let mut cache = self.cache.write().unwrap();
+        let len = cache.address_book.len();
+        cache.address_book.retain(|entry| !entry.is_alias(alias));
+        if cache.address_book.len() == len {
+            return Err(Error::AddressBookEntryNotFound(alias.to_string()));
+        }
+        self.set_modified(true);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{message::*, traits::WalletApi};
+    use waglayla_addresses::{Prefix, Version};
+
+    #[tokio::test]
+    async fn test_address_book_wallet_secret() -> Result<()> {
+        let store = Arc::new(LocalStore::try_new(true)?);
+        let wallet_secret = Secret::from("wallet-secret");
+        let wrong_secret = Secret::from("wrong-secret");
+        store.create(&wallet_secret, CreateArgs::new(None, None, EncryptionKind::XChaCha20Poly1305, None, false)).await?;
+        store.check_secret(&wallet_secret).await?;
+        assert!(store.check_secret(&wrong_secret).await.is_err());
+
+        let wallet = Arc::new(Wallet::try_with_rpc(None, store.clone(), None)?);
+        let address_book = store.as_address_book_store()?;
+        let address = Address::new(Prefix::Testnet, Version::PubKey, &[1; 32]);
+        let entry = AddressBookEntry::new("alice".to_string(), "Alice".to_string(), address.clone(), None);
+
+        // requests with a wrong secret are refused before the address book is changed
+        let request = AddressBookAddRequest { wallet_secret: wrong_secret.clone(), entry: entry.clone() };
+        assert!(wallet.clone().address_book_add_call(request).await.is_err());
+        assert!(address_book.is_empty().await?);
+
+        wallet.clone().address_book_add_call(AddressBookAddRequest { wallet_secret: wallet_secret.clone(), entry }).await?;
+
+        let entry = AddressBookEntry::new("bob".to_string(), "Bob".to_string(), address, None);
+        let request = AddressBookEditRequest { wallet_secret: wrong_secret.clone(), alias: "alice".to_string(), entry };
+        assert!(wallet.clone().address_book_edit_call(request).await.is_err());
+        let request = AddressBookRemoveRequest { wallet_secret: wrong_secret, alias: "alice".to_string() };
+        assert!(wallet.clone().address_book_remove_call(request).await.is_err());
+        assert!(address_book.load_single("alice").await?.is_some_and(|entry| entry.title == "Alice"));
+
+        wallet.clone().address_book_remove_call(AddressBookRemoveRequest { wallet_secret, alias: "alice".to_string() }).await?;
+        assert!(address_book.is_empty().await?);
+
+        Ok(())
+    }
 }
@@ -8,20 +8,24 @@
 use crate::derivation::AddressDerivationMeta;
 use crate::imports::*;
 use crate::storage::IdT;
+use waglayla_consensus_core::tx::TransactionOutpoint;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AccountMetadata {
     pub id: AccountId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub indexes: Option<AddressDerivationMeta>,
+    /// UTXOs excluded from the automatic UTXO selection (coin control)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub frozen_utxos: Vec<TransactionOutpoint>,
 }
 
 impl AccountMetadata {
     const STORAGE_MAGIC: u32 = 0x4154454d;
-    const STORAGE_VERSION: u32 = 0;
+    const STORAGE_VERSION: u32 = 1;
 
     pub fn new(id: AccountId, indexes: AddressDerivationMeta) -> Self {
-        Self { id, indexes: Some(indexes) }
+        Self { id, indexes: Some(indexes), frozen_utxos: vec![] }
     }
 
     pub fn address_derivation_indexes(&self) -> Option<AddressDerivationMeta> {
@@ -41,6 +45,7 @@ impl BorshSerialize for AccountMetadata {
         StorageHeader::new(Self::STORAGE_MAGIC, Self::STORAGE_VERSION).serialize(writer)?;
         BorshSerialize::serialize(&self.id, writer)?;
         BorshSerialize::serialize(&self.indexes, writer)?;
+        BorshSerialize::serialize(&self.frozen_utxos, writer)?;
 
         Ok(())
     }
@@ -48,12 +53,53 @@ impl BorshSerialize for AccountMetadata {
 
 impl BorshDeserialize for AccountMetadata {
     fn deserialize(buf: &mut &[u8]) -> IoResult<Self> {
-        let StorageHeader { version: _, .. } =
+        let StorageHeader { version, .. } =
             StorageHeader::deserialize(buf)?.try_magic(Self::STORAGE_MAGIC)?.try_version(Self::STORAGE_VERSION)?;
 
         let id = BorshDeserialize::deserialize(buf)?;
         let indexes = BorshDeserialize::deserialize(buf)?;
+        // frozen UTXOs were introduced in version 1
+        let frozen_utxos = if version > 0 { BorshDeserialize::deserialize(buf)? } else { vec![] };
 
-        Ok(Self { id, indexes })
+        Ok(Self { id, indexes, frozen_utxos })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use waglayla_consensus_core::tx::TransactionId;
+
+    fn account_id() -> AccountId {
+        make_account_hashes(from_data(&BIP32_ACCOUNT_KIND.into(), &[0x00, 0x01, 0x02, 0x03])).0
+    }
+
+    #[test]
+    fn test_storage_account_metadata() -> Result<()> {
+        let mut storable_in = AccountMetadata::new(account_id(), AddressDerivationMeta::new(3, 5));
+        storable_in.frozen_utxos = vec![TransactionOutpoint::new(TransactionId::from_u64_word(7), 1)];
+        let guard = StorageGuard::new(&storable_in);
+        let storable_out = guard.validate()?;
+
+        assert_eq!(storable_in.id, storable_out.id);
+        assert_eq!(storable_out.indexes.as_ref().map(|indexes| (indexes.receive(), indexes.change())), Some((3, 5)));
+        assert_eq!(storable_in.frozen_utxos, storable_out.frozen_utxos);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_storage_account_metadata_v0() -> Result<()> {
+        let mut bytes = vec![];
+        StorageHeader::new(AccountMetadata::STORAGE_MAGIC, 0).serialize(&mut bytes)?;
+        BorshSerialize::serialize(&account_id(), &mut bytes)?;
+        BorshSerialize::serialize(&Some(AddressDerivationMeta::new(3, 5)), &mut bytes)?;
+
+        let metadata = AccountMetadata::try_from_slice(&bytes)?;
+        assert_eq!(metadata.id, account_id());
+        assert!(metadata.frozen_utxos.is_empty());
+
+        Ok(())
     }
 }
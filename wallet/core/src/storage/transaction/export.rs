@@ -0,0 +1,414 @@
+//!
+//! Transaction history export (CSV and JSON).
+//!
+
+use super::*;
+use crate::imports::*;
+use waglayla_addresses::Prefix;
+use waglayla_txscript::extract_script_pub_key_address;
+
+/// Encoding of exported transaction history.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl TransactionExportFormat {
+    const CSV_HEADER: &'static str =
+        "id,account,kind,unixtime_msec,block_daa_score,amount,fees,addresses,counterparties,note,metadata";
+
+    /// Text preceding the exported rows
+    pub fn header(&self) -> String {
+        match self {
+            TransactionExportFormat::Csv => format!("{}\n", Self::CSV_HEADER),
+            TransactionExportFormat::Json => "[".to_string(),
+        }
+    }
+
+    /// Text of the row at `index` (starting at 0) of the export
+    pub fn row(&self, index: usize, row: &TransactionExportRow) -> Result<String> {
+        match self {
+            TransactionExportFormat::Csv => Ok(row.to_csv()),
+            TransactionExportFormat::Json => {
+                let separator = if index == 0 { "\n" } else { ",\n" };
+                Ok(format!("{separator}{}", serde_json::to_string(row)?))
+            }
+        }
+    }
+
+    /// Text following the exported rows
+    pub fn footer(&self) -> String {
+        match self {
+            TransactionExportFormat::Csv => String::new(),
+            TransactionExportFormat::Json => "\n]\n".to_string(),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TransactionExportFormat::Csv => "csv",
+            TransactionExportFormat::Json => "json",
+        }
+    }
+}
+
+impl FromStr for TransactionExportFormat {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(TransactionExportFormat::Csv),
+            "json" => Ok(TransactionExportFormat::Json),
+            _ => Err(Error::custom(format!("invalid transaction export format '{s}' (expected 'csv' or 'json')"))),
+        }
+    }
+}
+
+impl std::fmt::Display for TransactionExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+/// Selects the exported transaction records. Ranges include their start and exclude their end,
+/// an unset bound leaves the range open. Records without a timestamp are excluded when a time
+/// range is set.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionExportFilter {
+    pub kinds: Option<Vec<TransactionKind>>,
+    pub start_unixtime_msec: Option<u64>,
+    pub end_unixtime_msec: Option<u64>,
+    pub start_daa_score: Option<u64>,
+    pub end_daa_score: Option<u64>,
+}
+
+impl TransactionExportFilter {
+    pub fn matches(&self, record: &TransactionRecord) -> bool {
+        if let Some(kinds) = &self.kinds {
+            if !kinds.contains(&record.kind()) {
+                return false;
+            }
+        }
+
+        if self.start_unixtime_msec.is_some() || self.end_unixtime_msec.is_some() {
+            let Some(unixtime_msec) = record.unixtime_msec() else {
+                return false;
+            };
+            if self.start_unixtime_msec.is_some_and(|start| unixtime_msec < start)
+                || self.end_unixtime_msec.is_some_and(|end| unixtime_msec >= end)
+            {
+                return false;
+            }
+        }
+
+        let block_daa_score = record.block_daa_score();
+        !(self.start_daa_score.is_some_and(|start| block_daa_score < start)
+            || self.end_daa_score.is_some_and(|end| block_daa_score >= end))
+    }
+}
+
+/// Flattened [`TransactionRecord`] as exported. Amounts are in sompi.
+///
+/// `addresses` are the wallet addresses spent from or received to, while `counterparties` are
+/// the destinations of outgoing transactions and the change address of incoming transfers
+/// (senders of other incoming transactions are not known to the wallet).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionExportRow {
+    pub id: TransactionId,
+    pub account: String,
+    pub kind: TransactionKind,
+    pub unixtime_msec: Option<u64>,
+    pub block_daa_score: u64,
+    pub amount: u64,
+    pub fees: Option<u64>,
+    pub addresses: Vec<String>,
+    pub counterparties: Vec<String>,
+    pub note: Option<String>,
+    pub metadata: Option<String>,
+}
+
+impl TransactionExportRow {
+    fn to_csv(&self) -> String {
+        let fields = [
+            self.id.to_string(),
+            self.account.clone(),
+            self.kind.to_string(),
+            self.unixtime_msec.map(|v| v.to_string()).unwrap_or_default(),
+            self.block_daa_score.to_string(),
+            self.amount.to_string(),
+            self.fees.map(|v| v.to_string()).unwrap_or_default(),
+            self.addresses.join(" "),
+            self.counterparties.join(" "),
+            self.note.clone().unwrap_or_default(),
+            self.metadata.clone().unwrap_or_default(),
+        ];
+        let mut line = fields.iter().map(|field| csv_escape(field)).collect::<Vec<_>>().join(",");
+        line.push('\n');
+        line
+    }
+}
+
+impl From<&TransactionRecord> for TransactionExportRow {
+    fn from(record: &TransactionRecord) -> Self {
+        let prefix = Prefix::from(record.network_id);
+
+        let (fees, utxo_entries, counterparties) = match record.transaction_data() {
+            TransactionData::Reorg { utxo_entries, .. }
+            | TransactionData::Stasis { utxo_entries, .. }
+            | TransactionData::Incoming { utxo_entries, .. }
+            | TransactionData::External { utxo_entries, .. } => (None, utxo_entries, vec![]),
+            TransactionData::Change { utxo_entries, .. } => (None, utxo_entries, vec![]),
+            TransactionData::Batch { fees, utxo_entries, .. } => (Some(*fees), utxo_entries, vec![]),
+            TransactionData::Outgoing { fees, utxo_entries, transaction, change_value, .. }
+            | TransactionData::TransferOutgoing { fees, utxo_entries, transaction, change_value, .. } => {
+                // change is always the last output of transactions created by the wallet
+                let payments = if *change_value > 0 { transaction.outputs.len().saturating_sub(1) } else { transaction.outputs.len() };
+                let counterparties = transaction.outputs[..payments]
+                    .iter()
+                    .filter_map(|output| extract_script_pub_key_address(&output.script_public_key, prefix).ok())
+                    .collect();
+                (Some(*fees), utxo_entries, counterparties)
+            }
+            TransactionData::TransferIncoming { fees, utxo_entries, transaction, change_value, .. } => {
+                let counterparties = transaction
+                    .outputs
+                    .last()
+                    .filter(|_| *change_value > 0)
+                    .and_then(|output| extract_script_pub_key_address(&output.script_public_key, prefix).ok())
+                    .into_iter()
+                    .collect();
+                (Some(*fees), utxo_entries, counterparties)
+            }
+        };
+
+        let mut addresses = vec![];
+        for address in utxo_entries.iter().filter_map(|utxo| utxo.address.as_ref()) {
+            if !addresses.contains(address) {
+                addresses.push(address.clone());
+            }
+        }
+
+        Self {
+            id: *record.id(),
+            account: record.binding().to_hex(),
+            kind: record.kind(),
+            unixtime_msec: record.unixtime_msec(),
+            block_daa_score: record.block_daa_score(),
+            amount: record.value(),
+            fees,
+            addresses: addresses.iter().map(|address| address.to_string()).collect(),
+            counterparties: counterparties.iter().map(|address: &Address| address.to_string()).collect(),
+            note: record.note.clone(),
+            metadata: record.metadata.clone(),
+        }
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break. Fields starting with
+/// `=`, `+`, `-` or `@` are prefixed with `'` so that spreadsheet applications do not evaluate
+/// them as formulas.
+fn csv_escape(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@']) { format!("'{field}") } else { field.to_string() };
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deterministic::AccountId;
+    use waglayla_addresses::Version;
+    use waglayla_consensus_core::network::NetworkType;
+    use waglayla_consensus_core::subnets::SUBNETWORK_ID_NATIVE;
+    use waglayla_consensus_core::tx::{Transaction, TransactionOutput};
+    use waglayla_hashes::Hash;
+    use waglayla_txscript::pay_to_address_script;
+
+    const NETWORK_TYPE: NetworkType = NetworkType::Testnet;
+
+    fn make_address(n: u8) -> Address {
+        Address::new(NETWORK_TYPE.into(), Version::PubKey, &[n; 32])
+    }
+
+    fn make_record(unixtime_msec: Option<u64>, block_daa_score: u64, transaction_data: TransactionData) -> TransactionRecord {
+        TransactionRecord {
+            id: TransactionId::from_bytes([1; 32]),
+            unixtime_msec,
+            value: 100,
+            binding: Binding::Account(AccountId(Hash::from_bytes([2; 32]))),
+            block_daa_score,
+            network_id: NetworkId::with_suffix(NETWORK_TYPE, 10),
+            transaction_data,
+            note: None,
+            metadata: None,
+        }
+    }
+
+    fn make_incoming(unixtime_msec: Option<u64>, block_daa_score: u64) -> TransactionRecord {
+        make_record(unixtime_msec, block_daa_score, TransactionData::Incoming { utxo_entries: vec![], aggregate_input_value: 100 })
+    }
+
+    fn make_utxo_record(address: &Address) -> UtxoRecord {
+        UtxoRecord {
+            address: Some(address.clone()),
+            index: 0,
+            amount: 100,
+            script_public_key: pay_to_address_script(address),
+            is_coinbase: false,
+        }
+    }
+
+    /// Wallet transaction spending from `spent` and paying to `outputs`, the change output being the last one if any
+    fn make_transfer(spent: &Address, outputs: &[&Address]) -> (Transaction, Vec<UtxoRecord>) {
+        let outputs = outputs.iter().map(|address| TransactionOutput::new(10, pay_to_address_script(address))).collect();
+        let transaction = Transaction::new(0, vec![], outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+        (transaction, vec![make_utxo_record(spent), make_utxo_record(spent)])
+    }
+
+    #[test]
+    fn test_csv_escape() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_escape("multi\nline"), "\"multi\nline\"");
+
+        // formulas are not evaluated by spreadsheet applications
+        assert_eq!(csv_escape("=SUM(A1:A2)"), "'=SUM(A1:A2)");
+        assert_eq!(csv_escape("+1"), "'+1");
+        assert_eq!(csv_escape("-1"), "'-1");
+        assert_eq!(csv_escape("@cmd"), "'@cmd");
+        assert_eq!(csv_escape("=A1,B1"), "\"'=A1,B1\"");
+        assert_eq!(csv_escape("a=b"), "a=b");
+    }
+
+    #[test]
+    fn test_export_filter_matches() {
+        let filter = TransactionExportFilter::default();
+        assert!(filter.matches(&make_incoming(None, 0)));
+        assert!(filter.matches(&make_incoming(Some(1000), 10)));
+
+        // time ranges include their start and exclude their end
+        let filter = TransactionExportFilter { start_unixtime_msec: Some(1000), end_unixtime_msec: Some(2000), ..Default::default() };
+        let matches = [999, 1000, 1999, 2000].map(|unixtime_msec| filter.matches(&make_incoming(Some(unixtime_msec), 0)));
+        assert_eq!(matches, [false, true, true, false]);
+
+        // records without a timestamp are excluded by time ranges, even open ones
+        assert!(!filter.matches(&make_incoming(None, 0)));
+        let filter = TransactionExportFilter { start_unixtime_msec: Some(0), ..Default::default() };
+        assert!(!filter.matches(&make_incoming(None, 0)));
+        assert!(filter.matches(&make_incoming(Some(u64::MAX), 0)));
+        let filter = TransactionExportFilter { end_unixtime_msec: Some(2000), ..Default::default() };
+        assert!(!filter.matches(&make_incoming(None, 0)));
+        assert!(filter.matches(&make_incoming(Some(0), 0)));
+
+        // DAA score ranges include their start and exclude their end, regardless of timestamps
+        let filter = TransactionExportFilter { start_daa_score: Some(10), end_daa_score: Some(20), ..Default::default() };
+        let matches = [9, 10, 19, 20].map(|block_daa_score| filter.matches(&make_incoming(None, block_daa_score)));
+        assert_eq!(matches, [false, true, true, false]);
+        let filter = TransactionExportFilter { end_daa_score: Some(20), ..Default::default() };
+        assert!(filter.matches(&make_incoming(None, 0)));
+
+        let filter = TransactionExportFilter { kinds: Some(vec![TransactionKind::Outgoing]), ..Default::default() };
+        assert!(!filter.matches(&make_incoming(Some(1000), 10)));
+        let filter = TransactionExportFilter {
+            kinds: Some(vec![TransactionKind::Outgoing, TransactionKind::Incoming]),
+            start_daa_score: Some(10),
+            ..Default::default()
+        };
+        assert!(filter.matches(&make_incoming(None, 10)));
+        assert!(!filter.matches(&make_incoming(None, 9)));
+    }
+
+    #[test]
+    fn test_export_row_counterparties() {
+        let [wallet, payee, other_payee, change] = [1, 2, 3, 4].map(make_address);
+
+        // outgoing transactions list their payment destinations, excluding the change output
+        let (transaction, utxo_entries) = make_transfer(&wallet, &[&payee, &other_payee, &change]);
+        let row = TransactionExportRow::from(&make_record(
+            Some(1000),
+            10,
+            TransactionData::Outgoing {
+                fees: 5,
+                aggregate_input_value: 200,
+                aggregate_output_value: 30,
+                transaction,
+                payment_value: Some(20),
+                change_value: 10,
+                accepted_daa_score: None,
+                utxo_entries,
+            },
+        ));
+        assert_eq!(row.counterparties, vec![payee.to_string(), other_payee.to_string()]);
+        assert_eq!(row.addresses, vec![wallet.to_string()]);
+        assert_eq!(row.fees, Some(5));
+
+        // without change, every output is a payment
+        let (transaction, utxo_entries) = make_transfer(&wallet, &[&payee, &other_payee]);
+        let row = TransactionExportRow::from(&make_record(
+            Some(1000),
+            10,
+            TransactionData::TransferOutgoing {
+                fees: 5,
+                aggregate_input_value: 200,
+                aggregate_output_value: 20,
+                transaction,
+                payment_value: Some(20),
+                change_value: 0,
+                accepted_daa_score: None,
+                utxo_entries,
+            },
+        ));
+        assert_eq!(row.counterparties, vec![payee.to_string(), other_payee.to_string()]);
+
+        // incoming transfers list the change address of the sending account
+        let (transaction, utxo_entries) = make_transfer(&wallet, &[&payee, &change]);
+        let row = TransactionExportRow::from(&make_record(
+            Some(1000),
+            10,
+            TransactionData::TransferIncoming {
+                fees: 5,
+                aggregate_input_value: 200,
+                aggregate_output_value: 20,
+                transaction: transaction.clone(),
+                payment_value: Some(10),
+                change_value: 10,
+                accepted_daa_score: None,
+                utxo_entries: utxo_entries.clone(),
+            },
+        ));
+        assert_eq!(row.counterparties, vec![change.to_string()]);
+        let row = TransactionExportRow::from(&make_record(
+            Some(1000),
+            10,
+            TransactionData::TransferIncoming {
+                fees: 5,
+                aggregate_input_value: 200,
+                aggregate_output_value: 20,
+                transaction,
+                payment_value: Some(10),
+                change_value: 0,
+                accepted_daa_score: None,
+                utxo_entries,
+            },
+        ));
+        assert!(row.counterparties.is_empty());
+
+        // senders of other incoming transactions are not known
+        let row = TransactionExportRow::from(&make_record(
+            None,
+            10,
+            TransactionData::Incoming { utxo_entries: vec![make_utxo_record(&payee)], aggregate_input_value: 100 },
+        ));
+        assert!(row.counterparties.is_empty());
+        assert_eq!(row.addresses, vec![payee.to_string()]);
+        assert_eq!(row.fees, None);
+    }
+}
@@ -10,6 +10,7 @@ use workflow_core::time::{unixtime_as_millis_u64, unixtime_to_locale_string};
 use workflow_wasm::utils::try_get_js_value_prop;
 
 pub use waglayla_consensus_core::tx::TransactionId;
+use waglayla_consensus_core::tx::Transaction;
 use zeroize::Zeroize;
 
 #[wasm_bindgen(typescript_custom_section)]
@@ -533,6 +534,52 @@ impl TransactionRecord {
         }
     }
 
+    /// Outgoing transaction that was not issued by this instance of the wallet
+    /// but spends from this address set, as rebuilt from the node address index.
+    /// `spent` holds the previous outputs of the address set redeemed by the
+    /// transaction and `received` its outputs paying back to the address set.
+    pub fn new_external_outgoing(
+        utxo_context: &UtxoContext,
+        transaction: Transaction,
+        accepted_daa_score: u64,
+        spent: &[UtxoEntryReference],
+        received: &[UtxoEntryReference],
+    ) -> Self {
+        let binding = Binding::from(utxo_context.binding());
+        let utxo_entries = spent.iter().map(UtxoRecord::from).collect::<Vec<_>>();
+        let aggregate_input_value = utxo_entries.iter().map(|utxo| utxo.amount).sum::<u64>();
+        let aggregate_output_value = transaction.outputs.iter().map(|output| output.value).sum::<u64>();
+        let change_value = received.iter().map(|utxo| utxo.amount()).sum::<u64>();
+        let payment_value = aggregate_output_value.saturating_sub(change_value);
+        // inputs not belonging to the address set are unknown, so the fees are a lower bound
+        let fees = aggregate_input_value.saturating_sub(aggregate_output_value);
+        let id = transaction.id();
+
+        let transaction_data = TransactionData::Outgoing {
+            fees,
+            aggregate_input_value,
+            aggregate_output_value,
+            transaction,
+            payment_value: Some(payment_value),
+            change_value,
+            accepted_daa_score: Some(accepted_daa_score),
+            utxo_entries,
+        };
+        let unixtime = unixtime_as_millis_u64();
+
+        TransactionRecord {
+            id,
+            unixtime_msec: Some(unixtime),
+            value: payment_value,
+            binding,
+            transaction_data,
+            block_daa_score: accepted_daa_score,
+            network_id: utxo_context.processor().network_id().expect("network expected for transaction record generation"),
+            metadata: None,
+            note: None,
+        }
+    }
+
     pub fn new_outgoing(
         utxo_context: &UtxoContext,
         outgoing_tx: &OutgoingTransaction,
@@ -11,7 +11,7 @@ use waglayla_notify::subscription::{MutationPolicies, UtxosChangedMutationPolicy
 use waglayla_rpc_core::api::ctl::RpcCtl;
 use waglayla_rpc_core::{api::rpc::RpcApi, *};
 use waglayla_rpc_core::{notify::connection::ChannelConnection, RpcResult};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 pub type RpcCoreNotifier = Notifier<Notification, ChannelConnection>;
 
@@ -25,6 +25,9 @@ pub struct RpcCoreMock {
     ctl: RpcCtl,
     core_notifier: Arc<RpcCoreNotifier>,
     _sync_receiver: Receiver<()>,
+    utxos: Mutex<Vec<RpcUtxosByAddressesEntry>>,
+    utxos_requests: Mutex<Vec<Vec<RpcAddress>>>,
+    fee_estimate: Mutex<Option<RpcFeeEstimate>>,
 }
 
 impl RpcCoreMock {
@@ -41,7 +44,14 @@ impl RpcCoreMock {
             policies,
             Some(sync_sender),
         ));
-        Self { core_notifier, _sync_receiver: sync_receiver, ctl: RpcCtl::new() }
+        Self {
+            core_notifier,
+            _sync_receiver: sync_receiver,
+            ctl: RpcCtl::new(),
+            utxos: Default::default(),
+            utxos_requests: Default::default(),
+            fee_estimate: Default::default(),
+        }
     }
 
     pub fn core_notifier(&self) -> Arc<RpcCoreNotifier> {
@@ -72,6 +82,24 @@ impl RpcCoreMock {
     pub fn ctl(&self) -> RpcCtl {
         self.ctl.clone()
     }
+
+    /// Sets the UTXO set served by `get_utxos_by_addresses`
+    #[allow(dead_code)]
+    pub fn set_utxos(&self, utxos: Vec<RpcUtxosByAddressesEntry>) {
+        *self.utxos.lock().unwrap() = utxos;
+    }
+
+    /// Addresses of every `get_utxos_by_addresses` request received so far
+    #[allow(dead_code)]
+    pub fn utxos_requests(&self) -> Vec<Vec<RpcAddress>> {
+        self.utxos_requests.lock().unwrap().clone()
+    }
+
+    /// Sets the fee estimate served by `get_fee_estimate`
+    #[allow(dead_code)]
+    pub fn set_fee_estimate(&self, estimate: RpcFeeEstimate) {
+        self.fee_estimate.lock().unwrap().replace(estimate);
+    }
 }
 
 impl Default for RpcCoreMock {
@@ -151,6 +179,13 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
+    async fn submit_transaction_replacement_call(
+        &self,
+        _request: SubmitTransactionReplacementRequest,
+    ) -> RpcResult<SubmitTransactionReplacementResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
     async fn get_block_call(&self, _request: GetBlockRequest) -> RpcResult<GetBlockResponse> {
         Err(RpcError::NotImplemented)
     }
@@ -204,8 +239,17 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
-    async fn get_utxos_by_addresses_call(&self, _request: GetUtxosByAddressesRequest) -> RpcResult<GetUtxosByAddressesResponse> {
-        Err(RpcError::NotImplemented)
+    async fn get_utxos_by_addresses_call(&self, request: GetUtxosByAddressesRequest) -> RpcResult<GetUtxosByAddressesResponse> {
+        let entries = self
+            .utxos
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|entry| entry.address.as_ref().is_some_and(|address| request.addresses.contains(address)))
+            .cloned()
+            .collect();
+        self.utxos_requests.lock().unwrap().push(request.addresses);
+        Ok(GetUtxosByAddressesResponse::new(entries))
     }
 
     async fn get_sink_blue_score_call(&self, _request: GetSinkBlueScoreRequest) -> RpcResult<GetSinkBlueScoreResponse> {
@@ -245,6 +289,44 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
+    async fn get_fee_estimate_call(&self, _request: GetFeeEstimateRequest) -> RpcResult<GetFeeEstimateResponse> {
+        let estimate = self.fee_estimate.lock().unwrap().clone().ok_or(RpcError::NotImplemented)?;
+        Ok(GetFeeEstimateResponse { estimate })
+    }
+
+    async fn get_transaction_call(&self, _request: GetTransactionRequest) -> RpcResult<GetTransactionResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_transactions_by_addresses_call(
+        &self,
+        _request: GetTransactionsByAddressesRequest,
+    ) -> RpcResult<GetTransactionsByAddressesResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_chain_block_by_daa_score_call(
+        &self,
+        _request: GetChainBlockByDaaScoreRequest,
+    ) -> RpcResult<GetChainBlockByDaaScoreResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_chain_block_by_timestamp_call(
+        &self,
+        _request: GetChainBlockByTimestampRequest,
+    ) -> RpcResult<GetChainBlockByTimestampResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_chain_block_headers_call(&self, _request: GetChainBlockHeadersRequest) -> RpcResult<GetChainBlockHeadersResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_banned_peers_call(&self, _request: GetBannedPeersRequest) -> RpcResult<GetBannedPeersResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
 
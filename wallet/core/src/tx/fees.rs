@@ -3,8 +3,10 @@
 //!
 
 use crate::result::Result;
+use crate::tx::mass::calc_minimum_required_transaction_relay_fee;
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
+use waglayla_rpc_core::RpcFeeEstimate;
 
 /// Transaction fees.  Fees are comprised of 2 values:
 ///
@@ -55,6 +57,62 @@ impl Fees {
             _ => 0,
         }
     }
+
+    /// Creates sender-paid priority fees such that a transaction of the given `mass`
+    /// pays a total of `feerate * mass` sompi. The minimum relay fee is already
+    /// charged by the transaction generator, so only the remainder above it is
+    /// returned as the priority fee.
+    pub fn from_feerate(feerate: f64, mass: u64) -> Self {
+        let total = (feerate.max(0.0) * mass as f64).ceil() as u64;
+        Fees::SenderPays(total.saturating_sub(calc_minimum_required_transaction_relay_fee(mass)))
+    }
+
+    /// Selects fees for a transaction of the given `mass` from the node-provided
+    /// fee estimate (see [`RpcApi::get_fee_estimate`](waglayla_rpc_core::api::rpc::RpcApi::get_fee_estimate)).
+    pub fn from_fee_estimate(estimate: &RpcFeeEstimate, priority: FeeRatePriority, mass: u64) -> Self {
+        Self::from_feerate(priority.feerate(estimate), mass)
+    }
+}
+
+/// Inclusion priority used for automatic fee selection
+/// based on the feerate buckets provided by the node.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeRatePriority {
+    /// Sub-second inclusion
+    Priority,
+    /// Sub-minute inclusion
+    #[default]
+    Normal,
+    /// Sub-hour inclusion
+    Low,
+}
+
+impl FeeRatePriority {
+    /// Returns the feerate (in sompi/gram) matching this priority.
+    pub fn feerate(&self, estimate: &RpcFeeEstimate) -> f64 {
+        match self {
+            FeeRatePriority::Priority => estimate.priority_bucket.feerate,
+            FeeRatePriority::Normal => estimate.normal_buckets.first().unwrap_or(&estimate.priority_bucket).feerate,
+            FeeRatePriority::Low => {
+                estimate.low_buckets.first().or(estimate.normal_buckets.last()).unwrap_or(&estimate.priority_bucket).feerate
+            }
+        }
+    }
+}
+
+impl TryFrom<&str> for FeeRatePriority {
+    type Error = crate::error::Error;
+    fn try_from(priority: &str) -> Result<Self> {
+        match priority.to_lowercase().as_str() {
+            "priority" | "high" => Ok(FeeRatePriority::Priority),
+            "normal" => Ok(FeeRatePriority::Normal),
+            "low" => Ok(FeeRatePriority::Low),
+            _ => Err(crate::error::Error::custom(format!(
+                "invalid fee rate priority '{priority}' (expected 'priority', 'normal' or 'low')"
+            ))),
+        }
+    }
 }
 
 /// This trait converts supplied positive `i64` value as `Exclude` fees
@@ -94,3 +152,26 @@ impl TryFrom<String> for Fees {
         Self::try_from(fee.as_str())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use waglayla_rpc_core::RpcFeerateBucket;
+
+    #[test]
+    fn test_fees_from_fee_estimate() {
+        let bucket = |feerate| RpcFeerateBucket { feerate, estimated_seconds: 0.0 };
+        let estimate = RpcFeeEstimate {
+            priority_bucket: bucket(10.0),
+            normal_buckets: vec![bucket(4.0), bucket(2.0)],
+            low_buckets: vec![bucket(1.0)],
+        };
+
+        assert_eq!(Fees::from_fee_estimate(&estimate, FeeRatePriority::Priority, 2_000).additional(), 18_000);
+        assert_eq!(Fees::from_fee_estimate(&estimate, FeeRatePriority::Normal, 2_000).additional(), 6_000);
+        // The minimum relay fee is covered by the generator
+        assert_eq!(Fees::from_fee_estimate(&estimate, FeeRatePriority::Low, 2_000).additional(), 0);
+        assert_eq!(FeeRatePriority::try_from("HIGH").unwrap(), FeeRatePriority::Priority);
+        assert!(FeeRatePriority::try_from("fast").is_err());
+    }
+}
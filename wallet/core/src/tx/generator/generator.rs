@@ -103,6 +103,8 @@ struct Context {
     utxo_stash: VecDeque<UtxoEntryReference>,
     /// final transaction id
     final_transaction_id: Option<TransactionId>,
+    /// final transaction mass
+    final_transaction_mass: Option<u64>,
     /// signifies that the generator is finished
     /// no more items will be produced in the
     /// iterator or a stream
@@ -396,7 +398,8 @@ impl Generator {
 
         let standard_change_output_mass =
             mass_calculator.calc_mass_for_output(&TransactionOutput::new(0, pay_to_address_script(&change_address)));
-        let signature_mass_per_input = mass_calculator.calc_signature_mass(minimum_signatures);
+        let signature_mass_per_input =
+            mass_calculator.calc_signature_mass(minimum_signatures) + mass_calculator.calc_redeem_script_mass(sig_op_count);
         let final_transaction_outputs_compute_mass = mass_calculator.calc_mass_for_outputs(&final_transaction_outputs);
         let final_transaction_payload = final_transaction_payload.unwrap_or_default();
         let final_transaction_payload_mass = mass_calculator.calc_mass_for_payload(final_transaction_payload.len());
@@ -422,6 +425,7 @@ impl Generator {
             stage: Some(Box::default()),
             utxo_stash: VecDeque::default(),
             final_transaction_id: None,
+            final_transaction_mass: None,
             is_done: false,
         });
 
@@ -988,6 +992,7 @@ impl Generator {
                 );
 
                 context.final_transaction_id = Some(tx.id());
+                context.final_transaction_mass = Some(aggregate_mass);
                 context.number_of_transactions += 1;
 
                 Ok(Some(PendingTransaction::try_new(
@@ -1090,6 +1095,7 @@ impl Generator {
             aggregated_fees: context.aggregate_fees,
             final_transaction_amount: self.final_transaction_value_no_fees(),
             final_transaction_id: context.final_transaction_id,
+            final_transaction_mass: context.final_transaction_mass,
             number_of_generated_transactions: context.number_of_transactions,
         }
     }
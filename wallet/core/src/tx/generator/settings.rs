@@ -7,8 +7,9 @@ use crate::events::Events;
 use crate::imports::*;
 use crate::result::Result;
 use crate::tx::{Fees, PaymentDestination};
-use crate::utxo::{UtxoContext, UtxoEntryReference, UtxoIterator};
+use crate::utxo::{UtxoContext, UtxoEntryId, UtxoEntryReference, UtxoIterator};
 use waglayla_addresses::Address;
+use waglayla_consensus_core::tx::TransactionOutpoint;
 use workflow_core::channel::Multiplexer;
 
 pub struct GeneratorSettings {
@@ -84,7 +85,7 @@ impl GeneratorSettings {
             destination_utxo_context: None,
         };
 
-        Ok(settings)
+        Ok(settings.exclude_utxos(&account.frozen_utxos()))
     }
 
     pub fn try_new_with_context(
@@ -151,4 +152,39 @@ impl GeneratorSettings {
         self.destination_utxo_context = Some(destination_utxo_context.clone());
         self
     }
+
+    /// Prevents the supplied UTXOs (such as UTXOs frozen by the account)
+    /// from being selected by the generator.
+    pub fn exclude_utxos(mut self, outpoints: &[TransactionOutpoint]) -> Self {
+        if !outpoints.is_empty() {
+            let excluded = outpoints.iter().map(|outpoint| UtxoEntryId::from(*outpoint)).collect::<AHashSet<_>>();
+            self.utxo_iterator = Box::new(self.utxo_iterator.filter(move |utxo_entry| !excluded.contains(utxo_entry.id_as_ref())));
+        }
+        self
+    }
+
+    /// Coin control: spend only the supplied UTXOs of the source [`UtxoContext`],
+    /// in the supplied order, instead of selecting UTXOs automatically.
+    /// Explicitly selected UTXOs are spent even if they are frozen.
+    pub fn try_with_utxo_selection(mut self, outpoints: &[TransactionOutpoint]) -> Result<Self> {
+        let utxo_context =
+            self.source_utxo_context.as_ref().ok_or_else(|| Error::custom("UTXO selection requires a source UTXO context"))?;
+
+        let mature = utxo_context.mature_utxos();
+        let mut utxo_entries: Vec<UtxoEntryReference> = Vec::with_capacity(outpoints.len());
+        for outpoint in outpoints {
+            let id = UtxoEntryId::from(*outpoint);
+            if utxo_entries.iter().any(|utxo_entry| utxo_entry.id_as_ref() == &id) {
+                continue;
+            }
+            match mature.iter().find(|utxo_entry| utxo_entry.id_as_ref() == &id) {
+                Some(utxo_entry) => utxo_entries.push(utxo_entry.clone()),
+                None if utxo_context.find(&id).is_some() => return Err(Error::UtxoNotMature(*outpoint)),
+                None => return Err(Error::UtxoNotFound(*outpoint)),
+            }
+        }
+
+        self.utxo_iterator = Box::new(utxo_entries.into_iter());
+        Ok(self)
+    }
 }
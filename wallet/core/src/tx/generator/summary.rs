@@ -20,6 +20,7 @@ pub struct GeneratorSummary {
     pub number_of_generated_transactions: usize,
     pub final_transaction_amount: Option<u64>,
     pub final_transaction_id: Option<TransactionId>,
+    pub final_transaction_mass: Option<u64>,
 }
 
 impl GeneratorSummary {
@@ -50,6 +51,10 @@ impl GeneratorSummary {
     pub fn final_transaction_id(&self) -> Option<TransactionId> {
         self.final_transaction_id
     }
+
+    pub fn final_transaction_mass(&self) -> Option<u64> {
+        self.final_transaction_mass
+    }
 }
 
 impl fmt::Display for GeneratorSummary {
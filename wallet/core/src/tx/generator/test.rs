@@ -2,16 +2,18 @@
 
 use crate::error::Error;
 use crate::result::Result;
-use crate::tx::{Fees, MassCalculator, PaymentDestination};
-use crate::utxo::UtxoEntryReference;
+use crate::tests::RpcCoreMock;
+use crate::tx::{Fees, MassCalculator, PaymentDestination, PaymentOutput};
+use crate::utxo::{UtxoContext, UtxoContextBinding, UtxoEntryReference, UtxoProcessor};
 use crate::{tx::PaymentOutputs, utils::waglayla_to_sompi};
-use waglayla_addresses::Address;
+use waglayla_addresses::{Address, Prefix, Version};
 use waglayla_consensus_core::network::{NetworkId, NetworkType};
-use waglayla_consensus_core::tx::Transaction;
+use waglayla_consensus_core::tx::{Transaction, TransactionId, TransactionOutpoint};
 use rand::prelude::*;
 use std::cell::RefCell;
 use std::fmt::Debug;
 use std::rc::Rc;
+use std::sync::Arc;
 use workflow_log::style;
 
 use super::*;
@@ -418,7 +420,9 @@ where
 pub(crate) fn change_address(network_type: NetworkType) -> Address {
     match network_type {
         NetworkType::Mainnet => Address::try_from("waglayla:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j").unwrap(),
-        NetworkType::Testnet => Address::try_from("waglaylatest:qqz22l98sf8jun72rwh5rqe2tm8lhwtdxdmynrz4ypwak427qed5juktjt7ju").unwrap(),
+        NetworkType::Testnet => {
+            Address::try_from("waglaylatest:qqz22l98sf8jun72rwh5rqe2tm8lhwtdxdmynrz4ypwak427qed5juktjt7ju").unwrap()
+        }
         _ => unreachable!("network type not supported"),
     }
 }
@@ -426,7 +430,9 @@ pub(crate) fn change_address(network_type: NetworkType) -> Address {
 pub(crate) fn output_address(network_type: NetworkType) -> Address {
     match network_type {
         NetworkType::Mainnet => Address::try_from("waglayla:qrd9efkvg3pg34sgp6ztwyv3r569qlc43wa5w8nfs302532dzj47knu04aftm").unwrap(),
-        NetworkType::Testnet => Address::try_from("waglaylatest:qqrewmx4gpuekvk8grenkvj2hp7xt0c35rxgq383f6gy223c4ud5s58ptm6er").unwrap(),
+        NetworkType::Testnet => {
+            Address::try_from("waglaylatest:qqrewmx4gpuekvk8grenkvj2hp7xt0c35rxgq383f6gy223c4ud5s58ptm6er").unwrap()
+        }
         _ => unreachable!("network type not supported"),
     }
 }
@@ -477,11 +483,17 @@ fn test_generator_sweep_two_utxos_with_priority_fees_rejection() -> Result<()> {
 
 #[test]
 fn test_generator_compound_200k_10wala_transactions() -> Result<()> {
-    generator(test_network_id(), &[10.0; 200_000], &[], Fees::sender(Waglayla(5.0)), [(output_address, Waglayla(190_000.0))].as_slice())
-        .unwrap()
-        .harness()
-        .validate()
-        .finalize();
+    generator(
+        test_network_id(),
+        &[10.0; 200_000],
+        &[],
+        Fees::sender(Waglayla(5.0)),
+        [(output_address, Waglayla(190_000.0))].as_slice(),
+    )
+    .unwrap()
+    .harness()
+    .validate()
+    .finalize();
 
     Ok(())
 }
@@ -492,7 +504,11 @@ fn test_generator_compound_100k_random_transactions() -> Result<()> {
     let inputs: Vec<f64> = (0..100_000).map(|_| rng.gen_range(0.001..10.0)).collect();
     let total = inputs.iter().sum::<f64>();
     let outputs = [(output_address, Waglayla(total - 10.0))];
-    generator(test_network_id(), &inputs, &[], Fees::sender(Waglayla(5.0)), outputs.as_slice()).unwrap().harness().validate().finalize();
+    generator(test_network_id(), &inputs, &[], Fees::sender(Waglayla(5.0)), outputs.as_slice())
+        .unwrap()
+        .harness()
+        .validate()
+        .finalize();
 
     Ok(())
 }
@@ -680,3 +696,95 @@ fn test_generator_inputs_903_outputs_2_fees_exclude() -> Result<()> {
 
     Ok(())
 }
+
+/// Creates a UTXO context holding mature UTXOs of the supplied amounts
+async fn make_utxo_context(network_id: NetworkId, values: &[f64]) -> Result<(UtxoContext, Vec<UtxoEntryReference>)> {
+    let processor = UtxoProcessor::new(Some(Arc::new(RpcCoreMock::new()).into()), Some(network_id), None, None);
+    let utxo_context = UtxoContext::new(&processor, UtxoContextBinding::default());
+    let utxo_entries: Vec<UtxoEntryReference> =
+        values.iter().cloned().map(waglayla_to_sompi).map(UtxoEntryReference::simulated).collect();
+    utxo_context.extend_from_scan(utxo_entries.clone(), u64::MAX / 2).await?;
+    Ok((utxo_context, utxo_entries))
+}
+
+fn make_context_generator(
+    utxo_context: &UtxoContext,
+    payment: f64,
+    configure: impl FnOnce(GeneratorSettings) -> Result<GeneratorSettings>,
+) -> Result<Generator> {
+    let prefix = Prefix::from(utxo_context.processor().network_id()?);
+    let destination = PaymentOutput::new(Address::new(prefix, Version::PubKey, &[1; 32]), waglayla_to_sompi(payment));
+    let settings = GeneratorSettings::try_new_with_context(
+        utxo_context.clone(),
+        Address::new(prefix, Version::PubKey, &[2; 32]),
+        1,
+        1,
+        destination.into(),
+        Fees::SenderPays(0),
+        None,
+        None,
+    )?;
+    Generator::try_new(configure(settings)?, None, None)
+}
+
+/// Outpoints spent by all the transactions produced by the generator
+fn spent_outpoints(generator: &Generator) -> Result<Vec<TransactionOutpoint>> {
+    let mut outpoints = vec![];
+    while let Some(pending) = generator.generate_transaction()? {
+        outpoints.extend(pending.transaction().inputs.iter().map(|input| input.previous_outpoint));
+    }
+    Ok(outpoints)
+}
+
+fn outpoint(utxo_entry: &UtxoEntryReference) -> TransactionOutpoint {
+    let id = utxo_entry.id();
+    TransactionOutpoint::new(id.transaction_id, id.index)
+}
+
+#[tokio::test]
+async fn test_generator_frozen_utxos_excluded() -> Result<()> {
+    let (utxo_context, utxo_entries) = make_utxo_context(test_network_id(), &[10.0, 20.0, 30.0]).await?;
+    let frozen = outpoint(&utxo_entries[2]);
+
+    // the frozen UTXO is never selected, even if the payment requires it
+    let generator = make_context_generator(&utxo_context, 25.0, |settings| Ok(settings.exclude_utxos(&[frozen])))?;
+    let spent = spent_outpoints(&generator)?;
+    assert_eq!(spent.len(), 2);
+    assert!(!spent.contains(&frozen));
+
+    let generator = make_context_generator(&utxo_context, 35.0, |settings| Ok(settings.exclude_utxos(&[frozen])))?;
+    assert!(matches!(spent_outpoints(&generator), Err(Error::InsufficientFunds { .. })));
+
+    // without exclusions, all the UTXOs are available
+    let generator = make_context_generator(&utxo_context, 35.0, Ok)?;
+    assert!(spent_outpoints(&generator)?.contains(&frozen));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generator_utxo_selection() -> Result<()> {
+    let (utxo_context, utxo_entries) = make_utxo_context(test_network_id(), &[10.0, 20.0, 30.0]).await?;
+    let outpoints = utxo_entries.iter().map(outpoint).collect::<Vec<_>>();
+    let frozen = outpoints[2];
+
+    // only the selected UTXOs are spent, including frozen ones
+    let selection = [outpoints[0], frozen];
+    let generator =
+        make_context_generator(&utxo_context, 35.0, |settings| settings.exclude_utxos(&[frozen]).try_with_utxo_selection(&selection))?;
+    assert_eq!(spent_outpoints(&generator)?, selection);
+
+    let generator = make_context_generator(&utxo_context, 5.0, |settings| settings.try_with_utxo_selection(&[frozen]))?;
+    assert_eq!(spent_outpoints(&generator)?, vec![frozen]);
+
+    // the selection is not complemented by other UTXOs
+    let generator = make_context_generator(&utxo_context, 45.0, |settings| settings.try_with_utxo_selection(&selection))?;
+    assert!(matches!(spent_outpoints(&generator), Err(Error::InsufficientFunds { .. })));
+
+    // UTXOs missing from the context can not be selected
+    let unknown = TransactionOutpoint::new(TransactionId::from_bytes([1; 32]), 0);
+    let generator = make_context_generator(&utxo_context, 5.0, |settings| settings.try_with_utxo_selection(&[unknown]));
+    assert!(matches!(generator, Err(Error::UtxoNotFound(outpoint)) if outpoint == unknown));
+
+    Ok(())
+}
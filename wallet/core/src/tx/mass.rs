@@ -280,6 +280,17 @@ impl MassCalculator {
         SIGNATURE_SIZE * self.mass_per_tx_byte * minimum_signatures as u64
     }
 
+    /// Mass of the redeem script pushed by multisig inputs spending to `sig_op_count` public keys
+    pub fn calc_redeem_script_mass(&self, sig_op_count: u8) -> u64 {
+        if sig_op_count <= 1 {
+            return 0;
+        }
+        // OP_m, OP_DATA_33 + public key (ECDSA keys being the largest) per key, OP_n, OP_CHECKMULTISIG
+        let redeem_script_size = 34 * sig_op_count as u64 + 3;
+        // the redeem script is pushed with a prefix of up to 3 bytes
+        (redeem_script_size + 3) * self.mass_per_tx_byte
+    }
+
     pub fn calc_signature_mass_for_inputs(&self, number_of_inputs: usize, minimum_signatures: u16) -> u64 {
         let minimum_signatures = std::cmp::max(1, minimum_signatures);
         SIGNATURE_SIZE * self.mass_per_tx_byte * minimum_signatures as u64 * number_of_inputs as u64
@@ -7,9 +7,11 @@ pub mod fees;
 pub mod generator;
 pub mod mass;
 pub mod payment;
+pub mod pskt;
 
 pub use self::consensus::*;
 pub use self::fees::*;
 pub use self::generator::*;
 pub use self::mass::*;
 pub use self::payment::*;
+pub use self::pskt::*;
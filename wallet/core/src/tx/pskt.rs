@@ -0,0 +1,569 @@
+//!
+//! Partially Signed Waglayla Transaction (PSKT) allowing a transaction
+//! to be created, signed and submitted by different wallets.
+//!
+//! A PSKT goes through the following roles:
+//! - **Creator**: builds the PSKT from an unsigned transaction and its UTXO entries
+//!   ([`Pskt::from_pending_transaction`]), attaching the redeem scripts of P2SH inputs.
+//! - **Signer**: adds the signatures of the keys it holds ([`Pskt::sign`]).
+//! - **Combiner**: merges the signatures of PSKTs signed separately ([`Pskt::combine`]).
+//! - **Finalizer**: builds the signature scripts once enough signatures are present
+//!   ([`Pskt::finalize`]), after which the transaction can be extracted and submitted.
+//!
+//! PSKTs are exchanged as a `PSKT` prefixed hex string (see [`Pskt::serialize`]) so
+//! they can be copied between air-gapped and online wallets.
+//!
+
+use crate::imports::*;
+use crate::result::Result;
+use crate::tx::PendingTransaction;
+use waglayla_consensus_core::hashing::sighash::{calc_ecdsa_signature_hash, calc_schnorr_signature_hash, SigHashReusedValues};
+use waglayla_consensus_core::hashing::sighash_type::{SigHashType, SIG_HASH_ALL};
+use waglayla_consensus_core::subnets::SubnetworkId;
+use waglayla_consensus_core::tx::{
+    SignableTransaction, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry, VerifiableTransaction,
+};
+use waglayla_rpc_core::RpcTransactionId;
+use waglayla_txscript::opcodes::codes::{OpCheckMultiSig, OpCheckMultiSigECDSA, OpData65, OpTrue};
+use waglayla_txscript::script_class::ScriptClass;
+use waglayla_txscript::{pay_to_script_hash_script, pay_to_script_hash_signature_script};
+
+/// Prefix of serialized PSKTs
+pub const PSKT_PREFIX: &str = "PSKT";
+/// Version of the PSKT format
+pub const PSKT_VERSION: u16 = 0;
+
+/// Signature of an input, keyed by the public key (x-only for schnorr, compressed for ECDSA) that produced it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PsktSignature {
+    #[serde(with = "waglayla_utils::serde_bytes")]
+    pub public_key: Vec<u8>,
+    /// Signature followed by its sighash type byte
+    #[serde(with = "waglayla_utils::serde_bytes")]
+    pub signature: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PsktInput {
+    pub previous_outpoint: TransactionOutpoint,
+    pub sequence: u64,
+    pub sig_op_count: u8,
+    pub utxo_entry: UtxoEntry,
+    pub sighash_type: u8,
+    /// Redeem script of P2SH inputs, empty otherwise
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "waglayla_utils::serde_bytes")]
+    pub redeem_script: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub partial_signatures: Vec<PsktSignature>,
+    /// Signature script set by the finalizer
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "waglayla_utils::serde_bytes")]
+    pub final_signature_script: Vec<u8>,
+}
+
+impl PsktInput {
+    pub fn is_finalized(&self) -> bool {
+        !self.final_signature_script.is_empty()
+    }
+
+    /// Public keys allowed to sign the input along with the amount of required
+    /// signatures and whether they are ECDSA keys
+    fn signing_keys(&self) -> Result<(Vec<Vec<u8>>, usize, bool)> {
+        let script = self.utxo_entry.script_public_key.script();
+        if ScriptClass::is_pay_to_pubkey(script) {
+            Ok((vec![script[1..33].to_vec()], 1, false))
+        } else if ScriptClass::is_pay_to_pubkey_ecdsa(script) {
+            Ok((vec![script[1..34].to_vec()], 1, true))
+        } else if ScriptClass::is_pay_to_script_hash(script) {
+            if self.redeem_script.is_empty() {
+                return Err(Error::InvalidPskt(format!("missing redeem script of P2SH input {}", self.previous_outpoint)));
+            }
+            parse_multisig_redeem_script(&self.redeem_script)
+                .ok_or_else(|| Error::InvalidPskt(format!("unsupported redeem script of input {}", self.previous_outpoint)))
+        } else {
+            Err(Error::InvalidPskt(format!("unsupported script public key of input {}", self.previous_outpoint)))
+        }
+    }
+
+    /// Returns the amount of signatures collected and the amount required to finalize the input
+    pub fn signature_status(&self) -> Result<(usize, usize)> {
+        let (_, required, _) = self.signing_keys()?;
+        Ok((self.partial_signatures.len(), required))
+    }
+
+    /// Returns true if `signature` is a valid signature of this input, being the input at `index` of `tx`,
+    /// by one of its signing keys
+    fn verify_signature(
+        &self,
+        tx: &impl VerifiableTransaction,
+        index: usize,
+        signature: &PsktSignature,
+        reused_values: &mut SigHashReusedValues,
+    ) -> Result<bool> {
+        let (public_keys, _, ecdsa) = self.signing_keys()?;
+        let Some((&sighash_type, signature_bytes)) = signature.signature.split_last() else {
+            return Ok(false);
+        };
+        if signature.signature.len() != 65 || sighash_type != self.sighash_type || !public_keys.contains(&signature.public_key) {
+            return Ok(false);
+        }
+
+        let sighash_type = SigHashType::from_u8(sighash_type).map_err(|err| Error::InvalidPskt(err.to_string()))?;
+        let valid = if ecdsa {
+            let sig_hash = calc_ecdsa_signature_hash(tx, index, sighash_type, reused_values);
+            let msg = secp256k1::Message::from_digest_slice(sig_hash.as_bytes().as_slice())?;
+            match (secp256k1::ecdsa::Signature::from_compact(signature_bytes), secp256k1::PublicKey::from_slice(&signature.public_key))
+            {
+                (Ok(sig), Ok(public_key)) => secp256k1::SECP256K1.verify_ecdsa(&msg, &sig, &public_key).is_ok(),
+                _ => false,
+            }
+        } else {
+            let sig_hash = calc_schnorr_signature_hash(tx, index, sighash_type, reused_values);
+            let msg = secp256k1::Message::from_digest_slice(sig_hash.as_bytes().as_slice())?;
+            match (
+                secp256k1::schnorr::Signature::from_slice(signature_bytes),
+                secp256k1::XOnlyPublicKey::from_slice(&signature.public_key),
+            ) {
+                (Ok(sig), Ok(public_key)) => secp256k1::SECP256K1.verify_schnorr(&sig, &msg, &public_key).is_ok(),
+                _ => false,
+            }
+        };
+        Ok(valid)
+    }
+
+    fn insert_signature(&mut self, signature: PsktSignature) {
+        match self.partial_signatures.iter_mut().find(|existing| existing.public_key == signature.public_key) {
+            Some(existing) => *existing = signature,
+            None => self.partial_signatures.push(signature),
+        }
+    }
+}
+
+/// Partially Signed Waglayla Transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pskt {
+    pub version: u16,
+    pub tx_version: u16,
+    pub inputs: Vec<PsktInput>,
+    pub outputs: Vec<TransactionOutput>,
+    pub lock_time: u64,
+    pub subnetwork_id: SubnetworkId,
+    pub gas: u64,
+    #[serde(with = "waglayla_utils::serde_bytes")]
+    pub payload: Vec<u8>,
+}
+
+impl Pskt {
+    /// Creates a PSKT from a transaction and its populated UTXO entries, all inputs using [`SIG_HASH_ALL`].
+    /// Signature scripts already present in the transaction are kept as finalized.
+    pub fn from_signable_transaction(signable_tx: &SignableTransaction) -> Result<Self> {
+        let tx = signable_tx.tx.as_ref();
+        let inputs = tx
+            .inputs
+            .iter()
+            .zip(signable_tx.entries.iter())
+            .map(|(input, entry)| {
+                let utxo_entry = entry
+                    .clone()
+                    .ok_or_else(|| Error::InvalidPskt(format!("missing UTXO entry of input {}", input.previous_outpoint)))?;
+                Ok(PsktInput {
+                    previous_outpoint: input.previous_outpoint,
+                    sequence: input.sequence,
+                    sig_op_count: input.sig_op_count,
+                    utxo_entry,
+                    sighash_type: SIG_HASH_ALL.to_u8(),
+                    redeem_script: vec![],
+                    partial_signatures: vec![],
+                    final_signature_script: input.signature_script.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            version: PSKT_VERSION,
+            tx_version: tx.version,
+            inputs,
+            outputs: tx.outputs.clone(),
+            lock_time: tx.lock_time,
+            subnetwork_id: tx.subnetwork_id.clone(),
+            gas: tx.gas,
+            payload: tx.payload.clone(),
+        })
+    }
+
+    pub fn from_pending_transaction(pending_transaction: &PendingTransaction) -> Result<Self> {
+        Self::from_signable_transaction(&pending_transaction.signable_transaction())
+    }
+
+    /// Sets the redeem script of the P2SH input at `index`, checking that it matches the hash of the UTXO script
+    pub fn set_redeem_script(&mut self, index: usize, redeem_script: Vec<u8>) -> Result<()> {
+        let input = self.inputs.get_mut(index).ok_or_else(|| Error::InvalidPskt(format!("no input at index {index}")))?;
+        if pay_to_script_hash_script(&redeem_script) != input.utxo_entry.script_public_key {
+            return Err(Error::InvalidPskt(format!("redeem script does not match the UTXO of input {index}")));
+        }
+        input.redeem_script = redeem_script;
+        Ok(())
+    }
+
+    /// Id of the transaction, which does not depend on the signature scripts
+    pub fn id(&self) -> TransactionId {
+        self.to_transaction_unchecked().id()
+    }
+
+    pub fn is_finalized(&self) -> bool {
+        self.inputs.iter().all(PsktInput::is_finalized)
+    }
+
+    /// Signs every non-finalized input that one of the `private_keys` is allowed to sign,
+    /// returning the amount of signatures added.
+    pub fn sign(&mut self, private_keys: &[[u8; 32]]) -> Result<usize> {
+        let keypairs = private_keys
+            .iter()
+            .map(|key| secp256k1::Keypair::from_seckey_slice(secp256k1::SECP256K1, key))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let signable_tx = self.to_signable_transaction();
+        let verifiable_tx = signable_tx.as_verifiable();
+        let mut reused_values = SigHashReusedValues::new();
+        let mut signatures = 0;
+        for (index, input) in self.inputs.iter_mut().enumerate() {
+            if input.is_finalized() {
+                continue;
+            }
+            let (public_keys, _, ecdsa) = input.signing_keys()?;
+            let sighash_type = SigHashType::from_u8(input.sighash_type).map_err(|err| Error::InvalidPskt(err.to_string()))?;
+            for keypair in keypairs.iter() {
+                let public_key =
+                    if ecdsa { keypair.public_key().serialize().to_vec() } else { keypair.x_only_public_key().0.serialize().to_vec() };
+                if !public_keys.contains(&public_key) {
+                    continue;
+                }
+
+                let signature = if ecdsa {
+                    let sig_hash = calc_ecdsa_signature_hash(&verifiable_tx, index, sighash_type, &mut reused_values);
+                    let msg = secp256k1::Message::from_digest_slice(sig_hash.as_bytes().as_slice())?;
+                    keypair.secret_key().sign_ecdsa(msg).serialize_compact().to_vec()
+                } else {
+                    let sig_hash = calc_schnorr_signature_hash(&verifiable_tx, index, sighash_type, &mut reused_values);
+                    let msg = secp256k1::Message::from_digest_slice(sig_hash.as_bytes().as_slice())?;
+                    keypair.sign_schnorr(msg).as_ref().to_vec()
+                };
+                let signature = signature.into_iter().chain([sighash_type.to_u8()]).collect();
+                input.insert_signature(PsktSignature { public_key, signature });
+                signatures += 1;
+            }
+        }
+
+        Ok(signatures)
+    }
+
+    /// Merges the signatures and redeem scripts of `other`, which must spend the same transaction. Every redeem script
+    /// and signature of `other` is verified before `self` is modified, a valid signature already held for the same
+    /// public key being kept. Final signature scripts of `other` are never adopted, inputs being finalized locally.
+    pub fn combine(&mut self, other: &Pskt) -> Result<()> {
+        if self.id() != other.id() || self.inputs.len() != other.inputs.len() {
+            return Err(Error::PsktMismatch);
+        }
+
+        let signable_tx = self.to_signable_transaction();
+        let verifiable_tx = signable_tx.as_verifiable();
+        let mut reused_values = SigHashReusedValues::new();
+        let mut inputs = self.inputs.clone();
+        for (index, (input, other)) in inputs.iter_mut().zip(other.inputs.iter()).enumerate() {
+            if input.utxo_entry != other.utxo_entry || input.sighash_type != other.sighash_type {
+                return Err(Error::PsktMismatch);
+            }
+            if input.is_finalized() {
+                continue;
+            }
+            if input.redeem_script.is_empty() && !other.redeem_script.is_empty() {
+                if pay_to_script_hash_script(&other.redeem_script) != input.utxo_entry.script_public_key {
+                    return Err(Error::InvalidPskt(format!("redeem script does not match the UTXO of input {index}")));
+                }
+                input.redeem_script = other.redeem_script.clone();
+            }
+            for signature in other.partial_signatures.iter() {
+                if !input.verify_signature(&verifiable_tx, index, signature, &mut reused_values)? {
+                    return Err(Error::InvalidPskt(format!("invalid signature of input {index}")));
+                }
+                match input.partial_signatures.iter().position(|existing| existing.public_key == signature.public_key) {
+                    Some(position)
+                        if input.verify_signature(
+                            &verifiable_tx,
+                            index,
+                            &input.partial_signatures[position],
+                            &mut reused_values,
+                        )? => {}
+                    Some(position) => input.partial_signatures[position] = signature.clone(),
+                    None => input.partial_signatures.push(signature.clone()),
+                }
+            }
+        }
+
+        self.inputs = inputs;
+        Ok(())
+    }
+
+    /// Builds the signature script of every input holding enough signatures, failing if any input can't be finalized
+    pub fn finalize(&mut self) -> Result<()> {
+        for (index, input) in self.inputs.iter_mut().enumerate() {
+            if input.is_finalized() {
+                continue;
+            }
+
+            let (public_keys, required, _) = input.signing_keys()?;
+            // signatures must be ordered like the public keys of the script
+            let signatures = public_keys
+                .iter()
+                .filter_map(|public_key| input.partial_signatures.iter().find(|signature| &signature.public_key == public_key))
+                .take(required)
+                .collect::<Vec<_>>();
+            if signatures.len() < required {
+                return Err(Error::PsktMissingSignatures { index, signatures: signatures.len(), required });
+            }
+            // every signature is pushed as a 65 bytes data element
+            if signatures.iter().any(|signature| signature.signature.len() != 65) {
+                return Err(Error::InvalidPskt(format!("invalid signature length in input {index}")));
+            }
+
+            let signature_script = signatures
+                .into_iter()
+                .flat_map(|signature| std::iter::once(OpData65).chain(signature.signature.iter().copied()))
+                .collect::<Vec<_>>();
+            input.final_signature_script = if input.redeem_script.is_empty() {
+                signature_script
+            } else {
+                pay_to_script_hash_signature_script(input.redeem_script.clone(), signature_script)?
+            };
+            input.partial_signatures.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Extracts the transaction of a finalized PSKT
+    pub fn to_transaction(&self) -> Result<Transaction> {
+        if !self.is_finalized() {
+            return Err(Error::PsktNotFinalized);
+        }
+        Ok(self.to_transaction_unchecked())
+    }
+
+    /// Returns the transaction with its UTXO entries, inputs that are not finalized having empty signature scripts
+    pub fn to_signable_transaction(&self) -> SignableTransaction {
+        let entries = self.inputs.iter().map(|input| input.utxo_entry.clone()).collect();
+        SignableTransaction::with_entries(self.to_transaction_unchecked(), entries)
+    }
+
+    fn to_transaction_unchecked(&self) -> Transaction {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|input| {
+                TransactionInput::new(
+                    input.previous_outpoint,
+                    input.final_signature_script.clone(),
+                    input.sequence,
+                    input.sig_op_count,
+                )
+            })
+            .collect();
+        Transaction::new(
+            self.tx_version,
+            inputs,
+            self.outputs.clone(),
+            self.lock_time,
+            self.subnetwork_id.clone(),
+            self.gas,
+            self.payload.clone(),
+        )
+    }
+
+    /// Submits the transaction of a finalized PSKT on the supplied rpc
+    pub async fn submit(&self, rpc: &Arc<DynRpcApi>) -> Result<RpcTransactionId> {
+        let transaction = self.to_transaction()?;
+        Ok(rpc.submit_transaction((&transaction).into(), false).await?)
+    }
+
+    /// Serializes the PSKT to a JSON string
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let pskt: Self = serde_json::from_str(json)?;
+        if pskt.version != PSKT_VERSION {
+            return Err(Error::InvalidPskt(format!("unsupported version {}", pskt.version)));
+        }
+        Ok(pskt)
+    }
+
+    /// Serializes the PSKT to a [`PSKT_PREFIX`] prefixed hex string of its JSON representation
+    pub fn serialize(&self) -> Result<String> {
+        Ok(format!("{PSKT_PREFIX}{}", self.to_json()?.as_bytes().to_hex()))
+    }
+
+    pub fn deserialize(text: &str) -> Result<Self> {
+        let hex = text.trim().strip_prefix(PSKT_PREFIX).ok_or_else(|| Error::InvalidPskt(format!("missing {PSKT_PREFIX} prefix")))?;
+        let json = Vec::<u8>::from_hex(hex).map_err(|_| Error::InvalidPskt("invalid hex encoding".to_string()))?;
+        Self::from_json(std::str::from_utf8(&json)?)
+    }
+}
+
+impl TryFrom<&PendingTransaction> for Pskt {
+    type Error = Error;
+    fn try_from(pending_transaction: &PendingTransaction) -> Result<Self> {
+        Self::from_pending_transaction(pending_transaction)
+    }
+}
+
+/// Parses a standard multisig redeem script into its public keys,
+/// the amount of required signatures and whether the keys are ECDSA keys
+fn parse_multisig_redeem_script(script: &[u8]) -> Option<(Vec<Vec<u8>>, usize, bool)> {
+    let small_int = |op: u8| (OpTrue..=OpTrue + 15).contains(&op).then(|| (op - OpTrue + 1) as usize);
+
+    let (&op_required, mut rest) = script.split_first()?;
+    let required = small_int(op_required)?;
+    let ecdsa = *script.last()? == OpCheckMultiSigECDSA;
+    if !ecdsa && *script.last()? != OpCheckMultiSig {
+        return None;
+    }
+    let key_size = if ecdsa { 33 } else { 32 };
+
+    let mut public_keys = vec![];
+    while rest.len() > 2 {
+        let (&len, tail) = rest.split_first()?;
+        if len as usize != key_size || tail.len() < key_size {
+            return None;
+        }
+        public_keys.push(tail[..key_size].to_vec());
+        rest = &tail[key_size..];
+    }
+
+    let count = small_int(*rest.first()?)?;
+    (rest.len() == 2 && count == public_keys.len() && required <= count).then_some((public_keys, required, ecdsa))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use waglayla_consensus_core::subnets::SUBNETWORK_ID_NATIVE;
+    use waglayla_consensus_core::tx::MutableTransaction;
+    use waglayla_txscript::caches::Cache;
+    use waglayla_txscript::{multisig_redeem_script, pay_to_address_script, TxScriptEngine};
+
+    fn keys() -> Vec<[u8; 32]> {
+        (1..=3u8).map(|i| [i; 32]).collect()
+    }
+
+    fn xonly(key: &[u8; 32]) -> [u8; 32] {
+        secp256k1::Keypair::from_seckey_slice(secp256k1::SECP256K1, key).unwrap().x_only_public_key().0.serialize()
+    }
+
+    fn create(script_public_key: ScriptPublicKey, sig_op_count: u8) -> Pskt {
+        let outpoint = TransactionOutpoint::new(TransactionId::from_bytes([7; 32]), 0);
+        let input = TransactionInput::new(outpoint, vec![], 0, sig_op_count);
+        let output = TransactionOutput::new(900, ScriptPublicKey::from_vec(0, vec![0x51]));
+        let tx = Transaction::new(0, vec![input], vec![output], 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+        let entry = UtxoEntry::new(1000, script_public_key, 0, false);
+        Pskt::from_signable_transaction(&MutableTransaction::with_entries(tx, vec![entry])).unwrap()
+    }
+
+    fn verify(pskt: &Pskt) -> bool {
+        let tx = MutableTransaction::with_entries(pskt.to_transaction().unwrap(), vec![pskt.inputs[0].utxo_entry.clone()]);
+        let tx = tx.as_verifiable();
+        let (input, entry) = tx.populated_inputs().next().unwrap();
+        let cache = Cache::new(10);
+        let mut reused_values = SigHashReusedValues::new();
+        let mut engine = TxScriptEngine::from_transaction_input(&tx, input, 0, entry, &mut reused_values, &cache).unwrap();
+        engine.execute().is_ok()
+    }
+
+    #[test]
+    fn test_pskt_p2pk() {
+        let keys = keys();
+        let address = Address::new(Prefix::Testnet, waglayla_addresses::Version::PubKey, &xonly(&keys[0]));
+        let mut pskt = create(pay_to_address_script(&address), 1);
+
+        assert_eq!(pskt.sign(&keys[1..]).unwrap(), 0);
+        assert!(matches!(pskt.finalize(), Err(Error::PsktMissingSignatures { index: 0, signatures: 0, required: 1 })));
+        assert_eq!(pskt.sign(&keys).unwrap(), 1);
+        pskt.finalize().unwrap();
+        assert!(verify(&pskt));
+    }
+
+    #[test]
+    fn test_pskt_multisig() {
+        let keys = keys();
+        let redeem_script = multisig_redeem_script(keys.iter().map(xonly), 2).unwrap();
+        assert_eq!(
+            parse_multisig_redeem_script(&redeem_script).unwrap(),
+            (keys.iter().map(|k| xonly(k).to_vec()).collect(), 2, false)
+        );
+
+        // The creator attaches the redeem script and passes the PSKT to the cosigners
+        let mut pskt = create(pay_to_script_hash_script(&redeem_script), 3);
+        assert!(pskt.set_redeem_script(0, vec![0x51]).is_err());
+        pskt.set_redeem_script(0, redeem_script.clone()).unwrap();
+        let pskt = Pskt::deserialize(&pskt.serialize().unwrap()).unwrap();
+
+        // Cosigners sign separately, in any order relative to the script keys
+        let mut first = pskt.clone();
+        assert_eq!(first.sign(&keys[2..3]).unwrap(), 1);
+        let mut second = pskt.clone();
+        assert_eq!(second.sign(&keys[0..1]).unwrap(), 1);
+        assert!(matches!(first.clone().finalize(), Err(Error::PsktMissingSignatures { signatures: 1, required: 2, .. })));
+
+        first.combine(&second).unwrap();
+        assert_eq!(first.inputs[0].signature_status().unwrap(), (2, 2));
+        assert!(matches!(first.to_transaction(), Err(Error::PsktNotFinalized)));
+
+        // Invalid signatures are refused while a valid signature held for a key is kept
+        let mut tampered = second.clone();
+        tampered.inputs[0].partial_signatures[0].signature[0] ^= 1;
+        assert!(matches!(first.clone().combine(&tampered), Err(Error::InvalidPskt(_))));
+        let mut foreign = pskt.clone();
+        assert_eq!(foreign.sign(&keys[1..2]).unwrap(), 1);
+        foreign.inputs[0].partial_signatures[0].public_key = xonly(&[9; 32]).to_vec();
+        assert!(matches!(first.clone().combine(&foreign), Err(Error::InvalidPskt(_))));
+        let mut kept = second.clone();
+        kept.combine(&first).unwrap();
+        assert_eq!(kept.inputs[0].partial_signatures, first.inputs[0].partial_signatures.iter().rev().cloned().collect::<Vec<_>>());
+        let mut repaired = tampered.clone();
+        repaired.combine(&second).unwrap();
+        assert_eq!(repaired.inputs[0].partial_signatures, second.inputs[0].partial_signatures);
+
+        // Redeem scripts are only adopted if they match the UTXO, and nothing is merged when any input is refused
+        let unscripted = create(pay_to_script_hash_script(&redeem_script), 3);
+        let mut wrong_script = first.clone();
+        wrong_script.inputs[0].redeem_script = vec![0x51];
+        let mut target = unscripted.clone();
+        assert!(matches!(target.combine(&wrong_script), Err(Error::InvalidPskt(_))));
+        assert!(matches!(target.combine(&tampered), Err(Error::InvalidPskt(_))));
+        assert_eq!(target, unscripted);
+        target.combine(&first).unwrap();
+        assert_eq!(target.inputs[0], first.inputs[0]);
+
+        // Final signature scripts are never adopted from another PSKT
+        let mut forged = pskt.clone();
+        forged.inputs[0].final_signature_script = vec![0x51];
+        let mut unfinalized = second.clone();
+        unfinalized.combine(&forged).unwrap();
+        assert_eq!(unfinalized, second);
+
+        // Signatures which can't be pushed as 65 bytes are refused
+        let mut truncated = first.clone();
+        truncated.inputs[0].partial_signatures[0].signature.pop();
+        assert!(matches!(truncated.finalize(), Err(Error::InvalidPskt(_))));
+
+        first.finalize().unwrap();
+        assert!(verify(&first));
+
+        // PSKTs of other transactions can't be combined
+        let mut other = pskt.clone();
+        other.lock_time = 1;
+        assert!(matches!(other.combine(&second), Err(Error::PsktMismatch)));
+    }
+}
@@ -206,6 +206,19 @@ impl UtxoContext {
         self.context().pending.len()
     }
 
+    pub fn mature_utxos(&self) -> Vec<UtxoEntryReference> {
+        self.context().mature.clone()
+    }
+
+    pub fn pending_utxos(&self) -> Vec<UtxoEntryReference> {
+        self.context().pending.values().cloned().collect()
+    }
+
+    /// Returns the UTXO entry with the supplied id if it is held by this context
+    pub fn find(&self, id: &UtxoEntryId) -> Option<UtxoEntryReference> {
+        self.context().map.get(id).cloned()
+    }
+
     pub fn balance(&self) -> Option<Balance> {
         self.context().balance.clone()
     }
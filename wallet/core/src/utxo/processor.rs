@@ -719,6 +719,10 @@ pub(crate) mod mock {
             self.inner.is_connected.store(connected, Ordering::SeqCst);
         }
 
+        pub async fn mock_register_notification_listener(&self) -> Result<()> {
+            self.register_notification_listener().await
+        }
+
         // pub fn mock_set_daa_score(&self, connected : bool) {
         //     self.inner.is_connected.store(connected, Ordering::SeqCst);
         // }
@@ -1,17 +1,26 @@
 //!
 //! Address scanner implementation, responsible for
 //! aggregating UTXOs from multiple addresses and
-//! building corresponding balances.
+//! building corresponding balances. If the node
+//! maintains an address index, the transaction
+//! history of the scanned addresses is rebuilt
+//! as well.
 //!
 
 use crate::derivation::AddressManager;
 use crate::imports::*;
+use crate::storage::TransactionRecord;
+use waglayla_consensus_core::tx::Transaction;
+use waglayla_rpc_core::RpcAddressTransaction;
 use crate::utxo::balance::AtomicBalance;
 use crate::utxo::{UtxoContext, UtxoEntryReference, UtxoEntryReferenceExtension};
 use std::cmp::max;
 
 pub const DEFAULT_WINDOW_SIZE: usize = 8;
 
+/// Number of transactions requested per `get_transactions_by_addresses()` call
+pub const HISTORY_PAGE_SIZE: u32 = 1_000;
+
 #[derive(Default, Clone, Copy)]
 pub enum ScanExtent {
     /// Scan until an empty range is found
@@ -69,6 +78,8 @@ impl Scan {
 
         let mut cursor: u32 = 0;
         let mut last_address_index = address_manager.index();
+        // the history of all scanned addresses, or `None` if the node does not maintain an address index
+        let mut history = Some(Vec::new());
 
         'scan: loop {
             // scan first up to address index, then in window chunks
@@ -84,6 +95,29 @@ impl Scan {
             // addresses used before we start interacting with them.
             utxo_context.register_addresses(&addresses).await?;
 
+            // an address with a spent history but no UTXOs left is used as well,
+            // so the window history is fetched to locate the last used address
+            let window_history = match history {
+                Some(_) => self.fetch_history(addresses.clone(), utxo_context).await,
+                None => None,
+            };
+            let window_used = match (&mut history, window_history) {
+                (Some(history), Some(window_history)) => {
+                    for transaction in window_history.iter() {
+                        if let Some(address_index) = address_manager.inner().address_to_index_map.get(&transaction.address) {
+                            last_address_index = last_address_index.max(*address_index);
+                        }
+                    }
+                    let window_used = !window_history.is_empty();
+                    history.extend(window_history);
+                    window_used
+                }
+                _ => {
+                    history = None;
+                    false
+                }
+            };
+
             let ts = Instant::now();
             let resp = utxo_context.processor().rpc_api().get_utxos_by_addresses(addresses).await?;
             let elapsed_msec = ts.elapsed().as_secs_f32();
@@ -119,7 +153,7 @@ impl Scan {
                 utxo_context.extend_from_scan(refs, self.current_daa_score).await?;
 
                 self.balance.add(balance);
-            } else {
+            } else if !window_used {
                 match &extent {
                     ScanExtent::EmptyWindow => {
                         if cursor > last_address_index + window_size {
@@ -139,6 +173,11 @@ impl Scan {
         // update address manager with the last used index
         address_manager.set_index(last_address_index)?;
 
+        // the history covers the full scanned range, including the trailing empty window
+        if let Some(history) = history {
+            self.process_history(history, utxo_context).await?;
+        }
+
         Ok(())
     }
 
@@ -147,7 +186,7 @@ impl Scan {
         let address_vec = address_set.iter().cloned().collect::<Vec<_>>();
 
         utxo_context.register_addresses(&address_vec).await?;
-        let resp = utxo_context.processor().rpc_api().get_utxos_by_addresses(address_vec).await?;
+        let resp = utxo_context.processor().rpc_api().get_utxos_by_addresses(address_vec.clone()).await?;
         let refs: Vec<UtxoEntryReference> = resp.into_iter().map(UtxoEntryReference::from).collect();
 
         let balance: Balance = refs.iter().fold(Balance::default(), |mut balance, r| {
@@ -167,6 +206,105 @@ impl Scan {
             self.balance.add(balance);
         }
 
+        self.scan_history(address_vec, utxo_context).await?;
+
         Ok(())
     }
+
+    /// Rebuilds the transaction records of the given addresses from the node address index,
+    /// covering the history retained by the node (above its pruning point).
+    ///
+    /// If the node does not run with `--addressindex`, no history is rebuilt.
+    pub async fn scan_history(&self, addresses: Vec<Address>, utxo_context: &UtxoContext) -> Result<()> {
+        if let Some(history) = self.fetch_history(addresses, utxo_context).await {
+            self.process_history(history, utxo_context).await?;
+        }
+        Ok(())
+    }
+
+    /// Fetches all the pages of the history of the given addresses from the node address index.
+    ///
+    /// Returns `None` if the node does not provide address history.
+    async fn fetch_history(&self, addresses: Vec<Address>, utxo_context: &UtxoContext) -> Option<Vec<RpcAddressTransaction>> {
+        let mut history = Vec::new();
+        let mut from_daa_score = 0;
+        loop {
+            let resp = match utxo_context
+                .processor()
+                .rpc_api()
+                .get_transactions_by_addresses(addresses.clone(), from_daa_score, HISTORY_PAGE_SIZE)
+                .await
+            {
+                Ok(resp) => resp,
+                Err(err) => {
+                    // history is a best effort addition to the UTXO scan
+                    log_info!("address history is unavailable: {err}");
+                    return None;
+                }
+            };
+            history.extend(resp.transactions);
+
+            match resp.next_daa_score {
+                Some(next_daa_score) => from_daa_score = next_daa_score,
+                None => break,
+            }
+            yield_executor().await;
+        }
+        Some(history)
+    }
+
+    /// Produces a transaction record for each transaction of the history.
+    ///
+    /// A transaction spending from the addresses produces an outgoing record, its outputs paying back to the
+    /// addresses being accounted as change. Since the address index does not hold the transactions themselves,
+    /// these are fetched from the node transaction index and, if it is unavailable, only the outputs paying to
+    /// the addresses are recorded. Any other transaction produces an external record for the outputs it pays to
+    /// the addresses. Records already present in the wallet storage are skipped by the discovery handler.
+    async fn process_history(&self, history: Vec<RpcAddressTransaction>, utxo_context: &UtxoContext) -> Result<()> {
+        // a transaction touching several addresses is reported once per address
+        let mut transactions: HashMap<TransactionId, (u64, Vec<UtxoEntryReference>, Vec<UtxoEntryReference>)> = HashMap::new();
+        for transaction in history {
+            let (_, received, spent) = transactions
+                .entry(transaction.transaction_id)
+                .or_insert_with(|| (transaction.accepting_block_daa_score, Vec::new(), Vec::new()));
+            received.extend(transaction.received.into_iter().map(UtxoEntryReference::from));
+            spent.extend(transaction.spent.into_iter().map(UtxoEntryReference::from));
+        }
+
+        let mut transaction_index_available = true;
+        for (id, (accepting_block_daa_score, received, spent)) in transactions {
+            if !spent.is_empty() && transaction_index_available {
+                match self.fetch_transaction(id, utxo_context).await {
+                    Ok(transaction) => {
+                        let record = TransactionRecord::new_external_outgoing(
+                            utxo_context,
+                            transaction,
+                            accepting_block_daa_score,
+                            &spent,
+                            &received,
+                        );
+                        utxo_context.processor().handle_discovery(record).await?;
+                        continue;
+                    }
+                    Err(err) => {
+                        log_info!("outgoing history is unavailable: {err}");
+                        transaction_index_available = false;
+                    }
+                }
+            }
+            if !received.is_empty() {
+                let record = TransactionRecord::new_external(utxo_context, id, &received);
+                utxo_context.processor().handle_discovery(record).await?;
+            }
+            yield_executor().await;
+        }
+
+        Ok(())
+    }
+
+    /// Fetches a transaction from the node transaction index
+    async fn fetch_transaction(&self, id: TransactionId, utxo_context: &UtxoContext) -> Result<Transaction> {
+        let resp = utxo_context.processor().rpc_api().get_transaction(id).await?;
+        Transaction::try_from(&resp.transaction).map_err(|err| Error::custom(format!("invalid transaction {id}: {err}")))
+    }
 }
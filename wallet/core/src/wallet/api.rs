@@ -8,6 +8,7 @@ use crate::result::Result;
 use crate::storage::interface::TransactionRangeResult;
 use crate::storage::Binding;
 use crate::tx::Fees;
+use waglayla_consensus_core::tx::TransactionOutpoint;
 use workflow_core::channel::Receiver;
 
 #[async_trait]
@@ -343,13 +344,22 @@ impl WalletApi for super::Wallet {
     }
 
     async fn accounts_send_call(self: Arc<Self>, request: AccountsSendRequest) -> Result<AccountsSendResponse> {
-        let AccountsSendRequest { account_id, wallet_secret, payment_secret, destination, priority_fee_sompi, payload } = request;
+        let AccountsSendRequest {
+            account_id,
+            wallet_secret,
+            payment_secret,
+            destination,
+            priority_fee_sompi,
+            payload,
+            utxo_selection,
+        } = request;
 
         let account = self.get_account_by_id(&account_id).await?.ok_or(Error::AccountNotFound(account_id))?;
 
         let abortable = Abortable::new();
-        let (generator_summary, transaction_ids) =
-            account.send(destination, priority_fee_sompi, payload, wallet_secret, payment_secret, &abortable, None).await?;
+        let (generator_summary, transaction_ids) = account
+            .send(destination, priority_fee_sompi, payload, utxo_selection, wallet_secret, payment_secret, &abortable, None)
+            .await?;
 
         Ok(AccountsSendResponse { generator_summary, transaction_ids })
     }
@@ -380,7 +390,7 @@ impl WalletApi for super::Wallet {
 
         account.scan(window_size, extent).await?;
 
-        Ok(AccountsScanResponse { })
+        Ok(AccountsScanResponse {})
     }
 
     async fn accounts_addresses_call(self: Arc<Self>, request: AccountsAddressesRequest) -> Result<AccountsAddressesResponse> {
@@ -389,7 +399,8 @@ impl WalletApi for super::Wallet {
         let window = request.window.clone();
         let account = self.get_account_by_id(&account_id).await?.ok_or(Error::AccountNotFound(account_id))?;
 
-        let addresses = account.addresses(request.wallet_secret.clone(), start.unwrap_or_default(), window.unwrap_or_default()).await?;
+        let addresses =
+            account.addresses(request.wallet_secret.clone(), start.unwrap_or_default(), window.unwrap_or_default()).await?;
 
         Ok(AccountsAddressesResponse { addresses })
     }
@@ -423,7 +434,7 @@ impl WalletApi for super::Wallet {
     }
 
     async fn accounts_estimate_call(self: Arc<Self>, request: AccountsEstimateRequest) -> Result<AccountsEstimateResponse> {
-        let AccountsEstimateRequest { account_id, destination, priority_fee_sompi, payload } = request;
+        let AccountsEstimateRequest { account_id, destination, priority_fee_sompi, payload, utxo_selection } = request;
 
         let account = self.get_account_by_id(&account_id).await?.ok_or(Error::AccountNotFound(account_id))?;
 
@@ -440,12 +451,62 @@ impl WalletApi for super::Wallet {
 
         let abortable = Abortable::new();
         self.inner.estimation_abortables.lock().unwrap().insert(account_id, abortable.clone());
-        let result = account.estimate(destination, priority_fee_sompi, payload, &abortable).await;
+        let result = account.estimate(destination, priority_fee_sompi, payload, utxo_selection, &abortable).await;
         self.inner.estimation_abortables.lock().unwrap().remove(&account_id);
 
         Ok(AccountsEstimateResponse { generator_summary: result? })
     }
 
+    async fn accounts_get_utxos_call(self: Arc<Self>, request: AccountsGetUtxosRequest) -> Result<AccountsGetUtxosResponse> {
+        let AccountsGetUtxosRequest { account_id } = request;
+
+        let account = self.get_account_by_id(&account_id).await?.ok_or(Error::AccountNotFound(account_id))?;
+        let frozen_utxos = account.frozen_utxos();
+
+        let utxo_context = account.utxo_context();
+        let mature = utxo_context.mature_utxos().into_iter().map(|utxo_entry| (utxo_entry, true));
+        let pending = utxo_context.pending_utxos().into_iter().map(|utxo_entry| (utxo_entry, false));
+        let utxos = mature
+            .chain(pending)
+            .map(|(utxo_entry, is_mature)| {
+                let utxo_entry = utxo_entry.as_ref();
+                let outpoint = TransactionOutpoint::from(utxo_entry.outpoint.clone());
+                AccountUtxo {
+                    outpoint,
+                    address: utxo_entry.address.clone(),
+                    amount: utxo_entry.amount,
+                    block_daa_score: utxo_entry.block_daa_score,
+                    is_coinbase: utxo_entry.is_coinbase,
+                    is_mature,
+                    is_frozen: frozen_utxos.contains(&outpoint),
+                }
+            })
+            .collect();
+
+        Ok(AccountsGetUtxosResponse { utxos })
+    }
+
+    async fn accounts_freeze_utxos_call(self: Arc<Self>, request: AccountsFreezeUtxosRequest) -> Result<AccountsFreezeUtxosResponse> {
+        let AccountsFreezeUtxosRequest { account_id, outpoints } = request;
+
+        let account = self.get_account_by_id(&account_id).await?.ok_or(Error::AccountNotFound(account_id))?;
+        account.freeze_utxos(&outpoints).await?;
+
+        Ok(AccountsFreezeUtxosResponse {})
+    }
+
+    async fn accounts_unfreeze_utxos_call(
+        self: Arc<Self>,
+        request: AccountsUnfreezeUtxosRequest,
+    ) -> Result<AccountsUnfreezeUtxosResponse> {
+        let AccountsUnfreezeUtxosRequest { account_id, outpoints } = request;
+
+        let account = self.get_account_by_id(&account_id).await?.ok_or(Error::AccountNotFound(account_id))?;
+        account.unfreeze_utxos(&outpoints).await?;
+
+        Ok(AccountsUnfreezeUtxosResponse {})
+    }
+
     async fn transactions_data_get_call(self: Arc<Self>, request: TransactionsDataGetRequest) -> Result<TransactionsDataGetResponse> {
         let TransactionsDataGetRequest { account_id, network_id, filter, start, end } = request;
 
@@ -489,10 +550,63 @@ impl WalletApi for super::Wallet {
         Ok(TransactionsReplaceMetadataResponse {})
     }
 
+    async fn transactions_export_call(self: Arc<Self>, request: TransactionsExportRequest) -> Result<TransactionsExportResponse> {
+        let TransactionsExportRequest { account_ids, network_id, filter, format } = request;
+
+        let mut stream = self.export_transactions(network_id, account_ids, filter, format).await?;
+        let mut data = String::new();
+        while let Some(chunk) = stream.try_next().await? {
+            data.push_str(&chunk);
+        }
+
+        Ok(TransactionsExportResponse { data })
+    }
+
     async fn address_book_enumerate_call(
         self: Arc<Self>,
         _request: AddressBookEnumerateRequest,
     ) -> Result<AddressBookEnumerateResponse> {
-        return Err(Error::NotImplemented);
+        let entries = self.store().as_address_book_store()?.iter().await?.try_collect::<Vec<_>>().await?;
+        let entries = entries.into_iter().map(|entry| (*entry).clone()).collect();
+        Ok(AddressBookEnumerateResponse { entries })
+    }
+
+    async fn address_book_search_call(self: Arc<Self>, request: AddressBookSearchRequest) -> Result<AddressBookSearchResponse> {
+        let AddressBookSearchRequest { search } = request;
+
+        let entries = self.store().as_address_book_store()?.search(&search).await?;
+        let entries = entries.into_iter().map(|entry| (*entry).clone()).collect();
+        Ok(AddressBookSearchResponse { entries })
+    }
+
+    async fn address_book_add_call(self: Arc<Self>, request: AddressBookAddRequest) -> Result<AddressBookAddResponse> {
+        let AddressBookAddRequest { wallet_secret, entry } = request;
+
+        // check the secret first, a commit failing on it would leave an unsaved change in the cache
+        self.store().check_secret(&wallet_secret).await?;
+        self.store().as_address_book_store()?.add(entry).await?;
+        self.store().commit(&wallet_secret).await?;
+
+        Ok(AddressBookAddResponse {})
+    }
+
+    async fn address_book_edit_call(self: Arc<Self>, request: AddressBookEditRequest) -> Result<AddressBookEditResponse> {
+        let AddressBookEditRequest { wallet_secret, alias, entry } = request;
+
+        self.store().check_secret(&wallet_secret).await?;
+        self.store().as_address_book_store()?.update(&alias, entry).await?;
+        self.store().commit(&wallet_secret).await?;
+
+        Ok(AddressBookEditResponse {})
+    }
+
+    async fn address_book_remove_call(self: Arc<Self>, request: AddressBookRemoveRequest) -> Result<AddressBookRemoveResponse> {
+        let AddressBookRemoveRequest { wallet_secret, alias } = request;
+
+        self.store().check_secret(&wallet_secret).await?;
+        self.store().as_address_book_store()?.remove(&alias).await?;
+        self.store().commit(&wallet_secret).await?;
+
+        Ok(AddressBookRemoveResponse {})
     }
 }
@@ -2,6 +2,7 @@
 //! Structs used as various arguments for internal wallet operations.
 //!
 
+use crate::account::watchonly::WatchOnlySource;
 use crate::imports::*;
 // use crate::secret::Secret;
 use crate::storage::interface::CreateArgs;
@@ -142,6 +143,11 @@ pub enum AccountCreateArgs {
         name: Option<String>,
         minimum_signatures: u16,
     },
+    WatchOnly {
+        account_name: Option<String>,
+        source: WatchOnlySource,
+        ecdsa: bool,
+    },
 }
 
 impl AccountCreateArgs {
@@ -168,4 +174,8 @@ impl AccountCreateArgs {
     ) -> Self {
         AccountCreateArgs::Multisig { prv_key_data_args, additional_xpub_keys, name, minimum_signatures }
     }
+
+    pub fn new_watch_only(account_name: Option<String>, source: WatchOnlySource, ecdsa: bool) -> Self {
+        AccountCreateArgs::WatchOnly { account_name, source, ecdsa }
+    }
 }
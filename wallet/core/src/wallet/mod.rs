@@ -6,16 +6,20 @@ pub mod args;
 pub mod maps;
 pub use args::*;
 
+use crate::account::watchonly::WatchOnlySource;
 use crate::account::ScanNotifier;
 use crate::compat::gen1::decrypt_mnemonic;
 use crate::error::Error::Custom;
 use crate::factory::try_load_account;
 use crate::imports::*;
 use crate::settings::{SettingsStore, WalletSettings};
-use crate::storage::interface::{OpenArgs, StorageDescriptor};
+use crate::storage::interface::{OpenArgs, StorageDescriptor, StorageStream};
+use crate::storage::transaction::{TransactionExportFilter, TransactionExportFormat, TransactionExportRow};
 use crate::storage::local::interface::LocalStore;
 use crate::storage::local::Storage;
+use crate::tx::Pskt;
 use crate::wallet::maps::ActiveAccountMap;
+use futures::future;
 use waglayla_bip32::{ExtendedKey, Language, Mnemonic, Prefix as KeyPrefix, WordCount};
 use waglayla_notify::{
     listener::ListenerId,
@@ -455,6 +459,21 @@ impl Wallet {
     }
 
     pub async fn is_account_key_encrypted(&self, account: &Arc<dyn Account>) -> Result<Option<bool>> {
+        // watch-only accounts have no private keys
+        if account.account_kind() == WATCH_ONLY_ACCOUNT_KIND {
+            return Ok(None);
+        }
+        // multisig accounts can hold the private keys of multiple cosigners
+        if let Ok(multisig) = account.clone().downcast_arc::<multisig::MultiSig>() {
+            let store = self.inner.store.as_prv_key_data_store()?;
+            let mut is_encrypted = None;
+            for prv_key_data_id in multisig.prv_key_data_ids().iter().flat_map(|ids| ids.iter()) {
+                if let Some(info) = store.load_key_info(prv_key_data_id).await? {
+                    is_encrypted = Some(is_encrypted.unwrap_or(false) || info.is_encrypted());
+                }
+            }
+            return Ok(is_encrypted);
+        }
         Ok(self.get_prv_key_info(account).await?.map(|info| info.is_encrypted()))
     }
 
@@ -562,8 +581,9 @@ impl Wallet {
         Ok(())
     }
 
-    pub async fn broadcast(&self) -> Result<()> {
-        Ok(())
+    /// Submit the transaction of a finalized [`Pskt`] to the network
+    pub async fn broadcast(&self, pskt: &Pskt) -> Result<TransactionId> {
+        pskt.submit(&self.rpc_api()).await
     }
 
     pub fn set_network_id(&self, network_id: &NetworkId) -> Result<()> {
@@ -616,6 +636,9 @@ impl Wallet {
             AccountCreateArgs::Multisig { prv_key_data_args, additional_xpub_keys, name, minimum_signatures } => {
                 self.create_account_multisig(wallet_secret, prv_key_data_args, additional_xpub_keys, name, minimum_signatures).await?
             }
+            AccountCreateArgs::WatchOnly { account_name, source, ecdsa } => {
+                self.create_account_watch_only(wallet_secret, account_name, source, ecdsa).await?
+            }
         };
 
         if notify {
@@ -771,6 +794,36 @@ impl Wallet {
         Ok(account)
     }
 
+    /// Creates an account tracking the funds of an xpub, a list of addresses or a public key
+    /// without storing any private key data in the wallet.
+    pub async fn create_account_watch_only(
+        self: &Arc<Wallet>,
+        wallet_secret: &Secret,
+        account_name: Option<String>,
+        source: WatchOnlySource,
+        ecdsa: bool,
+    ) -> Result<Arc<dyn Account>> {
+        let account_store = self.inner.store.clone().as_account_store()?;
+
+        if let WatchOnlySource::Addresses(addresses) = &source {
+            let prefix = self.address_prefix()?;
+            if let Some(address) = addresses.iter().find(|address| address.prefix != prefix) {
+                return Err(Error::custom(format!("address {address} does not belong to the wallet network")));
+            }
+        }
+
+        let account: Arc<dyn Account> = Arc::new(watchonly::WatchOnly::try_new(self, account_name, source, ecdsa).await?);
+
+        if account_store.load_single(account.id()).await?.is_some() {
+            return Err(Error::AccountAlreadyExists(*account.id()));
+        }
+
+        account_store.store_single(&account.to_storage()?, account.metadata()?.as_ref()).await?;
+        self.inner.store.commit(wallet_secret).await?;
+
+        Ok(account)
+    }
+
     pub async fn create_wallet(
         self: &Arc<Wallet>,
         wallet_secret: &Secret,
@@ -1063,6 +1116,75 @@ impl Wallet {
         Ok(Box::pin(stream))
     }
 
+    /// Resolves a payment destination that is either an address or the alias
+    /// of an address book entry usable on the current wallet network.
+    pub async fn resolve_destination(&self, destination: &str) -> Result<Address> {
+        if let Ok(address) = Address::try_from(destination) {
+            return Ok(address);
+        }
+
+        let entry = self
+            .store()
+            .as_address_book_store()?
+            .load_single(destination)
+            .await?
+            .ok_or_else(|| Error::AddressBookEntryNotFound(destination.to_string()))?;
+        let network_id = self.network_id()?;
+        if !entry.is_available_on(&network_id) {
+            return Err(Error::custom(format!("address book entry '{}' is not available on the {network_id} network", entry.alias)));
+        }
+
+        Ok(entry.address.clone())
+    }
+
+    /// Streams the transaction history of `account_ids` (or of all wallet accounts if `None`) on `network_id`
+    /// encoded in `format`, skipping the transaction records that do not match `filter`.
+    pub async fn export_transactions(
+        self: &Arc<Self>,
+        network_id: NetworkId,
+        account_ids: Option<Vec<AccountId>>,
+        filter: TransactionExportFilter,
+        format: TransactionExportFormat,
+    ) -> Result<StorageStream<String>> {
+        let account_ids = match account_ids {
+            Some(account_ids) => account_ids,
+            None => {
+                let mut iter = self.inner.store.as_account_store()?.iter(None).await?;
+                let mut account_ids = vec![];
+                while let Some((account_storage, _)) = iter.try_next().await? {
+                    account_ids.push(account_storage.id);
+                }
+                account_ids
+            }
+        };
+
+        let store = self.inner.store.as_transaction_record_store()?;
+        let records = futures::stream::iter(account_ids)
+            .then(move |account_id| {
+                let store = store.clone();
+                async move {
+                    match store.transaction_data_iter(&Binding::Account(account_id), &network_id).await {
+                        Ok(records) => records,
+                        // accounts without any transactions have no records
+                        Err(Error::NoRecordsFound) => Box::pin(futures::stream::empty()),
+                        Err(err) => Box::pin(futures::stream::once(future::ready(Err(err)))),
+                    }
+                }
+            })
+            .flatten();
+
+        let rows = records
+            .try_filter(move |record| future::ready(filter.matches(record)))
+            .enumerate()
+            .map(move |(index, record)| record.and_then(|record| format.row(index, &TransactionExportRow::from(record.as_ref()))));
+
+        let stream = futures::stream::once(future::ready(Ok(format.header())))
+            .chain(rows)
+            .chain(futures::stream::once(future::ready(Ok(format.footer()))));
+
+        Ok(Box::pin(stream))
+    }
+
     pub async fn remove_bip32_account(
         self: &Arc<Wallet>,
         wallet_secret: Secret,
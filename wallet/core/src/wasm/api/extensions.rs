@@ -1,5 +1,7 @@
 use crate::imports::*;
 use js_sys::Object;
+use waglayla_consensus_client::TransactionOutpointInner;
+use waglayla_consensus_core::tx::TransactionOutpoint;
 use waglayla_consensus_core::Hash;
 
 pub trait WalletApiObjectExtension {
@@ -10,6 +12,7 @@ pub trait WalletApiObjectExtension {
     fn get_prv_key_data_id(&self, key: &str) -> Result<PrvKeyDataId>;
     fn get_account_id(&self, key: &str) -> Result<AccountId>;
     fn try_get_account_id_list(&self, key: &str) -> Result<Option<Vec<AccountId>>>;
+    fn try_get_outpoint_list(&self, key: &str) -> Result<Option<Vec<TransactionOutpoint>>>;
     fn get_transaction_id(&self, key: &str) -> Result<Hash>;
 }
 
@@ -69,4 +72,19 @@ impl WalletApiObjectExtension for Object {
             Ok(None)
         }
     }
+
+    fn try_get_outpoint_list(&self, key: &str) -> Result<Option<Vec<TransactionOutpoint>>> {
+        if let Ok(array) = self.get_vec(key) {
+            let outpoints = array
+                .into_iter()
+                .map(|js_value| {
+                    let TransactionOutpointInner { transaction_id, index } = TransactionOutpointInner::try_from(&js_value)?;
+                    Ok(TransactionOutpoint::new(transaction_id, index))
+                })
+                .collect::<Result<Vec<TransactionOutpoint>>>()?;
+            Ok(Some(outpoints))
+        } else {
+            Ok(None)
+        }
+    }
 }
@@ -2,8 +2,10 @@
 
 use super::extensions::*;
 use crate::account::descriptor::IAccountDescriptor;
+use crate::account::watchonly::WatchOnlySource;
 use crate::api::message::*;
 use crate::imports::*;
+use crate::storage::transaction::{TransactionExportFilter, TransactionExportFormat};
 use crate::tx::{Fees, PaymentDestination, PaymentOutputs};
 use crate::wasm::tx::fees::IFees;
 use crate::wasm::tx::GeneratorSummary;
@@ -1022,6 +1024,14 @@ declare! {
         accountIndex?:number;
         prvKeyDataId:string;
         paymentSecret?:string;
+    } | {
+        walletSecret: string;
+        type: "watchonly";
+        accountName?:string;
+        xpub?:string;
+        addresses?:string[];
+        publicKey?:HexString;
+        ecdsa?:boolean;
     };
     //   |{
     //     walletSecret: string;
@@ -1049,8 +1059,29 @@ try_from! (args: IAccountsCreateRequest, AccountsCreateRequest, {
 
     let kind = AccountKind::try_from(args.try_get_value("type")?.ok_or(Error::custom("type is required"))?)?;
 
+    if kind == crate::account::WATCH_ONLY_ACCOUNT_KIND {
+        let source = if let Some(xpub) = args.try_get_string("xpub")? {
+            let xpub = ExtendedPublicKeySecp256k1::from_str(&xpub).map_err(|err| Error::InvalidExtendedPublicKey(xpub, err))?;
+            WatchOnlySource::Xpub(xpub)
+        } else if let Some(public_key) = args.try_get_string("publicKey")? {
+            let public_key = secp256k1::PublicKey::from_str(&public_key).map_err(|err| Error::custom(format!("publicKey: {err}")))?;
+            WatchOnlySource::PublicKey(public_key)
+        } else if let Ok(addresses) = args.get_vec("addresses") {
+            let addresses = addresses.into_iter().map(Address::try_cast_from).collect::<std::result::Result<Vec<_>, _>>()?;
+            WatchOnlySource::try_from_addresses(addresses.into_iter().map(|address| address.into_owned()).collect())?
+        } else {
+            return Err(Error::custom("xpub, publicKey or addresses are required"));
+        };
+
+        let account_name = args.try_get_string("accountName")?;
+        let ecdsa = args.try_get_bool("ecdsa")?.unwrap_or(false);
+        let account_create_args = AccountCreateArgs::new_watch_only(account_name, source, ecdsa);
+
+        return Ok(AccountsCreateRequest { wallet_secret, account_create_args });
+    }
+
     if kind != crate::account::BIP32_ACCOUNT_KIND {
-        return Err(Error::custom("only BIP32 accounts are currently supported"));
+        return Err(Error::custom("only BIP32 and watch-only accounts are currently supported"));
     }
 
     let prv_key_data_args = PrvKeyDataArgs {
@@ -1373,6 +1404,12 @@ declare! {
          * If not supplied, the destination will be the change address resulting in a UTXO compound transaction.
          */
         destination? : IPaymentOutput[];
+        /**
+         * Optional list of UTXOs to spend (coin control). If not supplied,
+         * UTXOs are selected automatically, excluding frozen UTXOs.
+         * Outpoints can be supplied as objects or as `<txid>-<index>` strings.
+         */
+        utxoSelection? : ITransactionOutpoint[] | string[];
     }
     "#,
 }
@@ -1388,7 +1425,9 @@ try_from! ( args: IAccountsSendRequest, AccountsSendRequest, {
     let destination: PaymentDestination =
         if outputs.is_undefined() { PaymentDestination::Change } else { PaymentOutputs::try_owned_from(outputs)?.into() };
 
-    Ok(AccountsSendRequest { account_id, wallet_secret, payment_secret, priority_fee_sompi, destination, payload })
+    let utxo_selection = args.try_get_outpoint_list("utxoSelection")?;
+
+    Ok(AccountsSendRequest { account_id, wallet_secret, payment_secret, priority_fee_sompi, destination, payload, utxo_selection })
 });
 
 declare! {
@@ -1704,6 +1743,7 @@ declare! {
         destination : IPaymentOutput[];
         priorityFeeSompi : IFees | bigint;
         payload? : Uint8Array | string;
+        utxoSelection? : ITransactionOutpoint[] | string[];
     }
     "#,
 }
@@ -1717,7 +1757,9 @@ try_from! ( args: IAccountsEstimateRequest, AccountsEstimateRequest, {
     let destination: PaymentDestination =
         if outputs.is_undefined() { PaymentDestination::Change } else { PaymentOutputs::try_owned_from(outputs)?.into() };
 
-    Ok(AccountsEstimateRequest { account_id, priority_fee_sompi, destination, payload })
+    let utxo_selection = args.try_get_outpoint_list("utxoSelection")?;
+
+    Ok(AccountsEstimateRequest { account_id, priority_fee_sompi, destination, payload, utxo_selection })
 });
 
 declare! {
@@ -1742,6 +1784,145 @@ try_from! ( args: AccountsEstimateResponse, IAccountsEstimateResponse, {
 
 // ---
 
+declare! {
+    IAccountsGetUtxosRequest,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAccountsGetUtxosRequest {
+        accountId : HexString;
+    }
+    "#,
+}
+
+try_from! ( args: IAccountsGetUtxosRequest, AccountsGetUtxosRequest, {
+    let account_id = args.get_account_id("accountId")?;
+    Ok(AccountsGetUtxosRequest { account_id })
+});
+
+declare! {
+    IAccountsGetUtxosResponse,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAccountsGetUtxosResponse {
+        utxos : IAccountUtxo[];
+    }
+
+    /**
+     * UTXO held by an account.
+     * 
+     * @category Wallet API
+     */
+    export interface IAccountUtxo {
+        outpoint : ITransactionOutpoint;
+        address? : string;
+        amount : bigint;
+        blockDaaScore : bigint;
+        isCoinbase : boolean;
+        /**
+         * `false` if the UTXO is pending maturity and can not be spent yet.
+         */
+        isMature : boolean;
+        /**
+         * `true` if the UTXO is excluded from the automatic UTXO selection.
+         */
+        isFrozen : boolean;
+    }
+    "#,
+}
+
+try_from! ( args: AccountsGetUtxosResponse, IAccountsGetUtxosResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IAccountsFreezeUtxosRequest,
+    r#"
+    /**
+     * Frozen UTXOs are never selected automatically when creating
+     * transactions, but can be spent by supplying them in `utxoSelection`.
+     *  
+     * @category Wallet API
+     */
+    export interface IAccountsFreezeUtxosRequest {
+        accountId : HexString;
+        outpoints : ITransactionOutpoint[] | string[];
+    }
+    "#,
+}
+
+try_from! ( args: IAccountsFreezeUtxosRequest, AccountsFreezeUtxosRequest, {
+    let account_id = args.get_account_id("accountId")?;
+    let outpoints = args.try_get_outpoint_list("outpoints")?.ok_or(Error::custom("outpoints are required"))?;
+    Ok(AccountsFreezeUtxosRequest { account_id, outpoints })
+});
+
+declare! {
+    IAccountsFreezeUtxosResponse,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAccountsFreezeUtxosResponse { }
+    "#,
+}
+
+try_from! ( _args: AccountsFreezeUtxosResponse, IAccountsFreezeUtxosResponse, {
+    Ok(IAccountsFreezeUtxosResponse::default())
+});
+
+// ---
+
+declare! {
+    IAccountsUnfreezeUtxosRequest,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAccountsUnfreezeUtxosRequest {
+        accountId : HexString;
+        outpoints : ITransactionOutpoint[] | string[];
+    }
+    "#,
+}
+
+try_from! ( args: IAccountsUnfreezeUtxosRequest, AccountsUnfreezeUtxosRequest, {
+    let account_id = args.get_account_id("accountId")?;
+    let outpoints = args.try_get_outpoint_list("outpoints")?.ok_or(Error::custom("outpoints are required"))?;
+    Ok(AccountsUnfreezeUtxosRequest { account_id, outpoints })
+});
+
+declare! {
+    IAccountsUnfreezeUtxosResponse,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAccountsUnfreezeUtxosResponse { }
+    "#,
+}
+
+try_from! ( _args: AccountsUnfreezeUtxosResponse, IAccountsUnfreezeUtxosResponse, {
+    Ok(IAccountsUnfreezeUtxosResponse::default())
+});
+
+// ---
+
 declare! {
     ITransactionsDataGetRequest,
     r#"
@@ -1935,6 +2116,111 @@ try_from! ( _args: TransactionsReplaceMetadataResponse, ITransactionsReplaceMeta
 
 // ---
 
+declare! {
+    ITransactionsExportRequest,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface ITransactionsExportRequest {
+        /**
+         * Accounts to export the transactions of.
+         * If not supplied, transactions of all wallet accounts are exported.
+         */
+        accountIds?: HexString[],
+        /**
+         * The network id of the transactions.
+         */
+        networkId: NetworkId | string,
+        /**
+         * Export format: "csv" (default) or "json".
+         */
+        format?: string,
+        /**
+         * Transaction kinds to export (all kinds if not supplied).
+         */
+        kinds?: TransactionKind[],
+        /**
+         * Optional time range (unix time in milliseconds, end excluded).
+         */
+        startUnixtimeMsec?: bigint,
+        endUnixtimeMsec?: bigint,
+        /**
+         * Optional DAA score range (end excluded).
+         */
+        startDaaScore?: bigint,
+        endDaaScore?: bigint,
+    }
+    "#,
+}
+
+try_from! ( args: ITransactionsExportRequest, TransactionsExportRequest, {
+    let account_ids = args.try_get_account_id_list("accountIds")?;
+    let network_id = args.get_network_id("networkId")?;
+    let format = args.try_get_string("format")?.map(|format| TransactionExportFormat::from_str(&format)).transpose()?.unwrap_or_default();
+    let kinds = args.get_vec("kinds").ok().map(|kinds| {
+        kinds.into_iter().map(TransactionKind::try_from).collect::<Result<Vec<TransactionKind>>>()
+    }).transpose()?;
+    let filter = TransactionExportFilter {
+        kinds,
+        start_unixtime_msec: args.get_u64("startUnixtimeMsec").ok(),
+        end_unixtime_msec: args.get_u64("endUnixtimeMsec").ok(),
+        start_daa_score: args.get_u64("startDaaScore").ok(),
+        end_daa_score: args.get_u64("endDaaScore").ok(),
+    };
+
+    Ok(TransactionsExportRequest { account_ids, network_id, filter, format })
+});
+
+declare! {
+    ITransactionsExportResponse,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface ITransactionsExportResponse {
+        /**
+         * Exported transactions encoded in the requested format.
+         */
+        data: string,
+    }
+    "#,
+}
+
+try_from! ( args: TransactionsExportResponse, ITransactionsExportResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IAddressBookEntry,
+    r#"
+    /**
+     * Address book entry (contact).
+     *  
+     * @category Wallet API
+     */
+    export interface IAddressBookEntry {
+        /**
+         * Unique (case-insensitive) name of the entry,
+         * which can be used in place of the address.
+         */
+        alias: string;
+        title: string;
+        address: Address | string;
+        /**
+         * Network the entry is restricted to.
+         */
+        networkId?: NetworkId | string;
+    }
+    "#,
+}
+
 declare! {
     IAddressBookEnumerateRequest,
     r#"
@@ -1960,13 +2246,177 @@ declare! {
      * @category Wallet API
      */
     export interface IAddressBookEnumerateResponse {
-        // TODO
+        entries: IAddressBookEntry[];
     }
     "#,
 }
 
-try_from! ( _args: AddressBookEnumerateResponse, IAddressBookEnumerateResponse, {
-    Err(Error::NotImplemented)
+try_from! ( args: AddressBookEnumerateResponse, IAddressBookEnumerateResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IAddressBookSearchRequest,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAddressBookSearchRequest {
+        /**
+         * Text to look for in the entry alias, title or address.
+         */
+        search: string;
+    }
+    "#,
+}
+
+try_from! ( args: IAddressBookSearchRequest, AddressBookSearchRequest, {
+    let search = args.get_string("search")?;
+    Ok(AddressBookSearchRequest { search })
+});
+
+declare! {
+    IAddressBookSearchResponse,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAddressBookSearchResponse {
+        entries: IAddressBookEntry[];
+    }
+    "#,
+}
+
+try_from! ( args: AddressBookSearchResponse, IAddressBookSearchResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IAddressBookAddRequest,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAddressBookAddRequest {
+        walletSecret: string;
+        entry: IAddressBookEntry;
+    }
+    "#,
+}
+
+try_from! ( args: IAddressBookAddRequest, AddressBookAddRequest, {
+    let wallet_secret = args.get_secret("walletSecret")?;
+    let entry = from_value::<AddressBookEntry>(args.get_value("entry")?)?;
+    Ok(AddressBookAddRequest { wallet_secret, entry })
+});
+
+declare! {
+    IAddressBookAddResponse,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAddressBookAddResponse { }
+    "#,
+}
+
+try_from! ( _args: AddressBookAddResponse, IAddressBookAddResponse, {
+    Ok(IAddressBookAddResponse::default())
+});
+
+// ---
+
+declare! {
+    IAddressBookEditRequest,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAddressBookEditRequest {
+        walletSecret: string;
+        /**
+         * Alias of the entry to replace.
+         */
+        alias: string;
+        entry: IAddressBookEntry;
+    }
+    "#,
+}
+
+try_from! ( args: IAddressBookEditRequest, AddressBookEditRequest, {
+    let wallet_secret = args.get_secret("walletSecret")?;
+    let alias = args.get_string("alias")?;
+    let entry = from_value::<AddressBookEntry>(args.get_value("entry")?)?;
+    Ok(AddressBookEditRequest { wallet_secret, alias, entry })
+});
+
+declare! {
+    IAddressBookEditResponse,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAddressBookEditResponse { }
+    "#,
+}
+
+try_from! ( _args: AddressBookEditResponse, IAddressBookEditResponse, {
+    Ok(IAddressBookEditResponse::default())
+});
+
+// ---
+
+declare! {
+    IAddressBookRemoveRequest,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAddressBookRemoveRequest {
+        walletSecret: string;
+        alias: string;
+    }
+    "#,
+}
+
+try_from! ( args: IAddressBookRemoveRequest, AddressBookRemoveRequest, {
+    let wallet_secret = args.get_secret("walletSecret")?;
+    let alias = args.get_string("alias")?;
+    Ok(AddressBookRemoveRequest { wallet_secret, alias })
+});
+
+declare! {
+    IAddressBookRemoveResponse,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAddressBookRemoveResponse { }
+    "#,
+}
+
+try_from! ( _args: AddressBookRemoveResponse, IAddressBookRemoveResponse, {
+    Ok(IAddressBookRemoveResponse::default())
 });
 
 // ---
@@ -52,8 +52,16 @@ declare_wasm_handlers!([
     AccountsAddresses,
     AccountsTransfer,
     AccountsEstimate,
+    AccountsGetUtxos,
+    AccountsFreezeUtxos,
+    AccountsUnfreezeUtxos,
     TransactionsDataGet,
     TransactionsReplaceNote,
     TransactionsReplaceMetadata,
+    TransactionsExport,
     AddressBookEnumerate,
+    AddressBookSearch,
+    AddressBookAdd,
+    AddressBookEdit,
+    AddressBookRemove,
 ]);
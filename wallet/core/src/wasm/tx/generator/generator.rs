@@ -2,10 +2,12 @@ use crate::imports::*;
 use crate::result::Result;
 use crate::tx::{generator as native, Fees, PaymentDestination, PaymentOutputs};
 use crate::utxo::{TryIntoUtxoEntryReferences, UtxoEntryReference};
+use crate::wasm::api::extensions::WalletApiObjectExtension;
 use crate::wasm::tx::generator::*;
 use crate::wasm::tx::IFees;
 // use crate::wasm::wallet::Account;
 use crate::wasm::UtxoContext;
+use waglayla_consensus_core::tx::TransactionOutpoint;
 
 // TODO-WASM fix outputs
 #[wasm_bindgen(typescript_custom_section)]
@@ -76,6 +78,16 @@ interface IGeneratorSettingsObject {
      * Optional data payload to be included in the transaction.
      */
     payload?: Uint8Array | HexString;
+    /**
+     * Optional list of UTXOs to spend instead of selecting UTXOs
+     * automatically (coin control). Requires {@link IGeneratorSettingsObject.entries}
+     * to be a {@link UtxoContext}.
+     */
+    utxoSelection?: ITransactionOutpoint[] | string[];
+    /**
+     * Optional list of UTXOs that must not be selected automatically.
+     */
+    frozenUtxos?: ITransactionOutpoint[] | string[];
 
     /**
      * Optional NetworkId or network id as string (i.e. `mainnet` or `testnet-11`). Required when {@link IGeneratorSettingsObject.entries} is array
@@ -154,6 +166,8 @@ impl Generator {
             sig_op_count,
             minimum_signatures,
             payload,
+            utxo_selection,
+            frozen_utxos,
         } = settings;
 
         let settings = match source {
@@ -196,6 +210,11 @@ impl Generator {
               // }
         };
 
+        let mut settings = settings.exclude_utxos(&frozen_utxos.unwrap_or_default());
+        if let Some(utxo_selection) = utxo_selection {
+            settings = settings.try_with_utxo_selection(&utxo_selection)?;
+        }
+
         let abortable = Abortable::default();
         let generator = native::Generator::try_new(settings, None, Some(&abortable))?;
 
@@ -251,6 +270,8 @@ struct GeneratorSettings {
     pub sig_op_count: u8,
     pub minimum_signatures: u16,
     pub payload: Option<Vec<u8>>,
+    pub utxo_selection: Option<Vec<TransactionOutpoint>>,
+    pub frozen_utxos: Option<Vec<TransactionOutpoint>>,
 }
 
 impl TryFrom<IGeneratorSettingsObject> for GeneratorSettings {
@@ -288,6 +309,9 @@ impl TryFrom<IGeneratorSettingsObject> for GeneratorSettings {
 
         let payload = args.get_vec_u8("payload").ok();
 
+        let utxo_selection = args.try_get_outpoint_list("utxoSelection")?;
+        let frozen_utxos = args.try_get_outpoint_list("frozenUtxos")?;
+
         let settings = GeneratorSettings {
             network_id,
             source: generator_source,
@@ -298,6 +322,8 @@ impl TryFrom<IGeneratorSettingsObject> for GeneratorSettings {
             sig_op_count,
             minimum_signatures,
             payload,
+            utxo_selection,
+            frozen_utxos,
         };
 
         Ok(settings)
@@ -1,7 +1,7 @@
 use crate::imports::*;
 use crate::result::Result;
-use crate::tx::generator as native;
-use crate::wasm::PrivateKeyArrayT;
+use crate::tx::{generator as native, Pskt as NativePskt};
+use crate::wasm::{PrivateKeyArrayT, Pskt};
 use waglayla_consensus_client::{numeric, string};
 use waglayla_consensus_client::{ITransaction, Transaction};
 use waglayla_wallet_keys::privatekey::PrivateKey;
@@ -87,6 +87,13 @@ impl PendingTransaction {
         }
     }
 
+    /// Creates a {@link PSKT} allowing the transaction to be signed by
+    /// other parties, such as multisig cosigners or offline wallets.
+    #[wasm_bindgen(js_name = toPSKT)]
+    pub fn to_pskt(&self) -> Result<Pskt> {
+        Ok(NativePskt::from_pending_transaction(&self.inner)?.into())
+    }
+
     /// Submit transaction to the supplied [`RpcClient`]
     /// **IMPORTANT:** This method will remove UTXOs from the associated
     /// {@link UtxoContext} if one was used to create the transaction
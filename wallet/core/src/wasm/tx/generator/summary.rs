@@ -46,6 +46,11 @@ impl GeneratorSummary {
     pub fn final_transaction_id(&self) -> Option<String> {
         self.inner.final_transaction_id().map(|id| id.to_string())
     }
+
+    #[wasm_bindgen(getter, js_name = finalTransactionMass)]
+    pub fn final_transaction_mass(&self) -> Option<BigInt> {
+        self.inner.final_transaction_mass().map(BigInt::from)
+    }
 }
 
 impl From<core::GeneratorSummary> for GeneratorSummary {
@@ -2,10 +2,12 @@ pub mod consensus;
 pub mod fees;
 pub mod generator;
 pub mod mass;
+pub mod pskt;
 pub mod utils;
 
 pub use self::consensus::*;
 pub use self::fees::*;
 pub use self::generator::*;
 pub use self::mass::*;
+pub use self::pskt::*;
 pub use self::utils::*;
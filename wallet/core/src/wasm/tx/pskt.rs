@@ -0,0 +1,127 @@
+use crate::imports::*;
+use crate::result::Result;
+use crate::tx::pskt as native;
+use crate::wasm::PrivateKeyArrayT;
+use waglayla_consensus_client::{Transaction, UtxoEntry};
+use waglayla_wallet_keys::privatekey::PrivateKey;
+use waglayla_wasm_core::types::BinaryT;
+use waglayla_wrpc_wasm::RpcClient;
+
+/// Partially Signed Waglayla Transaction (PSKT) allowing a transaction to be
+/// signed by multiple parties (such as multisig cosigners or offline wallets)
+/// before being submitted to the network.
+///
+/// PSKTs are created using {@link PendingTransaction.toPSKT} and exchanged
+/// between the signing parties using {@link PSKT.serialize}.
+///
+/// @category Wallet SDK
+#[wasm_bindgen(inspectable, js_name = PSKT)]
+pub struct Pskt {
+    inner: native::Pskt,
+}
+
+#[wasm_bindgen(js_class = PSKT)]
+impl Pskt {
+    /// Deserializes a PSKT produced by {@link PSKT.serialize}
+    #[wasm_bindgen(constructor)]
+    pub fn constructor(pskt: String) -> Result<Pskt> {
+        Ok(native::Pskt::deserialize(&pskt)?.into())
+    }
+
+    /// Id of the transaction, which remains the same while the PSKT gets signed
+    #[wasm_bindgen(getter)]
+    pub fn id(&self) -> String {
+        self.inner.id().to_string()
+    }
+
+    #[wasm_bindgen(getter, js_name = isFinalized)]
+    pub fn is_finalized(&self) -> bool {
+        self.inner.is_finalized()
+    }
+
+    /// Sets the redeem script of the pay-to-script-hash input at `index`
+    /// (such as a script created by {@link createMultisigAddress} keys).
+    #[wasm_bindgen(js_name = setRedeemScript)]
+    pub fn set_redeem_script(&mut self, index: usize, redeem_script: BinaryT) -> Result<()> {
+        self.inner.set_redeem_script(index, redeem_script.try_as_vec_u8()?)
+    }
+
+    /// Sign the inputs that the supplied private keys are allowed to sign,
+    /// returning the number of signatures added.
+    pub fn sign(&mut self, js_value: PrivateKeyArrayT) -> Result<usize> {
+        if let Ok(keys) = js_value.dyn_into::<Array>() {
+            let keys = keys
+                .iter()
+                .map(PrivateKey::try_cast_from)
+                .collect::<std::result::Result<Vec<_>, waglayla_wallet_keys::error::Error>>()?;
+            let mut keys = keys.iter().map(|key| key.as_ref().secret_bytes()).collect::<Vec<_>>();
+            let result = self.inner.sign(&keys);
+            keys.zeroize();
+            result
+        } else {
+            Err(Error::custom("Please supply an array of keys"))
+        }
+    }
+
+    /// Merge the signatures of another PSKT of the same transaction
+    pub fn combine(&mut self, pskt: &Pskt) -> Result<()> {
+        self.inner.combine(&pskt.inner)
+    }
+
+    /// Build the signature scripts of all inputs, failing if an input lacks signatures
+    pub fn finalize(&mut self) -> Result<()> {
+        self.inner.finalize()
+    }
+
+    /// Returns the signed {@link Transaction} of a finalized PSKT
+    #[wasm_bindgen(js_name = toTransaction)]
+    pub fn to_transaction(&self) -> Result<Transaction> {
+        let transaction = self.inner.to_transaction()?;
+        let utxo_entries = self
+            .inner
+            .inputs
+            .iter()
+            .map(|input| {
+                let utxo_entry = UtxoEntryReference::from(UtxoEntry {
+                    address: None,
+                    outpoint: input.previous_outpoint.into(),
+                    amount: input.utxo_entry.amount,
+                    script_public_key: input.utxo_entry.script_public_key.clone(),
+                    block_daa_score: input.utxo_entry.block_daa_score,
+                    is_coinbase: input.utxo_entry.is_coinbase,
+                });
+                (utxo_entry.id(), utxo_entry)
+            })
+            .collect::<AHashMap<_, _>>();
+        Ok(Transaction::from_cctx_transaction(&transaction, &utxo_entries))
+    }
+
+    /// Submit the transaction of a finalized PSKT to the supplied [`RpcClient`]
+    /// @see {@link RpcClient.submitTransaction}
+    pub async fn submit(&self, wasm_rpc_client: &RpcClient) -> Result<String> {
+        let rpc: Arc<DynRpcApi> = wasm_rpc_client.client().clone();
+        Ok(self.inner.submit(&rpc).await?.to_string())
+    }
+
+    /// Serializes the PSKT to a string that can be passed to the other signing parties
+    pub fn serialize(&self) -> Result<String> {
+        self.inner.serialize()
+    }
+
+    /// Serializes the PSKT to a JSON string
+    #[wasm_bindgen(js_name = "serializeToJSON")]
+    pub fn serialize_to_json(&self) -> Result<String> {
+        self.inner.to_json()
+    }
+
+    #[wasm_bindgen(js_name = "deserializeFromJSON")]
+    pub fn deserialize_from_json(json: String) -> Result<Pskt> {
+        Ok(native::Pskt::from_json(&json)?.into())
+    }
+}
+
+impl From<native::Pskt> for Pskt {
+    fn from(pskt: native::Pskt) -> Self {
+        Self { inner: pskt }
+    }
+}